@@ -1,6 +1,6 @@
 // Code generated by gen_trampolines; DO NOT EDIT.
-//go:build amd64
-// +build amd64
+//go:build amd64 && !windows
+// +build amd64,!windows
 
 #include "textflag.h"
 
@@ -0,0 +1,245 @@
+// Code generated by gen_trampolines; DO NOT EDIT.
+//go:build windows && amd64
+// +build windows,amd64
+
+#include "textflag.h"
+
+// func sum_u8_32_raw() uint32
+TEXT ·sum_u8_32_raw(SB), NOSPLIT, $0-20
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL sum_u8_32(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+16(FP)
+    RET
+
+// func sum_u8_64_raw() uint32
+TEXT ·sum_u8_64_raw(SB), NOSPLIT, $0-20
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL sum_u8_64(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+16(FP)
+    RET
+
+// func sum_u8_16_raw() uint32
+TEXT ·sum_u8_16_raw(SB), NOSPLIT, $0-20
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL sum_u8_16(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+16(FP)
+    RET
+
+// func is_ascii32_raw() uint8
+TEXT ·is_ascii32_raw(SB), NOSPLIT, $0-17
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL is_ascii32(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+16(FP)
+    RET
+
+// func is_ascii64_raw() uint8
+TEXT ·is_ascii64_raw(SB), NOSPLIT, $0-17
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL is_ascii64(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+16(FP)
+    RET
+
+// func is_ascii16_raw() uint8
+TEXT ·is_ascii16_raw(SB), NOSPLIT, $0-17
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    SUBQ $32, SP
+    CALL is_ascii16(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+16(FP)
+    RET
+
+// func validate_u8_lut32_raw() uint8
+TEXT ·validate_u8_lut32_raw(SB), NOSPLIT, $0-25
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ lut+16(FP), R8
+    SUBQ $32, SP
+    CALL validate_u8_lut32(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+24(FP)
+    RET
+
+// func validate_u8_lut64_raw() uint8
+TEXT ·validate_u8_lut64_raw(SB), NOSPLIT, $0-25
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ lut+16(FP), R8
+    SUBQ $32, SP
+    CALL validate_u8_lut64(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+24(FP)
+    RET
+
+// func validate_u8_lut16_raw() uint8
+TEXT ·validate_u8_lut16_raw(SB), NOSPLIT, $0-25
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ lut+16(FP), R8
+    SUBQ $32, SP
+    CALL validate_u8_lut16(SB)
+    ADDQ $32, SP
+    MOVB AL, ret+24(FP)
+    RET
+
+// func map_u8_lut32_raw()
+TEXT ·map_u8_lut32_raw(SB), NOSPLIT, $0-32
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ dst+16(FP), R8
+    MOVQ lut+24(FP), R9
+    SUBQ $32, SP
+    CALL map_u8_lut32(SB)
+    ADDQ $32, SP
+    RET
+
+// func map_u8_lut64_raw()
+TEXT ·map_u8_lut64_raw(SB), NOSPLIT, $0-32
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ dst+16(FP), R8
+    MOVQ lut+24(FP), R9
+    SUBQ $32, SP
+    CALL map_u8_lut64(SB)
+    ADDQ $32, SP
+    RET
+
+// func map_u8_lut16_raw()
+TEXT ·map_u8_lut16_raw(SB), NOSPLIT, $0-32
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVQ dst+16(FP), R8
+    MOVQ lut+24(FP), R9
+    SUBQ $32, SP
+    CALL map_u8_lut16(SB)
+    ADDQ $32, SP
+    RET
+
+// func eq_u8_masks32_raw() uintptr
+TEXT ·eq_u8_masks32_raw(SB), NOSPLIT, $0-40
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVBLZX needle+16(FP), R8
+    MOVQ out+24(FP), R9
+    SUBQ $32, SP
+    CALL eq_u8_masks32(SB)
+    ADDQ $32, SP
+    MOVQ AX, ret+32(FP)
+    RET
+
+// func eq_u8_masks64_raw() uintptr
+TEXT ·eq_u8_masks64_raw(SB), NOSPLIT, $0-40
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVBLZX needle+16(FP), R8
+    MOVQ out+24(FP), R9
+    SUBQ $32, SP
+    CALL eq_u8_masks64(SB)
+    ADDQ $32, SP
+    MOVQ AX, ret+32(FP)
+    RET
+
+// func eq_u8_masks16_raw() uintptr
+TEXT ·eq_u8_masks16_raw(SB), NOSPLIT, $0-40
+    MOVQ src+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVBLZX needle+16(FP), R8
+    MOVQ out+24(FP), R9
+    SUBQ $32, SP
+    CALL eq_u8_masks16(SB)
+    ADDQ $32, SP
+    MOVQ AX, ret+32(FP)
+    RET
+
+// func noop_raw()
+TEXT ·noop_raw(SB), NOSPLIT, $0-0
+    SUBQ $32, SP
+    CALL noop(SB)
+    ADDQ $32, SP
+    RET
+
+// func crc32_update_32_raw() uint32
+TEXT ·crc32_update_32_raw(SB), NOSPLIT, $0-24
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVL init+16(FP), R8
+    SUBQ $32, SP
+    CALL crc32_update_32(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+24(FP)
+    RET
+
+// func crc32_update_64_raw() uint32
+TEXT ·crc32_update_64_raw(SB), NOSPLIT, $0-24
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVL init+16(FP), R8
+    SUBQ $32, SP
+    CALL crc32_update_64(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+24(FP)
+    RET
+
+// func crc32_combine_raw() uint32
+TEXT ·crc32_combine_raw(SB), NOSPLIT, $0-20
+    MOVL crc1+0(FP), CX
+    MOVL crc2+4(FP), DX
+    MOVQ len2+8(FP), R8
+    SUBQ $32, SP
+    CALL crc32_combine(SB)
+    ADDQ $32, SP
+    MOVL AX, ret+16(FP)
+    RET
+
+// func trampoline_sanity_raw() uintptr
+TEXT ·trampoline_sanity_raw(SB), NOSPLIT, $0-56
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVL val32+16(FP), R8
+    MOVBLZX val8+20(FP), R9
+    SUBQ $56, SP
+    MOVQ val64+24(FP), AX
+    MOVQ AX, 32(SP)
+    MOVQ f64bits+32(FP), AX
+    MOVQ AX, 40(SP)
+    MOVL f32bits+40(FP), AX
+    MOVL AX, 48(SP)
+    CALL trampoline_sanity(SB)
+    ADDQ $56, SP
+    MOVQ AX, ret+48(FP)
+    RET
+
+// func trampoline_echo_raw()
+TEXT ·trampoline_echo_raw(SB), NOSPLIT, $0-56
+    MOVQ ptr+0(FP), CX
+    MOVQ n+8(FP), DX
+    MOVL v32+16(FP), R8
+    MOVBLZX v8+20(FP), R9
+    SUBQ $64, SP
+    MOVQ v64+24(FP), AX
+    MOVQ AX, 32(SP)
+    MOVQ f64bits+32(FP), AX
+    MOVQ AX, 40(SP)
+    MOVL f32bits+40(FP), AX
+    MOVL AX, 48(SP)
+    MOVQ out+44(FP), AX
+    MOVQ AX, 56(SP)
+    CALL trampoline_echo(SB)
+    ADDQ $64, SP
+    RET
+
@@ -0,0 +1,288 @@
+//! Emits `simba.json`, a machine-readable manifest of every C export, into
+//! `OUT_DIR` (and to `$SIMBA_MANIFEST` when that is set).  Binding generators
+//! read it instead of tracking signatures by hand.
+//!
+//! The manifest is derived from `include/simba.h` (see `scripts/gen_header.sh`)
+//! so it describes exactly what C callers see:
+//!
+//! * `symbol_prefix`: prepended to every `name` in the linked object;
+//! * `args` / `returns`: C type and width in bits (pointer-sized for pointers,
+//!   `size_t` and `ptrdiff_t`);
+//! * `nullable`: whether the export documents a null pointer as accepted;
+//! * `lanes`: the SIMD lane count for width-specific kernels, `"auto"` for
+//!   the runtime-dispatched ones, `null` otherwise.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+struct Arg {
+    name: String,
+    ty: String,
+}
+
+struct Export {
+    name: String,
+    ret: String,
+    args: Vec<Arg>,
+    doc: String,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=include/simba.h");
+    println!("cargo:rerun-if-changed=src/abi.rs");
+    println!("cargo:rerun-if-env-changed=SIMBA_MANIFEST");
+    println!("cargo:rerun-if-env-changed=SIMBA_SYMBOL_PREFIX");
+
+    // Every export is linked as `$SIMBA_SYMBOL_PREFIX<name>` (see `c_symbol!`),
+    // so big binaries can keep generic names like `noop` out of the global
+    // namespace.
+    let prefix = env::var("SIMBA_SYMBOL_PREFIX").unwrap_or_default();
+    assert!(
+        prefix
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_'),
+        "SIMBA_SYMBOL_PREFIX must be a C identifier prefix, got {prefix:?}"
+    );
+    println!("cargo:rustc-env=SIMBA_SYMBOL_PREFIX={prefix}");
+
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let header = fs::read_to_string(Path::new(&dir).join("include/simba.h"))
+        .expect("include/simba.h is missing; run scripts/gen_header.sh");
+    let abi = fs::read_to_string(Path::new(&dir).join("src/abi.rs")).unwrap();
+    let ptr_bits: u32 = env::var("CARGO_CFG_TARGET_POINTER_WIDTH")
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let json = manifest(abi_version(&abi), &prefix, &parse_header(&header), ptr_bits);
+    fs::write(
+        Path::new(&env::var("OUT_DIR").unwrap()).join("simba.json"),
+        &json,
+    )
+    .unwrap();
+    if let Ok(path) = env::var("SIMBA_MANIFEST") {
+        fs::write(path, &json).unwrap();
+    }
+}
+
+fn abi_version(abi: &str) -> u32 {
+    abi.lines()
+        .find_map(|l| l.strip_prefix("const ABI_VERSION: u32 = "))
+        .and_then(|v| v.trim_end_matches(';').parse().ok())
+        .expect("ABI_VERSION not found in src/abi.rs")
+}
+
+/// Collect every prototype in the `extern "C"` block with the doc comment
+/// preceding it.
+fn parse_header(header: &str) -> Vec<Export> {
+    let mut out = Vec::new();
+    let mut doc = String::new();
+    let mut in_doc = false;
+    let mut in_extern = false;
+    let mut proto = String::new();
+    for line in header.lines() {
+        let t = line.trim();
+        if t == "extern \"C\" {" {
+            in_extern = true;
+            continue;
+        }
+        if in_doc {
+            if t == "*/" {
+                in_doc = false;
+            } else {
+                doc.push_str(t.trim_start_matches('*').trim());
+                doc.push(' ');
+            }
+            continue;
+        }
+        if t == "/**" {
+            in_doc = true;
+            doc.clear();
+            continue;
+        }
+        if in_extern && (!proto.is_empty() || t.contains('(')) {
+            proto.push_str(t);
+            proto.push(' ');
+            if t.ends_with(");") {
+                out.push(parse_proto(&proto, &doc));
+                proto.clear();
+                doc.clear();
+            }
+        }
+    }
+    out
+}
+
+/// Split `ret name(type a, type b);` into its parts.  A `*` written against
+/// a name belongs to the type, and a pointer-to-array parameter `T (*a)[N]`
+/// has type `T (*)[N]`.
+fn parse_proto(proto: &str, doc: &str) -> Export {
+    let open = proto.find('(').unwrap();
+    let close = proto.rfind(')').unwrap();
+    let (ret, name) = split_decl(&proto[..open]);
+    let params = proto[open + 1..close].trim();
+    let args = if params == "void" {
+        Vec::new()
+    } else {
+        params
+            .split(',')
+            .map(|p| {
+                let (ty, name) = split_decl(p);
+                assert!(
+                    is_ident(&name),
+                    "cannot parse parameter `{}` of {proto}",
+                    p.trim()
+                );
+                Arg { name, ty }
+            })
+            .collect()
+    };
+    assert!(is_ident(&name), "cannot parse prototype {proto}");
+    Export {
+        name,
+        ret,
+        args,
+        doc: doc.to_string(),
+    }
+}
+
+fn split_decl(decl: &str) -> (String, String) {
+    let decl = decl.trim();
+    let (ty, name) = if let Some(open) = decl.find("(*") {
+        let close = open + decl[open..].find(')').unwrap();
+        (
+            format!("{} (*){}", &decl[..open], &decl[close + 1..]),
+            decl[open + 2..close].trim(),
+        )
+    } else {
+        let at = decl.rfind([' ', '*']).unwrap();
+        let (ty, name) = decl.split_at(at + 1);
+        (ty.to_string(), name)
+    };
+    (
+        ty.split_whitespace().collect::<Vec<_>>().join(" "),
+        name.to_string(),
+    )
+}
+
+/// Whether `s` is a C identifier, i.e. the declarator was understood.
+fn is_ident(s: &str) -> bool {
+    s.bytes().next().is_some_and(|b| !b.is_ascii_digit())
+        && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Whether the C type is a pointer (including a pointer to an array).
+fn is_pointer(ty: &str) -> bool {
+    ty.ends_with('*') || ty.contains("(*)")
+}
+
+/// Width in bits of a C type from the header; 0 for `void`.
+fn bits(ty: &str, ptr_bits: u32) -> u32 {
+    if is_pointer(ty) {
+        return ptr_bits;
+    }
+    match ty {
+        "void" => 0,
+        "bool" | "uint8_t" | "int8_t" => 8,
+        "uint16_t" | "int16_t" => 16,
+        "uint32_t" | "int32_t" | "float" => 32,
+        "uint64_t" | "int64_t" | "double" => 64,
+        "size_t" | "ptrdiff_t" => ptr_bits,
+        _ => panic!("simba.h uses a type the manifest does not know: {ty}"),
+    }
+}
+
+/// Whether the doc comment accepts a null `arg`: "`a` and `b` must be null
+/// or valid ...", "`p` must be either null or ...", "(null if `key` is null)".
+fn nullable(doc: &str, arg: &str) -> bool {
+    let tick = format!("`{arg}`");
+    doc.split([';', '.', '(', ')']).any(|clause| {
+        let Some(at) = clause.find(&tick) else {
+            return false;
+        };
+        let rest = &clause[at..];
+        ["must be null", "must be either null", "is null", "are null"]
+            .iter()
+            .any(|p| rest.contains(p))
+    })
+}
+
+/// Lane count from the kernel's doc comment: "N-lane", "N lanes", or the
+/// "picking the lane width" wording of the runtime-dispatched kernels.
+fn lanes(doc: &str) -> Option<String> {
+    if doc.contains("picking the lane width") || doc.contains("lane width picked") {
+        return Some("\"auto\"".into());
+    }
+    let words: Vec<&str> = doc.split_whitespace().collect();
+    words.iter().enumerate().find_map(|(i, w)| {
+        let n = if let Some(n) = w.strip_suffix("-lane") {
+            n
+        } else if words
+            .get(i + 1)
+            .is_some_and(|next| next.starts_with("lanes"))
+        {
+            w
+        } else {
+            return None;
+        };
+        n.parse::<u32>().ok().map(|n| n.to_string())
+    })
+}
+
+fn manifest(version: u32, prefix: &str, exports: &[Export], ptr_bits: u32) -> String {
+    let mut s = String::new();
+    writeln!(s, "{{").unwrap();
+    writeln!(s, "  \"abi_version\": {version},").unwrap();
+    writeln!(s, "  \"pointer_bits\": {ptr_bits},").unwrap();
+    writeln!(s, "  \"symbol_prefix\": \"{prefix}\",").unwrap();
+    writeln!(s, "  \"symbols\": [").unwrap();
+    for (i, e) in exports.iter().enumerate() {
+        writeln!(s, "    {{").unwrap();
+        writeln!(s, "      \"name\": \"{}\",", e.name).unwrap();
+        writeln!(
+            s,
+            "      \"lanes\": {},",
+            lanes(&e.doc).as_deref().unwrap_or("null")
+        )
+        .unwrap();
+        if e.ret == "void" {
+            writeln!(s, "      \"returns\": null,").unwrap();
+        } else {
+            writeln!(
+                s,
+                "      \"returns\": {{ \"type\": \"{}\", \"bits\": {} }},",
+                e.ret,
+                bits(&e.ret, ptr_bits)
+            )
+            .unwrap();
+        }
+        write!(s, "      \"args\": [").unwrap();
+        for (j, a) in e.args.iter().enumerate() {
+            let sep = if j == 0 { "\n" } else { ",\n" };
+            write!(
+                s,
+                "{sep}        {{ \"name\": \"{}\", \"type\": \"{}\", \"bits\": {}",
+                a.name,
+                a.ty,
+                bits(&a.ty, ptr_bits)
+            )
+            .unwrap();
+            if is_pointer(&a.ty) {
+                write!(s, ", \"nullable\": {}", nullable(&e.doc, &a.name)).unwrap();
+            }
+            write!(s, " }}").unwrap();
+        }
+        if e.args.is_empty() {
+            writeln!(s, "]").unwrap();
+        } else {
+            writeln!(s, "\n      ]").unwrap();
+        }
+        let comma = if i + 1 == exports.len() { "" } else { "," };
+        writeln!(s, "    }}{comma}").unwrap();
+    }
+    writeln!(s, "  ]").unwrap();
+    writeln!(s, "}}").unwrap();
+    s
+}
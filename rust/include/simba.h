@@ -0,0 +1,3810 @@
+/* SIMBA: SIMD kernels exported by the Rust crate in rust/. */
+
+#ifndef SIMBA_H
+#define SIMBA_H
+
+/* Generated by scripts/gen_header.sh (cbindgen); do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Malformed input: a bad character, a truncated stream, ...
+ */
+#define SIMBA_ERR_INVALID_INPUT -1
+
+/**
+ * The caller's output buffer is too small.
+ */
+#define SIMBA_ERR_CAPACITY -2
+
+/**
+ * A required pointer argument is null.
+ */
+#define SIMBA_ERR_NULL_POINTER -3
+
+/**
+ * A parameter is outside the range the kernel supports.
+ */
+#define SIMBA_ERR_UNSUPPORTED -4
+
+/**
+ * A parsed value does not fit the result type.
+ */
+#define SIMBA_ERR_OVERFLOW -5
+
+/**
+ * `flags` value: pipeline buffers of at least [`PIPELINE_THRESHOLD`] bytes.
+ */
+#define PIPELINE_AUTO 0
+
+/**
+ * `flags` value: always pipeline.
+ */
+#define PIPELINE_ON 1
+
+/**
+ * `flags` value: never pipeline; same as the plain kernel.
+ */
+#define PIPELINE_OFF 2
+
+/**
+ * Size from which [`PIPELINE_AUTO`] pipelines: larger than the L2 of
+ * current cores, so the buffer is streamed from L3 or DRAM.
+ */
+#define PIPELINE_THRESHOLD (4 << 20)
+
+/**
+ * [`simba_selftest`] failure bit: CRC32C and CRC32 (IEEE).
+ */
+#define SELFTEST_CRC (1 << 0)
+
+/**
+ * [`simba_selftest`] failure bit: byte sums.
+ */
+#define SELFTEST_SUM (1 << 1)
+
+/**
+ * [`simba_selftest`] failure bit: byte-equality bitmasks.
+ */
+#define SELFTEST_MASKS (1 << 2)
+
+/**
+ * [`simba_selftest`] failure bit: lookup-table map, validate and count.
+ */
+#define SELFTEST_LUT (1 << 3)
+
+/**
+ * [`simba_selftest`] failure bit: XXH64 and XXH3.
+ */
+#define SELFTEST_HASH (1 << 4)
+
+/**
+ * [`simba_selftest`] failure bit: ASCII validation.
+ */
+#define SELFTEST_ASCII (1 << 5)
+
+/**
+ * Maximum number of patterns per set.
+ */
+#define TEDDY_MAX_PATTERNS 64
+
+/**
+ * `kind` value for [`simba_validator_new`]: every byte must be < 0x80.
+ */
+#define VALIDATOR_ASCII 0
+
+/**
+ * `kind` value for [`simba_validator_new`]: the stream must be valid UTF-8.
+ */
+#define VALIDATOR_UTF8 1
+
+/**
+ * Streaming BLAKE3 state.  Opaque to callers.
+ */
+struct Blake3;
+
+/**
+ * Streaming hasher for one algorithm.  Opaque to callers.
+ */
+struct Hasher;
+
+/**
+ * Needle plus the offsets of the two probe bytes used by the SIMD filter.
+ */
+struct Searcher;
+
+/**
+ * Streaming SHA-256 state.  Opaque to callers.
+ */
+struct Sha256;
+
+/**
+ * A compiled pattern set.  Opaque to callers.
+ */
+struct Teddy;
+
+/**
+ * State carried between chunks.  Opaque to callers.
+ */
+struct Validator;
+
+/**
+ * Streaming XXH3 state (64- and 128-bit digests).  Opaque to callers.
+ */
+struct Xxh3;
+
+/**
+ * Streaming xxHash64 state.  Opaque to callers.
+ */
+struct Xxh64;
+
+/**
+ * One input segment, laid out as POSIX `struct iovec`.
+ */
+struct Iovec {
+  const uint8_t *ptr;
+  size_t len;
+};
+
+/**
+ *Smallest and largest of a `i16` buffer, written by [`minmax_i16`].
+ */
+struct MinMaxI16 {
+  int16_t min;
+  int16_t max;
+};
+
+/**
+ *Smallest and largest of a `i32` buffer, written by [`minmax_i32`].
+ */
+struct MinMaxI32 {
+  int32_t min;
+  int32_t max;
+};
+
+/**
+ *Smallest and largest of a `i64` buffer, written by [`minmax_i64`].
+ */
+struct MinMaxI64 {
+  int64_t min;
+  int64_t max;
+};
+
+/**
+ *Smallest and largest of a `i8` buffer, written by [`minmax_i8`].
+ */
+struct MinMaxI8 {
+  int8_t min;
+  int8_t max;
+};
+
+/**
+ *Smallest and largest of a `u16` buffer, written by [`minmax_u16`].
+ */
+struct MinMaxU16 {
+  uint16_t min;
+  uint16_t max;
+};
+
+/**
+ *Smallest and largest of a `u32` buffer, written by [`minmax_u32`].
+ */
+struct MinMaxU32 {
+  uint32_t min;
+  uint32_t max;
+};
+
+/**
+ *Smallest and largest of a `u64` buffer, written by [`minmax_u64`].
+ */
+struct MinMaxU64 {
+  uint64_t min;
+  uint64_t max;
+};
+
+/**
+ *Smallest and largest of a `u8` buffer, written by [`minmax_u8`].
+ */
+struct MinMaxU8 {
+  uint8_t min;
+  uint8_t max;
+};
+
+/**
+ * A 256-entry byte-class table compiled for nibble-shuffle lookup (the
+ * pshufb/tbl technique): byte `b` is a member when
+ * `hi[k][b >> 4] & lo[k][b & 15]` is non-zero for `k` 0 or 1.  Each distinct
+ * non-empty row of the table (the set of low nibbles accepted under one high
+ * nibble) gets one of the 16 class bits, so every table compiles exactly.
+ * Build one with `nibble_lut_compile`.
+ */
+struct NibbleLut {
+  uint8_t lo[2][16];
+  uint8_t hi[2][16];
+};
+
+/**
+ * Echo structure for detailed trampoline debugging (test builds only).
+ */
+struct Echo {
+  size_t ptr;
+  size_t len;
+  uint32_t v32;
+  uint8_t v8;
+  uint64_t v64;
+  uint64_t f64bits;
+  uint32_t f32bits;
+};
+
+/**
+ * Outcome of [`varint_decode_u64`].
+ */
+struct VarintBatch {
+  /**
+   * Input bytes consumed; on error, the offset of the malformed varint.
+   */
+  size_t consumed;
+  /**
+   * Values written to `dst`.
+   */
+  size_t produced;
+};
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Combine two Adler-32 checksums: given `adler1 = adler32(A)`,
+ * `adler2 = adler32(B)` and `len2 = len(B)`, return `adler32(A || B)`.
+ */
+uint32_t adler32_combine(uint32_t adler1, uint32_t adler2, size_t len2);
+
+/**
+ * Update an Adler-32 checksum (start from 1) with additional bytes.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t adler32_update(uint32_t init, const uint8_t *ptr, size_t len);
+
+/**
+ * Return 1 if every byte lies in `lo..=hi`, 0 otherwise (always 0 for a
+ * non-empty input when `lo > hi`).  Uses plain compares instead of the table
+ * gather of `validate_u8_lut*`, for digit-only or printable-only fields.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint8_t all_in_range_u8(const uint8_t *ptr, size_t len, uint8_t lo, uint8_t hi);
+
+/**
+ * Composite `n_pixels` premultiplied RGBA8 pixels from `src` over `dst`
+ * (Porter-Duff source-over), in place: every channel, alpha included,
+ * becomes `s + round(d * (255 - s_alpha) / 255)`, saturating at 255 for
+ * inputs that are not validly premultiplied.  Convert straight-alpha
+ * pixels with [`premultiply_alpha`] first.
+ *
+ * # Safety
+ * `dst` and `src` must be valid for `4 * n_pixels` bytes and must not
+ * partially overlap.
+ */
+void alpha_blend(uint8_t *dst, const uint8_t *src, size_t n_pixels);
+
+/**
+ * Estimate the number of distinct values among `n` 64-bit hashes using a
+ * HyperLogLog sketch with 2^12 registers (standard error ≈ 1.6 %).
+ *
+ * The inputs must already be well-mixed hashes, not raw keys: the top 12
+ * bits pick the register and the remaining bits feed the rank.
+ *
+ * # Safety
+ * `hashes` must be null or valid for `n` u64 values.
+ */
+uint64_t approx_distinct_u64(const uint64_t *hashes, size_t n);
+
+/**
+ * Index of the first maximum of the `len` `f32` values at `ptr`, or -1
+ * when there is none (empty or all NaN).  NaNs are skipped.  The maximum
+ * itself is written to `value` unless that is null.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` values; `value` must be null or
+ * valid for one write.
+ */
+ptrdiff_t argmax_f32(const float *ptr, size_t len, float *value);
+
+/**
+ *`dst[i] += a * x[i]` over `len` `f32` values (the product is rounded before the add; no FMA).
+ *
+ *# Safety `dst` and `x` must be valid for `len` values.  `dst` may alias `x` exactly, but must not partially overlap it.
+ */
+void axpy_f32(float *dst,
+              const float *x,
+              float a,
+              size_t len);
+
+/**
+ *`dst[i] += a * x[i]` over `len` `f64` values (the product is rounded before the add; no FMA).
+ *
+ *# Safety `dst` and `x` must be valid for `len` values.  `dst` may alias `x` exactly, but must not partially overlap it.
+ */
+void axpy_f64(double *dst,
+              const double *x,
+              double a,
+              size_t len);
+
+/**
+ *Base32-decode `len` characters of the extended-hex (`0-9A-V`) alphabet (case-sensitive); trailing `=` padding is optional but, when present, the input length must be a multiple of 8. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base32_decoded_len(len)` bytes and must not overlap `src`.
+ */
+ptrdiff_t base32_decode_hex(const uint8_t *src,
+                            size_t len,
+                            uint8_t *dst);
+
+/**
+ *Base32-decode `len` characters of the standard (`A-Z2-7`) alphabet (case-sensitive); trailing `=` padding is optional but, when present, the input length must be a multiple of 8. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base32_decoded_len(len)` bytes and must not overlap `src`.
+ */
+ptrdiff_t base32_decode_std(const uint8_t *src,
+                            size_t len,
+                            uint8_t *dst);
+
+/**
+ * Return the `dst` capacity required by the `base32_decode_*` kernels for
+ * `len` input characters: `5 * len / 8` rounded down to whole bytes.  This is
+ * exact for unpadded input and an upper bound for padded input.
+ */
+size_t base32_decoded_len(size_t len);
+
+/**
+ *Base32-encode `len` bytes with the extended-hex (`0-9A-V`) alphabet (RFC 4648, `=`-padded). Returns the number of bytes written, `base32_encoded_len(len)`.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base32_encoded_len(len)` bytes and must not overlap `src`.
+ */
+size_t base32_encode_hex(const uint8_t *src,
+                         size_t len,
+                         uint8_t *dst);
+
+/**
+ *Base32-encode `len` bytes with the standard (`A-Z2-7`) alphabet (RFC 4648, `=`-padded). Returns the number of bytes written, `base32_encoded_len(len)`.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base32_encoded_len(len)` bytes and must not overlap `src`.
+ */
+size_t base32_encode_std(const uint8_t *src,
+                         size_t len,
+                         uint8_t *dst);
+
+/**
+ * Return the padded base32 length of `len` input bytes (`8 * ceil(len / 5)`),
+ * i.e. the `dst` capacity required by the `base32_encode_*` kernels.
+ */
+size_t base32_encoded_len(size_t len);
+
+/**
+ * Base58-decode `len` characters of the Bitcoin alphabet; each leading `1`
+ * becomes a leading zero byte.  Returns the number of bytes written on
+ * success, or `-(offset + 1)` where `offset` is the position of the first
+ * invalid character.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `base58_decoded_max_len(len)` bytes and must not overlap `src`.
+ */
+ptrdiff_t base58_decode(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ * Return the `dst` capacity required by [`base58_decode`] for `len` input
+ * characters.  This bounds the output and the limb scratch kept in `dst`.
+ */
+size_t base58_decoded_max_len(size_t len);
+
+/**
+ * Base58-encode `len` bytes with the Bitcoin alphabet; each leading zero byte
+ * becomes a leading `1`.  Returns the number of bytes written.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `base58_encoded_max_len(len)` bytes and must not overlap `src`.
+ */
+size_t base58_encode(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ * Return the `dst` capacity required by [`base58_encode`] for `len` input
+ * bytes (`len + 4` for [`base58check_encode`]).  This bounds the output and
+ * the limb scratch kept in `dst`, and exceeds the true maximum
+ * (`ceil(len * log(256) / log(58))`) by at most 1% plus five bytes.
+ */
+size_t base58_encoded_max_len(size_t len);
+
+/**
+ * Base58Check-encode `len` payload bytes: base58 of the payload followed by
+ * the first four bytes of its double SHA-256.  A version byte, if any, is
+ * part of the payload.  Returns the number of bytes written.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `base58_encoded_max_len(len + 4)` bytes and must not overlap `src`.
+ */
+size_t base58check_encode(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ *Base64-decode `len` characters of the standard (`+/`) alphabet; trailing `=` padding is optional but, when present, the input length must be a multiple of 4. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base64_decoded_len(len)` bytes and must not overlap `src`.
+ */
+ptrdiff_t base64_decode_std(const uint8_t *src,
+                            size_t len,
+                            uint8_t *dst);
+
+/**
+ *Base64-decode `len` characters of the URL-safe (`-_`) alphabet; trailing `=` padding is optional but, when present, the input length must be a multiple of 4. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base64_decoded_len(len)` bytes and must not overlap `src`.
+ */
+ptrdiff_t base64_decode_url(const uint8_t *src,
+                            size_t len,
+                            uint8_t *dst);
+
+/**
+ * Return the `dst` capacity required by the `base64_decode_*` kernels for
+ * `len` input characters: `3 * len / 4` rounded down to whole bytes.  This is
+ * exact for unpadded input and an upper bound for padded input.
+ */
+size_t base64_decoded_len(size_t len);
+
+/**
+ *Base64-encode `len` bytes with the standard (`+/`) alphabet (RFC 4648, `=`-padded) using a shuffle-based SIMD kernel. Returns the number of bytes written, `base64_encoded_len(len)`.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base64_encoded_len(len)` bytes and must not overlap `src`.
+ */
+size_t base64_encode_std(const uint8_t *src,
+                         size_t len,
+                         uint8_t *dst);
+
+/**
+ *Base64-encode `len` bytes with the URL-safe (`-_`) alphabet (RFC 4648, `=`-padded) using a shuffle-based SIMD kernel. Returns the number of bytes written, `base64_encoded_len(len)`.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `base64_encoded_len(len)` bytes and must not overlap `src`.
+ */
+size_t base64_encode_url(const uint8_t *src,
+                         size_t len,
+                         uint8_t *dst);
+
+/**
+ * Return the padded base64 length of `len` input bytes (`4 * ceil(len / 3)`),
+ * i.e. the `dst` capacity required by the `base64_encode_*` kernels.
+ */
+size_t base64_encoded_len(size_t len);
+
+/**
+ * Find the first window of `hay` that equals `pattern` up to at most
+ * `max_errors` substituted bytes (Hamming distance), using the Shift-And
+ * bit-parallel automaton: each haystack byte costs `max_errors + 1` word
+ * operations regardless of pattern length.
+ *
+ * Returns the start offset of the earliest matching window, -1 if there is
+ * none, or -2 if `plen` exceeds 64 bytes.  An empty pattern matches at 0.
+ *
+ * # Safety
+ * `hay`/`pattern` must be null or valid for `len`/`plen` bytes.
+ */
+ptrdiff_t bitap_find(const uint8_t *hay,
+                     size_t len,
+                     const uint8_t *pattern,
+                     size_t plen,
+                     uint32_t max_errors);
+
+/**
+ *Compute `dst[i] = a[i] & b[i]` over `nwords` u64 bitset words.
+ *
+ *# Safety All pointers must be valid for `nwords` u64 values.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either.
+ */
+void bitset_and(uint64_t *dst,
+                const uint64_t *a,
+                const uint64_t *b,
+                size_t nwords);
+
+/**
+ * Return the number of bits set in `a & b` over `nwords` u64 words without
+ * materializing the intersection.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `nwords` u64 values.
+ */
+uint64_t bitset_and_count(const uint64_t *a, const uint64_t *b, size_t nwords);
+
+/**
+ *Compute `dst[i] = a[i] & !b[i]` over `nwords` u64 bitset words.
+ *
+ *# Safety All pointers must be valid for `nwords` u64 values.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either.
+ */
+void bitset_andnot(uint64_t *dst,
+                   const uint64_t *a,
+                   const uint64_t *b,
+                   size_t nwords);
+
+/**
+ *Compute `dst[i] = a[i] | b[i]` over `nwords` u64 bitset words.
+ *
+ *# Safety All pointers must be valid for `nwords` u64 values.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either.
+ */
+void bitset_or(uint64_t *dst,
+               const uint64_t *a,
+               const uint64_t *b,
+               size_t nwords);
+
+/**
+ * BLAKE3 hash of `len` bytes.  Writes `out_len` bytes of output (32 for the
+ * standard digest; longer outputs extend it).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for
+ * `out_len` bytes.
+ */
+void blake3(const uint8_t *ptr, size_t len, uint8_t *out, size_t out_len);
+
+/**
+ * BLAKE3 key derivation: derive `out_len` bytes of key material from `len`
+ * bytes of input keying material under the application's `context` string.
+ *
+ * # Safety
+ * As [`blake3`]; `context` must be null or valid for `context_len` bytes.
+ */
+void blake3_derive_key(const uint8_t *context,
+                       size_t context_len,
+                       const uint8_t *ptr,
+                       size_t len,
+                       uint8_t *out,
+                       size_t out_len);
+
+/**
+ * Write `out_len` output bytes for everything fed so far; the state is left
+ * intact, so more input may follow.
+ *
+ * # Safety
+ * `s` must be null or a live handle; `out` must be valid for `out_len` bytes.
+ */
+void blake3_finalize(const struct Blake3 *s, uint8_t *out, size_t out_len);
+
+/**
+ * Release a handle from one of the `blake3_new*` constructors.  Null is
+ * ignored.
+ *
+ * # Safety
+ * `s` must be null or a live handle; it must not be used afterwards.
+ */
+void blake3_free(struct Blake3 *s);
+
+/**
+ * Keyed BLAKE3 (a MAC/PRF) of `len` bytes under the 32-byte `key`.
+ *
+ * # Safety
+ * As [`blake3`]; `key` must be valid for 32 bytes.
+ */
+void blake3_keyed(const uint8_t *key, const uint8_t *ptr, size_t len, uint8_t *out, size_t out_len);
+
+/**
+ * Start a streaming BLAKE3 hash.  Release with [`blake3_free`].
+ */
+struct Blake3 *blake3_new(void);
+
+/**
+ * Start a streaming BLAKE3 key derivation for `context`.
+ *
+ * # Safety
+ * `context` must be null or valid for `context_len` bytes.
+ */
+struct Blake3 *blake3_new_derive_key(const uint8_t *context, size_t context_len);
+
+/**
+ * Start a streaming keyed BLAKE3 hash (null if `key` is null).
+ *
+ * # Safety
+ * `key` must be null or valid for 32 bytes.
+ */
+struct Blake3 *blake3_new_keyed(const uint8_t *key);
+
+/**
+ * Feed `len` more bytes to a streaming BLAKE3.
+ *
+ * # Safety
+ * `s` must be a live handle; `ptr` must be null or valid for `len` bytes.
+ */
+void blake3_update(struct Blake3 *s, const uint8_t *ptr, size_t len);
+
+/**
+ * Insert the `n` hashes at `hashes` into the Bloom filter in `bits`
+ * (`nwords` u64 words), setting `k` bits per key, in order.  When `out` is
+ * non-null it receives `n.div_ceil(64)` mask words marking the keys that
+ * were already present (all `k` bits set, including by an earlier key of
+ * the same batch), as a test-and-set.  Returns the number of such keys,
+ * or `SIMBA_ERR_INVALID_INPUT` if `nwords` is 0 or above 2^32.
+ *
+ * # Safety
+ * `bits` must be valid for `nwords` words, `hashes` for `n` values and
+ * `out` null or valid for `n.div_ceil(64)` words.
+ */
+ptrdiff_t bloom_insert(uint64_t *bits,
+                       size_t nwords,
+                       uint32_t k,
+                       const uint64_t *hashes,
+                       size_t n,
+                       uint64_t *out);
+
+/**
+ * Probe `k` bits per key for the `n` hashes at `hashes` against the Bloom
+ * filter in `bits` (`nwords` u64 words).  Writes `n.div_ceil(64)` mask
+ * words to `out`, bit `j % 64` of word `j / 64` set when `hashes[j]` may
+ * be present (bits past `n` are zero), and returns the number of such
+ * hits, or `SIMBA_ERR_INVALID_INPUT` if `nwords` is 0 or above 2^32.
+ *
+ * # Safety
+ * `bits` must be valid for `nwords` words, `hashes` for `n` values and
+ * `out` for `n.div_ceil(64)` words.
+ */
+ptrdiff_t bloom_probe(const uint64_t *bits,
+                      size_t nwords,
+                      uint32_t k,
+                      const uint64_t *hashes,
+                      size_t n,
+                      uint64_t *out);
+
+/**
+ *Reverse the byte order of `count` u16 elements (32 per SIMD step), e.g. big-endian wire data to host order. The buffers need no alignment.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u16>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap16_buf(const uint8_t *src,
+                 size_t count,
+                 uint8_t *dst);
+
+/**
+ *`bswap16_buf` with non-temporal stores.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u16>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap16_buf_nt(const uint8_t *src,
+                    size_t count,
+                    uint8_t *dst);
+
+/**
+ *Reverse the byte order of `count` u32 elements (16 per SIMD step), e.g. big-endian wire data to host order. The buffers need no alignment.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u32>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap32_buf(const uint8_t *src,
+                 size_t count,
+                 uint8_t *dst);
+
+/**
+ *`bswap32_buf` with non-temporal stores.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u32>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap32_buf_nt(const uint8_t *src,
+                    size_t count,
+                    uint8_t *dst);
+
+/**
+ *Reverse the byte order of `count` u64 elements (8 per SIMD step), e.g. big-endian wire data to host order. The buffers need no alignment.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u64>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap64_buf(const uint8_t *src,
+                 size_t count,
+                 uint8_t *dst);
+
+/**
+ *`bswap64_buf` with non-temporal stores.
+ *
+ *# Safety `src` and `dst` must be valid for `count * size_of::<u64>()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void bswap64_buf_nt(const uint8_t *src,
+                    size_t count,
+                    uint8_t *dst);
+
+/**
+ * XOR `len` bytes of `src` with the ChaCha20 keystream for the 32-byte
+ * `key` and 12-byte `nonce`, starting at block `counter`, into `dst`.
+ * Encryption and decryption are the same operation.  Returns 0, or
+ *
+ * * `SIMBA_ERR_NULL_POINTER` if a pointer is null while `len > 0`;
+ * * `SIMBA_ERR_OVERFLOW` if the input would run the 32-bit block counter
+ *   past `2^32 - 1` (RFC 8439 caps a nonce at 256 GiB of keystream).
+ *
+ * Nothing is written on error.
+ *
+ * # Safety
+ * `key` must be valid for 32 bytes, `nonce` for 12, and `src` and `dst`
+ * for `len` bytes.  `dst` may alias `src` exactly (in-place), but must not
+ * partially overlap it.
+ */
+int32_t chacha20_xor(const uint8_t *key,
+                     const uint8_t *nonce,
+                     uint32_t counter,
+                     const uint8_t *src,
+                     size_t len,
+                     uint8_t *dst);
+
+/**
+ * Clamp `len` f32 values in place to `[lo, hi]`.  NaN becomes `lo`; if
+ * `lo > hi` every value becomes `hi`.
+ *
+ * # Safety
+ * `dst` must be null or valid for `len` f32 values.
+ */
+void clamp_f32(float *dst, size_t len, float lo, float hi);
+
+/**
+ *Generate class-membership bitmasks across chunks of 16 lanes: bit `i` of a word is set when the byte's entry in the 256-byte `lut` is non-zero. Writes and returns `len.div_ceil(16)` words; the last word covers a partial chunk when `len` is not a multiple of 16, with the bits past the end zero.
+ *
+ *# Safety `src`/`lut` must be valid for `len`/256 bytes; `out` must be valid for `len.div_ceil(16)` elements.
+ */
+size_t classify_u8_masks16(const uint8_t *src,
+                           size_t len,
+                           const uint8_t *lut,
+                           uint16_t *out);
+
+/**
+ *Generate class-membership bitmasks across chunks of 32 lanes: bit `i` of a word is set when the byte's entry in the 256-byte `lut` is non-zero. Writes and returns `len.div_ceil(32)` words; the last word covers a partial chunk when `len` is not a multiple of 32, with the bits past the end zero.
+ *
+ *# Safety `src`/`lut` must be valid for `len`/256 bytes; `out` must be valid for `len.div_ceil(32)` elements.
+ */
+size_t classify_u8_masks32(const uint8_t *src,
+                           size_t len,
+                           const uint8_t *lut,
+                           uint32_t *out);
+
+/**
+ *Generate class-membership bitmasks across chunks of 64 lanes: bit `i` of a word is set when the byte's entry in the 256-byte `lut` is non-zero. Writes and returns `len.div_ceil(64)` words; the last word covers a partial chunk when `len` is not a multiple of 64, with the bits past the end zero.
+ *
+ *# Safety `src`/`lut` must be valid for `len`/256 bytes; `out` must be valid for `len.div_ceil(64)` elements.
+ */
+size_t classify_u8_masks64(const uint8_t *src,
+                           size_t len,
+                           const uint8_t *lut,
+                           uint64_t *out);
+
+/**
+ * Copy the bytes whose entry in the 256-byte `lut` is non-zero from `src` to
+ * `dst`, preserving order, e.g. to strip whitespace.  Returns the number of
+ * bytes written.
+ *
+ * # Safety
+ * `src`/`lut` must be valid for `len`/256 bytes; `dst` must be valid for
+ * `len` bytes.  `dst` may alias `src` exactly (in-place), but must not
+ * partially overlap it.
+ */
+size_t compact_u8(const uint8_t *src, size_t len, const uint8_t *lut, uint8_t *dst);
+
+/**
+ * Copy the bytes of `src` whose bit is set in `masks` to `dst`, preserving
+ * order: bit `i` of word `w` selects byte `64 * w + i`, the layout written by
+ * `classify_u8_masks64` and `eq_u8_masks64_full`.  Returns the number of
+ * bytes written.
+ *
+ * # Safety
+ * `src` must be valid for `len` bytes, `masks` for `len.div_ceil(64)` words
+ * and `dst` for `len` bytes.  `dst` may alias `src` exactly (in-place), but
+ * must not partially overlap it.
+ */
+size_t compact_u8_masks(const uint8_t *src, size_t len, const uint64_t *masks, uint8_t *dst);
+
+/**
+ * Compare two byte strings lexicographically (unsigned bytes, shorter prefix
+ * sorts first), like Go's `bytes.Compare`.  Returns -1, 0 or 1.
+ *
+ * # Safety
+ * `a`/`b` must be null or valid for `alen`/`blen` bytes.  A null pointer is
+ * treated as an empty string.
+ */
+int32_t compare_u8(const uint8_t *a, size_t alen, const uint8_t *b, size_t blen);
+
+/**
+ * Cosine similarity `a . b / (|a| |b|)` of the `dim`-element vectors `a`
+ * and `b`, in `[-1, 1]`; 0 when either vector is all zeros.  The norms are
+ * combined in f64, so large vectors do not overflow.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `dim` values.
+ */
+float cosine_similarity_f32(const float *a, const float *b, size_t dim);
+
+/**
+ * Count the non-overlapping occurrences of `needle` in `haystack`, scanning
+ * left to right like Go's `bytes.Count`; candidates are filtered with the
+ * same SIMD probe as [`find_substring`].  An empty needle matches at every
+ * position and yields `hlen + 1`.
+ *
+ * # Safety
+ * `haystack`/`needle` must be null or valid for `hlen`/`nlen` bytes.
+ */
+uint64_t count_substring(const uint8_t *haystack, size_t hlen, const uint8_t *needle, size_t nlen);
+
+/**
+ *Count the bytes equal to `needle` using a 16-lane SIMD kernel.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_u8_16(const uint8_t *ptr, size_t len, uint8_t needle);
+
+/**
+ *Count the bytes equal to `needle` using a 32-lane SIMD kernel.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_u8_32(const uint8_t *ptr, size_t len, uint8_t needle);
+
+/**
+ *Count the bytes equal to `needle` using a 64-lane SIMD kernel.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_u8_64(const uint8_t *ptr, size_t len, uint8_t needle);
+
+/**
+ * Count the bytes with a non-zero entry in the 256-byte class table `lut`
+ * (e.g. printable characters), without a map + sum round trip.  Inputs of a
+ * vector or more classify with nibble shuffles (see `NibbleLut`); shorter
+ * ones read `lut` directly.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `lut` must be valid for 256
+ * bytes.
+ */
+uint64_t count_u8_lut(const uint8_t *ptr, size_t len, const uint8_t *lut);
+
+/**
+ * `count_u8_lut` with a registered table: the number of bytes with a
+ * non-zero entry, or `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+int64_t count_u8_lut_id(const uint8_t *ptr, size_t len, uint32_t id);
+
+/**
+ * Count UTF-8 code points; alias of the 64-lane kernel.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_utf8_runes(const uint8_t *ptr, size_t len);
+
+/**
+ *Count the UTF-8 code points in the buffer as the bytes that are not continuation bytes (`0b10xx_xxxx`), using a 32-lane SIMD kernel. The input is not validated: on valid UTF-8 this equals Go's `utf8.RuneCount`, but a stray continuation byte counts 0 where Go counts one `RuneError`.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_utf8_runes_32(const uint8_t *ptr,
+                             size_t len);
+
+/**
+ *Count the UTF-8 code points in the buffer as the bytes that are not continuation bytes (`0b10xx_xxxx`), using a 64-lane SIMD kernel. The input is not validated: on valid UTF-8 this equals Go's `utf8.RuneCount`, but a stray continuation byte counts 0 where Go counts one `RuneError`.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t count_utf8_runes_64(const uint8_t *ptr,
+                             size_t len);
+
+/**
+ * Update CRC16-CCITT (polynomial 0x1021, MSB-first, no final XOR; the
+ * "CCITT-FALSE" parameters) with additional bytes.  Start from 0xFFFF.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint16_t crc16_ccitt_update(const uint8_t *ptr, size_t len, uint16_t init);
+
+/**
+ * Update CRC16-MODBUS (polynomial 0x8005 reflected, no final XOR) with
+ * additional bytes.  Start from 0xFFFF; the result goes on the wire low
+ * byte first.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint16_t crc16_modbus_update(const uint8_t *ptr, size_t len, uint16_t init);
+
+uint32_t crc32_combine(uint32_t crc1, uint32_t crc2, size_t len2);
+
+/**
+ * Combine `n` finalised CRC32C digests of consecutive chunks into the digest
+ * of their concatenation: `crcs[i]` covers `lens[i]` bytes.  Equivalent to
+ * folding [`crc32_combine_raw`] over the array in one call.  Returns 0 (the
+ * CRC of no data) when `n` is 0.
+ *
+ * # Safety
+ * `crcs` and `lens` must be null or valid for `n` reads each.
+ */
+uint32_t crc32_combine_many(const uint32_t *crcs, const size_t *lens, size_t n);
+
+/**
+ * Combine two finalised CRC32C digests (Castagnoli) as per Go's semantics.
+ */
+uint32_t crc32_combine_raw(uint32_t crc1, uint32_t crc2, size_t len2);
+
+/**
+ * Combine two finalised CRC32 (IEEE) digests: given `crc1 = crc(A)`,
+ * `crc2 = crc(B)` and `len2 = len(B)`, return `crc(A || B)`.
+ */
+uint32_t crc32_ieee_combine(uint32_t crc1, uint32_t crc2, size_t len2);
+
+/**
+ *Update CRC32 (IEEE, polynomial 0xEDB88320) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_ieee_update_32(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ *Update CRC32 (IEEE, polynomial 0xEDB88320) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_ieee_update_64(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ *Update CRC32C (Castagnoli) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_update_32(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ *Update CRC32C (Castagnoli) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_update_32_raw(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ *Update CRC32C (Castagnoli) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_update_64(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ *Update CRC32C (Castagnoli) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32_update_64_raw(const uint8_t *ptr, size_t len, uint32_t init);
+
+/**
+ * Update a finalised CRC32C with every segment in order (as
+ * `crc32_update_64` over the concatenation).
+ *
+ * # Safety
+ * `iov` must be null or valid for `n` entries, each null or valid for `len`
+ * bytes.
+ */
+uint32_t crc32_update_iov(const struct Iovec *iov, size_t n, uint32_t init);
+
+/**
+ * `crc32c` split across up to `nthreads` threads (0 = one per CPU); the
+ * CRCs of the pieces are combined in order.  Buffers under 2 MiB, or
+ * `nthreads == 1`, run on the calling thread.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32c_parallel(const uint8_t *ptr, size_t len, uint32_t init, size_t nthreads);
+
+/**
+ * `crc32c` with a big-buffer mode selected by `flags` (see the module docs):
+ * the CRCs of the two halves are combined at the end.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t crc32c_pipelined(const uint8_t *ptr, size_t len, uint32_t init, uint32_t flags);
+
+/**
+ *Combine two finalised CRC64 (NVMe, polynomial 0x9A6C9329AC4BC9B5 reflected) digests: given `crc1 = crc(A)`, `crc2 = crc(B)` and `len2 = len(B)`, return `crc(A || B)`.
+ */
+uint64_t crc64_nvme_combine(uint64_t crc1,
+                            uint64_t crc2,
+                            size_t len2);
+
+/**
+ *Update CRC64 (NVMe, polynomial 0x9A6C9329AC4BC9B5 reflected) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t crc64_nvme_update(const uint8_t *ptr, size_t len, uint64_t init);
+
+/**
+ *Combine two finalised CRC64 (XZ / ECMA-182, polynomial 0xC96C5795D7870F42 reflected) digests: given `crc1 = crc(A)`, `crc2 = crc(B)` and `len2 = len(B)`, return `crc(A || B)`.
+ */
+uint64_t crc64_xz_combine(uint64_t crc1,
+                          uint64_t crc2,
+                          size_t len2);
+
+/**
+ *Update CRC64 (XZ / ECMA-182, polynomial 0xC96C5795D7870F42 reflected) with additional bytes.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t crc64_xz_update(const uint8_t *ptr, size_t len, uint64_t init);
+
+/**
+ * Hardware features the CRC kernels currently use, as a bit set: 1 = CRC32
+ * instruction (SSE4.2 on x86_64, the CRC extension on aarch64), 2 =
+ * carry-less multiply folding (PCLMULQDQ).  0 means every CRC runs on the
+ * table-driven fallback.
+ */
+uint32_t crc_hw_features(void);
+
+/**
+ * Restrict the CRC kernels to the hardware features in `mask` (see
+ * [`crc_hw_features`]) and return the resulting set.  Features the CPU lacks
+ * are never enabled, so 0 forces the table-driven fallback and `u32::MAX`
+ * restores full detection.  Results are identical whichever kernels run.
+ */
+uint32_t crc_set_hw_features(uint32_t mask);
+
+/**
+ * Compare two NUL-terminated strings over at most `max` bytes, like
+ * `strncmp(a, b, max) == 0`.  Returns 1 if equal, 0 otherwise.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `max` bytes each.  Two null
+ * pointers compare equal; a null and a non-null pointer do not.
+ */
+uint8_t cstr_eq(const uint8_t *a, const uint8_t *b, size_t max);
+
+/**
+ * Return the length of the NUL-terminated string at `ptr`, scanning at most
+ * `max_scan` bytes, or -1 if no NUL occurs within the bound.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `max_scan` bytes (the whole field, even if
+ * the terminator comes earlier): the kernel reads full vectors.
+ */
+ptrdiff_t cstr_len(const uint8_t *ptr, size_t max_scan);
+
+/**
+ * Scan `len` bytes of CSV and write three mask words per 64-byte block (the
+ * last block may be partial) to `out`, as `[delims, quotes, newlines]`
+ * triples: bit `i` of block `b`'s words refers to byte `64 * b + i`.
+ *
+ * * delims: `delim` bytes outside quoted fields.
+ * * quotes: every `quote` byte.
+ * * newlines: `\n` bytes outside quoted fields.
+ *
+ * `state` carries the in-quote flag between calls so a large input can be
+ * scanned in chunks; start with 0.  Every chunk but the last must be a
+ * multiple of 64 bytes.  Returns the number of blocks written,
+ * `len.div_ceil(64)`.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `state` must be valid; `out`
+ * must be valid for `3 * len.div_ceil(64)` u64 writes.
+ */
+size_t csv_scan_masks(const uint8_t *ptr,
+                      size_t len,
+                      uint8_t delim,
+                      uint8_t quote,
+                      uint64_t *state,
+                      uint64_t *out);
+
+/**
+ * Constant-time equality for secrets (MACs, tokens): returns 1 if the `len`
+ * bytes at `a` and `b` are equal, 0 otherwise.  Unlike `mismatch_index`,
+ * `compare_u8` and the other comparison kernels, this never stops at the
+ * first difference: it always reads all `len` bytes of both buffers, and
+ * neither the loop nor the final 0/1 conversion branches on their
+ * contents, so the running time depends on `len` alone.  Only the lengths
+ * and the pointers' nullness are public.
+ *
+ * # Safety
+ * `a` and `b` must be valid for `len` bytes; a null pointer compares
+ * unequal unless `len` is 0.
+ */
+uint8_t ct_eq_u8(const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ *Split `2 * n` interleaved `f32` elements from `src` into the even (`a`) and odd (`b`) streams.
+ *
+ *# Safety `src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave2_f32(const float *src,
+                       size_t n,
+                       float *a,
+                       float *b);
+
+/**
+ *Split `2 * n` interleaved `u16` elements from `src` into the even (`a`) and odd (`b`) streams.
+ *
+ *# Safety `src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave2_u16(const uint16_t *src,
+                       size_t n,
+                       uint16_t *a,
+                       uint16_t *b);
+
+/**
+ *Split `2 * n` interleaved `u32` elements from `src` into the even (`a`) and odd (`b`) streams.
+ *
+ *# Safety `src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave2_u32(const uint32_t *src,
+                       size_t n,
+                       uint32_t *a,
+                       uint32_t *b);
+
+/**
+ *Split `2 * n` interleaved `u64` elements from `src` into the even (`a`) and odd (`b`) streams.
+ *
+ *# Safety `src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave2_u64(const uint64_t *src,
+                       size_t n,
+                       uint64_t *a,
+                       uint64_t *b);
+
+/**
+ *Split `2 * n` interleaved `u8` elements from `src` into the even (`a`) and odd (`b`) streams.
+ *
+ *# Safety `src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave2_u8(const uint8_t *src,
+                      size_t n,
+                      uint8_t *a,
+                      uint8_t *b);
+
+/**
+ *Split `3 * n` interleaved `f32` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).
+ *
+ *# Safety `src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave3_f32(const float *src,
+                       size_t n,
+                       float *a,
+                       float *b,
+                       float *c);
+
+/**
+ *Split `3 * n` interleaved `u16` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).
+ *
+ *# Safety `src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave3_u16(const uint16_t *src,
+                       size_t n,
+                       uint16_t *a,
+                       uint16_t *b,
+                       uint16_t *c);
+
+/**
+ *Split `3 * n` interleaved `u32` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).
+ *
+ *# Safety `src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave3_u32(const uint32_t *src,
+                       size_t n,
+                       uint32_t *a,
+                       uint32_t *b,
+                       uint32_t *c);
+
+/**
+ *Split `3 * n` interleaved `u64` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).
+ *
+ *# Safety `src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave3_u64(const uint64_t *src,
+                       size_t n,
+                       uint64_t *a,
+                       uint64_t *b,
+                       uint64_t *c);
+
+/**
+ *Split `3 * n` interleaved `u8` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).
+ *
+ *# Safety `src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave3_u8(const uint8_t *src,
+                      size_t n,
+                      uint8_t *a,
+                      uint8_t *b,
+                      uint8_t *c);
+
+/**
+ *Split `4 * n` interleaved `f32` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).
+ *
+ *# Safety `src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave4_f32(const float *src,
+                       size_t n,
+                       float *a,
+                       float *b,
+                       float *c,
+                       float *d);
+
+/**
+ *Split `4 * n` interleaved `u16` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).
+ *
+ *# Safety `src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave4_u16(const uint16_t *src,
+                       size_t n,
+                       uint16_t *a,
+                       uint16_t *b,
+                       uint16_t *c,
+                       uint16_t *d);
+
+/**
+ *Split `4 * n` interleaved `u32` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).
+ *
+ *# Safety `src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave4_u32(const uint32_t *src,
+                       size_t n,
+                       uint32_t *a,
+                       uint32_t *b,
+                       uint32_t *c,
+                       uint32_t *d);
+
+/**
+ *Split `4 * n` interleaved `u64` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).
+ *
+ *# Safety `src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave4_u64(const uint64_t *src,
+                       size_t n,
+                       uint64_t *a,
+                       uint64_t *b,
+                       uint64_t *c,
+                       uint64_t *d);
+
+/**
+ *Split `4 * n` interleaved `u8` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).
+ *
+ *# Safety `src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other.
+ */
+void deinterleave4_u8(const uint8_t *src,
+                      size_t n,
+                      uint8_t *a,
+                      uint8_t *b,
+                      uint8_t *c,
+                      uint8_t *d);
+
+/**
+ *Decode `len` delta-coded `i32` values (a wrapping prefix sum): `dst[i] = base + src[0] + … + src[i]`.  Inverse of `delta_encode_i32` for the same `base`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void delta_decode_i32(const int32_t *src,
+                      size_t len,
+                      int32_t base,
+                      int32_t *dst);
+
+/**
+ *Decode `len` delta-coded `i64` values (a wrapping prefix sum): `dst[i] = base + src[0] + … + src[i]`.  Inverse of `delta_encode_i64` for the same `base`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void delta_decode_i64(const int64_t *src,
+                      size_t len,
+                      int64_t base,
+                      int64_t *dst);
+
+/**
+ *Delta-encode `len` `i32` values: `dst[i] = src[i] - src[i - 1]` with wrapping arithmetic, taking `base` as `src[-1]` (pass the last value of the previous chunk to encode a stream in pieces).
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void delta_encode_i32(const int32_t *src,
+                      size_t len,
+                      int32_t base,
+                      int32_t *dst);
+
+/**
+ *Delta-encode `len` `i64` values: `dst[i] = src[i] - src[i - 1]` with wrapping arithmetic, taking `base` as `src[-1]` (pass the last value of the previous chunk to encode a stream in pieces).
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void delta_encode_i64(const int64_t *src,
+                      size_t len,
+                      int64_t base,
+                      int64_t *dst);
+
+/**
+ *Decode `n` dictionary-encoded `u16` codes: entry `codes[i]` of `dict` (`dict_len` entries of `width` bytes each) is copied to `dst[i * width..]`. Every code is checked against `dict_len` before anything is written. Returns `n`, or `-(i + 1)` for the first `i` whose code is out of range (`dst` is then untouched).
+ *
+ *# Safety `codes` must be valid for `n` values, `dict` for `dict_len * width` bytes and `dst` for `n * width` bytes; `dst` must not overlap `codes` or `dict`.
+ */
+ptrdiff_t dict_decode_u16(const uint16_t *codes,
+                          size_t n,
+                          const uint8_t *dict,
+                          size_t dict_len,
+                          size_t width,
+                          uint8_t *dst);
+
+/**
+ *Decode `n` dictionary-encoded `u32` codes: entry `codes[i]` of `dict` (`dict_len` entries of `width` bytes each) is copied to `dst[i * width..]`. Every code is checked against `dict_len` before anything is written. Returns `n`, or `-(i + 1)` for the first `i` whose code is out of range (`dst` is then untouched).
+ *
+ *# Safety `codes` must be valid for `n` values, `dict` for `dict_len * width` bytes and `dst` for `n * width` bytes; `dst` must not overlap `codes` or `dict`.
+ */
+ptrdiff_t dict_decode_u32(const uint32_t *codes,
+                          size_t n,
+                          const uint8_t *dict,
+                          size_t dict_len,
+                          size_t width,
+                          uint8_t *dst);
+
+/**
+ * Return the exact number of distinct byte values (0..=256) in the buffer.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t distinct_u8(const uint8_t *ptr, size_t len);
+
+/**
+ * Decode `n` timestamps produced by [`dod_encode_u64`] from the first
+ * `nbits` bits of `src_bits` into `out`.
+ *
+ * Returns `n` on success, or -1 if the stream ends before `n` values.
+ *
+ * # Safety
+ * `src_bits` must be valid for `ceil(nbits / 64)` u64 words and `out` for
+ * `n` u64 values.
+ */
+ptrdiff_t dod_decode_u64(const uint64_t *src_bits, size_t nbits, uint64_t *out, size_t n);
+
+/**
+ * Encode `n` timestamps with Gorilla delta-of-delta compression into the
+ * MSB-first bit stream `dst_bits` (`cap` u64 words).
+ *
+ * Layout: the first timestamp verbatim (64 bits), then per value the
+ * delta-of-delta `D` (the first delta is taken against 0) as
+ * `0` when `D == 0`, `10`+7 bits, `110`+9 bits, `1110`+12 bits (signed
+ * two's complement) or `1111`+64 bits.  Deltas use wrapping arithmetic.
+ *
+ * Returns the number of bits written, or -1 if `cap` words are too few.
+ *
+ * # Safety
+ * `ts` must be valid for `n` u64 values and `dst_bits` for `cap` u64 words.
+ */
+ptrdiff_t dod_encode_u64(const uint64_t *ts, size_t n, uint64_t *dst_bits, size_t cap);
+
+/**
+ *Dot product of two `len`-element `f32` vectors, in the same order as [`sum_f32`] over the products.
+ *
+ *# Safety `a` and `b` must be null or valid for `len` values.
+ */
+float dot_f32(const float *a,
+              const float *b,
+              size_t len);
+
+/**
+ *Dot product of two `len`-element `f64` vectors, in the same order as [`sum_f64`] over the products.
+ *
+ *# Safety `a` and `b` must be null or valid for `len` values.
+ */
+double dot_f64(const double *a,
+               const double *b,
+               size_t len);
+
+/**
+ * Dot product of two `len`-element i8 vectors, accumulated in i32 modulo
+ * 2^32 (exact up to 2^17 elements).  Uses AVX-512 VNNI / AVX-VNNI or the
+ * aarch64 dot-product extension when present.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len` values.
+ */
+int32_t dot_i8(const int8_t *a, const int8_t *b, size_t len);
+
+/**
+ * Dot product of a `len`-element u8 vector with an i8 one (the
+ * asymmetric activation x weight form), accumulated like [`dot_i8`].
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len` values.
+ */
+int32_t dot_u8_i8(const uint8_t *a, const int8_t *b, size_t len);
+
+/**
+ * Compute the Levenshtein (unit-cost insert/delete/substitute) distance
+ * between two byte strings using Myers' bit-parallel algorithm, processing
+ * 64 DP rows per machine word.
+ *
+ * Returns the distance when it is `<= max_k`, otherwise -1; the scan stops
+ * as soon as the bound can no longer be met.  A negative `max_k` disables
+ * the bound.
+ *
+ * # Safety
+ * `a`/`b` must be null or valid for `a_len`/`b_len` bytes.
+ */
+int32_t edit_distance_le(const uint8_t *a,
+                         size_t a_len,
+                         const uint8_t *b,
+                         size_t b_len,
+                         int32_t max_k);
+
+/**
+ * Return 1 if the first `len` bytes of `a` and `b` are equal under ASCII
+ * case folding (`A-Z` ≡ `a-z`; other bytes must match exactly), else 0.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len` bytes.
+ */
+uint8_t eq_ignore_ascii_case(const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ *Generate one u64 equality bitmask per 64 `u16` elements (bit `i` of word `w` is element `64 * w + i`). Writes and returns `len.div_ceil(64)` words; bits past the end of the array are zero.
+ *
+ *# Safety `ptr` must be valid for `len` `u16` values; `out` must be valid for `len.div_ceil(64)` u64 writes.
+ */
+size_t eq_u16_masks(const uint16_t *ptr,
+                    size_t len,
+                    uint16_t needle,
+                    uint64_t *out);
+
+/**
+ *Generate one u64 equality bitmask per 64 `u32` elements (bit `i` of word `w` is element `64 * w + i`). Writes and returns `len.div_ceil(64)` words; bits past the end of the array are zero.
+ *
+ *# Safety `ptr` must be valid for `len` `u32` values; `out` must be valid for `len.div_ceil(64)` u64 writes.
+ */
+size_t eq_u32_masks(const uint32_t *ptr,
+                    size_t len,
+                    uint32_t needle,
+                    uint64_t *out);
+
+/**
+ *Generate one u64 equality bitmask per 64 `u64` elements (bit `i` of word `w` is element `64 * w + i`). Writes and returns `len.div_ceil(64)` words; bits past the end of the array are zero.
+ *
+ *# Safety `ptr` must be valid for `len` `u64` values; `out` must be valid for `len.div_ceil(64)` u64 writes.
+ */
+size_t eq_u64_masks(const uint64_t *ptr,
+                    size_t len,
+                    uint64_t needle,
+                    uint64_t *out);
+
+/**
+ * Equality bitmasks of each byte against `needle`, as 64-bit words
+ * regardless of the lane width picked from the CPU and `len`.  Writes and
+ * returns `len.div_ceil(64)` words; bits past the end of the buffer are zero.
+ *
+ * # Safety
+ * `src` must be valid for `len` bytes and `out` for `len.div_ceil(64)` words.
+ */
+size_t eq_u8_masks(const uint8_t *src, size_t len, uint8_t needle, uint64_t *out);
+
+/**
+ *Generate equality bitmasks comparing each byte to `needle` across chunks of 16 lanes. The resulting mask words are stored in `out`. Returns number of mask words written. A final partial chunk is ignored; see `eq_u8_masks16_full`.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len/16` elements respectively.
+ */
+size_t eq_u8_masks16(const uint8_t *src,
+                     size_t len,
+                     uint8_t needle,
+                     uint16_t *out);
+
+/**
+ *Like `eq_u8_masks16`, but also emits a mask word for a final partial chunk, so `len.div_ceil(16)` words are written and returned. The last word has `len % 16` valid low bits (all 16 if that is 0); the bits past the end of the buffer are zero.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len.div_ceil(16)` elements respectively.
+ */
+size_t eq_u8_masks16_full(const uint8_t *src,
+                          size_t len,
+                          uint8_t needle,
+                          uint16_t *out);
+
+/**
+ *Generate equality bitmasks comparing each byte to `needle` across chunks of 32 lanes. The resulting mask words are stored in `out`. Returns number of mask words written. A final partial chunk is ignored; see `eq_u8_masks32_full`.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len/32` elements respectively.
+ */
+size_t eq_u8_masks32(const uint8_t *src,
+                     size_t len,
+                     uint8_t needle,
+                     uint32_t *out);
+
+/**
+ *Like `eq_u8_masks32`, but also emits a mask word for a final partial chunk, so `len.div_ceil(32)` words are written and returned. The last word has `len % 32` valid low bits (all 32 if that is 0); the bits past the end of the buffer are zero.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len.div_ceil(32)` elements respectively.
+ */
+size_t eq_u8_masks32_full(const uint8_t *src,
+                          size_t len,
+                          uint8_t needle,
+                          uint32_t *out);
+
+/**
+ *Generate equality bitmasks comparing each byte to `needle` across chunks of 64 lanes. The resulting mask words are stored in `out`. Returns number of mask words written. A final partial chunk is ignored; see `eq_u8_masks64_full`.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len/64` elements respectively.
+ */
+size_t eq_u8_masks64(const uint8_t *src,
+                     size_t len,
+                     uint8_t needle,
+                     uint64_t *out);
+
+/**
+ *Like `eq_u8_masks64`, but also emits a mask word for a final partial chunk, so `len.div_ceil(64)` words are written and returned. The last word has `len % 64` valid low bits (all 64 if that is 0); the bits past the end of the buffer are zero.
+ *
+ *# Safety `src` and `out` must be valid for `len` and `len.div_ceil(64)` elements respectively.
+ */
+size_t eq_u8_masks64_full(const uint8_t *src,
+                          size_t len,
+                          uint8_t needle,
+                          uint64_t *out);
+
+/**
+ * Scatter `src` into `nwords * 64` bytes of `dst`, the inverse of
+ * `compact_u8_masks`: where bit `i` of word `w` is set, `dst[64 * w + i]`
+ * takes the next byte of `src`; elsewhere it is `fill`.  Returns the number
+ * of `src` bytes consumed, the total popcount of `masks`.
+ *
+ * # Safety
+ * `masks` must be valid for `nwords` words, `src` for as many bytes as
+ * `masks` has set bits, and `dst` for `nwords * 64` bytes; `dst` must not
+ * overlap `src`.
+ */
+size_t expand_u8(const uint8_t *src,
+                 const uint64_t *masks,
+                 size_t nwords,
+                 uint8_t fill,
+                 uint8_t *dst);
+
+/**
+ * Widen `len` IEEE half-precision values (raw `u16` bits) to f32.  Exact for
+ * every input; NaNs are quietened.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` u16 values and `dst` for `len` f32
+ * writes; they must not overlap.
+ */
+void f16_to_f32(const uint16_t *src, size_t len, float *dst);
+
+/**
+ *`crate::half::f16_to_f32` with non-temporal stores.
+ *
+ *# Safety `src` must be null or valid for `len` u16 values and `dst` for `len` f32 writes; they must not overlap.
+ */
+void f16_to_f32_nt(const uint16_t *src,
+                   size_t len,
+                   float *dst);
+
+/**
+ * Narrow `len` f32 values to IEEE half precision (raw `u16` bits), rounding
+ * to nearest-even; values beyond the half range become infinities.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` f32 values and `dst` for `len` u16
+ * writes; they must not overlap.
+ */
+void f32_to_f16(const float *src, size_t len, uint16_t *dst);
+
+/**
+ *`crate::half::f32_to_f16` with non-temporal stores.
+ *
+ *# Safety `src` must be null or valid for `len` f32 values and `dst` for `len` u16 writes; they must not overlap.
+ */
+void f32_to_f16_nt(const float *src,
+                   size_t len,
+                   uint16_t *dst);
+
+/**
+ * Convert `len` f32 values to i32, truncating toward zero like Go's
+ * `int32(f)`; out-of-range values saturate to `i32::MIN`/`i32::MAX` and NaN
+ * becomes 0.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` f32 values and `dst` for `len` i32
+ * writes; they must not overlap.
+ */
+void f32_to_i32_saturating(const float *src, size_t len, int32_t *dst);
+
+/**
+ *`crate::numeric::f32_to_i32_saturating` with non-temporal stores.
+ *
+ *# Safety `src` must be null or valid for `len` f32 values and `dst` for `len` i32 writes; they must not overlap.
+ */
+void f32_to_i32_saturating_nt(const float *src,
+                              size_t len,
+                              int32_t *dst);
+
+/**
+ * Return the offset of the first occurrence of `needle` in `haystack`, or -1
+ * if absent.  An empty needle matches at 0.  Candidates are filtered on the
+ * needle's first and last bytes with 32-lane SIMD compares and then verified.
+ *
+ * # Safety
+ * `haystack`/`needle` must be null or valid for `hlen`/`nlen` bytes.
+ */
+ptrdiff_t find_substring(const uint8_t *haystack, size_t hlen, const uint8_t *needle, size_t nlen);
+
+/**
+ * memcmp-style alias of [`mismatch_index`]: -1 when the buffers are equal,
+ * otherwise the index of the first mismatching byte.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len` bytes.
+ */
+ptrdiff_t first_diff_u8(const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ * Return the index of the first non-ASCII byte (>= 0x80), or -1 if every
+ * byte is ASCII.  Same scan as `is_ascii`, but hands back where the
+ * non-ASCII region starts.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t first_non_ascii(const uint8_t *ptr, size_t len);
+
+/**
+ * Return the index of the first byte that is not ASCII whitespace
+ * (`\t \n \v \f \r` or space), or -1 if every byte is whitespace.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t first_non_whitespace(const uint8_t *ptr, size_t len);
+
+/**
+ * Return the index of the first byte whose `lut` entry is zero (i.e. not in
+ * the trim set), or -1 if every byte is in the set.
+ *
+ * # Safety
+ * `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+ptrdiff_t first_not_in_lut(const uint8_t *ptr, size_t len, const uint8_t *lut);
+
+/**
+ * Return the index of the first byte outside `lo..=hi`, or -1 if every byte
+ * is in range.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t first_not_in_range_u8(const uint8_t *ptr, size_t len, uint8_t lo, uint8_t hi);
+
+/**
+ *Combine two Fletcher-32 checksums: given `f1 = fletcher(A)`, `f2 = fletcher(B)` and `len2 = len(B)` in words, return `fletcher(A || B)`.
+ */
+uint32_t fletcher32_combine(uint32_t f1,
+                            uint32_t f2,
+                            size_t len2);
+
+/**
+ *Update a Fletcher-32 checksum (start from 0) with `nwords` u16 words, summed as native integers.  The result packs the two running sums as `s2 << 16 | s1`, each mod `2^16 - 1`.
+ *
+ *# Safety `ptr` must be null or valid for `nwords` u16 reads.
+ */
+uint32_t fletcher32_update(uint32_t init,
+                           const uint16_t *ptr,
+                           size_t nwords);
+
+/**
+ *Combine two Fletcher-64 checksums: given `f1 = fletcher(A)`, `f2 = fletcher(B)` and `len2 = len(B)` in words, return `fletcher(A || B)`.
+ */
+uint64_t fletcher64_combine(uint64_t f1,
+                            uint64_t f2,
+                            size_t len2);
+
+/**
+ *Update a Fletcher-64 checksum (start from 0) with `nwords` u32 words, summed as native integers.  The result packs the two running sums as `s2 << 32 | s1`, each mod `2^32 - 1`.
+ *
+ *# Safety `ptr` must be null or valid for `nwords` u32 reads.
+ */
+uint64_t fletcher64_update(uint64_t init,
+                           const uint32_t *ptr,
+                           size_t nwords);
+
+/**
+ *Update an FNV-1a u32 hash with additional bytes, as Go's `hash/fnv` `New32a`; start from the offset basis `0x811C9DC5`. The recurrence is inherently serial; this saves the per-byte Go overhead.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t fnv1a_32(const uint8_t *ptr,
+                  size_t len,
+                  uint32_t init);
+
+/**
+ *Update an FNV-1a u64 hash with additional bytes, as Go's `hash/fnv` `New64a`; start from the offset basis `0xCBF29CE484222325`. The recurrence is inherently serial; this saves the per-byte Go overhead.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t fnv1a_64(const uint8_t *ptr,
+                  size_t len,
+                  uint64_t init);
+
+/**
+ * Write the shortest decimal that parses back to `value` into `dst`:
+ * plain notation for `1e-6 <= |value| < 1e21` (`0.001`, `-12.5`, `1e+21`
+ * otherwise, as in JavaScript and Go's `encoding/json`), and `NaN`, `+Inf`
+ * or `-Inf` for non-finite values.  At most 25 bytes are written.
+ *
+ * Returns the number of bytes written, or 0 if they do not fit in `cap`.
+ *
+ * # Safety
+ * `dst` must be null or valid for `cap` bytes.
+ */
+size_t format_f64(double value, uint8_t *dst, size_t cap);
+
+/**
+ * Write `n` f64 values as [`format_f64`] does to `dst`, joined by the byte
+ * `sep` when `sep` is 0..=255 (no trailing separator) or back to back when
+ * `sep` is negative.
+ *
+ * Returns the number of bytes written, or `SIMBA_ERR_CAPACITY` if they do
+ * not fit in `cap` (`dst` then holds a prefix of the output).  `26 * n`
+ * bytes always suffice.
+ *
+ * # Safety
+ * `values` must be null or valid for `n` values and `dst` for `cap` bytes;
+ * the buffers must not overlap.
+ */
+ptrdiff_t format_f64_batch(const double *values, size_t n, uint8_t *dst, size_t cap, int32_t sep);
+
+/**
+ * Write `n` u64 values in decimal to `dst`, joined by the byte `sep` when
+ * `sep` is 0..=255 (no trailing separator) or back to back when `sep` is
+ * negative.
+ *
+ * Returns the number of bytes written, or `SIMBA_ERR_CAPACITY` if they do
+ * not fit in `cap` (`dst` then holds a prefix of the output).  `21 * n`
+ * bytes always suffice.
+ *
+ * # Safety
+ * `values` must be null or valid for `n` values and `dst` for `cap` bytes;
+ * the buffers must not overlap.
+ */
+ptrdiff_t format_u64_batch(const uint64_t *values, size_t n, uint8_t *dst, size_t cap, int32_t sep);
+
+/**
+ * Gather `n` fixed-width records: record `indices[i]` (the `width` bytes at
+ * `src[indices[i] * width..]`) is copied to `dst[i * width..]`.  Indices are
+ * checked against the `src_len / width` whole records in `src`.  Returns
+ * `n`, or `-(i + 1)` for the first `i` whose index is out of range
+ * (records `..i` are then written).
+ *
+ * # Safety
+ * `src` must be valid for `src_len` bytes, `indices` for `n` values and
+ * `dst` for `n * width` bytes; `dst` must not overlap `src` or `indices`.
+ */
+ptrdiff_t gather_records(const uint8_t *src,
+                         size_t src_len,
+                         size_t width,
+                         const uint32_t *indices,
+                         size_t n,
+                         uint8_t *dst);
+
+/**
+ * Gather the bytes `src[indices[i]]` for `i < n` into `dst`, checking each
+ * index against `src_len`.  Returns `n`, or `-(i + 1)` for the first `i`
+ * whose index is out of range (`dst[..i]` is then written).
+ *
+ * # Safety
+ * `src` must be valid for `src_len` bytes, `indices` for `n` values and
+ * `dst` for `n` bytes; `dst` must not overlap `src` or `indices`.
+ */
+ptrdiff_t gather_u8(const uint8_t *src,
+                    size_t src_len,
+                    const uint32_t *indices,
+                    size_t n,
+                    uint8_t *dst);
+
+/**
+ * Like [`gather_u8`] without bounds checks, for index lists already known to
+ * be in range.
+ *
+ * # Safety
+ * Every `indices[i]` for `i < n` must be a valid offset into `src`;
+ * `indices` must be valid for `n` values and `dst` for `n` bytes, and `dst`
+ * must not overlap `src` or `indices`.
+ */
+void gather_u8_unchecked(const uint8_t *src, const uint32_t *indices, size_t n, uint8_t *dst);
+
+/**
+ * Multiplicative inverse of `a` in GF(2^8); 0 (which has none) maps to 0.
+ */
+uint8_t gf_inv(uint8_t a);
+
+/**
+ * Multiply the `rows x cols` coefficient matrix `coefs` (row-major) by
+ * `cols` source shards of `len` bytes: `dsts[r][i] = sum over c of
+ * coefs[r * cols + c] * srcs[c][i]`.  This is Reed–Solomon encoding (with
+ * the parity rows of the generator matrix) and decoding (with the rows of
+ * the inverted survivor matrix).  Shards are walked in blocks so each
+ * source block is reused from cache by every output row.
+ *
+ * # Safety
+ * `coefs` must be valid for `rows * cols` bytes, `srcs` for `cols` and
+ * `dsts` for `rows` pointers, each valid for `len` bytes.  Outputs must
+ * not overlap each other or any source.
+ */
+void gf_matmul(const uint8_t *coefs,
+               size_t rows,
+               size_t cols,
+               const uint8_t *const *srcs,
+               uint8_t *const *dsts,
+               size_t len);
+
+/**
+ * Product of `a` and `b` in GF(2^8).
+ */
+uint8_t gf_mul(uint8_t a, uint8_t b);
+
+/**
+ * Compute `dst[i] ^= coef * src[i]` in GF(2^8) for `len` bytes: one term
+ * of a Reed–Solomon parity or reconstruction sum.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+ * exactly, but must not partially overlap it.
+ */
+void gf_mul_add_slice(const uint8_t *src, size_t len, uint8_t *dst, uint8_t coef);
+
+/**
+ * Compute `dst[i] = coef * src[i]` in GF(2^8) for `len` bytes.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+ * exactly (in-place), but must not partially overlap it.
+ */
+void gf_mul_slice(const uint8_t *src, size_t len, uint8_t *dst, uint8_t coef);
+
+/**
+ * Return the Hamming distance (number of differing bits) between the
+ * `len_bytes`-byte bit vectors `a` and `b`.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len_bytes` bytes.
+ */
+uint64_t hamming_distance(const uint8_t *a, const uint8_t *b, size_t len_bytes);
+
+/**
+ * Write to `out[r]` the Hamming distance between the `len_bytes`-byte
+ * `query` and row `r` of `matrix`, which holds `nrows` such bit vectors
+ * back to back.
+ *
+ * # Safety
+ * `query` must be valid for `len_bytes` bytes, `matrix` for
+ * `nrows * len_bytes` bytes and `out` for `nrows` u64 values.
+ */
+void hamming_distance_batch(const uint8_t *query,
+                            size_t len_bytes,
+                            const uint8_t *matrix,
+                            size_t nrows,
+                            uint64_t *out);
+
+/**
+ * Hash `n` independent keys in one call, writing `out[i] = hash(keys[i],
+ * seeds[i])`.  `kind` selects the algorithm: 0 xxHash64, 1 XXH3-64,
+ * 2 wyhash, 3 FNV-1a 64.  A null `seeds` hashes every key with seed 0; for
+ * FNV-1a the seed is XOR-ed into the offset basis, so 0 gives the standard
+ * digest.  Returns the number of hashes written: `n`, or 0 for an unknown
+ * `kind` or null `keys`/`out`.
+ *
+ * FNV-1a runs [`BATCH_LANES`] keys side by side in SIMD lanes; the other
+ * algorithms are built on 64x64->128 multiplies that do not vectorise, so
+ * they loop over the keys, which still removes the per-key FFI call.
+ *
+ * # Safety
+ * `keys` must be valid for `n` entries, each null or valid for `len` bytes;
+ * `seeds` must be null or valid for `n` reads; `out` must be valid for `n`
+ * writes.
+ */
+size_t hash_batch(uint32_t kind,
+                  const struct Iovec *keys,
+                  size_t n,
+                  const uint64_t *seeds,
+                  uint64_t *out);
+
+/**
+ * Release a handle from [`simba_hasher_new`].  Null is ignored.
+ *
+ * # Safety
+ * `h` must be null or a live handle; it must not be used afterwards.
+ */
+void hasher_free(struct Hasher *h);
+
+/**
+ * Discard everything fed so far, as if freshly created.
+ *
+ * # Safety
+ * `h` must be null or a live handle from [`simba_hasher_new`].
+ */
+void hasher_reset(struct Hasher *h);
+
+/**
+ * Digest length in bytes of a hasher (Go's `Size`); 0 for null.
+ *
+ * # Safety
+ * `h` must be null or a live handle from [`simba_hasher_new`].
+ */
+size_t hasher_size(const struct Hasher *h);
+
+/**
+ * Write the digest of everything fed so far to `out` and return its length
+ * ([`hasher_size`] bytes; 0 if `h` or `out` is null).  The state is left
+ * intact, so more input may follow.
+ *
+ * # Safety
+ * `h` must be null or a live handle from [`simba_hasher_new`]; `out` must be
+ * null or valid for [`hasher_size`] bytes.
+ */
+size_t hasher_sum(const struct Hasher *h, uint8_t *out);
+
+/**
+ * Feed `len` more bytes to a hasher.
+ *
+ * # Safety
+ * `h` must be null or a live handle from [`simba_hasher_new`]; `ptr` must be
+ * null or valid for `len` bytes.
+ */
+void hasher_write(struct Hasher *h, const uint8_t *ptr, size_t len);
+
+/**
+ * Decode hex digits; alias of the 32-lane kernel.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `len / 2` bytes and must not overlap `src`.
+ */
+ptrdiff_t hex_decode(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ *Decode `len` hex digits (either case) into `len / 2` bytes using a 16-lane SIMD kernel. Returns the number of bytes written, or `-(offset + 1)` where `offset` is the position of the first invalid digit; an odd-length input reports its last digit.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `len / 2` bytes and must not overlap `src`.
+ */
+ptrdiff_t hex_decode16(const uint8_t *src,
+                       size_t len,
+                       uint8_t *dst);
+
+/**
+ *Decode `len` hex digits (either case) into `len / 2` bytes using a 32-lane SIMD kernel. Returns the number of bytes written, or `-(offset + 1)` where `offset` is the position of the first invalid digit; an odd-length input reports its last digit.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `len / 2` bytes and must not overlap `src`.
+ */
+ptrdiff_t hex_decode32(const uint8_t *src,
+                       size_t len,
+                       uint8_t *dst);
+
+/**
+ *Decode `len` hex digits (either case) into `len / 2` bytes using a 64-lane SIMD kernel. Returns the number of bytes written, or `-(offset + 1)` where `offset` is the position of the first invalid digit; an odd-length input reports its last digit.
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `len / 2` bytes and must not overlap `src`.
+ */
+ptrdiff_t hex_decode64(const uint8_t *src,
+                       size_t len,
+                       uint8_t *dst);
+
+/**
+ * Hex-encode `len` bytes; alias of the 32-lane kernel.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `2 * len` bytes and must not overlap `src`.
+ */
+void hex_encode(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ *Hex-encode `len` bytes as `2 * len` lowercase digits using a 16-lane SIMD kernel (nibble shuffle).
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `2 * len` bytes and must not overlap `src`.
+ */
+void hex_encode16(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst);
+
+/**
+ *Hex-encode `len` bytes as `2 * len` lowercase digits using a 32-lane SIMD kernel (nibble shuffle).
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `2 * len` bytes and must not overlap `src`.
+ */
+void hex_encode32(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst);
+
+/**
+ *Hex-encode `len` bytes as `2 * len` lowercase digits using a 64-lane SIMD kernel (nibble shuffle).
+ *
+ *# Safety `src` must be null or valid for `len` bytes; `dst` must be valid for `2 * len` bytes and must not overlap `src`.
+ */
+void hex_encode64(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst);
+
+/**
+ * Convert `len` i32 values to f32 (round to nearest-even above 2^24).
+ *
+ * # Safety
+ * `src` must be null or valid for `len` i32 values and `dst` for `len` f32
+ * writes; they must not overlap.
+ */
+void i32_to_f32(const int32_t *src, size_t len, float *dst);
+
+/**
+ *`crate::numeric::i32_to_f32` with non-temporal stores.
+ *
+ *# Safety `src` must be null or valid for `len` i32 values and `dst` for `len` f32 writes; they must not overlap.
+ */
+void i32_to_f32_nt(const int32_t *src,
+                   size_t len,
+                   float *dst);
+
+/**
+ *Return the index (in elements) of the first `u16` equal to `needle` using a 32-lane SIMD kernel, or -1 if absent.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u16` values.
+ */
+ptrdiff_t index_of_u16(const uint16_t *ptr,
+                       size_t len,
+                       uint16_t needle);
+
+/**
+ *Return the index (in elements) of the first `u32` equal to `needle` using a 16-lane SIMD kernel, or -1 if absent.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u32` values.
+ */
+ptrdiff_t index_of_u32(const uint32_t *ptr,
+                       size_t len,
+                       uint32_t needle);
+
+/**
+ *Return the index (in elements) of the first `u64` equal to `needle` using a 8-lane SIMD kernel, or -1 if absent.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u64` values.
+ */
+ptrdiff_t index_of_u64(const uint64_t *ptr,
+                       size_t len,
+                       uint64_t needle);
+
+/**
+ *Return the offset of the first byte equal to `needle` using a 16-lane SIMD kernel, or -1 if absent. Stops at the first matching chunk.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t index_of_u8_16(const uint8_t *ptr,
+                         size_t len,
+                         uint8_t needle);
+
+/**
+ *Return the offset of the first byte equal to `needle` using a 32-lane SIMD kernel, or -1 if absent. Stops at the first matching chunk.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t index_of_u8_32(const uint8_t *ptr,
+                         size_t len,
+                         uint8_t needle);
+
+/**
+ *Return the offset of the first byte equal to `needle` using a 64-lane SIMD kernel, or -1 if absent. Stops at the first matching chunk.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t index_of_u8_64(const uint8_t *ptr,
+                         size_t len,
+                         uint8_t needle);
+
+/**
+ * Return the offset of the first byte equal to `n1` or `n2` (memchr2), or
+ * -1 if neither occurs.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t index_of_u8_any2(const uint8_t *ptr, size_t len, uint8_t n1, uint8_t n2);
+
+/**
+ * Return the offset of the first byte equal to `n1`, `n2` or `n3`
+ * (memchr3), or -1 if none occurs.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t index_of_u8_any3(const uint8_t *ptr, size_t len, uint8_t n1, uint8_t n2, uint8_t n3);
+
+/**
+ *Interleave `n` `f32` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.
+ *
+ *# Safety `a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs.
+ */
+void interleave2_f32(const float *a,
+                     const float *b,
+                     size_t n,
+                     float *dst);
+
+/**
+ *Interleave `n` `u16` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.
+ *
+ *# Safety `a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs.
+ */
+void interleave2_u16(const uint16_t *a,
+                     const uint16_t *b,
+                     size_t n,
+                     uint16_t *dst);
+
+/**
+ *Interleave `n` `u32` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.
+ *
+ *# Safety `a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs.
+ */
+void interleave2_u32(const uint32_t *a,
+                     const uint32_t *b,
+                     size_t n,
+                     uint32_t *dst);
+
+/**
+ *Interleave `n` `u64` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.
+ *
+ *# Safety `a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs.
+ */
+void interleave2_u64(const uint64_t *a,
+                     const uint64_t *b,
+                     size_t n,
+                     uint64_t *dst);
+
+/**
+ *Interleave `n` `u8` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.
+ *
+ *# Safety `a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs.
+ */
+void interleave2_u8(const uint8_t *a,
+                    const uint8_t *b,
+                    size_t n,
+                    uint8_t *dst);
+
+/**
+ *Interleave `n` `f32` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.
+ *
+ *# Safety `a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs.
+ */
+void interleave3_f32(const float *a,
+                     const float *b,
+                     const float *c,
+                     size_t n,
+                     float *dst);
+
+/**
+ *Interleave `n` `u16` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.
+ *
+ *# Safety `a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs.
+ */
+void interleave3_u16(const uint16_t *a,
+                     const uint16_t *b,
+                     const uint16_t *c,
+                     size_t n,
+                     uint16_t *dst);
+
+/**
+ *Interleave `n` `u32` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.
+ *
+ *# Safety `a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs.
+ */
+void interleave3_u32(const uint32_t *a,
+                     const uint32_t *b,
+                     const uint32_t *c,
+                     size_t n,
+                     uint32_t *dst);
+
+/**
+ *Interleave `n` `u64` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.
+ *
+ *# Safety `a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs.
+ */
+void interleave3_u64(const uint64_t *a,
+                     const uint64_t *b,
+                     const uint64_t *c,
+                     size_t n,
+                     uint64_t *dst);
+
+/**
+ *Interleave `n` `u8` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.
+ *
+ *# Safety `a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs.
+ */
+void interleave3_u8(const uint8_t *a,
+                    const uint8_t *b,
+                    const uint8_t *c,
+                    size_t n,
+                    uint8_t *dst);
+
+/**
+ *Interleave `n` `f32` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.
+ *
+ *# Safety `a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs.
+ */
+void interleave4_f32(const float *a,
+                     const float *b,
+                     const float *c,
+                     const float *d,
+                     size_t n,
+                     float *dst);
+
+/**
+ *Interleave `n` `u16` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.
+ *
+ *# Safety `a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs.
+ */
+void interleave4_u16(const uint16_t *a,
+                     const uint16_t *b,
+                     const uint16_t *c,
+                     const uint16_t *d,
+                     size_t n,
+                     uint16_t *dst);
+
+/**
+ *Interleave `n` `u32` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.
+ *
+ *# Safety `a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs.
+ */
+void interleave4_u32(const uint32_t *a,
+                     const uint32_t *b,
+                     const uint32_t *c,
+                     const uint32_t *d,
+                     size_t n,
+                     uint32_t *dst);
+
+/**
+ *Interleave `n` `u64` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.
+ *
+ *# Safety `a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs.
+ */
+void interleave4_u64(const uint64_t *a,
+                     const uint64_t *b,
+                     const uint64_t *c,
+                     const uint64_t *d,
+                     size_t n,
+                     uint64_t *dst);
+
+/**
+ *Interleave `n` `u8` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.
+ *
+ *# Safety `a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs.
+ */
+void interleave4_u8(const uint8_t *a,
+                    const uint8_t *b,
+                    const uint8_t *c,
+                    const uint8_t *d,
+                    size_t n,
+                    uint8_t *dst);
+
+/**
+ * Intersect the sorted sets `a` (`alen` values) and `b` (`blen` values),
+ * writing the common values in ascending order to `out`.  Returns the size
+ * of the intersection; only the first `cap` values are written, so a
+ * result above `cap` means `out` was too small (`cap = 0` just counts).
+ *
+ * Both inputs must be strictly increasing; with duplicates the result is
+ * unspecified (but memory-safe).
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `alen` and `blen` values, and `out`
+ * null (with `cap == 0`) or valid for `cap` values.
+ */
+size_t intersect_u32(const uint32_t *a,
+                     size_t alen,
+                     const uint32_t *b,
+                     size_t blen,
+                     uint32_t *out,
+                     size_t cap);
+
+/**
+ * Return 1 if all bytes are ASCII (< 0x80), 0 otherwise, picking the lane
+ * width from the CPU and `len`.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint8_t is_ascii(const uint8_t *ptr, size_t len);
+
+/**
+ *Return 1 if all bytes are ASCII (< 0x80) using a 16-lane SIMD kernel, 0 otherwise.
+ *
+ *# Safety Same as other FFI helpers: `ptr` must be null or valid for `len` bytes.
+ */
+uint8_t is_ascii16(const uint8_t *ptr, size_t len);
+
+/**
+ *Return 1 if all bytes are ASCII (< 0x80) using a 32-lane SIMD kernel, 0 otherwise.
+ *
+ *# Safety Same as other FFI helpers: `ptr` must be null or valid for `len` bytes.
+ */
+uint8_t is_ascii32(const uint8_t *ptr, size_t len);
+
+/**
+ *Return 1 if all bytes are ASCII (< 0x80) using a 64-lane SIMD kernel, 0 otherwise.
+ *
+ *# Safety Same as other FFI helpers: `ptr` must be null or valid for `len` bytes.
+ */
+uint8_t is_ascii64(const uint8_t *ptr, size_t len);
+
+/**
+ * Return 1 if every byte of every segment is ASCII (< 0x80), 0 otherwise.
+ * Stops at the first segment that fails.
+ *
+ * # Safety
+ * `iov` must be null or valid for `n` entries, each null or valid for `len`
+ * bytes.
+ */
+uint8_t is_ascii_iov(const struct Iovec *iov, size_t n);
+
+/**
+ * Escape `len` bytes for use inside a JSON string literal (without the
+ * surrounding quotes): `"` and `\` are backslash-escaped, control bytes
+ * become `\b \f \n \r \t` or `\u00XX`, everything else (including UTF-8) is
+ * copied verbatim.  Clean runs are found and copied with SIMD.
+ *
+ * Returns the number of bytes written, or -1 if `dst_cap` is too small
+ * (`6 * len` always suffices).
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `dst_cap` bytes and must not overlap `src`.
+ */
+ptrdiff_t json_escape(const uint8_t *src, size_t len, uint8_t *dst, size_t dst_cap);
+
+/**
+ * Find the structural positions of a JSON document — the operators
+ * `{ } [ ] : ,`, the opening quote of every string and the first byte of
+ * every other scalar (number, `true`, …) — honouring backslash escapes
+ * inside strings, and write them as u32 offsets in ascending order.
+ *
+ * Returns the number of indices written, -1 if `cap` is too small, or -2 if
+ * the document ends inside a string.  This is stage 1 only: the grammar and
+ * string contents are not validated.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes (`len <= u32::MAX`); `out`
+ * must be valid for `cap` u32 writes.
+ */
+ptrdiff_t json_structural_indices(const uint8_t *ptr, size_t len, uint32_t *out, size_t cap);
+
+/**
+ * Decode the escapes of a JSON string literal body (without the surrounding
+ * quotes): `\" \\ \/ \b \f \n \r \t` and `\uXXXX`, combining surrogate pairs
+ * into UTF-8 and replacing unpaired surrogates with U+FFFD.  Other bytes are
+ * copied verbatim; runs without a backslash are skipped with SIMD.
+ *
+ * Returns the number of bytes written, -1 if `dst_cap` is too small (`len`
+ * always suffices), or `-(offset + 2)` where `offset` is the position of the
+ * backslash starting a malformed escape.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes; `dst` must be valid for
+ * `dst_cap` bytes and must not overlap `src`.
+ */
+ptrdiff_t json_unescape(const uint8_t *src, size_t len, uint8_t *dst, size_t dst_cap);
+
+/**
+ * Write to `out[r]` the Euclidean distance between the `dim`-element
+ * `query` and row `r` of the row-major `nrows x dim` `matrix`: one call
+ * for a whole shard instead of one per row.
+ *
+ * # Safety
+ * `query` must be valid for `dim` values, `matrix` for `nrows * dim` and
+ * `out` for `nrows`.
+ */
+void l2_distance_batch_f32(const float *query,
+                           size_t dim,
+                           const float *matrix,
+                           size_t nrows,
+                           float *out);
+
+/**
+ * Euclidean (L2) distance between the `dim`-element vectors `a` and `b`.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `dim` values.
+ */
+float l2_distance_f32(const float *a, const float *b, size_t dim);
+
+/**
+ * Return the index of the last byte that is not ASCII whitespace, or -1 if
+ * every byte is whitespace.  The trimmed slice is `[first, last]` inclusive.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t last_non_whitespace(const uint8_t *ptr, size_t len);
+
+/**
+ * Return the index of the last byte whose `lut` entry is zero, or -1 if
+ * every byte is in the set.
+ *
+ * # Safety
+ * `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+ptrdiff_t last_not_in_lut(const uint8_t *ptr, size_t len, const uint8_t *lut);
+
+/**
+ * For each of the `n` needles, write to `out_indices` the index of the
+ * first element of the ascending `haystack` that is not less than it
+ * (`hlen` if there is none), like C++ `std::lower_bound`.  Eight needles
+ * are searched at once with branchless steps and gathered loads.
+ *
+ * # Safety
+ * `haystack` must be null or valid for `hlen` values, `needles` for `n`
+ * values and `out_indices` for `n` writes.
+ */
+void lower_bound_u64_batch(const uint64_t *haystack,
+                           size_t hlen,
+                           const uint64_t *needles,
+                           size_t n,
+                           size_t *out_indices);
+
+/**
+ * Copy the 256-byte `table` into library-owned, cache-line-aligned storage
+ * and return its id (non-zero) for the `*_lut_id` kernels.  Returns 0 when
+ * `table` is null or all 64 slots are in use.  Thread-safe.
+ *
+ * # Safety
+ * `table` must be null or valid for 256 bytes.
+ */
+uint32_t lut_register(const uint8_t *table);
+
+/**
+ * Release the table registered under `id` so its slot can be reused.
+ * Returns 0, or `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.  The
+ * caller must ensure no `*_lut_id` call using `id` is still running.
+ */
+int32_t lut_release(uint32_t id);
+
+/**
+ * Map each source byte through a 256-byte translation table into `dst`,
+ * picking the lane width from the CPU and `len`.
+ *
+ * # Safety
+ * All pointers must be non-null and valid for `len` bytes (`map` for 256).
+ * Buffers may overlap.
+ */
+void map_u8_lut(const uint8_t *src, size_t len, uint8_t *dst, const uint8_t *map);
+
+/**
+ *Map each source byte through a 256-byte translation table using a 16-lane SIMD kernel and write results to `dst`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` bytes and `map` for 256. `src` and `dst` may overlap in any way (the result is as if `src` were copied first, like `memmove`); `map` must not overlap `dst`.
+ */
+void map_u8_lut16(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst,
+                  const uint8_t *map);
+
+/**
+ *Map each source byte through a 256-byte translation table using a 32-lane SIMD kernel and write results to `dst`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` bytes and `map` for 256. `src` and `dst` may overlap in any way (the result is as if `src` were copied first, like `memmove`); `map` must not overlap `dst`.
+ */
+void map_u8_lut32(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst,
+                  const uint8_t *map);
+
+/**
+ *Map each source byte through a 256-byte translation table using a 64-lane SIMD kernel and write results to `dst`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` bytes and `map` for 256. `src` and `dst` may overlap in any way (the result is as if `src` were copied first, like `memmove`); `map` must not overlap `dst`.
+ */
+void map_u8_lut64(const uint8_t *src,
+                  size_t len,
+                  uint8_t *dst,
+                  const uint8_t *map);
+
+/**
+ * `map_u8_lut64` with a registered table.  Returns 0, or
+ * `SIMBA_ERR_INVALID_INPUT` (writing nothing) if `id` is not registered.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes. Buffers may overlap.
+ */
+int32_t map_u8_lut_id(const uint8_t *src, size_t len, uint8_t *dst, uint32_t id);
+
+/**
+ * `map_u8_lut64` with non-temporal stores.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes and `map` for 256.  `dst`
+ * may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void map_u8_lut_nt(const uint8_t *src, size_t len, uint8_t *dst, const uint8_t *map);
+
+/**
+ *Expand `nwords` `u16` bitmask words (as produced by the matching `eq_u8_masks*` kernel) into ascending byte offsets: bit `i` of word `w` yields `base + w * u16::BITS + i`. Writes at most `cap` offsets (size `out` with `popcount_u8` over the masks) and returns the number written.
+ *
+ *# Safety `masks` must be null or valid for `nwords` words; `out` must be valid for `cap` u64 writes.
+ */
+size_t mask_to_indices16(const uint16_t *masks,
+                         size_t nwords,
+                         uint64_t base,
+                         uint64_t *out,
+                         size_t cap);
+
+/**
+ *Expand `nwords` `u32` bitmask words (as produced by the matching `eq_u8_masks*` kernel) into ascending byte offsets: bit `i` of word `w` yields `base + w * u32::BITS + i`. Writes at most `cap` offsets (size `out` with `popcount_u8` over the masks) and returns the number written.
+ *
+ *# Safety `masks` must be null or valid for `nwords` words; `out` must be valid for `cap` u64 writes.
+ */
+size_t mask_to_indices32(const uint32_t *masks,
+                         size_t nwords,
+                         uint64_t base,
+                         uint64_t *out,
+                         size_t cap);
+
+/**
+ *Expand `nwords` `u64` bitmask words (as produced by the matching `eq_u8_masks*` kernel) into ascending byte offsets: bit `i` of word `w` yields `base + w * u64::BITS + i`. Writes at most `cap` offsets (size `out` with `popcount_u8` over the masks) and returns the number written.
+ *
+ *# Safety `masks` must be null or valid for `nwords` words; `out` must be valid for `cap` u64 writes.
+ */
+size_t mask_to_indices64(const uint64_t *masks,
+                         size_t nwords,
+                         uint64_t base,
+                         uint64_t *out,
+                         size_t cap);
+
+/**
+ *Write the minimum and maximum of `len` `i16` values to `out` in one 16-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i16` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_i16(const int16_t *ptr,
+                   size_t len,
+                   struct MinMaxI16 *out);
+
+/**
+ *Write the minimum and maximum of `len` `i32` values to `out` in one 8-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i32` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_i32(const int32_t *ptr,
+                   size_t len,
+                   struct MinMaxI32 *out);
+
+/**
+ *Write the minimum and maximum of `len` `i64` values to `out` in one 4-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i64` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_i64(const int64_t *ptr,
+                   size_t len,
+                   struct MinMaxI64 *out);
+
+/**
+ *Write the minimum and maximum of `len` `i8` values to `out` in one 32-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i8` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_i8(const int8_t *ptr,
+                  size_t len,
+                  struct MinMaxI8 *out);
+
+/**
+ *Write the minimum and maximum of `len` `u16` values to `out` in one 16-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u16` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_u16(const uint16_t *ptr,
+                   size_t len,
+                   struct MinMaxU16 *out);
+
+/**
+ *Write the minimum and maximum of `len` `u32` values to `out` in one 8-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u32` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_u32(const uint32_t *ptr,
+                   size_t len,
+                   struct MinMaxU32 *out);
+
+/**
+ *Write the minimum and maximum of `len` `u64` values to `out` in one 4-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u64` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_u64(const uint64_t *ptr,
+                   size_t len,
+                   struct MinMaxU64 *out);
+
+/**
+ *Write the minimum and maximum of `len` `u8` values to `out` in one 32-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u8` values; `out` must be null or valid for one write.
+ */
+uint8_t minmax_u8(const uint8_t *ptr,
+                  size_t len,
+                  struct MinMaxU8 *out);
+
+/**
+ * Return the index of the first byte where `a` and `b` differ, or -1 if the
+ * first `len` bytes are equal.
+ *
+ * # Safety
+ * `a` and `b` must be null or valid for `len` bytes.
+ */
+ptrdiff_t mismatch_index(const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ *Count the `\n` bytes in the buffer and, if `out` is non-null, write the byte offsets of the first `cap` of them as `u32` values. Returns the total number of newlines, which exceeds `cap` when the output was truncated.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes, with `len <= u32::MAX`; `out` must be null or valid for `cap` writes.
+ */
+uint64_t newline_offsets_u32(const uint8_t *ptr,
+                             size_t len,
+                             uint32_t *out,
+                             size_t cap);
+
+/**
+ *Count the `\n` bytes in the buffer and, if `out` is non-null, write the byte offsets of the first `cap` of them as `u64` values. Returns the total number of newlines, which exceeds `cap` when the output was truncated.
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes; `out` must be null or valid for `cap` writes.
+ */
+uint64_t newline_offsets_u64(const uint8_t *ptr,
+                             size_t len,
+                             uint64_t *out,
+                             size_t cap);
+
+/**
+ * Compile the 256-byte class table `lut` (non-zero entry = member) into the
+ * nibble form used by `validate_u8_nibble_lut*`, so a table reused across
+ * calls is prepared once.
+ *
+ * # Safety
+ * `lut` must be valid for 256 bytes and `out` for one `NibbleLut` write.
+ */
+void nibble_lut_compile(const uint8_t *lut, struct NibbleLut *out);
+
+void noop(void);
+
+/**
+ * Pack `n` bytes into an LSB-first bitmap (Arrow validity layout): bit `i`
+ * is set when `src[i]` is non-zero.  Writes `n.div_ceil(8)` bytes; the
+ * unused high bits of the last byte are zero.
+ *
+ * # Safety
+ * `src` must be valid for `n` bytes and `dst` for `n.div_ceil(8)` bytes.
+ */
+void pack_bytes_to_bits(const uint8_t *src, size_t n, uint8_t *dst);
+
+/**
+ * Parse a decimal floating-point literal (`[+-]digits[.digits][e[+-]digits]`,
+ * or `inf` / `infinity` / `nan` in any case) from the `len` bytes at `ptr`
+ * into `*out`, rounded to the nearest f64 (ties to even).
+ *
+ * Returns 0 on success.  On a malformed input returns
+ * `SIMBA_ERR_INVALID_INPUT` and stores the index of the first bad character
+ * in `*out`.  A finite literal beyond the f64 range returns
+ * `SIMBA_ERR_OVERFLOW` and stores ±infinity; underflow quietly rounds to
+ * (signed) zero.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+ * write.
+ */
+int32_t parse_f64(const uint8_t *ptr, size_t len, double *out);
+
+/**
+ * Parse an optionally signed (`+` / `-`) decimal integer from the `len`
+ * bytes at `ptr` into `*out`.
+ *
+ * Returns 0 on success.  On a malformed input (empty, a lone sign or a
+ * non-digit) returns `SIMBA_ERR_INVALID_INPUT` and stores the index of the
+ * first bad character in `*out` (`len` when the digits are missing).  On
+ * overflow returns `SIMBA_ERR_OVERFLOW` and stores `i64::MIN` or `i64::MAX`
+ * according to the sign.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+ * write.
+ */
+int32_t parse_i64(const uint8_t *ptr, size_t len, int64_t *out);
+
+/**
+ * Parse the dotted-quad IPv4 address in the `len` bytes at `ptr` into
+ * `*out` as `a << 24 | b << 16 | c << 8 | d` (the address in network order
+ * as a native integer).
+ *
+ * Returns 0, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+ * character stored in `*out` (`len` when the input ends early).  Octets
+ * with leading zeros are rejected, as Go's `netip` does.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+ * write.
+ */
+int32_t parse_ipv4(const uint8_t *ptr, size_t len, uint32_t *out);
+
+/**
+ * Parse the IPv6 address in the `len` bytes at `ptr` (e.g. `2001:db8::1`,
+ * `::ffff:192.0.2.1`) into the 16 network-order bytes at `out`.  Zones
+ * (`%eth0`) are not accepted.
+ *
+ * Returns 0, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+ * character stored as a native-endian u32 in the first four bytes of `out`
+ * (`len` when the input ends early).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for 16
+ * bytes.
+ */
+int32_t parse_ipv6(const uint8_t *ptr, size_t len, uint8_t *out);
+
+/**
+ * Parse `n` decimal fields into `out`, writing each field's status to
+ * `errs` (when non-null): 0, `SIMBA_ERR_INVALID_INPUT` for an empty field or
+ * one with a non-digit, or `SIMBA_ERR_OVERFLOW` for a value above
+ * `u64::MAX`.  Fields are digits only (leading zeros allowed); failed
+ * fields get `out[i] = 0`.
+ *
+ * Returns the number of fields that failed.
+ *
+ * # Safety
+ * `fields` must be valid for `n` entries, each null or valid for `len`
+ * bytes; `out` must be valid for `n` values and `errs` null or valid for `n`.
+ */
+size_t parse_u64_batch(const struct Iovec *fields, size_t n, uint64_t *out, int32_t *errs);
+
+/**
+ *Split `n_frames` interleaved stereo `f32` frames from `src` into planar `left` and `right`.
+ *
+ *# Safety `src` must be valid for `2 * n_frames` elements and `left`/`right` for `n_frames`; outputs must not overlap `src` or each other.
+ */
+void pcm_deinterleave_stereo_f32(const float *src,
+                                 size_t n_frames,
+                                 float *left,
+                                 float *right);
+
+/**
+ *Split `n_frames` interleaved stereo `i16` frames from `src` into planar `left` and `right`.
+ *
+ *# Safety `src` must be valid for `2 * n_frames` elements and `left`/`right` for `n_frames`; outputs must not overlap `src` or each other.
+ */
+void pcm_deinterleave_stereo_i16(const int16_t *src,
+                                 size_t n_frames,
+                                 int16_t *left,
+                                 int16_t *right);
+
+/**
+ * Convert `n` f32 samples to signed 16-bit PCM: `round(x * 32768)`,
+ * saturated to `[-32768, 32767]` (NaN becomes 0).
+ *
+ * When `dither` is non-zero, triangular (TPDF) dither of ±1 LSB is added
+ * before rounding.  The dither sequence is generated from a fixed seed, so
+ * the output is deterministic for a given input.
+ *
+ * # Safety
+ * `src` must be valid for `n` f32 values and `dst` for `n` i16 values; the
+ * buffers must not overlap.
+ */
+void pcm_f32_to_i16(const float *src, size_t n, int16_t *dst, uint8_t dither);
+
+/**
+ * Convert `n` signed 16-bit PCM samples to f32 in `[-1.0, 1.0)` (`s / 32768`).
+ *
+ * # Safety
+ * `src` must be valid for `n` i16 values and `dst` for `n` f32 values; the
+ * buffers must not overlap.
+ */
+void pcm_i16_to_f32(const int16_t *src, size_t n, float *dst);
+
+/**
+ *Interleave `n_frames` planar `f32` samples from `left` and `right` into `dst` as `L R L R …`.
+ *
+ *# Safety `left`/`right` must be valid for `n_frames` elements and `dst` for `2 * n_frames`; `dst` must not overlap the inputs.
+ */
+void pcm_interleave_stereo_f32(const float *left,
+                               const float *right,
+                               size_t n_frames,
+                               float *dst);
+
+/**
+ *Interleave `n_frames` planar `i16` samples from `left` and `right` into `dst` as `L R L R …`.
+ *
+ *# Safety `left`/`right` must be valid for `n_frames` elements and `dst` for `2 * n_frames`; `dst` must not overlap the inputs.
+ */
+void pcm_interleave_stereo_i16(const int16_t *left,
+                               const int16_t *right,
+                               size_t n_frames,
+                               int16_t *dst);
+
+/**
+ * Reverse one PNG scanline filter in place.
+ *
+ * `filter_type` is the per-row PNG filter byte (0 None, 1 Sub, 2 Up,
+ * 3 Average, 4 Paeth); `cur_row` holds the `len` filtered bytes that follow
+ * it and `prev_row` the already-reconstructed previous scanline, or null for
+ * the first row of an image/pass (treated as all zeros).  `bpp` is the number
+ * of bytes per complete pixel, rounded up to 1 for sub-byte formats.
+ *
+ * Returns 1 on success, 0 if `filter_type` or `bpp` (1..=8) is invalid; the
+ * row is left untouched in that case.
+ *
+ * # Safety
+ * `cur_row` must be valid for `len` bytes of writes and, when non-null,
+ * `prev_row` for `len` bytes of reads.  The rows must not overlap.
+ */
+uint8_t png_defilter_row(uint8_t filter_type,
+                         const uint8_t *prev_row,
+                         uint8_t *cur_row,
+                         size_t len,
+                         size_t bpp);
+
+/**
+ * Return the number of set bits across `len` bytes (e.g. the number of valid
+ * entries in a validity bitmap).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t popcount_u8(const uint8_t *ptr, size_t len);
+
+/**
+ * Convert `n_pixels` straight-alpha RGBA8 pixels to premultiplied alpha:
+ * each colour channel becomes `round(c * a / 255)`, alpha is copied as is.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `4 * n_pixels` bytes.  `dst` may alias
+ * `src` exactly (in-place), but must not partially overlap it.
+ */
+void premultiply_alpha(const uint8_t *src, size_t n_pixels, uint8_t *dst);
+
+/**
+ *Convert `n_pixels` packed RGB8 pixels to 8-bit luma using BT.601 weights in Q8 fixed point: `y = (wr*r + wg*g + wb*b + 128) >> 8`.
+ *
+ *# Safety `src` must be valid for `3 * n_pixels` bytes and `dst` for `n_pixels` bytes; the buffers must not overlap.
+ */
+void rgb_to_gray(const uint8_t *src,
+                 size_t n_pixels,
+                 uint8_t *dst);
+
+/**
+ *Convert `n_pixels` packed RGB8 pixels to 8-bit luma using BT.709 weights in Q8 fixed point: `y = (wr*r + wg*g + wb*b + 128) >> 8`.
+ *
+ *# Safety `src` must be valid for `3 * n_pixels` bytes and `dst` for `n_pixels` bytes; the buffers must not overlap.
+ */
+void rgb_to_gray_bt709(const uint8_t *src,
+                       size_t n_pixels,
+                       uint8_t *dst);
+
+/**
+ * `rgba_premultiply` spelling of [`premultiply_alpha`].
+ *
+ * # Safety
+ * As for [`premultiply_alpha`].
+ */
+void rgba_premultiply(const uint8_t *src, size_t n_pixels, uint8_t *dst);
+
+/**
+ *Compute `dst[i] = a[i] + b[i]` for `len` bytes, clamped to `0..=255` instead of wrapping.
+ *
+ *# Safety All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either.
+ */
+void saturating_add_u8(uint8_t *dst,
+                       const uint8_t *a,
+                       const uint8_t *b,
+                       size_t len);
+
+/**
+ *Compute `dst[i] = a[i] - b[i]` for `len` bytes, clamped to `0..=255` instead of wrapping.
+ *
+ *# Safety All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either.
+ */
+void saturating_sub_u8(uint8_t *dst,
+                       const uint8_t *a,
+                       const uint8_t *b,
+                       size_t len);
+
+/**
+ *`dst[i] *= a` over `len` `f32` values.
+ *
+ *# Safety `dst` must be null or valid for `len` values.
+ */
+void scale_f32(float *dst, float a, size_t len);
+
+/**
+ *`dst[i] *= a` over `len` `f64` values.
+ *
+ *# Safety `dst` must be null or valid for `len` values.
+ */
+void scale_f64(double *dst, double a, size_t len);
+
+/**
+ * Compile `needle` into a reusable searcher.  Needle-derived state (a copy
+ * of the needle and the choice of the two rarest bytes to filter on) is
+ * computed once here instead of on every search.  Release the handle with
+ * [`searcher_free`].
+ *
+ * # Safety
+ * `needle` must be null or valid for `nlen` bytes.
+ */
+struct Searcher *searcher_compile(const uint8_t *needle, size_t nlen);
+
+/**
+ * Return the offset of the first occurrence of the compiled needle in
+ * `haystack`, or -1 if absent (or `s` is null).
+ *
+ * # Safety
+ * `s` must be a live handle from [`searcher_compile`]; `haystack` must be
+ * null or valid for `len` bytes.
+ */
+ptrdiff_t searcher_find(const struct Searcher *s, const uint8_t *haystack, size_t len);
+
+/**
+ * Release a handle from [`searcher_compile`].  Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a live handle; it must not be used afterwards.
+ */
+void searcher_free(struct Searcher *s);
+
+/**
+ * SHA-256 of `len` bytes, written to the 32 bytes at `out`.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for 32
+ * bytes.
+ */
+void sha256(const uint8_t *ptr, size_t len, uint8_t *out);
+
+/**
+ * Write the digest of everything fed so far to the 32 bytes at `out`; the
+ * state is left intact, so more input may follow.
+ *
+ * # Safety
+ * `s` must be null or a live handle from [`sha256_new`]; `out` must be valid
+ * for 32 bytes.
+ */
+void sha256_digest(const struct Sha256 *s, uint8_t *out);
+
+/**
+ * Release a handle from [`sha256_new`].  Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a live handle; it must not be used afterwards.
+ */
+void sha256_free(struct Sha256 *s);
+
+/**
+ * Start a streaming SHA-256.  Release with [`sha256_free`].
+ */
+struct Sha256 *sha256_new(void);
+
+/**
+ * Feed `len` more bytes to a streaming SHA-256.
+ *
+ * # Safety
+ * `s` must be a live handle from [`sha256_new`]; `ptr` must be null or valid
+ * for `len` bytes.
+ */
+void sha256_update(struct Sha256 *s, const uint8_t *ptr, size_t len);
+
+/**
+ * Version of the exported C ABI; callers should refuse to run against a
+ * version they were not built for.
+ */
+uint32_t simba_abi_version(void);
+
+/**
+ * Instruction-set extensions present on the running CPU, as a bit set.
+ *
+ * x86_64: bit 0 SSE4.2, 1 AVX2, 2 AVX-512F, 3 AVX-512BW, 4 PCLMULQDQ,
+ * 5 SHA, 6 BMI2, 7 POPCNT, 8 FMA, 9 AVX-512 VNNI, 10 AVX-VNNI.
+ * aarch64: bit 16 NEON, 17 SVE, 18 CRC32, 19 SHA2, 20 AES/PMULL,
+ * 21 DOTPROD.
+ *
+ * Bits for the other architecture are always clear.
+ */
+uint64_t simba_cpu_features(void);
+
+/**
+ * Return 1 if this build exports a function named by the `len` bytes at
+ * `name`, 0 otherwise.  Names are accepted with or without the build's
+ * `SIMBA_SYMBOL_PREFIX`.
+ *
+ * # Safety
+ * `name` must be null or valid for `len` bytes.
+ */
+uint8_t simba_has_symbol(const uint8_t *name, size_t len);
+
+/**
+ * Start a streaming hasher for algorithm `kind`: 0 CRC32C, 1 CRC32 (IEEE),
+ * 2 CRC64 (XZ), 3 CRC64 (NVMe), 4 Adler-32, 5 FNV-1a 32, 6 FNV-1a 64,
+ * 7 xxHash64, 8 XXH3-64, 9 XXH3-128, 10 SHA-256, 11 BLAKE3.  Seeded and
+ * keyed variants start from their defaults (seed 0, unkeyed).  Returns null
+ * for an unknown `kind`.  Release with [`hasher_free`].
+ */
+struct Hasher *simba_hasher_new(uint32_t kind);
+
+/**
+ * `SIMBA_ERR_*` code of the calling thread's most recent failure, or 0 if
+ * none has been recorded.
+ */
+int32_t simba_last_error(void);
+
+/**
+ * Copy the detail message of the calling thread's most recent failure
+ * (UTF-8, not NUL-terminated) into `buf`, truncated to `cap` bytes.
+ *
+ * Returns the full message length, so a caller can retry with a larger
+ * buffer; 0 if no error has been recorded.
+ *
+ * # Safety
+ * `buf` must be null or valid for `cap` bytes.
+ */
+size_t simba_last_error_message(uint8_t *buf, size_t cap);
+
+/**
+ * Run built-in known-answer checks for each kernel family on the running
+ * CPU and return a bit set of the families that failed (`SELFTEST_*`); 0
+ * means every check passed.  Cheap enough to call once at startup, as a
+ * guard that the dispatched code paths compute correct results here.
+ */
+uint32_t simba_selftest(void);
+
+/**
+ * Feed the next chunk of the stream.  Multi-byte UTF-8 sequences may be
+ * split across chunks.  Returns 1 while no error has been seen, 0 once the
+ * stream is known to be invalid (later chunks are then ignored).
+ *
+ * # Safety
+ * `v` must be a live handle from [`simba_validator_new`]; `ptr` must be null
+ * or valid for `len` bytes.
+ */
+uint8_t simba_validator_feed(struct Validator *v, const uint8_t *ptr, size_t len);
+
+/**
+ * Finish the stream.  Returns -1 if everything fed so far is valid,
+ * otherwise the stream offset of the first invalid byte; a UTF-8 sequence
+ * left incomplete at the end reports the offset of its lead byte.  The
+ * handle stays valid, so more chunks may still be fed afterwards.
+ *
+ * # Safety
+ * `v` must be a live handle from [`simba_validator_new`].
+ */
+int64_t simba_validator_finish(const struct Validator *v);
+
+/**
+ * Release a handle from [`simba_validator_new`].  Null is ignored.
+ *
+ * # Safety
+ * `v` must be null or a live handle; it must not be used afterwards.
+ */
+void simba_validator_free(struct Validator *v);
+
+/**
+ * Create a streaming validator of the given `kind` ([`VALIDATOR_ASCII`] or
+ * [`VALIDATOR_UTF8`]).  Returns null for an unknown kind.  The handle must be
+ * released with [`simba_validator_free`].
+ */
+struct Validator *simba_validator_new(uint32_t kind);
+
+/**
+ * SipHash-2-4 of `len` bytes under the 128-bit key `(k0, k1)` (the key's
+ * first and second 8 bytes read little-endian).  Use for hash tables fed
+ * untrusted keys, where a keyed hash resists flooding.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t siphash24(uint64_t k0, uint64_t k1, const uint8_t *ptr, size_t len);
+
+/**
+ *Sort the `len` `u32` values at `ptr` ascending, in place.
+ *
+ *# Safety `ptr` must be null or valid for `len` values.
+ */
+void sort_u32(uint32_t *ptr, size_t len);
+
+/**
+ *Sort the `len` `u64` values at `ptr` ascending, in place.
+ *
+ *# Safety `ptr` must be null or valid for `len` values.
+ */
+void sort_u64(uint64_t *ptr, size_t len);
+
+/**
+ * Split the buffer on `delim` in one pass and, if `out` is non-null, write
+ * the `(start, end)` byte offsets of the first `cap` fields as `u32` pairs
+ * (`out[2k]`, `out[2k + 1]`; `end` is exclusive).  `n` delimiters make
+ * `n + 1` fields, empty ones included, like Go's `bytes.Split`.  Returns the
+ * total number of fields, which exceeds `cap` when the output was truncated.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes, with `len <= u32::MAX`;
+ * `out` must be null or valid for `2 * cap` writes.
+ */
+size_t split_u8(const uint8_t *ptr, size_t len, uint8_t delim, uint32_t *out, size_t cap);
+
+/**
+ * Decode `n` u32 values written by [`streamvbyte_encode_u32`] from the
+ * `len` bytes at `src` into `dst`.
+ *
+ * Returns the number of bytes consumed, `SIMBA_ERR_INVALID_INPUT` if the
+ * stream ends before `n` values, or `SIMBA_ERR_NULL_POINTER`.
+ *
+ * # Safety
+ * `src` must be valid for `len` bytes and `dst` for `n` values.
+ */
+ptrdiff_t streamvbyte_decode_u32(const uint8_t *src, size_t len, size_t n, uint32_t *dst);
+
+/**
+ * Encode `n` u32 values in StreamVByte format: `ceil(n / 4)` control bytes
+ * followed by the packed data bytes.  Returns the number of bytes written.
+ *
+ * # Safety
+ * `src` must be null or valid for `n` values; `dst` must be valid for
+ * `streamvbyte_max_encoded_len(n)` bytes and must not overlap `src`.
+ */
+size_t streamvbyte_encode_u32(const uint32_t *src, size_t n, uint8_t *dst);
+
+/**
+ * Return the worst-case StreamVByte size of `n` values, i.e. the `dst`
+ * capacity required by [`streamvbyte_encode_u32`].
+ */
+size_t streamvbyte_max_encoded_len(size_t n);
+
+/**
+ * `strnlen` semantics: the offset of the first NUL in `ptr[..maxlen]`, or
+ * `maxlen` if there is none (0 for null).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `maxlen` bytes (the whole field, even if
+ * the terminator comes earlier): the kernel reads full vectors.
+ */
+size_t strnlen_u8(const uint8_t *ptr, size_t maxlen);
+
+/**
+ *Sum `len` `f32` values with 32 lane accumulators folded pairwise (see the module notes for the exact order).
+ *
+ *# Safety `ptr` must be null or valid for `len` values.
+ */
+float sum_f32(const float *ptr,
+              size_t len);
+
+/**
+ *Sum `len` `f64` values with 16 lane accumulators folded pairwise (see the module notes for the exact order).
+ *
+ *# Safety `ptr` must be null or valid for `len` values.
+ */
+double sum_f64(const double *ptr,
+               size_t len);
+
+/**
+ *Sum `len` `i32` values into a `i64` using a 8-lane SIMD kernel. Exact (no wrap) for fewer than 2^32 values.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i32` values.
+ */
+int64_t sum_i32(const int32_t *ptr,
+                size_t len);
+
+/**
+ *Sum `len` `i64` values into a `i64` using a 8-lane SIMD kernel. Wraps in two's complement on overflow.
+ *
+ *# Safety `ptr` must be null or valid for `len` `i64` values.
+ */
+int64_t sum_i64(const int64_t *ptr,
+                size_t len);
+
+/**
+ *Sum `len` `u16` values into a `u64` using a 16-lane SIMD kernel. Exact (no wrap) for fewer than 2^48 values.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u16` values.
+ */
+uint64_t sum_u16(const uint16_t *ptr,
+                 size_t len);
+
+/**
+ *Sum `len` `u32` values into a `u64` using a 8-lane SIMD kernel. Exact (no wrap) for fewer than 2^32 values.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u32` values.
+ */
+uint64_t sum_u32(const uint32_t *ptr,
+                 size_t len);
+
+/**
+ *Sum `len` `u64` values into a `u64` using a 8-lane SIMD kernel. Wraps modulo 2^64 on overflow.
+ *
+ *# Safety `ptr` must be null or valid for `len` `u64` values.
+ */
+uint64_t sum_u64(const uint64_t *ptr, size_t len);
+
+/**
+ * Sum the bytes in `data` modulo 2^32, picking the lane width from the CPU
+ * and `len` (see `sum_u8_16`/`_32`/`_64` for fixed widths).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t sum_u8(const uint8_t *ptr, size_t len);
+
+/**
+ *Sum the bytes in `data` using a 16-lane portable SIMD kernel and return the running total modulo 2^32.
+ *
+ *# Safety • `ptr` must be either null or valid for `len` bytes. • The buffer must not be mutated for the duration of the call.
+ */
+uint32_t sum_u8_16(const uint8_t *ptr,
+                   size_t len);
+
+/**
+ *Sum the bytes in `data` using a 32-lane portable SIMD kernel and return the running total modulo 2^32.
+ *
+ *# Safety • `ptr` must be either null or valid for `len` bytes. • The buffer must not be mutated for the duration of the call.
+ */
+uint32_t sum_u8_32(const uint8_t *ptr,
+                   size_t len);
+
+/**
+ *Sum the bytes in `data` using a 64-lane portable SIMD kernel and return the running total modulo 2^32.
+ *
+ *# Safety • `ptr` must be either null or valid for `len` bytes. • The buffer must not be mutated for the duration of the call.
+ */
+uint32_t sum_u8_64(const uint8_t *ptr,
+                   size_t len);
+
+/**
+ * Sum the bytes of every segment, modulo 2^32 (as `sum_u8_32` over the
+ * concatenation).
+ *
+ * # Safety
+ * `iov` must be null or valid for `n` entries, each null or valid for `len`
+ * bytes.
+ */
+uint32_t sum_u8_iov(const struct Iovec *iov, size_t n);
+
+/**
+ * `sum_u8` split across up to `nthreads` threads (0 = one per CPU).
+ * Buffers under 2 MiB, or `nthreads == 1`, run on the calling thread.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t sum_u8_parallel(const uint8_t *ptr, size_t len, size_t nthreads);
+
+/**
+ * `sum_u8` with a big-buffer mode selected by `flags` (see the module docs).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint32_t sum_u8_pipelined(const uint8_t *ptr, size_t len, uint32_t flags);
+
+/**
+ * Reorder the channels of `n_pixels` packed 4-byte pixels.
+ *
+ * `order` packs four source-channel indices, one per byte, little-endian:
+ * output channel `i` receives source channel `(order >> 8*i) & 3`.  For
+ * example RGBA→BGRA is `0x0300_0102`, identity is `0x0302_0100`.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `4 * n_pixels` bytes.  `dst` may alias
+ * `src` exactly (in-place), but must not partially overlap it.
+ */
+void swizzle_rgba(const uint8_t *src, size_t n_pixels, uint32_t order, uint8_t *dst);
+
+/**
+ * Compile `n` literal patterns for [`teddy_find`].  Pattern `i` occupies
+ * `lens[i]` bytes of `pats`, the patterns being stored back to back.
+ * Returns null if `n` is 0 or exceeds [`TEDDY_MAX_PATTERNS`], or if any
+ * pattern is empty.  Release the handle with [`teddy_free`].
+ *
+ * # Safety
+ * `lens` must be valid for `n` values and `pats` for their sum in bytes.
+ */
+struct Teddy *teddy_compile(const uint8_t *pats, const uint32_t *lens, size_t n);
+
+/**
+ * Find the leftmost occurrence of any compiled pattern in `haystack` and
+ * return its offset, storing the pattern's index in `*out_id`; when several
+ * patterns start at that offset the lowest index wins.  Returns -1 if none
+ * occurs (`*out_id` is then left untouched).
+ *
+ * Candidates are found 16 positions at a time by matching the low and high
+ * nibbles of the first (up to 3) pattern bytes against per-bucket tables
+ * with byte shuffles, then verified exactly.
+ *
+ * # Safety
+ * `t` must be a live handle from [`teddy_compile`]; `haystack` must be null
+ * or valid for `len` bytes; `out_id` must be null or valid for a u32 write.
+ */
+ptrdiff_t teddy_find(const struct Teddy *t, const uint8_t *haystack, size_t len, uint32_t *out_id);
+
+/**
+ * Release a handle from [`teddy_compile`].  Null is ignored.
+ *
+ * # Safety
+ * `t` must be null or a live handle; it must not be used afterwards.
+ */
+void teddy_free(struct Teddy *t);
+
+/**
+ * Convert ASCII `A-Z` to `a-z`; all other bytes (including non-ASCII) are
+ * copied unchanged.  Uses a range compare instead of a table gather.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+ * exactly (in-place), but must not partially overlap it.
+ */
+void to_lower_ascii(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ * Convert ASCII `a-z` to `A-Z`; all other bytes (including non-ASCII) are
+ * copied unchanged.  Uses a range compare instead of a table gather.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+ * exactly (in-place), but must not partially overlap it.
+ */
+void to_upper_ascii(const uint8_t *src, size_t len, uint8_t *dst);
+
+/**
+ * Write the `k` largest of the `len` `f32` values at `ptr`, best first, to
+ * `out_val`, and their indices to `out_idx`; equal values are ordered by
+ * index.  NaNs are skipped.  Returns the number of entries written, which
+ * is below `k` only when there are fewer than `k` non-NaN values.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` values; `out_idx` and `out_val`
+ * must be valid for `k` writes.
+ */
+size_t topk_f32(const float *ptr, size_t len, size_t k, size_t *out_idx, float *out_val);
+
+/**
+ * Bounce all parameters back to the caller; used by Go unit tests to pinpoint
+ * which argument (if any) is mis-marshalled by the assembly trampolines.
+ */
+void trampoline_echo(const uint8_t *ptr,
+                     size_t len,
+                     uint32_t v32,
+                     uint8_t v8,
+                     uint64_t v64,
+                     uint64_t f64bits,
+                     uint32_t f32bits,
+                     struct Echo *out);
+
+/**
+ * Simple checksum over the arguments; used only by Go tests to verify that
+ * assembly trampolines pass parameters with the correct width/order.
+ */
+size_t trampoline_sanity(const uint8_t *ptr,
+                         size_t len,
+                         uint32_t val32,
+                         uint8_t val8,
+                         uint64_t val64,
+                         uint64_t f64_bits,
+                         uint32_t f32_bits);
+
+/**
+ * Transpose a `rows x cols` row-major byte matrix into `dst` as a
+ * `cols x rows` row-major matrix (`dst[c * rows + r] = src[r * cols + c]`),
+ * i.e. turn fixed-width records into one plane per byte column.  Works in
+ * 16x16 register tiles; ragged edges are copied scalar.
+ *
+ * # Safety
+ * `src` and `dst` must be valid for `rows * cols` bytes and must not
+ * overlap.
+ */
+void transpose_u8(const uint8_t *src, size_t rows, size_t cols, uint8_t *dst);
+
+/**
+ * Expand the first `nbits` bits of an LSB-first bitmap (Arrow validity
+ * layout) into one byte per bit, 1 for set and 0 for clear.
+ *
+ * # Safety
+ * `bitmap` must be valid for `nbits.div_ceil(8)` bytes and `dst` for `nbits`
+ * bytes.
+ */
+void unpack_bits_to_bytes(const uint8_t *bitmap, size_t nbits, uint8_t *dst);
+
+/**
+ * Decode `len` percent-encoded bytes from `src` into `dst`: `%XX` (either
+ * case) becomes the byte it names, and bytes with a non-zero `lut` entry
+ * are copied (a null `lut` accepts every byte).  `+` is not special; map it
+ * to a space first for `application/x-www-form-urlencoded` input.  The
+ * output is never longer than the input.
+ *
+ * Returns the number of bytes written, or `-(offset + 1)` where `offset` is
+ * the first input byte that is not allowed (a byte outside `lut`, or a `%`
+ * not followed by two hex digits) or whose output did not fit in `cap`;
+ * `simba_last_error` tells the two apart.
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes and `dst` for `cap` bytes;
+ * `lut` must be null or valid for 256 bytes; `dst` must not overlap `src`.
+ */
+ptrdiff_t url_decode(const uint8_t *src, size_t len, uint8_t *dst, size_t cap, const uint8_t *lut);
+
+/**
+ * Percent-encode `len` bytes from `src` into `dst`: bytes with a non-zero
+ * `lut` entry are copied, every other byte becomes `%XX` (uppercase hex).
+ * A null `lut` copies only the RFC 3986 unreserved characters
+ * (`A-Z a-z 0-9 - . _ ~`).  `3 * len` bytes of output always suffice.
+ *
+ * Returns the number of bytes written, or `-(offset + 1)` where `offset` is
+ * the first input byte whose encoding did not fit in `cap` (`dst` then holds
+ * the encoding of `src[..offset]`).
+ *
+ * # Safety
+ * `src` must be null or valid for `len` bytes and `dst` for `cap` bytes;
+ * `lut` must be null or valid for 256 bytes; `dst` must not overlap `src`.
+ */
+ptrdiff_t url_encode(const uint8_t *src, size_t len, uint8_t *dst, size_t cap, const uint8_t *lut);
+
+/**
+ * Write a bitmap of the continuation bytes (`0b10xx_xxxx`) in the buffer:
+ * bit `i % 64` of `out[i / 64]` is set iff byte `i` is a continuation byte.
+ * `out` must hold `len.div_ceil(64)` words; unused high bits of the last word
+ * are cleared.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for
+ * `len.div_ceil(64)` u64 writes.
+ */
+void utf8_continuation_mask(const uint8_t *ptr, size_t len, uint64_t *out);
+
+/**
+ * Write the 16 bytes at `src` as a lowercase canonical UUID
+ * (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, 36 bytes) to `dst`.
+ *
+ * # Safety
+ * `src` must be valid for 16 bytes and `dst` for 36 bytes.
+ */
+void uuid_format(const uint8_t *src, uint8_t *dst);
+
+/**
+ * Parse the UUID in the `len` bytes at `ptr` into the 16 bytes at `out`.
+ * Accepts the canonical 36-character form and 32 bare hex digits, in either
+ * case.
+ *
+ * Returns 16, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+ * character stored as a native-endian u32 in the first four bytes of `out`
+ * (`len` when the input is short).
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for 16
+ * bytes.
+ */
+ptrdiff_t uuid_parse(const uint8_t *ptr, size_t len, uint8_t *out);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 16-lane SIMD kernel. Non-zero table entry marks valid byte. Returns 1 on success, 0 on first mismatch.
+ *
+ *# Safety • `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+uint8_t validate_u8_lut16(const uint8_t *ptr,
+                          size_t len,
+                          const uint8_t *lut);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 32-lane SIMD kernel. Non-zero table entry marks valid byte. Returns 1 on success, 0 on first mismatch.
+ *
+ *# Safety • `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+uint8_t validate_u8_lut32(const uint8_t *ptr,
+                          size_t len,
+                          const uint8_t *lut);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 64-lane SIMD kernel. Non-zero table entry marks valid byte. Returns 1 on success, 0 on first mismatch.
+ *
+ *# Safety • `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+uint8_t validate_u8_lut64(const uint8_t *ptr,
+                          size_t len,
+                          const uint8_t *lut);
+
+/**
+ * `validate_u8_nibble_lut` with a registered table: returns 1 if every byte
+ * has a non-zero entry, 0 on the first mismatch, or
+ * `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+int32_t validate_u8_lut_id(const uint8_t *ptr, size_t len, uint32_t id);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 16-lane SIMD kernel, like `validate_u8_lut16`. Returns the index of the first byte whose table entry is zero, or -1 if every byte is valid.
+ *
+ *# Safety `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+ptrdiff_t validate_u8_lut_pos16(const uint8_t *ptr,
+                                size_t len,
+                                const uint8_t *lut);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 32-lane SIMD kernel, like `validate_u8_lut32`. Returns the index of the first byte whose table entry is zero, or -1 if every byte is valid.
+ *
+ *# Safety `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+ptrdiff_t validate_u8_lut_pos32(const uint8_t *ptr,
+                                size_t len,
+                                const uint8_t *lut);
+
+/**
+ *Validate every byte against a 256-byte lookup table using a 64-lane SIMD kernel, like `validate_u8_lut64`. Returns the index of the first byte whose table entry is zero, or -1 if every byte is valid.
+ *
+ *# Safety `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+ */
+ptrdiff_t validate_u8_lut_pos64(const uint8_t *ptr,
+                                size_t len,
+                                const uint8_t *lut);
+
+/**
+ * `validate_u8_lut_pos` with a big-buffer mode selected by `flags` (see the
+ * module docs).  Returns the index of the first byte whose table entry is
+ * zero, or -1 if every byte is valid.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes and `lut` for 256.
+ */
+ptrdiff_t validate_u8_lut_pos_pipelined(const uint8_t *ptr,
+                                        size_t len,
+                                        const uint8_t *lut,
+                                        uint32_t flags);
+
+/**
+ * Like `validate_u8_lut64` with a table compiled by `nibble_lut_compile`.
+ * Returns 1 if every byte is a member, 0 otherwise.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `set` must point to a
+ * compiled `NibbleLut`.
+ */
+uint8_t validate_u8_nibble_lut(const uint8_t *ptr, size_t len, const struct NibbleLut *set);
+
+/**
+ * Like `validate_u8_lut_pos64` with a table compiled by
+ * `nibble_lut_compile`.  Returns the index of the first non-member byte, or
+ * -1 if every byte is a member.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `set` must point to a
+ * compiled `NibbleLut`.
+ */
+ptrdiff_t validate_u8_nibble_lut_pos(const uint8_t *ptr, size_t len, const struct NibbleLut *set);
+
+/**
+ *Validate UTF-8 using a 32-lane SIMD kernel. Returns -1 if the buffer is valid, otherwise the offset at which the first invalid or truncated sequence starts (i.e. the length of the longest valid prefix).
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t validate_utf8_32(const uint8_t *ptr,
+                           size_t len);
+
+/**
+ *Validate UTF-8 using a 64-lane SIMD kernel. Returns -1 if the buffer is valid, otherwise the offset at which the first invalid or truncated sequence starts (i.e. the length of the longest valid prefix).
+ *
+ *# Safety `ptr` must be null or valid for `len` bytes.
+ */
+ptrdiff_t validate_utf8_64(const uint8_t *ptr,
+                           size_t len);
+
+/**
+ * Decode a run of LEB128 (protobuf) varints from the `len` bytes at `src`
+ * into up to `cap` u64 values at `dst`, stopping when `dst` is full or no
+ * complete varint is left.  Bytes and values used are written to `out`; a
+ * varint truncated by the end of `src` is not consumed, so the caller can
+ * refill and resume at `out.consumed`.
+ *
+ * Returns 0, `SIMBA_ERR_INVALID_INPUT` for a varint longer than 10 bytes or
+ * one that overflows 64 bits (`out.consumed` is then its offset, and the
+ * values before it have been decoded), or `SIMBA_ERR_NULL_POINTER`.
+ *
+ * # Safety
+ * `src` must be valid for `len` bytes, `dst` for `cap` values and `out`
+ * for one write.
+ */
+int32_t varint_decode_u64(const uint8_t *src,
+                          size_t len,
+                          uint64_t *dst,
+                          size_t cap,
+                          struct VarintBatch *out);
+
+/**
+ * wyhash (final version 4, default secret) of `len` bytes.
+ *
+ * This is the recommended default for hashing short-to-medium keys: a
+ * couple of 64x64->128 multiplies per 16 bytes and no setup cost.  Prefer
+ * [`xxh3_64`] for bulk data and [`siphash24`] for untrusted keys.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t wyhash(const uint8_t *ptr, size_t len, uint64_t seed);
+
+/**
+ * Compute `dst[i] = a[i] ^ b[i]` for `len` bytes.
+ *
+ * # Safety
+ * All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or
+ * `b` exactly, but must not partially overlap either.
+ */
+void xor_u8(uint8_t *dst, const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ * Compute `dst[i] ^= src[i]` in place for `len` bytes.
+ *
+ * # Safety
+ * `dst` and `src` must be valid for `len` bytes and must not partially
+ * overlap.
+ */
+void xor_u8_inplace(uint8_t *dst, const uint8_t *src, size_t len);
+
+/**
+ * `xor_u8` with non-temporal stores.
+ *
+ * # Safety
+ * All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or
+ * `b` exactly, but must not partially overlap either.
+ */
+void xor_u8_nt(uint8_t *dst, const uint8_t *a, const uint8_t *b, size_t len);
+
+/**
+ * Decode `n` values produced by [`xorf_encode_f64`] from the first `nbits`
+ * bits of `src_bits` into `out`.
+ *
+ * Returns `n` on success, or -1 if the stream is truncated or malformed.
+ *
+ * # Safety
+ * `src_bits` must be valid for `ceil(nbits / 64)` u64 words and `out` for
+ * `n` f64 values.
+ */
+ptrdiff_t xorf_decode_f64(const uint64_t *src_bits, size_t nbits, double *out, size_t n);
+
+/**
+ * Encode `n` f64 values with Gorilla XOR compression into the MSB-first bit
+ * stream `dst_bits` (`cap` u64 words).
+ *
+ * Layout: the first value's bits verbatim, then per value `X = bits ^ prev`
+ * as `0` when `X == 0`; `10` + the meaningful bits when `X` fits the current
+ * leading/trailing-zero window; otherwise `11` + 5-bit leading-zero count +
+ * 6-bit meaningful length (64 encoded as 0) + the meaningful bits, which
+ * also becomes the new window.  NaN payloads and signed zeros round-trip.
+ *
+ * Returns the number of bits written, or -1 if `cap` words are too few.
+ *
+ * # Safety
+ * `values` must be valid for `n` f64 values and `dst_bits` for `cap` u64
+ * words.
+ */
+ptrdiff_t xorf_encode_f64(const double *values, size_t n, uint64_t *dst_bits, size_t cap);
+
+/**
+ * XXH3 128-bit hash of `len` bytes, written to `out` as `[low64, high64]`.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes; `out` must be valid for two
+ * u64 writes.
+ */
+void xxh3_128(const uint8_t *ptr, size_t len, uint64_t seed, uint64_t *out);
+
+/**
+ * XXH3 64-bit hash of `len` bytes.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t xxh3_64(const uint8_t *ptr, size_t len, uint64_t seed);
+
+/**
+ * XXH3 128-bit digest of everything fed so far, written to `out` as
+ * `[low64, high64]`; more input may follow.
+ *
+ * # Safety
+ * `s` must be null or a live handle from [`xxh3_new`]; `out` must be valid
+ * for two u64 writes.
+ */
+void xxh3_digest128(const struct Xxh3 *s, uint64_t *out);
+
+/**
+ * XXH3 64-bit digest of everything fed so far; more input may follow.
+ *
+ * # Safety
+ * `s` must be null or a live handle from [`xxh3_new`].
+ */
+uint64_t xxh3_digest64(const struct Xxh3 *s);
+
+/**
+ * Release a handle from [`xxh3_new`].  Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a live handle; it must not be used afterwards.
+ */
+void xxh3_free(struct Xxh3 *s);
+
+/**
+ * Start a streaming XXH3 (either digest width).  Release with [`xxh3_free`].
+ */
+struct Xxh3 *xxh3_new(uint64_t seed);
+
+/**
+ * Feed `len` more bytes to a streaming XXH3.
+ *
+ * # Safety
+ * `s` must be a live handle from [`xxh3_new`]; `ptr` must be null or valid
+ * for `len` bytes.
+ */
+void xxh3_update(struct Xxh3 *s, const uint8_t *ptr, size_t len);
+
+/**
+ * xxHash64 of `len` bytes.
+ *
+ * # Safety
+ * `ptr` must be null or valid for `len` bytes.
+ */
+uint64_t xxh64(const uint8_t *ptr, size_t len, uint64_t seed);
+
+/**
+ * Digest of everything fed so far; the state is left intact, so more input
+ * may follow.
+ *
+ * # Safety
+ * `s` must be null or a live handle from [`xxh64_new`].
+ */
+uint64_t xxh64_digest(const struct Xxh64 *s);
+
+/**
+ * Release a handle from [`xxh64_new`].  Null is ignored.
+ *
+ * # Safety
+ * `s` must be null or a live handle; it must not be used afterwards.
+ */
+void xxh64_free(struct Xxh64 *s);
+
+/**
+ * Start a streaming xxHash64.  Release with [`xxh64_free`].
+ */
+struct Xxh64 *xxh64_new(uint64_t seed);
+
+/**
+ * Feed `len` more bytes to a streaming xxHash64.
+ *
+ * # Safety
+ * `s` must be a live handle from [`xxh64_new`]; `ptr` must be null or valid
+ * for `len` bytes.
+ */
+void xxh64_update(struct Xxh64 *s, const uint8_t *ptr, size_t len);
+
+/**
+ *Zigzag-decode `len` `u32` values back into `i32`: `(u >> 1) ^ -(u & 1)`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void zigzag_decode_i32(const uint32_t *src,
+                       size_t len,
+                       int32_t *dst);
+
+/**
+ *Zigzag-decode `len` `u64` values back into `i64`: `(u >> 1) ^ -(u & 1)`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void zigzag_decode_i64(const uint64_t *src,
+                       size_t len,
+                       int64_t *dst);
+
+/**
+ *Zigzag-encode `len` `i32` values into `u32`: `(x << 1) ^ (x >> i32::BITS - 1)`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void zigzag_encode_i32(const int32_t *src,
+                       size_t len,
+                       uint32_t *dst);
+
+/**
+ *Zigzag-encode `len` `i64` values into `u64`: `(x << 1) ^ (x >> i64::BITS - 1)`.
+ *
+ *# Safety `src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it.
+ */
+void zigzag_encode_i64(const int64_t *src,
+                       size_t len,
+                       uint64_t *dst);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SIMBA_H */
@@ -0,0 +1,497 @@
+//! ABI version and capability queries.  The Go side links a prebuilt object
+//! file; these let it verify at startup that the object matches the bindings
+//! it was generated against instead of failing (or misbehaving) later on a
+//! stale build.
+
+/// Version of the exported C ABI.  Bump whenever an export is removed or its
+/// signature or semantics change; adding exports only extends
+/// [`EXPORTED_SYMBOLS`], which [`simba_has_symbol`] reports.
+const ABI_VERSION: u32 = 1;
+
+/// Every export of the crate, sorted, without the symbol prefix.
+const EXPORTED_SYMBOLS: &[&str] = &[
+    "adler32_combine",
+    "adler32_update",
+    "all_in_range_u8",
+    "alpha_blend",
+    "approx_distinct_u64",
+    "argmax_f32",
+    "axpy_f32",
+    "axpy_f64",
+    "base32_decode_hex",
+    "base32_decode_std",
+    "base32_decoded_len",
+    "base32_encode_hex",
+    "base32_encode_std",
+    "base32_encoded_len",
+    "base58_decode",
+    "base58_decoded_max_len",
+    "base58_encode",
+    "base58_encoded_max_len",
+    "base58check_encode",
+    "base64_decode_std",
+    "base64_decode_url",
+    "base64_decoded_len",
+    "base64_encode_std",
+    "base64_encode_url",
+    "base64_encoded_len",
+    "bitap_find",
+    "bitset_and",
+    "bitset_and_count",
+    "bitset_andnot",
+    "bitset_or",
+    "blake3",
+    "blake3_derive_key",
+    "blake3_finalize",
+    "blake3_free",
+    "blake3_keyed",
+    "blake3_new",
+    "blake3_new_derive_key",
+    "blake3_new_keyed",
+    "blake3_update",
+    "bloom_insert",
+    "bloom_probe",
+    "bswap16_buf",
+    "bswap16_buf_nt",
+    "bswap32_buf",
+    "bswap32_buf_nt",
+    "bswap64_buf",
+    "bswap64_buf_nt",
+    "chacha20_xor",
+    "clamp_f32",
+    "classify_u8_masks16",
+    "classify_u8_masks32",
+    "classify_u8_masks64",
+    "compact_u8",
+    "compact_u8_masks",
+    "compare_u8",
+    "cosine_similarity_f32",
+    "count_substring",
+    "count_u8_16",
+    "count_u8_32",
+    "count_u8_64",
+    "count_u8_lut",
+    "count_u8_lut_id",
+    "count_utf8_runes",
+    "count_utf8_runes_32",
+    "count_utf8_runes_64",
+    "crc16_ccitt_update",
+    "crc16_modbus_update",
+    "crc32_combine",
+    "crc32_combine_many",
+    "crc32_combine_raw",
+    "crc32_ieee_combine",
+    "crc32_ieee_update_32",
+    "crc32_ieee_update_64",
+    "crc32_update_32",
+    "crc32_update_32_raw",
+    "crc32_update_64",
+    "crc32_update_64_raw",
+    "crc32_update_iov",
+    "crc32c_parallel",
+    "crc32c_pipelined",
+    "crc64_nvme_combine",
+    "crc64_nvme_update",
+    "crc64_xz_combine",
+    "crc64_xz_update",
+    "crc_hw_features",
+    "crc_set_hw_features",
+    "cstr_eq",
+    "cstr_len",
+    "csv_scan_masks",
+    "ct_eq_u8",
+    "deinterleave2_f32",
+    "deinterleave2_u16",
+    "deinterleave2_u32",
+    "deinterleave2_u64",
+    "deinterleave2_u8",
+    "deinterleave3_f32",
+    "deinterleave3_u16",
+    "deinterleave3_u32",
+    "deinterleave3_u64",
+    "deinterleave3_u8",
+    "deinterleave4_f32",
+    "deinterleave4_u16",
+    "deinterleave4_u32",
+    "deinterleave4_u64",
+    "deinterleave4_u8",
+    "delta_decode_i32",
+    "delta_decode_i64",
+    "delta_encode_i32",
+    "delta_encode_i64",
+    "dict_decode_u16",
+    "dict_decode_u32",
+    "distinct_u8",
+    "dod_decode_u64",
+    "dod_encode_u64",
+    "dot_f32",
+    "dot_f64",
+    "dot_i8",
+    "dot_u8_i8",
+    "edit_distance_le",
+    "eq_ignore_ascii_case",
+    "eq_u16_masks",
+    "eq_u32_masks",
+    "eq_u64_masks",
+    "eq_u8_masks",
+    "eq_u8_masks16",
+    "eq_u8_masks16_full",
+    "eq_u8_masks32",
+    "eq_u8_masks32_full",
+    "eq_u8_masks64",
+    "eq_u8_masks64_full",
+    "expand_u8",
+    "f16_to_f32",
+    "f16_to_f32_nt",
+    "f32_to_f16",
+    "f32_to_f16_nt",
+    "f32_to_i32_saturating",
+    "f32_to_i32_saturating_nt",
+    "find_substring",
+    "first_diff_u8",
+    "first_non_ascii",
+    "first_non_whitespace",
+    "first_not_in_lut",
+    "first_not_in_range_u8",
+    "fletcher32_combine",
+    "fletcher32_update",
+    "fletcher64_combine",
+    "fletcher64_update",
+    "fnv1a_32",
+    "fnv1a_64",
+    "format_f64",
+    "format_f64_batch",
+    "format_u64_batch",
+    "gather_records",
+    "gather_u8",
+    "gather_u8_unchecked",
+    "gf_inv",
+    "gf_matmul",
+    "gf_mul",
+    "gf_mul_add_slice",
+    "gf_mul_slice",
+    "hamming_distance",
+    "hamming_distance_batch",
+    "hash_batch",
+    "hasher_free",
+    "hasher_reset",
+    "hasher_size",
+    "hasher_sum",
+    "hasher_write",
+    "hex_decode",
+    "hex_decode16",
+    "hex_decode32",
+    "hex_decode64",
+    "hex_encode",
+    "hex_encode16",
+    "hex_encode32",
+    "hex_encode64",
+    "i32_to_f32",
+    "i32_to_f32_nt",
+    "index_of_u16",
+    "index_of_u32",
+    "index_of_u64",
+    "index_of_u8_16",
+    "index_of_u8_32",
+    "index_of_u8_64",
+    "index_of_u8_any2",
+    "index_of_u8_any3",
+    "interleave2_f32",
+    "interleave2_u16",
+    "interleave2_u32",
+    "interleave2_u64",
+    "interleave2_u8",
+    "interleave3_f32",
+    "interleave3_u16",
+    "interleave3_u32",
+    "interleave3_u64",
+    "interleave3_u8",
+    "interleave4_f32",
+    "interleave4_u16",
+    "interleave4_u32",
+    "interleave4_u64",
+    "interleave4_u8",
+    "intersect_u32",
+    "is_ascii",
+    "is_ascii16",
+    "is_ascii32",
+    "is_ascii64",
+    "is_ascii_iov",
+    "json_escape",
+    "json_structural_indices",
+    "json_unescape",
+    "l2_distance_batch_f32",
+    "l2_distance_f32",
+    "last_non_whitespace",
+    "last_not_in_lut",
+    "lower_bound_u64_batch",
+    "lut_register",
+    "lut_release",
+    "map_u8_lut",
+    "map_u8_lut16",
+    "map_u8_lut32",
+    "map_u8_lut64",
+    "map_u8_lut_id",
+    "map_u8_lut_nt",
+    "mask_to_indices16",
+    "mask_to_indices32",
+    "mask_to_indices64",
+    "minmax_i16",
+    "minmax_i32",
+    "minmax_i64",
+    "minmax_i8",
+    "minmax_u16",
+    "minmax_u32",
+    "minmax_u64",
+    "minmax_u8",
+    "mismatch_index",
+    "newline_offsets_u32",
+    "newline_offsets_u64",
+    "nibble_lut_compile",
+    "noop",
+    "pack_bytes_to_bits",
+    "parse_f64",
+    "parse_i64",
+    "parse_ipv4",
+    "parse_ipv6",
+    "parse_u64_batch",
+    "pcm_deinterleave_stereo_f32",
+    "pcm_deinterleave_stereo_i16",
+    "pcm_f32_to_i16",
+    "pcm_i16_to_f32",
+    "pcm_interleave_stereo_f32",
+    "pcm_interleave_stereo_i16",
+    "png_defilter_row",
+    "popcount_u8",
+    "premultiply_alpha",
+    "rgb_to_gray",
+    "rgb_to_gray_bt709",
+    "rgba_premultiply",
+    "saturating_add_u8",
+    "saturating_sub_u8",
+    "scale_f32",
+    "scale_f64",
+    "searcher_compile",
+    "searcher_find",
+    "searcher_free",
+    "sha256",
+    "sha256_digest",
+    "sha256_free",
+    "sha256_new",
+    "sha256_update",
+    "simba_abi_version",
+    "simba_cpu_features",
+    "simba_has_symbol",
+    "simba_hasher_new",
+    "simba_last_error",
+    "simba_last_error_message",
+    "simba_selftest",
+    "simba_validator_feed",
+    "simba_validator_finish",
+    "simba_validator_free",
+    "simba_validator_new",
+    "siphash24",
+    "sort_u32",
+    "sort_u64",
+    "split_u8",
+    "streamvbyte_decode_u32",
+    "streamvbyte_encode_u32",
+    "streamvbyte_max_encoded_len",
+    "strnlen_u8",
+    "sum_f32",
+    "sum_f64",
+    "sum_i32",
+    "sum_i64",
+    "sum_u16",
+    "sum_u32",
+    "sum_u64",
+    "sum_u8",
+    "sum_u8_16",
+    "sum_u8_32",
+    "sum_u8_64",
+    "sum_u8_iov",
+    "sum_u8_parallel",
+    "sum_u8_pipelined",
+    "swizzle_rgba",
+    "teddy_compile",
+    "teddy_find",
+    "teddy_free",
+    "to_lower_ascii",
+    "to_upper_ascii",
+    "topk_f32",
+    "trampoline_echo",
+    "trampoline_sanity",
+    "transpose_u8",
+    "unpack_bits_to_bytes",
+    "url_decode",
+    "url_encode",
+    "utf8_continuation_mask",
+    "uuid_format",
+    "uuid_parse",
+    "validate_u8_lut16",
+    "validate_u8_lut32",
+    "validate_u8_lut64",
+    "validate_u8_lut_id",
+    "validate_u8_lut_pos16",
+    "validate_u8_lut_pos32",
+    "validate_u8_lut_pos64",
+    "validate_u8_lut_pos_pipelined",
+    "validate_u8_nibble_lut",
+    "validate_u8_nibble_lut_pos",
+    "validate_utf8_32",
+    "validate_utf8_64",
+    "varint_decode_u64",
+    "wyhash",
+    "xor_u8",
+    "xor_u8_inplace",
+    "xor_u8_nt",
+    "xorf_decode_f64",
+    "xorf_encode_f64",
+    "xxh3_128",
+    "xxh3_64",
+    "xxh3_digest128",
+    "xxh3_digest64",
+    "xxh3_free",
+    "xxh3_new",
+    "xxh3_update",
+    "xxh64",
+    "xxh64_digest",
+    "xxh64_free",
+    "xxh64_new",
+    "xxh64_update",
+    "zigzag_decode_i32",
+    "zigzag_decode_i64",
+    "zigzag_encode_i32",
+    "zigzag_encode_i64",
+];
+
+/// Go trampoline test helpers, left out of wasm builds.
+const WASM_OMITTED: &[&str] = &["noop", "trampoline_echo", "trampoline_sanity"];
+
+/// Heap-backed handles, left out of builds without the `alloc` feature.
+const ALLOC_ONLY: &[&str] = &[
+    "blake3_free",
+    "blake3_new",
+    "blake3_new_derive_key",
+    "blake3_new_keyed",
+    "edit_distance_le",
+    "hasher_free",
+    "hasher_reset",
+    "hasher_size",
+    "hasher_sum",
+    "hasher_write",
+    "searcher_compile",
+    "searcher_find",
+    "searcher_free",
+    "sha256_free",
+    "sha256_new",
+    "simba_hasher_new",
+    "simba_validator_feed",
+    "simba_validator_finish",
+    "simba_validator_free",
+    "simba_validator_new",
+    "teddy_compile",
+    "teddy_find",
+    "teddy_free",
+    "xxh3_free",
+    "xxh3_new",
+    "xxh64_free",
+    "xxh64_new",
+];
+
+/// Kernels needing `std` floating-point functions, left out of `no_std`
+/// builds.
+const STD_ONLY: &[&str] = &[
+    "approx_distinct_u64",
+    "cosine_similarity_f32",
+    "l2_distance_batch_f32",
+    "l2_distance_f32",
+];
+
+/// Multi-threaded kernels, only built with the `parallel` feature.
+const PARALLEL_ONLY: &[&str] = &["crc32c_parallel", "sum_u8_parallel"];
+
+/// Version of the exported C ABI; callers should refuse to run against a
+/// version they were not built for.
+#[unsafe(export_name = c_symbol!("simba_abi_version"))]
+pub unsafe extern "C" fn simba_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Return 1 if this build exports a function named by the `len` bytes at
+/// `name`, 0 otherwise.  Names are accepted with or without the build's
+/// `SIMBA_SYMBOL_PREFIX`.
+///
+/// # Safety
+/// `name` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("simba_has_symbol"))]
+pub unsafe extern "C" fn simba_has_symbol(name: *const u8, len: usize) -> u8 {
+    if name.is_null() || len == 0 {
+        return 0;
+    }
+    let omitted = |list: &[&str], name: &[u8]| list.iter().any(|s| s.as_bytes() == name);
+    let compiled_out = |name: &[u8]| {
+        (cfg!(target_family = "wasm") && omitted(WASM_OMITTED, name))
+            || (cfg!(not(feature = "alloc")) && omitted(ALLOC_ONLY, name))
+            || (cfg!(not(feature = "std")) && omitted(STD_ONLY, name))
+            || (cfg!(not(feature = "parallel")) && omitted(PARALLEL_ONLY, name))
+    };
+    let known = |name: &[u8]| {
+        !compiled_out(name)
+            && EXPORTED_SYMBOLS
+                .binary_search_by(|s| s.as_bytes().cmp(name))
+                .is_ok()
+    };
+    let name = core::slice::from_raw_parts(name, len);
+    let unprefixed = name.strip_prefix(env!("SIMBA_SYMBOL_PREFIX").as_bytes());
+    (known(name) || unprefixed.is_some_and(known)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_table_sorted() {
+        assert!(EXPORTED_SYMBOLS.windows(2).all(|w| w[0] < w[1]));
+        for name in [WASM_OMITTED, ALLOC_ONLY, STD_ONLY, PARALLEL_ONLY].concat() {
+            assert!(EXPORTED_SYMBOLS.contains(&name), "{name} is not exported");
+        }
+    }
+
+    #[test]
+    fn test_has_symbol() {
+        let has = |s: &str| unsafe { simba_has_symbol(s.as_ptr(), s.len()) };
+        assert_eq!(has("crc32_update_64_raw"), 1);
+        assert_eq!(has("simba_has_symbol"), 1);
+        assert_eq!(has("xxh64"), 1);
+        assert_eq!(has(concat!(env!("SIMBA_SYMBOL_PREFIX"), "xxh64")), 1);
+        assert_eq!(has("xxh6"), 0);
+        assert_eq!(has(""), 0);
+        assert_eq!(unsafe { simba_abi_version() }, ABI_VERSION);
+    }
+
+    #[test]
+    fn test_header_declares_every_symbol() {
+        // Regenerate with scripts/gen_header.sh if this fails.
+        let header = include_str!("../include/simba.h");
+        for name in EXPORTED_SYMBOLS {
+            assert!(
+                header.contains(&format!(" {name}(")) || header.contains(&format!("*{name}(")),
+                "include/simba.h does not declare {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_manifest_lists_every_symbol() {
+        let manifest = include_str!(concat!(env!("OUT_DIR"), "/simba.json"));
+        assert!(manifest.contains(&format!("\"abi_version\": {ABI_VERSION},")));
+        for name in EXPORTED_SYMBOLS {
+            assert!(
+                manifest.contains(&format!("\"name\": \"{name}\",")),
+                "simba.json does not list {name}"
+            );
+        }
+    }
+}
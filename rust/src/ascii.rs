@@ -0,0 +1,475 @@
+//! ASCII text kernels (case conversion, case-insensitive comparison,
+//! trimming, byte-range checks, locating non-ASCII bytes).
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Mask, Simd, SupportedLaneCount};
+
+use crate::{load_simd, store_simd};
+
+const ASCII_LANES: usize = 32;
+
+// === Case conversion =========================================================
+
+/// Flip bit 5 of every byte in `lo..=hi`, leaving all other bytes untouched.
+#[inline(always)]
+fn flip_case_range<const L: usize>(v: Simd<u8, L>, lo: u8, hi: u8) -> Simd<u8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let hit = v.simd_ge(Simd::splat(lo)) & v.simd_le(Simd::splat(hi));
+    v ^ hit.select(Simd::splat(0x20), Simd::splat(0))
+}
+
+#[inline(always)]
+unsafe fn flip_case_impl<const L: usize>(src: *const u8, len: usize, dst: *mut u8, lo: u8, hi: u8)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= len {
+        let v = load_simd::<u8, L>(src.add(i));
+        store_simd(dst.add(i), flip_case_range(v, lo, hi));
+        i += L;
+    }
+    while i < len {
+        let b = *src.add(i);
+        *dst.add(i) = if (lo..=hi).contains(&b) { b ^ 0x20 } else { b };
+        i += 1;
+    }
+}
+
+/// Convert ASCII `a-z` to `A-Z`; all other bytes (including non-ASCII) are
+/// copied unchanged.  Uses a range compare instead of a table gather.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+/// exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("to_upper_ascii"))]
+pub unsafe extern "C" fn to_upper_ascii(src: *const u8, len: usize, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    flip_case_impl::<ASCII_LANES>(src, len, dst, b'a', b'z');
+}
+
+/// Convert ASCII `A-Z` to `a-z`; all other bytes (including non-ASCII) are
+/// copied unchanged.  Uses a range compare instead of a table gather.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+/// exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("to_lower_ascii"))]
+pub unsafe extern "C" fn to_lower_ascii(src: *const u8, len: usize, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    flip_case_impl::<ASCII_LANES>(src, len, dst, b'A', b'Z');
+}
+
+// === Case-insensitive equality ===============================================
+
+#[inline(always)]
+unsafe fn eq_ignore_ascii_case_impl<const L: usize>(a: &[u8], b: &[u8]) -> bool
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut ca = a.chunks_exact(L);
+    let mut cb = b.chunks_exact(L);
+    for (x, y) in (&mut ca).zip(&mut cb) {
+        let x = flip_case_range(Simd::<u8, L>::from_slice(x), b'A', b'Z');
+        let y = flip_case_range(Simd::<u8, L>::from_slice(y), b'A', b'Z');
+        if x.simd_ne(y).any() {
+            return false;
+        }
+    }
+    ca.remainder().eq_ignore_ascii_case(cb.remainder())
+}
+
+/// Return 1 if the first `len` bytes of `a` and `b` are equal under ASCII
+/// case folding (`A-Z` ≡ `a-z`; other bytes must match exactly), else 0.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("eq_ignore_ascii_case"))]
+pub unsafe extern "C" fn eq_ignore_ascii_case(a: *const u8, b: *const u8, len: usize) -> u8 {
+    if len == 0 {
+        return 1;
+    }
+    if a.is_null() || b.is_null() {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    eq_ignore_ascii_case_impl::<ASCII_LANES>(a, b) as u8
+}
+
+// === Trimming ================================================================
+
+/// ASCII whitespace as in Go's `unicode.IsSpace` for bytes < 0x80:
+/// `\t \n \v \f \r` and space.
+#[inline(always)]
+fn is_space<const L: usize>(v: Simd<u8, L>) -> Mask<i8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    v.simd_eq(Simd::splat(b' ')) | (v.simd_ge(Simd::splat(0x09)) & v.simd_le(Simd::splat(0x0D)))
+}
+
+#[inline(always)]
+fn is_space_scalar(b: u8) -> bool {
+    b == b' ' || (0x09..=0x0D).contains(&b)
+}
+
+/// Membership in a 256-byte table (non-zero entry = member).
+#[inline(always)]
+fn in_lut<const L: usize>(v: Simd<u8, L>, lut: &[u8]) -> Mask<i8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let idx: Simd<usize, L> = v.cast();
+    Simd::<u8, L>::gather_or_default(lut, idx).simd_ne(Simd::splat(0))
+}
+
+/// Index of the first byte for which `member` is false.
+#[inline(always)]
+fn first_not_impl<const L: usize>(
+    data: &[u8],
+    member: impl Fn(Simd<u8, L>) -> Mask<i8, L>,
+    member_scalar: impl Fn(u8) -> bool,
+) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let keep = !member(Simd::from_slice(chunk));
+        if keep.any() {
+            return Some(i * L + keep.to_bitmask().trailing_zeros() as usize);
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|&b| !member_scalar(b))
+        .map(|i| base + i)
+}
+
+/// Index of the last byte for which `member` is false.
+#[inline(always)]
+fn last_not_impl<const L: usize>(
+    data: &[u8],
+    member: impl Fn(Simd<u8, L>) -> Mask<i8, L>,
+    member_scalar: impl Fn(u8) -> bool,
+) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut chunks = data.rchunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let keep = !member(Simd::from_slice(chunk));
+        if keep.any() {
+            let lane = 63 - keep.to_bitmask().leading_zeros() as usize;
+            return Some(data.len() - (i + 1) * L + lane);
+        }
+    }
+    chunks.remainder().iter().rposition(|&b| !member_scalar(b))
+}
+
+/// Return the index of the first byte that is not ASCII whitespace
+/// (`\t \n \v \f \r` or space), or -1 if every byte is whitespace.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("first_non_whitespace"))]
+pub unsafe extern "C" fn first_non_whitespace(ptr: *const u8, len: usize) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    first_not_impl::<ASCII_LANES>(data, is_space, is_space_scalar).map_or(-1, |i| i as isize)
+}
+
+/// Return the index of the last byte that is not ASCII whitespace, or -1 if
+/// every byte is whitespace.  The trimmed slice is `[first, last]` inclusive.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("last_non_whitespace"))]
+pub unsafe extern "C" fn last_non_whitespace(ptr: *const u8, len: usize) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    last_not_impl::<ASCII_LANES>(data, is_space, is_space_scalar).map_or(-1, |i| i as isize)
+}
+
+/// Return the index of the first byte whose `lut` entry is zero (i.e. not in
+/// the trim set), or -1 if every byte is in the set.
+///
+/// # Safety
+/// `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+#[unsafe(export_name = c_symbol!("first_not_in_lut"))]
+pub unsafe extern "C" fn first_not_in_lut(ptr: *const u8, len: usize, lut: *const u8) -> isize {
+    if ptr.is_null() || lut.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let lut = core::slice::from_raw_parts(lut, 256);
+    first_not_impl::<ASCII_LANES>(data, |v| in_lut(v, lut), |b| lut[b as usize] != 0)
+        .map_or(-1, |i| i as isize)
+}
+
+/// Return the index of the last byte whose `lut` entry is zero, or -1 if
+/// every byte is in the set.
+///
+/// # Safety
+/// `ptr`/`lut` must be valid for `len`/256 bytes respectively.
+#[unsafe(export_name = c_symbol!("last_not_in_lut"))]
+pub unsafe extern "C" fn last_not_in_lut(ptr: *const u8, len: usize, lut: *const u8) -> isize {
+    if ptr.is_null() || lut.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let lut = core::slice::from_raw_parts(lut, 256);
+    last_not_impl::<ASCII_LANES>(data, |v| in_lut(v, lut), |b| lut[b as usize] != 0)
+        .map_or(-1, |i| i as isize)
+}
+
+// === Byte-range check ========================================================
+
+/// Whether every byte lies in `lo..=hi`.  A wrapping subtract maps the range
+/// to `0..=hi - lo`, so one unsigned max per block decides it.
+#[inline(always)]
+fn all_in_range_impl<const L: usize>(data: &[u8], lo: u8, hi: u8) -> bool
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let span = hi.wrapping_sub(lo);
+    let mut chunks = data.chunks_exact(L);
+    for chunk in &mut chunks {
+        if (Simd::<u8, L>::from_slice(chunk) - Simd::splat(lo)).reduce_max() > span {
+            return false;
+        }
+    }
+    chunks
+        .remainder()
+        .iter()
+        .all(|&b| b.wrapping_sub(lo) <= span)
+}
+
+/// Return 1 if every byte lies in `lo..=hi`, 0 otherwise (always 0 for a
+/// non-empty input when `lo > hi`).  Uses plain compares instead of the table
+/// gather of `validate_u8_lut*`, for digit-only or printable-only fields.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("all_in_range_u8"))]
+pub unsafe extern "C" fn all_in_range_u8(ptr: *const u8, len: usize, lo: u8, hi: u8) -> u8 {
+    if ptr.is_null() || len == 0 {
+        return 1;
+    }
+    if lo > hi {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    all_in_range_impl::<ASCII_LANES>(data, lo, hi) as u8
+}
+
+/// Return the index of the first byte outside `lo..=hi`, or -1 if every byte
+/// is in range.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("first_not_in_range_u8"))]
+pub unsafe extern "C" fn first_not_in_range_u8(
+    ptr: *const u8,
+    len: usize,
+    lo: u8,
+    hi: u8,
+) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    if lo > hi {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let span = hi - lo;
+    first_not_impl::<ASCII_LANES>(
+        data,
+        |v| (v - Simd::splat(lo)).simd_le(Simd::splat(span)),
+        |b| b.wrapping_sub(lo) <= span,
+    )
+    .map_or(-1, |i| i as isize)
+}
+
+// === First non-ASCII byte ====================================================
+
+/// Index of the first byte >= 0x80, if any.
+#[inline(always)]
+pub(crate) fn first_non_ascii_impl<const L: usize>(data: &[u8]) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    first_not_impl::<L>(data, |v| v.simd_lt(Simd::splat(0x80)), |b| b < 0x80)
+}
+
+/// Return the index of the first non-ASCII byte (>= 0x80), or -1 if every
+/// byte is ASCII.  Same scan as `is_ascii`, but hands back where the
+/// non-ASCII region starts.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("first_non_ascii"))]
+pub unsafe extern "C" fn first_non_ascii(ptr: *const u8, len: usize) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    first_non_ascii_impl::<ASCII_LANES>(data).map_or(-1, |i| i as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversion_all_bytes() {
+        let src: Vec<u8> = (0..=255u8).cycle().take(700).collect();
+        let mut dst = vec![0u8; src.len()];
+        unsafe {
+            to_upper_ascii(src.as_ptr(), src.len(), dst.as_mut_ptr());
+            assert_eq!(dst, src.to_ascii_uppercase());
+            to_lower_ascii(src.as_ptr(), src.len(), dst.as_mut_ptr());
+            assert_eq!(dst, src.to_ascii_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_case_conversion_in_place() {
+        let mut buf = b"Content-Type: Text/HTML; charset=UTF-8 \xC3\x89t\xC3\xA9".repeat(5);
+        let want = buf.to_ascii_lowercase();
+        let p = buf.as_mut_ptr();
+        unsafe { to_lower_ascii(p, buf.len(), p) };
+        assert_eq!(buf, want);
+    }
+
+    #[test]
+    fn test_eq_ignore_ascii_case() {
+        let a = b"Content-Type: application/JSON; charset=utf-8 -- X-Request-Id".to_vec();
+        let b = a.to_ascii_uppercase();
+        let eq =
+            |x: &[u8], y: &[u8]| unsafe { eq_ignore_ascii_case(x.as_ptr(), y.as_ptr(), x.len()) };
+        assert_eq!(eq(&a, &b), 1);
+        for i in 0..a.len() {
+            let mut c = b.clone();
+            // '@' vs '`' and '[' vs '{' differ only in bit 5 but are not letters.
+            c[i] ^= 0x20;
+            assert_eq!(eq(&a, &c), a[i].is_ascii_alphabetic() as u8, "i={}", i);
+        }
+        assert_eq!(eq(b"@[", b"`{"), 0);
+        assert_eq!(eq(b"\xC9", b"\xE9"), 0);
+        assert_eq!(
+            unsafe { eq_ignore_ascii_case(core::ptr::null(), core::ptr::null(), 0) },
+            1
+        );
+    }
+
+    #[test]
+    fn test_trim_whitespace() {
+        let ws = b" \t\n\x0B\x0C\r";
+        for pre in [0usize, 1, 31, 32, 33, 70] {
+            for post in [0usize, 1, 31, 32, 33, 70] {
+                let mut buf: Vec<u8> = (0..pre).map(|i| ws[i % ws.len()]).collect();
+                buf.extend_from_slice(b"x \x85y");
+                buf.extend((0..post).map(|i| ws[i % ws.len()]));
+                unsafe {
+                    assert_eq!(first_non_whitespace(buf.as_ptr(), buf.len()), pre as isize);
+                    assert_eq!(
+                        last_non_whitespace(buf.as_ptr(), buf.len()),
+                        (pre + 3) as isize
+                    );
+                }
+            }
+        }
+        let blank = b" \t ".repeat(40);
+        unsafe {
+            assert_eq!(first_non_whitespace(blank.as_ptr(), blank.len()), -1);
+            assert_eq!(last_non_whitespace(blank.as_ptr(), blank.len()), -1);
+        }
+    }
+
+    #[test]
+    fn test_trim_lut() {
+        let mut lut = [0u8; 256];
+        lut[b'/' as usize] = 1;
+        lut[b'.' as usize] = 1;
+        let buf = [b"./".repeat(20), b"a/b".to_vec(), b"/.".repeat(30)].concat();
+        unsafe {
+            assert_eq!(first_not_in_lut(buf.as_ptr(), buf.len(), lut.as_ptr()), 40);
+            assert_eq!(last_not_in_lut(buf.as_ptr(), buf.len(), lut.as_ptr()), 42);
+            assert_eq!(first_not_in_lut(buf.as_ptr(), 40, lut.as_ptr()), -1);
+        }
+    }
+
+    #[test]
+    fn test_first_non_ascii() {
+        for len in [1usize, 31, 32, 33, 100] {
+            let clean = vec![b'a'; len];
+            unsafe {
+                assert_eq!(first_non_ascii(clean.as_ptr(), len), -1);
+                for at in [0, len / 2, len - 1] {
+                    let mut buf = clean.clone();
+                    buf[at] = 0x80 | at as u8;
+                    buf[len - 1] = 0xFF;
+                    assert_eq!(first_non_ascii(buf.as_ptr(), len), at as isize);
+                }
+            }
+        }
+        assert_eq!(unsafe { first_non_ascii(core::ptr::null(), 0) }, -1);
+    }
+
+    #[test]
+    fn test_byte_range() {
+        let digits: Vec<u8> = b"0123456789".repeat(10);
+        unsafe {
+            assert_eq!(
+                all_in_range_u8(digits.as_ptr(), digits.len(), b'0', b'9'),
+                1
+            );
+            assert_eq!(
+                first_not_in_range_u8(digits.as_ptr(), digits.len(), b'0', b'9'),
+                -1
+            );
+            assert_eq!(
+                all_in_range_u8(digits.as_ptr(), digits.len(), b'1', b'9'),
+                0
+            );
+            assert_eq!(
+                first_not_in_range_u8(digits.as_ptr(), digits.len(), b'1', b'9'),
+                0
+            );
+            assert_eq!(all_in_range_u8(digits.as_ptr(), 0, b'9', b'0'), 1);
+            assert_eq!(first_not_in_range_u8(digits.as_ptr(), 5, b'9', b'0'), 0);
+            for at in [1, 31, 32, 63, 70, 99] {
+                let mut buf = digits.clone();
+                buf[at] = if at % 2 == 0 { b'/' } else { b':' };
+                assert_eq!(all_in_range_u8(buf.as_ptr(), buf.len(), b'0', b'9'), 0);
+                assert_eq!(
+                    first_not_in_range_u8(buf.as_ptr(), buf.len(), b'0', b'9'),
+                    at as isize
+                );
+            }
+            let all: Vec<u8> = (0..=255).collect();
+            assert_eq!(all_in_range_u8(all.as_ptr(), all.len(), 0, 255), 1);
+            assert_eq!(
+                first_not_in_range_u8(all.as_ptr(), all.len(), 0x20, 0x7E),
+                0
+            );
+            assert_eq!(
+                first_not_in_range_u8(all[0x20..].as_ptr(), 0x60, 0x20, 0x7E),
+                0x5F
+            );
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! PCM sample-format kernels (i16 ↔ f32 conversion, stereo interleaving).
+use core::simd::prelude::{SimdFloat, SimdInt, SimdOrd, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Simd, SupportedLaneCount};
+
+use crate::interleave::{deinterleave2_impl, interleave2_impl};
+
+const PCM_LANES: usize = 16;
+
+/// Full-scale factor: i16 samples map onto `[-1.0, 1.0)`.
+const PCM_SCALE: f32 = 32768.0;
+
+// === i16 → f32 ===============================================================
+
+#[inline(always)]
+unsafe fn pcm_i16_to_f32_impl<const L: usize>(src: &[i16], dst: &mut [f32])
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let inv = Simd::<f32, L>::splat(1.0 / PCM_SCALE);
+    let mut chunks = src.chunks_exact(L);
+    let mut out_chunks = dst.chunks_exact_mut(L);
+    for (chunk, out) in (&mut chunks).zip(&mut out_chunks) {
+        let v = Simd::<i16, L>::from_slice(chunk).cast::<f32>();
+        (v * inv).copy_to_slice(out);
+    }
+    for (&s, out) in chunks.remainder().iter().zip(out_chunks.into_remainder()) {
+        *out = s as f32 / PCM_SCALE;
+    }
+}
+
+/// Convert `n` signed 16-bit PCM samples to f32 in `[-1.0, 1.0)` (`s / 32768`).
+///
+/// # Safety
+/// `src` must be valid for `n` i16 values and `dst` for `n` f32 values; the
+/// buffers must not overlap.
+#[unsafe(export_name = c_symbol!("pcm_i16_to_f32"))]
+pub unsafe extern "C" fn pcm_i16_to_f32(src: *const i16, n: usize, dst: *mut f32) {
+    if src.is_null() || dst.is_null() || n == 0 {
+        return;
+    }
+    let src_slice = core::slice::from_raw_parts(src, n);
+    let dst_slice = core::slice::from_raw_parts_mut(dst, n);
+    pcm_i16_to_f32_impl::<PCM_LANES>(src_slice, dst_slice);
+}
+
+// === f32 → i16 ===============================================================
+
+/// Seed of the dither generator.  Fixed so conversions are reproducible.
+const DITHER_SEED: u32 = 0x9E37_79B9;
+
+/// One xorshift32 step per lane.
+#[inline(always)]
+fn xorshift32<const L: usize>(mut x: Simd<u32, L>) -> Simd<u32, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    x
+}
+
+/// Map the top 24 bits of each lane onto `[0, 1)`.
+#[inline(always)]
+fn unit_f32<const L: usize>(x: Simd<u32, L>) -> Simd<f32, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    (x >> 8).cast::<f32>() * Simd::splat(1.0 / (1u32 << 24) as f32)
+}
+
+/// `round(v)` (half away from zero) saturated to i16, NaN → 0.  `StdFloat`
+/// rounding is not in `core`, so round from the truncated integer: `v - t`
+/// is exact, and the float → int cast already saturates and maps NaN to 0.
+#[inline(always)]
+fn round_sat_i16<const L: usize>(v: Simd<f32, L>) -> Simd<i16, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let t = v.cast::<i32>();
+    let frac = v - t.cast::<f32>();
+    let up = frac
+        .simd_ge(Simd::splat(0.5))
+        .select(Simd::splat(1), Simd::splat(0));
+    let down = frac
+        .simd_le(Simd::splat(-0.5))
+        .select(Simd::splat(1), Simd::splat(0));
+    t.saturating_add(up)
+        .saturating_sub(down)
+        .simd_clamp(Simd::splat(i16::MIN as i32), Simd::splat(i16::MAX as i32))
+        .cast::<i16>()
+}
+
+#[inline(always)]
+unsafe fn pcm_f32_to_i16_impl<const L: usize>(src: &[f32], dst: &mut [i16], dither: bool)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let scale = Simd::<f32, L>::splat(PCM_SCALE);
+    // Independent generator per lane; the lane index breaks the symmetry.
+    let mut state = Simd::<u32, L>::from_array(core::array::from_fn(|i| {
+        DITHER_SEED ^ (i as u32).wrapping_mul(0x85EB_CA6B)
+    }));
+    state = xorshift32(state);
+
+    let mut convert = |v: Simd<f32, L>| {
+        let mut v = v * scale;
+        if dither {
+            // TPDF: difference of two uniforms, ±1 LSB peak.
+            let r1 = unit_f32(state);
+            state = xorshift32(state);
+            let r2 = unit_f32(state);
+            state = xorshift32(state);
+            v += r1 - r2;
+        }
+        round_sat_i16(v)
+    };
+
+    let mut chunks = src.chunks_exact(L);
+    let mut out_chunks = dst.chunks_exact_mut(L);
+    for (chunk, out) in (&mut chunks).zip(&mut out_chunks) {
+        convert(Simd::from_slice(chunk)).copy_to_slice(out);
+    }
+    // Run the tail through the same vector path so it is dithered alike.
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        let mut tail = [0f32; L];
+        tail[..rem.len()].copy_from_slice(rem);
+        let v = convert(Simd::from_array(tail));
+        out_chunks
+            .into_remainder()
+            .copy_from_slice(&v.as_array()[..rem.len()]);
+    }
+}
+
+/// Convert `n` f32 samples to signed 16-bit PCM: `round(x * 32768)`,
+/// saturated to `[-32768, 32767]` (NaN becomes 0).
+///
+/// When `dither` is non-zero, triangular (TPDF) dither of ±1 LSB is added
+/// before rounding.  The dither sequence is generated from a fixed seed, so
+/// the output is deterministic for a given input.
+///
+/// # Safety
+/// `src` must be valid for `n` f32 values and `dst` for `n` i16 values; the
+/// buffers must not overlap.
+#[unsafe(export_name = c_symbol!("pcm_f32_to_i16"))]
+pub unsafe extern "C" fn pcm_f32_to_i16(src: *const f32, n: usize, dst: *mut i16, dither: u8) {
+    if src.is_null() || dst.is_null() || n == 0 {
+        return;
+    }
+    let src_slice = core::slice::from_raw_parts(src, n);
+    let dst_slice = core::slice::from_raw_parts_mut(dst, n);
+    pcm_f32_to_i16_impl::<PCM_LANES>(src_slice, dst_slice, dither != 0);
+}
+
+// === Stereo interleave / deinterleave ========================================
+
+macro_rules! export_pcm_stereo {
+    ($interleave:ident, $deinterleave:ident, $t:ty) => {
+        #[doc = concat!(
+            "Interleave `n_frames` planar `", stringify!($t), "` samples from `left` and `right` into `dst` as `L R L R …`.\n\n",
+            "# Safety\n",
+            "`left`/`right` must be valid for `n_frames` elements and `dst` for `2 * n_frames`; `dst` must not overlap the inputs."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($interleave)))]
+        pub unsafe extern "C" fn $interleave(
+            left: *const $t,
+            right: *const $t,
+            n_frames: usize,
+            dst: *mut $t,
+        ) {
+            if left.is_null() || right.is_null() || dst.is_null() || n_frames == 0 {
+                return;
+            }
+            interleave2_impl::<$t, PCM_LANES>(left, right, n_frames, dst);
+        }
+
+        #[doc = concat!(
+            "Split `n_frames` interleaved stereo `", stringify!($t), "` frames from `src` into planar `left` and `right`.\n\n",
+            "# Safety\n",
+            "`src` must be valid for `2 * n_frames` elements and `left`/`right` for `n_frames`; outputs must not overlap `src` or each other."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($deinterleave)))]
+        pub unsafe extern "C" fn $deinterleave(
+            src: *const $t,
+            n_frames: usize,
+            left: *mut $t,
+            right: *mut $t,
+        ) {
+            if src.is_null() || left.is_null() || right.is_null() || n_frames == 0 {
+                return;
+            }
+            deinterleave2_impl::<$t, PCM_LANES>(src, n_frames, left, right);
+        }
+    };
+}
+export_pcm_stereo!(pcm_interleave_stereo_i16, pcm_deinterleave_stereo_i16, i16);
+export_pcm_stereo!(pcm_interleave_stereo_f32, pcm_deinterleave_stereo_f32, f32);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_pcm_i16_f32_round_trip() {
+        let src: Vec<i16> = (0..1000i32)
+            .map(|i| (i * 65 - 32768).clamp(-32768, 32767) as i16)
+            .chain([i16::MIN, i16::MAX, 0, -1, 1])
+            .collect();
+        let mut f = vec![0f32; src.len()];
+        let mut back = vec![0i16; src.len()];
+        unsafe {
+            super::pcm_i16_to_f32(src.as_ptr(), src.len(), f.as_mut_ptr());
+            super::pcm_f32_to_i16(f.as_ptr(), f.len(), back.as_mut_ptr(), 0);
+        }
+        for (i, &s) in src.iter().enumerate() {
+            assert_eq!(f[i], s as f32 / 32768.0);
+        }
+        assert_eq!(back, src);
+    }
+
+    #[test]
+    fn test_round_sat_i16() {
+        let mut vals = vec![0.5f32, -0.5, 1.5, -2.5, 0.49999997, -0.49999997, 32766.5];
+        vals.extend([
+            32767.5,
+            -32768.5,
+            1e9,
+            -1e9,
+            3e9,
+            -3e9,
+            f32::NAN,
+            f32::INFINITY,
+        ]);
+        vals.extend((0..20000).map(|i| (i as f32 - 10000.0) * 3.3e-3));
+        vals.extend((0..20000).map(|i| (i as f32 - 10000.0) * 3.27681));
+        for v in vals {
+            let want = v.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            let got = super::round_sat_i16(core::simd::Simd::<f32, 4>::splat(v))[0];
+            assert_eq!(got, want, "{v}");
+        }
+    }
+
+    #[test]
+    fn test_pcm_f32_to_i16_saturates() {
+        let src = [2.0f32, -2.0, 1.0, -1.0, f32::NAN, 0.5];
+        let mut dst = [0i16; 6];
+        unsafe { super::pcm_f32_to_i16(src.as_ptr(), src.len(), dst.as_mut_ptr(), 0) };
+        assert_eq!(dst, [32767, -32768, 32767, -32768, 0, 16384]);
+    }
+
+    #[test]
+    fn test_pcm_f32_to_i16_dither_bounded() {
+        let src: Vec<f32> = (0..257).map(|i| (i as f32 - 128.0) / 300.0).collect();
+        let mut plain = vec![0i16; src.len()];
+        let mut dith = vec![0i16; src.len()];
+        let mut again = vec![0i16; src.len()];
+        unsafe {
+            super::pcm_f32_to_i16(src.as_ptr(), src.len(), plain.as_mut_ptr(), 0);
+            super::pcm_f32_to_i16(src.as_ptr(), src.len(), dith.as_mut_ptr(), 1);
+            super::pcm_f32_to_i16(src.as_ptr(), src.len(), again.as_mut_ptr(), 1);
+        }
+        assert_eq!(dith, again, "dither must be deterministic");
+        assert_ne!(dith, plain);
+        for (a, b) in plain.iter().zip(&dith) {
+            assert!((*a as i32 - *b as i32).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_pcm_stereo_round_trip() {
+        for n in [1usize, 15, 16, 17, 100] {
+            let left: Vec<i16> = (0..n as i16).collect();
+            let right: Vec<i16> = (0..n as i16).map(|x| -x - 1).collect();
+            let mut inter = vec![0i16; 2 * n];
+            let (mut l2, mut r2) = (vec![0i16; n], vec![0i16; n]);
+            unsafe {
+                super::pcm_interleave_stereo_i16(
+                    left.as_ptr(),
+                    right.as_ptr(),
+                    n,
+                    inter.as_mut_ptr(),
+                );
+                super::pcm_deinterleave_stereo_i16(
+                    inter.as_ptr(),
+                    n,
+                    l2.as_mut_ptr(),
+                    r2.as_mut_ptr(),
+                );
+            }
+            for i in 0..n {
+                assert_eq!([inter[2 * i], inter[2 * i + 1]], [left[i], right[i]]);
+            }
+            assert_eq!((l2, r2), (left, right));
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Unsuffixed byte kernels (`sum_u8`, `is_ascii`, `map_u8_lut`,
+//! `eq_u8_masks`) that pick the lane width at runtime from the CPU and the
+//! buffer length.
+use core::simd::{LaneCount, SupportedLaneCount};
+
+#[cfg(target_arch = "x86_64")]
+use crate::avx512;
+use crate::{eq_u8_words, is_ascii_impl, map_u8_lut_impl, sum_u8_impl};
+
+/// Widest byte vector the running CPU executes natively: 64 lanes with
+/// AVX-512BW, 32 with AVX2, otherwise 16 (SSE2).  64 lanes run on the
+/// [`avx512`](crate::avx512) specialisations.
+///
+/// NEON registers are 128 bits, but aarch64 cores issue several vector ops
+/// per cycle, so 32 lanes (a pair of registers per step) keeps the pipes busy
+/// without paying for the four-register bitmask assembly a 64-lane mask needs
+/// (NEON has no movemask).
+fn native_lanes() -> usize {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if avx512::available() {
+            return 64;
+        }
+        if x86_feature!("avx2") {
+            return 32;
+        }
+    }
+    if cfg!(target_arch = "aarch64") {
+        32
+    } else {
+        16
+    }
+}
+
+/// Lane count for the unsuffixed kernels: the native width, narrowed so a
+/// short buffer still fills at least one vector.
+fn auto_lanes(len: usize) -> usize {
+    match native_lanes() {
+        64 if len >= 64 => 64,
+        n if n >= 32 && len >= 32 => 32,
+        _ => 16,
+    }
+}
+
+/// Sum the bytes in `data` modulo 2^32, picking the lane width from the CPU
+/// and `len` (see `sum_u8_16`/`_32`/`_64` for fixed widths).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("sum_u8"))]
+pub unsafe extern "C" fn sum_u8(ptr: *const u8, len: usize) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    match auto_lanes(len) {
+        #[cfg(target_arch = "x86_64")]
+        64 => avx512::sum_u8(data),
+        #[cfg(not(target_arch = "x86_64"))]
+        64 => sum_u8_impl::<64>(data),
+        32 => sum_u8_impl::<32>(data),
+        _ => sum_u8_impl::<16>(data),
+    }
+}
+
+/// Return 1 if all bytes are ASCII (< 0x80), 0 otherwise, picking the lane
+/// width from the CPU and `len`.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("is_ascii"))]
+pub unsafe extern "C" fn is_ascii(ptr: *const u8, len: usize) -> u8 {
+    if ptr.is_null() || len == 0 {
+        return 1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    (match auto_lanes(len) {
+        #[cfg(target_arch = "x86_64")]
+        64 => avx512::is_ascii(data),
+        #[cfg(not(target_arch = "x86_64"))]
+        64 => is_ascii_impl::<64>(data),
+        32 => is_ascii_impl::<32>(data),
+        _ => is_ascii_impl::<16>(data),
+    }) as u8
+}
+
+/// Map each source byte through a 256-byte translation table into `dst`,
+/// picking the lane width from the CPU and `len`.
+///
+/// # Safety
+/// All pointers must be non-null and valid for `len` bytes (`map` for 256).
+/// Buffers may overlap.
+#[unsafe(export_name = c_symbol!("map_u8_lut"))]
+pub unsafe extern "C" fn map_u8_lut(src: *const u8, len: usize, dst: *mut u8, map: *const u8) {
+    if len == 0 || src.is_null() || dst.is_null() || map.is_null() {
+        return;
+    }
+    match auto_lanes(len) {
+        #[cfg(target_arch = "x86_64")]
+        64 => avx512::map_u8_lut(src, len, dst, map),
+        #[cfg(not(target_arch = "x86_64"))]
+        64 => map_u8_lut_impl::<64>(src, len, dst, map),
+        32 => map_u8_lut_impl::<32>(src, len, dst, map),
+        _ => map_u8_lut_impl::<16>(src, len, dst, map),
+    }
+}
+
+/// Fill 64-bit mask words for `data`, comparing `L` lanes at a time.
+#[inline(always)]
+pub(crate) unsafe fn eq_u8_masks_u64_impl<const L: usize>(data: &[u8], needle: u8, out: &mut [u64])
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    out.fill(0);
+    eq_u8_words::<L>(data, needle, |i, m| out[i * L / 64] |= m << (i * L % 64));
+}
+
+/// Equality bitmasks of each byte against `needle`, as 64-bit words
+/// regardless of the lane width picked from the CPU and `len`.  Writes and
+/// returns `len.div_ceil(64)` words; bits past the end of the buffer are zero.
+///
+/// # Safety
+/// `src` must be valid for `len` bytes and `out` for `len.div_ceil(64)` words.
+#[unsafe(export_name = c_symbol!("eq_u8_masks"))]
+pub unsafe extern "C" fn eq_u8_masks(
+    src: *const u8,
+    len: usize,
+    needle: u8,
+    out: *mut u64,
+) -> usize {
+    if src.is_null() || out.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(src, len);
+    let out = core::slice::from_raw_parts_mut(out, len.div_ceil(64));
+    match auto_lanes(len) {
+        #[cfg(target_arch = "x86_64")]
+        64 => avx512::eq_u8_masks_u64(data, needle, out),
+        #[cfg(not(target_arch = "x86_64"))]
+        64 => eq_u8_masks_u64_impl::<64>(data, needle, out),
+        32 => eq_u8_masks_u64_impl::<32>(data, needle, out),
+        _ => eq_u8_masks_u64_impl::<16>(data, needle, out),
+    }
+    out.len()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_auto_dispatch() {
+        let map: Vec<u8> = (0..=255u16).map(|b| (b as u8).rotate_left(3)).collect();
+        for len in [0usize, 1, 15, 16, 31, 32, 63, 64, 65, 200, 1000] {
+            let src: Vec<u8> = (0..len as u32).map(|i| (i * 37 % 251) as u8).collect();
+            let ascii = src.iter().all(|&b| b < 0x80) as u8;
+            let mut dst = vec![0u8; len];
+            let mut masks = vec![0u64; len.div_ceil(64)];
+            let mut want_masks = vec![0u64; len.div_ceil(64)];
+            for (i, &b) in src.iter().enumerate() {
+                want_masks[i / 64] |= ((b == 74) as u64) << (i % 64);
+            }
+            unsafe {
+                assert_eq!(
+                    super::sum_u8(src.as_ptr(), len),
+                    crate::sum_u8_16(src.as_ptr(), len)
+                );
+                assert_eq!(super::is_ascii(src.as_ptr(), len), ascii);
+                super::map_u8_lut(src.as_ptr(), len, dst.as_mut_ptr(), map.as_ptr());
+                assert_eq!(
+                    super::eq_u8_masks(src.as_ptr(), len, 74, masks.as_mut_ptr()),
+                    masks.len()
+                );
+            }
+            let want: Vec<u8> = src.iter().map(|&b| b.rotate_left(3)).collect();
+            assert_eq!(dst, want, "len {len}");
+            assert_eq!(masks, want_masks, "len {len}");
+            for lanes in [16, 32, 64] {
+                let mut m = vec![0u64; len.div_ceil(64)];
+                unsafe {
+                    match lanes {
+                        16 => super::eq_u8_masks_u64_impl::<16>(&src, 74, &mut m),
+                        32 => super::eq_u8_masks_u64_impl::<32>(&src, 74, &mut m),
+                        _ => super::eq_u8_masks_u64_impl::<64>(&src, 74, &mut m),
+                    }
+                }
+                assert_eq!(m, want_masks, "len {len} lanes {lanes}");
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! AVX-512BW specialisations of the hot byte kernels.
+//!
+//! The 64-lane portable-SIMD kernels only lower to zmm registers when the
+//! enclosing function is compiled with AVX-512 enabled; without it LLVM
+//! splits every vector into narrower ones.  Each wrapper here instantiates the
+//! shared `#[inline(always)]` kernel inside a `#[target_feature]` function, and
+//! callers take it only after [`available`] confirms the CPU support.
+use crate::auto::eq_u8_masks_u64_impl;
+use crate::{eq_u8_masks_impl, is_ascii_impl, map_u8_lut_impl, sum_u8_impl};
+
+/// Whether the running CPU has AVX-512F and AVX-512BW.
+#[inline]
+pub(crate) fn available() -> bool {
+    x86_feature!("avx512f") && x86_feature!("avx512bw")
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+pub(crate) unsafe fn sum_u8(data: &[u8]) -> u32 {
+    sum_u8_impl::<64>(data)
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+pub(crate) unsafe fn is_ascii(data: &[u8]) -> bool {
+    is_ascii_impl::<64>(data)
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+pub(crate) unsafe fn map_u8_lut(src: *const u8, len: usize, dst: *mut u8, table: *const u8) {
+    map_u8_lut_impl::<64>(src, len, dst, table)
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+pub(crate) unsafe fn eq_u8_masks(
+    src: *const u8,
+    len: usize,
+    needle: u8,
+    out: *mut u64,
+    tail: bool,
+) -> usize {
+    eq_u8_masks_impl::<64, u64>(src, len, needle, out, |m| m, tail)
+}
+
+#[target_feature(enable = "avx512f,avx512bw")]
+pub(crate) unsafe fn eq_u8_masks_u64(data: &[u8], needle: u8, out: &mut [u64]) {
+    eq_u8_masks_u64_impl::<64>(data, needle, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avx512_matches_portable() {
+        if !available() {
+            return;
+        }
+        let map: Vec<u8> = (0..=255u16).map(|b| (b as u8) ^ 0x5A).collect();
+        for len in [0usize, 1, 63, 64, 65, 1000, 4099] {
+            let src: Vec<u8> = (0..len as u32).map(|i| (i * 13 % 127) as u8).collect();
+            unsafe {
+                assert_eq!(sum_u8(&src), sum_u8_impl::<16>(&src), "len {len}");
+                assert_eq!(is_ascii(&src), is_ascii_impl::<16>(&src), "len {len}");
+                let mut a = vec![0u8; len];
+                let mut b = vec![0u8; len];
+                map_u8_lut(src.as_ptr(), len, a.as_mut_ptr(), map.as_ptr());
+                map_u8_lut_impl::<16>(src.as_ptr(), len, b.as_mut_ptr(), map.as_ptr());
+                assert_eq!(a, b, "len {len}");
+                let mut a = vec![0u64; len.div_ceil(64)];
+                let mut b = vec![0u64; len.div_ceil(64)];
+                eq_u8_masks(src.as_ptr(), len, 7, a.as_mut_ptr(), true);
+                eq_u8_masks_u64_impl::<16>(&src, 7, &mut b);
+                assert_eq!(a, b, "len {len}");
+                eq_u8_masks_u64(&src, 7, &mut a);
+                assert_eq!(a, b, "len {len}");
+            }
+        }
+    }
+}
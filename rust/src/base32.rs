@@ -0,0 +1,328 @@
+//! Base32 kernels (RFC 4648 standard and extended-hex alphabets).
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use core::simd::prelude::{SimdPartialOrd, SimdUint};
+use core::simd::{Simd, ToBytes};
+
+/// Characters handled per SIMD step: 8 groups of 5 bytes ↔ 8 characters.
+const B32_LANES: usize = 64;
+
+/// Input bytes consumed (encoder) or produced (decoder) per SIMD step.
+const B32_BYTES: usize = B32_LANES / 8 * 5;
+
+/// Each RFC 4648 base32 alphabet is two contiguous ASCII ranges; values
+/// `0..split` map to `base[0] + v` and the rest to `base[1] + v`.
+struct Alphabet {
+    chars: &'static [u8; 32],
+    split: u8,
+    base: [u8; 2],
+    /// ASCII → 5-bit value, `INVALID` for bytes outside the alphabet.
+    decode: [u8; 256],
+}
+
+const INVALID: u8 = 0xFF;
+
+const fn decode_table(chars: &[u8; 32]) -> [u8; 256] {
+    let mut t = [INVALID; 256];
+    let mut i = 0;
+    while i < 32 {
+        t[chars[i] as usize] = i as u8;
+        i += 1;
+    }
+    t
+}
+
+const STD: Alphabet = Alphabet {
+    chars: b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+    split: 26,
+    base: [b'A', b'2' - 26],
+    decode: decode_table(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567"),
+};
+
+const HEX: Alphabet = Alphabet {
+    chars: b"0123456789ABCDEFGHIJKLMNOPQRSTUV",
+    split: 10,
+    base: [b'0', b'A' - 10],
+    decode: decode_table(b"0123456789ABCDEFGHIJKLMNOPQRSTUV"),
+};
+
+// === Encoder =================================================================
+
+/// Encode `src` into `dst` (which must hold `8 * src.len().div_ceil(5)`
+/// bytes), padding the last group with `=`.  Returns bytes written.
+#[inline(always)]
+unsafe fn base32_encode_impl(src: &[u8], dst: &mut [u8], abc: &Alphabet) -> usize {
+    // Character 8g+j covers bits 5j..5j+5 of group g, which lie in the
+    // big-endian byte pair starting at 5g + 5j/8: shift that pair right by
+    // 11 - 5j%8 and keep five bits.
+    let ia = Simd::<u8, B32_LANES>::from_array(core::array::from_fn(|k| {
+        (5 * (k / 8) + 5 * (k % 8) / 8) as u8
+    }));
+    let ib = ia + Simd::splat(1);
+    let sr =
+        Simd::<u16, B32_LANES>::from_array(core::array::from_fn(|k| 11 - (5 * (k % 8) % 8) as u16));
+    let split = Simd::splat(abc.split);
+    let (lo, hi) = (Simd::splat(abc.base[0]), Simd::splat(abc.base[1]));
+
+    let (mut i, mut o) = (0, 0);
+    // Each step reads a full vector but only consumes 5/8 of it.
+    while i + B32_LANES <= src.len() {
+        let v = Simd::<u8, B32_LANES>::from_slice(&src[i..i + B32_LANES]);
+        let pair = v.swizzle_dyn(ia).cast::<u16>() << 8 | v.swizzle_dyn(ib).cast::<u16>();
+        let idx = ((pair >> sr) & Simd::splat(31)).cast::<u8>();
+        let ascii = idx + idx.simd_lt(split).select(lo, hi);
+        ascii.copy_to_slice(&mut dst[o..o + B32_LANES]);
+        i += B32_BYTES;
+        o += B32_LANES;
+    }
+
+    for g in src[i..].chunks(5) {
+        let mut bytes = [0u8; 8];
+        bytes[3..3 + g.len()].copy_from_slice(g);
+        let v = u64::from_be_bytes(bytes);
+        // Characters needed to carry every input bit of the group.
+        let used = (8 * g.len()).div_ceil(5);
+        for (k, d) in dst[o..o + 8].iter_mut().enumerate() {
+            *d = if k < used {
+                abc.chars[(v >> (35 - 5 * k)) as usize & 31]
+            } else {
+                b'='
+            };
+        }
+        o += 8;
+    }
+    o
+}
+
+/// Return the padded base32 length of `len` input bytes (`8 * ceil(len / 5)`),
+/// i.e. the `dst` capacity required by the `base32_encode_*` kernels.
+#[unsafe(export_name = c_symbol!("base32_encoded_len"))]
+pub extern "C" fn base32_encoded_len(len: usize) -> usize {
+    len.div_ceil(5) * 8
+}
+
+macro_rules! export_base32_encode {
+    ($name:ident, $abc:expr, $desc:literal) => {
+        #[doc = concat!(
+            "Base32-encode `len` bytes with the ", $desc, " alphabet (RFC 4648, `=`-padded). Returns the number of bytes written, `base32_encoded_len(len)`.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `base32_encoded_len(len)` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) -> usize {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return 0;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, base32_encoded_len(len));
+            base32_encode_impl(src, dst, &$abc)
+        }
+    };
+}
+export_base32_encode!(base32_encode_std, STD, "standard (`A-Z2-7`)");
+export_base32_encode!(base32_encode_hex, HEX, "extended-hex (`0-9A-V`)");
+
+// === Decoder =================================================================
+
+/// Decode `src` (optionally `=`-padded) into `dst`, which must hold
+/// [`base32_decoded_len`] bytes.  Returns bytes written, or the offset of the
+/// first invalid character.
+#[inline(always)]
+unsafe fn base32_decode_impl(src: &[u8], dst: &mut [u8], abc: &Alphabet) -> Result<usize, usize> {
+    let pad = src.iter().rev().take(6).take_while(|&&c| c == b'=').count();
+    if pad > 0 && !src.len().is_multiple_of(8) {
+        return Err(src.len() - pad);
+    }
+    let body = &src[..src.len() - pad];
+
+    let in_range = |c: Simd<u8, B32_LANES>, base: u8, n: u8| {
+        let v = c - Simd::splat(base);
+        (v, v.simd_lt(Simd::splat(n)))
+    };
+    let (n0, n1) = (abc.split, 32 - abc.split);
+    // Output byte 5g+j is byte 3+j of the big-endian 40-bit group g held in
+    // u64 lane g.
+    let out = Simd::<u8, B32_LANES>::from_array(core::array::from_fn(|k| {
+        if k < B32_BYTES {
+            (8 * (k / 5) + 3 + k % 5) as u8
+        } else {
+            0
+        }
+    }));
+
+    let (mut i, mut o) = (0, 0);
+    while i + B32_LANES <= body.len() {
+        let c = Simd::<u8, B32_LANES>::from_slice(&body[i..i + B32_LANES]);
+        let (v0, ok0) = in_range(c, abc.base[0], n0);
+        let (v1, ok1) = in_range(c, abc.base[1].wrapping_add(n0), n1);
+        let bad = !(ok0 | ok1);
+        if bad.any() {
+            return Err(i + bad.to_bitmask().trailing_zeros() as usize);
+        }
+        let v = ok0.select(v0, v1 + Simd::splat(n0));
+        // Merge neighbours pairwise: 2 x 5 → 10, 2 x 10 → 20, 2 x 20 → 40 bits.
+        let w = Simd::<u16, 32>::from_le_bytes(v);
+        let w = (w & Simd::splat(0xFF)) << 5 | w >> 8;
+        let w = Simd::<u32, 16>::from_le_bytes(w.to_le_bytes());
+        let w = (w & Simd::splat(0xFFFF)) << 10 | w >> 16;
+        let w = Simd::<u64, 8>::from_le_bytes(w.to_le_bytes());
+        let w = (w & Simd::splat(0xFFFF_FFFF)) << 20 | w >> 32;
+        let bytes = w.to_be_bytes().swizzle_dyn(out);
+        dst[o..o + B32_BYTES].copy_from_slice(&bytes.as_array()[..B32_BYTES]);
+        i += B32_LANES;
+        o += B32_BYTES;
+    }
+
+    let mut acc = 0u64;
+    let mut n = 0;
+    for (k, &c) in body[i..].iter().enumerate() {
+        let v = abc.decode[c as usize];
+        if v == INVALID {
+            return Err(i + k);
+        }
+        acc = acc << 5 | v as u64;
+        n += 1;
+        if n == 8 {
+            dst[o..o + 5].copy_from_slice(&acc.to_be_bytes()[3..]);
+            o += 5;
+            (acc, n) = (0, 0);
+        }
+    }
+    match n {
+        0 => {}
+        // 1, 3 and 6 characters never end a valid encoding: they carry
+        // fewer bits than one more byte would need, or bits left over.
+        1 | 3 | 6 => return Err(body.len() - 1),
+        _ => {
+            let bytes = (acc << (5 * (8 - n))).to_be_bytes();
+            dst[o..o + 5 * n / 8].copy_from_slice(&bytes[3..3 + 5 * n / 8]);
+            o += 5 * n / 8;
+        }
+    }
+    Ok(o)
+}
+
+/// Return the `dst` capacity required by the `base32_decode_*` kernels for
+/// `len` input characters: `5 * len / 8` rounded down to whole bytes.  This is
+/// exact for unpadded input and an upper bound for padded input.
+#[unsafe(export_name = c_symbol!("base32_decoded_len"))]
+pub extern "C" fn base32_decoded_len(len: usize) -> usize {
+    len / 8 * 5 + len % 8 * 5 / 8
+}
+
+macro_rules! export_base32_decode {
+    ($name:ident, $abc:expr, $desc:literal) => {
+        #[doc = concat!(
+            "Base32-decode `len` characters of the ", $desc, " alphabet (case-sensitive); trailing `=` padding is optional but, when present, the input length must be a multiple of 8. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `base32_decoded_len(len)` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) -> isize {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return 0;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, base32_decoded_len(len));
+            match base32_decode_impl(src, dst, &$abc) {
+                Ok(n) => n as isize,
+                Err(at) => {
+                    fail(
+                        SIMBA_ERR_INVALID_INPUT,
+                        format_args!("invalid base32 character at offset {at}"),
+                    );
+                    -(at as isize) - 1
+                }
+            }
+        }
+    };
+}
+export_base32_decode!(base32_decode_std, STD, "standard (`A-Z2-7`)");
+export_base32_decode!(base32_decode_hex, HEX, "extended-hex (`0-9A-V`)");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(src: &[u8], hex: bool) -> Vec<u8> {
+        let mut dst = vec![0u8; base32_encoded_len(src.len())];
+        let f = if hex {
+            base32_encode_hex
+        } else {
+            base32_encode_std
+        };
+        let n = unsafe { f(src.as_ptr(), src.len(), dst.as_mut_ptr()) };
+        dst.truncate(n);
+        dst
+    }
+
+    fn decode(src: &[u8], hex: bool) -> Result<Vec<u8>, usize> {
+        let mut dst = vec![0u8; base32_decoded_len(src.len())];
+        let f = if hex {
+            base32_decode_hex
+        } else {
+            base32_decode_std
+        };
+        let n = unsafe { f(src.as_ptr(), src.len(), dst.as_mut_ptr()) };
+        if n < 0 {
+            return Err((-n - 1) as usize);
+        }
+        dst.truncate(n as usize);
+        Ok(dst)
+    }
+
+    #[test]
+    fn test_base32_known() {
+        // RFC 4648 §10 test vectors.
+        let vectors: [(&[u8], &[u8], &[u8]); 7] = [
+            (b"", b"", b""),
+            (b"f", b"MY======", b"CO======"),
+            (b"fo", b"MZXQ====", b"CPNG===="),
+            (b"foo", b"MZXW6===", b"CPNMU==="),
+            (b"foob", b"MZXW6YQ=", b"CPNMUOG="),
+            (b"fooba", b"MZXW6YTB", b"CPNMUOJ1"),
+            (b"foobar", b"MZXW6YTBOI======", b"CPNMUOJ1E8======"),
+        ];
+        for (raw, std, hex) in vectors {
+            assert_eq!(encode(raw, false), std);
+            assert_eq!(encode(raw, true), hex);
+            assert_eq!(decode(std, false).unwrap(), raw);
+            assert_eq!(decode(hex, true).unwrap(), raw);
+            let unpadded: Vec<u8> = std.iter().copied().filter(|&c| c != b'=').collect();
+            assert_eq!(decode(&unpadded, false).unwrap(), raw);
+        }
+        assert_eq!(decode(b"mzxw6===", false), Err(0));
+        assert_eq!(decode(b"MZXW6YQ", false).unwrap(), b"foob");
+        assert_eq!(decode(b"MZX", false), Err(2));
+        assert_eq!(decode(b"MZXW6==", false), Err(5));
+        assert_eq!(decode(b"MZ1W6===", false), Err(2));
+    }
+
+    #[test]
+    fn test_base32_roundtrip_and_errors() {
+        let src: Vec<u8> = (0..1000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        for len in [1, 4, 5, 39, 40, 41, 63, 64, 65, 80, 121, 1000] {
+            for (abc, hex) in [(&STD, false), (&HEX, true)] {
+                let enc = encode(&src[..len], hex);
+                // Scalar reference: one character per 5 bits, MSB first.
+                let bits = 8 * len;
+                for (k, &c) in enc.iter().enumerate().take(bits.div_ceil(5)) {
+                    let v = (0..5).fold(0usize, |v, b| {
+                        let bit = 5 * k + b;
+                        let x = bit < bits && src[bit / 8] >> (7 - bit % 8) & 1 == 1;
+                        v << 1 | x as usize
+                    });
+                    assert_eq!(c, abc.chars[v], "len={len} k={k}");
+                }
+                assert_eq!(decode(&enc, hex).unwrap(), &src[..len], "len={len}");
+                let unpadded: Vec<u8> = enc.iter().copied().filter(|&c| c != b'=').collect();
+                for at in [0, unpadded.len() / 2, unpadded.len() - 1] {
+                    let mut bad = unpadded.clone();
+                    bad[at] = b'*';
+                    assert_eq!(decode(&bad, hex), Err(at), "len={len} at={at}");
+                }
+            }
+        }
+    }
+}
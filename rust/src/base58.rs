@@ -0,0 +1,296 @@
+//! Base58 (Bitcoin alphabet) and Base58Check.  Base58 is a big-number base
+//! conversion, so there is no data-parallel kernel; instead the conversion
+//! works on limbs of `58^5` (encode) or `2^32` (decode) and uses `dst` itself
+//! as the limb scratch, cutting the quadratic inner loop about twentyfold
+//! against the digit-at-a-time textbook version.
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use crate::sha256::Sha256;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const INVALID: u8 = 0xFF;
+
+const DECODE: [u8; 256] = {
+    let mut t = [INVALID; 256];
+    let mut i = 0;
+    while i < 58 {
+        t[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    t
+};
+
+/// Five base58 digits per encoder limb; `58^5 < 2^32`.
+const LIMB_58: u64 = 58 * 58 * 58 * 58 * 58;
+
+/// Base58Check appends the first four bytes of `sha256(sha256(payload))`.
+const CHECK_LEN: usize = 4;
+
+#[inline(always)]
+fn limb(buf: &[u8], k: usize) -> u32 {
+    u32::from_le_bytes(buf[4 * k..4 * k + 4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn set_limb(buf: &mut [u8], k: usize, v: u32) {
+    buf[4 * k..4 * k + 4].copy_from_slice(&v.to_le_bytes());
+}
+
+/// `limbs = limbs * mul + add` over the first `*n` little-endian limbs of
+/// `buf` in base `radix`, growing `*n` as the carry spills.
+#[inline(always)]
+fn mul_add(buf: &mut [u8], n: &mut usize, radix: u64, mul: u64, add: u64) {
+    let mut carry = add;
+    for k in 0..*n {
+        carry += limb(buf, k) as u64 * mul;
+        set_limb(buf, k, (carry % radix) as u32);
+        carry /= radix;
+    }
+    while carry > 0 {
+        set_limb(buf, *n, (carry % radix) as u32);
+        carry /= radix;
+        *n += 1;
+    }
+}
+
+// === Encoder =================================================================
+
+/// Encode the bytes yielded by `src` into `dst`, which must hold
+/// [`base58_encoded_max_len`] bytes for that many input bytes.
+fn base58_encode_impl(src: impl Iterator<Item = u8> + Clone, dst: &mut [u8]) -> usize {
+    // Each leading zero byte becomes a literal '1'.
+    let zeros = src.clone().take_while(|&b| b == 0).count();
+    let scratch = &mut dst[zeros..];
+    let mut n = 0;
+    let (mut acc, mut m) = (0u64, 0);
+    for b in src.skip(zeros) {
+        (acc, m) = (acc << 8 | b as u64, m + 1);
+        if m == 4 {
+            mul_add(scratch, &mut n, LIMB_58, 1 << 32, acc);
+            (acc, m) = (0, 0);
+        }
+    }
+    if m > 0 {
+        mul_add(scratch, &mut n, LIMB_58, 1 << (8 * m), acc);
+    }
+    // Spread each limb into five little-endian digits, top limb first so the
+    // wider digit run never overwrites a limb that is still unread.
+    for k in (0..n).rev() {
+        let mut v = limb(scratch, k);
+        for d in &mut scratch[5 * k..5 * k + 5] {
+            *d = (v % 58) as u8;
+            v /= 58;
+        }
+    }
+    let digits = &mut scratch[..5 * n];
+    digits.reverse();
+    let skip = digits.iter().take_while(|&&d| d == 0).count();
+    let len = digits.len() - skip;
+    digits.copy_within(skip.., 0);
+    for d in &mut digits[..len] {
+        *d = ALPHABET[*d as usize];
+    }
+    dst[..zeros].fill(b'1');
+    zeros + len
+}
+
+/// Return the `dst` capacity required by [`base58_encode`] for `len` input
+/// bytes (`len + 4` for [`base58check_encode`]).  This bounds the output and
+/// the limb scratch kept in `dst`, and exceeds the true maximum
+/// (`ceil(len * log(256) / log(58))`) by at most 1% plus five bytes.
+#[unsafe(export_name = c_symbol!("base58_encoded_max_len"))]
+pub extern "C" fn base58_encoded_max_len(len: usize) -> usize {
+    len * 40 / 29 + 5
+}
+
+/// Base58-encode `len` bytes with the Bitcoin alphabet; each leading zero byte
+/// becomes a leading `1`.  Returns the number of bytes written.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `base58_encoded_max_len(len)` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("base58_encode"))]
+pub unsafe extern "C" fn base58_encode(src: *const u8, len: usize, dst: *mut u8) -> usize {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return 0;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, base58_encoded_max_len(len));
+    base58_encode_impl(src.iter().copied(), dst)
+}
+
+/// Base58Check-encode `len` payload bytes: base58 of the payload followed by
+/// the first four bytes of its double SHA-256.  A version byte, if any, is
+/// part of the payload.  Returns the number of bytes written.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `base58_encoded_max_len(len + 4)` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("base58check_encode"))]
+pub unsafe extern "C" fn base58check_encode(src: *const u8, len: usize, dst: *mut u8) -> usize {
+    if dst.is_null() {
+        return 0;
+    }
+    let src: &[u8] = if src.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(src, len)
+    };
+    let mut h = Sha256::new();
+    h.update(src);
+    let mut h2 = Sha256::new();
+    h2.update(&h.digest());
+    let check = h2.digest();
+    let dst = core::slice::from_raw_parts_mut(dst, base58_encoded_max_len(src.len() + CHECK_LEN));
+    base58_encode_impl(src.iter().chain(&check[..CHECK_LEN]).copied(), dst)
+}
+
+// === Decoder =================================================================
+
+/// Decode `src` into `dst`, which must hold [`base58_decoded_max_len`] bytes.
+/// Returns bytes written, or the offset of the first invalid character.
+fn base58_decode_impl(src: &[u8], dst: &mut [u8]) -> Result<usize, usize> {
+    let zeros = src.iter().take_while(|&&c| c == b'1').count();
+    let scratch = &mut dst[zeros..];
+    let mut n = 0;
+    let (mut acc, mut mul) = (0u64, 1u64);
+    for (i, &c) in src.iter().enumerate().skip(zeros) {
+        let v = DECODE[c as usize];
+        if v == INVALID {
+            return Err(i);
+        }
+        (acc, mul) = (acc * 58 + v as u64, mul * 58);
+        if mul == LIMB_58 {
+            mul_add(scratch, &mut n, 1 << 32, mul, acc);
+            (acc, mul) = (0, 1);
+        }
+    }
+    if mul > 1 {
+        mul_add(scratch, &mut n, 1 << 32, mul, acc);
+    }
+    // Little-endian limbs, least significant first, reversed byte-wise are
+    // the big-endian number.
+    let bytes = &mut scratch[..4 * n];
+    bytes.reverse();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    bytes.copy_within(skip.., 0);
+    dst[..zeros].fill(0);
+    Ok(zeros + 4 * n - skip)
+}
+
+/// Return the `dst` capacity required by [`base58_decode`] for `len` input
+/// characters.  This bounds the output and the limb scratch kept in `dst`.
+#[unsafe(export_name = c_symbol!("base58_decoded_max_len"))]
+pub extern "C" fn base58_decoded_max_len(len: usize) -> usize {
+    len + 4
+}
+
+/// Base58-decode `len` characters of the Bitcoin alphabet; each leading `1`
+/// becomes a leading zero byte.  Returns the number of bytes written on
+/// success, or `-(offset + 1)` where `offset` is the position of the first
+/// invalid character.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `base58_decoded_max_len(len)` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("base58_decode"))]
+pub unsafe extern "C" fn base58_decode(src: *const u8, len: usize, dst: *mut u8) -> isize {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return 0;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, base58_decoded_max_len(len));
+    match base58_decode_impl(src, dst) {
+        Ok(n) => n as isize,
+        Err(at) => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid base58 character at offset {at}"),
+            );
+            -(at as isize) - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(src: &[u8]) -> Vec<u8> {
+        let mut dst = vec![0u8; base58_encoded_max_len(src.len())];
+        let n = unsafe { base58_encode(src.as_ptr(), src.len(), dst.as_mut_ptr()) };
+        dst.truncate(n);
+        dst
+    }
+
+    fn decode(src: &[u8]) -> Result<Vec<u8>, usize> {
+        let mut dst = vec![0u8; base58_decoded_max_len(src.len())];
+        let n = unsafe { base58_decode(src.as_ptr(), src.len(), dst.as_mut_ptr()) };
+        if n < 0 {
+            return Err((-n - 1) as usize);
+        }
+        dst.truncate(n as usize);
+        Ok(dst)
+    }
+
+    /// Digit-at-a-time reference encoder.
+    fn encode_ref(src: &[u8]) -> Vec<u8> {
+        let mut digits: Vec<u8> = Vec::new();
+        for &b in src {
+            let mut carry = b as u32;
+            for d in digits.iter_mut() {
+                carry += (*d as u32) << 8;
+                *d = (carry % 58) as u8;
+                carry /= 58;
+            }
+            while carry > 0 {
+                digits.push((carry % 58) as u8);
+                carry /= 58;
+            }
+        }
+        let zeros = src.iter().take_while(|&&b| b == 0).count();
+        let mut out = vec![b'1'; zeros];
+        out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize]));
+        out
+    }
+
+    #[test]
+    fn test_base58_known() {
+        assert_eq!(encode(b"Hello World!"), b"2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), b"11233QC4");
+        assert_eq!(encode(&[0, 0]), b"11");
+        assert_eq!(decode(b"2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(decode(b"11233QC4").unwrap(), [0, 0, 0x28, 0x7f, 0xb4, 0xcd]);
+        assert_eq!(decode(b"111").unwrap(), [0, 0, 0]);
+        assert_eq!(decode(b"2NEp0"), Err(4));
+        assert_eq!(decode(b"1Il"), Err(1));
+
+        // Version 0 + HASH160 → the familiar P2PKH address.
+        let payload: [u8; 21] = [
+            0x00, 0x01, 0x09, 0x66, 0x77, 0x60, 0x06, 0x95, 0x3D, 0x55, 0x67, 0x43, 0x9E, 0x5E,
+            0x39, 0xF8, 0x6A, 0x0D, 0x27, 0x3B, 0xEE,
+        ];
+        let mut dst = vec![0u8; base58_encoded_max_len(payload.len() + 4)];
+        let n = unsafe { base58check_encode(payload.as_ptr(), payload.len(), dst.as_mut_ptr()) };
+        assert_eq!(&dst[..n], b"16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM");
+    }
+
+    #[test]
+    fn test_base58_roundtrip() {
+        let src: Vec<u8> = (0..300u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        for len in [1, 2, 3, 4, 5, 7, 8, 9, 25, 32, 33, 64, 300] {
+            for zeros in [0, 1, 3] {
+                let mut raw = vec![0u8; zeros];
+                raw.extend_from_slice(&src[..len]);
+                let enc = encode(&raw);
+                assert_eq!(enc, encode_ref(&raw), "len={len} zeros={zeros}");
+                assert!(enc.len() <= base58_encoded_max_len(raw.len()));
+                assert_eq!(decode(&enc).unwrap(), raw, "len={len} zeros={zeros}");
+            }
+            let ff = vec![0xFFu8; len];
+            assert_eq!(decode(&encode(&ff)).unwrap(), ff);
+        }
+    }
+}
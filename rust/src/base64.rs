@@ -0,0 +1,393 @@
+//! Base64 kernels (RFC 4648 standard and URL-safe alphabets).
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Simd, SupportedLaneCount};
+
+/// Output bytes produced per encoder step; consumes `3 / 4` as many input
+/// bytes.  Must be a multiple of 16 so every step holds whole 3→4 groups.
+const B64_LANES: usize = 32;
+
+struct Alphabet {
+    chars: &'static [u8; 64],
+    /// Wrapping offset added to a 6-bit value to reach its ASCII character,
+    /// indexed by the class computed in [`encode_ascii`].
+    offsets: [u8; 16],
+    /// ASCII → 6-bit value, `INVALID` for bytes outside the alphabet.
+    decode: [u8; 256],
+}
+
+const INVALID: u8 = 0xFF;
+
+const fn decode_table(chars: &[u8; 64]) -> [u8; 256] {
+    let mut t = [INVALID; 256];
+    let mut i = 0;
+    while i < 64 {
+        t[chars[i] as usize] = i as u8;
+        i += 1;
+    }
+    t
+}
+
+const fn offsets(c62: u8, c63: u8) -> [u8; 16] {
+    // Classes 1..=10 are the digits 52..=61.
+    let mut t = [b'0'.wrapping_sub(52); 16];
+    t[0] = b'a' - 26;
+    t[11] = c62.wrapping_sub(62);
+    t[12] = c63.wrapping_sub(63);
+    t[13] = b'A';
+    t
+}
+
+const STD: Alphabet = Alphabet {
+    chars: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    offsets: offsets(b'+', b'/'),
+    decode: decode_table(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"),
+};
+
+const URL: Alphabet = Alphabet {
+    chars: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    offsets: offsets(b'-', b'_'),
+    decode: decode_table(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"),
+};
+
+// === Encoder =================================================================
+
+/// Map 6-bit values to ASCII without a 64-entry table (Muła's method): 52..=63
+/// saturate to classes 1..=12, 26..=51 to 0, and 0..=25 are forced to 13;
+/// a 16-entry `swizzle_dyn` then yields the per-class offset.
+#[inline(always)]
+fn encode_ascii<const L: usize>(idx: Simd<u8, L>, lut: Simd<u8, L>) -> Simd<u8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let class = idx.saturating_sub(Simd::splat(51));
+    let class = idx.simd_lt(Simd::splat(26)).select(Simd::splat(13), class);
+    idx + lut.swizzle_dyn(class)
+}
+
+/// Encode `src` into `dst` (which must hold `4 * src.len().div_ceil(3)`
+/// bytes), padding the last group with `=`.  Returns bytes written.
+#[inline(always)]
+unsafe fn base64_encode_impl<const L: usize>(src: &[u8], dst: &mut [u8], abc: &Alphabet) -> usize
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let lut = Simd::<u8, L>::from_array(core::array::from_fn(|i| abc.offsets[i % 16]));
+    // Output lane 4i+j of each step is built from input bytes of group i:
+    //   j=0: b0 >> 2             j=1: (b0 & 3) << 4 | b1 >> 4
+    //   j=2: (b1 & 15) << 2 | b2 >> 6    j=3: b2 & 63
+    // as ((A << sl) & ma) | ((B >> sr) & mb) with per-lane shifts and masks.
+    let lane = |t: [u8; 4]| Simd::<u8, L>::from_array(core::array::from_fn(|i| t[i % 4]));
+    let group = |t: [u8; 4]| {
+        Simd::<u8, L>::from_array(core::array::from_fn(|i| (3 * (i / 4)) as u8 + t[i % 4]))
+    };
+    let (ia, sl, ma) = (
+        group([0, 0, 1, 2]),
+        lane([0, 4, 2, 0]),
+        lane([0, 0x30, 0x3C, 0x3F]),
+    );
+    let (ib, sr, mb) = (
+        group([0, 1, 2, 0]),
+        lane([2, 4, 6, 0]),
+        lane([0x3F, 0x0F, 0x03, 0]),
+    );
+
+    let (mut i, mut o) = (0, 0);
+    // Each step reads a full vector but only consumes 3/4 of it.
+    while i + L <= src.len() {
+        let v = Simd::<u8, L>::from_slice(&src[i..i + L]);
+        let a = v.swizzle_dyn(ia);
+        let b = v.swizzle_dyn(ib);
+        let idx = ((a << sl) & ma) | ((b >> sr) & mb);
+        encode_ascii(idx, lut).copy_to_slice(&mut dst[o..o + L]);
+        i += L / 4 * 3;
+        o += L;
+    }
+
+    let mut groups = src[i..].chunks_exact(3);
+    for g in &mut groups {
+        let v = (g[0] as u32) << 16 | (g[1] as u32) << 8 | g[2] as u32;
+        for (k, d) in dst[o..o + 4].iter_mut().enumerate() {
+            *d = abc.chars[(v >> (18 - 6 * k)) as usize & 63];
+        }
+        o += 4;
+    }
+    let rem = groups.remainder();
+    if !rem.is_empty() {
+        let v = (rem[0] as u32) << 16 | rem.get(1).map_or(0, |&b| (b as u32) << 8);
+        for (k, d) in dst[o..o + 4].iter_mut().enumerate() {
+            *d = if k <= rem.len() {
+                abc.chars[(v >> (18 - 6 * k)) as usize & 63]
+            } else {
+                b'='
+            };
+        }
+        o += 4;
+    }
+    o
+}
+
+/// Return the padded base64 length of `len` input bytes (`4 * ceil(len / 3)`),
+/// i.e. the `dst` capacity required by the `base64_encode_*` kernels.
+#[unsafe(export_name = c_symbol!("base64_encoded_len"))]
+pub extern "C" fn base64_encoded_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+macro_rules! export_base64_encode {
+    ($name:ident, $abc:expr, $desc:literal) => {
+        #[doc = concat!(
+            "Base64-encode `len` bytes with the ", $desc, " alphabet (RFC 4648, `=`-padded) using a shuffle-based SIMD kernel. Returns the number of bytes written, `base64_encoded_len(len)`.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `base64_encoded_len(len)` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) -> usize {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return 0;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, base64_encoded_len(len));
+            base64_encode_impl::<B64_LANES>(src, dst, &$abc)
+        }
+    };
+}
+export_base64_encode!(base64_encode_std, STD, "standard (`+/`)");
+export_base64_encode!(base64_encode_url, URL, "URL-safe (`-_`)");
+
+// === Decoder =================================================================
+
+/// Map ASCII to 6-bit values by range; lanes outside the alphabet are set in
+/// the returned mask.
+#[inline(always)]
+fn decode_ascii<const L: usize>(
+    c: Simd<u8, L>,
+    c62: u8,
+    c63: u8,
+) -> (Simd<u8, L>, core::simd::Mask<i8, L>)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let in_range = |lo: u8, hi: u8| c.simd_ge(Simd::splat(lo)) & c.simd_le(Simd::splat(hi));
+    let upper = in_range(b'A', b'Z');
+    let lower = in_range(b'a', b'z');
+    let digit = in_range(b'0', b'9');
+    let is62 = c.simd_eq(Simd::splat(c62));
+    let is63 = c.simd_eq(Simd::splat(c63));
+    let v = upper.select(c - Simd::splat(b'A'), Simd::splat(0));
+    let v = lower.select(c - Simd::splat(b'a' - 26), v);
+    let v = digit.select(c + Simd::splat(52 - b'0'), v);
+    let v = is62.select(Simd::splat(62), v);
+    let v = is63.select(Simd::splat(63), v);
+    (v, !(upper | lower | digit | is62 | is63))
+}
+
+/// Decode `src` (optionally `=`-padded) into `dst`, which must hold
+/// [`base64_decoded_len`] bytes.  Returns bytes written, or the offset of the
+/// first invalid character.
+#[inline(always)]
+unsafe fn base64_decode_impl<const L: usize>(
+    src: &[u8],
+    dst: &mut [u8],
+    abc: &Alphabet,
+) -> Result<usize, usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let pad = src.iter().rev().take(2).take_while(|&&c| c == b'=').count();
+    if pad > 0 && !src.len().is_multiple_of(4) {
+        return Err(src.len() - pad);
+    }
+    let body = &src[..src.len() - pad];
+
+    // Output byte 3i+j of each step is (s[4i+j] << sl) | (s[4i+j+1] >> sr);
+    // the u8 shift left drops the bits that belong to the previous byte.
+    let out_lanes = L / 4 * 3;
+    let ia = Simd::<u8, L>::from_array(core::array::from_fn(|k| {
+        if k < out_lanes {
+            (4 * (k / 3) + k % 3) as u8
+        } else {
+            0
+        }
+    }));
+    let ib = ia + Simd::splat(1);
+    let sl = Simd::<u8, L>::from_array(core::array::from_fn(|k| [2, 4, 6][k % 3]));
+    let sr = Simd::<u8, L>::from_array(core::array::from_fn(|k| [4, 2, 0][k % 3]));
+    let (c62, c63) = (abc.chars[62], abc.chars[63]);
+
+    let (mut i, mut o) = (0, 0);
+    while i + L <= body.len() {
+        let (v, bad) = decode_ascii(Simd::<u8, L>::from_slice(&body[i..i + L]), c62, c63);
+        if bad.any() {
+            return Err(i + bad.to_bitmask().trailing_zeros() as usize);
+        }
+        let bytes = (v.swizzle_dyn(ia) << sl) | (v.swizzle_dyn(ib) >> sr);
+        dst[o..o + out_lanes].copy_from_slice(&bytes.as_array()[..out_lanes]);
+        i += L;
+        o += out_lanes;
+    }
+
+    let mut acc = 0u32;
+    let mut n = 0;
+    for (k, &c) in body[i..].iter().enumerate() {
+        let v = abc.decode[c as usize];
+        if v == INVALID {
+            return Err(i + k);
+        }
+        acc = acc << 6 | v as u32;
+        n += 1;
+        if n == 4 {
+            dst[o..o + 3].copy_from_slice(&acc.to_be_bytes()[1..]);
+            o += 3;
+            (acc, n) = (0, 0);
+        }
+    }
+    match n {
+        0 => {}
+        // A lone trailing character cannot encode a whole byte.
+        1 => return Err(body.len() - 1),
+        _ => {
+            let bytes = (acc << (6 * (4 - n))).to_be_bytes();
+            dst[o..o + n - 1].copy_from_slice(&bytes[1..n]);
+            o += n - 1;
+        }
+    }
+    Ok(o)
+}
+
+/// Return the `dst` capacity required by the `base64_decode_*` kernels for
+/// `len` input characters: `3 * len / 4` rounded down to whole bytes.  This is
+/// exact for unpadded input and an upper bound for padded input.
+#[unsafe(export_name = c_symbol!("base64_decoded_len"))]
+pub extern "C" fn base64_decoded_len(len: usize) -> usize {
+    len / 4 * 3 + len % 4 * 3 / 4
+}
+
+macro_rules! export_base64_decode {
+    ($name:ident, $abc:expr, $desc:literal) => {
+        #[doc = concat!(
+            "Base64-decode `len` characters of the ", $desc, " alphabet; trailing `=` padding is optional but, when present, the input length must be a multiple of 4. Returns the number of bytes written on success, or `-(offset + 1)` where `offset` is the position of the first invalid character.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `base64_decoded_len(len)` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) -> isize {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return 0;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, base64_decoded_len(len));
+            match base64_decode_impl::<B64_LANES>(src, dst, &$abc) {
+                Ok(n) => n as isize,
+                Err(at) => {
+                    fail(
+                        SIMBA_ERR_INVALID_INPUT,
+                        format_args!("invalid base64 character at offset {at}"),
+                    );
+                    -(at as isize) - 1
+                }
+            }
+        }
+    };
+}
+export_base64_decode!(base64_decode_std, STD, "standard (`+/`)");
+export_base64_decode!(base64_decode_url, URL, "URL-safe (`-_`)");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_ref(src: &[u8], abc: &Alphabet) -> Vec<u8> {
+        let mut out = Vec::new();
+        for g in src.chunks(3) {
+            let v = g
+                .iter()
+                .enumerate()
+                .fold(0u32, |v, (k, &b)| v | (b as u32) << (16 - 8 * k));
+            for k in 0..4 {
+                out.push(if k <= g.len() {
+                    abc.chars[(v >> (18 - 6 * k)) as usize & 63]
+                } else {
+                    b'='
+                });
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_base64_encode_known() {
+        let mut dst = [0u8; 16];
+        unsafe {
+            let n = base64_encode_std(b"foobar".as_ptr(), 6, dst.as_mut_ptr());
+            assert_eq!(&dst[..n], b"Zm9vYmFy");
+            let n = base64_encode_std(b"fo".as_ptr(), 2, dst.as_mut_ptr());
+            assert_eq!(&dst[..n], b"Zm8=");
+            let n = base64_encode_url([0xFB, 0xFF, 0xBF].as_ptr(), 3, dst.as_mut_ptr());
+            assert_eq!(&dst[..n], b"-_-_");
+        }
+    }
+
+    #[test]
+    fn test_base64_encode_matches_scalar() {
+        let src: Vec<u8> = (0..1000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        for len in [0, 1, 2, 3, 23, 24, 31, 32, 33, 47, 48, 100, 1000] {
+            let mut dst = vec![0u8; base64_encoded_len(len)];
+            let n = unsafe { base64_encode_std(src.as_ptr(), len, dst.as_mut_ptr()) };
+            assert_eq!(&dst[..n], &encode_ref(&src[..len], &STD)[..], "len={}", len);
+            let n = unsafe { base64_encode_url(src.as_ptr(), len, dst.as_mut_ptr()) };
+            assert_eq!(&dst[..n], &encode_ref(&src[..len], &URL)[..], "len={}", len);
+        }
+    }
+
+    fn decode(src: &[u8], url: bool) -> Result<Vec<u8>, usize> {
+        let mut dst = vec![0u8; base64_decoded_len(src.len())];
+        let f = if url {
+            base64_decode_url
+        } else {
+            base64_decode_std
+        };
+        let n = unsafe { f(src.as_ptr(), src.len(), dst.as_mut_ptr()) };
+        if n < 0 {
+            return Err((-n - 1) as usize);
+        }
+        dst.truncate(n as usize);
+        Ok(dst)
+    }
+
+    #[test]
+    fn test_base64_decode_known() {
+        assert_eq!(decode(b"Zm9vYmFy", false).unwrap(), b"foobar");
+        assert_eq!(decode(b"Zm8=", false).unwrap(), b"fo");
+        assert_eq!(decode(b"Zm8", false).unwrap(), b"fo");
+        assert_eq!(decode(b"Zg==", false).unwrap(), b"f");
+        assert_eq!(decode(b"-_-_", true).unwrap(), [0xFB, 0xFF, 0xBF]);
+        assert_eq!(decode(b"-_-_", false), Err(0));
+        assert_eq!(decode(b"Zm=v", false), Err(2));
+        assert_eq!(decode(b"Zm8==", false), Err(3));
+        assert_eq!(decode(b"Z===", false), Err(1));
+        assert_eq!(decode(b"Zm9vY", false), Err(4));
+    }
+
+    #[test]
+    fn test_base64_decode_roundtrip_and_errors() {
+        let src: Vec<u8> = (0..1000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+        for len in [1, 2, 3, 23, 24, 31, 32, 33, 47, 48, 100, 1000] {
+            for (abc, url) in [(&STD, false), (&URL, true)] {
+                let enc = encode_ref(&src[..len], abc);
+                assert_eq!(decode(&enc, url).unwrap(), &src[..len], "len={}", len);
+                let unpadded: Vec<u8> = enc.iter().copied().filter(|&c| c != b'=').collect();
+                assert_eq!(decode(&unpadded, url).unwrap(), &src[..len]);
+                for at in [0, unpadded.len() / 2, unpadded.len() - 1] {
+                    let mut bad = unpadded.clone();
+                    bad[at] = b'*';
+                    assert_eq!(decode(&bad, url), Err(at), "len={} at={}", len, at);
+                }
+            }
+        }
+    }
+}
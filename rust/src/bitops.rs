@@ -0,0 +1,786 @@
+//! Bulk bitwise kernels over byte buffers and u64-word bitsets, and
+//! endianness byte swaps.
+use core::simd::prelude::{SimdPartialEq, SimdUint};
+use core::simd::{LaneCount, Mask, Simd, SupportedLaneCount};
+
+use crate::{load_simd, store_simd};
+
+const BITOPS_LANES: usize = 64;
+/// Words per step for u64 bitset kernels (one 512-bit vector).
+const WORD_LANES: usize = 8;
+
+// === XOR =====================================================================
+
+#[inline(always)]
+unsafe fn xor_u8_impl<const L: usize>(dst: *mut u8, a: *const u8, b: *const u8, len: usize)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= len {
+        let v = load_simd::<u8, L>(a.add(i)) ^ load_simd::<u8, L>(b.add(i));
+        store_simd(dst.add(i), v);
+        i += L;
+    }
+    while i < len {
+        *dst.add(i) = *a.add(i) ^ *b.add(i);
+        i += 1;
+    }
+}
+
+/// Compute `dst[i] = a[i] ^ b[i]` for `len` bytes.
+///
+/// # Safety
+/// All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or
+/// `b` exactly, but must not partially overlap either.
+#[unsafe(export_name = c_symbol!("xor_u8"))]
+pub unsafe extern "C" fn xor_u8(dst: *mut u8, a: *const u8, b: *const u8, len: usize) {
+    if dst.is_null() || a.is_null() || b.is_null() || len == 0 {
+        return;
+    }
+    xor_u8_impl::<BITOPS_LANES>(dst, a, b, len);
+}
+
+/// Compute `dst[i] ^= src[i]` in place for `len` bytes.
+///
+/// # Safety
+/// `dst` and `src` must be valid for `len` bytes and must not partially
+/// overlap.
+#[unsafe(export_name = c_symbol!("xor_u8_inplace"))]
+pub unsafe extern "C" fn xor_u8_inplace(dst: *mut u8, src: *const u8, len: usize) {
+    if dst.is_null() || src.is_null() || len == 0 {
+        return;
+    }
+    xor_u8_impl::<BITOPS_LANES>(dst, dst, src, len);
+}
+
+// === Constant-time equality ==================================================
+
+/// OR of `a[i] ^ b[i]` over all `len` bytes.  Every byte is read and folded
+/// in with no branch on the data, so the result (zero iff equal) carries
+/// the differences of the whole buffer, not just the first one.
+#[inline(always)]
+unsafe fn ct_diff_impl<const L: usize>(a: *const u8, b: *const u8, len: usize) -> u8
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut acc = Simd::<u8, L>::splat(0);
+    let mut i = 0;
+    while i + L <= len {
+        acc |= load_simd::<u8, L>(a.add(i)) ^ load_simd::<u8, L>(b.add(i));
+        i += L;
+    }
+    let mut tail = 0;
+    while i < len {
+        tail |= *a.add(i) ^ *b.add(i);
+        i += 1;
+    }
+    acc.reduce_or() | tail
+}
+
+/// Constant-time equality for secrets (MACs, tokens): returns 1 if the `len`
+/// bytes at `a` and `b` are equal, 0 otherwise.  Unlike `mismatch_index`,
+/// `compare_u8` and the other comparison kernels, this never stops at the
+/// first difference: it always reads all `len` bytes of both buffers, and
+/// neither the loop nor the final 0/1 conversion branches on their
+/// contents, so the running time depends on `len` alone.  Only the lengths
+/// and the pointers' nullness are public.
+///
+/// # Safety
+/// `a` and `b` must be valid for `len` bytes; a null pointer compares
+/// unequal unless `len` is 0.
+#[unsafe(export_name = c_symbol!("ct_eq_u8"))]
+pub unsafe extern "C" fn ct_eq_u8(a: *const u8, b: *const u8, len: usize) -> u8 {
+    if len == 0 {
+        return 1;
+    }
+    if a.is_null() || b.is_null() {
+        return 0;
+    }
+    // Hide the accumulated difference from the optimiser so it cannot
+    // reintroduce an early exit once any bit is set.
+    let diff = core::hint::black_box(ct_diff_impl::<BITOPS_LANES>(a, b, len)) as u32;
+    // 0 -> 1, 1..=255 -> 0: only 0 borrows into the top bit.
+    (diff.wrapping_sub(1) >> 31) as u8
+}
+
+// === Bitsets =================================================================
+
+#[inline(always)]
+unsafe fn bitset_op_impl<const L: usize>(
+    dst: *mut u64,
+    a: *const u64,
+    b: *const u64,
+    n: usize,
+    op: impl Fn(Simd<u64, L>, Simd<u64, L>) -> Simd<u64, L>,
+    op1: impl Fn(u64, u64) -> u64,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= n {
+        let v = op(load_simd(a.add(i)), load_simd(b.add(i)));
+        store_simd(dst.add(i), v);
+        i += L;
+    }
+    while i < n {
+        *dst.add(i) = op1(*a.add(i), *b.add(i));
+        i += 1;
+    }
+}
+
+macro_rules! export_bitset_op {
+    ($name:ident, $desc:literal, |$x:ident, $y:ident| $body:expr) => {
+        #[doc = concat!(
+            "Compute `dst[i] = ", $desc, "` over `nwords` u64 bitset words.\n\n",
+            "# Safety\n",
+            "All pointers must be valid for `nwords` u64 values.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(dst: *mut u64, a: *const u64, b: *const u64, nwords: usize) {
+            if dst.is_null() || a.is_null() || b.is_null() || nwords == 0 {
+                return;
+            }
+            bitset_op_impl::<WORD_LANES>(dst, a, b, nwords, |$x, $y| $body, |$x, $y| $body);
+        }
+    };
+}
+export_bitset_op!(bitset_and, "a[i] & b[i]", |x, y| x & y);
+export_bitset_op!(bitset_or, "a[i] | b[i]", |x, y| x | y);
+export_bitset_op!(bitset_andnot, "a[i] & !b[i]", |x, y| x & !y);
+
+#[inline(always)]
+fn bitset_and_count_impl<const L: usize>(a: &[u64], b: &[u64]) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut acc = Simd::<u64, L>::splat(0);
+    let mut ca = a.chunks_exact(L);
+    let mut cb = b.chunks_exact(L);
+    for (x, y) in (&mut ca).zip(&mut cb) {
+        acc += (Simd::<u64, L>::from_slice(x) & Simd::from_slice(y)).count_ones();
+    }
+    let tail: u32 = ca
+        .remainder()
+        .iter()
+        .zip(cb.remainder())
+        .map(|(x, y)| (x & y).count_ones())
+        .sum();
+    acc.reduce_sum() + tail as u64
+}
+
+/// Return the number of bits set in `a & b` over `nwords` u64 words without
+/// materializing the intersection.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `nwords` u64 values.
+#[unsafe(export_name = c_symbol!("bitset_and_count"))]
+pub unsafe extern "C" fn bitset_and_count(a: *const u64, b: *const u64, nwords: usize) -> u64 {
+    if a.is_null() || b.is_null() || nwords == 0 {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a, nwords);
+    let b = core::slice::from_raw_parts(b, nwords);
+    bitset_and_count_impl::<WORD_LANES>(a, b)
+}
+
+// === Popcount ================================================================
+
+#[inline(always)]
+fn popcount_u8_impl<const L: usize>(data: &[u8]) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut total = 0u64;
+    let mut chunks = data.chunks_exact(L);
+    // Per-lane u8 counters gain at most 8 per step; flush before they wrap.
+    loop {
+        let mut acc = Simd::<u8, L>::splat(0);
+        let mut steps = 0;
+        for chunk in (&mut chunks).take(31) {
+            acc += Simd::<u8, L>::from_slice(chunk).count_ones();
+            steps += 1;
+        }
+        total += acc.cast::<u32>().reduce_sum() as u64;
+        if steps < 31 {
+            break;
+        }
+    }
+    total
+        + chunks
+            .remainder()
+            .iter()
+            .map(|b| b.count_ones() as u64)
+            .sum::<u64>()
+}
+
+/// Return the number of set bits across `len` bytes (e.g. the number of valid
+/// entries in a validity bitmap).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("popcount_u8"))]
+pub unsafe extern "C" fn popcount_u8(ptr: *const u8, len: usize) -> u64 {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    popcount_u8_impl::<BITOPS_LANES>(data)
+}
+
+// === Mask expansion ==========================================================
+
+#[inline(always)]
+fn mask_to_indices_impl(
+    words: impl Iterator<Item = u64>,
+    bits: u64,
+    base: u64,
+    out: &mut [u64],
+) -> usize {
+    let mut n = 0;
+    for (w, mut m) in words.enumerate() {
+        let word_base = base + w as u64 * bits;
+        while m != 0 {
+            let Some(slot) = out.get_mut(n) else {
+                return n;
+            };
+            *slot = word_base + m.trailing_zeros() as u64;
+            n += 1;
+            m &= m - 1;
+        }
+    }
+    n
+}
+
+macro_rules! export_mask_to_indices {
+    ($name:ident, $int:ty) => {
+        #[doc = concat!(
+            "Expand `nwords` `", stringify!($int), "` bitmask words (as produced by the matching `eq_u8_masks*` kernel) into ascending byte offsets: bit `i` of word `w` yields `base + w * ", stringify!($int), "::BITS + i`. Writes at most `cap` offsets (size `out` with `popcount_u8` over the masks) and returns the number written.\n\n",
+            "# Safety\n",
+            "`masks` must be null or valid for `nwords` words; `out` must be valid for `cap` u64 writes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(
+            masks: *const $int,
+            nwords: usize,
+            base: u64,
+            out: *mut u64,
+            cap: usize,
+        ) -> usize {
+            if masks.is_null() || out.is_null() || nwords == 0 || cap == 0 {
+                return 0;
+            }
+            let masks = core::slice::from_raw_parts(masks, nwords);
+            let out = core::slice::from_raw_parts_mut(out, cap);
+            mask_to_indices_impl(masks.iter().map(|&m| m as u64), <$int>::BITS as u64, base, out)
+        }
+    };
+}
+export_mask_to_indices!(mask_to_indices16, u16);
+export_mask_to_indices!(mask_to_indices32, u32);
+export_mask_to_indices!(mask_to_indices64, u64);
+
+// === Compaction ==============================================================
+
+/// Bytes compacted per table lookup.
+const COMPACT_GROUP: usize = 8;
+
+/// For each 8-bit keep mask, the lane indices of the kept bytes packed to the
+/// front (the remaining lanes repeat lane 0; they are overwritten by the next
+/// group or lie past the returned length).
+const COMPACT_SHUFFLE: [[u8; COMPACT_GROUP]; 256] = {
+    let mut t = [[0u8; COMPACT_GROUP]; 256];
+    let mut m = 0;
+    while m < 256 {
+        let (mut bit, mut n) = (0, 0);
+        while bit < COMPACT_GROUP {
+            if m >> bit & 1 == 1 {
+                t[m][n] = bit as u8;
+                n += 1;
+            }
+            bit += 1;
+        }
+        m += 1;
+    }
+    t
+};
+
+/// Copy the bytes of `src[..len]` whose bit is set in `keep(chunk)` (bit `i`
+/// of chunk `c` selects byte `64c + i`) to the front of `dst`, in order.
+/// Each 8-byte group is packed with one shuffle and stored whole; the store
+/// ends at or before the end of the group, so it never clobbers unread input
+/// when `dst == src`.
+#[inline(always)]
+unsafe fn compact_u8_impl(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    mut keep: impl FnMut(usize, Simd<u8, BITOPS_LANES>) -> u64,
+) -> usize {
+    let (mut i, mut o) = (0, 0);
+    while i + BITOPS_LANES <= len {
+        let v = load_simd::<u8, BITOPS_LANES>(src.add(i));
+        let mask = keep(i / BITOPS_LANES, v);
+        for g in 0..BITOPS_LANES / COMPACT_GROUP {
+            let bits = (mask >> (g * COMPACT_GROUP)) as u8;
+            let group = load_simd::<u8, COMPACT_GROUP>(src.add(i + g * COMPACT_GROUP));
+            let idx = Simd::from_array(COMPACT_SHUFFLE[bits as usize]);
+            store_simd(dst.add(o), group.swizzle_dyn(idx));
+            o += bits.count_ones() as usize;
+        }
+        i += BITOPS_LANES;
+    }
+    if i < len {
+        let mask = keep(i / BITOPS_LANES, Simd::splat(0));
+        for k in 0..len - i {
+            if mask >> k & 1 == 1 {
+                *dst.add(o) = *src.add(i + k);
+                o += 1;
+            }
+        }
+    }
+    o
+}
+
+/// Copy the bytes whose entry in the 256-byte `lut` is non-zero from `src` to
+/// `dst`, preserving order, e.g. to strip whitespace.  Returns the number of
+/// bytes written.
+///
+/// # Safety
+/// `src`/`lut` must be valid for `len`/256 bytes; `dst` must be valid for
+/// `len` bytes.  `dst` may alias `src` exactly (in-place), but must not
+/// partially overlap it.
+#[unsafe(export_name = c_symbol!("compact_u8"))]
+pub unsafe extern "C" fn compact_u8(
+    src: *const u8,
+    len: usize,
+    lut: *const u8,
+    dst: *mut u8,
+) -> usize {
+    if src.is_null() || lut.is_null() || dst.is_null() || len == 0 {
+        return 0;
+    }
+    let table = core::slice::from_raw_parts(lut, 256);
+    compact_u8_impl(src, len, dst, |c, v| {
+        let start = c * BITOPS_LANES;
+        if start + BITOPS_LANES <= len {
+            let flags = Simd::<u8, BITOPS_LANES>::gather_or_default(table, v.cast());
+            flags.simd_ne(Simd::splat(0)).to_bitmask()
+        } else {
+            (start..len).fold(0u64, |m, k| {
+                m | ((table[*src.add(k) as usize] != 0) as u64) << (k - start)
+            })
+        }
+    })
+}
+
+/// Copy the bytes of `src` whose bit is set in `masks` to `dst`, preserving
+/// order: bit `i` of word `w` selects byte `64 * w + i`, the layout written by
+/// `classify_u8_masks64` and `eq_u8_masks64_full`.  Returns the number of
+/// bytes written.
+///
+/// # Safety
+/// `src` must be valid for `len` bytes, `masks` for `len.div_ceil(64)` words
+/// and `dst` for `len` bytes.  `dst` may alias `src` exactly (in-place), but
+/// must not partially overlap it.
+#[unsafe(export_name = c_symbol!("compact_u8_masks"))]
+pub unsafe extern "C" fn compact_u8_masks(
+    src: *const u8,
+    len: usize,
+    masks: *const u64,
+    dst: *mut u8,
+) -> usize {
+    if src.is_null() || masks.is_null() || dst.is_null() || len == 0 {
+        return 0;
+    }
+    let masks = core::slice::from_raw_parts(masks, len.div_ceil(BITOPS_LANES));
+    compact_u8_impl(src, len, dst, |c, _| masks[c])
+}
+
+/// For each 8-bit mask, the source lane feeding each set bit: bit `k` takes
+/// the byte at its rank among the set bits (cleared bits read lane 0 and are
+/// replaced by the fill byte).
+const EXPAND_SHUFFLE: [[u8; COMPACT_GROUP]; 256] = {
+    let mut t = [[0u8; COMPACT_GROUP]; 256];
+    let mut m = 0;
+    while m < 256 {
+        let (mut bit, mut n) = (0, 0);
+        while bit < COMPACT_GROUP {
+            if m >> bit & 1 == 1 {
+                t[m][bit] = n;
+                n += 1;
+            }
+            bit += 1;
+        }
+        m += 1;
+    }
+    t
+};
+
+/// Scatter `src` into `nwords * 64` bytes of `dst`, the inverse of
+/// `compact_u8_masks`: where bit `i` of word `w` is set, `dst[64 * w + i]`
+/// takes the next byte of `src`; elsewhere it is `fill`.  Returns the number
+/// of `src` bytes consumed, the total popcount of `masks`.
+///
+/// # Safety
+/// `masks` must be valid for `nwords` words, `src` for as many bytes as
+/// `masks` has set bits, and `dst` for `nwords * 64` bytes; `dst` must not
+/// overlap `src`.
+#[unsafe(export_name = c_symbol!("expand_u8"))]
+pub unsafe extern "C" fn expand_u8(
+    src: *const u8,
+    masks: *const u64,
+    nwords: usize,
+    fill: u8,
+    dst: *mut u8,
+) -> usize {
+    if masks.is_null() || dst.is_null() || nwords == 0 {
+        return 0;
+    }
+    let masks = core::slice::from_raw_parts(masks, nwords);
+    let total: usize = masks.iter().map(|m| m.count_ones() as usize).sum();
+    if total > 0 && src.is_null() {
+        return 0;
+    }
+    let fill = Simd::<u8, COMPACT_GROUP>::splat(fill);
+    let mut s = 0;
+    for (w, &mask) in masks.iter().enumerate() {
+        for g in 0..BITOPS_LANES / COMPACT_GROUP {
+            let bits = (mask >> (g * COMPACT_GROUP)) as u8;
+            let out = dst.add(w * BITOPS_LANES + g * COMPACT_GROUP);
+            // Full 8-byte loads only while they stay inside `src`.
+            let group = if s + COMPACT_GROUP <= total {
+                load_simd::<u8, COMPACT_GROUP>(src.add(s))
+            } else {
+                let mut tail = [0u8; COMPACT_GROUP];
+                core::ptr::copy_nonoverlapping(src.add(s), tail.as_mut_ptr(), total - s);
+                Simd::from_array(tail)
+            };
+            let idx = Simd::from_array(EXPAND_SHUFFLE[bits as usize]);
+            let keep = Mask::<i8, COMPACT_GROUP>::from_bitmask(bits as u64);
+            store_simd(out, keep.select(group.swizzle_dyn(idx), fill));
+            s += bits.count_ones() as usize;
+        }
+    }
+    total
+}
+
+// === Validity bitmaps ========================================================
+
+// Arrow bitmaps are LSB-first: bit `i` is bit `i % 8` of byte `i / 8`.
+
+/// Expand the first `nbits` bits of an LSB-first bitmap (Arrow validity
+/// layout) into one byte per bit, 1 for set and 0 for clear.
+///
+/// # Safety
+/// `bitmap` must be valid for `nbits.div_ceil(8)` bytes and `dst` for `nbits`
+/// bytes.
+#[unsafe(export_name = c_symbol!("unpack_bits_to_bytes"))]
+pub unsafe extern "C" fn unpack_bits_to_bytes(bitmap: *const u8, nbits: usize, dst: *mut u8) {
+    if bitmap.is_null() || dst.is_null() || nbits == 0 {
+        return;
+    }
+    let (one, zero) = (Simd::<u8, BITOPS_LANES>::splat(1), Simd::splat(0));
+    let mut i = 0;
+    while i + BITOPS_LANES <= nbits {
+        let word = u64::from_le_bytes(core::ptr::read_unaligned(
+            bitmap.add(i / 8) as *const [u8; 8]
+        ));
+        store_simd(
+            dst.add(i),
+            Mask::<i8, BITOPS_LANES>::from_bitmask(word).select(one, zero),
+        );
+        i += BITOPS_LANES;
+    }
+    while i < nbits {
+        *dst.add(i) = *bitmap.add(i / 8) >> (i % 8) & 1;
+        i += 1;
+    }
+}
+
+/// Pack `n` bytes into an LSB-first bitmap (Arrow validity layout): bit `i`
+/// is set when `src[i]` is non-zero.  Writes `n.div_ceil(8)` bytes; the
+/// unused high bits of the last byte are zero.
+///
+/// # Safety
+/// `src` must be valid for `n` bytes and `dst` for `n.div_ceil(8)` bytes.
+#[unsafe(export_name = c_symbol!("pack_bytes_to_bits"))]
+pub unsafe extern "C" fn pack_bytes_to_bits(src: *const u8, n: usize, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || n == 0 {
+        return;
+    }
+    let zero = Simd::<u8, BITOPS_LANES>::splat(0);
+    let mut i = 0;
+    while i + BITOPS_LANES <= n {
+        let word = load_simd::<u8, BITOPS_LANES>(src.add(i))
+            .simd_ne(zero)
+            .to_bitmask();
+        core::ptr::write_unaligned(dst.add(i / 8) as *mut [u8; 8], word.to_le_bytes());
+        i += BITOPS_LANES;
+    }
+    let tail = (i..n).fold(0u64, |m, k| m | ((*src.add(k) != 0) as u64) << (k - i));
+    let bytes = tail.to_le_bytes();
+    core::ptr::copy_nonoverlapping(bytes.as_ptr(), dst.add(i / 8), (n - i).div_ceil(8));
+}
+
+// === Byte swap ===============================================================
+
+macro_rules! export_bswap {
+    ($name:ident, $t:ty, $lanes:expr) => {
+        #[doc = concat!(
+            "Reverse the byte order of `count` ", stringify!($t), " elements (", stringify!($lanes), " per SIMD step), e.g. big-endian wire data to host order. The buffers need no alignment.\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `count * size_of::<", stringify!($t), ">()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, count: usize, dst: *mut u8) {
+            if src.is_null() || dst.is_null() || count == 0 {
+                return;
+            }
+            let (src, dst) = (src as *const $t, dst as *mut $t);
+            let mut i = 0;
+            while i + $lanes <= count {
+                store_simd(dst.add(i), load_simd::<$t, $lanes>(src.add(i)).swap_bytes());
+                i += $lanes;
+            }
+            while i < count {
+                dst.add(i).write_unaligned(src.add(i).read_unaligned().swap_bytes());
+                i += 1;
+            }
+        }
+    };
+}
+export_bswap!(bswap16_buf, u16, 32);
+export_bswap!(bswap32_buf, u32, 16);
+export_bswap!(bswap64_buf, u64, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xor_u8() {
+        let a: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        let b: Vec<u8> = (0..300u32).map(|i| (i * 13 + 5) as u8).collect();
+        let want: Vec<u8> = a.iter().zip(&b).map(|(x, y)| x ^ y).collect();
+        for len in [0, 1, 63, 64, 65, 300] {
+            let mut dst = vec![0u8; len];
+            unsafe { xor_u8(dst.as_mut_ptr(), a.as_ptr(), b.as_ptr(), len) };
+            assert_eq!(dst, &want[..len]);
+            let mut acc = a[..len].to_vec();
+            unsafe { xor_u8_inplace(acc.as_mut_ptr(), b.as_ptr(), len) };
+            assert_eq!(acc, &want[..len]);
+            // XOR-ing twice restores the original (parity reconstruction).
+            unsafe { xor_u8_inplace(acc.as_mut_ptr(), b.as_ptr(), len) };
+            assert_eq!(acc, &a[..len]);
+        }
+    }
+
+    #[test]
+    fn test_ct_eq_u8() {
+        let a: Vec<u8> = (0..300u32).map(|i| (i * 13) as u8).collect();
+        for len in [1usize, 63, 64, 65, 300] {
+            let mut b = a.clone();
+            assert_eq!(unsafe { ct_eq_u8(a.as_ptr(), b.as_ptr(), len) }, 1);
+            for at in [0, len / 2, len - 1] {
+                b[at] ^= 0x40;
+                assert_eq!(unsafe { ct_eq_u8(a.as_ptr(), b.as_ptr(), len) }, 0);
+                b[at] ^= 0x40;
+            }
+            // No short-circuit: a difference in the first byte still lets
+            // the last byte's difference reach the accumulator.
+            b[0] ^= 0x01;
+            b[len - 1] ^= 0x80;
+            assert_eq!(
+                unsafe { ct_diff_impl::<64>(a.as_ptr(), b.as_ptr(), len) },
+                0x81
+            );
+            assert_eq!(
+                unsafe { ct_diff_impl::<16>(a.as_ptr(), b.as_ptr(), len) },
+                0x81
+            );
+        }
+        assert_eq!(
+            unsafe { ct_eq_u8(core::ptr::null(), core::ptr::null(), 0) },
+            1
+        );
+        assert_eq!(unsafe { ct_eq_u8(a.as_ptr(), core::ptr::null(), 1) }, 0);
+    }
+
+    #[test]
+    fn test_bitset_ops() {
+        let a: Vec<u64> = (0..37u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        let b: Vec<u64> = (0..37u64)
+            .map(|i| (i + 11).wrapping_mul(0xBF58_476D_1CE4_E5B9))
+            .collect();
+        for n in [0, 1, 7, 8, 9, 37] {
+            let mut dst = vec![0u64; n];
+            unsafe {
+                bitset_and(dst.as_mut_ptr(), a.as_ptr(), b.as_ptr(), n);
+                assert!(dst.iter().zip(&a).zip(&b).all(|((d, x), y)| *d == x & y));
+                bitset_or(dst.as_mut_ptr(), a.as_ptr(), b.as_ptr(), n);
+                assert!(dst.iter().zip(&a).zip(&b).all(|((d, x), y)| *d == x | y));
+                bitset_andnot(dst.as_mut_ptr(), a.as_ptr(), b.as_ptr(), n);
+                assert!(dst.iter().zip(&a).zip(&b).all(|((d, x), y)| *d == x & !y));
+                let want: u64 = a[..n]
+                    .iter()
+                    .zip(&b)
+                    .map(|(x, y)| (x & y).count_ones() as u64)
+                    .sum();
+                assert_eq!(bitset_and_count(a.as_ptr(), b.as_ptr(), n), want);
+            }
+        }
+        // In place: a &= b.
+        let mut c = a.clone();
+        let p = c.as_mut_ptr();
+        unsafe { bitset_and(p, p, b.as_ptr(), c.len()) };
+        assert!(c.iter().zip(&a).zip(&b).all(|((d, x), y)| *d == x & y));
+    }
+
+    #[test]
+    fn test_popcount_u8() {
+        let data: Vec<u8> = (0..5000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 9) as u8)
+            .collect();
+        for len in [0, 1, 63, 64, 65, 31 * 64, 31 * 64 + 1, 5000] {
+            let want: u64 = data[..len].iter().map(|b| b.count_ones() as u64).sum();
+            assert_eq!(unsafe { popcount_u8(data.as_ptr(), len) }, want);
+        }
+        let ones = vec![0xFFu8; 4000];
+        assert_eq!(unsafe { popcount_u8(ones.as_ptr(), ones.len()) }, 32_000);
+    }
+
+    #[test]
+    fn test_mask_to_indices() {
+        let masks: [u64; 3] = [0b1001, 0, 1 << 63 | 1];
+        let mut out = [0u64; 8];
+        unsafe {
+            assert_eq!(
+                mask_to_indices64(masks.as_ptr(), 3, 1000, out.as_mut_ptr(), 8),
+                4
+            );
+            assert_eq!(out[..4], [1000, 1003, 1128, 1191]);
+            // Truncated at `cap`.
+            assert_eq!(
+                mask_to_indices64(masks.as_ptr(), 3, 0, out.as_mut_ptr(), 3),
+                3
+            );
+            assert_eq!(out[..3], [0, 3, 128]);
+            let m16: [u16; 2] = [1 << 15, 0b11];
+            assert_eq!(
+                mask_to_indices16(m16.as_ptr(), 2, 0, out.as_mut_ptr(), 8),
+                3
+            );
+            assert_eq!(out[..3], [15, 16, 17]);
+        }
+    }
+
+    #[test]
+    fn test_compact_expand_u8() {
+        let src: Vec<u8> = (0..300u32)
+            .map(|i| b" a\tbc\n"[(i * 7 % 11 % 6) as usize])
+            .collect();
+        let mut lut = [1u8; 256];
+        for c in *b" \t\n" {
+            lut[c as usize] = 0;
+        }
+        for len in [0, 1, 8, 63, 64, 65, 200, 300] {
+            let want: Vec<u8> = src[..len]
+                .iter()
+                .copied()
+                .filter(|&c| lut[c as usize] != 0)
+                .collect();
+            let mut dst = vec![0u8; len];
+            let n = unsafe { compact_u8(src.as_ptr(), len, lut.as_ptr(), dst.as_mut_ptr()) };
+            assert_eq!(&dst[..n], &want[..], "len={len}");
+
+            // Same selection from precomputed masks, in place.
+            let masks: Vec<u64> = src[..len]
+                .chunks(64)
+                .map(|c| {
+                    c.iter()
+                        .enumerate()
+                        .fold(0, |m, (k, &b)| m | (lut[b as usize] as u64) << k)
+                })
+                .collect();
+            let mut buf = src[..len].to_vec();
+            let n =
+                unsafe { compact_u8_masks(buf.as_ptr(), len, masks.as_ptr(), buf.as_mut_ptr()) };
+            assert_eq!(&buf[..n], &want[..], "len={len}");
+
+            // Expanding the compacted bytes restores the selection in place.
+            let mut back = vec![0u8; masks.len() * 64];
+            assert_eq!(
+                unsafe {
+                    expand_u8(
+                        buf.as_ptr(),
+                        masks.as_ptr(),
+                        masks.len(),
+                        b'_',
+                        back.as_mut_ptr(),
+                    )
+                },
+                n
+            );
+            for (k, &c) in src[..len].iter().enumerate() {
+                let kept = lut[c as usize] != 0;
+                assert_eq!(back[k], if kept { c } else { b'_' }, "len={len} k={k}");
+            }
+            assert!(back[len..].iter().all(|&c| c == b'_'));
+        }
+    }
+
+    #[test]
+    fn test_validity_bitmaps() {
+        for n in [1usize, 7, 8, 9, 63, 64, 65, 130, 200] {
+            let bytes: Vec<u8> = (0..n)
+                .map(|i| (i * 5 % 7 < 3) as u8 * (i as u8 | 1))
+                .collect();
+            let mut bits = vec![0xFFu8; n.div_ceil(8)];
+            unsafe { pack_bytes_to_bits(bytes.as_ptr(), n, bits.as_mut_ptr()) };
+            for i in 0..n {
+                assert_eq!(
+                    bits[i / 8] >> (i % 8) & 1 == 1,
+                    bytes[i] != 0,
+                    "n={n} i={i}"
+                );
+            }
+            if n % 8 != 0 {
+                assert_eq!(bits[n / 8] >> (n % 8), 0, "n={n}");
+            }
+            let mut back = vec![0xFFu8; n];
+            unsafe { unpack_bits_to_bytes(bits.as_ptr(), n, back.as_mut_ptr()) };
+            let want: Vec<u8> = bytes.iter().map(|&b| (b != 0) as u8).collect();
+            assert_eq!(back, want, "n={n}");
+        }
+    }
+
+    #[test]
+    fn test_bswap_buf() {
+        let bytes: Vec<u8> = (0..8 * 77 + 1).map(|i| (i * 31 + 7) as u8).collect();
+        // Odd offset: no alignment is assumed.
+        let src = &bytes[1..];
+        for (width, f) in [
+            (
+                2,
+                bswap16_buf as unsafe extern "C" fn(*const u8, usize, *mut u8),
+            ),
+            (4, bswap32_buf),
+            (8, bswap64_buf),
+        ] {
+            let count = src.len() / width;
+            let want: Vec<u8> = src[..count * width]
+                .chunks(width)
+                .flat_map(|c| c.iter().rev().copied())
+                .collect();
+            let mut dst = vec![0u8; count * width];
+            unsafe { f(src.as_ptr(), count, dst.as_mut_ptr()) };
+            assert_eq!(dst, want, "width={width}");
+            let mut inplace = src[..count * width].to_vec();
+            unsafe { f(inplace.as_ptr(), count, inplace.as_mut_ptr()) };
+            assert_eq!(inplace, want, "in place, width={width}");
+        }
+    }
+}
@@ -0,0 +1,618 @@
+//! BLAKE3: hash, keyed hash and key derivation, one-shot and streaming, with
+//! extendable output.
+//!
+//! Whole chunks are compressed eight at a time, one chunk per SIMD lane; the
+//! chaining-value tree is merged as each batch completes.
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::simd::Simd;
+
+const OUT_LEN: usize = 32;
+const KEY_LEN: usize = 32;
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+/// Most subtrees pending at once: a 2^64-byte input has 2^54 chunks.
+const MAX_DEPTH: usize = 54;
+/// Chunks hashed together by [`hash_chunks`].
+const CHUNK_LANES: usize = 8;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// Column then diagonal steps of one round: `(a, b, c, d)` state indices.
+const G_STEPS: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The seven rounds over a state of any lane type: plain `u32` for single
+/// blocks, `Simd<u32, N>` for N blocks at once.
+macro_rules! rounds {
+    ($state:ident, $m:ident, $add:expr, $rotr:expr) => {{
+        let add = $add;
+        let rotr = $rotr;
+        let mut m = $m;
+        for r in 0..7 {
+            for (i, &[a, b, c, d]) in G_STEPS.iter().enumerate() {
+                $state[a] = add(add($state[a], $state[b]), m[2 * i]);
+                $state[d] = rotr($state[d] ^ $state[a], 16);
+                $state[c] = add($state[c], $state[d]);
+                $state[b] = rotr($state[b] ^ $state[c], 12);
+                $state[a] = add(add($state[a], $state[b]), m[2 * i + 1]);
+                $state[d] = rotr($state[d] ^ $state[a], 8);
+                $state[c] = add($state[c], $state[d]);
+                $state[b] = rotr($state[b] ^ $state[c], 7);
+            }
+            if r < 6 {
+                m = core::array::from_fn(|i| m[MSG_PERMUTATION[i]]);
+            }
+        }
+    }};
+}
+
+fn compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut s = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let m = *block;
+    rounds!(s, m, u32::wrapping_add, u32::rotate_right);
+    for i in 0..8 {
+        s[i] ^= s[i + 8];
+        s[i + 8] ^= cv[i];
+    }
+    s
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+fn first_8(w: [u32; 16]) -> [u32; 8] {
+    core::array::from_fn(|i| w[i])
+}
+
+/// Chaining values of `CHUNK_LANES` consecutive whole chunks (counters from
+/// `counter`), one chunk per lane.
+fn hash_chunks(key: &[u32; 8], counter: u64, flags: u32, chunks: &[u8]) -> [[u32; 8]; CHUNK_LANES] {
+    type V = Simd<u32, CHUNK_LANES>;
+    let mut cv: [V; 8] = core::array::from_fn(|i| V::splat(key[i]));
+    let ctr: [u64; CHUNK_LANES] = core::array::from_fn(|l| counter + l as u64);
+    let ctr_lo = V::from_array(ctr.map(|c| c as u32));
+    let ctr_hi = V::from_array(ctr.map(|c| (c >> 32) as u32));
+    for b in 0..CHUNK_LEN / BLOCK_LEN {
+        let m: [V; 16] = core::array::from_fn(|w| {
+            V::from_array(core::array::from_fn(|l| {
+                let at = l * CHUNK_LEN + b * BLOCK_LEN + 4 * w;
+                u32::from_le_bytes(chunks[at..at + 4].try_into().unwrap())
+            }))
+        });
+        let mut f = flags;
+        if b == 0 {
+            f |= CHUNK_START;
+        }
+        if b == CHUNK_LEN / BLOCK_LEN - 1 {
+            f |= CHUNK_END;
+        }
+        let mut s = [
+            cv[0],
+            cv[1],
+            cv[2],
+            cv[3],
+            cv[4],
+            cv[5],
+            cv[6],
+            cv[7],
+            V::splat(IV[0]),
+            V::splat(IV[1]),
+            V::splat(IV[2]),
+            V::splat(IV[3]),
+            ctr_lo,
+            ctr_hi,
+            V::splat(BLOCK_LEN as u32),
+            V::splat(f),
+        ];
+        let rotr = |x: V, n: u32| (x >> n) | (x << (32 - n));
+        rounds!(s, m, |a: V, b: V| a + b, rotr);
+        cv = core::array::from_fn(|i| s[i] ^ s[i + 8]);
+    }
+    core::array::from_fn(|l| core::array::from_fn(|i| cv[i][l]))
+}
+
+/// A compression whose chaining value or root output is yet to be taken.
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (i, o) in out.chunks_mut(2 * OUT_LEN).enumerate() {
+            let w = compress(
+                &self.cv,
+                &self.block,
+                i as u64,
+                self.block_len,
+                self.flags | ROOT,
+            );
+            for (o, w) in o.chunks_mut(4).zip(w) {
+                o.copy_from_slice(&w.to_le_bytes()[..o.len()]);
+            }
+        }
+    }
+}
+
+fn parent_output(left: [u32; 8], right: [u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Output {
+        cv: *key,
+        block,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT | flags,
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> Self {
+        ChunkState {
+            cv: *key,
+            counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // The last block of a chunk is kept back for `output`.
+            if self.block_len == BLOCK_LEN {
+                let flags = self.flags | self.start_flag();
+                let w = words(&self.block);
+                self.cv = first_8(compress(
+                    &self.cv,
+                    &w,
+                    self.counter,
+                    BLOCK_LEN as u32,
+                    flags,
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            cv: self.cv,
+            block: words(&self.block),
+            counter: self.counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// Streaming BLAKE3 state.  Opaque to callers.
+#[derive(Clone)]
+pub struct Blake3 {
+    key: [u32; 8],
+    chunk: ChunkState,
+    /// Chaining values of completed subtrees, largest first; the first
+    /// `stack_len` are live.
+    stack: [[u32; 8]; MAX_DEPTH],
+    stack_len: usize,
+    flags: u32,
+}
+
+impl Blake3 {
+    fn with_key(key: [u32; 8], flags: u32) -> Self {
+        Blake3 {
+            key,
+            chunk: ChunkState::new(&key, 0, flags),
+            stack: [[0; 8]; MAX_DEPTH],
+            stack_len: 0,
+            flags,
+        }
+    }
+
+    pub(crate) fn new() -> Self {
+        Self::with_key(IV, 0)
+    }
+
+    fn new_keyed(key: &[u8; KEY_LEN]) -> Self {
+        Self::with_key(words(key), KEYED_HASH)
+    }
+
+    fn new_derive_key(context: &[u8]) -> Self {
+        let mut h = Self::with_key(IV, DERIVE_KEY_CONTEXT);
+        h.update(context);
+        let mut key = [0u8; KEY_LEN];
+        h.finalize(&mut key);
+        Self::with_key(words(&key), DERIVE_KEY_MATERIAL)
+    }
+
+    /// Merge the chaining value of chunk number `total - 1` into the tree:
+    /// every trailing zero bit of `total` completes a subtree.
+    fn push_chunk_cv(&mut self, mut cv: [u32; 8], mut total: u64) {
+        while total & 1 == 0 {
+            self.stack_len -= 1;
+            let left = self.stack[self.stack_len];
+            cv = parent_output(left, cv, &self.key, self.flags).chaining_value();
+            total >>= 1;
+        }
+        self.stack[self.stack_len] = cv;
+        self.stack_len += 1;
+    }
+
+    pub(crate) fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // A full chunk is only finished once more input shows it is not
+            // the root.
+            if self.chunk.len() == CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let total = self.chunk.counter + 1;
+                self.push_chunk_cv(cv, total);
+                self.chunk = ChunkState::new(&self.key, total, self.flags);
+            }
+            if self.chunk.len() == 0 && input.len() > CHUNK_LANES * CHUNK_LEN {
+                let counter = self.chunk.counter;
+                let (batch, rest) = input.split_at(CHUNK_LANES * CHUNK_LEN);
+                let cvs = hash_chunks(&self.key, counter, self.flags, batch);
+                for (i, cv) in cvs.into_iter().enumerate() {
+                    self.push_chunk_cv(cv, counter + i as u64 + 1);
+                }
+                self.chunk = ChunkState::new(&self.key, counter + CHUNK_LANES as u64, self.flags);
+                input = rest;
+                continue;
+            }
+            let take = (CHUNK_LEN - self.chunk.len()).min(input.len());
+            self.chunk.update(&input[..take]);
+            input = &input[take..];
+        }
+    }
+
+    pub(crate) fn finalize(&self, out: &mut [u8]) {
+        let mut output = self.chunk.output();
+        for &left in self.stack[..self.stack_len].iter().rev() {
+            output = parent_output(left, output.chaining_value(), &self.key, self.flags);
+        }
+        output.root_bytes(out);
+    }
+}
+
+/// Slice view of a nullable input pointer.
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    }
+}
+
+unsafe fn finish(h: &Blake3, out: *mut u8, out_len: usize) {
+    if !out.is_null() && out_len > 0 {
+        h.finalize(core::slice::from_raw_parts_mut(out, out_len));
+    }
+}
+
+/// BLAKE3 hash of `len` bytes.  Writes `out_len` bytes of output (32 for the
+/// standard digest; longer outputs extend it).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for
+/// `out_len` bytes.
+#[unsafe(export_name = c_symbol!("blake3"))]
+pub unsafe extern "C" fn blake3(ptr: *const u8, len: usize, out: *mut u8, out_len: usize) {
+    let mut h = Blake3::new();
+    h.update(input(ptr, len));
+    finish(&h, out, out_len);
+}
+
+/// Keyed BLAKE3 (a MAC/PRF) of `len` bytes under the 32-byte `key`.
+///
+/// # Safety
+/// As [`blake3`]; `key` must be valid for 32 bytes.
+#[unsafe(export_name = c_symbol!("blake3_keyed"))]
+pub unsafe extern "C" fn blake3_keyed(
+    key: *const u8,
+    ptr: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) {
+    if key.is_null() {
+        return;
+    }
+    let mut h = Blake3::new_keyed(&*(key as *const [u8; KEY_LEN]));
+    h.update(input(ptr, len));
+    finish(&h, out, out_len);
+}
+
+/// BLAKE3 key derivation: derive `out_len` bytes of key material from `len`
+/// bytes of input keying material under the application's `context` string.
+///
+/// # Safety
+/// As [`blake3`]; `context` must be null or valid for `context_len` bytes.
+#[unsafe(export_name = c_symbol!("blake3_derive_key"))]
+pub unsafe extern "C" fn blake3_derive_key(
+    context: *const u8,
+    context_len: usize,
+    ptr: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+) {
+    let mut h = Blake3::new_derive_key(input(context, context_len));
+    h.update(input(ptr, len));
+    finish(&h, out, out_len);
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming BLAKE3 hash.  Release with [`blake3_free`].
+#[unsafe(export_name = c_symbol!("blake3_new"))]
+pub extern "C" fn blake3_new() -> *mut Blake3 {
+    Box::into_raw(Box::new(Blake3::new()))
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming keyed BLAKE3 hash (null if `key` is null).
+///
+/// # Safety
+/// `key` must be null or valid for 32 bytes.
+#[unsafe(export_name = c_symbol!("blake3_new_keyed"))]
+pub unsafe extern "C" fn blake3_new_keyed(key: *const u8) -> *mut Blake3 {
+    if key.is_null() {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Blake3::new_keyed(&*(key as *const [u8; KEY_LEN]))))
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming BLAKE3 key derivation for `context`.
+///
+/// # Safety
+/// `context` must be null or valid for `context_len` bytes.
+#[unsafe(export_name = c_symbol!("blake3_new_derive_key"))]
+pub unsafe extern "C" fn blake3_new_derive_key(
+    context: *const u8,
+    context_len: usize,
+) -> *mut Blake3 {
+    Box::into_raw(Box::new(Blake3::new_derive_key(input(
+        context,
+        context_len,
+    ))))
+}
+
+/// Feed `len` more bytes to a streaming BLAKE3.
+///
+/// # Safety
+/// `s` must be a live handle; `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("blake3_update"))]
+pub unsafe extern "C" fn blake3_update(s: *mut Blake3, ptr: *const u8, len: usize) {
+    if let Some(s) = s.as_mut() {
+        s.update(input(ptr, len));
+    }
+}
+
+/// Write `out_len` output bytes for everything fed so far; the state is left
+/// intact, so more input may follow.
+///
+/// # Safety
+/// `s` must be null or a live handle; `out` must be valid for `out_len` bytes.
+#[unsafe(export_name = c_symbol!("blake3_finalize"))]
+pub unsafe extern "C" fn blake3_finalize(s: *const Blake3, out: *mut u8, out_len: usize) {
+    if let Some(s) = s.as_ref() {
+        finish(s, out, out_len);
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Release a handle from one of the `blake3_new*` constructors.  Null is
+/// ignored.
+///
+/// # Safety
+/// `s` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("blake3_free"))]
+pub unsafe extern "C" fn blake3_free(s: *mut Blake3) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8; 32] = b"whats the Elvish word for friend";
+    const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    /// (input length, hash, keyed hash, derived key) from the official test
+    /// vectors: input byte `i` is `i % 251`.
+    const VECTORS: [(usize, &str, &str, &str); 6] = [
+        (
+            0,
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26",
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d",
+        ),
+        (
+            1,
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+            "6d7878dfff2f485635d39013278ae14f1454b8c0a3a2d34bc1ab38228a80c95b",
+            "b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c",
+        ),
+        (
+            1024,
+            "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+            "75c46f6f3d9eb4f55ecaaee480db732e6c2105546f1e675003687c31719c7ba4",
+            "7356cd7720d5b66b6d0697eb3177d9f8d73a4a5c5e968896eb6a689684302706",
+        ),
+        (
+            1025,
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+            "357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69",
+            "effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb",
+        ),
+        (
+            8193,
+            "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+            "954a2a75420c8d6547e3ba5b98d963e6fa6491addc8c023189cc519821b4a1f5",
+            "af1e0346e389b17c23200270a64aa4e1ead98c61695d917de7d5b00491c9b0f1",
+        ),
+        (
+            31744,
+            "62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47",
+            "efa53b389ab67c593dba624d898d0f7353ab99e4ac9d42302ee64cbf9939a419",
+            "39772aef80e0ebe60596361e45b061e8f417429d529171b6764468c22928e28e",
+        ),
+    ];
+
+    fn hex(d: &[u8]) -> String {
+        d.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn data(n: usize) -> Vec<u8> {
+        (0..n).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_blake3_vectors() {
+        for (n, hash, keyed, derived) in VECTORS {
+            let d = data(n);
+            let mut out = [0u8; OUT_LEN];
+            unsafe {
+                blake3(d.as_ptr(), n, out.as_mut_ptr(), OUT_LEN);
+                assert_eq!(hex(&out), hash, "hash len {n}");
+                blake3_keyed(KEY.as_ptr(), d.as_ptr(), n, out.as_mut_ptr(), OUT_LEN);
+                assert_eq!(hex(&out), keyed, "keyed len {n}");
+                blake3_derive_key(
+                    CONTEXT.as_ptr(),
+                    CONTEXT.len(),
+                    d.as_ptr(),
+                    n,
+                    out.as_mut_ptr(),
+                    OUT_LEN,
+                );
+                assert_eq!(hex(&out), derived, "derive len {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_blake3_streaming_and_xof() {
+        let d = data(40_000);
+        for n in [0usize, 64, 1024, 8192, 8193, 9216, 17_409, 40_000] {
+            let mut want = [0u8; 100];
+            unsafe { blake3(d.as_ptr(), n, want.as_mut_ptr(), want.len()) };
+            // The extended output starts with the standard digest.
+            let mut short = [0u8; OUT_LEN];
+            unsafe { blake3(d.as_ptr(), n, short.as_mut_ptr(), OUT_LEN) };
+            assert_eq!(short, want[..OUT_LEN]);
+            for step in [1usize, 1000, 1024, 9000] {
+                let mut got = [0u8; 100];
+                unsafe {
+                    let s = blake3_new();
+                    for c in d[..n].chunks(step) {
+                        blake3_update(s, c.as_ptr(), c.len());
+                    }
+                    blake3_finalize(s, got.as_mut_ptr(), got.len());
+                    blake3_free(s);
+                }
+                assert_eq!(got, want, "len {n} step {step}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_chunks_matches_chunk_state() {
+        let d = data(CHUNK_LANES * CHUNK_LEN);
+        let cvs = hash_chunks(&IV, 5, 0, &d);
+        for (l, cv) in cvs.iter().enumerate() {
+            let mut c = ChunkState::new(&IV, 5 + l as u64, 0);
+            c.update(&d[l * CHUNK_LEN..(l + 1) * CHUNK_LEN]);
+            assert_eq!(*cv, c.output().chaining_value());
+        }
+    }
+}
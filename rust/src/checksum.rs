@@ -0,0 +1,278 @@
+//! Additive checksums: Adler-32 (zlib, Go's `hash/adler32`) and
+//! Fletcher-32/64.
+use core::simd::prelude::SimdUint;
+use core::simd::{LaneCount, Simd, SupportedLaneCount};
+
+/// Lanes per step of the vectorised sums.
+const CHECKSUM_LANES: usize = 32;
+
+// === Adler-32 ================================================================
+
+/// Largest prime below 2^16.
+const ADLER_MOD: u32 = 65521;
+/// Longest run (rounded down to whole vectors) before the sums can overflow
+/// u32 and must be reduced; zlib's NMAX is 5552.
+const ADLER_BLOCK: usize = 5552 / CHECKSUM_LANES * CHECKSUM_LANES;
+
+/// Advance `(s1, s2)` over `data` without reducing; `data.len()` must be a
+/// multiple of `L` and at most [`ADLER_BLOCK`].
+///
+/// With chunks `v_0..v_n` and weights `w = [L, L-1, .., 1]`:
+/// `s2 += n*L*s1 + L*Σ_k Σ_{j<k} sum(v_j) + Σ_k v_k·w`, `s1 += Σ_k sum(v_k)`.
+#[inline(always)]
+fn adler32_block<const L: usize>(s1: u32, s2: u32, data: &[u8]) -> (u32, u32)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let w = Simd::<u32, L>::from_array(core::array::from_fn(|i| (L - i) as u32));
+    let mut vs1 = Simd::<u32, L>::splat(0);
+    let mut vprev = Simd::<u32, L>::splat(0);
+    let mut vdot = Simd::<u32, L>::splat(0);
+    for chunk in data.chunks_exact(L) {
+        let v: Simd<u32, L> = Simd::<u8, L>::from_slice(chunk).cast();
+        vprev += vs1;
+        vs1 += v;
+        vdot += v * w;
+    }
+    let n = (data.len() / L) as u32;
+    let s2 = s2 + n * L as u32 * s1 + L as u32 * vprev.reduce_sum() + vdot.reduce_sum();
+    (s1 + vs1.reduce_sum(), s2)
+}
+
+/// Update an Adler-32 checksum (start from 1) with additional bytes.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("adler32_update"))]
+pub unsafe extern "C" fn adler32_update(init: u32, ptr: *const u8, len: usize) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return init;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let (mut s1, mut s2) = (init & 0xFFFF, init >> 16);
+    let mut blocks = data.chunks_exact(ADLER_BLOCK);
+    for block in &mut blocks {
+        (s1, s2) = adler32_block::<CHECKSUM_LANES>(s1, s2, block);
+        (s1, s2) = (s1 % ADLER_MOD, s2 % ADLER_MOD);
+    }
+    let rem = blocks.remainder();
+    let split = rem.len() - rem.len() % CHECKSUM_LANES;
+    (s1, s2) = adler32_block::<CHECKSUM_LANES>(s1, s2, &rem[..split]);
+    for &b in &rem[split..] {
+        s1 += b as u32;
+        s2 += s1;
+    }
+    ((s2 % ADLER_MOD) << 16) | (s1 % ADLER_MOD)
+}
+
+/// Combine two Adler-32 checksums: given `adler1 = adler32(A)`,
+/// `adler2 = adler32(B)` and `len2 = len(B)`, return `adler32(A || B)`.
+#[unsafe(export_name = c_symbol!("adler32_combine"))]
+pub unsafe extern "C" fn adler32_combine(adler1: u32, adler2: u32, len2: usize) -> u32 {
+    let rem = (len2 % ADLER_MOD as usize) as u32;
+    let a1 = adler1 & 0xFFFF;
+    // adler2's s1 already includes the leading 1, so drop one copy.
+    let s1 = (a1 + (adler2 & 0xFFFF) + ADLER_MOD - 1) % ADLER_MOD;
+    let s2 =
+        ((rem * a1) % ADLER_MOD + (adler1 >> 16) + (adler2 >> 16) + ADLER_MOD - rem) % ADLER_MOD;
+    (s2 << 16) | s1
+}
+
+// === Fletcher-32 / Fletcher-64 ===============================================
+
+/// u64 lanes per step of the Fletcher sums.
+const FLETCHER_LANES: usize = 8;
+/// Words summed between modular reductions.  Sized for Fletcher-64 (32-bit
+/// words): the unreduced `s2` stays below `2^60`.
+const FLETCHER_BLOCK: usize = 16384;
+
+/// Fletcher analogue of [`adler32_block`] over 16- or 32-bit words widened
+/// to u64 lanes, unreduced.
+#[inline(always)]
+fn fletcher_block<const L: usize>(
+    s1: u64,
+    s2: u64,
+    chunks: impl Iterator<Item = Simd<u64, L>>,
+) -> (u64, u64)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let w = Simd::<u64, L>::from_array(core::array::from_fn(|i| (L - i) as u64));
+    let mut vs1 = Simd::<u64, L>::splat(0);
+    let mut vprev = Simd::<u64, L>::splat(0);
+    let mut vdot = Simd::<u64, L>::splat(0);
+    let mut n = 0u64;
+    for v in chunks {
+        vprev += vs1;
+        vs1 += v;
+        vdot += v * w;
+        n += 1;
+    }
+    let s2 = s2 + n * L as u64 * s1 + L as u64 * vprev.reduce_sum() + vdot.reduce_sum();
+    (s1 + vs1.reduce_sum(), s2)
+}
+
+macro_rules! export_fletcher {
+    ($update:ident, $combine:ident, $word:ty, $sum:ty, $bits:literal, $half:literal) => {
+        #[doc = concat!(
+            "Update a Fletcher-", $bits, " checksum (start from 0) with `nwords` ", stringify!($word),
+            " words, summed as native integers.  The result packs the two running sums as `s2 << ", $half, " | s1`, ",
+            "each mod `2^", $half, " - 1`.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `nwords` ", stringify!($word), " reads."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($update)))]
+        pub unsafe extern "C" fn $update(init: $sum, ptr: *const $word, nwords: usize) -> $sum {
+            const HALF: u32 = <$word>::BITS;
+            const MOD: u64 = <$word>::MAX as u64;
+            if ptr.is_null() || nwords == 0 {
+                return init;
+            }
+            let words = core::slice::from_raw_parts(ptr, nwords);
+            let (mut s1, mut s2) = ((init as u64) & MOD, (init as u64) >> HALF);
+            for block in words.chunks(FLETCHER_BLOCK) {
+                let mut chunks = block.chunks_exact(FLETCHER_LANES);
+                let wide = (&mut chunks).map(|c| Simd::<$word, FLETCHER_LANES>::from_slice(c).cast::<u64>());
+                (s1, s2) = fletcher_block(s1, s2, wide);
+                for &x in chunks.remainder() {
+                    s1 += x as u64;
+                    s2 += s1;
+                }
+                (s1, s2) = (s1 % MOD, s2 % MOD);
+            }
+            ((s2 << HALF) | s1) as $sum
+        }
+
+        #[doc = concat!(
+            "Combine two Fletcher-", $bits, " checksums: given `f1 = fletcher(A)`, `f2 = fletcher(B)` ",
+            "and `len2 = len(B)` in words, return `fletcher(A || B)`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($combine)))]
+        pub unsafe extern "C" fn $combine(f1: $sum, f2: $sum, len2: usize) -> $sum {
+            const HALF: u32 = <$word>::BITS;
+            const MOD: u64 = <$word>::MAX as u64;
+            let (a1, b1) = ((f1 as u64) & MOD, (f1 as u64) >> HALF);
+            let (a2, b2) = ((f2 as u64) & MOD, (f2 as u64) >> HALF);
+            let n = (len2 as u64) % MOD;
+            let s1 = (a1 + a2) % MOD;
+            let s2 = ((b1 + b2) % MOD + (n as u128 * a1 as u128 % MOD as u128) as u64) % MOD;
+            ((s2 << HALF) | s1) as $sum
+        }
+    };
+}
+
+export_fletcher!(fletcher32_update, fletcher32_combine, u16, u32, "32", "16");
+export_fletcher!(fletcher64_update, fletcher64_combine, u32, u64, "64", "32");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adler_ref(init: u32, data: &[u8]) -> u32 {
+        let (mut a, mut b) = (init & 0xFFFF, init >> 16);
+        for &x in data {
+            a = (a + x as u32) % ADLER_MOD;
+            b = (b + a) % ADLER_MOD;
+        }
+        (b << 16) | a
+    }
+
+    #[test]
+    fn test_adler32() {
+        unsafe {
+            assert_eq!(adler32_update(1, b"Wikipedia".as_ptr(), 9), 0x11E6_0398);
+            // Worst case for overflow: all 0xFF across several blocks.
+            let ff = vec![0xFFu8; 3 * ADLER_BLOCK + 77];
+            assert_eq!(adler32_update(1, ff.as_ptr(), ff.len()), adler_ref(1, &ff));
+            let data: Vec<u8> = (0..20_000u32).map(|i| (i * 7 + i / 13) as u8).collect();
+            for len in [1usize, 31, 32, 33, 5535, 5536, 5552, 11_111, 20_000] {
+                let want = adler_ref(1, &data[..len]);
+                assert_eq!(adler32_update(1, data.as_ptr(), len), want, "len {len}");
+                assert_eq!(
+                    adler32_update(0xFFF0_FFF0, data.as_ptr(), len),
+                    adler_ref(0xFFF0_FFF0, &data[..len])
+                );
+            }
+            let whole = adler32_update(1, data.as_ptr(), data.len());
+            for split in [0usize, 1, 5552, 7000, 20_000] {
+                let (a, b) = data.split_at(split);
+                let (ca, cb) = (
+                    adler32_update(1, a.as_ptr(), a.len()),
+                    adler32_update(1, b.as_ptr(), b.len()),
+                );
+                assert_eq!(adler32_combine(ca, cb, b.len()), whole, "split {split}");
+            }
+        }
+    }
+
+    fn fletcher_ref(words: &[u64], half: u32) -> u64 {
+        let m = (1u64 << half) - 1;
+        let (mut a, mut b) = (0u64, 0u64);
+        for &w in words {
+            a = (a + w) % m;
+            b = (b + a) % m;
+        }
+        (b << half) | a
+    }
+
+    #[test]
+    fn test_fletcher() {
+        // Wikipedia check values, bytes read as little-endian words.
+        let w16 = |s: &[u8]| -> Vec<u16> {
+            s.chunks(2)
+                .map(|c| c[0] as u16 | (*c.get(1).unwrap_or(&0) as u16) << 8)
+                .collect()
+        };
+        let w32 = |s: &[u8]| -> Vec<u32> {
+            s.chunks(4)
+                .map(|c| (0..4).fold(0, |x, i| x | (*c.get(i).unwrap_or(&0) as u32) << (8 * i)))
+                .collect()
+        };
+        unsafe {
+            for (s, f32, f64) in [
+                (&b"abcde"[..], 0xF04F_C729u32, 0xC8C6_C527_6463_62C6u64),
+                (b"abcdef", 0x5650_2D2A, 0xC8C7_2B27_6463_C8C6),
+                (b"abcdefgh", 0xEBE1_9591, 0x312E_2B28_CCCA_C8C6),
+            ] {
+                let (a, b) = (w16(s), w32(s));
+                assert_eq!(fletcher32_update(0, a.as_ptr(), a.len()), f32);
+                assert_eq!(fletcher64_update(0, b.as_ptr(), b.len()), f64);
+            }
+            let big: Vec<u32> = (0..40_000u32)
+                .map(|i| u32::MAX - i.wrapping_mul(2_654_435_761) % 7)
+                .collect();
+            let small: Vec<u16> = big.iter().map(|&x| x as u16).collect();
+            for n in [1usize, 7, 8, 9, 16_383, 16_384, 16_385, 40_000] {
+                let wide: Vec<u64> = big[..n].iter().map(|&x| x as u64).collect();
+                assert_eq!(
+                    fletcher64_update(0, big.as_ptr(), n),
+                    fletcher_ref(&wide, 32),
+                    "n {n}"
+                );
+                let wide: Vec<u64> = small[..n].iter().map(|&x| x as u64).collect();
+                assert_eq!(
+                    fletcher32_update(0, small.as_ptr(), n) as u64,
+                    fletcher_ref(&wide, 16),
+                    "n {n}"
+                );
+            }
+            for split in [0usize, 3, 16_384, 39_999] {
+                let whole = fletcher64_update(0, big.as_ptr(), big.len());
+                let (a, b) = big.split_at(split);
+                let f1 = fletcher64_update(0, a.as_ptr(), a.len());
+                assert_eq!(
+                    fletcher64_combine(f1, fletcher64_update(0, b.as_ptr(), b.len()), b.len()),
+                    whole
+                );
+                assert_eq!(fletcher64_update(f1, b.as_ptr(), b.len()), whole);
+                let whole = fletcher32_update(0, small.as_ptr(), small.len());
+                let (a, b) = small.split_at(split);
+                let f1 = fletcher32_update(0, a.as_ptr(), a.len());
+                assert_eq!(
+                    fletcher32_combine(f1, fletcher32_update(0, b.as_ptr(), b.len()), b.len()),
+                    whole
+                );
+            }
+        }
+    }
+}
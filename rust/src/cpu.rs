@@ -0,0 +1,80 @@
+//! CPU feature introspection, so callers can see which instruction-set
+//! extensions the kernels can use on the running host.
+
+/// Bit set of x86_64 features present on the running CPU.
+#[cfg(target_arch = "x86_64")]
+fn detect() -> u64 {
+    [
+        x86_feature!("sse4.2"),
+        x86_feature!("avx2"),
+        x86_feature!("avx512f"),
+        x86_feature!("avx512bw"),
+        x86_feature!("pclmulqdq"),
+        x86_feature!("sha"),
+        x86_feature!("bmi2"),
+        x86_feature!("popcnt"),
+        x86_feature!("fma"),
+        x86_feature!("avx512vnni"),
+        x86_feature!("avxvnni"),
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |f, (bit, &on)| f | (on as u64) << bit)
+}
+
+/// Bit set of aarch64 features present on the running CPU.
+#[cfg(target_arch = "aarch64")]
+fn detect() -> u64 {
+    [
+        aarch64_feature!("neon"),
+        aarch64_feature!("sve"),
+        aarch64_feature!("crc"),
+        aarch64_feature!("sha2"),
+        aarch64_feature!("aes"),
+        aarch64_feature!("dotprod"),
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |f, (bit, &on)| f | (on as u64) << (16 + bit))
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn detect() -> u64 {
+    0
+}
+
+/// Instruction-set extensions present on the running CPU, as a bit set.
+///
+/// x86_64: bit 0 SSE4.2, 1 AVX2, 2 AVX-512F, 3 AVX-512BW, 4 PCLMULQDQ,
+/// 5 SHA, 6 BMI2, 7 POPCNT, 8 FMA, 9 AVX-512 VNNI, 10 AVX-VNNI.
+/// aarch64: bit 16 NEON, 17 SVE, 18 CRC32, 19 SHA2, 20 AES/PMULL,
+/// 21 DOTPROD.
+///
+/// Bits for the other architecture are always clear.
+#[unsafe(export_name = c_symbol!("simba_cpu_features"))]
+pub unsafe extern "C" fn simba_cpu_features() -> u64 {
+    detect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_features() {
+        let f = unsafe { simba_cpu_features() };
+        #[cfg(target_arch = "x86_64")]
+        {
+            assert_eq!(f >> 16, 0);
+            assert_eq!(f & 1 != 0, x86_feature!("sse4.2"));
+            assert_eq!(f >> 4 & 1 != 0, x86_feature!("pclmulqdq"));
+            // AVX-512BW implies AVX-512F.
+            assert!(f & 0b1100 != 0b1000);
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            assert_eq!(f & 0xFFFF, 0);
+            assert_eq!(f >> 16 & 1 != 0, aarch64_feature!("neon"));
+        }
+    }
+}
@@ -0,0 +1,884 @@
+//! CRC kernels: CRC32C (Castagnoli), CRC32 (IEEE 802.3, as used by
+//! zip/gzip), CRC64 (ECMA-182 as used by XZ, and NVMe) and CRC16 (CCITT,
+//! MODBUS).  The CRC32C FFI exports live in the crate root; this module
+//! provides the kernel behind them.
+//!
+//! The CRC32/CRC64 exports follow Go's `hash/crc32` / `hash/crc64`
+//! conventions: CRCs are passed and returned in *finalised* form (complemented
+//! before and after the update), so a fresh checksum starts from 0.  The
+//! CRC16 variants have no final XOR, so the running value is the register
+//! itself and a fresh checksum starts from the variant's initial value.
+//!
+//! Long buffers are folded 64 bytes at a time with carry-less multiplies
+//! (PCLMULQDQ) when the CPU has them; everything else goes through
+//! slicing-by-8 tables.  CRC32C (and CRC32 on ARMv8) prefers the dedicated
+//! CRC32 instruction, run over three interleaved streams.  Which of these
+//! paths is used is decided once, on first use, and can be inspected or
+//! narrowed through [`crc_hw_features`] / [`crc_set_hw_features`].
+
+// Targets without a CRC32 instruction (e.g. wasm32) never reach the
+// interleaved kernels or the feature bits they dispatch on.
+#![cfg_attr(
+    not(any(target_arch = "x86_64", target_arch = "aarch64")),
+    allow(dead_code)
+)]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Tables and folding constants for one reflected polynomial of width ≤ 64.
+///
+/// Register values are kept in the reflected domain: bit `width - 1 - d`
+/// holds the coefficient of `x^d`.
+struct CrcParams {
+    /// Reflected polynomial (without the `x^width` term).
+    poly: u64,
+    width: u32,
+    /// `tables[k][b]`: raw CRC of byte `b` followed by `k` zero bytes.
+    tables: [[u64; 256]; 8],
+    /// Multipliers for the carry-less folding path.
+    #[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+    fold: FoldConsts,
+}
+
+/// Carry-less multiply constants for folding a 128-bit accumulator forward
+/// (see [`CrcParams::fold_const`]).
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+struct FoldConsts {
+    /// Forward 512 bits: multipliers for the low and high 64-bit halves.
+    k512: [u64; 2],
+    /// Forward 128 bits.
+    k128: [u64; 2],
+}
+
+impl CrcParams {
+    const fn new(poly: u64, width: u32) -> Self {
+        let mut tables = [[0u64; 256]; 8];
+        let mut b = 0;
+        while b < 256 {
+            let mut r = b as u64;
+            let mut i = 0;
+            while i < 8 {
+                r = if r & 1 != 0 { (r >> 1) ^ poly } else { r >> 1 };
+                i += 1;
+            }
+            tables[0][b] = r;
+            b += 1;
+        }
+        let mut k = 1;
+        while k < 8 {
+            let mut b = 0;
+            while b < 256 {
+                let prev = tables[k - 1][b];
+                tables[k][b] = (prev >> 8) ^ tables[0][(prev & 0xFF) as usize];
+                b += 1;
+            }
+            k += 1;
+        }
+        let mut p = CrcParams {
+            poly,
+            width,
+            tables,
+            fold: FoldConsts {
+                k512: [0; 2],
+                k128: [0; 2],
+            },
+        };
+        p.fold = FoldConsts {
+            k512: [p.fold_const(512 + 64), p.fold_const(512)],
+            k128: [p.fold_const(128 + 64), p.fold_const(128)],
+        };
+        p
+    }
+
+    const fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    /// `a * b mod P` in the reflected domain.
+    const fn mul(&self, a: u64, mut b: u64) -> u64 {
+        let mut m = 1u64 << (self.width - 1);
+        let mut p = 0;
+        while m != 0 {
+            if a & m != 0 {
+                p ^= b;
+            }
+            b = if b & 1 != 0 {
+                (b >> 1) ^ self.poly
+            } else {
+                b >> 1
+            };
+            m >>= 1;
+        }
+        p
+    }
+
+    /// `x^n mod P` in the reflected domain.
+    const fn xpow(&self, mut n: u64) -> u64 {
+        let mut result = 1u64 << (self.width - 1);
+        let mut sq = 1u64 << (self.width - 2);
+        while n != 0 {
+            if n & 1 != 0 {
+                result = self.mul(result, sq);
+            }
+            sq = self.mul(sq, sq);
+            n >>= 1;
+        }
+        result
+    }
+
+    /// Multiplier that carries a 64-bit accumulator half forward by `bits`.
+    ///
+    /// A little-endian 64-bit load of reflected data has bit `i` at degree
+    /// `63 - i`, and a carry-less product of two such values lands one degree
+    /// short in the 128-bit result, hence `x^(bits - 1)`.
+    const fn fold_const(&self, bits: u64) -> u64 {
+        self.xpow(bits - 1) << (64 - self.width)
+    }
+
+    /// Advance the raw (un-finalised) register over `data`, byte-table path.
+    fn update_table(&self, mut r: u64, data: &[u8]) -> u64 {
+        let t = &self.tables;
+        let mut chunks = data.chunks_exact(8);
+        for c in &mut chunks {
+            let x = r ^ u64::from_le_bytes(c.try_into().unwrap());
+            r = t[7][(x & 0xFF) as usize]
+                ^ t[6][(x >> 8 & 0xFF) as usize]
+                ^ t[5][(x >> 16 & 0xFF) as usize]
+                ^ t[4][(x >> 24 & 0xFF) as usize]
+                ^ t[3][(x >> 32 & 0xFF) as usize]
+                ^ t[2][(x >> 40 & 0xFF) as usize]
+                ^ t[1][(x >> 48 & 0xFF) as usize]
+                ^ t[0][(x >> 56) as usize];
+        }
+        for &b in chunks.remainder() {
+            r = (r >> 8) ^ t[0][((r ^ b as u64) & 0xFF) as usize];
+        }
+        r
+    }
+
+    /// Fold `data` (at least 64 bytes) into a 128-bit remainder with carry-less
+    /// multiplies, then finish the last bytes through the tables.
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "pclmulqdq")]
+    unsafe fn update_clmul(&self, r: u64, data: &[u8]) -> u64 {
+        use core::arch::x86_64::*;
+
+        #[inline(always)]
+        unsafe fn load(c: &[u8]) -> __m128i {
+            _mm_loadu_si128(c.as_ptr() as *const __m128i)
+        }
+        #[inline(always)]
+        unsafe fn fold(acc: __m128i, k: __m128i, next: __m128i) -> __m128i {
+            let lo = _mm_clmulepi64_si128(acc, k, 0x00);
+            let hi = _mm_clmulepi64_si128(acc, k, 0x11);
+            _mm_xor_si128(_mm_xor_si128(lo, hi), next)
+        }
+
+        let k512 = _mm_set_epi64x(self.fold.k512[1] as i64, self.fold.k512[0] as i64);
+        let k128 = _mm_set_epi64x(self.fold.k128[1] as i64, self.fold.k128[0] as i64);
+
+        // The incoming register is the same as XOR-ing it into the first bytes
+        // and starting from zero.
+        let mut blocks = data.chunks_exact(64);
+        let first = blocks.next().unwrap();
+        let mut acc = [0, 16, 32, 48].map(|o| load(&first[o..]));
+        acc[0] = _mm_xor_si128(acc[0], _mm_set_epi64x(0, r as i64));
+        for b in &mut blocks {
+            for (j, a) in acc.iter_mut().enumerate() {
+                *a = fold(*a, k512, load(&b[16 * j..]));
+            }
+        }
+        let mut a = fold(acc[0], k128, acc[1]);
+        a = fold(a, k128, acc[2]);
+        a = fold(a, k128, acc[3]);
+        let mut rest = blocks.remainder().chunks_exact(16);
+        for c in &mut rest {
+            a = fold(a, k128, load(c));
+        }
+
+        // The accumulator is congruent to the message so far; its CRC from a
+        // zero register is the message's.
+        let mut buf = [0u8; 16];
+        _mm_storeu_si128(buf.as_mut_ptr() as *mut __m128i, a);
+        let r = self.update_table(0, &buf);
+        self.update_table(r, rest.remainder())
+    }
+
+    /// Advance the raw register over `data`.
+    fn update_raw(&self, r: u64, data: &[u8]) -> u64 {
+        #[cfg(target_arch = "x86_64")]
+        if data.len() >= 64 && hw_features() & CRC_HW_CLMUL != 0 {
+            return unsafe { self.update_clmul(r, data) };
+        }
+        self.update_table(r, data)
+    }
+
+    /// Update a finalised CRC with `data`.
+    fn update(&self, crc: u64, data: &[u8]) -> u64 {
+        let m = self.mask();
+        !self.update_raw(!crc & m, data) & m
+    }
+
+    /// CRC of `A || B` from the finalised CRCs of `A` and `B` and `len(B)`.
+    fn combine(&self, crc1: u64, crc2: u64, len2: u64) -> u64 {
+        self.mul(self.xpow(len2.wrapping_mul(8)), crc1) ^ crc2
+    }
+}
+
+static CRC32_IEEE: CrcParams = CrcParams::new(0xEDB8_8320, 32);
+static CRC64_XZ: CrcParams = CrcParams::new(0xC96C_5795_D787_0F42, 64);
+static CRC64_NVME: CrcParams = CrcParams::new(0x9A6C_9329_AC4B_C9B5, 64);
+static CRC16_MODBUS: CrcParams = CrcParams::new(0xA001, 16);
+
+/// MSB-first table for CRC16-CCITT (polynomial 0x1021, not reflected).
+static CRC16_CCITT_TABLE: [u16; 256] = {
+    let mut t = [0u16; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut r = (b as u16) << 8;
+        let mut i = 0;
+        while i < 8 {
+            r = if r & 0x8000 != 0 {
+                (r << 1) ^ 0x1021
+            } else {
+                r << 1
+            };
+            i += 1;
+        }
+        t[b] = r;
+        b += 1;
+    }
+    t
+};
+
+// === Runtime dispatch ========================================================
+
+/// Feature bit: a CRC32 instruction (SSE4.2 `crc32` for CRC32C; the ARMv8 CRC
+/// extension for both CRC32C and CRC32).
+const CRC_HW_CRC32: u32 = 1;
+/// Feature bit: carry-less multiply folding (PCLMULQDQ).
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+const CRC_HW_CLMUL: u32 = 2;
+/// Sentinel for "not detected yet".
+const CRC_HW_UNSET: u32 = u32::MAX;
+
+/// Hardware features the CRC kernels dispatch on.
+static CRC_HW: AtomicU32 = AtomicU32::new(CRC_HW_UNSET);
+
+/// Features supported by the running CPU (and compiled for this target).
+fn detect_hw_features() -> u32 {
+    #[allow(unused_mut)]
+    let mut f = 0;
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86_feature!("sse4.2") {
+            f |= CRC_HW_CRC32;
+        }
+        if x86_feature!("pclmulqdq") {
+            f |= CRC_HW_CLMUL;
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    if aarch64_feature!("crc") {
+        f |= CRC_HW_CRC32;
+    }
+    f
+}
+
+/// Selected features, detecting them on first use.
+#[inline]
+fn hw_features() -> u32 {
+    match CRC_HW.load(Ordering::Relaxed) {
+        CRC_HW_UNSET => {
+            let f = detect_hw_features();
+            // Racing first callers detect the same value; a concurrent
+            // `crc_set_hw_features` wins.
+            let _ = CRC_HW.compare_exchange(CRC_HW_UNSET, f, Ordering::Relaxed, Ordering::Relaxed);
+            CRC_HW.load(Ordering::Relaxed)
+        }
+        f => f,
+    }
+}
+
+/// Hardware features the CRC kernels currently use, as a bit set: 1 = CRC32
+/// instruction (SSE4.2 on x86_64, the CRC extension on aarch64), 2 =
+/// carry-less multiply folding (PCLMULQDQ).  0 means every CRC runs on the
+/// table-driven fallback.
+#[unsafe(export_name = c_symbol!("crc_hw_features"))]
+pub unsafe extern "C" fn crc_hw_features() -> u32 {
+    hw_features()
+}
+
+/// Restrict the CRC kernels to the hardware features in `mask` (see
+/// [`crc_hw_features`]) and return the resulting set.  Features the CPU lacks
+/// are never enabled, so 0 forces the table-driven fallback and `u32::MAX`
+/// restores full detection.  Results are identical whichever kernels run.
+#[unsafe(export_name = c_symbol!("crc_set_hw_features"))]
+pub unsafe extern "C" fn crc_set_hw_features(mask: u32) -> u32 {
+    let f = detect_hw_features() & mask;
+    CRC_HW.store(f, Ordering::Relaxed);
+    f
+}
+
+// === Three-way interleaved CRC32 instruction ================================
+
+/// Per-stream stripe lengths for the three-way interleaved path, longest
+/// first.  Buffers shorter than three of the shortest stripe run as a single
+/// stream.
+const STRIPES: [usize; 2] = [8192, 256];
+
+/// Byte-sliced tables advancing a raw 32-bit register over each of
+/// [`STRIPES`] zero bytes.
+type StripeShift = [[[u32; 256]; 4]; 2];
+
+const fn stripe_shift(p: &CrcParams) -> StripeShift {
+    [shift_tables(p, STRIPES[0]), shift_tables(p, STRIPES[1])]
+}
+
+/// Tables for multiplying a 32-bit register by `x^(8n) mod P`, one byte of
+/// the register at a time (the map is linear, so the bytes XOR together).
+const fn shift_tables(p: &CrcParams, n: usize) -> [[u32; 256]; 4] {
+    let k = p.xpow(8 * n as u64);
+    let mut t = [[0u32; 256]; 4];
+    let mut j = 0;
+    while j < 4 {
+        let mut b = 0;
+        while b < 256 {
+            t[j][b] = p.mul(k, (b as u64) << (8 * j)) as u32;
+            b += 1;
+        }
+        j += 1;
+    }
+    t
+}
+
+#[inline(always)]
+fn crc32c_shift(t: &[[u32; 256]; 4], r: u32) -> u32 {
+    t[0][(r & 0xFF) as usize]
+        ^ t[1][(r >> 8 & 0xFF) as usize]
+        ^ t[2][(r >> 16 & 0xFF) as usize]
+        ^ t[3][(r >> 24) as usize]
+}
+
+#[inline(always)]
+fn le64(c: &[u8]) -> u64 {
+    u64::from_le_bytes(c.try_into().unwrap())
+}
+
+/// Advance a raw 32-bit register over `data` with a hardware step function,
+/// splitting long buffers into three equal stripes so the three dependency
+/// chains overlap in the CRC unit's pipeline.
+///
+/// The stripes after the first start from a zero register; since the raw CRC
+/// is linear, `crc(A || B) = shift(crc(A), len(B)) ^ crc0(B)` stitches them
+/// back together.
+#[inline(always)]
+fn crc32_interleaved(
+    shifts: &StripeShift,
+    mut r: u32,
+    mut data: &[u8],
+    word: impl Fn(u32, u64) -> u32,
+    byte: impl Fn(u32, u8) -> u32,
+) -> u32 {
+    for (&stripe, shift) in STRIPES.iter().zip(shifts) {
+        while data.len() >= 3 * stripe {
+            let (a, rest) = data.split_at(stripe);
+            let (b, rest) = rest.split_at(stripe);
+            let (c, rest) = rest.split_at(stripe);
+            let (mut ra, mut rb, mut rc) = (r, 0, 0);
+            for ((x, y), z) in a
+                .chunks_exact(8)
+                .zip(b.chunks_exact(8))
+                .zip(c.chunks_exact(8))
+            {
+                ra = word(ra, le64(x));
+                rb = word(rb, le64(y));
+                rc = word(rc, le64(z));
+            }
+            r = crc32c_shift(shift, crc32c_shift(shift, ra) ^ rb) ^ rc;
+            data = rest;
+        }
+    }
+    let mut chunks = data.chunks_exact(8);
+    for c in &mut chunks {
+        r = word(r, le64(c));
+    }
+    chunks.remainder().iter().fold(r, |r, &b| byte(r, b))
+}
+
+// === CRC32C (Castagnoli) ====================================================
+
+static CRC32C: CrcParams = CrcParams::new(0x82F6_3B78, 32);
+static CRC32C_SHIFT: StripeShift = stripe_shift(&CRC32C);
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn crc32c_sse42(r: u32, data: &[u8]) -> u32 {
+    use core::arch::x86_64::{_mm_crc32_u8, _mm_crc32_u64};
+    crc32_interleaved(
+        &CRC32C_SHIFT,
+        r,
+        data,
+        |r, w| _mm_crc32_u64(r as u64, w) as u32,
+        |r, b| _mm_crc32_u8(r, b),
+    )
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "crc")]
+unsafe fn crc32c_armv8(r: u32, data: &[u8]) -> u32 {
+    use core::arch::aarch64::{__crc32cb, __crc32cd};
+    crc32_interleaved(
+        &CRC32C_SHIFT,
+        r,
+        data,
+        |r, w| __crc32cd(r, w),
+        |r, b| __crc32cb(r, b),
+    )
+}
+
+/// Advance the raw CRC32C register over `data`.
+fn crc32c_update_raw(r: u32, data: &[u8]) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    if hw_features() & CRC_HW_CRC32 != 0 {
+        return unsafe { crc32c_sse42(r, data) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if hw_features() & CRC_HW_CRC32 != 0 {
+        return unsafe { crc32c_armv8(r, data) };
+    }
+    CRC32C.update_raw(r as u64, data) as u32
+}
+
+/// Update a finalised CRC32C with `data` (Go's `crc32.Update` semantics).
+pub(crate) fn crc32c_update(crc: u32, data: &[u8]) -> u32 {
+    !crc32c_update_raw(!crc, data)
+}
+
+/// CRC32C of `A || B` from the finalised CRCs of `A` and `B` and `len(B)`.
+pub(crate) fn crc32c_combine(crc1: u32, crc2: u32, len2: usize) -> u32 {
+    CRC32C.combine(crc1 as u64, crc2 as u64, len2 as u64) as u32
+}
+
+/// CRC32C of the concatenation of chunks with finalised CRCs `crcs` and
+/// lengths `lens`.  The shift multiplier is reused across runs of equal
+/// lengths, so fixed-size chunking pays for `x^(8n) mod P` once.
+pub(crate) fn crc32c_combine_many(crcs: &[u32], lens: &[usize]) -> u32 {
+    let mut acc = 0u64;
+    let mut shift = (0usize, CRC32C.xpow(0));
+    for (&crc, &len) in crcs.iter().zip(lens) {
+        if len != shift.0 {
+            shift = (len, CRC32C.xpow((len as u64).wrapping_mul(8)));
+        }
+        acc = CRC32C.mul(shift.1, acc) ^ crc as u64;
+    }
+    acc as u32
+}
+
+// === CRC32 (IEEE) update & combine ===========================================
+
+#[cfg_attr(not(any(target_arch = "aarch64", test)), allow(dead_code))]
+static CRC32_IEEE_SHIFT: StripeShift = stripe_shift(&CRC32_IEEE);
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "crc")]
+unsafe fn crc32_ieee_armv8(r: u32, data: &[u8]) -> u32 {
+    use core::arch::aarch64::{__crc32b, __crc32d};
+    crc32_interleaved(
+        &CRC32_IEEE_SHIFT,
+        r,
+        data,
+        |r, w| __crc32d(r, w),
+        |r, b| __crc32b(r, b),
+    )
+}
+
+/// Update a finalised CRC32 (IEEE) with `data`.
+pub(crate) fn crc32_ieee_update(crc: u32, data: &[u8]) -> u32 {
+    #[cfg(target_arch = "aarch64")]
+    if hw_features() & CRC_HW_CRC32 != 0 {
+        return !unsafe { crc32_ieee_armv8(!crc, data) };
+    }
+    CRC32_IEEE.update(crc as u64, data) as u32
+}
+
+macro_rules! export_crc32_ieee_update {
+    ($name:ident) => {
+        #[doc = "Update CRC32 (IEEE, polynomial 0xEDB88320) with additional bytes.\n\n\
+                # Safety\n\
+                `ptr` must be null or valid for `len` bytes."]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, init: u32) -> u32 {
+            if ptr.is_null() || len == 0 {
+                return init;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            crc32_ieee_update(init, data)
+        }
+    };
+}
+
+export_crc32_ieee_update!(crc32_ieee_update_32);
+export_crc32_ieee_update!(crc32_ieee_update_64);
+
+/// Combine two finalised CRC32 (IEEE) digests: given `crc1 = crc(A)`,
+/// `crc2 = crc(B)` and `len2 = len(B)`, return `crc(A || B)`.
+#[unsafe(export_name = c_symbol!("crc32_ieee_combine"))]
+pub unsafe extern "C" fn crc32_ieee_combine(crc1: u32, crc2: u32, len2: usize) -> u32 {
+    CRC32_IEEE.combine(crc1 as u64, crc2 as u64, len2 as u64) as u32
+}
+
+// === CRC64 (XZ, NVMe) update & combine ======================================
+
+macro_rules! export_crc64 {
+    ($update:ident, $combine:ident, $params:ident, $label:literal) => {
+        #[doc = concat!("Update CRC64 (", $label, ") with additional bytes.\n\n",
+                "# Safety\n",
+                "`ptr` must be null or valid for `len` bytes.")]
+        #[unsafe(export_name = c_symbol!(stringify!($update)))]
+        pub unsafe extern "C" fn $update(ptr: *const u8, len: usize, init: u64) -> u64 {
+            if ptr.is_null() || len == 0 {
+                return init;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            $params.update(init, data)
+        }
+
+        #[doc = concat!("Combine two finalised CRC64 (", $label, ") digests: given `crc1 = crc(A)`, ",
+                "`crc2 = crc(B)` and `len2 = len(B)`, return `crc(A || B)`.")]
+        #[unsafe(export_name = c_symbol!(stringify!($combine)))]
+        pub unsafe extern "C" fn $combine(crc1: u64, crc2: u64, len2: usize) -> u64 {
+            $params.combine(crc1, crc2, len2 as u64)
+        }
+    };
+}
+
+export_crc64!(
+    crc64_xz_update,
+    crc64_xz_combine,
+    CRC64_XZ,
+    "XZ / ECMA-182, polynomial 0xC96C5795D7870F42 reflected"
+);
+export_crc64!(
+    crc64_nvme_update,
+    crc64_nvme_combine,
+    CRC64_NVME,
+    "NVMe, polynomial 0x9A6C9329AC4BC9B5 reflected"
+);
+
+// === CRC16 (CCITT, MODBUS) update ===========================================
+
+/// Update CRC16-CCITT (polynomial 0x1021, MSB-first, no final XOR; the
+/// "CCITT-FALSE" parameters) with additional bytes.  Start from 0xFFFF.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("crc16_ccitt_update"))]
+pub unsafe extern "C" fn crc16_ccitt_update(ptr: *const u8, len: usize, init: u16) -> u16 {
+    if ptr.is_null() || len == 0 {
+        return init;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    data.iter().fold(init, |r, &b| {
+        (r << 8) ^ CRC16_CCITT_TABLE[((r >> 8) as u8 ^ b) as usize]
+    })
+}
+
+/// Update CRC16-MODBUS (polynomial 0x8005 reflected, no final XOR) with
+/// additional bytes.  Start from 0xFFFF; the result goes on the wire low
+/// byte first.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("crc16_modbus_update"))]
+pub unsafe extern "C" fn crc16_modbus_update(ptr: *const u8, len: usize, init: u16) -> u16 {
+    if ptr.is_null() || len == 0 {
+        return init;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    CRC16_MODBUS.update_raw(init as u64, data) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bit-at-a-time reference over a finalised CRC.
+    fn bitwise(poly: u64, width: u32, crc: u64, data: &[u8]) -> u64 {
+        let mask = u64::MAX >> (64 - width);
+        let mut r = !crc & mask;
+        for &b in data {
+            r ^= b as u64;
+            for _ in 0..8 {
+                r = if r & 1 != 0 { (r >> 1) ^ poly } else { r >> 1 };
+            }
+        }
+        !r & mask
+    }
+
+    fn data(n: usize) -> Vec<u8> {
+        let mut x = 0x2545_F491_4F6C_DD1Du64;
+        (0..n)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_crc32c_interleaved_matches_bitwise() {
+        let buf = data(3 * 8192 + 3 * 256 + 100);
+        let lens = [
+            0usize,
+            1,
+            9,
+            767,
+            768,
+            769,
+            1000,
+            3 * 8192 - 1,
+            3 * 8192,
+            buf.len(),
+        ];
+        for len in lens {
+            for init in [0u32, 0xDEAD_BEEF] {
+                let want = bitwise(0x82F6_3B78, 32, init as u64, &buf[..len]) as u32;
+                assert_eq!(crc32c_update(init, &buf[..len]), want, "len {len}");
+                let table = !CRC32C.update_raw(!init as u64, &buf[..len]) as u32;
+                assert_eq!(table, want, "table len {len}");
+                let soft = !crc32_interleaved(
+                    &CRC32C_SHIFT,
+                    !init,
+                    &buf[..len],
+                    |r, w| CRC32C.update_table(r as u64, &w.to_le_bytes()) as u32,
+                    |r, b| CRC32C.update_table(r as u64, &[b]) as u32,
+                );
+                assert_eq!(soft, want, "interleaved len {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc32_ieee_interleaved_matches_bitwise() {
+        let buf = data(3 * 8192 + 3 * 256 + 100);
+        for len in [0usize, 767, 768, 1000, 3 * 8192, buf.len()] {
+            let want = bitwise(0xEDB8_8320, 32, 0, &buf[..len]) as u32;
+            let soft = !crc32_interleaved(
+                &CRC32_IEEE_SHIFT,
+                !0,
+                &buf[..len],
+                |r, w| CRC32_IEEE.update_table(r as u64, &w.to_le_bytes()) as u32,
+                |r, b| CRC32_IEEE.update_table(r as u64, &[b]) as u32,
+            );
+            assert_eq!(soft, want, "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_crc32c_combine_many() {
+        let buf = data(20000);
+        let whole = crc32c_update(0, &buf);
+        let mut lens = vec![1000usize; 12];
+        lens.extend([0, 1, 4095, 3000, 904]);
+        let mut rest = &buf[..];
+        let crcs: Vec<u32> = lens
+            .iter()
+            .map(|&n| {
+                let (chunk, tail) = rest.split_at(n);
+                rest = tail;
+                crc32c_update(0, chunk)
+            })
+            .collect();
+        assert!(rest.is_empty());
+        assert_eq!(crc32c_combine_many(&crcs, &lens), whole);
+        assert_eq!(crc32c_combine_many(&crcs[..1], &lens[..1]), crcs[0]);
+        assert_eq!(crc32c_combine_many(&[], &[]), 0);
+    }
+
+    #[test]
+    fn test_hw_features_restrict() {
+        let buf = data(30000);
+        let detected = detect_hw_features();
+        let want = (
+            crc32c_update(0, &buf),
+            crc32_ieee_update(0, &buf),
+            CRC64_XZ.update(0, &buf),
+        );
+        unsafe {
+            assert_eq!(crc_set_hw_features(0), 0);
+            assert_eq!(crc_hw_features(), 0);
+            let got = (
+                crc32c_update(0, &buf),
+                crc32_ieee_update(0, &buf),
+                CRC64_XZ.update(0, &buf),
+            );
+            assert_eq!(crc_set_hw_features(u32::MAX), detected);
+            assert_eq!(got, want);
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn test_armv8_crc_matches_table() {
+        if !aarch64_feature!("crc") {
+            return;
+        }
+        let buf = data(3 * 8192 + 3 * 256 + 100);
+        for len in [0usize, 7, 767, 768, 1000, 3 * 8192, buf.len()] {
+            let d = &buf[..len];
+            let want = CRC32C.update_table(0xFFFF_FFFF, d) as u32;
+            assert_eq!(unsafe { crc32c_armv8(!0, d) }, want, "crc32c len {len}");
+            let want = CRC32_IEEE.update_table(0xFFFF_FFFF, d) as u32;
+            assert_eq!(unsafe { crc32_ieee_armv8(!0, d) }, want, "ieee len {len}");
+        }
+    }
+
+    #[test]
+    fn test_crc32c_combine() {
+        let buf = data(2000);
+        let whole = crc32c_update(0, &buf);
+        for split in [0usize, 1, 800, 1999, 2000] {
+            let (a, b) = buf.split_at(split);
+            let (ca, cb) = (crc32c_update(0, a), crc32c_update(0, b));
+            assert_eq!(crc32c_combine(ca, cb, b.len()), whole, "split {split}");
+        }
+    }
+
+    #[test]
+    fn test_crc32_ieee_check_value() {
+        let check = b"123456789";
+        unsafe {
+            assert_eq!(
+                crc32_ieee_update_64(check.as_ptr(), check.len(), 0),
+                0xCBF4_3926
+            );
+            assert_eq!(crc32_ieee_update_32(core::ptr::null(), 0, 7), 7);
+        }
+    }
+
+    #[test]
+    fn test_crc32_ieee_matches_bitwise() {
+        let buf = data(3000);
+        for len in [
+            0usize, 1, 7, 8, 15, 16, 63, 64, 65, 100, 128, 200, 1000, 2999,
+        ] {
+            for init in [0u64, 0xDEAD_BEEF] {
+                let want = bitwise(0xEDB8_8320, 32, init, &buf[..len]);
+                assert_eq!(CRC32_IEEE.update(init, &buf[..len]), want, "len {len}");
+                let m = CRC32_IEEE.mask();
+                let table = !CRC32_IEEE.update_table(!init & m, &buf[..len]) & m;
+                assert_eq!(table, want, "table len {len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc32_ieee_combine() {
+        let buf = data(1500);
+        let whole = unsafe { crc32_ieee_update_64(buf.as_ptr(), buf.len(), 0) };
+        for split in [0usize, 1, 64, 700, 1499, 1500] {
+            let (a, b) = buf.split_at(split);
+            let (ca, cb) = unsafe {
+                (
+                    crc32_ieee_update_64(a.as_ptr(), a.len(), 0),
+                    crc32_ieee_update_64(b.as_ptr(), b.len(), 0),
+                )
+            };
+            assert_eq!(
+                unsafe { crc32_ieee_combine(ca, cb, b.len()) },
+                whole,
+                "split {split}"
+            );
+            assert_eq!(
+                unsafe { crc32_ieee_update_64(b.as_ptr(), b.len(), ca) },
+                whole
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc64_check_values() {
+        let check = b"123456789";
+        unsafe {
+            assert_eq!(
+                crc64_xz_update(check.as_ptr(), check.len(), 0),
+                0x995D_C9BB_DF19_39FA
+            );
+            assert_eq!(
+                crc64_nvme_update(check.as_ptr(), check.len(), 0),
+                0xAE8B_1486_0A79_9888
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc64_matches_bitwise_and_combines() {
+        let buf = data(2100);
+        for (params, poly) in [
+            (&CRC64_XZ, 0xC96C_5795_D787_0F42),
+            (&CRC64_NVME, 0x9A6C_9329_AC4B_C9B5),
+        ] {
+            for len in [0usize, 1, 9, 16, 63, 64, 80, 129, 1000, 2100] {
+                assert_eq!(
+                    params.update(0, &buf[..len]),
+                    bitwise(poly, 64, 0, &buf[..len]),
+                    "len {len}"
+                );
+            }
+            let whole = params.update(0, &buf);
+            for split in [0usize, 5, 64, 1027, 2100] {
+                let (a, b) = buf.split_at(split);
+                let (ca, cb) = (params.update(0, a), params.update(0, b));
+                assert_eq!(
+                    params.combine(ca, cb, b.len() as u64),
+                    whole,
+                    "split {split}"
+                );
+            }
+        }
+        unsafe {
+            let (a, b) = buf.split_at(700);
+            let ca = crc64_nvme_update(a.as_ptr(), a.len(), 0);
+            let cb = crc64_nvme_update(b.as_ptr(), b.len(), 0);
+            assert_eq!(
+                crc64_nvme_combine(ca, cb, b.len()),
+                crc64_nvme_update(b.as_ptr(), b.len(), ca)
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc16() {
+        let check = b"123456789";
+        let buf = data(500);
+        unsafe {
+            assert_eq!(
+                crc16_ccitt_update(check.as_ptr(), check.len(), 0xFFFF),
+                0x29B1
+            );
+            assert_eq!(
+                crc16_modbus_update(check.as_ptr(), check.len(), 0xFFFF),
+                0x4B37
+            );
+            for len in [0usize, 3, 64, 100, 500] {
+                // No final XOR: undo the reference's complement on both sides.
+                let want = !bitwise(0xA001, 16, !0xFFFF, &buf[..len]) & 0xFFFF;
+                assert_eq!(
+                    crc16_modbus_update(buf.as_ptr(), len, 0xFFFF) as u64,
+                    want,
+                    "len {len}"
+                );
+            }
+            // Chunked updates chain.
+            let whole = crc16_ccitt_update(buf.as_ptr(), buf.len(), 0xFFFF);
+            let head = crc16_ccitt_update(buf.as_ptr(), 123, 0xFFFF);
+            assert_eq!(
+                crc16_ccitt_update(buf[123..].as_ptr(), buf.len() - 123, head),
+                whole
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! CSV stage-1 structural scanner: one pass producing per-64-byte bitmasks of
+//! field delimiters, quotes and record separators outside quoted fields.
+use core::simd::Simd;
+use core::simd::prelude::SimdPartialEq;
+
+/// Bytes per mask word.
+const BLOCK: usize = 64;
+
+/// Inclusive prefix XOR of a bitmask: bit `i` of the result is the parity of
+/// bits `0..=i` of `x` (the portable form of a carry-less multiply by !0).
+#[inline(always)]
+pub(crate) fn prefix_xor(mut x: u64) -> u64 {
+    x ^= x << 1;
+    x ^= x << 2;
+    x ^= x << 4;
+    x ^= x << 8;
+    x ^= x << 16;
+    x ^= x << 32;
+    x
+}
+
+/// Structural masks of one block.
+struct CsvBlock {
+    delims: u64,
+    quotes: u64,
+    newlines: u64,
+}
+
+/// Classify one 64-byte block; `in_quote` is all-ones when the block starts
+/// inside a quoted field and is updated for the next block.
+#[inline(always)]
+fn csv_block(block: &[u8; BLOCK], delim: u8, quote: u8, in_quote: &mut u64) -> CsvBlock {
+    let v = Simd::<u8, BLOCK>::from_array(*block);
+    let quotes = v.simd_eq(Simd::splat(quote)).to_bitmask();
+    let delims = v.simd_eq(Simd::splat(delim)).to_bitmask();
+    let newlines = v.simd_eq(Simd::splat(b'\n')).to_bitmask();
+    // A doubled quote ("") toggles twice, so escaped quotes need no special
+    // handling: the region between them is empty.
+    let inside = prefix_xor(quotes) ^ *in_quote;
+    *in_quote = ((inside as i64) >> 63) as u64;
+    CsvBlock {
+        delims: delims & !inside,
+        quotes,
+        newlines: newlines & !inside,
+    }
+}
+
+/// Scan `len` bytes of CSV and write three mask words per 64-byte block (the
+/// last block may be partial) to `out`, as `[delims, quotes, newlines]`
+/// triples: bit `i` of block `b`'s words refers to byte `64 * b + i`.
+///
+/// * delims: `delim` bytes outside quoted fields.
+/// * quotes: every `quote` byte.
+/// * newlines: `\n` bytes outside quoted fields.
+///
+/// `state` carries the in-quote flag between calls so a large input can be
+/// scanned in chunks; start with 0.  Every chunk but the last must be a
+/// multiple of 64 bytes.  Returns the number of blocks written,
+/// `len.div_ceil(64)`.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `state` must be valid; `out`
+/// must be valid for `3 * len.div_ceil(64)` u64 writes.
+#[unsafe(export_name = c_symbol!("csv_scan_masks"))]
+pub unsafe extern "C" fn csv_scan_masks(
+    ptr: *const u8,
+    len: usize,
+    delim: u8,
+    quote: u8,
+    state: *mut u64,
+    out: *mut u64,
+) -> usize {
+    if ptr.is_null() || state.is_null() || out.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let out = core::slice::from_raw_parts_mut(out, 3 * len.div_ceil(BLOCK));
+    let mut in_quote = if *state != 0 { !0 } else { 0 };
+    let mut chunks = data.chunks_exact(BLOCK);
+    let mut triples = out.chunks_exact_mut(3);
+    for (chunk, o) in (&mut chunks).zip(&mut triples) {
+        let m = csv_block(chunk.try_into().unwrap(), delim, quote, &mut in_quote);
+        o.copy_from_slice(&[m.delims, m.quotes, m.newlines]);
+    }
+    let rem = chunks.remainder();
+    if let Some(o) = triples.next().filter(|_| !rem.is_empty()) {
+        let mut block = [0u8; BLOCK];
+        block[..rem.len()].copy_from_slice(rem);
+        let valid = (1u64 << rem.len()) - 1;
+        // Padding bytes must neither match nor flip the quote state.
+        let carry = in_quote;
+        let m = csv_block(&block, delim, quote, &mut in_quote);
+        let quotes = m.quotes & valid;
+        in_quote = if quotes.count_ones() & 1 == 1 {
+            !carry
+        } else {
+            carry
+        };
+        o.copy_from_slice(&[m.delims & valid, quotes, m.newlines & valid]);
+    }
+    *state = in_quote & 1;
+    len.div_ceil(BLOCK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scalar reference: returns (delims, quotes, newlines) as position lists.
+    fn reference(data: &[u8], delim: u8, quote: u8) -> [Vec<usize>; 3] {
+        let mut r: [Vec<usize>; 3] = Default::default();
+        let mut inside = false;
+        for (i, &b) in data.iter().enumerate() {
+            if b == quote {
+                inside = !inside;
+                r[1].push(i);
+            } else if !inside && b == delim {
+                r[0].push(i);
+            } else if !inside && b == b'\n' {
+                r[2].push(i);
+            }
+        }
+        r
+    }
+
+    fn positions(words: &[u64], len: usize) -> Vec<usize> {
+        (0..len)
+            .filter(|&i| words[i / 64] >> (i % 64) & 1 == 1)
+            .collect()
+    }
+
+    fn scan(data: &[u8], chunk: usize) -> [Vec<usize>; 3] {
+        let blocks = data.len().div_ceil(64);
+        let mut out = vec![0u64; 3 * blocks];
+        let mut state = 0u64;
+        let mut b = 0;
+        for c in data.chunks(chunk) {
+            let o = out[3 * b..].as_mut_ptr();
+            b += unsafe { csv_scan_masks(c.as_ptr(), c.len(), b',', b'"', &mut state, o) };
+        }
+        assert_eq!(b, blocks);
+        [0, 1, 2].map(|k| {
+            let words: Vec<u64> = out.iter().skip(k).step_by(3).copied().collect();
+            positions(&words, data.len())
+        })
+    }
+
+    #[test]
+    fn test_csv_scan_matches_reference() {
+        let rows = [
+            &b"id,name,comment\n"[..],
+            b"1,\"Smith, John\",\"said \"\"hi\"\"\"\n",
+            b"2,plain,\"multi\nline, field\"\n",
+            b"3,,\n",
+            b"4,\"\",\"a,b,c,d,e,f,g,h,i,j,k,l,m,n,o,p,q,r,s,t,u,v,w,x,y,z\"\n",
+        ];
+        let data: Vec<u8> = rows
+            .iter()
+            .cycle()
+            .take(40)
+            .flat_map(|r| r.iter().copied())
+            .collect();
+        let want = reference(&data, b',', b'"');
+        assert_eq!(scan(&data, data.len()), want);
+        assert_eq!(scan(&data, 64), want);
+        assert_eq!(scan(&data, 192), want);
+        // Partial last block.
+        assert_eq!(
+            scan(&data[..data.len() - 7], 128),
+            reference(&data[..data.len() - 7], b',', b'"')
+        );
+    }
+
+    #[test]
+    fn test_prefix_xor() {
+        assert_eq!(prefix_xor(0b1001), 0b0111);
+        assert_eq!(prefix_xor(1), !0);
+        assert_eq!(prefix_xor(1 << 63), 1 << 63);
+    }
+}
@@ -0,0 +1,227 @@
+//! Integer column codecs: delta coding (decode is a prefix sum) and the
+//! zigzag transform that maps small signed deltas to small unsigned values.
+use core::simd::prelude::{SimdInt, SimdUint};
+use core::simd::{Simd, Swizzle};
+
+use crate::{load_simd, store_simd};
+
+// === Delta coding ============================================================
+
+/// `N`-lane window of `concat(first, second)` starting `K` lanes before
+/// `second`: lanes `0..K` are the last `K` of `first`, the rest the leading
+/// lanes of `second`.
+struct ShiftIn<const K: usize>;
+
+impl<const N: usize, const K: usize> Swizzle<N> for ShiftIn<K> {
+    const INDEX: [usize; N] = {
+        let mut idx = [0; N];
+        let mut i = 0;
+        while i < N {
+            idx[i] = N - K + i;
+            i += 1;
+        }
+        idx
+    };
+}
+
+macro_rules! export_delta {
+    ($encode:ident, $decode:ident, $t:ty, $lanes:expr) => {
+        #[doc = concat!(
+            "Delta-encode `len` `", stringify!($t), "` values: `dst[i] = src[i] - src[i - 1]` with wrapping arithmetic, taking `base` as `src[-1]` (pass the last value of the previous chunk to encode a stream in pieces).\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($encode)))]
+        pub unsafe extern "C" fn $encode(src: *const $t, len: usize, base: $t, dst: *mut $t) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            // Carry the previous vector instead of reloading `src[i - 1]`,
+            // which an in-place call has already overwritten.
+            let mut prev = Simd::<$t, $lanes>::splat(base);
+            let mut i = 0;
+            while i + $lanes <= len {
+                let cur = load_simd::<$t, $lanes>(src.add(i));
+                store_simd(dst.add(i), cur - ShiftIn::<1>::concat_swizzle(prev, cur));
+                prev = cur;
+                i += $lanes;
+            }
+            let mut last = if i == 0 { base } else { prev[$lanes - 1] };
+            while i < len {
+                let x = *src.add(i);
+                *dst.add(i) = x.wrapping_sub(last);
+                last = x;
+                i += 1;
+            }
+        }
+
+        #[doc = concat!(
+            "Decode `len` delta-coded `", stringify!($t), "` values (a wrapping prefix sum): `dst[i] = base + src[0] + … + src[i]`.  Inverse of `", stringify!($encode), "` for the same `base`.\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($decode)))]
+        pub unsafe extern "C" fn $decode(src: *const $t, len: usize, base: $t, dst: *mut $t) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            let zero = Simd::<$t, $lanes>::splat(0);
+            let mut acc = base;
+            let mut i = 0;
+            while i + $lanes <= len {
+                // Log-step inclusive scan within the vector, then add the
+                // running total carried in from the previous one.
+                let mut v = load_simd::<$t, $lanes>(src.add(i));
+                v += ShiftIn::<1>::concat_swizzle(zero, v);
+                v += ShiftIn::<2>::concat_swizzle(zero, v);
+                v += ShiftIn::<4>::concat_swizzle(zero, v);
+                if $lanes > 8 {
+                    v += ShiftIn::<8>::concat_swizzle(zero, v);
+                }
+                v += Simd::splat(acc);
+                store_simd(dst.add(i), v);
+                acc = v[$lanes - 1];
+                i += $lanes;
+            }
+            while i < len {
+                acc = acc.wrapping_add(*src.add(i));
+                *dst.add(i) = acc;
+                i += 1;
+            }
+        }
+    };
+}
+export_delta!(delta_encode_i32, delta_decode_i32, i32, 16);
+export_delta!(delta_encode_i64, delta_decode_i64, i64, 8);
+
+// === Zigzag ==================================================================
+
+// Zigzag interleaves signed values as 0, -1, 1, -2, 2, … → 0, 1, 2, 3, 4, …
+// so a varint or bit-packing stage sees small magnitudes either way.
+
+macro_rules! export_zigzag {
+    ($encode:ident, $decode:ident, $s:ty, $u:ty, $lanes:expr) => {
+        #[doc = concat!(
+            "Zigzag-encode `len` `", stringify!($s), "` values into `", stringify!($u), "`: `(x << 1) ^ (x >> ", stringify!($s), "::BITS - 1)`.\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($encode)))]
+        pub unsafe extern "C" fn $encode(src: *const $s, len: usize, dst: *mut $u) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            let sign = Simd::<$s, $lanes>::splat(<$s>::BITS as $s - 1);
+            let mut i = 0;
+            while i + $lanes <= len {
+                let x = load_simd::<$s, $lanes>(src.add(i));
+                store_simd(dst.add(i), ((x << 1) ^ (x >> sign)).cast::<$u>());
+                i += $lanes;
+            }
+            while i < len {
+                let x = *src.add(i);
+                *dst.add(i) = ((x << 1) ^ (x >> (<$s>::BITS - 1))) as $u;
+                i += 1;
+            }
+        }
+
+        #[doc = concat!(
+            "Zigzag-decode `len` `", stringify!($u), "` values back into `", stringify!($s), "`: `(u >> 1) ^ -(u & 1)`.\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `len` values.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($decode)))]
+        pub unsafe extern "C" fn $decode(src: *const $u, len: usize, dst: *mut $s) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            let (zero, one) = (Simd::<$u, $lanes>::splat(0), Simd::splat(1));
+            let mut i = 0;
+            while i + $lanes <= len {
+                let u = load_simd::<$u, $lanes>(src.add(i));
+                store_simd(dst.add(i), ((u >> one) ^ (zero - (u & one))).cast::<$s>());
+                i += $lanes;
+            }
+            while i < len {
+                let u = *src.add(i);
+                *dst.add(i) = ((u >> 1) as $s) ^ -((u & 1) as $s);
+                i += 1;
+            }
+        }
+    };
+}
+export_zigzag!(zigzag_encode_i32, zigzag_decode_i32, i32, u32, 16);
+export_zigzag!(zigzag_encode_i64, zigzag_decode_i64, i64, u64, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_round_trip() {
+        for len in [1usize, 7, 16, 33, 100] {
+            let src: Vec<i64> = (0..len as i64)
+                .map(|i| 1_700_000_000_000 + i * 1000 + (i * i) % 7 - 3)
+                .collect();
+            let want: Vec<i64> = (0..len)
+                .map(|i| src[i].wrapping_sub(if i == 0 { 5 } else { src[i - 1] }))
+                .collect();
+            let mut enc = vec![0i64; len];
+            unsafe { delta_encode_i64(src.as_ptr(), len, 5, enc.as_mut_ptr()) };
+            assert_eq!(enc, want, "len={len}");
+
+            // In place, and split into two chunks chained through `base`.
+            let mut buf = enc.clone();
+            let mid = len / 2;
+            unsafe {
+                delta_decode_i64(buf.as_ptr(), mid, 5, buf.as_mut_ptr());
+                let base = if mid == 0 { 5 } else { buf[mid - 1] };
+                delta_decode_i64(
+                    buf[mid..].as_ptr(),
+                    len - mid,
+                    base,
+                    buf[mid..].as_mut_ptr(),
+                );
+            }
+            assert_eq!(buf, src, "len={len}");
+        }
+
+        // i32 wraps instead of overflowing.
+        let src: Vec<i32> = (0..40)
+            .map(|i| if i % 2 == 0 { i32::MAX } else { i32::MIN + i })
+            .collect();
+        let mut buf = src.clone();
+        unsafe {
+            delta_encode_i32(buf.as_ptr(), 40, 0, buf.as_mut_ptr());
+            assert_eq!(buf[1], i32::MIN.wrapping_add(1).wrapping_sub(i32::MAX));
+            delta_decode_i32(buf.as_ptr(), 40, 0, buf.as_mut_ptr());
+        }
+        assert_eq!(buf, src);
+    }
+
+    #[test]
+    fn test_zigzag() {
+        let src: Vec<i32> = [0, -1, 1, -2, 2, i32::MAX, i32::MIN]
+            .into_iter()
+            .chain(-20..20)
+            .collect();
+        let mut enc = vec![0u32; src.len()];
+        let mut dec = vec![0i32; src.len()];
+        unsafe {
+            zigzag_encode_i32(src.as_ptr(), src.len(), enc.as_mut_ptr());
+            zigzag_decode_i32(enc.as_ptr(), enc.len(), dec.as_mut_ptr());
+        }
+        assert_eq!(&enc[..7], &[0, 1, 2, 3, 4, u32::MAX - 1, u32::MAX]);
+        assert_eq!(dec, src);
+
+        let mut buf: Vec<i64> = (-40..40).map(|i| i * 1_000_000_007).collect();
+        let orig = buf.clone();
+        unsafe {
+            zigzag_encode_i64(buf.as_ptr(), buf.len(), buf.as_mut_ptr() as *mut u64);
+            assert_eq!(buf[40] as u64, 0);
+            assert_eq!(buf[39] as u64, 2 * 1_000_000_007 - 1);
+            zigzag_decode_i64(buf.as_ptr() as *const u64, buf.len(), buf.as_mut_ptr());
+        }
+        assert_eq!(buf, orig);
+    }
+}
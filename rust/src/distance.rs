@@ -0,0 +1,570 @@
+//! Distance and similarity kernels over embedding vectors, for brute-force
+//! nearest-neighbour scoring.
+use core::simd::Simd;
+use core::simd::prelude::{SimdInt, SimdUint};
+
+// === f32 distance and similarity =============================================
+// These need `sqrt` and fused multiply-add, which `core` lacks, so they are
+// `std`-only.  FMA is used when the running CPU has it (checked at runtime on
+// x86_64, baseline on aarch64); results can differ from the non-FMA path in
+// the last bits.
+
+#[cfg(feature = "std")]
+use std::simd::StdFloat;
+#[cfg(feature = "std")]
+use std::simd::prelude::SimdFloat;
+
+#[cfg(feature = "std")]
+const SIM_LANES: usize = 16;
+
+#[cfg(feature = "std")]
+type V = Simd<f32, SIM_LANES>;
+
+/// `acc + x * y`, fused when `FMA`.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn madd<const FMA: bool>(x: V, y: V, acc: V) -> V {
+    if FMA { x.mul_add(y, acc) } else { acc + x * y }
+}
+
+/// Whole vectors of `a` and `b` side by side, then the zero-padded tails
+/// (which add nothing to any of the sums below).
+#[cfg(feature = "std")]
+#[inline(always)]
+fn pairs<'a>(a: &'a [f32], b: &'a [f32]) -> impl Iterator<Item = (V, V)> + 'a {
+    let (ca, cb) = (a.chunks_exact(SIM_LANES), b.chunks_exact(SIM_LANES));
+    let tail = (
+        V::load_or_default(ca.remainder()),
+        V::load_or_default(cb.remainder()),
+    );
+    ca.zip(cb)
+        .map(|(x, y)| (V::from_slice(x), V::from_slice(y)))
+        .chain(core::iter::once(tail))
+}
+
+/// Squared Euclidean distance.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn l2_sq_impl<const FMA: bool>(a: &[f32], b: &[f32]) -> f32 {
+    let mut acc = V::splat(0.0);
+    for (x, y) in pairs(a, b) {
+        let d = x - y;
+        acc = madd::<FMA>(d, d, acc);
+    }
+    acc.reduce_sum()
+}
+
+/// Euclidean distances from `query` to each `query.len()`-element row of
+/// `matrix`.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn l2_rows_impl<const FMA: bool>(query: &[f32], matrix: &[f32], out: &mut [f32]) {
+    for (row, o) in matrix.chunks_exact(query.len()).zip(out) {
+        *o = l2_sq_impl::<FMA>(query, row).sqrt();
+    }
+}
+
+/// `a . b`, `a . a` and `b . b`.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn cosine_parts_impl<const FMA: bool>(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    let (mut ab, mut aa, mut bb) = (V::splat(0.0), V::splat(0.0), V::splat(0.0));
+    for (x, y) in pairs(a, b) {
+        ab = madd::<FMA>(x, y, ab);
+        aa = madd::<FMA>(x, x, aa);
+        bb = madd::<FMA>(y, y, bb);
+    }
+    (ab.reduce_sum(), aa.reduce_sum(), bb.reduce_sum())
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn l2_rows_fma(query: &[f32], matrix: &[f32], out: &mut [f32]) {
+    l2_rows_impl::<true>(query, matrix, out)
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn cosine_parts_fma(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    cosine_parts_impl::<true>(a, b)
+}
+
+/// Whether to take the `target_feature` FMA paths.
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn fma_available() -> bool {
+    x86_feature!("avx2") && x86_feature!("fma")
+}
+
+#[cfg(feature = "std")]
+fn l2_rows(query: &[f32], matrix: &[f32], out: &mut [f32]) {
+    #[cfg(target_arch = "x86_64")]
+    if fma_available() {
+        return unsafe { l2_rows_fma(query, matrix, out) };
+    }
+    l2_rows_impl::<{ cfg!(target_arch = "aarch64") }>(query, matrix, out)
+}
+
+#[cfg(feature = "std")]
+fn cosine_parts(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    #[cfg(target_arch = "x86_64")]
+    if fma_available() {
+        return unsafe { cosine_parts_fma(a, b) };
+    }
+    cosine_parts_impl::<{ cfg!(target_arch = "aarch64") }>(a, b)
+}
+
+#[cfg(feature = "std")]
+/// Euclidean (L2) distance between the `dim`-element vectors `a` and `b`.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `dim` values.
+#[unsafe(export_name = c_symbol!("l2_distance_f32"))]
+pub unsafe extern "C" fn l2_distance_f32(a: *const f32, b: *const f32, dim: usize) -> f32 {
+    if a.is_null() || b.is_null() || dim == 0 {
+        return 0.0;
+    }
+    let a = core::slice::from_raw_parts(a, dim);
+    let b = core::slice::from_raw_parts(b, dim);
+    let mut out = [0.0];
+    l2_rows(a, b, &mut out);
+    out[0]
+}
+
+#[cfg(feature = "std")]
+/// Write to `out[r]` the Euclidean distance between the `dim`-element
+/// `query` and row `r` of the row-major `nrows x dim` `matrix`: one call
+/// for a whole shard instead of one per row.
+///
+/// # Safety
+/// `query` must be valid for `dim` values, `matrix` for `nrows * dim` and
+/// `out` for `nrows`.
+#[unsafe(export_name = c_symbol!("l2_distance_batch_f32"))]
+pub unsafe extern "C" fn l2_distance_batch_f32(
+    query: *const f32,
+    dim: usize,
+    matrix: *const f32,
+    nrows: usize,
+    out: *mut f32,
+) {
+    if out.is_null() || nrows == 0 {
+        return;
+    }
+    let out = core::slice::from_raw_parts_mut(out, nrows);
+    if query.is_null() || matrix.is_null() || dim == 0 {
+        out.fill(0.0);
+        return;
+    }
+    let query = core::slice::from_raw_parts(query, dim);
+    let matrix = core::slice::from_raw_parts(matrix, nrows * dim);
+    l2_rows(query, matrix, out);
+}
+
+#[cfg(feature = "std")]
+/// Cosine similarity `a . b / (|a| |b|)` of the `dim`-element vectors `a`
+/// and `b`, in `[-1, 1]`; 0 when either vector is all zeros.  The norms are
+/// combined in f64, so large vectors do not overflow.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `dim` values.
+#[unsafe(export_name = c_symbol!("cosine_similarity_f32"))]
+pub unsafe extern "C" fn cosine_similarity_f32(a: *const f32, b: *const f32, dim: usize) -> f32 {
+    if a.is_null() || b.is_null() || dim == 0 {
+        return 0.0;
+    }
+    let a = core::slice::from_raw_parts(a, dim);
+    let b = core::slice::from_raw_parts(b, dim);
+    let (ab, aa, bb) = cosine_parts(a, b);
+    let norms = aa as f64 * bb as f64;
+    if norms == 0.0 {
+        return 0.0;
+    }
+    (ab as f64 / norms.sqrt()) as f32
+}
+
+// === Quantized int8 dot products ==============================================
+// Products accumulate in i32 modulo 2^32, so only inputs of more than 2^17
+// elements can wrap.  The instructions that multiply bytes and add groups of
+// four straight into i32 lanes take one signed and one unsigned operand on
+// x86 (VNNI `vpdpbusd`) and two signed ones on aarch64 (`sdot`).  The other
+// signedness is reached by flipping the top bit of `a`, which offsets every
+// element by 128, and taking `128 * sum(b)` back out with a second product.
+
+const DOT_LANES: usize = 32;
+
+/// Element of `a` as a signed or unsigned value.
+#[inline(always)]
+fn widen<const SIGNED: bool>(x: u8) -> i32 {
+    if SIGNED { x as i8 as i32 } else { x as i32 }
+}
+
+fn dot_scalar<const SIGNED: bool>(a: &[u8], b: &[i8]) -> i32 {
+    a.iter().zip(b).fold(0i32, |s, (&x, &y)| {
+        s.wrapping_add(widen::<SIGNED>(x) * y as i32)
+    })
+}
+
+/// Widening multiply-add without the dot-product instructions.
+#[inline(always)]
+fn dot_portable<const SIGNED: bool>(a: &[u8], b: &[i8]) -> i32 {
+    let (ca, cb) = (a.chunks_exact(DOT_LANES), b.chunks_exact(DOT_LANES));
+    let tail = dot_scalar::<SIGNED>(ca.remainder(), cb.remainder());
+    let mut acc = Simd::<i32, DOT_LANES>::splat(0);
+    for (x, y) in ca.zip(cb) {
+        let x = Simd::<u8, DOT_LANES>::from_slice(x);
+        let x: Simd<i32, DOT_LANES> = if SIGNED {
+            x.cast::<i8>().cast()
+        } else {
+            x.cast()
+        };
+        acc += x * Simd::<i8, DOT_LANES>::from_slice(y).cast();
+    }
+    acc.reduce_sum().wrapping_add(tail)
+}
+
+/// `vpdpbusd` loop over `$lanes`-byte vectors; the unsigned operand is `a`,
+/// flipped to `a + 128` when `SIGNED`.
+#[cfg(target_arch = "x86_64")]
+macro_rules! vnni_dot {
+    ($name:ident, $features:literal, $lanes:expr, $dpbusd:ident) => {
+        #[target_feature(enable = $features)]
+        unsafe fn $name<const SIGNED: bool>(a: &[u8], b: &[i8]) -> i32 {
+            use core::arch::x86_64::$dpbusd as dpbusd;
+            let flip = Simd::<u8, $lanes>::splat(if SIGNED { 0x80 } else { 0 });
+            let (ca, cb) = (a.chunks_exact($lanes), b.chunks_exact($lanes));
+            let tail = dot_scalar::<SIGNED>(ca.remainder(), cb.remainder());
+            let mut acc = Simd::<i32, { $lanes / 4 }>::splat(0);
+            let mut bias = acc;
+            for (x, y) in ca.zip(cb) {
+                let x = Simd::<u8, $lanes>::from_slice(x) ^ flip;
+                let y = Simd::<i8, $lanes>::from_slice(y);
+                acc = dpbusd(acc.into(), x.into(), y.into()).into();
+                if SIGNED {
+                    bias = dpbusd(bias.into(), flip.into(), y.into()).into();
+                }
+            }
+            (acc - bias).reduce_sum().wrapping_add(tail)
+        }
+    };
+}
+#[cfg(target_arch = "x86_64")]
+vnni_dot!(dot_vnni256, "avx2,avxvnni", 32, _mm256_dpbusd_avx_epi32);
+#[cfg(target_arch = "x86_64")]
+vnni_dot!(dot_vnni512, "avx512f,avx512vnni", 64, _mm512_dpbusd_epi32);
+
+/// `sdot` loop over 16-byte vectors; `a` is flipped to `a - 128` unless
+/// `SIGNED`.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon,dotprod")]
+unsafe fn dot_sdot<const SIGNED: bool>(a: &[u8], b: &[i8]) -> i32 {
+    let sdot = |acc: Simd<i32, 4>, x: Simd<i8, 16>, y: Simd<i8, 16>| {
+        let mut acc = acc;
+        core::arch::asm!(
+            "sdot {acc:v}.4s, {x:v}.16b, {y:v}.16b",
+            acc = inout(vreg) acc,
+            x = in(vreg) x,
+            y = in(vreg) y,
+            options(pure, nomem, nostack, preserves_flags),
+        );
+        acc
+    };
+    let flip = Simd::<u8, 16>::splat(if SIGNED { 0 } else { 0x80 });
+    let (ca, cb) = (a.chunks_exact(16), b.chunks_exact(16));
+    let tail = dot_scalar::<SIGNED>(ca.remainder(), cb.remainder());
+    let (mut acc, mut sum_b) = (Simd::<i32, 4>::splat(0), Simd::<i32, 4>::splat(0));
+    for (x, y) in ca.zip(cb) {
+        let x = (Simd::<u8, 16>::from_slice(x) ^ flip).cast::<i8>();
+        let y = Simd::<i8, 16>::from_slice(y);
+        acc = sdot(acc, x, y);
+        if !SIGNED {
+            sum_b = sdot(sum_b, Simd::splat(1), y);
+        }
+    }
+    (acc + (sum_b << 7)).reduce_sum().wrapping_add(tail)
+}
+
+/// Dot product through the widest dot-product instruction the CPU has.
+fn dot_impl<const SIGNED: bool>(a: &[u8], b: &[i8]) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86_feature!("avx512vnni") && crate::avx512::available() {
+            return unsafe { dot_vnni512::<SIGNED>(a, b) };
+        }
+        if x86_feature!("avxvnni") {
+            return unsafe { dot_vnni256::<SIGNED>(a, b) };
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    if aarch64_feature!("dotprod") {
+        return unsafe { dot_sdot::<SIGNED>(a, b) };
+    }
+    dot_portable::<SIGNED>(a, b)
+}
+
+/// Dot product of two `len`-element i8 vectors, accumulated in i32 modulo
+/// 2^32 (exact up to 2^17 elements).  Uses AVX-512 VNNI / AVX-VNNI or the
+/// aarch64 dot-product extension when present.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len` values.
+#[unsafe(export_name = c_symbol!("dot_i8"))]
+pub unsafe extern "C" fn dot_i8(a: *const i8, b: *const i8, len: usize) -> i32 {
+    if a.is_null() || b.is_null() || len == 0 {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a as *const u8, len);
+    let b = core::slice::from_raw_parts(b, len);
+    dot_impl::<true>(a, b)
+}
+
+/// Dot product of a `len`-element u8 vector with an i8 one (the
+/// asymmetric activation x weight form), accumulated like [`dot_i8`].
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len` values.
+#[unsafe(export_name = c_symbol!("dot_u8_i8"))]
+pub unsafe extern "C" fn dot_u8_i8(a: *const u8, b: *const i8, len: usize) -> i32 {
+    if a.is_null() || b.is_null() || len == 0 {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    dot_impl::<false>(a, b)
+}
+
+// === Hamming distance ========================================================
+
+const HAMMING_LANES: usize = 32;
+
+/// Number of bits that differ between `a` and `b`: whole vectors through
+/// per-lane byte counters, the rest a u64 word (then a byte) at a time, so a
+/// short signature costs a few scalar popcounts.
+#[inline(always)]
+fn hamming_impl(a: &[u8], b: &[u8]) -> u64 {
+    type V = Simd<u8, HAMMING_LANES>;
+    let (mut ca, mut cb) = (a.chunks_exact(HAMMING_LANES), b.chunks_exact(HAMMING_LANES));
+    let mut total = 0u64;
+    // Per-lane u8 counters gain at most 8 per step; flush before they wrap.
+    loop {
+        let mut acc = V::splat(0);
+        let mut steps = 0;
+        for (x, y) in (&mut ca).zip(&mut cb).take(31) {
+            acc += (V::from_slice(x) ^ V::from_slice(y)).count_ones();
+            steps += 1;
+        }
+        total += acc.cast::<u32>().reduce_sum() as u64;
+        if steps < 31 {
+            break;
+        }
+    }
+    let word = |w: &[u8]| u64::from_ne_bytes(w.try_into().unwrap());
+    let (wa, wb) = (
+        ca.remainder().chunks_exact(8),
+        cb.remainder().chunks_exact(8),
+    );
+    let bytes: u64 = wa
+        .remainder()
+        .iter()
+        .zip(wb.remainder())
+        .map(|(x, y)| (x ^ y).count_ones() as u64)
+        .sum();
+    let words: u64 = wa
+        .zip(wb)
+        .map(|(x, y)| (word(x) ^ word(y)).count_ones() as u64)
+        .sum();
+    total + words + bytes
+}
+
+#[inline(always)]
+fn hamming_rows_impl(query: &[u8], matrix: &[u8], out: &mut [u64]) {
+    for (row, o) in matrix.chunks_exact(query.len()).zip(out) {
+        *o = hamming_impl(query, row);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,popcnt")]
+unsafe fn hamming_rows_avx2(query: &[u8], matrix: &[u8], out: &mut [u64]) {
+    hamming_rows_impl(query, matrix, out)
+}
+
+/// Distances from `query` to each `query.len()`-byte row of `matrix`, with
+/// the hardware popcount when the CPU has it.
+fn hamming_rows(query: &[u8], matrix: &[u8], out: &mut [u64]) {
+    #[cfg(target_arch = "x86_64")]
+    if x86_feature!("avx2") && x86_feature!("popcnt") {
+        return unsafe { hamming_rows_avx2(query, matrix, out) };
+    }
+    hamming_rows_impl(query, matrix, out)
+}
+
+/// Return the Hamming distance (number of differing bits) between the
+/// `len_bytes`-byte bit vectors `a` and `b`.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len_bytes` bytes.
+#[unsafe(export_name = c_symbol!("hamming_distance"))]
+pub unsafe extern "C" fn hamming_distance(a: *const u8, b: *const u8, len_bytes: usize) -> u64 {
+    if a.is_null() || b.is_null() || len_bytes == 0 {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a, len_bytes);
+    let b = core::slice::from_raw_parts(b, len_bytes);
+    let mut out = [0];
+    hamming_rows(a, b, &mut out);
+    out[0]
+}
+
+/// Write to `out[r]` the Hamming distance between the `len_bytes`-byte
+/// `query` and row `r` of `matrix`, which holds `nrows` such bit vectors
+/// back to back.
+///
+/// # Safety
+/// `query` must be valid for `len_bytes` bytes, `matrix` for
+/// `nrows * len_bytes` bytes and `out` for `nrows` u64 values.
+#[unsafe(export_name = c_symbol!("hamming_distance_batch"))]
+pub unsafe extern "C" fn hamming_distance_batch(
+    query: *const u8,
+    len_bytes: usize,
+    matrix: *const u8,
+    nrows: usize,
+    out: *mut u64,
+) {
+    if out.is_null() || nrows == 0 {
+        return;
+    }
+    let out = core::slice::from_raw_parts_mut(out, nrows);
+    if query.is_null() || matrix.is_null() || len_bytes == 0 {
+        out.fill(0);
+        return;
+    }
+    let query = core::slice::from_raw_parts(query, len_bytes);
+    let matrix = core::slice::from_raw_parts(matrix, nrows * len_bytes);
+    hamming_rows(query, matrix, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(seed: u32, dim: usize) -> Vec<f32> {
+        (0..dim as u32)
+            .map(|i| ((i.wrapping_mul(2_654_435_761) ^ seed) % 2001) as f32 / 1000.0 - 1.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_l2_cosine_f32() {
+        for dim in [1usize, 3, 15, 16, 17, 100, 768] {
+            let (a, b) = (vector(1, dim), vector(0x5bd1_e995, dim));
+            let l2: f64 = a
+                .iter()
+                .zip(&b)
+                .map(|(&x, &y)| (x as f64 - y as f64).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            let dot: f64 = a.iter().zip(&b).map(|(&x, &y)| x as f64 * y as f64).sum();
+            let na: f64 = a.iter().map(|&x| x as f64 * x as f64).sum::<f64>().sqrt();
+            let nb: f64 = b.iter().map(|&y| y as f64 * y as f64).sum::<f64>().sqrt();
+            let got = unsafe { l2_distance_f32(a.as_ptr(), b.as_ptr(), dim) };
+            assert!((got as f64 - l2).abs() <= 1e-5 * l2.max(1.0), "dim={dim}");
+            let got = unsafe { cosine_similarity_f32(a.as_ptr(), b.as_ptr(), dim) };
+            assert!((got as f64 - dot / (na * nb)).abs() <= 1e-5, "dim={dim}");
+            // Both paths agree up to rounding.
+            let (f, p) = (l2_sq_impl::<true>(&a, &b), l2_sq_impl::<false>(&a, &b));
+            assert!((f - p).abs() <= 1e-5 * p.max(1.0), "dim={dim}");
+        }
+        let a = [3.0f32, 4.0, 0.0];
+        let b = [0.0f32, 0.0, 0.0];
+        let c = [-6.0f32, -8.0, 0.0];
+        unsafe {
+            assert_eq!(l2_distance_f32(a.as_ptr(), b.as_ptr(), 3), 5.0);
+            assert_eq!(cosine_similarity_f32(a.as_ptr(), b.as_ptr(), 3), 0.0);
+            assert_eq!(cosine_similarity_f32(a.as_ptr(), c.as_ptr(), 3), -1.0);
+            assert_eq!(cosine_similarity_f32(a.as_ptr(), a.as_ptr(), 3), 1.0);
+            assert_eq!(l2_distance_f32(a.as_ptr(), c.as_ptr(), 0), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_l2_distance_batch_f32() {
+        for dim in [1usize, 16, 17, 128] {
+            let query = vector(3, dim);
+            let matrix = vector(4, dim * 40);
+            let mut out = vec![f32::NAN; 40];
+            unsafe {
+                l2_distance_batch_f32(query.as_ptr(), dim, matrix.as_ptr(), 40, out.as_mut_ptr())
+            };
+            for (r, &d) in out.iter().enumerate() {
+                let row = &matrix[r * dim..][..dim];
+                let want = unsafe { l2_distance_f32(query.as_ptr(), row.as_ptr(), dim) };
+                assert_eq!(d, want, "dim={dim} row={r}");
+            }
+        }
+        let mut out = [1.0f32; 3];
+        let q = [1.0f32];
+        unsafe { l2_distance_batch_f32(q.as_ptr(), 0, q.as_ptr(), 3, out.as_mut_ptr()) };
+        assert_eq!(out, [0.0; 3]);
+    }
+
+    #[test]
+    fn test_dot_i8() {
+        let a: Vec<u8> = (0..1000u32).map(|i| (i * 37 + i / 5) as u8).collect();
+        let b: Vec<i8> = (0..1000u32).map(|i| (i * 101 + 7) as u8 as i8).collect();
+        for len in [1usize, 15, 16, 31, 32, 33, 64, 100, 1000] {
+            let (a, b) = (&a[..len], &b[..len]);
+            let signed: i32 = a
+                .iter()
+                .zip(b)
+                .map(|(&x, &y)| x as i8 as i32 * y as i32)
+                .sum();
+            let unsigned: i32 = a.iter().zip(b).map(|(&x, &y)| x as i32 * y as i32).sum();
+            let ai = a.as_ptr() as *const i8;
+            assert_eq!(unsafe { dot_i8(ai, b.as_ptr(), len) }, signed, "len={len}");
+            assert_eq!(unsafe { dot_u8_i8(a.as_ptr(), b.as_ptr(), len) }, unsigned);
+            assert_eq!(dot_portable::<true>(a, b), signed, "len={len}");
+            assert_eq!(dot_portable::<false>(a, b), unsigned, "len={len}");
+            #[cfg(target_arch = "x86_64")]
+            if x86_feature!("avxvnni") {
+                assert_eq!(unsafe { dot_vnni256::<true>(a, b) }, signed, "len={len}");
+                assert_eq!(unsafe { dot_vnni256::<false>(a, b) }, unsigned);
+            }
+        }
+        // Extremes: -128 * -128 and 255 * -128 in every lane.
+        let min = [-128i8; 100];
+        let max = [255u8; 100];
+        assert_eq!(
+            unsafe { dot_i8(min.as_ptr(), min.as_ptr(), 100) },
+            100 * 16384
+        );
+        assert_eq!(
+            unsafe { dot_u8_i8(max.as_ptr(), min.as_ptr(), 100) },
+            -100 * 32640
+        );
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let naive = |a: &[u8], b: &[u8]| -> u64 {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x ^ y).count_ones() as u64)
+                .sum()
+        };
+        let a: Vec<u8> = (0..9000u32).map(|i| (i * 131 + i / 9) as u8).collect();
+        let b: Vec<u8> = (0..9000u32).map(|i| (i * 61 + 5) as u8).collect();
+        for len in [1usize, 7, 8, 9, 31, 32, 33, 40, 1000, 9000] {
+            let got = unsafe { hamming_distance(a.as_ptr(), b.as_ptr(), len) };
+            assert_eq!(got, naive(&a[..len], &b[..len]), "len={len}");
+            assert_eq!(hamming_impl(&a[..len], &b[..len]), got, "len={len}");
+        }
+        // 256-bit signatures against a query.
+        let (query, rows) = (&a[..32], &b[..32 * 50]);
+        let mut out = vec![!0u64; 50];
+        unsafe { hamming_distance_batch(query.as_ptr(), 32, rows.as_ptr(), 50, out.as_mut_ptr()) };
+        for (r, &d) in out.iter().enumerate() {
+            assert_eq!(d, naive(query, &rows[32 * r..32 * r + 32]), "row={r}");
+        }
+        unsafe { hamming_distance_batch(query.as_ptr(), 0, rows.as_ptr(), 50, out.as_mut_ptr()) };
+        assert!(out.iter().all(|&d| d == 0));
+    }
+}
@@ -0,0 +1,150 @@
+//! Crate-wide error convention.  Fallible kernels return a negative
+//! `SIMBA_ERR_*` code (kernels that predate it keep the negative sentinels
+//! they document) and record a human-readable detail for the calling thread,
+//! which [`simba_last_error_message`] hands back.  Successful calls leave the
+//! last error alone, like `errno`.
+//!
+//! Without the `std` feature there is no thread-local storage: codes are
+//! still returned but no detail is kept.
+
+/// Malformed input: a bad character, a truncated stream, ...
+pub const SIMBA_ERR_INVALID_INPUT: i32 = -1;
+/// The caller's output buffer is too small.
+pub const SIMBA_ERR_CAPACITY: i32 = -2;
+/// A required pointer argument is null.
+pub const SIMBA_ERR_NULL_POINTER: i32 = -3;
+/// A parameter is outside the range the kernel supports.
+pub const SIMBA_ERR_UNSUPPORTED: i32 = -4;
+/// A parsed value does not fit the result type.
+pub const SIMBA_ERR_OVERFLOW: i32 = -5;
+
+#[cfg(feature = "std")]
+mod last {
+    use core::cell::RefCell;
+    use core::fmt;
+
+    /// Longest detail kept; longer ones are cut at a char boundary.
+    const MESSAGE_CAP: usize = 256;
+
+    pub(super) struct LastError {
+        pub(super) code: i32,
+        pub(super) buf: [u8; MESSAGE_CAP],
+        pub(super) len: usize,
+    }
+
+    impl fmt::Write for LastError {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let mut n = s.len().min(MESSAGE_CAP - self.len);
+            while !s.is_char_boundary(n) {
+                n -= 1;
+            }
+            self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+            self.len += n;
+            Ok(())
+        }
+    }
+
+    std::thread_local! {
+        pub(super) static LAST_ERROR: RefCell<LastError> = const {
+            RefCell::new(LastError { code: 0, buf: [0; MESSAGE_CAP], len: 0 })
+        };
+    }
+}
+
+/// Record `code` and `detail` as the calling thread's last error and return
+/// `code`.
+pub(crate) fn fail(code: i32, detail: core::fmt::Arguments) -> i32 {
+    #[cfg(feature = "std")]
+    last::LAST_ERROR.with_borrow_mut(|e| {
+        e.code = code;
+        e.len = 0;
+        let _ = core::fmt::Write::write_fmt(e, detail);
+    });
+    #[cfg(not(feature = "std"))]
+    let _ = detail;
+    code
+}
+
+/// `SIMBA_ERR_*` code of the calling thread's most recent failure, or 0 if
+/// none has been recorded.
+#[unsafe(export_name = c_symbol!("simba_last_error"))]
+pub extern "C" fn simba_last_error() -> i32 {
+    #[cfg(feature = "std")]
+    return last::LAST_ERROR.with_borrow(|e| e.code);
+    #[cfg(not(feature = "std"))]
+    0
+}
+
+/// Copy the detail message of the calling thread's most recent failure
+/// (UTF-8, not NUL-terminated) into `buf`, truncated to `cap` bytes.
+///
+/// Returns the full message length, so a caller can retry with a larger
+/// buffer; 0 if no error has been recorded.
+///
+/// # Safety
+/// `buf` must be null or valid for `cap` bytes.
+#[unsafe(export_name = c_symbol!("simba_last_error_message"))]
+pub unsafe extern "C" fn simba_last_error_message(buf: *mut u8, cap: usize) -> usize {
+    #[cfg(feature = "std")]
+    return last::LAST_ERROR.with_borrow(|e| {
+        if !buf.is_null() {
+            let n = e.len.min(cap);
+            core::ptr::copy_nonoverlapping(e.buf.as_ptr(), buf, n);
+        }
+        e.len
+    });
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = (buf, cap);
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> String {
+        let mut buf = [0u8; 512];
+        let n = unsafe { simba_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        String::from_utf8(buf[..n].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_last_error() {
+        assert_eq!(
+            fail(SIMBA_ERR_CAPACITY, format_args!("need {} bytes", 12)),
+            -2
+        );
+        assert_eq!(simba_last_error(), SIMBA_ERR_CAPACITY);
+        assert_eq!(message(), "need 12 bytes");
+
+        // Truncated copies still report the full length.
+        let mut small = [0u8; 4];
+        assert_eq!(
+            unsafe { simba_last_error_message(small.as_mut_ptr(), 4) },
+            13
+        );
+        assert_eq!(&small, b"need");
+        assert_eq!(
+            unsafe { simba_last_error_message(core::ptr::null_mut(), 0) },
+            13
+        );
+
+        // Over-long details are cut at a char boundary.
+        fail(SIMBA_ERR_INVALID_INPUT, format_args!("{}", "é".repeat(200)));
+        assert_eq!(message(), "é".repeat(128));
+
+        // Kernels record their failures; other threads are unaffected.
+        let mut dst = [0u8; 3];
+        assert_eq!(
+            unsafe { crate::base64::base64_decode_std(b"QU*D".as_ptr(), 4, dst.as_mut_ptr()) },
+            -3
+        );
+        assert_eq!(simba_last_error(), SIMBA_ERR_INVALID_INPUT);
+        assert_eq!(message(), "invalid base64 character at offset 2");
+        std::thread::spawn(|| assert_eq!(simba_last_error(), 0))
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,511 @@
+//! Decimal formatting: the digits of a whole block are produced in vector
+//! lanes by a divide-and-split tree instead of one division per digit.
+use core::simd::Simd;
+use core::simd::prelude::{SimdPartialEq, SimdUint};
+use core::simd::simd_swizzle;
+
+use crate::error::{SIMBA_ERR_CAPACITY, fail};
+
+mod pow5;
+use pow5::{POW5_BITCOUNT, POW5_INV_BITCOUNT, POW5_INV_SPLIT, POW5_SPLIT};
+
+/// Longest decimal u64 (`u64::MAX` has 20 digits).
+const U64_MAX_DIGITS: usize = 20;
+
+/// The 16 decimal digits of `v < 10^16` (zero-padded), most significant
+/// first.  Each step splits every lane into quotient and remainder by the
+/// next power of ten: one 8-digit pair, four quads, eight pairs, sixteen
+/// digits.
+#[inline(always)]
+fn digits16(v: u64) -> Simd<u8, 16> {
+    let halves = Simd::<u32, 2>::from_array([(v / 100_000_000) as u32, (v % 100_000_000) as u32]);
+    let (q, r) = (halves / Simd::splat(10_000), halves % Simd::splat(10_000));
+    let quads = simd_swizzle!(q, r, [0, 2, 1, 3]).cast::<u16>();
+    let (q, r) = (quads / Simd::splat(100), quads % Simd::splat(100));
+    let pairs = simd_swizzle!(q, r, [0, 4, 1, 5, 2, 6, 3, 7]).cast::<u8>();
+    let (q, r) = (pairs / Simd::splat(10), pairs % Simd::splat(10));
+    simd_swizzle!(q, r, [0, 8, 1, 9, 2, 10, 3, 11, 4, 12, 5, 13, 6, 14, 7, 15])
+}
+
+/// Write the decimal form of `v` into the tail of `buf` and return it.
+#[inline(always)]
+pub(crate) fn format_u64_digits(v: u64, buf: &mut [u8; U64_MAX_DIGITS]) -> &[u8] {
+    let d = digits16(v % 10_000_000_000_000_000) + Simd::splat(b'0');
+    d.copy_to_slice(&mut buf[U64_MAX_DIGITS - 16..]);
+    let mut start = U64_MAX_DIGITS - 16;
+    if v < 10_000_000_000_000_000 {
+        // Skip the zero padding, keeping at least one digit.
+        let nonzero = d.simd_ne(Simd::splat(b'0')).to_bitmask() | 1 << 15;
+        start += nonzero.trailing_zeros() as usize;
+    } else {
+        // At most four more digits: u64::MAX / 10^16 = 1844.
+        let mut hi = v / 10_000_000_000_000_000;
+        while hi > 0 {
+            start -= 1;
+            buf[start] = b'0' + (hi % 10) as u8;
+            hi /= 10;
+        }
+    }
+    &buf[start..]
+}
+
+/// Write `n` u64 values in decimal to `dst`, joined by the byte `sep` when
+/// `sep` is 0..=255 (no trailing separator) or back to back when `sep` is
+/// negative.
+///
+/// Returns the number of bytes written, or `SIMBA_ERR_CAPACITY` if they do
+/// not fit in `cap` (`dst` then holds a prefix of the output).  `21 * n`
+/// bytes always suffice.
+///
+/// # Safety
+/// `values` must be null or valid for `n` values and `dst` for `cap` bytes;
+/// the buffers must not overlap.
+#[unsafe(export_name = c_symbol!("format_u64_batch"))]
+pub unsafe extern "C" fn format_u64_batch(
+    values: *const u64,
+    n: usize,
+    dst: *mut u8,
+    cap: usize,
+    sep: i32,
+) -> isize {
+    if values.is_null() || n == 0 {
+        return 0;
+    }
+    let values = core::slice::from_raw_parts(values, n);
+    let dst: &mut [u8] = if dst.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(dst, cap)
+    };
+    let sep = u8::try_from(sep).ok();
+    let mut buf = [0u8; U64_MAX_DIGITS];
+    let mut o = 0;
+    for (i, &v) in values.iter().enumerate() {
+        let digits = format_u64_digits(v, &mut buf);
+        let sep = sep.filter(|_| i > 0);
+        let need = digits.len() + sep.is_some() as usize;
+        if o + need > dst.len() {
+            return fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("value {i} does not fit in {cap} bytes"),
+            ) as isize;
+        }
+        if let Some(sep) = sep {
+            dst[o] = sep;
+            o += 1;
+        }
+        dst[o..o + digits.len()].copy_from_slice(digits);
+        o += digits.len();
+    }
+    o as isize
+}
+
+// === Floats ==================================================================
+
+// Shortest round-trip f64 → decimal with Ryu (Adams, "Ryū: fast float-to-string
+// conversion", PLDI 2018): the interval of decimals that round to the value is
+// computed exactly in 64-bit integers from one 125-bit power of five, then
+// digits are dropped while both ends still agree.
+
+/// Longest output of [`format_f64`]: `-0.0000012345678901234567`.
+const F64_MAX_LEN: usize = 25;
+
+/// `ceil(log2(5^e))` for `0 <= e <= 3528`.
+#[inline(always)]
+fn pow5_bits(e: i32) -> i32 {
+    ((e as u32 * 1_217_359) >> 19) as i32 + 1
+}
+
+/// `floor(log10(2^e))` for `0 <= e <= 1650`.
+#[inline(always)]
+fn log10_pow2(e: i32) -> u32 {
+    (e as u32 * 78_913) >> 18
+}
+
+/// `floor(log10(5^e))` for `0 <= e <= 2620`.
+#[inline(always)]
+fn log10_pow5(e: i32) -> u32 {
+    (e as u32 * 732_923) >> 20
+}
+
+#[inline(always)]
+fn multiple_of_pow5(mut v: u64, p: u32) -> bool {
+    let mut count = 0;
+    while v.is_multiple_of(5) && count < p {
+        v /= 5;
+        count += 1;
+    }
+    count >= p
+}
+
+/// `(m * mul) >> j` for a 125-bit `mul` and `j >= 64`.
+#[inline(always)]
+fn mul_shift(m: u64, mul: (u64, u64), j: i32) -> u64 {
+    let lo = m as u128 * mul.1 as u128;
+    let hi = m as u128 * mul.0 as u128;
+    (((lo >> 64) + hi) >> (j - 64)) as u64
+}
+
+/// Shortest decimal `(digits, exponent)` with `digits * 10^exponent`
+/// rounding back to the finite, non-zero f64 with the given fields.
+fn ryu_f64(ieee_mantissa: u64, ieee_exponent: u32) -> (u64, i32) {
+    let (m2, e2) = if ieee_exponent == 0 {
+        (ieee_mantissa, 1 - 1023 - 52 - 2)
+    } else {
+        (
+            ieee_mantissa | 1 << 52,
+            ieee_exponent as i32 - 1023 - 52 - 2,
+        )
+    };
+    // Round-half-even at the interval ends: they are included iff m2 is even.
+    let accept_bounds = m2 & 1 == 0;
+    let mv = 4 * m2;
+    // The lower neighbour is closer at a power of two (except the smallest).
+    let mm_shift = (ieee_mantissa != 0 || ieee_exponent <= 1) as u64;
+
+    // Step 1: the interval [vm, vp] around vr, scaled to decimal.
+    let (mut vr, mut vp, mut vm, e10);
+    let (mut vm_trailing_zeros, mut vr_trailing_zeros) = (false, false);
+    if e2 >= 0 {
+        let q = log10_pow2(e2) - (e2 > 3) as u32;
+        e10 = q as i32;
+        let k = POW5_INV_BITCOUNT + pow5_bits(q as i32) - 1;
+        let j = -e2 + q as i32 + k;
+        let mul = POW5_INV_SPLIT[q as usize];
+        vr = mul_shift(4 * m2, mul, j);
+        vp = mul_shift(4 * m2 + 2, mul, j);
+        vm = mul_shift(4 * m2 - 1 - mm_shift, mul, j);
+        if q <= 21 {
+            // Only one of mv - 1, mv, mv + 2 can be a multiple of 5.
+            if mv % 5 == 0 {
+                vr_trailing_zeros = multiple_of_pow5(mv, q);
+            } else if accept_bounds {
+                vm_trailing_zeros = multiple_of_pow5(mv - 1 - mm_shift, q);
+            } else {
+                vp -= multiple_of_pow5(mv + 2, q) as u64;
+            }
+        }
+    } else {
+        let q = log10_pow5(-e2) - (-e2 > 1) as u32;
+        e10 = q as i32 + e2;
+        let i = -e2 - q as i32;
+        let j = q as i32 - (pow5_bits(i) - POW5_BITCOUNT);
+        let mul = POW5_SPLIT[i as usize];
+        vr = mul_shift(4 * m2, mul, j);
+        vp = mul_shift(4 * m2 + 2, mul, j);
+        vm = mul_shift(4 * m2 - 1 - mm_shift, mul, j);
+        if q <= 1 {
+            vr_trailing_zeros = true;
+            if accept_bounds {
+                vm_trailing_zeros = mm_shift == 1;
+            } else {
+                vp -= 1;
+            }
+        } else if q < 63 {
+            vr_trailing_zeros = mv & ((1 << q) - 1) == 0;
+        }
+    }
+
+    // Step 2: drop digits while the interval still has a shorter member.
+    let mut removed = 0;
+    let output = if vm_trailing_zeros || vr_trailing_zeros {
+        let mut last_removed = 0;
+        while vp / 10 > vm / 10 {
+            vm_trailing_zeros &= vm % 10 == 0;
+            vr_trailing_zeros &= last_removed == 0;
+            last_removed = vr % 10;
+            (vr, vp, vm) = (vr / 10, vp / 10, vm / 10);
+            removed += 1;
+        }
+        if vm_trailing_zeros {
+            while vm % 10 == 0 {
+                vr_trailing_zeros &= last_removed == 0;
+                last_removed = vr % 10;
+                (vr, vp, vm) = (vr / 10, vp / 10, vm / 10);
+                removed += 1;
+            }
+        }
+        if vr_trailing_zeros && last_removed == 5 && vr % 2 == 0 {
+            // Exactly halfway: round to even.
+            last_removed = 4;
+        }
+        let round_up = (vr == vm && (!accept_bounds || !vm_trailing_zeros)) || last_removed >= 5;
+        vr + round_up as u64
+    } else {
+        // Common case: no trailing-zero bookkeeping needed.
+        let mut round_up = false;
+        if vp / 100 > vm / 100 {
+            round_up = vr % 100 >= 50;
+            (vr, vp, vm) = (vr / 100, vp / 100, vm / 100);
+            removed += 2;
+        }
+        while vp / 10 > vm / 10 {
+            round_up = vr % 10 >= 5;
+            (vr, vp, vm) = (vr / 10, vp / 10, vm / 10);
+            removed += 1;
+        }
+        vr + (vr == vm || round_up) as u64
+    };
+    (output, e10 + removed)
+}
+
+/// Write the shortest round-trip form of `v` into `buf`, returning the
+/// length.  Plain decimal for `1e-6 <= |v| < 1e21`, otherwise `d.ddde±x`,
+/// as JavaScript and Go's `encoding/json` print numbers.
+pub(crate) fn format_f64_into(v: f64, buf: &mut [u8; F64_MAX_LEN]) -> usize {
+    let bits = v.to_bits();
+    let (mantissa, exponent) = (bits & ((1 << 52) - 1), (bits >> 52) as u32 & 0x7FF);
+    let mut o = 0;
+    let mut put = |bytes: &[u8], o: &mut usize| {
+        buf[*o..*o + bytes.len()].copy_from_slice(bytes);
+        *o += bytes.len();
+    };
+    if exponent == 0x7FF {
+        let s: &[u8] = match (mantissa != 0, v.is_sign_negative()) {
+            (true, _) => b"NaN",
+            (false, false) => b"+Inf",
+            (false, true) => b"-Inf",
+        };
+        put(s, &mut o);
+        return o;
+    }
+    if v.is_sign_negative() {
+        put(b"-", &mut o);
+    }
+    if mantissa == 0 && exponent == 0 {
+        put(b"0", &mut o);
+        return o;
+    }
+    let (digits, e10) = ryu_f64(mantissa, exponent);
+    let mut dbuf = [0u8; U64_MAX_DIGITS];
+    let d = format_u64_digits(digits, &mut dbuf);
+    // Exponent of the leading digit.
+    let sci = e10 + d.len() as i32 - 1;
+    if (-6..21).contains(&sci) {
+        if e10 >= 0 {
+            put(d, &mut o);
+            (0..e10).for_each(|_| put(b"0", &mut o));
+        } else if sci >= 0 {
+            let (int, frac) = d.split_at(sci as usize + 1);
+            put(int, &mut o);
+            put(b".", &mut o);
+            put(frac, &mut o);
+        } else {
+            put(b"0.", &mut o);
+            (0..-sci - 1).for_each(|_| put(b"0", &mut o));
+            put(d, &mut o);
+        }
+    } else {
+        put(&d[..1], &mut o);
+        if d.len() > 1 {
+            put(b".", &mut o);
+            put(&d[1..], &mut o);
+        }
+        put(if sci < 0 { b"e-" } else { b"e+" }, &mut o);
+        let mut ebuf = [0u8; U64_MAX_DIGITS];
+        put(
+            format_u64_digits(sci.unsigned_abs() as u64, &mut ebuf),
+            &mut o,
+        );
+    }
+    o
+}
+
+/// Write the shortest decimal that parses back to `value` into `dst`:
+/// plain notation for `1e-6 <= |value| < 1e21` (`0.001`, `-12.5`, `1e+21`
+/// otherwise, as in JavaScript and Go's `encoding/json`), and `NaN`, `+Inf`
+/// or `-Inf` for non-finite values.  At most 25 bytes are written.
+///
+/// Returns the number of bytes written, or 0 if they do not fit in `cap`.
+///
+/// # Safety
+/// `dst` must be null or valid for `cap` bytes.
+#[unsafe(export_name = c_symbol!("format_f64"))]
+pub unsafe extern "C" fn format_f64(value: f64, dst: *mut u8, cap: usize) -> usize {
+    let mut buf = [0u8; F64_MAX_LEN];
+    let n = format_f64_into(value, &mut buf);
+    if dst.is_null() || n > cap {
+        fail(
+            SIMBA_ERR_CAPACITY,
+            format_args!("{n} bytes do not fit in {cap}"),
+        );
+        return 0;
+    }
+    core::ptr::copy_nonoverlapping(buf.as_ptr(), dst, n);
+    n
+}
+
+/// Write `n` f64 values as [`format_f64`] does to `dst`, joined by the byte
+/// `sep` when `sep` is 0..=255 (no trailing separator) or back to back when
+/// `sep` is negative.
+///
+/// Returns the number of bytes written, or `SIMBA_ERR_CAPACITY` if they do
+/// not fit in `cap` (`dst` then holds a prefix of the output).  `26 * n`
+/// bytes always suffice.
+///
+/// # Safety
+/// `values` must be null or valid for `n` values and `dst` for `cap` bytes;
+/// the buffers must not overlap.
+#[unsafe(export_name = c_symbol!("format_f64_batch"))]
+pub unsafe extern "C" fn format_f64_batch(
+    values: *const f64,
+    n: usize,
+    dst: *mut u8,
+    cap: usize,
+    sep: i32,
+) -> isize {
+    if values.is_null() || n == 0 {
+        return 0;
+    }
+    let values = core::slice::from_raw_parts(values, n);
+    let dst: &mut [u8] = if dst.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(dst, cap)
+    };
+    let sep = u8::try_from(sep).ok();
+    let mut buf = [0u8; F64_MAX_LEN];
+    let mut o = 0;
+    for (i, &v) in values.iter().enumerate() {
+        let len = format_f64_into(v, &mut buf);
+        let sep = sep.filter(|_| i > 0);
+        if o + len + sep.is_some() as usize > dst.len() {
+            return fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("value {i} does not fit in {cap} bytes"),
+            ) as isize;
+        }
+        if let Some(sep) = sep {
+            dst[o] = sep;
+            o += 1;
+        }
+        dst[o..o + len].copy_from_slice(&buf[..len]);
+        o += len;
+    }
+    o as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_u64_digits() {
+        let mut buf = [0u8; U64_MAX_DIGITS];
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        let mut check =
+            |v: u64| assert_eq!(format_u64_digits(v, &mut buf), v.to_string().as_bytes());
+        for k in 0..20 {
+            let p = 10u64.pow(k);
+            [p - 1, p, p + 1].into_iter().for_each(&mut check);
+        }
+        check(u64::MAX);
+        for _ in 0..10_000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            check(x);
+            check(x >> (x % 64));
+        }
+    }
+
+    #[test]
+    fn test_format_u64_batch() {
+        let values = [0u64, 7, 1234, u64::MAX, 10_000_000_000_000_000];
+        let mut dst = [0u8; 64];
+        let n = unsafe { format_u64_batch(values.as_ptr(), 5, dst.as_mut_ptr(), 64, b',' as i32) };
+        let want = "0,7,1234,18446744073709551615,10000000000000000";
+        assert_eq!(&dst[..n as usize], want.as_bytes());
+
+        let n = unsafe { format_u64_batch(values.as_ptr(), 3, dst.as_mut_ptr(), 64, -1) };
+        assert_eq!(&dst[..n as usize], b"071234");
+
+        let n = unsafe {
+            format_u64_batch(
+                values.as_ptr(),
+                5,
+                dst.as_mut_ptr(),
+                want.len() - 1,
+                b',' as i32,
+            )
+        };
+        assert_eq!(n, SIMBA_ERR_CAPACITY as isize);
+    }
+
+    fn fmt(v: f64) -> String {
+        let mut buf = [0u8; F64_MAX_LEN];
+        let n = format_f64_into(v, &mut buf);
+        String::from_utf8(buf[..n].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_format_f64() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "-0"),
+            (1.0, "1"),
+            (-12.5, "-12.5"),
+            (0.1, "0.1"),
+            (0.3, "0.3"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (123456.789, "123456.789"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e300, "1.5e+300"),
+            (0.000001, "0.000001"),
+            (1.25e-7, "1.25e-7"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+            (-5e-324, "-5e-324"),
+            (-1.2345678901234567e-6, "-0.0000012345678901234567"),
+            (9007199254740993.0, "9007199254740992"),
+            (1658206780088562.2, "1658206780088562.2"),
+            (f64::NAN, "NaN"),
+            (f64::INFINITY, "+Inf"),
+            (f64::NEG_INFINITY, "-Inf"),
+        ];
+        for (v, want) in cases {
+            assert_eq!(fmt(v), want, "{v:e}");
+        }
+
+        // Round-trips, with as few digits as core's shortest `{:e}` (exact
+        // ties may round the other way).
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        for _ in 0..50_000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let v = f64::from_bits(x);
+            if !v.is_finite() {
+                continue;
+            }
+            let s = fmt(v);
+            assert_eq!(s.parse::<f64>().unwrap().to_bits(), x, "{s}");
+            let digits = |s: &str| -> String {
+                let m = s.split(['e', 'E']).next().unwrap();
+                m.chars()
+                    .filter(char::is_ascii_digit)
+                    .collect::<String>()
+                    .trim_matches('0')
+                    .to_string()
+            };
+            assert_eq!(digits(&s).len(), digits(&format!("{v:e}")).len(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_format_f64_exports() {
+        let mut dst = [0u8; 64];
+        assert_eq!(unsafe { format_f64(-2.5e-10, dst.as_mut_ptr(), 64) }, 8);
+        assert_eq!(&dst[..8], b"-2.5e-10");
+        assert_eq!(unsafe { format_f64(1e100, dst.as_mut_ptr(), 6) }, 6);
+        assert_eq!(&dst[..6], b"1e+100");
+        assert_eq!(unsafe { format_f64(1e100, dst.as_mut_ptr(), 5) }, 0);
+
+        let values = [0.5, -1.0, 1e-9];
+        let n = unsafe { format_f64_batch(values.as_ptr(), 3, dst.as_mut_ptr(), 64, b' ' as i32) };
+        assert_eq!(&dst[..n as usize], b"0.5 -1 1e-9");
+        let n = unsafe { format_f64_batch(values.as_ptr(), 3, dst.as_mut_ptr(), 10, b' ' as i32) };
+        assert_eq!(n, SIMBA_ERR_CAPACITY as isize);
+    }
+}
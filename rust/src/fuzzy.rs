@@ -0,0 +1,297 @@
+//! Approximate string matching kernels (bit-parallel edit distance, Bitap).
+use crate::error::{SIMBA_ERR_UNSUPPORTED, fail};
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+// === Myers / Hyyrö bit-parallel Levenshtein ==================================
+
+#[cfg(feature = "alloc")]
+/// Per-block state of the bit-vector DP column (Hyyrö's block formulation).
+#[derive(Clone, Copy)]
+struct Block {
+    pv: u64,
+    mv: u64,
+}
+
+#[cfg(feature = "alloc")]
+/// Advance one 64-row block by one text column.  `eq` is the pattern match
+/// mask for the text byte, `hin` the horizontal delta entering from the block
+/// above (-1/0/+1) and `top` the mask of the block's last valid row.  Returns
+/// the horizontal delta leaving the block at that row.
+#[inline(always)]
+fn advance_block(b: &mut Block, mut eq: u64, hin: i32, top: u64) -> i32 {
+    let (pv, mv) = (b.pv, b.mv);
+    let xv = eq | mv;
+    if hin < 0 {
+        eq |= 1;
+    }
+    let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+    let mut ph = mv | !(xh | pv);
+    let mut mh = pv & xh;
+    let hout = if ph & top != 0 {
+        1
+    } else if mh & top != 0 {
+        -1
+    } else {
+        0
+    };
+    ph <<= 1;
+    mh <<= 1;
+    if hin < 0 {
+        mh |= 1;
+    } else if hin > 0 {
+        ph |= 1;
+    }
+    b.pv = mh | !(xv | ph);
+    b.mv = ph & xv;
+    hout
+}
+
+#[cfg(feature = "alloc")]
+/// Levenshtein distance between `pat` and `text`, or `None` once it is
+/// certain to exceed `max_k`.  `pat` should be the shorter input.
+fn edit_distance_impl(pat: &[u8], text: &[u8], max_k: usize) -> Option<usize> {
+    let (m, n) = (pat.len(), text.len());
+    if m.abs_diff(n) > max_k {
+        return None;
+    }
+    if m == 0 {
+        return Some(n);
+    }
+    let nblocks = m.div_ceil(64);
+    let mut peq = vec![0u64; 256 * nblocks];
+    for (i, &c) in pat.iter().enumerate() {
+        peq[c as usize * nblocks + i / 64] |= 1 << (i % 64);
+    }
+    let last_top = 1u64 << ((m - 1) % 64);
+    let mut blocks = vec![Block { pv: !0, mv: 0 }; nblocks];
+    let mut score = m;
+    for (j, &c) in text.iter().enumerate() {
+        let eqs = &peq[c as usize * nblocks..][..nblocks];
+        // Row 0 is D[0][j] = j, i.e. a +1 step into the first block.
+        let mut carry = 1;
+        for (k, (b, &eq)) in blocks.iter_mut().zip(eqs).enumerate() {
+            let top = if k + 1 == nblocks { last_top } else { 1 << 63 };
+            carry = advance_block(b, eq, carry, top);
+        }
+        score = score.wrapping_add_signed(carry as isize);
+        // Each remaining column lowers the score by at most one.
+        if score > max_k + (n - j - 1) {
+            return None;
+        }
+    }
+    (score <= max_k).then_some(score)
+}
+
+#[cfg(feature = "alloc")]
+/// Compute the Levenshtein (unit-cost insert/delete/substitute) distance
+/// between two byte strings using Myers' bit-parallel algorithm, processing
+/// 64 DP rows per machine word.
+///
+/// Returns the distance when it is `<= max_k`, otherwise -1; the scan stops
+/// as soon as the bound can no longer be met.  A negative `max_k` disables
+/// the bound.
+///
+/// # Safety
+/// `a`/`b` must be null or valid for `a_len`/`b_len` bytes.
+#[unsafe(export_name = c_symbol!("edit_distance_le"))]
+pub unsafe extern "C" fn edit_distance_le(
+    a: *const u8,
+    a_len: usize,
+    b: *const u8,
+    b_len: usize,
+    max_k: i32,
+) -> i32 {
+    let a = if a.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(a, a_len)
+    };
+    let b = if b.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(b, b_len)
+    };
+    let max_k = usize::try_from(max_k).unwrap_or(usize::MAX / 2);
+    // The shorter string becomes the bit-vector pattern.
+    let (pat, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    match edit_distance_impl(pat, text, max_k) {
+        Some(d) => i32::try_from(d).unwrap_or(i32::MAX),
+        None => -1,
+    }
+}
+
+// === Bitap (Shift-And) with mismatches =======================================
+
+/// Longest pattern a single machine word can track.
+const BITAP_MAX_PATTERN: usize = 64;
+
+/// Start of the first window of `hay` that matches `pat` with at most `k`
+/// substitutions.
+fn bitap_find_impl(hay: &[u8], pat: &[u8], k: usize) -> Option<usize> {
+    let m = pat.len();
+    if m == 0 {
+        return Some(0);
+    }
+    if hay.len() < m {
+        return None;
+    }
+    if k >= m {
+        return Some(0);
+    }
+    let mut masks = [0u64; 256];
+    for (i, &c) in pat.iter().enumerate() {
+        masks[c as usize] |= 1 << i;
+    }
+    let accept = 1u64 << (m - 1);
+    // r[d]: bit i set ⇔ pat[..=i] matches the text ending here with ≤ d
+    // mismatches.  Every level advances in lock-step, one shift per byte.
+    let mut r = [0u64; BITAP_MAX_PATTERN];
+    let r = &mut r[..=k];
+    for (i, &c) in hay.iter().enumerate() {
+        let eq = masks[c as usize];
+        let mut prev_old = r[0];
+        r[0] = ((r[0] << 1) | 1) & eq;
+        let mut hit = r[0];
+        for rd in r.iter_mut().skip(1) {
+            let old = *rd;
+            *rd = (((old << 1) | 1) & eq) | ((prev_old << 1) | 1);
+            prev_old = old;
+            hit |= *rd;
+        }
+        if hit & accept != 0 {
+            return Some(i + 1 - m);
+        }
+    }
+    None
+}
+
+/// Find the first window of `hay` that equals `pattern` up to at most
+/// `max_errors` substituted bytes (Hamming distance), using the Shift-And
+/// bit-parallel automaton: each haystack byte costs `max_errors + 1` word
+/// operations regardless of pattern length.
+///
+/// Returns the start offset of the earliest matching window, -1 if there is
+/// none, or -2 if `plen` exceeds 64 bytes.  An empty pattern matches at 0.
+///
+/// # Safety
+/// `hay`/`pattern` must be null or valid for `len`/`plen` bytes.
+#[unsafe(export_name = c_symbol!("bitap_find"))]
+pub unsafe extern "C" fn bitap_find(
+    hay: *const u8,
+    len: usize,
+    pattern: *const u8,
+    plen: usize,
+    max_errors: u32,
+) -> isize {
+    if plen > BITAP_MAX_PATTERN {
+        fail(
+            SIMBA_ERR_UNSUPPORTED,
+            format_args!("pattern of {plen} bytes exceeds {BITAP_MAX_PATTERN}"),
+        );
+        return -2;
+    }
+    let hay = if hay.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(hay, len)
+    };
+    let pat = if pattern.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(pattern, plen)
+    };
+    bitap_find_impl(hay, pat, max_errors as usize).map_or(-1, |i| i as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    fn levenshtein(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, &ca) in a.iter().enumerate() {
+            let mut diag = row[0];
+            row[0] = i + 1;
+            for (j, &cb) in b.iter().enumerate() {
+                let sub = diag + (ca != cb) as usize;
+                diag = row[j + 1];
+                row[j + 1] = sub.min(row[j] + 1).min(diag + 1);
+            }
+        }
+        row[b.len()]
+    }
+
+    fn dist(a: &[u8], b: &[u8], k: i32) -> i32 {
+        unsafe { super::edit_distance_le(a.as_ptr(), a.len(), b.as_ptr(), b.len(), k) }
+    }
+
+    #[test]
+    fn test_edit_distance_known() {
+        assert_eq!(dist(b"kitten", b"sitting", -1), 3);
+        assert_eq!(dist(b"flaw", b"lawn", -1), 2);
+        assert_eq!(dist(b"", b"abc", -1), 3);
+        assert_eq!(dist(b"same", b"same", 0), 0);
+        assert_eq!(dist(b"kitten", b"sitting", 2), -1);
+        assert_eq!(dist(b"kitten", b"sitting", 3), 3);
+    }
+
+    #[test]
+    fn test_edit_distance_matches_dp() {
+        let mut x = 0xDEAD_BEEFu32;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        for _ in 0..300 {
+            let la = (next() % 150) as usize;
+            let lb = (next() % 150) as usize;
+            // Small alphabet so strings actually share structure.
+            let a: Vec<u8> = (0..la).map(|_| b'a' + (next() % 4) as u8).collect();
+            let b: Vec<u8> = (0..lb).map(|_| b'a' + (next() % 4) as u8).collect();
+            let want = levenshtein(&a, &b) as i32;
+            assert_eq!(dist(&a, &b, -1), want, "a={:?} b={:?}", a, b);
+            let k = (next() % 80) as i32;
+            let bounded = if want <= k { want } else { -1 };
+            assert_eq!(dist(&a, &b, k), bounded);
+        }
+    }
+
+    fn bitap(h: &[u8], p: &[u8], k: u32) -> isize {
+        unsafe { super::bitap_find(h.as_ptr(), h.len(), p.as_ptr(), p.len(), k) }
+    }
+
+    #[test]
+    fn test_bitap_find_known() {
+        assert_eq!(bitap(b"hello world", b"world", 0), 6);
+        assert_eq!(bitap(b"hello world", b"wirld", 0), -1);
+        assert_eq!(bitap(b"hello world", b"wirld", 1), 6);
+        assert_eq!(bitap(b"hello world", b"", 0), 0);
+        assert_eq!(bitap(b"abc", b"abcd", 3), -1);
+        assert_eq!(bitap(b"abc", &[b'x'; 65], 0), -2);
+    }
+
+    #[test]
+    fn test_bitap_find_matches_naive() {
+        let mut x = 0x1234_5678u32;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        for _ in 0..300 {
+            let hay: Vec<u8> = (0..(next() % 300))
+                .map(|_| b'a' + (next() % 3) as u8)
+                .collect();
+            let m = 1 + (next() % 64) as usize;
+            let pat: Vec<u8> = (0..m).map(|_| b'a' + (next() % 3) as u8).collect();
+            let k = next() % 8;
+            let want = hay
+                .windows(m)
+                .position(|w| w.iter().zip(&pat).filter(|(a, b)| a != b).count() <= k as usize)
+                .map_or(-1, |i| i as isize);
+            assert_eq!(bitap(&hay, &pat, k), want);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Selection-vector gathers: materialise the bytes or fixed-width records a
+//! `u32` index list selects.
+use core::simd::Simd;
+use core::simd::prelude::{SimdConstPtr, SimdPartialOrd, SimdUint};
+
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use crate::{load_simd, store_simd};
+
+const GATHER_LANES: usize = 16;
+
+/// Gather `src[indices[i]]` into `dst[i]`; `Err(i)` gives the first index
+/// that is not below `src.len()` (`dst[..i]` is written).
+#[inline(always)]
+unsafe fn gather_u8_impl(
+    src: &[u8],
+    indices: *const u32,
+    n: usize,
+    dst: *mut u8,
+) -> Result<(), usize> {
+    let bound = Simd::<usize, GATHER_LANES>::splat(src.len());
+    let mut i = 0;
+    while i + GATHER_LANES <= n {
+        let idx: Simd<usize, GATHER_LANES> = load_simd::<u32, GATHER_LANES>(indices.add(i)).cast();
+        let ok = idx.simd_lt(bound);
+        if !ok.all() {
+            let bad = (!ok).to_bitmask().trailing_zeros() as usize;
+            for k in 0..bad {
+                *dst.add(i + k) = src[*indices.add(i + k) as usize];
+            }
+            return Err(i + bad);
+        }
+        store_simd(
+            dst.add(i),
+            Simd::<u8, GATHER_LANES>::gather_select_unchecked(src, ok.cast(), idx, Simd::splat(0)),
+        );
+        i += GATHER_LANES;
+    }
+    while i < n {
+        let Some(&b) = src.get(*indices.add(i) as usize) else {
+            return Err(i);
+        };
+        *dst.add(i) = b;
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Gather the bytes `src[indices[i]]` for `i < n` into `dst`, checking each
+/// index against `src_len`.  Returns `n`, or `-(i + 1)` for the first `i`
+/// whose index is out of range (`dst[..i]` is then written).
+///
+/// # Safety
+/// `src` must be valid for `src_len` bytes, `indices` for `n` values and
+/// `dst` for `n` bytes; `dst` must not overlap `src` or `indices`.
+#[unsafe(export_name = c_symbol!("gather_u8"))]
+pub unsafe extern "C" fn gather_u8(
+    src: *const u8,
+    src_len: usize,
+    indices: *const u32,
+    n: usize,
+    dst: *mut u8,
+) -> isize {
+    if indices.is_null() || dst.is_null() || n == 0 {
+        return 0;
+    }
+    let src: &[u8] = if src.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(src, src_len)
+    };
+    match gather_u8_impl(src, indices, n, dst) {
+        Ok(()) => n as isize,
+        Err(at) => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!(
+                    "index {} at position {at} is out of range for {src_len} bytes",
+                    *indices.add(at)
+                ),
+            );
+            -(at as isize) - 1
+        }
+    }
+}
+
+/// Like [`gather_u8`] without bounds checks, for index lists already known to
+/// be in range.
+///
+/// # Safety
+/// Every `indices[i]` for `i < n` must be a valid offset into `src`;
+/// `indices` must be valid for `n` values and `dst` for `n` bytes, and `dst`
+/// must not overlap `src` or `indices`.
+#[unsafe(export_name = c_symbol!("gather_u8_unchecked"))]
+pub unsafe extern "C" fn gather_u8_unchecked(
+    src: *const u8,
+    indices: *const u32,
+    n: usize,
+    dst: *mut u8,
+) {
+    if src.is_null() || indices.is_null() || dst.is_null() {
+        return;
+    }
+    let mut i = 0;
+    while i + GATHER_LANES <= n {
+        let idx = load_simd::<u32, GATHER_LANES>(indices.add(i));
+        store_simd(
+            dst.add(i),
+            Simd::<u8, GATHER_LANES>::gather_ptr(Simd::splat(src).wrapping_add(idx.cast())),
+        );
+        i += GATHER_LANES;
+    }
+    while i < n {
+        *dst.add(i) = *src.add(*indices.add(i) as usize);
+        i += 1;
+    }
+}
+
+/// Gather `n` fixed-width records: record `indices[i]` (the `width` bytes at
+/// `src[indices[i] * width..]`) is copied to `dst[i * width..]`.  Indices are
+/// checked against the `src_len / width` whole records in `src`.  Returns
+/// `n`, or `-(i + 1)` for the first `i` whose index is out of range
+/// (records `..i` are then written).
+///
+/// # Safety
+/// `src` must be valid for `src_len` bytes, `indices` for `n` values and
+/// `dst` for `n * width` bytes; `dst` must not overlap `src` or `indices`.
+#[unsafe(export_name = c_symbol!("gather_records"))]
+pub unsafe extern "C" fn gather_records(
+    src: *const u8,
+    src_len: usize,
+    width: usize,
+    indices: *const u32,
+    n: usize,
+    dst: *mut u8,
+) -> isize {
+    if indices.is_null() || dst.is_null() || n == 0 || width == 0 {
+        return 0;
+    }
+    let records = if src.is_null() { 0 } else { src_len / width };
+    let indices = core::slice::from_raw_parts(indices, n);
+    for (i, &r) in indices.iter().enumerate() {
+        if r as usize >= records {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("record {r} at position {i} is out of range for {records} records"),
+            );
+            return -(i as isize) - 1;
+        }
+        core::ptr::copy_nonoverlapping(src.add(r as usize * width), dst.add(i * width), width);
+    }
+    n as isize
+}
+
+// === Dictionary decode =======================================================
+
+/// Position of the first code that is not below `bound`.  Chunks are tested
+/// with a max-reduction first so in-range input takes a vectorised pass.
+#[inline(always)]
+fn first_out_of_range<I: Copy + Into<u32>>(codes: &[I], bound: usize) -> Option<usize> {
+    let mut chunks = codes.chunks(GATHER_LANES);
+    let mut base = 0;
+    for chunk in &mut chunks {
+        let max = chunk.iter().fold(0u32, |m, &c| m.max(c.into()));
+        if max as usize >= bound {
+            return chunk
+                .iter()
+                .position(|&c| c.into() as usize >= bound)
+                .map(|k| base + k);
+        }
+        base += chunk.len();
+    }
+    None
+}
+
+/// Copy dictionary entry `codes[i]` to slot `i` of `dst`, moving entries of
+/// 1, 2, 4 or 8 bytes as single values and wider ones with `memcpy`.
+#[inline(always)]
+unsafe fn dict_decode_impl<I: Copy + Into<u32>>(
+    codes: &[I],
+    dict: *const u8,
+    width: usize,
+    dst: *mut u8,
+) {
+    macro_rules! typed {
+        ($t:ty) => {
+            for (i, &c) in codes.iter().enumerate() {
+                let v = core::ptr::read_unaligned((dict as *const $t).add(c.into() as usize));
+                core::ptr::write_unaligned((dst as *mut $t).add(i), v);
+            }
+        };
+    }
+    match width {
+        1 => typed!(u8),
+        2 => typed!(u16),
+        4 => typed!(u32),
+        8 => typed!(u64),
+        _ => {
+            for (i, &c) in codes.iter().enumerate() {
+                core::ptr::copy_nonoverlapping(
+                    dict.add(c.into() as usize * width),
+                    dst.add(i * width),
+                    width,
+                );
+            }
+        }
+    }
+}
+
+macro_rules! export_dict_decode {
+    ($name:ident, $code:ty) => {
+        #[doc = concat!(
+            "Decode `n` dictionary-encoded `", stringify!($code), "` codes: entry `codes[i]` of `dict` (`dict_len` entries of `width` bytes each) is copied to `dst[i * width..]`. Every code is checked against `dict_len` before anything is written. Returns `n`, or `-(i + 1)` for the first `i` whose code is out of range (`dst` is then untouched).\n\n",
+            "# Safety\n",
+            "`codes` must be valid for `n` values, `dict` for `dict_len * width` bytes and `dst` for `n * width` bytes; `dst` must not overlap `codes` or `dict`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(
+            codes: *const $code,
+            n: usize,
+            dict: *const u8,
+            dict_len: usize,
+            width: usize,
+            dst: *mut u8,
+        ) -> isize {
+            if codes.is_null() || dst.is_null() || n == 0 || width == 0 {
+                return 0;
+            }
+            let codes = core::slice::from_raw_parts(codes, n);
+            let dict_len = if dict.is_null() { 0 } else { dict_len };
+            if let Some(at) = first_out_of_range(codes, dict_len) {
+                fail(
+                    SIMBA_ERR_INVALID_INPUT,
+                    format_args!("code {} at position {at} is out of range for {dict_len} entries", codes[at]),
+                );
+                return -(at as isize) - 1;
+            }
+            dict_decode_impl(codes, dict, width, dst);
+            n as isize
+        }
+    };
+}
+export_dict_decode!(dict_decode_u16, u16);
+export_dict_decode!(dict_decode_u32, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gather_u8() {
+        let src: Vec<u8> = (0..=255).collect();
+        let idx: Vec<u32> = (0..100u32).map(|i| i * 37 % 256).collect();
+        let want: Vec<u8> = idx.iter().map(|&i| i as u8).collect();
+        for n in [0, 1, 15, 16, 17, 100] {
+            let mut dst = vec![0u8; n];
+            let got =
+                unsafe { gather_u8(src.as_ptr(), src.len(), idx.as_ptr(), n, dst.as_mut_ptr()) };
+            assert_eq!(got, n as isize);
+            assert_eq!(dst, want[..n]);
+            let mut dst = vec![0u8; n];
+            unsafe { gather_u8_unchecked(src.as_ptr(), idx.as_ptr(), n, dst.as_mut_ptr()) };
+            assert_eq!(dst, want[..n]);
+        }
+
+        // Out of range in the vector body and in the tail.
+        for bad in [5, 20] {
+            let mut idx = idx[..25].to_vec();
+            idx[bad] = 256;
+            let mut dst = vec![0u8; 25];
+            let got = unsafe { gather_u8(src.as_ptr(), 256, idx.as_ptr(), 25, dst.as_mut_ptr()) };
+            assert_eq!(got, -(bad as isize) - 1);
+            assert_eq!(dst[..bad], want[..bad]);
+        }
+    }
+
+    #[test]
+    fn test_gather_records() {
+        let src: Vec<u8> = (0..60).collect();
+        let idx = [4u32, 0, 9, 4];
+        let mut dst = [0u8; 24];
+        let got = unsafe { gather_records(src.as_ptr(), 60, 6, idx.as_ptr(), 4, dst.as_mut_ptr()) };
+        assert_eq!(got, 4);
+        assert_eq!(dst[..6], src[24..30]);
+        assert_eq!(dst[12..18], src[54..60]);
+        // A partial trailing record is not addressable.
+        let got = unsafe { gather_records(src.as_ptr(), 59, 6, idx.as_ptr(), 4, dst.as_mut_ptr()) };
+        assert_eq!(got, -3);
+    }
+
+    #[test]
+    fn test_dict_decode() {
+        for width in [1usize, 2, 3, 4, 8, 12] {
+            let dict: Vec<u8> = (0..10 * width).map(|i| (i * 7 + 1) as u8).collect();
+            let codes: Vec<u16> = (0..50u16).map(|i| i * 3 % 10).collect();
+            let mut dst = vec![0u8; codes.len() * width];
+            let got = unsafe {
+                dict_decode_u16(
+                    codes.as_ptr(),
+                    codes.len(),
+                    dict.as_ptr(),
+                    10,
+                    width,
+                    dst.as_mut_ptr(),
+                )
+            };
+            assert_eq!(got, codes.len() as isize);
+            for (i, &c) in codes.iter().enumerate() {
+                let c = c as usize;
+                assert_eq!(
+                    dst[i * width..(i + 1) * width],
+                    dict[c * width..(c + 1) * width],
+                    "width={width}"
+                );
+            }
+        }
+
+        let dict = [10u32, 20, 30];
+        let codes = [2u32, 0, 1, 2, 3, 0];
+        let mut dst = [0u32; 6];
+        let (d, out) = (dict.as_ptr() as *const u8, dst.as_mut_ptr() as *mut u8);
+        assert_eq!(
+            unsafe { dict_decode_u32(codes.as_ptr(), 6, d, 3, 4, out) },
+            -5
+        );
+        assert_eq!(dst, [0; 6]);
+        let out = dst.as_mut_ptr() as *mut u8;
+        assert_eq!(
+            unsafe { dict_decode_u32(codes.as_ptr(), 4, d, 3, 4, out) },
+            4
+        );
+        assert_eq!(dst[..4], [30, 10, 20, 30]);
+    }
+}
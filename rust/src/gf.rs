@@ -0,0 +1,268 @@
+//! GF(2^8) arithmetic for Reed–Solomon erasure coding, over the polynomial
+//! x^8 + x^4 + x^3 + x^2 + 1 (0x11d) used by ISA-L, Backblaze and most
+//! other erasure-coding libraries.
+//!
+//! Multiplying a buffer by a constant `c` uses the split-table (`pshufb`)
+//! technique: `c * b = c * (b & 0x0f) ^ c * (b & 0xf0)`, and each half is
+//! one 16-entry shuffle of a table built for `c`.
+use core::simd::Simd;
+use core::simd::{LaneCount, SupportedLaneCount};
+
+use crate::{load_simd, store_simd};
+
+const GF_LANES: usize = 32;
+/// Reduction polynomial, including the x^8 term.
+const POLY: u16 = 0x11d;
+/// Bytes of each shard processed per pass of [`gf_matmul`], so the source
+/// blocks stay in L1 while every output row reads them.
+const MATMUL_BLOCK: usize = 4096;
+
+/// Product of `a` and `b` in GF(2^8).
+const fn mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut b, mut p) = (a as u16, b, 0u16);
+    while b != 0 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= POLY;
+        }
+        b >>= 1;
+    }
+    p as u8
+}
+
+/// Multiplicative inverse (`a^254`); 0 maps to 0.
+const fn inv(a: u8) -> u8 {
+    let (mut r, mut base, mut e) = (1u8, a, 254u32);
+    while e != 0 {
+        if e & 1 != 0 {
+            r = mul(r, base);
+        }
+        base = mul(base, base);
+        e >>= 1;
+    }
+    r
+}
+
+/// Products of `c` with every low nibble and with every high nibble.
+fn split_tables(c: u8) -> ([u8; 16], [u8; 16]) {
+    (
+        core::array::from_fn(|n| mul(c, n as u8)),
+        core::array::from_fn(|n| mul(c, (n as u8) << 4)),
+    )
+}
+
+/// `dst = c * src` (or `dst ^= c * src` when `ADD`) for `len` bytes.
+#[inline(always)]
+unsafe fn mul_slice_impl<const L: usize, const ADD: bool>(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    c: u8,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let (lo, hi) = split_tables(c);
+    let t = |t: &[u8; 16]| Simd::<u8, L>::from_array(core::array::from_fn(|i| t[i % 16]));
+    let (lo_v, hi_v) = (t(&lo), t(&hi));
+    let mut i = 0;
+    while i + L <= len {
+        let v = load_simd::<u8, L>(src.add(i));
+        let mut p = lo_v.swizzle_dyn(v & Simd::splat(15)) ^ hi_v.swizzle_dyn(v >> Simd::splat(4));
+        if ADD {
+            p ^= load_simd::<u8, L>(dst.add(i));
+        }
+        store_simd(dst.add(i), p);
+        i += L;
+    }
+    while i < len {
+        let b = *src.add(i);
+        let p = lo[(b & 15) as usize] ^ hi[(b >> 4) as usize];
+        *dst.add(i) = if ADD { *dst.add(i) ^ p } else { p };
+        i += 1;
+    }
+}
+
+/// Product of `a` and `b` in GF(2^8).
+#[unsafe(export_name = c_symbol!("gf_mul"))]
+pub extern "C" fn gf_mul(a: u8, b: u8) -> u8 {
+    mul(a, b)
+}
+
+/// Multiplicative inverse of `a` in GF(2^8); 0 (which has none) maps to 0.
+#[unsafe(export_name = c_symbol!("gf_inv"))]
+pub extern "C" fn gf_inv(a: u8) -> u8 {
+    inv(a)
+}
+
+/// Compute `dst[i] = coef * src[i]` in GF(2^8) for `len` bytes.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+/// exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("gf_mul_slice"))]
+pub unsafe extern "C" fn gf_mul_slice(src: *const u8, len: usize, dst: *mut u8, coef: u8) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    mul_slice_impl::<GF_LANES, false>(src, len, dst, coef);
+}
+
+/// Compute `dst[i] ^= coef * src[i]` in GF(2^8) for `len` bytes: one term
+/// of a Reed–Solomon parity or reconstruction sum.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes.  `dst` may alias `src`
+/// exactly, but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("gf_mul_add_slice"))]
+pub unsafe extern "C" fn gf_mul_add_slice(src: *const u8, len: usize, dst: *mut u8, coef: u8) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    mul_slice_impl::<GF_LANES, true>(src, len, dst, coef);
+}
+
+/// Multiply the `rows x cols` coefficient matrix `coefs` (row-major) by
+/// `cols` source shards of `len` bytes: `dsts[r][i] = sum over c of
+/// coefs[r * cols + c] * srcs[c][i]`.  This is Reed–Solomon encoding (with
+/// the parity rows of the generator matrix) and decoding (with the rows of
+/// the inverted survivor matrix).  Shards are walked in blocks so each
+/// source block is reused from cache by every output row.
+///
+/// # Safety
+/// `coefs` must be valid for `rows * cols` bytes, `srcs` for `cols` and
+/// `dsts` for `rows` pointers, each valid for `len` bytes.  Outputs must
+/// not overlap each other or any source.
+#[unsafe(export_name = c_symbol!("gf_matmul"))]
+pub unsafe extern "C" fn gf_matmul(
+    coefs: *const u8,
+    rows: usize,
+    cols: usize,
+    srcs: *const *const u8,
+    dsts: *const *mut u8,
+    len: usize,
+) {
+    if coefs.is_null() || srcs.is_null() || dsts.is_null() || rows == 0 || len == 0 {
+        return;
+    }
+    let coefs = core::slice::from_raw_parts(coefs, rows * cols);
+    let srcs = core::slice::from_raw_parts(srcs, cols);
+    let dsts = core::slice::from_raw_parts(dsts, rows);
+    let mut at = 0;
+    while at < len {
+        let n = MATMUL_BLOCK.min(len - at);
+        for (r, &dst) in dsts.iter().enumerate() {
+            let dst = dst.add(at);
+            if cols == 0 {
+                core::ptr::write_bytes(dst, 0, n);
+            }
+            for (c, &src) in srcs.iter().enumerate() {
+                let coef = coefs[r * cols + c];
+                if c == 0 {
+                    mul_slice_impl::<GF_LANES, false>(src.add(at), n, dst, coef);
+                } else {
+                    mul_slice_impl::<GF_LANES, true>(src.add(at), n, dst, coef);
+                }
+            }
+        }
+        at += n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf_scalar() {
+        assert_eq!(gf_mul(3, 7), 9);
+        assert_eq!(gf_mul(2, 0x80), 0x1d);
+        assert_eq!(gf_mul(0, 0x53), 0);
+        assert_eq!(gf_inv(0), 0);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1, "a={a}");
+            assert_eq!(gf_mul(a, 1), a);
+        }
+    }
+
+    #[test]
+    fn test_gf_mul_slices() {
+        let src: Vec<u8> = (0..300u32).map(|i| (i * 89 + 3) as u8).collect();
+        for coef in [0u8, 1, 2, 0x53, 0xff] {
+            for len in [1usize, 31, 32, 33, 300] {
+                let want: Vec<u8> = src[..len].iter().map(|&b| mul(coef, b)).collect();
+                let mut dst = vec![0xaau8; len];
+                unsafe { gf_mul_slice(src.as_ptr(), len, dst.as_mut_ptr(), coef) };
+                assert_eq!(dst, want, "coef={coef} len={len}");
+                let mut acc = src[..len].to_vec();
+                unsafe { gf_mul_add_slice(src.as_ptr(), len, acc.as_mut_ptr(), coef) };
+                let want: Vec<u8> = want.iter().zip(&src).map(|(p, s)| p ^ s).collect();
+                assert_eq!(acc, want, "coef={coef} len={len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_gf_matmul_reed_solomon() {
+        // Systematic 4+2 code with Vandermonde-style parity rows; losing two
+        // data shards is repaired by inverting the 2x2 survivor system.
+        let len = MATMUL_BLOCK + 100;
+        let data: Vec<Vec<u8>> = (0..4u32)
+            .map(|s| {
+                (0..len as u32)
+                    .map(|i| (i * 31 + s * 97 + i / 7) as u8)
+                    .collect()
+            })
+            .collect();
+        let parity_rows = [[1u8, 1, 1, 1], [1, 2, 4, 8]];
+        let mut parity = vec![vec![0u8; len]; 2];
+        let srcs: Vec<*const u8> = data.iter().map(|d| d.as_ptr()).collect();
+        let dsts: Vec<*mut u8> = parity.iter_mut().map(|p| p.as_mut_ptr()).collect();
+        unsafe {
+            gf_matmul(
+                parity_rows.as_flattened().as_ptr(),
+                2,
+                4,
+                srcs.as_ptr(),
+                dsts.as_ptr(),
+                len,
+            )
+        };
+        for i in [0, 1, len - 1] {
+            let want = (0..4).fold(0, |a, s| a ^ mul(parity_rows[1][s], data[s][i]));
+            assert_eq!(parity[1][i], want);
+        }
+
+        // Lose data shards 0 and 1.  With q = p0 ^ d2 ^ d3 and
+        // r = p1 ^ 4*d2 ^ 8*d3: d0 ^ d1 = q and d0 ^ 2*d1 = r, so
+        // d1 = (q ^ r) / 3 and d0 = q ^ d1.
+        let i3 = inv(3);
+        let srcs = [
+            parity[0].as_ptr(),
+            parity[1].as_ptr(),
+            data[2].as_ptr(),
+            data[3].as_ptr(),
+        ];
+        let q = [1u8, 0, 1, 1];
+        let r = [0u8, 1, 4, 8];
+        let d1: [u8; 4] = core::array::from_fn(|c| mul(i3, q[c] ^ r[c]));
+        let d0: [u8; 4] = core::array::from_fn(|c| q[c] ^ d1[c]);
+        let mut repaired = vec![vec![0u8; len]; 2];
+        let dsts: Vec<*mut u8> = repaired.iter_mut().map(|p| p.as_mut_ptr()).collect();
+        let coefs = [d0, d1];
+        unsafe {
+            gf_matmul(
+                coefs.as_flattened().as_ptr(),
+                2,
+                4,
+                srcs.as_ptr(),
+                dsts.as_ptr(),
+                len,
+            )
+        };
+        assert_eq!(repaired[0], data[0]);
+        assert_eq!(repaired[1], data[1]);
+    }
+}
@@ -0,0 +1,520 @@
+//! Gorilla time-series block codecs (delta-of-delta timestamps, XOR floats).
+//!
+//! Streams are packed MSB-first into `u64` words: bit 63 of word 0 is the
+//! first bit emitted.  Bits are accumulated in a 64-bit register and flushed a
+//! word at a time, so emission costs a couple of shifts per field rather than
+//! a loop per bit.
+use crate::error::{SIMBA_ERR_CAPACITY, SIMBA_ERR_INVALID_INPUT, SIMBA_ERR_NULL_POINTER, fail};
+
+// === Bit stream helpers ======================================================
+
+struct BitWriter<'a> {
+    out: &'a mut [u64],
+    word: usize,
+    acc: u64,
+    used: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(out: &'a mut [u64]) -> Self {
+        Self {
+            out,
+            word: 0,
+            acc: 0,
+            used: 0,
+        }
+    }
+
+    /// Append the low `nbits` (1..=64) of `value`.  Returns `false` when the
+    /// output buffer is full.
+    #[inline(always)]
+    fn put(&mut self, value: u64, nbits: u32) -> bool {
+        let v = value & (u64::MAX >> (64 - nbits));
+        let free = 64 - self.used;
+        if nbits < free {
+            self.acc |= v << (free - nbits);
+            self.used += nbits;
+            return true;
+        }
+        let spill = nbits - free;
+        self.acc |= v >> spill;
+        if self.word == self.out.len() {
+            return false;
+        }
+        self.out[self.word] = self.acc;
+        self.word += 1;
+        self.acc = if spill == 0 { 0 } else { v << (64 - spill) };
+        self.used = spill;
+        true
+    }
+
+    /// Flush the partial word and return the total number of bits written.
+    fn finish(self) -> Option<usize> {
+        if self.used > 0 {
+            *self.out.get_mut(self.word)? = self.acc;
+        }
+        Some(self.word * 64 + self.used as usize)
+    }
+}
+
+struct BitReader<'a> {
+    src: &'a [u64],
+    nbits: usize,
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(src: &'a [u64], nbits: usize) -> Self {
+        Self {
+            src,
+            nbits: nbits.min(src.len() * 64),
+            pos: 0,
+        }
+    }
+
+    /// Read the next `nbits` (1..=64) bits, or `None` past the end of stream.
+    #[inline(always)]
+    fn get(&mut self, nbits: u32) -> Option<u64> {
+        if self.pos + nbits as usize > self.nbits {
+            return None;
+        }
+        let w = self.pos / 64;
+        let off = (self.pos % 64) as u32;
+        let mut window = (self.src[w] as u128) << 64;
+        if off + nbits > 64 {
+            window |= self.src[w + 1] as u128;
+        }
+        self.pos += nbits as usize;
+        Some(((window << off) >> (128 - nbits)) as u64)
+    }
+
+    /// Count leading 1 bits, stopping after `max` of them.
+    #[inline(always)]
+    fn ones_prefix(&mut self, max: u32) -> Option<u32> {
+        let mut n = 0;
+        while n < max && self.get(1)? == 1 {
+            n += 1;
+        }
+        Some(n)
+    }
+}
+
+/// Sign-extend the low `bits` of `v`.
+#[inline(always)]
+fn sign_extend(v: u64, bits: u32) -> i64 {
+    ((v << (64 - bits)) as i64) >> (64 - bits)
+}
+
+// === Delta-of-delta timestamps ===============================================
+
+/// `(prefix, prefix_len, payload_bits)` buckets for a non-zero delta-of-delta;
+/// a payload fits a bucket when it is representable as a signed value of
+/// `payload_bits`.  The final escape bucket carries the full 64-bit value so
+/// arbitrary `u64` sequences round-trip.
+const DOD_BUCKETS: [(u64, u32, u32); 4] = [
+    (0b10, 2, 7),
+    (0b110, 3, 9),
+    (0b1110, 4, 12),
+    (0b1111, 4, 64),
+];
+
+#[inline(always)]
+fn dod_encode_impl(ts: &[u64], out: &mut [u64]) -> Option<usize> {
+    let mut w = BitWriter::new(out);
+    let (&first, rest) = ts.split_first()?;
+    if !w.put(first, 64) {
+        return None;
+    }
+    let mut prev = first;
+    let mut prev_delta = 0i64;
+    for &t in rest {
+        let delta = t.wrapping_sub(prev) as i64;
+        let dod = delta.wrapping_sub(prev_delta);
+        prev = t;
+        prev_delta = delta;
+        if dod == 0 {
+            if !w.put(0, 1) {
+                return None;
+            }
+            continue;
+        }
+        let &(prefix, plen, bits) = DOD_BUCKETS
+            .iter()
+            .find(|&&(_, _, bits)| bits == 64 || sign_extend(dod as u64, bits) == dod)?;
+        if !w.put(prefix, plen) || !w.put(dod as u64, bits) {
+            return None;
+        }
+    }
+    w.finish()
+}
+
+#[inline(always)]
+fn dod_decode_impl(src: &[u64], nbits: usize, out: &mut [u64]) -> Option<usize> {
+    let mut r = BitReader::new(src, nbits);
+    let Some((first, rest)) = out.split_first_mut() else {
+        return Some(0);
+    };
+    *first = r.get(64)?;
+    let mut prev = *first;
+    let mut prev_delta = 0i64;
+    for slot in rest {
+        let ones = r.ones_prefix(4)?;
+        let dod = if ones == 0 {
+            0
+        } else {
+            let bits = DOD_BUCKETS[ones as usize - 1].2;
+            sign_extend(r.get(bits)?, bits)
+        };
+        prev_delta = prev_delta.wrapping_add(dod);
+        prev = prev.wrapping_add(prev_delta as u64);
+        *slot = prev;
+    }
+    Some(out.len())
+}
+
+/// Encode `n` timestamps with Gorilla delta-of-delta compression into the
+/// MSB-first bit stream `dst_bits` (`cap` u64 words).
+///
+/// Layout: the first timestamp verbatim (64 bits), then per value the
+/// delta-of-delta `D` (the first delta is taken against 0) as
+/// `0` when `D == 0`, `10`+7 bits, `110`+9 bits, `1110`+12 bits (signed
+/// two's complement) or `1111`+64 bits.  Deltas use wrapping arithmetic.
+///
+/// Returns the number of bits written, or -1 if `cap` words are too few.
+///
+/// # Safety
+/// `ts` must be valid for `n` u64 values and `dst_bits` for `cap` u64 words.
+#[unsafe(export_name = c_symbol!("dod_encode_u64"))]
+pub unsafe extern "C" fn dod_encode_u64(
+    ts: *const u64,
+    n: usize,
+    dst_bits: *mut u64,
+    cap: usize,
+) -> isize {
+    if ts.is_null() || n == 0 {
+        return 0;
+    }
+    if dst_bits.is_null() {
+        fail(SIMBA_ERR_NULL_POINTER, format_args!("dst_bits is null"));
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(ts, n);
+    let out = core::slice::from_raw_parts_mut(dst_bits, cap);
+    match dod_encode_impl(src, out) {
+        Some(bits) => bits as isize,
+        None => {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("encoding needs more than {cap} words"),
+            );
+            -1
+        }
+    }
+}
+
+/// Decode `n` timestamps produced by [`dod_encode_u64`] from the first
+/// `nbits` bits of `src_bits` into `out`.
+///
+/// Returns `n` on success, or -1 if the stream ends before `n` values.
+///
+/// # Safety
+/// `src_bits` must be valid for `ceil(nbits / 64)` u64 words and `out` for
+/// `n` u64 values.
+#[unsafe(export_name = c_symbol!("dod_decode_u64"))]
+pub unsafe extern "C" fn dod_decode_u64(
+    src_bits: *const u64,
+    nbits: usize,
+    out: *mut u64,
+    n: usize,
+) -> isize {
+    if out.is_null() || n == 0 {
+        return 0;
+    }
+    if src_bits.is_null() {
+        fail(SIMBA_ERR_NULL_POINTER, format_args!("src_bits is null"));
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(src_bits, nbits.div_ceil(64));
+    let dst = core::slice::from_raw_parts_mut(out, n);
+    match dod_decode_impl(src, nbits, dst) {
+        Some(n) => n as isize,
+        None => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("stream of {nbits} bits ends before {n} values"),
+            );
+            -1
+        }
+    }
+}
+
+// === XOR-compressed floats ===================================================
+
+/// Leading-zero counts are stored in 5 bits, so larger values are clamped.
+const XOR_MAX_LEADING: u32 = 31;
+
+#[inline(always)]
+fn xorf_encode_impl(values: &[f64], out: &mut [u64]) -> Option<usize> {
+    let mut w = BitWriter::new(out);
+    let (&first, rest) = values.split_first()?;
+    if !w.put(first.to_bits(), 64) {
+        return None;
+    }
+    let mut prev = first.to_bits();
+    // Current (leading, trailing) window; `None` until the first control-11.
+    let mut window: Option<(u32, u32)> = None;
+    for &v in rest {
+        let bits = v.to_bits();
+        let x = bits ^ prev;
+        prev = bits;
+        if x == 0 {
+            if !w.put(0, 1) {
+                return None;
+            }
+            continue;
+        }
+        let lead = x.leading_zeros().min(XOR_MAX_LEADING);
+        let trail = x.trailing_zeros();
+        let ok = match window {
+            Some((pl, pt)) if lead >= pl && trail >= pt => {
+                // `10`: reuse the previous window.
+                w.put(0b10, 2) && w.put(x >> pt, 64 - pl - pt)
+            }
+            _ => {
+                // `11`: new window, 5-bit leading count, 6-bit length (64 → 0).
+                let len = 64 - lead - trail;
+                window = Some((lead, trail));
+                w.put(0b11, 2)
+                    && w.put(lead as u64, 5)
+                    && w.put(len as u64 & 63, 6)
+                    && w.put(x >> trail, len)
+            }
+        };
+        if !ok {
+            return None;
+        }
+    }
+    w.finish()
+}
+
+#[inline(always)]
+fn xorf_decode_impl(src: &[u64], nbits: usize, out: &mut [f64]) -> Option<usize> {
+    let mut r = BitReader::new(src, nbits);
+    let Some((first, rest)) = out.split_first_mut() else {
+        return Some(0);
+    };
+    let mut prev = r.get(64)?;
+    *first = f64::from_bits(prev);
+    let mut window: Option<(u32, u32)> = None;
+    for slot in rest {
+        if r.get(1)? == 1 {
+            if r.get(1)? == 1 {
+                let lead = r.get(5)? as u32;
+                let len = match r.get(6)? as u32 {
+                    0 => 64,
+                    l => l,
+                };
+                if lead + len > 64 {
+                    return None;
+                }
+                window = Some((lead, 64 - lead - len));
+            }
+            let (lead, trail) = window?;
+            prev ^= r.get(64 - lead - trail)? << trail;
+        }
+        *slot = f64::from_bits(prev);
+    }
+    Some(out.len())
+}
+
+/// Encode `n` f64 values with Gorilla XOR compression into the MSB-first bit
+/// stream `dst_bits` (`cap` u64 words).
+///
+/// Layout: the first value's bits verbatim, then per value `X = bits ^ prev`
+/// as `0` when `X == 0`; `10` + the meaningful bits when `X` fits the current
+/// leading/trailing-zero window; otherwise `11` + 5-bit leading-zero count +
+/// 6-bit meaningful length (64 encoded as 0) + the meaningful bits, which
+/// also becomes the new window.  NaN payloads and signed zeros round-trip.
+///
+/// Returns the number of bits written, or -1 if `cap` words are too few.
+///
+/// # Safety
+/// `values` must be valid for `n` f64 values and `dst_bits` for `cap` u64
+/// words.
+#[unsafe(export_name = c_symbol!("xorf_encode_f64"))]
+pub unsafe extern "C" fn xorf_encode_f64(
+    values: *const f64,
+    n: usize,
+    dst_bits: *mut u64,
+    cap: usize,
+) -> isize {
+    if values.is_null() || n == 0 {
+        return 0;
+    }
+    if dst_bits.is_null() {
+        fail(SIMBA_ERR_NULL_POINTER, format_args!("dst_bits is null"));
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(values, n);
+    let out = core::slice::from_raw_parts_mut(dst_bits, cap);
+    match xorf_encode_impl(src, out) {
+        Some(bits) => bits as isize,
+        None => {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("encoding needs more than {cap} words"),
+            );
+            -1
+        }
+    }
+}
+
+/// Decode `n` values produced by [`xorf_encode_f64`] from the first `nbits`
+/// bits of `src_bits` into `out`.
+///
+/// Returns `n` on success, or -1 if the stream is truncated or malformed.
+///
+/// # Safety
+/// `src_bits` must be valid for `ceil(nbits / 64)` u64 words and `out` for
+/// `n` f64 values.
+#[unsafe(export_name = c_symbol!("xorf_decode_f64"))]
+pub unsafe extern "C" fn xorf_decode_f64(
+    src_bits: *const u64,
+    nbits: usize,
+    out: *mut f64,
+    n: usize,
+) -> isize {
+    if out.is_null() || n == 0 {
+        return 0;
+    }
+    if src_bits.is_null() {
+        fail(SIMBA_ERR_NULL_POINTER, format_args!("src_bits is null"));
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(src_bits, nbits.div_ceil(64));
+    let dst = core::slice::from_raw_parts_mut(out, n);
+    match xorf_decode_impl(src, nbits, dst) {
+        Some(n) => n as isize,
+        None => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("stream of {nbits} bits ends before {n} values"),
+            );
+            -1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn round_trip(ts: &[u64]) -> usize {
+        let mut buf = vec![0u64; ts.len() + 2];
+        let bits =
+            unsafe { super::dod_encode_u64(ts.as_ptr(), ts.len(), buf.as_mut_ptr(), buf.len()) };
+        assert!(bits > 0);
+        let mut out = vec![0u64; ts.len()];
+        let n = unsafe {
+            super::dod_decode_u64(buf.as_ptr(), bits as usize, out.as_mut_ptr(), ts.len())
+        };
+        assert_eq!(n as usize, ts.len());
+        assert_eq!(out, ts);
+        bits as usize
+    }
+
+    #[test]
+    fn test_dod_regular_series_is_one_bit_per_point() {
+        let ts: Vec<u64> = (0..1000).map(|i| 1_700_000_000 + 60 * i).collect();
+        // 64-bit header, first delta in the 7-bit bucket, then 1 bit each.
+        assert_eq!(round_trip(&ts), 64 + 2 + 7 + 998);
+    }
+
+    #[test]
+    fn test_dod_round_trip_irregular() {
+        let mut x = 0x1234_5678u64;
+        let mut t = 1_600_000_000_000u64;
+        let mut ts = Vec::new();
+        for i in 0..2000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            t = t.wrapping_add(match i % 5 {
+                0 => 1000,
+                1 => 1000 + x % 50,
+                2 => x % 3000,
+                3 => x,
+                _ => 0,
+            });
+            ts.push(t);
+        }
+        ts.extend_from_slice(&[0, u64::MAX, 1, u64::MAX / 2]);
+        round_trip(&ts);
+    }
+
+    #[test]
+    fn test_dod_capacity_and_truncation() {
+        let ts = [10u64, 20, 30, 1 << 40];
+        let mut small = [0u64; 1];
+        assert_eq!(
+            unsafe { super::dod_encode_u64(ts.as_ptr(), 4, small.as_mut_ptr(), 1) },
+            -1
+        );
+
+        let mut buf = [0u64; 4];
+        let bits = unsafe { super::dod_encode_u64(ts.as_ptr(), 4, buf.as_mut_ptr(), 4) };
+        let mut out = [0u64; 4];
+        let got =
+            unsafe { super::dod_decode_u64(buf.as_ptr(), bits as usize - 1, out.as_mut_ptr(), 4) };
+        assert_eq!(got, -1);
+    }
+
+    fn xorf_round_trip(vals: &[f64]) -> usize {
+        let mut buf = vec![0u64; 2 * vals.len() + 2];
+        let bits = unsafe {
+            super::xorf_encode_f64(vals.as_ptr(), vals.len(), buf.as_mut_ptr(), buf.len())
+        };
+        assert!(bits > 0);
+        let mut out = vec![0f64; vals.len()];
+        let n = unsafe {
+            super::xorf_decode_f64(buf.as_ptr(), bits as usize, out.as_mut_ptr(), vals.len())
+        };
+        assert_eq!(n as usize, vals.len());
+        for (a, b) in vals.iter().zip(&out) {
+            assert_eq!(a.to_bits(), b.to_bits());
+        }
+        bits as usize
+    }
+
+    #[test]
+    fn test_xorf_constant_series() {
+        let vals = vec![42.5f64; 100];
+        assert_eq!(xorf_round_trip(&vals), 64 + 99);
+    }
+
+    #[test]
+    fn test_xorf_round_trip_mixed() {
+        let mut vals: Vec<f64> = (0..500).map(|i| 20.0 + (i as f64 * 0.1).sin()).collect();
+        vals.extend_from_slice(&[
+            0.0,
+            -0.0,
+            f64::NAN,
+            f64::INFINITY,
+            f64::MIN_POSITIVE,
+            f64::from_bits(1),
+            f64::from_bits(u64::MAX),
+            1.0,
+            1.0,
+        ]);
+        xorf_round_trip(&vals);
+    }
+
+    #[test]
+    fn test_xorf_truncated_stream() {
+        let vals = [1.0f64, 2.0, 3.0];
+        let mut buf = [0u64; 8];
+        let bits = unsafe { super::xorf_encode_f64(vals.as_ptr(), 3, buf.as_mut_ptr(), 8) };
+        let mut out = [0f64; 3];
+        let got =
+            unsafe { super::xorf_decode_f64(buf.as_ptr(), bits as usize - 1, out.as_mut_ptr(), 3) };
+        assert_eq!(got, -1);
+    }
+}
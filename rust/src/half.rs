@@ -0,0 +1,278 @@
+//! IEEE 754 half-precision (binary16) <-> f32 bulk conversion.
+//!
+//! x86_64 uses F16C when the CPU has it and aarch64 the base AdvSIMD
+//! `FCVTL`/`FCVTN` instructions; elsewhere a portable SIMD bit-twiddling
+//! kernel does the same job.  All paths agree bit for bit: f32 -> f16 rounds
+//! to nearest-even, overflow goes to infinity, NaNs stay NaN (quietened, with
+//! the top payload bits kept).
+use core::simd::Simd;
+use core::simd::prelude::{SimdFloat, SimdPartialEq, SimdPartialOrd, SimdUint};
+
+const HALF_LANES: usize = 8;
+
+// === Portable conversion =====================================================
+
+/// `2^-14` as f32 bits: the scale of a half subnormal's mantissa.
+const SUBNORMAL_MAGIC: u32 = 113 << 23;
+/// Adding this (as f32) to a value below `2^-14` leaves its half mantissa,
+/// correctly rounded, in the low bits.
+const DENORM_MAGIC: u32 = ((127 - 15) + (23 - 10) + 1) << 23;
+
+#[inline(always)]
+fn f16_to_f32_lanes(h: Simd<u32, HALF_LANES>) -> Simd<f32, HALF_LANES> {
+    let shifted_exp = Simd::splat(0x7C00 << 13);
+    let mut o = (h & Simd::splat(0x7FFF)) << 13;
+    let exp = o & shifted_exp;
+    o += Simd::splat((127 - 15) << 23);
+    // Inf/NaN: push the exponent to 255 and quieten NaNs.
+    let special = exp.simd_eq(shifted_exp);
+    let nan = special & (h & Simd::splat(0x3FF)).simd_ne(Simd::splat(0));
+    o = special.select(o + Simd::splat((128 - 16) << 23), o);
+    o = nan.select(o | Simd::splat(0x0040_0000), o);
+    // Zero/subnormal: renormalise through an f32 subtract.
+    let sub = exp.simd_eq(Simd::splat(0));
+    let renorm = Simd::<f32, HALF_LANES>::from_bits(o + Simd::splat(1 << 23))
+        - Simd::splat(f32::from_bits(SUBNORMAL_MAGIC));
+    o = sub.select(renorm.to_bits(), o);
+    Simd::from_bits(o | (h & Simd::splat(0x8000)) << 16)
+}
+
+#[inline(always)]
+fn f32_to_f16_lanes(v: Simd<f32, HALF_LANES>) -> Simd<u32, HALF_LANES> {
+    let bits = v.to_bits();
+    let sign = bits & Simd::splat(0x8000_0000);
+    let f = bits ^ sign;
+    // Normal range: rebias the exponent and round to nearest-even.
+    let odd = (f >> 13) & Simd::splat(1);
+    let normal = (f + Simd::splat((((15 - 127) << 23) as u32).wrapping_add(0xFFF)) + odd) >> 13;
+    // Below 2^-14: the magic add rounds the mantissa into place.
+    let tiny = (Simd::<f32, HALF_LANES>::from_bits(f) + Simd::splat(f32::from_bits(DENORM_MAGIC)))
+        .to_bits()
+        - Simd::splat(DENORM_MAGIC);
+    let mut o = f.simd_lt(Simd::splat(113 << 23)).select(tiny, normal);
+    // Overflow to infinity; NaNs keep the top payload bits, quietened.
+    o = f
+        .simd_ge(Simd::splat((127 + 16) << 23))
+        .select(Simd::splat(0x7C00), o);
+    let nan = f.simd_gt(Simd::splat(0x7F80_0000));
+    o = nan.select(Simd::splat(0x7E00) | (f >> 13) & Simd::splat(0x3FF), o);
+    o | sign >> 16
+}
+
+fn f16_to_f32_portable(src: &[u16], dst: &mut [f32]) {
+    let mut s = src.chunks_exact(HALF_LANES);
+    let mut d = dst.chunks_exact_mut(HALF_LANES);
+    for (s, d) in (&mut s).zip(&mut d) {
+        let h = Simd::<u16, HALF_LANES>::from_slice(s).cast::<u32>();
+        f16_to_f32_lanes(h).copy_to_slice(d);
+    }
+    for (&h, d) in s.remainder().iter().zip(d.into_remainder()) {
+        *d = f16_to_f32_lanes(Simd::splat(h as u32))[0];
+    }
+}
+
+fn f32_to_f16_portable(src: &[f32], dst: &mut [u16]) {
+    let mut s = src.chunks_exact(HALF_LANES);
+    let mut d = dst.chunks_exact_mut(HALF_LANES);
+    for (s, d) in (&mut s).zip(&mut d) {
+        f32_to_f16_lanes(Simd::from_slice(s))
+            .cast::<u16>()
+            .copy_to_slice(d);
+    }
+    for (&f, d) in s.remainder().iter().zip(d.into_remainder()) {
+        *d = f32_to_f16_lanes(Simd::splat(f))[0] as u16;
+    }
+}
+
+// === Hardware conversion =====================================================
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx,f16c")]
+unsafe fn f16_to_f32_f16c(src: &[u16], dst: &mut [f32]) {
+    use core::arch::x86_64::*;
+    let n = src.len() / 8 * 8;
+    for i in (0..n).step_by(8) {
+        let h = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        _mm256_storeu_ps(dst.as_mut_ptr().add(i), _mm256_cvtph_ps(h));
+    }
+    f16_to_f32_portable(&src[n..], &mut dst[n..]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx,f16c")]
+unsafe fn f32_to_f16_f16c(src: &[f32], dst: &mut [u16]) {
+    use core::arch::x86_64::*;
+    let n = src.len() / 8 * 8;
+    for i in (0..n).step_by(8) {
+        let v = _mm256_loadu_ps(src.as_ptr().add(i));
+        let h = _mm256_cvtps_ph::<_MM_FROUND_TO_NEAREST_INT>(v);
+        _mm_storeu_si128(dst.as_mut_ptr().add(i) as *mut __m128i, h);
+    }
+    f32_to_f16_portable(&src[n..], &mut dst[n..]);
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn f16_to_f32_neon(src: &[u16], dst: &mut [f32]) {
+    let n = src.len() / 8 * 8;
+    for i in (0..n).step_by(8) {
+        core::arch::asm!(
+            "ldr {h:q}, [{s}]",
+            "fcvtl {lo:v}.4s, {h:v}.4h",
+            "fcvtl2 {hi:v}.4s, {h:v}.8h",
+            "stp {lo:q}, {hi:q}, [{d}]",
+            s = in(reg) src.as_ptr().add(i),
+            d = in(reg) dst.as_mut_ptr().add(i),
+            h = out(vreg) _,
+            lo = out(vreg) _,
+            hi = out(vreg) _,
+            options(nostack, preserves_flags),
+        );
+    }
+    f16_to_f32_portable(&src[n..], &mut dst[n..]);
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn f32_to_f16_neon(src: &[f32], dst: &mut [u16]) {
+    let n = src.len() / 8 * 8;
+    for i in (0..n).step_by(8) {
+        core::arch::asm!(
+            "ldp {lo:q}, {hi:q}, [{s}]",
+            "fcvtn {h:v}.4h, {lo:v}.4s",
+            "fcvtn2 {h:v}.8h, {hi:v}.4s",
+            "str {h:q}, [{d}]",
+            s = in(reg) src.as_ptr().add(i),
+            d = in(reg) dst.as_mut_ptr().add(i),
+            h = out(vreg) _,
+            lo = out(vreg) _,
+            hi = out(vreg) _,
+            options(nostack, preserves_flags),
+        );
+    }
+    f32_to_f16_portable(&src[n..], &mut dst[n..]);
+}
+
+// === Exports =================================================================
+
+/// Widen `len` IEEE half-precision values (raw `u16` bits) to f32.  Exact for
+/// every input; NaNs are quietened.
+///
+/// # Safety
+/// `src` must be null or valid for `len` u16 values and `dst` for `len` f32
+/// writes; they must not overlap.
+#[unsafe(export_name = c_symbol!("f16_to_f32"))]
+pub unsafe extern "C" fn f16_to_f32(src: *const u16, len: usize, dst: *mut f32) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, len);
+    #[cfg(target_arch = "x86_64")]
+    if x86_feature!("f16c") {
+        return f16_to_f32_f16c(src, dst);
+    }
+    #[cfg(target_arch = "aarch64")]
+    f16_to_f32_neon(src, dst);
+    #[cfg(not(target_arch = "aarch64"))]
+    f16_to_f32_portable(src, dst);
+}
+
+/// Narrow `len` f32 values to IEEE half precision (raw `u16` bits), rounding
+/// to nearest-even; values beyond the half range become infinities.
+///
+/// # Safety
+/// `src` must be null or valid for `len` f32 values and `dst` for `len` u16
+/// writes; they must not overlap.
+#[unsafe(export_name = c_symbol!("f32_to_f16"))]
+pub unsafe extern "C" fn f32_to_f16(src: *const f32, len: usize, dst: *mut u16) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, len);
+    #[cfg(target_arch = "x86_64")]
+    if x86_feature!("f16c") {
+        return f32_to_f16_f16c(src, dst);
+    }
+    #[cfg(target_arch = "aarch64")]
+    f32_to_f16_neon(src, dst);
+    #[cfg(not(target_arch = "aarch64"))]
+    f32_to_f16_portable(src, dst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every f16 bit pattern.
+    fn all_halves() -> Vec<u16> {
+        (0..=u16::MAX).collect()
+    }
+
+    #[test]
+    fn test_f16_to_f32() {
+        let src = all_halves();
+        let mut got = vec![0f32; src.len()];
+        let mut portable = vec![0f32; src.len()];
+        unsafe { f16_to_f32(src.as_ptr(), src.len(), got.as_mut_ptr()) };
+        f16_to_f32_portable(&src, &mut portable);
+        for (&h, (&g, &p)) in src.iter().zip(got.iter().zip(&portable)) {
+            assert_eq!(g.to_bits(), p.to_bits(), "h={h:#06x}");
+        }
+        assert_eq!(got[0x3C00], 1.0);
+        assert_eq!(got[0xC000], -2.0);
+        assert_eq!(got[0x0001], 2f32.powi(-24));
+        assert_eq!(got[0x7BFF], 65504.0);
+        assert_eq!(got[0x7C00], f32::INFINITY);
+        assert_eq!(got[0x8000].to_bits(), (-0f32).to_bits());
+        assert!(got[0x7C01].is_nan() && got[0xFE00].is_nan());
+    }
+
+    #[test]
+    fn test_f32_to_f16() {
+        // Round trip of every half is exact (NaNs aside, which are quietened).
+        let halves = all_halves();
+        let mut wide = vec![0f32; halves.len()];
+        f16_to_f32_portable(&halves, &mut wide);
+        let mut back = vec![0u16; halves.len()];
+        unsafe { f32_to_f16(wide.as_ptr(), wide.len(), back.as_mut_ptr()) };
+        for (&h, &b) in halves.iter().zip(&back) {
+            let want = if h & 0x7C00 == 0x7C00 && h & 0x3FF != 0 {
+                h | 0x200
+            } else {
+                h
+            };
+            assert_eq!(b, want, "h={h:#06x}");
+        }
+
+        // Hardware and portable paths agree on rounding, ties, subnormals,
+        // overflow and NaN payloads.
+        let mut src: Vec<f32> = (0..200_000u32)
+            .map(|i| f32::from_bits(i.wrapping_mul(0x9E37_79B9) ^ (i << 7)))
+            .collect();
+        src.extend([
+            1.0 + 2f32.powi(-11),
+            1.0 + 3.0 * 2f32.powi(-11),
+            65520.0,
+            65519.99,
+            2f32.powi(-25),
+            3.0 * 2f32.powi(-26),
+            -0.0,
+            f32::MAX,
+            f32::NEG_INFINITY,
+            f32::from_bits(0x7F80_0001),
+        ]);
+        let mut got = vec![0u16; src.len()];
+        let mut portable = vec![0u16; src.len()];
+        unsafe { f32_to_f16(src.as_ptr(), src.len(), got.as_mut_ptr()) };
+        f32_to_f16_portable(&src, &mut portable);
+        for (&f, (&g, &p)) in src.iter().zip(got.iter().zip(&portable)) {
+            assert_eq!(g, p, "f={:#010x}", f.to_bits());
+        }
+        let n = src.len();
+        assert_eq!(
+            got[n - 10..n - 4],
+            [0x3C00, 0x3C02, 0x7C00, 0x7BFF, 0x0000, 0x0001]
+        );
+        assert_eq!(got[n - 4..], [0x8000, 0x7C00, 0xFC00, 0x7E00]);
+    }
+}
@@ -0,0 +1,1224 @@
+//! Non-cryptographic hashes: xxHash64 and XXH3 (64- and 128-bit), one-shot
+//! and streaming, wyhash, FNV-1a, and keyed SipHash-2-4.  Digests match the
+//! reference implementations (and Go's `hash/fnv`).
+use crate::iov::Iovec;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::simd::Simd;
+use core::simd::prelude::SimdPartialOrd;
+use core::simd::simd_swizzle;
+
+const P32_1: u64 = 0x9E37_79B1;
+const P32_2: u64 = 0x85EB_CA77;
+const P32_3: u64 = 0xC2B2_AE3D;
+const P64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const P64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const P64_3: u64 = 0x1656_67B1_9E37_79F9;
+const P64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const P64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+#[inline(always)]
+fn read64(b: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(b[at..at + 8].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read32(b: &[u8], at: usize) -> u64 {
+    u32::from_le_bytes(b[at..at + 4].try_into().unwrap()) as u64
+}
+
+// === xxHash64 ================================================================
+
+#[inline(always)]
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(P64_2))
+        .rotate_left(31)
+        .wrapping_mul(P64_1)
+}
+
+#[inline(always)]
+fn xxh64_merge(acc: u64, v: u64) -> u64 {
+    (acc ^ xxh64_round(0, v))
+        .wrapping_mul(P64_1)
+        .wrapping_add(P64_4)
+}
+
+#[inline(always)]
+fn xxh64_avalanche(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(P64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(P64_3);
+    h ^ (h >> 32)
+}
+
+/// Streaming xxHash64 state.  Opaque to callers.
+pub struct Xxh64 {
+    seed: u64,
+    v: [u64; 4],
+    buf: [u8; 32],
+    buffered: usize,
+    total: u64,
+}
+
+impl Xxh64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Xxh64 {
+            seed,
+            v: [
+                seed.wrapping_add(P64_1).wrapping_add(P64_2),
+                seed.wrapping_add(P64_2),
+                seed,
+                seed.wrapping_sub(P64_1),
+            ],
+            buf: [0; 32],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn stripe(&mut self, s: &[u8]) {
+        for (i, v) in self.v.iter_mut().enumerate() {
+            *v = xxh64_round(*v, read64(s, 8 * i));
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        if self.buffered > 0 {
+            let take = (32 - self.buffered).min(data.len());
+            self.buf[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < 32 {
+                return;
+            }
+            let buf = self.buf;
+            self.stripe(&buf);
+            self.buffered = 0;
+        }
+        let mut stripes = data.chunks_exact(32);
+        for s in &mut stripes {
+            self.stripe(s);
+        }
+        let rem = stripes.remainder();
+        self.buf[..rem.len()].copy_from_slice(rem);
+        self.buffered = rem.len();
+    }
+
+    pub(crate) fn digest(&self) -> u64 {
+        let [v1, v2, v3, v4] = self.v;
+        let mut h = if self.total >= 32 {
+            let h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            self.v.iter().fold(h, |h, &v| xxh64_merge(h, v))
+        } else {
+            self.seed.wrapping_add(P64_5)
+        };
+        h = h.wrapping_add(self.total);
+
+        let tail = &self.buf[..self.buffered];
+        let mut words = tail.chunks_exact(8);
+        for w in &mut words {
+            h ^= xxh64_round(0, read64(w, 0));
+            h = h.rotate_left(27).wrapping_mul(P64_1).wrapping_add(P64_4);
+        }
+        let mut rest = words.remainder();
+        if rest.len() >= 4 {
+            h ^= read32(rest, 0).wrapping_mul(P64_1);
+            h = h.rotate_left(23).wrapping_mul(P64_2).wrapping_add(P64_3);
+            rest = &rest[4..];
+        }
+        for &b in rest {
+            h ^= (b as u64).wrapping_mul(P64_5);
+            h = h.rotate_left(11).wrapping_mul(P64_1);
+        }
+        xxh64_avalanche(h)
+    }
+}
+
+/// xxHash64 of `len` bytes.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("xxh64"))]
+pub unsafe extern "C" fn xxh64(ptr: *const u8, len: usize, seed: u64) -> u64 {
+    let mut s = Xxh64::new(seed);
+    if !ptr.is_null() && len > 0 {
+        s.update(core::slice::from_raw_parts(ptr, len));
+    }
+    s.digest()
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming xxHash64.  Release with [`xxh64_free`].
+#[unsafe(export_name = c_symbol!("xxh64_new"))]
+pub extern "C" fn xxh64_new(seed: u64) -> *mut Xxh64 {
+    Box::into_raw(Box::new(Xxh64::new(seed)))
+}
+
+/// Feed `len` more bytes to a streaming xxHash64.
+///
+/// # Safety
+/// `s` must be a live handle from [`xxh64_new`]; `ptr` must be null or valid
+/// for `len` bytes.
+#[unsafe(export_name = c_symbol!("xxh64_update"))]
+pub unsafe extern "C" fn xxh64_update(s: *mut Xxh64, ptr: *const u8, len: usize) {
+    if let Some(s) = s.as_mut().filter(|_| !ptr.is_null() && len > 0) {
+        s.update(core::slice::from_raw_parts(ptr, len));
+    }
+}
+
+/// Digest of everything fed so far; the state is left intact, so more input
+/// may follow.
+///
+/// # Safety
+/// `s` must be null or a live handle from [`xxh64_new`].
+#[unsafe(export_name = c_symbol!("xxh64_digest"))]
+pub unsafe extern "C" fn xxh64_digest(s: *const Xxh64) -> u64 {
+    s.as_ref().map_or(0, Xxh64::digest)
+}
+
+#[cfg(feature = "alloc")]
+/// Release a handle from [`xxh64_new`].  Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("xxh64_free"))]
+pub unsafe extern "C" fn xxh64_free(s: *mut Xxh64) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+// === XXH3 ====================================================================
+
+const SECRET_SIZE: usize = 192;
+const DEFAULT_SECRET: [u8; SECRET_SIZE] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+const STRIPE_LEN: usize = 64;
+/// Stripes per block: the secret advances 8 bytes per stripe.
+const STRIPES_PER_BLOCK: usize = (SECRET_SIZE - STRIPE_LEN) / 8;
+const MID_SIZE_MAX: usize = 240;
+/// Inputs buffered by the streaming state before stripes are consumed.
+const XXH3_BUFFER: usize = 256;
+
+#[inline(always)]
+fn mul128_fold64(a: u64, b: u64) -> u64 {
+    let p = a as u128 * b as u128;
+    p as u64 ^ (p >> 64) as u64
+}
+
+#[inline(always)]
+fn xxh3_avalanche(mut h: u64) -> u64 {
+    h ^= h >> 37;
+    h = h.wrapping_mul(0x1656_6791_9E37_79F9);
+    h ^ (h >> 32)
+}
+
+#[inline(always)]
+fn rrmxmx(mut h: u64, len: u64) -> u64 {
+    h ^= h.rotate_left(49) ^ h.rotate_left(24);
+    h = h.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+    h ^= (h >> 35).wrapping_add(len);
+    h = h.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+    h ^ (h >> 28)
+}
+
+#[inline(always)]
+fn mix16(input: &[u8], at: usize, secret: &[u8], s: usize, seed: u64) -> u64 {
+    mul128_fold64(
+        read64(input, at) ^ read64(secret, s).wrapping_add(seed),
+        read64(input, at + 8) ^ read64(secret, s + 8).wrapping_sub(seed),
+    )
+}
+
+/// 128-bit variant of [`mix16`]: mixes two 16-byte lanes into `acc`.
+#[inline(always)]
+fn mix32(
+    acc: (u64, u64),
+    input: &[u8],
+    a: usize,
+    b: usize,
+    secret: &[u8],
+    s: usize,
+    seed: u64,
+) -> (u64, u64) {
+    let lo = acc.0.wrapping_add(mix16(input, a, secret, s, seed))
+        ^ read64(input, b).wrapping_add(read64(input, b + 8));
+    let hi = acc.1.wrapping_add(mix16(input, b, secret, s + 16, seed))
+        ^ read64(input, a).wrapping_add(read64(input, a + 8));
+    (lo, hi)
+}
+
+fn xxh3_64_short(input: &[u8], seed: u64) -> u64 {
+    let s = &DEFAULT_SECRET;
+    let len = input.len();
+    match len {
+        0 => xxh64_avalanche(seed ^ read64(s, 56) ^ read64(s, 64)),
+        1..=3 => {
+            let c = (input[0] as u64) << 16
+                | (input[len >> 1] as u64) << 24
+                | input[len - 1] as u64
+                | (len as u64) << 8;
+            xxh64_avalanche(c ^ (read32(s, 0) ^ read32(s, 4)).wrapping_add(seed))
+        }
+        4..=8 => {
+            let seed = seed ^ ((seed as u32).swap_bytes() as u64) << 32;
+            let bitflip = (read64(s, 8) ^ read64(s, 16)).wrapping_sub(seed);
+            let x = read32(input, len - 4) + (read32(input, 0) << 32);
+            rrmxmx(x ^ bitflip, len as u64)
+        }
+        9..=16 => {
+            let lo = read64(input, 0) ^ (read64(s, 24) ^ read64(s, 32)).wrapping_add(seed);
+            let hi = read64(input, len - 8) ^ (read64(s, 40) ^ read64(s, 48)).wrapping_sub(seed);
+            xxh3_avalanche(
+                (len as u64)
+                    .wrapping_add(lo.swap_bytes())
+                    .wrapping_add(hi)
+                    .wrapping_add(mul128_fold64(lo, hi)),
+            )
+        }
+        17..=128 => {
+            let mut acc = (len as u64).wrapping_mul(P64_1);
+            let rounds = (len - 1) / 32;
+            for i in (0..=rounds).rev() {
+                acc = acc
+                    .wrapping_add(mix16(input, 16 * i, s, 32 * i, seed))
+                    .wrapping_add(mix16(input, len - 16 * (i + 1), s, 32 * i + 16, seed));
+            }
+            xxh3_avalanche(acc)
+        }
+        _ => {
+            let mut acc = (len as u64).wrapping_mul(P64_1);
+            for i in 0..8 {
+                acc = acc.wrapping_add(mix16(input, 16 * i, s, 16 * i, seed));
+            }
+            acc = xxh3_avalanche(acc);
+            for i in 8..len / 16 {
+                acc = acc.wrapping_add(mix16(input, 16 * i, s, 16 * (i - 8) + 3, seed));
+            }
+            acc = acc.wrapping_add(mix16(input, len - 16, s, 136 - 17, seed));
+            xxh3_avalanche(acc)
+        }
+    }
+}
+
+fn xxh3_128_short(input: &[u8], seed: u64) -> (u64, u64) {
+    let s = &DEFAULT_SECRET;
+    let len = input.len();
+    match len {
+        0 => (
+            xxh64_avalanche(seed ^ read64(s, 64) ^ read64(s, 72)),
+            xxh64_avalanche(seed ^ read64(s, 80) ^ read64(s, 88)),
+        ),
+        1..=3 => {
+            let lo = (input[0] as u32) << 16
+                | (input[len >> 1] as u32) << 24
+                | input[len - 1] as u32
+                | (len as u32) << 8;
+            let hi = lo.swap_bytes().rotate_left(13);
+            let fl = (read32(s, 0) ^ read32(s, 4)).wrapping_add(seed);
+            let fh = (read32(s, 8) ^ read32(s, 12)).wrapping_sub(seed);
+            (
+                xxh64_avalanche(lo as u64 ^ fl),
+                xxh64_avalanche(hi as u64 ^ fh),
+            )
+        }
+        4..=8 => {
+            let seed = seed ^ ((seed as u32).swap_bytes() as u64) << 32;
+            let x = read32(input, 0) + (read32(input, len - 4) << 32);
+            let keyed = x ^ (read64(s, 16) ^ read64(s, 24)).wrapping_add(seed);
+            let m = keyed as u128 * P64_1.wrapping_add((len as u64) << 2) as u128;
+            let (mut lo, mut hi) = (m as u64, (m >> 64) as u64);
+            hi = hi.wrapping_add(lo << 1);
+            lo ^= hi >> 3;
+            lo ^= lo >> 35;
+            lo = lo.wrapping_mul(0x9FB2_1C65_1E98_DF25);
+            lo ^= lo >> 28;
+            (lo, xxh3_avalanche(hi))
+        }
+        9..=16 => {
+            let fl = (read64(s, 32) ^ read64(s, 40)).wrapping_sub(seed);
+            let fh = (read64(s, 48) ^ read64(s, 56)).wrapping_add(seed);
+            let in_lo = read64(input, 0);
+            let mut in_hi = read64(input, len - 8);
+            let m = (in_lo ^ in_hi ^ fl) as u128 * P64_1 as u128;
+            let mut m_lo = (m as u64).wrapping_add(((len - 1) as u64) << 54);
+            in_hi ^= fh;
+            let m_hi = ((m >> 64) as u64)
+                .wrapping_add(in_hi)
+                .wrapping_add((in_hi & 0xFFFF_FFFF).wrapping_mul(P32_2 - 1));
+            m_lo ^= m_hi.swap_bytes();
+            let h = m_lo as u128 * P64_2 as u128;
+            let h_hi = ((h >> 64) as u64).wrapping_add(m_hi.wrapping_mul(P64_2));
+            (xxh3_avalanche(h as u64), xxh3_avalanche(h_hi))
+        }
+        17..=128 => {
+            let mut acc = ((len as u64).wrapping_mul(P64_1), 0);
+            for i in (0..=(len - 1) / 32).rev() {
+                acc = mix32(acc, input, 16 * i, len - 16 * (i + 1), s, 32 * i, seed);
+            }
+            xxh3_128_finish(acc, len, seed)
+        }
+        _ => {
+            let mut acc = ((len as u64).wrapping_mul(P64_1), 0);
+            for i in 0..4 {
+                acc = mix32(acc, input, 32 * i, 32 * i + 16, s, 32 * i, seed);
+            }
+            acc = (xxh3_avalanche(acc.0), xxh3_avalanche(acc.1));
+            for i in 4..len / 32 {
+                acc = mix32(acc, input, 32 * i, 32 * i + 16, s, 32 * (i - 4) + 3, seed);
+            }
+            acc = mix32(
+                acc,
+                input,
+                len - 16,
+                len - 32,
+                s,
+                136 - 17 - 16,
+                0u64.wrapping_sub(seed),
+            );
+            xxh3_128_finish(acc, len, seed)
+        }
+    }
+}
+
+#[inline(always)]
+fn xxh3_128_finish(acc: (u64, u64), len: usize, seed: u64) -> (u64, u64) {
+    let lo = acc.0.wrapping_add(acc.1);
+    let hi = acc
+        .0
+        .wrapping_mul(P64_1)
+        .wrapping_add(acc.1.wrapping_mul(P64_4))
+        .wrapping_add((len as u64).wrapping_sub(seed).wrapping_mul(P64_2));
+    (xxh3_avalanche(lo), 0u64.wrapping_sub(xxh3_avalanche(hi)))
+}
+
+/// Long-input accumulator: eight 64-bit lanes fed one 64-byte stripe at a
+/// time, scrambled after every block of [`STRIPES_PER_BLOCK`] stripes.
+#[derive(Clone)]
+struct Xxh3Acc {
+    acc: Simd<u64, 8>,
+    /// Stripes consumed in the current block.
+    stripes: usize,
+}
+
+impl Xxh3Acc {
+    fn new() -> Self {
+        Xxh3Acc {
+            acc: Simd::from_array([P32_3, P64_1, P64_2, P64_3, P64_4, P32_2, P64_5, P32_1]),
+            stripes: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn accumulate(&mut self, stripe: &[u8], secret: &[u8]) {
+        let data = Simd::<u64, 8>::from_array(core::array::from_fn(|i| read64(stripe, 8 * i)));
+        let key = data ^ Simd::from_array(core::array::from_fn(|i| read64(secret, 8 * i)));
+        let product = (key & Simd::splat(0xFFFF_FFFF)) * (key >> 32);
+        // Each lane also takes its neighbour's raw input.
+        self.acc += product + simd_swizzle!(data, [1, 0, 3, 2, 5, 4, 7, 6]);
+    }
+
+    /// Consume one stripe that is not the last of the input.
+    #[inline(always)]
+    fn stripe(&mut self, stripe: &[u8], secret: &[u8; SECRET_SIZE]) {
+        self.accumulate(stripe, &secret[8 * self.stripes..]);
+        self.stripes += 1;
+        if self.stripes == STRIPES_PER_BLOCK {
+            let key = Simd::<u64, 8>::from_array(core::array::from_fn(|i| {
+                read64(secret, SECRET_SIZE - STRIPE_LEN + 8 * i)
+            }));
+            let a = self.acc;
+            self.acc = ((a ^ (a >> 47)) ^ key) * Simd::splat(P32_1);
+            self.stripes = 0;
+        }
+    }
+
+    /// Consume the final 64 bytes of the input and merge the lanes.
+    fn finish(mut self, last: &[u8], secret: &[u8; SECRET_SIZE], len: u64) -> (u64, u64) {
+        self.accumulate(last, &secret[SECRET_SIZE - STRIPE_LEN - 7..]);
+        let acc = self.acc.to_array();
+        let merge = |s: usize, start: u64| {
+            let r = (0..4).fold(start, |r, i| {
+                r.wrapping_add(mul128_fold64(
+                    acc[2 * i] ^ read64(secret, s + 16 * i),
+                    acc[2 * i + 1] ^ read64(secret, s + 16 * i + 8),
+                ))
+            });
+            xxh3_avalanche(r)
+        };
+        let lo = merge(11, len.wrapping_mul(P64_1));
+        let hi = merge(SECRET_SIZE - STRIPE_LEN - 11, !len.wrapping_mul(P64_2));
+        (lo, hi)
+    }
+}
+
+/// Default secret re-keyed with `seed`, as used for inputs over 240 bytes.
+fn seeded_secret(seed: u64) -> [u8; SECRET_SIZE] {
+    let mut out = DEFAULT_SECRET;
+    for (i, w) in out.chunks_exact_mut(8).enumerate() {
+        let v = read64(&DEFAULT_SECRET, 8 * i);
+        let v = if i % 2 == 0 {
+            v.wrapping_add(seed)
+        } else {
+            v.wrapping_sub(seed)
+        };
+        w.copy_from_slice(&v.to_le_bytes());
+    }
+    out
+}
+
+/// (low, high) halves of XXH3-128; the low half of the long-input path is
+/// also the XXH3-64 digest.
+fn xxh3_long(input: &[u8], seed: u64) -> (u64, u64) {
+    let secret = seeded_secret(seed);
+    let mut acc = Xxh3Acc::new();
+    let len = input.len();
+    for stripe in input[..len - 1].chunks_exact(STRIPE_LEN) {
+        acc.stripe(stripe, &secret);
+    }
+    acc.finish(&input[len - STRIPE_LEN..], &secret, len as u64)
+}
+
+fn xxh3_64_impl(input: &[u8], seed: u64) -> u64 {
+    if input.len() <= MID_SIZE_MAX {
+        xxh3_64_short(input, seed)
+    } else {
+        xxh3_long(input, seed).0
+    }
+}
+
+fn xxh3_128_impl(input: &[u8], seed: u64) -> (u64, u64) {
+    if input.len() <= MID_SIZE_MAX {
+        xxh3_128_short(input, seed)
+    } else {
+        xxh3_long(input, seed)
+    }
+}
+
+/// Streaming XXH3 state (64- and 128-bit digests).  Opaque to callers.
+pub struct Xxh3 {
+    seed: u64,
+    secret: [u8; SECRET_SIZE],
+    acc: Xxh3Acc,
+    /// Pending input; stripes are only consumed once more input follows them.
+    buf: [u8; XXH3_BUFFER],
+    buffered: usize,
+    /// Last consumed stripe, for a final stripe reaching back before `buf`.
+    last: [u8; STRIPE_LEN],
+    total: u64,
+}
+
+impl Xxh3 {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn new(seed: u64) -> Self {
+        Xxh3 {
+            seed,
+            secret: seeded_secret(seed),
+            acc: Xxh3Acc::new(),
+            buf: [0; XXH3_BUFFER],
+            buffered: 0,
+            last: [0; STRIPE_LEN],
+            total: 0,
+        }
+    }
+
+    /// Consume whole stripes, none of which is the last of the input.
+    fn consume(
+        acc: &mut Xxh3Acc,
+        last: &mut [u8; STRIPE_LEN],
+        secret: &[u8; SECRET_SIZE],
+        block: &[u8],
+    ) {
+        for stripe in block.chunks_exact(STRIPE_LEN) {
+            acc.stripe(stripe, secret);
+        }
+        last.copy_from_slice(&block[block.len() - STRIPE_LEN..]);
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        loop {
+            let take = (XXH3_BUFFER - self.buffered).min(data.len());
+            self.buf[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if data.is_empty() {
+                return;
+            }
+            // A full buffer with input still to come: all of it is consumable.
+            Self::consume(&mut self.acc, &mut self.last, &self.secret, &self.buf);
+            self.buffered = 0;
+            // Take whole buffers straight from the input, leaving at least one
+            // byte behind.
+            while data.len() > XXH3_BUFFER {
+                Self::consume(
+                    &mut self.acc,
+                    &mut self.last,
+                    &self.secret,
+                    &data[..XXH3_BUFFER],
+                );
+                data = &data[XXH3_BUFFER..];
+            }
+        }
+    }
+
+    /// Digest of an input longer than [`MID_SIZE_MAX`]; the pending bytes are
+    /// consumed on a copy so the state stays usable.
+    fn digest_long(&self) -> (u64, u64) {
+        let pending = &self.buf[..self.buffered];
+        let n = pending.len();
+        let mut acc = self.acc.clone();
+        for stripe in pending[..n - 1].chunks_exact(STRIPE_LEN) {
+            acc.stripe(stripe, &self.secret);
+        }
+        let mut last = [0u8; STRIPE_LEN];
+        if n >= STRIPE_LEN {
+            last.copy_from_slice(&pending[n - STRIPE_LEN..]);
+        } else {
+            last[..STRIPE_LEN - n].copy_from_slice(&self.last[n..]);
+            last[STRIPE_LEN - n..].copy_from_slice(pending);
+        }
+        acc.finish(&last, &self.secret, self.total)
+    }
+
+    pub(crate) fn digest64(&self) -> u64 {
+        if self.total <= MID_SIZE_MAX as u64 {
+            xxh3_64_short(&self.buf[..self.buffered], self.seed)
+        } else {
+            self.digest_long().0
+        }
+    }
+
+    pub(crate) fn digest128(&self) -> (u64, u64) {
+        if self.total <= MID_SIZE_MAX as u64 {
+            xxh3_128_short(&self.buf[..self.buffered], self.seed)
+        } else {
+            self.digest_long()
+        }
+    }
+}
+
+/// XXH3 64-bit hash of `len` bytes.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("xxh3_64"))]
+pub unsafe extern "C" fn xxh3_64(ptr: *const u8, len: usize, seed: u64) -> u64 {
+    let input = if ptr.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    xxh3_64_impl(input, seed)
+}
+
+/// XXH3 128-bit hash of `len` bytes, written to `out` as `[low64, high64]`.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for two
+/// u64 writes.
+#[unsafe(export_name = c_symbol!("xxh3_128"))]
+pub unsafe extern "C" fn xxh3_128(ptr: *const u8, len: usize, seed: u64, out: *mut u64) {
+    if out.is_null() {
+        return;
+    }
+    let input = if ptr.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    let (lo, hi) = xxh3_128_impl(input, seed);
+    *out = lo;
+    *out.add(1) = hi;
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming XXH3 (either digest width).  Release with [`xxh3_free`].
+#[unsafe(export_name = c_symbol!("xxh3_new"))]
+pub extern "C" fn xxh3_new(seed: u64) -> *mut Xxh3 {
+    Box::into_raw(Box::new(Xxh3::new(seed)))
+}
+
+/// Feed `len` more bytes to a streaming XXH3.
+///
+/// # Safety
+/// `s` must be a live handle from [`xxh3_new`]; `ptr` must be null or valid
+/// for `len` bytes.
+#[unsafe(export_name = c_symbol!("xxh3_update"))]
+pub unsafe extern "C" fn xxh3_update(s: *mut Xxh3, ptr: *const u8, len: usize) {
+    if let Some(s) = s.as_mut().filter(|_| !ptr.is_null() && len > 0) {
+        s.update(core::slice::from_raw_parts(ptr, len));
+    }
+}
+
+/// XXH3 64-bit digest of everything fed so far; more input may follow.
+///
+/// # Safety
+/// `s` must be null or a live handle from [`xxh3_new`].
+#[unsafe(export_name = c_symbol!("xxh3_digest64"))]
+pub unsafe extern "C" fn xxh3_digest64(s: *const Xxh3) -> u64 {
+    s.as_ref().map_or(0, Xxh3::digest64)
+}
+
+/// XXH3 128-bit digest of everything fed so far, written to `out` as
+/// `[low64, high64]`; more input may follow.
+///
+/// # Safety
+/// `s` must be null or a live handle from [`xxh3_new`]; `out` must be valid
+/// for two u64 writes.
+#[unsafe(export_name = c_symbol!("xxh3_digest128"))]
+pub unsafe extern "C" fn xxh3_digest128(s: *const Xxh3, out: *mut u64) {
+    if let Some(s) = s.as_ref().filter(|_| !out.is_null()) {
+        let (lo, hi) = s.digest128();
+        *out = lo;
+        *out.add(1) = hi;
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Release a handle from [`xxh3_new`].  Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("xxh3_free"))]
+pub unsafe extern "C" fn xxh3_free(s: *mut Xxh3) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+// === wyhash ==================================================================
+
+/// wyhash (final version 4) default secret.
+const WYP: [u64; 4] = [
+    0x2D35_8DCC_AA6C_78A5,
+    0x8BB8_4B93_962E_ACC9,
+    0x4B33_A62E_D433_D4A3,
+    0x4D5A_2DA5_1DE1_AA47,
+];
+
+#[inline(always)]
+fn wymum(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+    (r as u64, (r >> 64) as u64)
+}
+
+#[inline(always)]
+fn wymix(a: u64, b: u64) -> u64 {
+    let (lo, hi) = wymum(a, b);
+    lo ^ hi
+}
+
+/// wyhash (final version 4, default secret) of `len` bytes.
+///
+/// This is the recommended default for hashing short-to-medium keys: a
+/// couple of 64x64->128 multiplies per 16 bytes and no setup cost.  Prefer
+/// [`xxh3_64`] for bulk data and [`siphash24`] for untrusted keys.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("wyhash"))]
+pub unsafe extern "C" fn wyhash(ptr: *const u8, len: usize, seed: u64) -> u64 {
+    let p = if ptr.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    wyhash_impl(p, seed)
+}
+
+fn wyhash_impl(p: &[u8], seed: u64) -> u64 {
+    let len = p.len();
+    let mut seed = seed ^ wymix(seed ^ WYP[0], WYP[1]);
+    let (a, b) = if len <= 16 {
+        if len >= 4 {
+            let q = (len >> 3) << 2;
+            (
+                (read32(p, 0) << 32) | read32(p, q),
+                (read32(p, len - 4) << 32) | read32(p, len - 4 - q),
+            )
+        } else if len > 0 {
+            let a = (p[0] as u64) << 16 | (p[len >> 1] as u64) << 8 | p[len - 1] as u64;
+            (a, 0)
+        } else {
+            (0, 0)
+        }
+    } else {
+        let mut rest = p;
+        if rest.len() >= 48 {
+            let (mut see1, mut see2) = (seed, seed);
+            while rest.len() >= 48 {
+                seed = wymix(read64(rest, 0) ^ WYP[1], read64(rest, 8) ^ seed);
+                see1 = wymix(read64(rest, 16) ^ WYP[2], read64(rest, 24) ^ see1);
+                see2 = wymix(read64(rest, 32) ^ WYP[3], read64(rest, 40) ^ see2);
+                rest = &rest[48..];
+            }
+            seed ^= see1 ^ see2;
+        }
+        while rest.len() > 16 {
+            seed = wymix(read64(rest, 0) ^ WYP[1], read64(rest, 8) ^ seed);
+            rest = &rest[16..];
+        }
+        // The last 16 bytes of the input, overlapping already-mixed ones.
+        (read64(p, len - 16), read64(p, len - 8))
+    };
+    let (a, b) = wymum(a ^ WYP[1], b ^ seed);
+    wymix(a ^ WYP[0] ^ len as u64, b ^ WYP[1])
+}
+
+// === FNV-1a ==================================================================
+
+const FNV32_PRIME: u32 = 0x0100_0193;
+const FNV64_PRIME: u64 = 0x0000_0100_0000_01B3;
+/// FNV-1a 64 offset basis, the seed-0 start for [`hash_batch`].
+const FNV64_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+
+macro_rules! export_fnv1a {
+    ($name:ident, $t:ty, $go:literal, $offset:literal, $prime:ident) => {
+        #[doc = concat!(
+            "Update an FNV-1a ", stringify!($t), " hash with additional bytes, as Go's `hash/fnv` `",
+            $go, "`; start from the offset basis `", $offset, "`. ",
+            "The recurrence is inherently serial; this saves the per-byte Go overhead.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, init: $t) -> $t {
+            if ptr.is_null() || len == 0 {
+                return init;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let mut chunks = data.chunks_exact(4);
+            let mut h = init;
+            for c in &mut chunks {
+                h = (h ^ c[0] as $t).wrapping_mul($prime);
+                h = (h ^ c[1] as $t).wrapping_mul($prime);
+                h = (h ^ c[2] as $t).wrapping_mul($prime);
+                h = (h ^ c[3] as $t).wrapping_mul($prime);
+            }
+            chunks
+                .remainder()
+                .iter()
+                .fold(h, |h, &b| (h ^ b as $t).wrapping_mul($prime))
+        }
+    };
+}
+
+export_fnv1a!(fnv1a_32, u32, "New32a", "0x811C9DC5", FNV32_PRIME);
+export_fnv1a!(fnv1a_64, u64, "New64a", "0xCBF29CE484222325", FNV64_PRIME);
+
+// === SipHash-2-4 =============================================================
+
+#[inline(always)]
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// SipHash-2-4 of `len` bytes under the 128-bit key `(k0, k1)` (the key's
+/// first and second 8 bytes read little-endian).  Use for hash tables fed
+/// untrusted keys, where a keyed hash resists flooding.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("siphash24"))]
+pub unsafe extern "C" fn siphash24(k0: u64, k1: u64, ptr: *const u8, len: usize) -> u64 {
+    let data = if ptr.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    let mut v = [
+        k0 ^ 0x736F_6D65_7073_6575,
+        k1 ^ 0x646F_7261_6E64_6F6D,
+        k0 ^ 0x6C79_6765_6E65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let mut words = data.chunks_exact(8);
+    for w in &mut words {
+        let m = read64(w, 0);
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+    // Final word: the leftover bytes with the length (mod 256) on top.
+    let b = words
+        .remainder()
+        .iter()
+        .enumerate()
+        .fold((data.len() as u64) << 56, |b, (i, &x)| {
+            b | (x as u64) << (8 * i)
+        });
+    v[3] ^= b;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= b;
+    v[2] ^= 0xFF;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+// === Batch hashing ===========================================================
+
+/// Algorithm identifiers accepted by [`hash_batch`].
+const HASH_BATCH_XXH64: u32 = 0;
+const HASH_BATCH_XXH3_64: u32 = 1;
+const HASH_BATCH_WYHASH: u32 = 2;
+const HASH_BATCH_FNV1A_64: u32 = 3;
+
+/// Keys hashed side by side in the multi-lane FNV-1a path.
+const BATCH_LANES: usize = 8;
+
+/// FNV-1a 64 of `BATCH_LANES` keys at once, starting from `h`.  Each step
+/// takes byte `i` of every key still that long; shorter keys keep their hash.
+fn fnv1a_64_lanes(
+    keys: &[&[u8]; BATCH_LANES],
+    mut h: Simd<u64, BATCH_LANES>,
+) -> Simd<u64, BATCH_LANES> {
+    let lens = Simd::from_array(keys.map(|k| k.len() as u64));
+    let longest = keys.iter().map(|k| k.len()).max().unwrap_or(0);
+    let prime = Simd::splat(FNV64_PRIME);
+    for i in 0..longest {
+        let b = Simd::from_array(keys.map(|k| k.get(i).copied().unwrap_or(0) as u64));
+        let live = Simd::splat(i as u64).simd_lt(lens);
+        h = live.select((h ^ b) * prime, h);
+    }
+    h
+}
+
+/// Hash `n` independent keys in one call, writing `out[i] = hash(keys[i],
+/// seeds[i])`.  `kind` selects the algorithm: 0 xxHash64, 1 XXH3-64,
+/// 2 wyhash, 3 FNV-1a 64.  A null `seeds` hashes every key with seed 0; for
+/// FNV-1a the seed is XOR-ed into the offset basis, so 0 gives the standard
+/// digest.  Returns the number of hashes written: `n`, or 0 for an unknown
+/// `kind` or null `keys`/`out`.
+///
+/// FNV-1a runs [`BATCH_LANES`] keys side by side in SIMD lanes; the other
+/// algorithms are built on 64x64->128 multiplies that do not vectorise, so
+/// they loop over the keys, which still removes the per-key FFI call.
+///
+/// # Safety
+/// `keys` must be valid for `n` entries, each null or valid for `len` bytes;
+/// `seeds` must be null or valid for `n` reads; `out` must be valid for `n`
+/// writes.
+#[unsafe(export_name = c_symbol!("hash_batch"))]
+pub unsafe extern "C" fn hash_batch(
+    kind: u32,
+    keys: *const Iovec,
+    n: usize,
+    seeds: *const u64,
+    out: *mut u64,
+) -> usize {
+    let scalar: fn(&[u8], u64) -> u64 = match kind {
+        HASH_BATCH_XXH64 => |k, seed| {
+            let mut h = Xxh64::new(seed);
+            h.update(k);
+            h.digest()
+        },
+        HASH_BATCH_XXH3_64 => xxh3_64_impl,
+        HASH_BATCH_WYHASH => wyhash_impl,
+        HASH_BATCH_FNV1A_64 => {
+            |k, seed| unsafe { fnv1a_64(k.as_ptr(), k.len(), FNV64_OFFSET ^ seed) }
+        }
+        _ => return 0,
+    };
+    if keys.is_null() || out.is_null() || n == 0 {
+        return 0;
+    }
+    let keys = core::slice::from_raw_parts(keys, n);
+    let out = core::slice::from_raw_parts_mut(out, n);
+    let seed = |i: usize| if seeds.is_null() { 0 } else { *seeds.add(i) };
+    let key = |v: &Iovec| -> &[u8] {
+        if v.ptr.is_null() {
+            &[]
+        } else {
+            core::slice::from_raw_parts(v.ptr, v.len)
+        }
+    };
+    let mut i = 0;
+    if kind == HASH_BATCH_FNV1A_64 {
+        while i + BATCH_LANES <= n {
+            let lanes: [&[u8]; BATCH_LANES] = core::array::from_fn(|l| key(&keys[i + l]));
+            let h = Simd::from_array(core::array::from_fn(|l| FNV64_OFFSET ^ seed(i + l)));
+            out[i..i + BATCH_LANES].copy_from_slice(fnv1a_64_lanes(&lanes, h).as_array());
+            i += BATCH_LANES;
+        }
+    }
+    for j in i..n {
+        out[j] = scalar(key(&keys[j]), seed(j));
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (len, xxh64, xxh3_64, xxh3_128 low, xxh3_128 high) with seed 7 over
+    /// `data()`, from the reference implementation.
+    const VECTORS: [(usize, u64, u64, u64, u64); 6] = [
+        (
+            3,
+            0x31FA_8994_0479_BEB0,
+            0xAEB0_A523_002C_B5C7,
+            0xAEB0_A523_002C_B5C7,
+            0xDA98_7E9F_43FD_241C,
+        ),
+        (
+            8,
+            0x9D32_448D_CABC_EA9A,
+            0xD1AA_2282_E43E_1C7F,
+            0x1C27_5953_A3EF_7CFE,
+            0x7F49_5B17_668A_3F2F,
+        ),
+        (
+            16,
+            0xF2E4_B182_1372_9118,
+            0x2BA8_FBC9_3F6C_B095,
+            0xDED6_8403_7E1D_D42E,
+            0x6D15_A8DF_7479_2B7F,
+        ),
+        (
+            100,
+            0x4BC6_5215_3761_CD7C,
+            0xDB28_E71A_2724_07EA,
+            0xA432_4F46_AD10_E4F1,
+            0xBBAF_5E52_7639_8101,
+        ),
+        (
+            200,
+            0xA688_A82A_E3FA_4549,
+            0x91CD_D7AF_C713_37C6,
+            0x8196_9553_FF8B_2D69,
+            0x0E74_AE42_9A9F_0E80,
+        ),
+        (
+            1000,
+            0x2DFE_C5A4_D16C_5217,
+            0x20B3_FD7D_423D_70B8,
+            0x20B3_FD7D_423D_70B8,
+            0xBAEF_F6CF_E0A2_DC7B,
+        ),
+    ];
+
+    fn data() -> Vec<u8> {
+        (0..1000u32).map(|i| (i * 31 + i / 7) as u8).collect()
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let mut out = [0u64; 2];
+        unsafe {
+            assert_eq!(xxh64(core::ptr::null(), 0, 0), 0xEF46_DB37_51D8_E999);
+            assert_eq!(xxh3_64(core::ptr::null(), 0, 0), 0x2D06_8005_38D3_94C2);
+            xxh3_128(core::ptr::null(), 0, 0, out.as_mut_ptr());
+        }
+        assert_eq!(out, [0x6001_C324_468D_497F, 0x99AA_06D3_0147_98D8]);
+    }
+
+    #[test]
+    fn test_known_vectors() {
+        let data = data();
+        for (n, h64, h3, lo, hi) in VECTORS {
+            let mut out = [0u64; 2];
+            unsafe {
+                assert_eq!(xxh64(data.as_ptr(), n, 7), h64, "xxh64 len {n}");
+                assert_eq!(xxh3_64(data.as_ptr(), n, 7), h3, "xxh3_64 len {n}");
+                xxh3_128(data.as_ptr(), n, 7, out.as_mut_ptr());
+            }
+            assert_eq!(out, [lo, hi], "xxh3_128 len {n}");
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let data: Vec<u8> = data().repeat(3);
+        for n in [
+            0usize, 1, 17, 129, 240, 241, 255, 256, 257, 320, 1024, 1025, 3000,
+        ] {
+            let d = &data[..n];
+            for step in [1usize, 13, 64, 256, 1000] {
+                unsafe {
+                    let s3 = xxh3_new(9);
+                    let s64 = xxh64_new(9);
+                    for c in d.chunks(step) {
+                        xxh3_update(s3, c.as_ptr(), c.len());
+                        xxh64_update(s64, c.as_ptr(), c.len());
+                    }
+                    let mut got = [0u64; 2];
+                    let mut want = [0u64; 2];
+                    xxh3_digest128(s3, got.as_mut_ptr());
+                    xxh3_128(d.as_ptr(), n, 9, want.as_mut_ptr());
+                    assert_eq!(got, want, "xxh3_128 len {n} step {step}");
+                    assert_eq!(
+                        xxh3_digest64(s3),
+                        xxh3_64(d.as_ptr(), n, 9),
+                        "len {n} step {step}"
+                    );
+                    assert_eq!(
+                        xxh64_digest(s64),
+                        xxh64(d.as_ptr(), n, 9),
+                        "len {n} step {step}"
+                    );
+                    xxh3_free(s3);
+                    xxh64_free(s64);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        const FNV32_OFFSET: u32 = 0x811C_9DC5;
+        unsafe {
+            assert_eq!(fnv1a_32(core::ptr::null(), 0, FNV32_OFFSET), FNV32_OFFSET);
+            assert_eq!(fnv1a_32(b"a".as_ptr(), 1, FNV32_OFFSET), 0xE40C_292C);
+            assert_eq!(
+                fnv1a_64(b"a".as_ptr(), 1, FNV64_OFFSET),
+                0xAF63_DC4C_8601_EC8C
+            );
+            assert_eq!(fnv1a_32(b"foobar".as_ptr(), 6, FNV32_OFFSET), 0xBF9C_F968);
+            assert_eq!(
+                fnv1a_64(b"foobar".as_ptr(), 6, FNV64_OFFSET),
+                0x8594_4171_F739_67E8
+            );
+            // Chained updates continue the same hash.
+            let head = fnv1a_64(b"foo".as_ptr(), 3, FNV64_OFFSET);
+            assert_eq!(fnv1a_64(b"bar".as_ptr(), 3, head), 0x8594_4171_F739_67E8);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_siphash24() {
+        use core::hash::{Hasher, SipHasher};
+        // Reference vectors: key 00..0f, message 00..(n-1).
+        let key: Vec<u8> = (0..16).collect();
+        let (k0, k1) = (read64(&key, 0), read64(&key, 8));
+        let msg: Vec<u8> = (0..64).collect();
+        unsafe {
+            assert_eq!(siphash24(k0, k1, msg.as_ptr(), 0), 0x726F_DB47_DD0E_0E31);
+            assert_eq!(siphash24(k0, k1, msg.as_ptr(), 15), 0xA129_CA61_49BE_45E5);
+        }
+        for n in 0..64 {
+            let mut h = SipHasher::new_with_keys(k0, k1);
+            h.write(&msg[..n]);
+            assert_eq!(
+                unsafe { siphash24(k0, k1, msg.as_ptr(), n) },
+                h.finish(),
+                "len {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wyhash() {
+        // Test vectors from the reference wyhash.h (final version 4).
+        let cases: [(&[u8], u64); 7] = [
+            (b"", 0x9322_8A4D_E0EE_C5A2),
+            (b"a", 0xC5BA_C3DB_1787_13C4),
+            (b"abc", 0xA97F_2F7B_1D9B_3314),
+            (b"message digest", 0x786D_1F1D_F380_1DF4),
+            (b"abcdefghijklmnopqrstuvwxyz", 0xDCA5_A813_8AD3_7C87),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                0xB9E7_34F1_17CF_AF70,
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                0x6CC5_EAB4_9A92_D617,
+            ),
+        ];
+        for (seed, (msg, want)) in cases.iter().enumerate() {
+            assert_eq!(
+                unsafe { wyhash(msg.as_ptr(), msg.len(), seed as u64) },
+                *want,
+                "{seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_batch() {
+        let data = data();
+        let keys: Vec<&[u8]> = (0..21).map(|i| &data[i * 3..i * 3 + i % 13]).collect();
+        let mut iov: Vec<Iovec> = keys
+            .iter()
+            .map(|k| Iovec {
+                ptr: k.as_ptr(),
+                len: k.len(),
+            })
+            .collect();
+        iov[4].ptr = core::ptr::null();
+        iov[4].len = 0;
+        let seeds: Vec<u64> = (0..21).map(|i| i * 0x9E37).collect();
+        for kind in 0..4 {
+            let mut out = vec![0u64; keys.len()];
+            let n = unsafe {
+                hash_batch(
+                    kind,
+                    iov.as_ptr(),
+                    iov.len(),
+                    seeds.as_ptr(),
+                    out.as_mut_ptr(),
+                )
+            };
+            assert_eq!(n, keys.len());
+            for (i, k) in keys.iter().enumerate() {
+                let k: &[u8] = if i == 4 { &[] } else { k };
+                let (p, l, s) = (k.as_ptr(), k.len(), seeds[i]);
+                let want = unsafe {
+                    match kind {
+                        0 => xxh64(p, l, s),
+                        1 => xxh3_64(p, l, s),
+                        2 => wyhash(p, l, s),
+                        _ => fnv1a_64(p, l, FNV64_OFFSET ^ s),
+                    }
+                };
+                assert_eq!(out[i], want, "kind {kind} key {i}");
+            }
+        }
+        let mut out = [0u64; 1];
+        unsafe {
+            assert_eq!(
+                hash_batch(9, iov.as_ptr(), 1, core::ptr::null(), out.as_mut_ptr()),
+                0
+            );
+            assert_eq!(
+                hash_batch(2, iov.as_ptr(), 1, core::ptr::null(), out.as_mut_ptr()),
+                1
+            );
+            assert_eq!(out[0], wyhash(keys[0].as_ptr(), keys[0].len(), 0));
+        }
+    }
+}
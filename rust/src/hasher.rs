@@ -0,0 +1,293 @@
+//! Opaque streaming hasher handles shaped like Go's `hash.Hash`: create a
+//! handle for one algorithm, `Write` into it as data arrives, take `Sum` at
+//! any point and `Reset` to start over.  Every checksum and hash kernel with
+//! an incremental form plugs in here behind a single set of entry points.
+//!
+//! Digests are written in the byte order Go's `Sum` appends them: integer
+//! checksums (CRCs, Adler-32, FNV, xxHash) big-endian, SHA-256 and BLAKE3 as
+//! their natural byte strings.
+use crate::blake3::Blake3;
+use crate::checksum::adler32_update;
+use crate::crc::{crc32_ieee_update, crc32c_update, crc64_nvme_update, crc64_xz_update};
+use crate::hash::{Xxh3, Xxh64, fnv1a_32, fnv1a_64};
+use crate::sha256::Sha256;
+use alloc::boxed::Box;
+
+/// Algorithm identifiers accepted by [`simba_hasher_new`].
+const HASHER_CRC32C: u32 = 0;
+const HASHER_CRC32_IEEE: u32 = 1;
+const HASHER_CRC64_XZ: u32 = 2;
+const HASHER_CRC64_NVME: u32 = 3;
+const HASHER_ADLER32: u32 = 4;
+const HASHER_FNV1A_32: u32 = 5;
+const HASHER_FNV1A_64: u32 = 6;
+const HASHER_XXH64: u32 = 7;
+const HASHER_XXH3_64: u32 = 8;
+const HASHER_XXH3_128: u32 = 9;
+const HASHER_SHA256: u32 = 10;
+const HASHER_BLAKE3: u32 = 11;
+
+// Handles are boxed, so the spread between variant sizes costs nothing.
+#[allow(clippy::large_enum_variant)]
+enum State {
+    Crc32c(u32),
+    Crc32Ieee(u32),
+    Crc64Xz(u64),
+    Crc64Nvme(u64),
+    Adler32(u32),
+    Fnv1a32(u32),
+    Fnv1a64(u64),
+    Xxh64(Xxh64),
+    Xxh3_64(Xxh3),
+    Xxh3_128(Xxh3),
+    Sha256(Sha256),
+    Blake3(Blake3),
+}
+
+/// Streaming hasher for one algorithm.  Opaque to callers.
+pub struct Hasher {
+    kind: u32,
+    state: State,
+}
+
+impl State {
+    fn new(kind: u32) -> Option<Self> {
+        Some(match kind {
+            HASHER_CRC32C => State::Crc32c(0),
+            HASHER_CRC32_IEEE => State::Crc32Ieee(0),
+            HASHER_CRC64_XZ => State::Crc64Xz(0),
+            HASHER_CRC64_NVME => State::Crc64Nvme(0),
+            HASHER_ADLER32 => State::Adler32(1),
+            HASHER_FNV1A_32 => State::Fnv1a32(0x811C_9DC5),
+            HASHER_FNV1A_64 => State::Fnv1a64(0xCBF2_9CE4_8422_2325),
+            HASHER_XXH64 => State::Xxh64(Xxh64::new(0)),
+            HASHER_XXH3_64 => State::Xxh3_64(Xxh3::new(0)),
+            HASHER_XXH3_128 => State::Xxh3_128(Xxh3::new(0)),
+            HASHER_SHA256 => State::Sha256(Sha256::new()),
+            HASHER_BLAKE3 => State::Blake3(Blake3::new()),
+            _ => return None,
+        })
+    }
+
+    /// Digest length in bytes.
+    fn size(&self) -> usize {
+        match self {
+            State::Crc32c(_) | State::Crc32Ieee(_) | State::Adler32(_) | State::Fnv1a32(_) => 4,
+            State::Crc64Xz(_) | State::Crc64Nvme(_) | State::Fnv1a64(_) => 8,
+            State::Xxh64(_) | State::Xxh3_64(_) => 8,
+            State::Xxh3_128(_) => 16,
+            State::Sha256(_) | State::Blake3(_) => 32,
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        let (p, n) = (data.as_ptr(), data.len());
+        unsafe {
+            match self {
+                State::Crc32c(c) => *c = crc32c_update(*c, data),
+                State::Crc32Ieee(c) => *c = crc32_ieee_update(*c, data),
+                State::Crc64Xz(c) => *c = crc64_xz_update(p, n, *c),
+                State::Crc64Nvme(c) => *c = crc64_nvme_update(p, n, *c),
+                State::Adler32(c) => *c = adler32_update(*c, p, n),
+                State::Fnv1a32(h) => *h = fnv1a_32(p, n, *h),
+                State::Fnv1a64(h) => *h = fnv1a_64(p, n, *h),
+                State::Xxh64(h) => h.update(data),
+                State::Xxh3_64(h) | State::Xxh3_128(h) => h.update(data),
+                State::Sha256(h) => h.update(data),
+                State::Blake3(h) => h.update(data),
+            }
+        }
+    }
+
+    /// Write the digest to `out`, which is exactly [`State::size`] bytes.
+    fn sum(&self, out: &mut [u8]) {
+        match self {
+            State::Crc32c(c) | State::Crc32Ieee(c) | State::Adler32(c) | State::Fnv1a32(c) => {
+                out.copy_from_slice(&c.to_be_bytes())
+            }
+            State::Crc64Xz(c) | State::Crc64Nvme(c) | State::Fnv1a64(c) => {
+                out.copy_from_slice(&c.to_be_bytes())
+            }
+            State::Xxh64(h) => out.copy_from_slice(&h.digest().to_be_bytes()),
+            State::Xxh3_64(h) => out.copy_from_slice(&h.digest64().to_be_bytes()),
+            State::Xxh3_128(h) => {
+                let (lo, hi) = h.digest128();
+                out[..8].copy_from_slice(&hi.to_be_bytes());
+                out[8..].copy_from_slice(&lo.to_be_bytes());
+            }
+            State::Sha256(h) => out.copy_from_slice(&h.digest()),
+            State::Blake3(h) => h.finalize(out),
+        }
+    }
+}
+
+/// Start a streaming hasher for algorithm `kind`: 0 CRC32C, 1 CRC32 (IEEE),
+/// 2 CRC64 (XZ), 3 CRC64 (NVMe), 4 Adler-32, 5 FNV-1a 32, 6 FNV-1a 64,
+/// 7 xxHash64, 8 XXH3-64, 9 XXH3-128, 10 SHA-256, 11 BLAKE3.  Seeded and
+/// keyed variants start from their defaults (seed 0, unkeyed).  Returns null
+/// for an unknown `kind`.  Release with [`hasher_free`].
+#[unsafe(export_name = c_symbol!("simba_hasher_new"))]
+pub extern "C" fn simba_hasher_new(kind: u32) -> *mut Hasher {
+    match State::new(kind) {
+        Some(state) => Box::into_raw(Box::new(Hasher { kind, state })),
+        None => core::ptr::null_mut(),
+    }
+}
+
+/// Digest length in bytes of a hasher (Go's `Size`); 0 for null.
+///
+/// # Safety
+/// `h` must be null or a live handle from [`simba_hasher_new`].
+#[unsafe(export_name = c_symbol!("hasher_size"))]
+pub unsafe extern "C" fn hasher_size(h: *const Hasher) -> usize {
+    h.as_ref().map_or(0, |h| h.state.size())
+}
+
+/// Feed `len` more bytes to a hasher.
+///
+/// # Safety
+/// `h` must be null or a live handle from [`simba_hasher_new`]; `ptr` must be
+/// null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("hasher_write"))]
+pub unsafe extern "C" fn hasher_write(h: *mut Hasher, ptr: *const u8, len: usize) {
+    if let Some(h) = h.as_mut().filter(|_| !ptr.is_null() && len > 0) {
+        h.state.write(core::slice::from_raw_parts(ptr, len));
+    }
+}
+
+/// Write the digest of everything fed so far to `out` and return its length
+/// ([`hasher_size`] bytes; 0 if `h` or `out` is null).  The state is left
+/// intact, so more input may follow.
+///
+/// # Safety
+/// `h` must be null or a live handle from [`simba_hasher_new`]; `out` must be
+/// null or valid for [`hasher_size`] bytes.
+#[unsafe(export_name = c_symbol!("hasher_sum"))]
+pub unsafe extern "C" fn hasher_sum(h: *const Hasher, out: *mut u8) -> usize {
+    match h.as_ref().filter(|_| !out.is_null()) {
+        Some(h) => {
+            let n = h.state.size();
+            h.state.sum(core::slice::from_raw_parts_mut(out, n));
+            n
+        }
+        None => 0,
+    }
+}
+
+/// Discard everything fed so far, as if freshly created.
+///
+/// # Safety
+/// `h` must be null or a live handle from [`simba_hasher_new`].
+#[unsafe(export_name = c_symbol!("hasher_reset"))]
+pub unsafe extern "C" fn hasher_reset(h: *mut Hasher) {
+    if let Some(h) = h.as_mut() {
+        h.state = State::new(h.kind).unwrap();
+    }
+}
+
+/// Release a handle from [`simba_hasher_new`].  Null is ignored.
+///
+/// # Safety
+/// `h` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("hasher_free"))]
+pub unsafe extern "C" fn hasher_free(h: *mut Hasher) {
+    if !h.is_null() {
+        drop(Box::from_raw(h));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(h: *const Hasher) -> Vec<u8> {
+        let mut out = vec![0u8; unsafe { hasher_size(h) }];
+        assert_eq!(unsafe { hasher_sum(h, out.as_mut_ptr()) }, out.len());
+        out
+    }
+
+    #[test]
+    fn test_hasher_known_digests() {
+        let check = b"123456789";
+        let cases: [(u32, &[u8]); 7] = [
+            (HASHER_CRC32C, &0xE306_9283u32.to_be_bytes()),
+            (HASHER_CRC32_IEEE, &0xCBF4_3926u32.to_be_bytes()),
+            (HASHER_CRC64_XZ, &0x995D_C9BB_DF19_39FAu64.to_be_bytes()),
+            (HASHER_CRC64_NVME, &0xAE8B_1486_0A79_9888u64.to_be_bytes()),
+            (HASHER_ADLER32, &0x091E_01DEu32.to_be_bytes()),
+            (HASHER_FNV1A_32, &0xBB86_B11Cu32.to_be_bytes()),
+            (HASHER_FNV1A_64, &0x06D5_5739_23C6_CDFCu64.to_be_bytes()),
+        ];
+        for (kind, want) in cases {
+            let h = simba_hasher_new(kind);
+            unsafe {
+                hasher_write(h, check.as_ptr(), 4);
+                hasher_write(h, check[4..].as_ptr(), 5);
+                assert_eq!(sum(h), want, "kind {kind}");
+                hasher_reset(h);
+                hasher_write(h, check.as_ptr(), check.len());
+                assert_eq!(sum(h), want, "kind {kind} after reset");
+                hasher_free(h);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hasher_matches_one_shot() {
+        let data: Vec<u8> = (0..5000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let one_shot = |kind: u32| -> Vec<u8> {
+            unsafe {
+                match kind {
+                    HASHER_XXH64 => crate::hash::xxh64(data.as_ptr(), data.len(), 0)
+                        .to_be_bytes()
+                        .to_vec(),
+                    HASHER_XXH3_64 => crate::hash::xxh3_64(data.as_ptr(), data.len(), 0)
+                        .to_be_bytes()
+                        .to_vec(),
+                    HASHER_XXH3_128 => {
+                        let mut w = [0u64; 2];
+                        crate::hash::xxh3_128(data.as_ptr(), data.len(), 0, w.as_mut_ptr());
+                        [w[1].to_be_bytes(), w[0].to_be_bytes()].concat()
+                    }
+                    HASHER_SHA256 => {
+                        let mut d = vec![0u8; 32];
+                        crate::sha256::sha256(data.as_ptr(), data.len(), d.as_mut_ptr());
+                        d
+                    }
+                    _ => {
+                        let mut d = vec![0u8; 32];
+                        crate::blake3::blake3(data.as_ptr(), data.len(), d.as_mut_ptr(), 32);
+                        d
+                    }
+                }
+            }
+        };
+        for kind in [
+            HASHER_XXH64,
+            HASHER_XXH3_64,
+            HASHER_XXH3_128,
+            HASHER_SHA256,
+            HASHER_BLAKE3,
+        ] {
+            let h = simba_hasher_new(kind);
+            for chunk in data.chunks(333) {
+                unsafe { hasher_write(h, chunk.as_ptr(), chunk.len()) };
+            }
+            assert_eq!(sum(h), one_shot(kind), "kind {kind}");
+            unsafe { hasher_free(h) };
+        }
+    }
+
+    #[test]
+    fn test_hasher_invalid() {
+        assert!(simba_hasher_new(99).is_null());
+        let mut out = [0u8; 8];
+        unsafe {
+            hasher_write(core::ptr::null_mut(), out.as_ptr(), 8);
+            assert_eq!(hasher_size(core::ptr::null()), 0);
+            assert_eq!(hasher_sum(core::ptr::null(), out.as_mut_ptr()), 0);
+            hasher_reset(core::ptr::null_mut());
+            hasher_free(core::ptr::null_mut());
+        }
+    }
+}
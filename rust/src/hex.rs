@@ -0,0 +1,424 @@
+//! Hex encode/decode kernels (lowercase output, case-insensitive input, as
+//! Go's `encoding/hex`), and the UUID text form built on them.
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use core::simd::prelude::{Mask, SimdPartialEq, SimdPartialOrd};
+use core::simd::{LaneCount, Simd, SupportedLaneCount, simd_swizzle};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+// === Encoder =================================================================
+
+#[inline(always)]
+unsafe fn hex_encode_impl<const L: usize>(src: &[u8], dst: &mut [u8])
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let lut = Simd::<u8, L>::from_array(core::array::from_fn(|i| HEX_DIGITS[i % 16]));
+    let nib = Simd::<u8, L>::splat(0x0F);
+    let mut chunks = src.chunks_exact(L);
+    for (chunk, out) in (&mut chunks).zip(dst.chunks_exact_mut(2 * L)) {
+        let v = Simd::<u8, L>::from_slice(chunk);
+        let hi = lut.swizzle_dyn(v >> 4);
+        let lo = lut.swizzle_dyn(v & nib);
+        let (first, second) = hi.interleave(lo);
+        first.copy_to_slice(&mut out[..L]);
+        second.copy_to_slice(&mut out[L..]);
+    }
+    let done = src.len() - chunks.remainder().len();
+    for (&b, out) in chunks
+        .remainder()
+        .iter()
+        .zip(dst[2 * done..].chunks_exact_mut(2))
+    {
+        out[0] = HEX_DIGITS[(b >> 4) as usize];
+        out[1] = HEX_DIGITS[(b & 15) as usize];
+    }
+}
+
+macro_rules! export_hex_encode {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Hex-encode `len` bytes as `2 * len` lowercase digits using a ", stringify!($lanes), "-lane SIMD kernel (nibble shuffle).\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `2 * len` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, 2 * len);
+            hex_encode_impl::<$lanes>(src, dst);
+        }
+    };
+}
+export_hex_encode!(hex_encode16, 16);
+export_hex_encode!(hex_encode32, 32);
+export_hex_encode!(hex_encode64, 64);
+
+/// Hex-encode `len` bytes; alias of the 32-lane kernel.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `2 * len` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("hex_encode"))]
+pub unsafe extern "C" fn hex_encode(src: *const u8, len: usize, dst: *mut u8) {
+    hex_encode32(src, len, dst)
+}
+
+// === Decoder =================================================================
+
+/// Map hex digits to nibble values; lanes that are not `[0-9a-fA-F]` are set
+/// in the returned mask.
+#[inline(always)]
+pub(crate) fn decode_nibbles<const L: usize>(c: Simd<u8, L>) -> (Simd<u8, L>, Mask<i8, L>)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let digit = c.simd_ge(Simd::splat(b'0')) & c.simd_le(Simd::splat(b'9'));
+    // Setting bit 5 folds 'A'..='F' onto 'a'..='f' without touching digits.
+    let lc = c | Simd::splat(0x20);
+    let alpha = lc.simd_ge(Simd::splat(b'a')) & lc.simd_le(Simd::splat(b'f'));
+    let v = digit.select(c - Simd::splat(b'0'), lc - Simd::splat(b'a' - 10));
+    (v, !(digit | alpha))
+}
+
+#[inline(always)]
+fn nibble_scalar(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decode pairs of hex digits from `src` into `dst` (`src.len() / 2` bytes).
+/// Returns bytes written, or the offset of the first invalid character; an
+/// odd trailing digit is reported at `src.len() - 1`.
+#[inline(always)]
+unsafe fn hex_decode_impl<const L: usize>(src: &[u8], dst: &mut [u8]) -> Result<usize, usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut pairs = src.chunks_exact(2 * L);
+    for (i, (chunk, out)) in (&mut pairs).zip(dst.chunks_exact_mut(L)).enumerate() {
+        let a = Simd::<u8, L>::from_slice(&chunk[..L]);
+        let b = Simd::<u8, L>::from_slice(&chunk[L..]);
+        let (hi, lo) = a.deinterleave(b);
+        let (hv, hbad) = decode_nibbles(hi);
+        let (lv, lbad) = decode_nibbles(lo);
+        if (hbad | lbad).any() {
+            let h = 2 * hbad.to_bitmask().trailing_zeros() as usize;
+            let l = 2 * lbad.to_bitmask().trailing_zeros() as usize + 1;
+            return Err(2 * L * i + h.min(l));
+        }
+        ((hv << 4) | lv).copy_to_slice(out);
+    }
+    let base = src.len() - pairs.remainder().len();
+    for (k, &c) in pairs.remainder().iter().enumerate() {
+        let Some(v) = nibble_scalar(c) else {
+            return Err(base + k);
+        };
+        let o = (base + k) / 2;
+        if k % 2 == 0 {
+            if base + k + 1 == src.len() {
+                return Err(base + k);
+            }
+            dst[o] = v << 4;
+        } else {
+            dst[o] |= v;
+        }
+    }
+    Ok(src.len() / 2)
+}
+
+macro_rules! export_hex_decode {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Decode `len` hex digits (either case) into `len / 2` bytes using a ", stringify!($lanes), "-lane SIMD kernel. Returns the number of bytes written, or `-(offset + 1)` where `offset` is the position of the first invalid digit; an odd-length input reports its last digit.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` bytes; `dst` must be valid for `len / 2` bytes and must not overlap `src`."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8) -> isize {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return 0;
+            }
+            let src = core::slice::from_raw_parts(src, len);
+            let dst = core::slice::from_raw_parts_mut(dst, len / 2);
+            match hex_decode_impl::<$lanes>(src, dst) {
+                Ok(n) => n as isize,
+                Err(at) => {
+                    fail(
+                        SIMBA_ERR_INVALID_INPUT,
+                        format_args!("invalid hex digit at offset {at}"),
+                    );
+                    -(at as isize) - 1
+                }
+            }
+        }
+    };
+}
+export_hex_decode!(hex_decode16, 16);
+export_hex_decode!(hex_decode32, 32);
+export_hex_decode!(hex_decode64, 64);
+
+/// Decode hex digits; alias of the 32-lane kernel.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `len / 2` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("hex_decode"))]
+pub unsafe extern "C" fn hex_decode(src: *const u8, len: usize, dst: *mut u8) -> isize {
+    hex_decode32(src, len, dst)
+}
+
+// === UUID ====================================================================
+
+/// Length of the canonical `8-4-4-4-12` form.
+const UUID_LEN: usize = 36;
+/// Hyphen positions of the canonical form.
+const UUID_HYPHENS: u64 = 1 << 8 | 1 << 13 | 1 << 18 | 1 << 23;
+
+/// Position of hex digit `i` (0..32) in the canonical form.
+const fn uuid_digit_pos(i: usize) -> usize {
+    i + (i >= 8) as usize + (i >= 12) as usize + (i >= 16) as usize + (i >= 20) as usize
+}
+
+/// Positions of the high (`K = 0`) or low (`K = 1`) nibble of each byte,
+/// with (`HYPHENATED`) or without the canonical hyphens.
+const fn uuid_nibble_pos<const K: usize, const HYPHENATED: bool>() -> [usize; 16] {
+    let mut t = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        t[i] = if HYPHENATED {
+            uuid_digit_pos(2 * i + K)
+        } else {
+            2 * i + K
+        };
+        i += 1;
+    }
+    t
+}
+
+/// Canonical form from the 32 hex digits; hyphen lanes are patched after.
+const UUID_FORMAT: [usize; 64] = {
+    let mut t = [0; 64];
+    let mut i = 0;
+    while i < 32 {
+        t[uuid_digit_pos(i)] = i;
+        i += 1;
+    }
+    t
+};
+
+/// Parse a UUID in canonical `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` form or
+/// as 32 bare hex digits (either case), or return the offset of the first
+/// bad character (`len` for a short input, 36 for a long one).
+fn uuid_parse_impl(s: &[u8]) -> Result<[u8; 16], usize> {
+    let plain = s.len() == 32;
+    let n = s.len().min(UUID_LEN);
+    let mut buf = [0u8; 64];
+    buf[..n].copy_from_slice(&s[..n]);
+    let v = Simd::<u8, 64>::from_array(buf);
+
+    let hyphens = if plain { 0 } else { UUID_HYPHENS };
+    let dashes = v.simd_eq(Simd::splat(b'-')).to_bitmask();
+    let (_, not_hex) = decode_nibbles(v);
+    let bad = ((not_hex.to_bitmask() & !hyphens) | (hyphens & !dashes)) & ((1 << n) - 1);
+    if bad != 0 {
+        return Err(bad.trailing_zeros() as usize);
+    }
+    if n != s.len() || (!plain && n < UUID_LEN) {
+        return Err(n);
+    }
+
+    let (hi, lo) = if plain {
+        const HI: [usize; 16] = uuid_nibble_pos::<0, false>();
+        const LO: [usize; 16] = uuid_nibble_pos::<1, false>();
+        (simd_swizzle!(v, HI), simd_swizzle!(v, LO))
+    } else {
+        const HI: [usize; 16] = uuid_nibble_pos::<0, true>();
+        const LO: [usize; 16] = uuid_nibble_pos::<1, true>();
+        (simd_swizzle!(v, HI), simd_swizzle!(v, LO))
+    };
+    let ((hv, _), (lv, _)) = (decode_nibbles(hi), decode_nibbles(lo));
+    Ok(((hv << 4) | lv).to_array())
+}
+
+/// Parse the UUID in the `len` bytes at `ptr` into the 16 bytes at `out`.
+/// Accepts the canonical 36-character form and 32 bare hex digits, in either
+/// case.
+///
+/// Returns 16, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+/// character stored as a native-endian u32 in the first four bytes of `out`
+/// (`len` when the input is short).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for 16
+/// bytes.
+#[unsafe(export_name = c_symbol!("uuid_parse"))]
+pub unsafe extern "C" fn uuid_parse(ptr: *const u8, len: usize, out: *mut u8) -> isize {
+    let s: &[u8] = if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    match uuid_parse_impl(s) {
+        Ok(bytes) => {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, 16);
+            16
+        }
+        Err(at) => {
+            core::ptr::copy_nonoverlapping((at as u32).to_ne_bytes().as_ptr(), out, 4);
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid UUID character at offset {at}"),
+            ) as isize
+        }
+    }
+}
+
+/// Write the 16 bytes at `src` as a lowercase canonical UUID
+/// (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, 36 bytes) to `dst`.
+///
+/// # Safety
+/// `src` must be valid for 16 bytes and `dst` for 36 bytes.
+#[unsafe(export_name = c_symbol!("uuid_format"))]
+pub unsafe extern "C" fn uuid_format(src: *const u8, dst: *mut u8) {
+    if src.is_null() || dst.is_null() {
+        return;
+    }
+    let lut = Simd::<u8, 16>::from_array(*HEX_DIGITS);
+    let v = Simd::<u8, 16>::from_slice(core::slice::from_raw_parts(src, 16));
+    let (first, second) = lut
+        .swizzle_dyn(v >> 4)
+        .interleave(lut.swizzle_dyn(v & Simd::splat(0x0F)));
+    let mut text = simd_swizzle!(first, second, UUID_FORMAT).to_array();
+    for at in [8, 13, 18, 23] {
+        text[at] = b'-';
+    }
+    core::ptr::copy_nonoverlapping(text.as_ptr(), dst, UUID_LEN);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Enc = unsafe extern "C" fn(*const u8, usize, *mut u8);
+    type Dec = unsafe extern "C" fn(*const u8, usize, *mut u8) -> isize;
+    const KERNELS: [(Enc, Dec); 3] = [
+        (hex_encode16, hex_decode16),
+        (hex_encode32, hex_decode32),
+        (hex_encode64, hex_decode64),
+    ];
+
+    #[test]
+    fn test_hex_known_vectors() {
+        // Vectors from Go's encoding/hex tests.
+        let cases: [(&[u8], &[u8]); 4] = [
+            (b"", b""),
+            (&[0, 1, 2, 3, 4, 5, 6, 7], b"0001020304050607"),
+            (&[0xde, 0xad, 0xbe, 0xef], b"deadbeef"),
+            (b"g\xe3\xa1\x8a", b"67e3a18a"),
+        ];
+        for (raw, enc) in cases {
+            let mut out = [0u8; 16];
+            unsafe {
+                hex_encode(raw.as_ptr(), raw.len(), out.as_mut_ptr());
+                assert_eq!(&out[..enc.len()], enc);
+                let n = hex_decode(enc.as_ptr(), enc.len(), out.as_mut_ptr());
+                assert_eq!(&out[..n as usize], raw);
+            }
+        }
+        let mut out = [0u8; 4];
+        unsafe {
+            assert_eq!(hex_decode(b"DEADBEEF".as_ptr(), 8, out.as_mut_ptr()), 4);
+            assert_eq!(out, [0xde, 0xad, 0xbe, 0xef]);
+            assert_eq!(hex_decode(b"0g".as_ptr(), 2, out.as_mut_ptr()), -2);
+            assert_eq!(hex_decode(b"0".as_ptr(), 1, out.as_mut_ptr()), -1);
+            assert_eq!(hex_decode(b"zd4".as_ptr(), 3, out.as_mut_ptr()), -1);
+        }
+    }
+
+    #[test]
+    fn test_hex_roundtrip_and_errors() {
+        let src: Vec<u8> = (0..300u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 11) as u8)
+            .collect();
+        for (enc, dec) in KERNELS {
+            for len in [1, 15, 16, 17, 32, 63, 64, 65, 130, 300] {
+                let mut hex = vec![0u8; 2 * len];
+                unsafe { enc(src.as_ptr(), len, hex.as_mut_ptr()) };
+                let want: Vec<u8> = src[..len]
+                    .iter()
+                    .flat_map(|b| format!("{:02x}", b).into_bytes())
+                    .collect();
+                assert_eq!(hex, want);
+
+                let mut back = vec![0u8; len];
+                let n = unsafe { dec(hex.as_ptr(), hex.len(), back.as_mut_ptr()) };
+                assert_eq!(n, len as isize);
+                assert_eq!(back, &src[..len]);
+
+                for at in [0, 1, hex.len() / 2, hex.len() - 1] {
+                    let mut bad = hex.clone();
+                    bad[at] = b'x';
+                    let n = unsafe { dec(bad.as_ptr(), bad.len(), back.as_mut_ptr()) };
+                    assert_eq!(n, -(at as isize) - 1, "len={} at={}", len, at);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_uuid() {
+        let text = b"123e4567-e89b-12d3-a456-426614174000";
+        let bytes = [
+            0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3, 0xa4, 0x56, 0x42, 0x66, 0x14, 0x17,
+            0x40, 0x00,
+        ];
+        assert_eq!(uuid_parse_impl(text), Ok(bytes));
+        assert_eq!(uuid_parse_impl(&text.to_ascii_uppercase()), Ok(bytes));
+        let plain: Vec<u8> = text.iter().copied().filter(|&c| c != b'-').collect();
+        assert_eq!(uuid_parse_impl(&plain), Ok(bytes));
+
+        let mut dst = [0u8; UUID_LEN];
+        unsafe { uuid_format(bytes.as_ptr(), dst.as_mut_ptr()) };
+        assert_eq!(&dst, text);
+        for seed in 0..64u8 {
+            let b: [u8; 16] = core::array::from_fn(|i| {
+                seed.wrapping_mul(37)
+                    .wrapping_add((i as u8).wrapping_mul(29))
+            });
+            unsafe { uuid_format(b.as_ptr(), dst.as_mut_ptr()) };
+            assert_eq!(uuid_parse_impl(&dst), Ok(b));
+        }
+
+        let bad: [(&[u8], usize); 7] = [
+            (b"", 0),
+            (b"123e4567-e89b-12d3-a456-42661417400", 35),
+            (b"123e4567-e89b-12d3-a456-4266141740000", 36),
+            (b"123e4567e-89b-12d3-a456-426614174000", 8),
+            (b"123e4567-e89b-12d3-a456-42661417400g", 35),
+            (b"{123e4567-e89b-12d3-a456-426614174000}", 0),
+            (b"123e4567-e89b-12d3-a456_426614174000", 23),
+        ];
+        for (s, at) in bad {
+            assert_eq!(
+                uuid_parse_impl(s),
+                Err(at),
+                "{}",
+                String::from_utf8_lossy(s)
+            );
+        }
+        let mut out = [0u8; 16];
+        assert_eq!(
+            unsafe { uuid_parse(text.as_ptr(), 36, out.as_mut_ptr()) },
+            16
+        );
+        assert_eq!(out, bytes);
+        assert_eq!(
+            unsafe { uuid_parse(text.as_ptr(), 20, out.as_mut_ptr()) },
+            SIMBA_ERR_INVALID_INPUT as isize
+        );
+        assert_eq!(u32::from_ne_bytes(out[..4].try_into().unwrap()), 20);
+    }
+}
@@ -0,0 +1,579 @@
+//! Packed 8-bit pixel kernels (RGBA swizzle, premultiply and blend, RGB →
+//! luma, PNG row defiltering).
+use core::simd::prelude::{Mask, SimdUint};
+use core::simd::{LaneCount, Simd, SupportedLaneCount, Swizzle};
+
+use crate::{load_simd, store_simd};
+
+/// Lane width used by the pixel kernels; must be a multiple of 4 so that a
+/// vector always holds whole pixels.
+const PIXEL_LANES: usize = 32;
+
+/// Compute `round(c * a / 255)` exactly for 8-bit inputs widened to u16.
+#[inline(always)]
+fn mul_div255<const L: usize>(c: Simd<u16, L>, a: Simd<u16, L>) -> Simd<u16, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let t = c * a + Simd::splat(128);
+    (t + (t >> 8)) >> 8
+}
+
+#[inline(always)]
+fn mul_div255_scalar(c: u8, a: u8) -> u8 {
+    let t = c as u16 * a as u16 + 128;
+    ((t + (t >> 8)) >> 8) as u8
+}
+
+// === Channel swizzle =========================================================
+
+/// Unpack a swizzle `order` word: byte `i` (little-endian) names the source
+/// channel that lands in output channel `i`.  Indices are taken modulo 4.
+#[inline(always)]
+fn unpack_order(order: u32) -> [usize; 4] {
+    core::array::from_fn(|i| ((order >> (8 * i)) & 3) as usize)
+}
+
+#[inline(always)]
+unsafe fn swizzle_rgba_impl<const L: usize>(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    order: [usize; 4],
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let idx = Simd::<u8, L>::from_array(core::array::from_fn(|i| ((i & !3) + order[i & 3]) as u8));
+
+    let mut i = 0;
+    while i + L <= len {
+        let v = load_simd::<u8, L>(src.add(i));
+        store_simd(dst.add(i), v.swizzle_dyn(idx));
+        i += L;
+    }
+    while i < len {
+        let p = core::ptr::read_unaligned(src.add(i) as *const [u8; 4]);
+        let out = [p[order[0]], p[order[1]], p[order[2]], p[order[3]]];
+        core::ptr::write_unaligned(dst.add(i) as *mut [u8; 4], out);
+        i += 4;
+    }
+}
+
+/// Reorder the channels of `n_pixels` packed 4-byte pixels.
+///
+/// `order` packs four source-channel indices, one per byte, little-endian:
+/// output channel `i` receives source channel `(order >> 8*i) & 3`.  For
+/// example RGBA→BGRA is `0x0300_0102`, identity is `0x0302_0100`.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `4 * n_pixels` bytes.  `dst` may alias
+/// `src` exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("swizzle_rgba"))]
+pub unsafe extern "C" fn swizzle_rgba(src: *const u8, n_pixels: usize, order: u32, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || n_pixels == 0 {
+        return;
+    }
+    let Some(len) = n_pixels.checked_mul(4) else {
+        return;
+    };
+    swizzle_rgba_impl::<PIXEL_LANES>(src, len, dst, unpack_order(order));
+}
+
+// === Alpha premultiply =======================================================
+
+#[inline(always)]
+unsafe fn premultiply_alpha_impl<const L: usize>(src: *const u8, len: usize, dst: *mut u8)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    // Broadcast each pixel's alpha to all four of its lanes.
+    let alpha_idx = Simd::<u8, L>::from_array(core::array::from_fn(|i| (i | 3) as u8));
+    let alpha_lane = Mask::<i8, L>::from_array(core::array::from_fn(|i| i & 3 == 3));
+
+    let mut i = 0;
+    while i + L <= len {
+        let v = load_simd::<u8, L>(src.add(i));
+        let a = v.swizzle_dyn(alpha_idx);
+        let pm: Simd<u8, L> = mul_div255(v.cast::<u16>(), a.cast::<u16>()).cast();
+        store_simd(dst.add(i), alpha_lane.select(v, pm));
+        i += L;
+    }
+    while i < len {
+        let [r, g, b, a] = core::ptr::read_unaligned(src.add(i) as *const [u8; 4]);
+        let out = [
+            mul_div255_scalar(r, a),
+            mul_div255_scalar(g, a),
+            mul_div255_scalar(b, a),
+            a,
+        ];
+        core::ptr::write_unaligned(dst.add(i) as *mut [u8; 4], out);
+        i += 4;
+    }
+}
+
+/// Convert `n_pixels` straight-alpha RGBA8 pixels to premultiplied alpha:
+/// each colour channel becomes `round(c * a / 255)`, alpha is copied as is.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `4 * n_pixels` bytes.  `dst` may alias
+/// `src` exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("premultiply_alpha"))]
+pub unsafe extern "C" fn premultiply_alpha(src: *const u8, n_pixels: usize, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || n_pixels == 0 {
+        return;
+    }
+    let Some(len) = n_pixels.checked_mul(4) else {
+        return;
+    };
+    premultiply_alpha_impl::<PIXEL_LANES>(src, len, dst);
+}
+
+/// `rgba_premultiply` spelling of [`premultiply_alpha`].
+///
+/// # Safety
+/// As for [`premultiply_alpha`].
+#[unsafe(export_name = c_symbol!("rgba_premultiply"))]
+pub unsafe extern "C" fn rgba_premultiply(src: *const u8, n_pixels: usize, dst: *mut u8) {
+    premultiply_alpha(src, n_pixels, dst)
+}
+
+// === Alpha blend =============================================================
+
+#[inline(always)]
+unsafe fn alpha_blend_impl<const L: usize>(dst: *mut u8, src: *const u8, len: usize)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let alpha_idx = Simd::<u8, L>::from_array(core::array::from_fn(|i| (i | 3) as u8));
+
+    let mut i = 0;
+    while i + L <= len {
+        let s = load_simd::<u8, L>(src.add(i));
+        let d = load_simd::<u8, L>(dst.add(i));
+        let inv = Simd::splat(255) - s.swizzle_dyn(alpha_idx);
+        let kept: Simd<u8, L> = mul_div255(d.cast::<u16>(), inv.cast::<u16>()).cast();
+        store_simd(dst.add(i), s.saturating_add(kept));
+        i += L;
+    }
+    while i < len {
+        let s = core::ptr::read_unaligned(src.add(i) as *const [u8; 4]);
+        let d = core::ptr::read_unaligned(dst.add(i) as *const [u8; 4]);
+        let out: [u8; 4] =
+            core::array::from_fn(|c| s[c].saturating_add(mul_div255_scalar(d[c], 255 - s[3])));
+        core::ptr::write_unaligned(dst.add(i) as *mut [u8; 4], out);
+        i += 4;
+    }
+}
+
+/// Composite `n_pixels` premultiplied RGBA8 pixels from `src` over `dst`
+/// (Porter-Duff source-over), in place: every channel, alpha included,
+/// becomes `s + round(d * (255 - s_alpha) / 255)`, saturating at 255 for
+/// inputs that are not validly premultiplied.  Convert straight-alpha
+/// pixels with [`premultiply_alpha`] first.
+///
+/// # Safety
+/// `dst` and `src` must be valid for `4 * n_pixels` bytes and must not
+/// partially overlap.
+#[unsafe(export_name = c_symbol!("alpha_blend"))]
+pub unsafe extern "C" fn alpha_blend(dst: *mut u8, src: *const u8, n_pixels: usize) {
+    if src.is_null() || dst.is_null() || n_pixels == 0 {
+        return;
+    }
+    let Some(len) = n_pixels.checked_mul(4) else {
+        return;
+    };
+    alpha_blend_impl::<PIXEL_LANES>(dst, src, len);
+}
+
+// === RGB → luma ==============================================================
+
+/// Fixed-point (Q8) luma weights `[r, g, b]`; each set sums to 256 so white
+/// maps to 255 exactly.
+const BT601_Q8: [u16; 3] = [77, 150, 29];
+const BT709_Q8: [u16; 3] = [54, 183, 19];
+
+/// Pixels converted per vector iteration.  Each step reads 64 bytes but
+/// consumes only `3 * GRAY_PIXELS = 48`.
+const GRAY_PIXELS: usize = 16;
+
+/// Extracts channel `C` of 16 packed RGB pixels from a 64-byte window.
+struct RgbChannel<const C: usize>;
+
+impl<const C: usize> Swizzle<GRAY_PIXELS> for RgbChannel<C> {
+    const INDEX: [usize; GRAY_PIXELS] = {
+        let mut idx = [0; GRAY_PIXELS];
+        let mut i = 0;
+        while i < GRAY_PIXELS {
+            idx[i] = 3 * i + C;
+            i += 1;
+        }
+        idx
+    };
+}
+
+#[inline(always)]
+unsafe fn rgb_to_gray_impl(src: &[u8], dst: &mut [u8], w: [u16; 3]) {
+    let (wr, wg, wb) = (Simd::splat(w[0]), Simd::splat(w[1]), Simd::splat(w[2]));
+    let n = dst.len();
+    let mut px = 0;
+    // 255 * 256 + 128 still fits in u16, so the whole dot product stays in
+    // 16-bit lanes.
+    while 3 * px + 64 <= src.len() {
+        let v = Simd::<u8, 64>::from_slice(&src[3 * px..3 * px + 64]);
+        let r = RgbChannel::<0>::swizzle(v).cast::<u16>();
+        let g = RgbChannel::<1>::swizzle(v).cast::<u16>();
+        let b = RgbChannel::<2>::swizzle(v).cast::<u16>();
+        let y = (r * wr + g * wg + b * wb + Simd::splat(128)) >> 8;
+        y.cast::<u8>().copy_to_slice(&mut dst[px..px + GRAY_PIXELS]);
+        px += GRAY_PIXELS;
+    }
+    for (p, out) in src[3 * px..].chunks_exact(3).zip(&mut dst[px..n]) {
+        let y = p[0] as u16 * w[0] + p[1] as u16 * w[1] + p[2] as u16 * w[2] + 128;
+        *out = (y >> 8) as u8;
+    }
+}
+
+macro_rules! export_rgb_to_gray {
+    ($name:ident, $weights:expr, $std:literal) => {
+        #[doc = concat!(
+            "Convert `n_pixels` packed RGB8 pixels to 8-bit luma using ", $std, " weights in Q8 fixed point: `y = (wr*r + wg*g + wb*b + 128) >> 8`.\n\n",
+            "# Safety\n",
+            "`src` must be valid for `3 * n_pixels` bytes and `dst` for `n_pixels` bytes; the buffers must not overlap."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, n_pixels: usize, dst: *mut u8) {
+            if src.is_null() || dst.is_null() || n_pixels == 0 {
+                return;
+            }
+            let Some(len) = n_pixels.checked_mul(3) else {
+                return;
+            };
+            let src_slice = core::slice::from_raw_parts(src, len);
+            let dst_slice = core::slice::from_raw_parts_mut(dst, n_pixels);
+            rgb_to_gray_impl(src_slice, dst_slice, $weights);
+        }
+    };
+}
+export_rgb_to_gray!(rgb_to_gray, BT601_Q8, "BT.601");
+export_rgb_to_gray!(rgb_to_gray_bt709, BT709_Q8, "BT.709");
+
+// === PNG row defiltering =====================================================
+
+const PNG_LANES: usize = 16;
+
+/// Up: `cur[i] += prev[i]`; no horizontal dependency, fully vectorised.
+#[inline(always)]
+unsafe fn png_unfilter_up(cur: &mut [u8], prev: &[u8]) {
+    let mut chunks = cur.chunks_exact_mut(PNG_LANES);
+    let mut prev_chunks = prev.chunks_exact(PNG_LANES);
+    for (c, p) in (&mut chunks).zip(&mut prev_chunks) {
+        let v = Simd::<u8, PNG_LANES>::from_slice(c) + Simd::from_slice(p);
+        v.copy_to_slice(c);
+    }
+    for (c, &p) in chunks
+        .into_remainder()
+        .iter_mut()
+        .zip(prev_chunks.remainder())
+    {
+        *c = c.wrapping_add(p);
+    }
+}
+
+/// Sub: `cur[i] += cur[i - bpp]`.  For power-of-two `bpp` the running sum is
+/// computed as an in-register log-step prefix sum per vector plus a carry of
+/// the previous vector's last pixel; other pixel sizes go scalar.
+#[inline(always)]
+unsafe fn png_unfilter_sub(cur: &mut [u8], bpp: usize) {
+    let mut i = bpp;
+    if bpp.is_power_of_two() && bpp < PNG_LANES && cur.len() >= PNG_LANES {
+        let carry_idx = Simd::<u8, PNG_LANES>::from_array(core::array::from_fn(|k| {
+            (PNG_LANES - bpp + k % bpp) as u8
+        }));
+        let mut acc = Simd::<u8, PNG_LANES>::splat(0);
+        let mut pos = 0;
+        while pos + PNG_LANES <= cur.len() {
+            let mut v = Simd::<u8, PNG_LANES>::from_slice(&cur[pos..pos + PNG_LANES]);
+            let mut step = bpp;
+            while step < PNG_LANES {
+                // Out-of-range indices select zero, giving a lane shift.
+                let shift = Simd::<u8, PNG_LANES>::from_array(core::array::from_fn(|k| {
+                    if k >= step { (k - step) as u8 } else { u8::MAX }
+                }));
+                v += v.swizzle_dyn(shift);
+                step *= 2;
+            }
+            acc = v + acc.swizzle_dyn(carry_idx);
+            acc.copy_to_slice(&mut cur[pos..pos + PNG_LANES]);
+            pos += PNG_LANES;
+        }
+        i = pos.max(bpp);
+    }
+    while i < cur.len() {
+        cur[i] = cur[i].wrapping_add(cur[i - bpp]);
+        i += 1;
+    }
+}
+
+/// Average: `cur[i] += (cur[i - bpp] + prev[i]) / 2` (9-bit intermediate).
+#[inline(always)]
+fn png_unfilter_avg(cur: &mut [u8], prev: &[u8], bpp: usize) {
+    for i in 0..cur.len() {
+        let left = if i >= bpp { cur[i - bpp] as u16 } else { 0 };
+        cur[i] = cur[i].wrapping_add(((left + prev[i] as u16) >> 1) as u8);
+    }
+}
+
+#[inline(always)]
+fn paeth_predictor(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Paeth: `cur[i] += paeth(cur[i - bpp], prev[i], prev[i - bpp])`.
+#[inline(always)]
+fn png_unfilter_paeth(cur: &mut [u8], prev: &[u8], bpp: usize) {
+    for i in 0..cur.len() {
+        let (a, c) = if i >= bpp {
+            (cur[i - bpp], prev[i - bpp])
+        } else {
+            (0, 0)
+        };
+        cur[i] = cur[i].wrapping_add(paeth_predictor(a, prev[i], c));
+    }
+}
+
+/// Reverse one PNG scanline filter in place.
+///
+/// `filter_type` is the per-row PNG filter byte (0 None, 1 Sub, 2 Up,
+/// 3 Average, 4 Paeth); `cur_row` holds the `len` filtered bytes that follow
+/// it and `prev_row` the already-reconstructed previous scanline, or null for
+/// the first row of an image/pass (treated as all zeros).  `bpp` is the number
+/// of bytes per complete pixel, rounded up to 1 for sub-byte formats.
+///
+/// Returns 1 on success, 0 if `filter_type` or `bpp` (1..=8) is invalid; the
+/// row is left untouched in that case.
+///
+/// # Safety
+/// `cur_row` must be valid for `len` bytes of writes and, when non-null,
+/// `prev_row` for `len` bytes of reads.  The rows must not overlap.
+#[unsafe(export_name = c_symbol!("png_defilter_row"))]
+pub unsafe extern "C" fn png_defilter_row(
+    filter_type: u8,
+    prev_row: *const u8,
+    cur_row: *mut u8,
+    len: usize,
+    bpp: usize,
+) -> u8 {
+    if filter_type > 4 || !(1..=8).contains(&bpp) {
+        return 0;
+    }
+    if cur_row.is_null() || len == 0 || filter_type == 0 {
+        return 1;
+    }
+    let cur = core::slice::from_raw_parts_mut(cur_row, len);
+    if prev_row.is_null() {
+        // With an all-zero previous row Up is a no-op, Paeth degenerates to
+        // Sub, and Average halves only the left neighbour.
+        match filter_type {
+            1 | 4 => png_unfilter_sub(cur, bpp),
+            3 => {
+                for i in bpp..len {
+                    cur[i] = cur[i].wrapping_add(cur[i - bpp] >> 1);
+                }
+            }
+            _ => {}
+        }
+        return 1;
+    }
+    let prev = core::slice::from_raw_parts(prev_row, len);
+    match filter_type {
+        1 => png_unfilter_sub(cur, bpp),
+        2 => png_unfilter_up(cur, prev),
+        3 => png_unfilter_avg(cur, prev, bpp),
+        _ => png_unfilter_paeth(cur, prev, bpp),
+    }
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    fn pixels(n: usize) -> Vec<u8> {
+        (0..n * 4).map(|i| (i * 37 % 256) as u8).collect()
+    }
+
+    #[test]
+    fn test_swizzle_rgba_bgra() {
+        for n in [1usize, 7, 8, 9, 33, 100] {
+            let src = pixels(n);
+            let mut dst = vec![0u8; src.len()];
+            unsafe { super::swizzle_rgba(src.as_ptr(), n, 0x0300_0102, dst.as_mut_ptr()) };
+            for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact(4)) {
+                assert_eq!(d, [s[2], s[1], s[0], s[3]], "n={}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swizzle_rgba_in_place() {
+        let src = pixels(41);
+        let mut buf = src.clone();
+        // ARGB <- RGBA rotation.
+        let p = buf.as_mut_ptr();
+        unsafe { super::swizzle_rgba(p, 41, 0x0201_0003, p) };
+        for (s, d) in src.chunks_exact(4).zip(buf.chunks_exact(4)) {
+            assert_eq!(d, [s[3], s[0], s[1], s[2]]);
+        }
+    }
+
+    #[test]
+    fn test_premultiply_alpha_exact() {
+        // Exhaustively check the rounding against the f64 reference.
+        let mut src = Vec::with_capacity(256 * 256 * 4);
+        for a in 0..=255u8 {
+            for c in 0..=255u8 {
+                src.extend_from_slice(&[c, 255 - c, c / 2, a]);
+            }
+        }
+        let n = src.len() / 4;
+        let mut dst = vec![0u8; src.len()];
+        unsafe { super::premultiply_alpha(src.as_ptr(), n, dst.as_mut_ptr()) };
+        for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact(4)) {
+            let a = s[3] as f64;
+            for c in 0..3 {
+                let want = (s[c] as f64 * a / 255.0).round() as u8;
+                assert_eq!(d[c], want, "c={} a={}", s[c], s[3]);
+            }
+            assert_eq!(d[3], s[3]);
+        }
+    }
+
+    #[test]
+    fn test_alpha_blend() {
+        for n in [1usize, 7, 8, 9, 33, 100] {
+            let mut src = vec![0u8; n * 4];
+            unsafe { super::rgba_premultiply(pixels(n).as_ptr(), n, src.as_mut_ptr()) };
+            let base: Vec<u8> = pixels(n + 3)[12..].to_vec();
+            let mut dst = base.clone();
+            unsafe { super::alpha_blend(dst.as_mut_ptr(), src.as_ptr(), n) };
+            for ((s, d), o) in src
+                .chunks_exact(4)
+                .zip(base.chunks_exact(4))
+                .zip(dst.chunks_exact(4))
+            {
+                let inv = 255.0 - s[3] as f64;
+                for c in 0..4 {
+                    let want = s[c] as f64 + (d[c] as f64 * inv / 255.0).round();
+                    assert_eq!(o[c], want.min(255.0) as u8, "n={n} s={s:?} d={d:?}");
+                }
+            }
+        }
+        // Opaque source replaces, transparent source keeps the destination.
+        let src = [10u8, 20, 30, 255, 0, 0, 0, 0];
+        let mut dst = [200u8, 100, 50, 128, 200, 100, 50, 128];
+        unsafe { super::alpha_blend(dst.as_mut_ptr(), src.as_ptr(), 2) };
+        assert_eq!(dst, [10, 20, 30, 255, 200, 100, 50, 128]);
+    }
+
+    #[test]
+    fn test_rgb_to_gray_matches_scalar() {
+        for n in [1usize, 15, 16, 21, 22, 100, 257] {
+            let src: Vec<u8> = (0..n * 3).map(|i| (i * 91 % 256) as u8).collect();
+            let mut g601 = vec![0u8; n];
+            let mut g709 = vec![0u8; n];
+            unsafe {
+                super::rgb_to_gray(src.as_ptr(), n, g601.as_mut_ptr());
+                super::rgb_to_gray_bt709(src.as_ptr(), n, g709.as_mut_ptr());
+            }
+            for (i, p) in src.chunks_exact(3).enumerate() {
+                let y = |w: [u16; 3]| {
+                    ((p[0] as u32 * w[0] as u32
+                        + p[1] as u32 * w[1] as u32
+                        + p[2] as u32 * w[2] as u32
+                        + 128)
+                        >> 8) as u8
+                };
+                assert_eq!(g601[i], y(super::BT601_Q8), "601 n={} px={}", n, i);
+                assert_eq!(g709[i], y(super::BT709_Q8), "709 n={} px={}", n, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgb_to_gray_extremes() {
+        let src = [255u8; 3 * 40];
+        let mut dst = [0u8; 40];
+        unsafe { super::rgb_to_gray(src.as_ptr(), 40, dst.as_mut_ptr()) };
+        assert!(dst.iter().all(|&y| y == 255));
+    }
+
+    /// Straightforward per-byte PNG reconstruction used as the oracle.
+    fn defilter_ref(ft: u8, prev: &[u8], cur: &mut [u8], bpp: usize) {
+        for i in 0..cur.len() {
+            let a = if i >= bpp { cur[i - bpp] } else { 0 };
+            let b = prev[i];
+            let c = if i >= bpp { prev[i - bpp] } else { 0 };
+            let pred = match ft {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                _ => super::paeth_predictor(a, b, c),
+            };
+            cur[i] = cur[i].wrapping_add(pred);
+        }
+    }
+
+    #[test]
+    fn test_png_defilter_row_matches_reference() {
+        for bpp in 1..=8usize {
+            for len in [1usize, 7, 16, 33, 64, 100] {
+                let prev: Vec<u8> = (0..len).map(|i| (i * 29 + 3) as u8).collect();
+                let row: Vec<u8> = (0..len).map(|i| (i * 53 + bpp) as u8).collect();
+                for ft in 0..=4u8 {
+                    let mut want = row.clone();
+                    defilter_ref(ft, &prev, &mut want, bpp);
+                    let mut got = row.clone();
+                    let ok = unsafe {
+                        super::png_defilter_row(ft, prev.as_ptr(), got.as_mut_ptr(), len, bpp)
+                    };
+                    assert_eq!(ok, 1);
+                    assert_eq!(got, want, "ft={} bpp={} len={}", ft, bpp, len);
+
+                    let zeros = vec![0u8; len];
+                    let mut want = row.clone();
+                    defilter_ref(ft, &zeros, &mut want, bpp);
+                    let mut got = row.clone();
+                    unsafe {
+                        super::png_defilter_row(ft, core::ptr::null(), got.as_mut_ptr(), len, bpp)
+                    };
+                    assert_eq!(got, want, "null prev ft={} bpp={} len={}", ft, bpp, len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_png_defilter_row_rejects_bad_args() {
+        let mut row = [1u8, 2, 3];
+        unsafe {
+            assert_eq!(
+                super::png_defilter_row(5, core::ptr::null(), row.as_mut_ptr(), 3, 1),
+                0
+            );
+            assert_eq!(
+                super::png_defilter_row(1, core::ptr::null(), row.as_mut_ptr(), 3, 0),
+                0
+            );
+        }
+        assert_eq!(row, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,315 @@
+//! IP address parsing.  Characters are classified a vector at a time; the
+//! dotted / colon-separated structure is then walked with bitmask scans.
+use core::simd::Simd;
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd};
+
+use crate::error::{SIMBA_ERR_INVALID_INPUT, fail};
+use crate::hex::decode_nibbles;
+
+/// Longest dotted quad, `255.255.255.255`.
+const IPV4_MAX_LEN: usize = 15;
+/// Longest IPv6 literal, `ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255`.
+const IPV6_MAX_LEN: usize = 45;
+
+// === IPv4 ====================================================================
+
+/// Parse a dotted quad `a.b.c.d` (decimal octets 0..=255 without leading
+/// zeros) into `a << 24 | b << 16 | c << 8 | d`, or return the index of the
+/// first byte that does not fit.
+pub(crate) fn parse_ipv4_impl(s: &[u8]) -> Result<u32, usize> {
+    let mut buf = [0u8; IPV4_MAX_LEN + 1];
+    let n = s.len().min(buf.len());
+    buf[..n].copy_from_slice(&s[..n]);
+    let v = Simd::from_array(buf);
+    let digits = (v - Simd::splat(b'0'))
+        .simd_lt(Simd::splat(10))
+        .to_bitmask();
+    let dots = v.simd_eq(Simd::splat(b'.')).to_bitmask();
+
+    let (mut addr, mut start) = (0u32, 0);
+    for octet in 0..4 {
+        // Digits run up to the next non-digit.
+        let end = start + (!digits >> start).trailing_zeros() as usize;
+        let run = &s[start..end.min(s.len())];
+        if run.is_empty() {
+            return Err(start);
+        }
+        if run.len() > 1 && run[0] == b'0' {
+            return Err(start + 1);
+        }
+        let mut x = 0u32;
+        for (k, &c) in run.iter().enumerate() {
+            x = 10 * x + (c - b'0') as u32;
+            if x > 255 {
+                return Err(start + k);
+            }
+        }
+        addr = addr << 8 | x;
+        if octet < 3 {
+            if end >= s.len() || dots >> end & 1 == 0 {
+                return Err(end.min(s.len()));
+            }
+            start = end + 1;
+        } else if end != s.len() {
+            return Err(end);
+        }
+    }
+    Ok(addr)
+}
+
+/// Parse the dotted-quad IPv4 address in the `len` bytes at `ptr` into
+/// `*out` as `a << 24 | b << 16 | c << 8 | d` (the address in network order
+/// as a native integer).
+///
+/// Returns 0, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+/// character stored in `*out` (`len` when the input ends early).  Octets
+/// with leading zeros are rejected, as Go's `netip` does.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+/// write.
+#[unsafe(export_name = c_symbol!("parse_ipv4"))]
+pub unsafe extern "C" fn parse_ipv4(ptr: *const u8, len: usize, out: *mut u32) -> i32 {
+    let s: &[u8] = if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    match parse_ipv4_impl(s) {
+        Ok(addr) => {
+            *out = addr;
+            0
+        }
+        Err(at) => {
+            *out = at as u32;
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid IPv4 address: bad character at offset {at}"),
+            )
+        }
+    }
+}
+
+// === IPv6 ====================================================================
+
+/// Parse an IPv6 literal (RFC 4291 text form: eight colon-separated groups
+/// of 1–4 hex digits, at most one `::`, optionally ending in a dotted quad)
+/// into its 16 bytes, or return the index of the first byte that does not
+/// fit.
+fn parse_ipv6_impl(s: &[u8]) -> Result<[u8; 16], usize> {
+    if s.len() > IPV6_MAX_LEN {
+        return Err(IPV6_MAX_LEN);
+    }
+    let mut buf = [0u8; 64];
+    buf[..s.len()].copy_from_slice(s);
+    let (nibbles, not_hex) = decode_nibbles(Simd::<u8, 64>::from_array(buf));
+    let nibbles = nibbles.to_array();
+    let hex = !not_hex.to_bitmask() & ((1 << s.len()) - 1);
+    let colons = Simd::from_array(buf)
+        .simd_eq(Simd::splat(b':'))
+        .to_bitmask();
+
+    let mut groups = [0u16; 8];
+    let (mut n, mut ellipsis, mut i) = (0, None, 0);
+    if s.starts_with(b"::") {
+        ellipsis = Some((0, 1));
+        i = 2;
+    } else if s.first() == Some(&b':') {
+        return Err(1);
+    }
+    while i < s.len() {
+        let start = i;
+        let run = ((!hex >> start).trailing_zeros() as usize).min(5);
+        if run == 0 {
+            return Err(start);
+        }
+        if s.get(start + run) == Some(&b'.') {
+            // Trailing dotted quad: the last two groups.
+            if n > 6 {
+                return Err(start);
+            }
+            let v4 = parse_ipv4_impl(&s[start..]).map_err(|at| start + at)?;
+            groups[n] = (v4 >> 16) as u16;
+            groups[n + 1] = v4 as u16;
+            n += 2;
+            break;
+        }
+        if run > 4 {
+            return Err(start + 4);
+        }
+        if n == 8 {
+            return Err(start);
+        }
+        groups[n] = nibbles[start..start + run]
+            .iter()
+            .fold(0, |g, &d| g << 4 | d as u16);
+        n += 1;
+        i += run;
+        if i == s.len() {
+            break;
+        }
+        if colons >> i & 1 == 0 {
+            return Err(i);
+        }
+        i += 1;
+        if colons >> i & 1 != 0 {
+            if ellipsis.is_some() {
+                return Err(i);
+            }
+            ellipsis = Some((n, i));
+            i += 1;
+        } else if i == s.len() {
+            return Err(i);
+        }
+    }
+    match ellipsis {
+        None if n < 8 => return Err(s.len()),
+        // `::` must stand for at least one zero group.
+        Some((_, at)) if n == 8 => return Err(at),
+        Some((e, _)) => {
+            let zeros = 8 - n;
+            groups.copy_within(e..n, e + zeros);
+            groups[e..e + zeros].fill(0);
+        }
+        None => {}
+    }
+    let mut out = [0u8; 16];
+    for (o, g) in out.chunks_exact_mut(2).zip(groups) {
+        o.copy_from_slice(&g.to_be_bytes());
+    }
+    Ok(out)
+}
+
+/// Parse the IPv6 address in the `len` bytes at `ptr` (e.g. `2001:db8::1`,
+/// `::ffff:192.0.2.1`) into the 16 network-order bytes at `out`.  Zones
+/// (`%eth0`) are not accepted.
+///
+/// Returns 0, or `SIMBA_ERR_INVALID_INPUT` with the index of the first bad
+/// character stored as a native-endian u32 in the first four bytes of `out`
+/// (`len` when the input ends early).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for 16
+/// bytes.
+#[unsafe(export_name = c_symbol!("parse_ipv6"))]
+pub unsafe extern "C" fn parse_ipv6(ptr: *const u8, len: usize, out: *mut u8) -> i32 {
+    let s: &[u8] = if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    match parse_ipv6_impl(s) {
+        Ok(addr) => {
+            core::ptr::copy_nonoverlapping(addr.as_ptr(), out, 16);
+            0
+        }
+        Err(at) => {
+            core::ptr::copy_nonoverlapping((at as u32).to_ne_bytes().as_ptr(), out, 4);
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid IPv6 address: bad character at offset {at}"),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipv4() {
+        let ok = [
+            "0.0.0.0",
+            "1.2.3.4",
+            "192.168.0.255",
+            "255.255.255.255",
+            "10.0.100.9",
+        ];
+        for s in ok {
+            let want = u32::from(s.parse::<std::net::Ipv4Addr>().unwrap());
+            assert_eq!(parse_ipv4_impl(s.as_bytes()), Ok(want), "{s}");
+        }
+        let bad = [
+            ("", 0),
+            ("1.2.3", 5),
+            ("1.2.3.", 6),
+            ("1.2.3.4.", 7),
+            ("1.2.3.4.5", 7),
+            ("1..3.4", 2),
+            (".1.2.3", 0),
+            ("1.2.3.256", 8),
+            ("1.2.3.1000", 9),
+            ("01.2.3.4", 1),
+            ("1.2.3.4 ", 7),
+            ("1.2.x.4", 4),
+            ("1.2.3.4444444444444", 8),
+        ];
+        for (s, at) in bad {
+            assert_eq!(parse_ipv4_impl(s.as_bytes()), Err(at), "{s:?}");
+        }
+
+        let mut out = 0u32;
+        assert_eq!(unsafe { parse_ipv4(b"8.8.4.4".as_ptr(), 7, &mut out) }, 0);
+        assert_eq!(out, 0x0808_0404);
+        assert_eq!(
+            unsafe { parse_ipv4(b"8.8.4".as_ptr(), 5, &mut out) },
+            SIMBA_ERR_INVALID_INPUT
+        );
+        assert_eq!(out, 5);
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        let ok = [
+            "::",
+            "::1",
+            "1::",
+            "2001:db8::1",
+            "2001:DB8:0:0:8:800:200C:417A",
+            "fe80::1:2:3:4:5",
+            "1:2:3:4:5:6:7:8",
+            "1::8",
+            "::ffff:192.0.2.128",
+            "1:2:3:4:5:6:1.2.3.4",
+            "ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255",
+        ];
+        for s in ok {
+            let want = s.parse::<std::net::Ipv6Addr>().unwrap().octets();
+            assert_eq!(parse_ipv6_impl(s.as_bytes()), Ok(want), "{s}");
+        }
+        let bad = [
+            ("", 0),
+            (":", 1),
+            (":1::", 1),
+            ("1:2:3:4:5:6:7", 13),
+            ("1:2:3:4:5:6:7:8:9", 16),
+            ("1:2:3:4:5:6:7:8::", 16),
+            ("1::2::3", 5),
+            ("1:", 2),
+            ("12345::", 4),
+            ("1:2:3:4:5:6:7::8", 14),
+            ("::g", 2),
+            ("1:2:3:4:5:6:7:1.2.3.4", 14),
+            ("::1.2.3", 7),
+            ("fe80::1%eth0", 7),
+        ];
+        for (s, at) in bad {
+            assert_eq!(parse_ipv6_impl(s.as_bytes()), Err(at), "{s:?}");
+        }
+
+        let mut out = [0u8; 16];
+        let s = b"2001:db8::ff00:42:8329";
+        assert_eq!(
+            unsafe { parse_ipv6(s.as_ptr(), s.len(), out.as_mut_ptr()) },
+            0
+        );
+        assert_eq!(out[..4], [0x20, 0x01, 0x0d, 0xb8]);
+        assert_eq!(out[10..], [0xff, 0x00, 0x00, 0x42, 0x83, 0x29]);
+        assert_eq!(
+            unsafe { parse_ipv6(b"1::2::".as_ptr(), 6, out.as_mut_ptr()) },
+            SIMBA_ERR_INVALID_INPUT
+        );
+        assert_eq!(u32::from_ne_bytes(out[..4].try_into().unwrap()), 5);
+    }
+}
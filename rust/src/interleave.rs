@@ -0,0 +1,496 @@
+//! Fixed-arity interleave / deinterleave kernels (array-of-structs ↔ planes).
+use core::simd::{LaneCount, Simd, SimdElement, SupportedLaneCount, Swizzle};
+
+use crate::{load_simd, store_simd};
+
+const INTERLEAVE_LANES: usize = 16;
+
+// === Two-channel zip / unzip =================================================
+
+// The two-stream case maps directly onto the `zip`/`unzip` (punpck / zip1,
+// uzp1) instructions, so it gets dedicated kernels per element width.
+
+/// `L|R|L|R…` ← `L…`, `R…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn interleave2_impl<T: SimdElement + Copy, const L: usize>(
+    left: *const T,
+    right: *const T,
+    n_frames: usize,
+    dst: *mut T,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= n_frames {
+        let l = load_simd::<T, L>(left.add(i));
+        let r = load_simd::<T, L>(right.add(i));
+        let (lo, hi) = l.interleave(r);
+        store_simd(dst.add(2 * i), lo);
+        store_simd(dst.add(2 * i + L), hi);
+        i += L;
+    }
+    while i < n_frames {
+        *dst.add(2 * i) = *left.add(i);
+        *dst.add(2 * i + 1) = *right.add(i);
+        i += 1;
+    }
+}
+
+/// `L…`, `R…` ← `L|R|L|R…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn deinterleave2_impl<T: SimdElement + Copy, const L: usize>(
+    src: *const T,
+    n_frames: usize,
+    left: *mut T,
+    right: *mut T,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= n_frames {
+        let a = load_simd::<T, L>(src.add(2 * i));
+        let b = load_simd::<T, L>(src.add(2 * i + L));
+        let (l, r) = a.deinterleave(b);
+        store_simd(left.add(i), l);
+        store_simd(right.add(i), r);
+        i += L;
+    }
+    while i < n_frames {
+        *left.add(i) = *src.add(2 * i);
+        *right.add(i) = *src.add(2 * i + 1);
+        i += 1;
+    }
+}
+
+macro_rules! export_interleave2 {
+    ($interleave:ident, $deinterleave:ident, $t:ty) => {
+        #[doc = concat!(
+            "Interleave `n` `", stringify!($t), "` elements from `a` and `b` into `dst` as `a0 b0 a1 b1 …`.\n\n",
+            "# Safety\n",
+            "`a`/`b` must be valid for `n` elements and `dst` for `2 * n`; `dst` must not overlap the inputs."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($interleave)))]
+        pub unsafe extern "C" fn $interleave(a: *const $t, b: *const $t, n: usize, dst: *mut $t) {
+            if a.is_null() || b.is_null() || dst.is_null() || n == 0 {
+                return;
+            }
+            interleave2_impl::<$t, INTERLEAVE_LANES>(a, b, n, dst);
+        }
+
+        #[doc = concat!(
+            "Split `2 * n` interleaved `", stringify!($t), "` elements from `src` into the even (`a`) and odd (`b`) streams.\n\n",
+            "# Safety\n",
+            "`src` must be valid for `2 * n` elements and `a`/`b` for `n`; outputs must not overlap `src` or each other."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($deinterleave)))]
+        pub unsafe extern "C" fn $deinterleave(src: *const $t, n: usize, a: *mut $t, b: *mut $t) {
+            if src.is_null() || a.is_null() || b.is_null() || n == 0 {
+                return;
+            }
+            deinterleave2_impl::<$t, INTERLEAVE_LANES>(src, n, a, b);
+        }
+    };
+}
+export_interleave2!(interleave2_u8, deinterleave2_u8, u8);
+export_interleave2!(interleave2_u16, deinterleave2_u16, u16);
+export_interleave2!(interleave2_u32, deinterleave2_u32, u32);
+export_interleave2!(interleave2_f32, deinterleave2_f32, f32);
+export_interleave2!(interleave2_u64, deinterleave2_u64, u64);
+
+// === Three-channel zip / unzip ===============================================
+
+// No single instruction covers three streams, so each output vector is built
+// with two table shuffles: one over the first two inputs and one that fills
+// the remaining lanes from the third.
+
+/// Stage `S` of gathering channel `K` of `N` three-channel frames held in
+/// three `N`-lane vectors.  Stage 0 picks the lanes found in the first two
+/// vectors; stage 1 keeps those and fills the rest from the third.
+struct Unzip3<const K: usize, const S: usize>;
+
+impl<const N: usize, const K: usize, const S: usize> Swizzle<N> for Unzip3<K, S> {
+    const INDEX: [usize; N] = {
+        let mut idx = [0; N];
+        let mut j = 0;
+        while j < N {
+            let p = 3 * j + K;
+            idx[j] = match (S, p < 2 * N) {
+                (0, true) => p,
+                (0, false) => 0,
+                (_, true) => j,
+                (_, false) => p - N,
+            };
+            j += 1;
+        }
+        idx
+    };
+}
+
+/// Stage `S` of building output vector `M` of `a0 b0 c0 a1 …` from three
+/// `N`-lane channel vectors.  Stage 0 places the `a`/`b` lanes; stage 1
+/// keeps those and fills in the `c` lanes.
+struct Zip3<const M: usize, const S: usize>;
+
+impl<const N: usize, const M: usize, const S: usize> Swizzle<N> for Zip3<M, S> {
+    const INDEX: [usize; N] = {
+        let mut idx = [0; N];
+        let mut i = 0;
+        while i < N {
+            let p = M * N + i;
+            idx[i] = match (S, p % 3) {
+                (0, 0) => p / 3,
+                (0, 1) => N + p / 3,
+                (0, _) => 0,
+                (_, 2) => N + p / 3,
+                _ => i,
+            };
+            i += 1;
+        }
+        idx
+    };
+}
+
+#[inline(always)]
+fn zip3<T: SimdElement, const M: usize, const L: usize>(
+    a: Simd<T, L>,
+    b: Simd<T, L>,
+    c: Simd<T, L>,
+) -> Simd<T, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    Zip3::<M, 1>::concat_swizzle(Zip3::<M, 0>::concat_swizzle(a, b), c)
+}
+
+#[inline(always)]
+fn unzip3<T: SimdElement, const K: usize, const L: usize>(
+    v0: Simd<T, L>,
+    v1: Simd<T, L>,
+    v2: Simd<T, L>,
+) -> Simd<T, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    Unzip3::<K, 1>::concat_swizzle(Unzip3::<K, 0>::concat_swizzle(v0, v1), v2)
+}
+
+/// `A|B|C|A|B|C…` ← `A…`, `B…`, `C…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn interleave3_impl<T: SimdElement + Copy, const L: usize>(
+    a: *const T,
+    b: *const T,
+    c: *const T,
+    n_frames: usize,
+    dst: *mut T,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= n_frames {
+        let va = load_simd::<T, L>(a.add(i));
+        let vb = load_simd::<T, L>(b.add(i));
+        let vc = load_simd::<T, L>(c.add(i));
+        store_simd(dst.add(3 * i), zip3::<T, 0, L>(va, vb, vc));
+        store_simd(dst.add(3 * i + L), zip3::<T, 1, L>(va, vb, vc));
+        store_simd(dst.add(3 * i + 2 * L), zip3::<T, 2, L>(va, vb, vc));
+        i += L;
+    }
+    while i < n_frames {
+        *dst.add(3 * i) = *a.add(i);
+        *dst.add(3 * i + 1) = *b.add(i);
+        *dst.add(3 * i + 2) = *c.add(i);
+        i += 1;
+    }
+}
+
+/// `A…`, `B…`, `C…` ← `A|B|C|A|B|C…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn deinterleave3_impl<T: SimdElement + Copy, const L: usize>(
+    src: *const T,
+    n_frames: usize,
+    a: *mut T,
+    b: *mut T,
+    c: *mut T,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut i = 0;
+    while i + L <= n_frames {
+        let v0 = load_simd::<T, L>(src.add(3 * i));
+        let v1 = load_simd::<T, L>(src.add(3 * i + L));
+        let v2 = load_simd::<T, L>(src.add(3 * i + 2 * L));
+        store_simd(a.add(i), unzip3::<T, 0, L>(v0, v1, v2));
+        store_simd(b.add(i), unzip3::<T, 1, L>(v0, v1, v2));
+        store_simd(c.add(i), unzip3::<T, 2, L>(v0, v1, v2));
+        i += L;
+    }
+    while i < n_frames {
+        *a.add(i) = *src.add(3 * i);
+        *b.add(i) = *src.add(3 * i + 1);
+        *c.add(i) = *src.add(3 * i + 2);
+        i += 1;
+    }
+}
+
+macro_rules! export_interleave3 {
+    ($interleave:ident, $deinterleave:ident, $t:ty) => {
+        #[doc = concat!(
+            "Interleave `n` `", stringify!($t), "` elements from `a`, `b` and `c` into `dst` as `a0 b0 c0 a1 b1 c1 …`.\n\n",
+            "# Safety\n",
+            "`a`/`b`/`c` must be valid for `n` elements and `dst` for `3 * n`; `dst` must not overlap the inputs."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($interleave)))]
+        pub unsafe extern "C" fn $interleave(a: *const $t, b: *const $t, c: *const $t, n: usize, dst: *mut $t) {
+            if a.is_null() || b.is_null() || c.is_null() || dst.is_null() || n == 0 {
+                return;
+            }
+            interleave3_impl::<$t, INTERLEAVE_LANES>(a, b, c, n, dst);
+        }
+
+        #[doc = concat!(
+            "Split `3 * n` interleaved `", stringify!($t), "` elements from `src` into the streams `a`, `b` and `c` (e.g. RGB → planes).\n\n",
+            "# Safety\n",
+            "`src` must be valid for `3 * n` elements and `a`/`b`/`c` for `n`; outputs must not overlap `src` or each other."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($deinterleave)))]
+        pub unsafe extern "C" fn $deinterleave(src: *const $t, n: usize, a: *mut $t, b: *mut $t, c: *mut $t) {
+            if src.is_null() || a.is_null() || b.is_null() || c.is_null() || n == 0 {
+                return;
+            }
+            deinterleave3_impl::<$t, INTERLEAVE_LANES>(src, n, a, b, c);
+        }
+    };
+}
+export_interleave3!(interleave3_u8, deinterleave3_u8, u8);
+export_interleave3!(interleave3_u16, deinterleave3_u16, u16);
+export_interleave3!(interleave3_u32, deinterleave3_u32, u32);
+export_interleave3!(interleave3_f32, deinterleave3_f32, f32);
+export_interleave3!(interleave3_u64, deinterleave3_u64, u64);
+
+// === Four-channel zip / unzip ================================================
+
+// Four streams are two rounds of the two-stream zip: `a`/`c` and `b`/`d`
+// first, then the two halves against each other (and the reverse to unzip).
+
+/// `A|B|C|D|A|B|C|D…` ← `A…`, `B…`, `C…`, `D…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn interleave4_impl<T: SimdElement + Copy, const L: usize>(
+    src: [*const T; 4],
+    n_frames: usize,
+    dst: *mut T,
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let [a, b, c, d] = src;
+    let mut i = 0;
+    while i + L <= n_frames {
+        let (ac_lo, ac_hi) = load_simd::<T, L>(a.add(i)).interleave(load_simd(c.add(i)));
+        let (bd_lo, bd_hi) = load_simd::<T, L>(b.add(i)).interleave(load_simd(d.add(i)));
+        let (v0, v1) = ac_lo.interleave(bd_lo);
+        let (v2, v3) = ac_hi.interleave(bd_hi);
+        store_simd(dst.add(4 * i), v0);
+        store_simd(dst.add(4 * i + L), v1);
+        store_simd(dst.add(4 * i + 2 * L), v2);
+        store_simd(dst.add(4 * i + 3 * L), v3);
+        i += L;
+    }
+    while i < n_frames {
+        for (k, p) in src.iter().enumerate() {
+            *dst.add(4 * i + k) = *p.add(i);
+        }
+        i += 1;
+    }
+}
+
+/// `A…`, `B…`, `C…`, `D…` ← `A|B|C|D|A|B|C|D…` for `n_frames` frames.
+#[inline(always)]
+pub(crate) unsafe fn deinterleave4_impl<T: SimdElement + Copy, const L: usize>(
+    src: *const T,
+    n_frames: usize,
+    dst: [*mut T; 4],
+) where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let [a, b, c, d] = dst;
+    let mut i = 0;
+    while i + L <= n_frames {
+        let v0 = load_simd::<T, L>(src.add(4 * i));
+        let v1 = load_simd::<T, L>(src.add(4 * i + L));
+        let v2 = load_simd::<T, L>(src.add(4 * i + 2 * L));
+        let v3 = load_simd::<T, L>(src.add(4 * i + 3 * L));
+        let (ac_lo, bd_lo) = v0.deinterleave(v1);
+        let (ac_hi, bd_hi) = v2.deinterleave(v3);
+        let (va, vc) = ac_lo.deinterleave(ac_hi);
+        let (vb, vd) = bd_lo.deinterleave(bd_hi);
+        store_simd(a.add(i), va);
+        store_simd(b.add(i), vb);
+        store_simd(c.add(i), vc);
+        store_simd(d.add(i), vd);
+        i += L;
+    }
+    while i < n_frames {
+        for (k, p) in dst.iter().enumerate() {
+            *p.add(i) = *src.add(4 * i + k);
+        }
+        i += 1;
+    }
+}
+
+macro_rules! export_interleave4 {
+    ($interleave:ident, $deinterleave:ident, $t:ty) => {
+        #[doc = concat!(
+            "Interleave `n` `", stringify!($t), "` elements from `a`, `b`, `c` and `d` into `dst` as `a0 b0 c0 d0 a1 …`.\n\n",
+            "# Safety\n",
+            "`a`/`b`/`c`/`d` must be valid for `n` elements and `dst` for `4 * n`; `dst` must not overlap the inputs."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($interleave)))]
+        pub unsafe extern "C" fn $interleave(
+            a: *const $t,
+            b: *const $t,
+            c: *const $t,
+            d: *const $t,
+            n: usize,
+            dst: *mut $t,
+        ) {
+            if a.is_null() || b.is_null() || c.is_null() || d.is_null() || dst.is_null() || n == 0 {
+                return;
+            }
+            interleave4_impl::<$t, INTERLEAVE_LANES>([a, b, c, d], n, dst);
+        }
+
+        #[doc = concat!(
+            "Split `4 * n` interleaved `", stringify!($t), "` elements from `src` into the streams `a`, `b`, `c` and `d` (e.g. RGBA → planes).\n\n",
+            "# Safety\n",
+            "`src` must be valid for `4 * n` elements and `a`/`b`/`c`/`d` for `n`; outputs must not overlap `src` or each other."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($deinterleave)))]
+        pub unsafe extern "C" fn $deinterleave(
+            src: *const $t,
+            n: usize,
+            a: *mut $t,
+            b: *mut $t,
+            c: *mut $t,
+            d: *mut $t,
+        ) {
+            if src.is_null() || a.is_null() || b.is_null() || c.is_null() || d.is_null() || n == 0 {
+                return;
+            }
+            deinterleave4_impl::<$t, INTERLEAVE_LANES>(src, n, [a, b, c, d]);
+        }
+    };
+}
+export_interleave4!(interleave4_u8, deinterleave4_u8, u8);
+export_interleave4!(interleave4_u16, deinterleave4_u16, u16);
+export_interleave4!(interleave4_u32, deinterleave4_u32, u32);
+export_interleave4!(interleave4_f32, deinterleave4_f32, f32);
+export_interleave4!(interleave4_u64, deinterleave4_u64, u64);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_interleave2_u16_round_trip() {
+        for n in [1usize, 3, 16, 31, 64, 65] {
+            let a: Vec<u16> = (0..n as u16).collect();
+            let b: Vec<u16> = (0..n as u16).map(|x| 1000 + x).collect();
+            let mut zipped = vec![0u16; 2 * n];
+            let (mut a2, mut b2) = (vec![0u16; n], vec![0u16; n]);
+            unsafe {
+                super::interleave2_u16(a.as_ptr(), b.as_ptr(), n, zipped.as_mut_ptr());
+                super::deinterleave2_u16(zipped.as_ptr(), n, a2.as_mut_ptr(), b2.as_mut_ptr());
+            }
+            let want: Vec<u16> = a.iter().zip(&b).flat_map(|(&x, &y)| [x, y]).collect();
+            assert_eq!(zipped, want, "n={}", n);
+            assert_eq!((a2, b2), (a, b));
+        }
+    }
+
+    #[test]
+    fn test_deinterleave2_f32() {
+        let src: Vec<f32> = (0..70).map(|i| i as f32 * 0.5).collect();
+        let (mut a, mut b) = (vec![0f32; 35], vec![0f32; 35]);
+        unsafe { super::deinterleave2_f32(src.as_ptr(), 35, a.as_mut_ptr(), b.as_mut_ptr()) };
+        for i in 0..35 {
+            assert_eq!(a[i], src[2 * i]);
+            assert_eq!(b[i], src[2 * i + 1]);
+        }
+    }
+
+    #[test]
+    fn test_interleave3_round_trip() {
+        for n in [1usize, 5, 16, 17, 48, 67] {
+            let planes: [Vec<u8>; 3] =
+                core::array::from_fn(|k| (0..n).map(|i| (i * 3 + k) as u8).collect());
+            let mut rgb = vec![0u8; 3 * n];
+            let mut back: [Vec<u8>; 3] = core::array::from_fn(|_| vec![0u8; n]);
+            unsafe {
+                super::interleave3_u8(
+                    planes[0].as_ptr(),
+                    planes[1].as_ptr(),
+                    planes[2].as_ptr(),
+                    n,
+                    rgb.as_mut_ptr(),
+                );
+                let [a, b, c] = &mut back;
+                super::deinterleave3_u8(
+                    rgb.as_ptr(),
+                    n,
+                    a.as_mut_ptr(),
+                    b.as_mut_ptr(),
+                    c.as_mut_ptr(),
+                );
+            }
+            let want: Vec<u8> = (0..3 * n).map(|i| i as u8).collect();
+            assert_eq!(rgb, want, "n={}", n);
+            assert_eq!(back, planes, "n={}", n);
+        }
+
+        let src: Vec<f32> = (0..3 * 37).map(|i| i as f32).collect();
+        let mut out: [Vec<f32>; 3] = core::array::from_fn(|_| vec![0f32; 37]);
+        let [a, b, c] = &mut out;
+        unsafe {
+            super::deinterleave3_f32(
+                src.as_ptr(),
+                37,
+                a.as_mut_ptr(),
+                b.as_mut_ptr(),
+                c.as_mut_ptr(),
+            )
+        };
+        for (k, plane) in out.iter().enumerate() {
+            assert!(plane.iter().enumerate().all(|(i, &x)| x == src[3 * i + k]));
+        }
+    }
+
+    #[test]
+    fn test_interleave4_round_trip() {
+        for n in [1usize, 7, 16, 33, 64, 70] {
+            let planes: [Vec<u16>; 4] =
+                core::array::from_fn(|k| (0..n).map(|i| (i * 4 + k) as u16).collect());
+            let mut rgba = vec![0u16; 4 * n];
+            let mut back: [Vec<u16>; 4] = core::array::from_fn(|_| vec![0u16; n]);
+            unsafe {
+                let [a, b, c, d] = &planes;
+                super::interleave4_u16(
+                    a.as_ptr(),
+                    b.as_ptr(),
+                    c.as_ptr(),
+                    d.as_ptr(),
+                    n,
+                    rgba.as_mut_ptr(),
+                );
+                let [a, b, c, d] = &mut back;
+                super::deinterleave4_u16(
+                    rgba.as_ptr(),
+                    n,
+                    a.as_mut_ptr(),
+                    b.as_mut_ptr(),
+                    c.as_mut_ptr(),
+                    d.as_mut_ptr(),
+                );
+            }
+            let want: Vec<u16> = (0..4 * n).map(|i| i as u16).collect();
+            assert_eq!(rgba, want, "n={}", n);
+            assert_eq!(back, planes, "n={}", n);
+        }
+    }
+}
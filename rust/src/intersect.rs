@@ -0,0 +1,199 @@
+//! Intersection of sorted `u32` sets (posting lists).
+//!
+//! Lists of similar length are merged a block of eight against a block of
+//! eight: every element of the `a` block is compared with each `b` element
+//! broadcast across a vector, matches are emitted in order, and whichever
+//! block ends lower (or both) advances.  When one list is much shorter, each
+//! of its elements is instead located in the longer one by galloping
+//! (exponential then binary search), touching O(m log n) elements.
+use core::simd::Simd;
+use core::simd::prelude::SimdPartialEq;
+
+const SET_LANES: usize = 8;
+/// Length ratio from which the short list is galloped through the long one.
+const GALLOP_RATIO: usize = 32;
+
+/// Sink for intersection elements: stores the first `cap`, counts them all.
+struct Out<'a> {
+    buf: &'a mut [u32],
+    n: usize,
+}
+
+impl Out<'_> {
+    #[inline(always)]
+    fn push(&mut self, v: u32) {
+        if let Some(slot) = self.buf.get_mut(self.n) {
+            *slot = v;
+        }
+        self.n += 1;
+    }
+}
+
+/// Scalar merge of the remainders.
+fn merge_tail(a: &[u32], b: &[u32], out: &mut Out) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            core::cmp::Ordering::Less => i += 1,
+            core::cmp::Ordering::Greater => j += 1,
+            core::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+fn intersect_blocks(a: &[u32], b: &[u32], out: &mut Out) {
+    let (mut i, mut j) = (0, 0);
+    while i + SET_LANES <= a.len() && j + SET_LANES <= b.len() {
+        let av = Simd::<u32, SET_LANES>::from_slice(&a[i..]);
+        let bb = &b[j..j + SET_LANES];
+        let mut hit = av.simd_eq(Simd::splat(bb[0]));
+        for &x in &bb[1..] {
+            hit |= av.simd_eq(Simd::splat(x));
+        }
+        let mut bits = hit.to_bitmask();
+        while bits != 0 {
+            out.push(a[i + bits.trailing_zeros() as usize]);
+            bits &= bits - 1;
+        }
+        let (amax, bmax) = (a[i + SET_LANES - 1], bb[SET_LANES - 1]);
+        if amax <= bmax {
+            i += SET_LANES;
+        }
+        if bmax <= amax {
+            j += SET_LANES;
+        }
+    }
+    merge_tail(&a[i..], &b[j..], out);
+}
+
+/// Intersect the short list `small` with `large` by galloping.
+fn intersect_gallop(small: &[u32], large: &[u32], out: &mut Out) {
+    let mut lo = 0;
+    for &x in small {
+        // Find a bracket `large[lo + step / 2..lo + step]` holding `x`.
+        let mut step = 1;
+        while lo + step <= large.len() && large[lo + step - 1] < x {
+            step *= 2;
+        }
+        let hi = (lo + step).min(large.len());
+        lo += step / 2;
+        lo += large[lo..hi].partition_point(|&v| v < x);
+        if lo == large.len() {
+            return;
+        }
+        if large[lo] == x {
+            out.push(x);
+            lo += 1;
+        }
+    }
+}
+
+/// Intersect the sorted sets `a` (`alen` values) and `b` (`blen` values),
+/// writing the common values in ascending order to `out`.  Returns the size
+/// of the intersection; only the first `cap` values are written, so a
+/// result above `cap` means `out` was too small (`cap = 0` just counts).
+///
+/// Both inputs must be strictly increasing; with duplicates the result is
+/// unspecified (but memory-safe).
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `alen` and `blen` values, and `out`
+/// null (with `cap == 0`) or valid for `cap` values.
+#[unsafe(export_name = c_symbol!("intersect_u32"))]
+pub unsafe extern "C" fn intersect_u32(
+    a: *const u32,
+    alen: usize,
+    b: *const u32,
+    blen: usize,
+    out: *mut u32,
+    cap: usize,
+) -> usize {
+    if a.is_null() || b.is_null() || alen == 0 || blen == 0 {
+        return 0;
+    }
+    let a = core::slice::from_raw_parts(a, alen);
+    let b = core::slice::from_raw_parts(b, blen);
+    let buf: &mut [u32] = if out.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(out, cap)
+    };
+    let mut out = Out { buf, n: 0 };
+    let (small, large) = if alen <= blen { (a, b) } else { (b, a) };
+    if small.len() * GALLOP_RATIO < large.len() {
+        intersect_gallop(small, large, &mut out);
+    } else {
+        intersect_blocks(a, b, &mut out);
+    }
+    out.n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(a: &[u32], b: &[u32], cap: usize) -> (usize, Vec<u32>) {
+        let mut out = vec![0u32; cap];
+        let n = unsafe {
+            intersect_u32(
+                a.as_ptr(),
+                a.len(),
+                b.as_ptr(),
+                b.len(),
+                out.as_mut_ptr(),
+                cap,
+            )
+        };
+        out.truncate(n.min(cap));
+        (n, out)
+    }
+
+    #[test]
+    fn test_intersect_u32() {
+        let evens: Vec<u32> = (0..2000).map(|i| i * 2).collect();
+        let threes: Vec<u32> = (0..1500).map(|i| i * 3 + 3).collect();
+        let sparse: Vec<u32> = [1u32, 6, 7, 600, 601, 2400, 3998, 3999, 9000].to_vec();
+        for (a, b) in [
+            (&evens, &threes),
+            (&threes, &evens),
+            (&evens, &sparse),
+            (&sparse, &evens),
+        ] {
+            let want: Vec<u32> = a
+                .iter()
+                .copied()
+                .filter(|x| b.binary_search(x).is_ok())
+                .collect();
+            for alen in [0, 1, 7, 8, 9, 100, a.len()].map(|n| n.min(a.len())) {
+                let want: Vec<u32> = want
+                    .iter()
+                    .copied()
+                    .filter(|x| a[..alen].contains(x))
+                    .collect();
+                let (n, got) = run(&a[..alen], b, want.len() + 5);
+                assert_eq!(n, want.len(), "alen={alen}");
+                assert_eq!(got, want, "alen={alen}");
+            }
+        }
+        // Too small an output still reports the full size.
+        let (n, got) = run(&evens, &threes, 3);
+        assert_eq!((n, got), (666, vec![6, 12, 18]));
+        assert_eq!(
+            unsafe {
+                intersect_u32(
+                    evens.as_ptr(),
+                    2000,
+                    threes.as_ptr(),
+                    1500,
+                    core::ptr::null_mut(),
+                    0,
+                )
+            },
+            666
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Scatter/gather (`iovec`) variants of byte kernels: one FFI call walks a
+//! whole chain of `{ptr, len}` segments, so callers with buffers split into
+//! small pages pay the call overhead once rather than per segment.
+use crate::{crc32c_update, is_ascii_impl, sum_u8_impl};
+
+/// Lanes for the kernels the `_iov` exports run per segment.
+const IOV_LANES: usize = 32;
+
+/// One input segment, laid out as POSIX `struct iovec`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Iovec {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+/// Non-empty segments of the `n` entries at `iov` (none if `iov` is null).
+///
+/// # Safety
+/// `iov` must be null or valid for `n` entries, each null or valid for `len`
+/// bytes.
+pub(crate) unsafe fn segments<'a>(iov: *const Iovec, n: usize) -> impl Iterator<Item = &'a [u8]> {
+    let iov: &[Iovec] = if iov.is_null() || n == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(iov, n)
+    };
+    iov.iter()
+        .filter(|v| !v.ptr.is_null() && v.len > 0)
+        .map(|v| unsafe { core::slice::from_raw_parts(v.ptr, v.len) })
+}
+
+/// Sum the bytes of every segment, modulo 2^32 (as `sum_u8_32` over the
+/// concatenation).
+///
+/// # Safety
+/// `iov` must be null or valid for `n` entries, each null or valid for `len`
+/// bytes.
+#[unsafe(export_name = c_symbol!("sum_u8_iov"))]
+pub unsafe extern "C" fn sum_u8_iov(iov: *const Iovec, n: usize) -> u32 {
+    segments(iov, n).fold(0u32, |acc, s| acc.wrapping_add(sum_u8_impl::<IOV_LANES>(s)))
+}
+
+/// Update a finalised CRC32C with every segment in order (as
+/// `crc32_update_64` over the concatenation).
+///
+/// # Safety
+/// `iov` must be null or valid for `n` entries, each null or valid for `len`
+/// bytes.
+#[unsafe(export_name = c_symbol!("crc32_update_iov"))]
+pub unsafe extern "C" fn crc32_update_iov(iov: *const Iovec, n: usize, init: u32) -> u32 {
+    segments(iov, n).fold(init, crc32c_update)
+}
+
+/// Return 1 if every byte of every segment is ASCII (< 0x80), 0 otherwise.
+/// Stops at the first segment that fails.
+///
+/// # Safety
+/// `iov` must be null or valid for `n` entries, each null or valid for `len`
+/// bytes.
+#[unsafe(export_name = c_symbol!("is_ascii_iov"))]
+pub unsafe extern "C" fn is_ascii_iov(iov: *const Iovec, n: usize) -> u8 {
+    segments(iov, n).all(|s| is_ascii_impl::<IOV_LANES>(s)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iovecs(segs: &[&[u8]]) -> Vec<Iovec> {
+        segs.iter()
+            .map(|s| Iovec {
+                ptr: s.as_ptr(),
+                len: s.len(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_iov_matches_concatenation() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 128) as u8).collect();
+        let mut segs: Vec<&[u8]> = data.chunks(4096).collect();
+        segs.insert(1, &[]);
+        let mut iov = iovecs(&segs);
+        iov.push(Iovec {
+            ptr: core::ptr::null(),
+            len: 5,
+        });
+        unsafe {
+            let whole_sum = data.iter().map(|&b| b as u32).sum::<u32>();
+            assert_eq!(sum_u8_iov(iov.as_ptr(), iov.len()), whole_sum);
+            let whole_crc = crate::crc32c_update(0x1234, &data);
+            assert_eq!(crc32_update_iov(iov.as_ptr(), iov.len(), 0x1234), whole_crc);
+            assert_eq!(is_ascii_iov(iov.as_ptr(), iov.len()), 1);
+
+            let high = [b'a', 0x80];
+            let iov = iovecs(&[&data[..100], &high, &data[100..200]]);
+            assert_eq!(is_ascii_iov(iov.as_ptr(), iov.len()), 0);
+
+            assert_eq!(sum_u8_iov(core::ptr::null(), 3), 0);
+            assert_eq!(crc32_update_iov(core::ptr::null(), 0, 7), 7);
+            assert_eq!(is_ascii_iov(core::ptr::null(), 0), 1);
+        }
+    }
+}
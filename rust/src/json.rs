@@ -0,0 +1,479 @@
+//! JSON kernels: simdjson-style stage-1 structural indexing and string
+//! escaping.
+use core::simd::Simd;
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd};
+
+use crate::csv::prefix_xor;
+use crate::error::{SIMBA_ERR_CAPACITY, SIMBA_ERR_INVALID_INPUT, fail};
+use crate::search::index_of_u8_impl;
+
+/// Bytes per block; every classification is a u64 bitmask over one block.
+const BLOCK: usize = 64;
+const ODD_BITS: u64 = 0xAAAA_AAAA_AAAA_AAAA;
+
+// === Stage 1: structural indexing ============================================
+
+/// State carried from one block to the next.
+#[derive(Default)]
+struct Stage1 {
+    /// Bit 0: the first byte of the next block is escaped by a backslash.
+    next_is_escaped: u64,
+    /// All-ones when the next block starts inside a string.
+    in_string: u64,
+    /// Bit 0: the previous block ended with a non-quote scalar byte.
+    prev_scalar: u64,
+}
+
+impl Stage1 {
+    /// Bytes escaped by a preceding backslash: the odd-length tail of each
+    /// backslash run escapes the byte after it (simdjson's escape scanner).
+    #[inline(always)]
+    fn escaped(&mut self, backslash: u64) -> u64 {
+        if backslash == 0 {
+            return core::mem::take(&mut self.next_is_escaped);
+        }
+        let potential = backslash & !self.next_is_escaped;
+        let maybe_escaped = potential << 1;
+        let code = ((maybe_escaped | ODD_BITS).wrapping_sub(potential)) ^ ODD_BITS;
+        let escaped = code ^ (backslash | self.next_is_escaped);
+        self.next_is_escaped = (code & backslash) >> 63;
+        escaped
+    }
+
+    /// Return the structural mask of one block: operators, string openings
+    /// and scalar starts, all outside strings.
+    #[inline(always)]
+    fn block(&mut self, block: &[u8; BLOCK]) -> u64 {
+        let v = Simd::<u8, BLOCK>::from_array(*block);
+        let eq = |c: u8| v.simd_eq(Simd::splat(c)).to_bitmask();
+        let escaped = self.escaped(eq(b'\\'));
+        let quote = eq(b'"') & !escaped;
+        let in_string = prefix_xor(quote) ^ self.in_string;
+        self.in_string = ((in_string as i64) >> 63) as u64;
+        // String contents plus closing quote, but not the opening quote.
+        let string_tail = in_string ^ quote;
+
+        let op = eq(b'{') | eq(b'}') | eq(b'[') | eq(b']') | eq(b':') | eq(b',');
+        let ws = eq(b' ') | eq(b'\t') | eq(b'\n') | eq(b'\r');
+        let scalar = !(op | ws);
+        let nonquote_scalar = scalar & !quote;
+        let follows_scalar = (nonquote_scalar << 1) | self.prev_scalar;
+        self.prev_scalar = nonquote_scalar >> 63;
+        (op | (scalar & !follows_scalar)) & !string_tail
+    }
+}
+
+/// Find the structural positions of a JSON document — the operators
+/// `{ } [ ] : ,`, the opening quote of every string and the first byte of
+/// every other scalar (number, `true`, …) — honouring backslash escapes
+/// inside strings, and write them as u32 offsets in ascending order.
+///
+/// Returns the number of indices written, -1 if `cap` is too small, or -2 if
+/// the document ends inside a string.  This is stage 1 only: the grammar and
+/// string contents are not validated.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes (`len <= u32::MAX`); `out`
+/// must be valid for `cap` u32 writes.
+#[unsafe(export_name = c_symbol!("json_structural_indices"))]
+pub unsafe extern "C" fn json_structural_indices(
+    ptr: *const u8,
+    len: usize,
+    out: *mut u32,
+    cap: usize,
+) -> isize {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    if out.is_null() || u32::try_from(len).is_err() {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let out = core::slice::from_raw_parts_mut(out, cap);
+    let mut st = Stage1::default();
+    let mut n = 0;
+    for (b, chunk) in data.chunks(BLOCK).enumerate() {
+        // Whitespace padding never creates structurals or flips state.
+        let mut block = [b' '; BLOCK];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let mut bits = st.block(&block);
+        if n + bits.count_ones() as usize > out.len() {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("more than {} structural characters", out.len()),
+            );
+            return -1;
+        }
+        while bits != 0 {
+            out[n] = (b * BLOCK) as u32 + bits.trailing_zeros();
+            n += 1;
+            bits &= bits - 1;
+        }
+    }
+    if st.in_string != 0 {
+        fail(SIMBA_ERR_INVALID_INPUT, format_args!("unterminated string"));
+        return -2;
+    }
+    n as isize
+}
+
+// === String escaping =========================================================
+
+const ESCAPE_LANES: usize = 32;
+
+/// Length of the leading run of `src` that needs no escaping.
+#[inline(always)]
+fn clean_prefix(src: &[u8]) -> usize {
+    let mut chunks = src.chunks_exact(ESCAPE_LANES);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let v = Simd::<u8, ESCAPE_LANES>::from_slice(chunk);
+        let dirty = v.simd_lt(Simd::splat(0x20))
+            | v.simd_eq(Simd::splat(b'"'))
+            | v.simd_eq(Simd::splat(b'\\'));
+        if dirty.any() {
+            return i * ESCAPE_LANES + dirty.to_bitmask().trailing_zeros() as usize;
+        }
+    }
+    let base = src.len() - chunks.remainder().len();
+    base + chunks
+        .remainder()
+        .iter()
+        .position(|&b| b < 0x20 || b == b'"' || b == b'\\')
+        .unwrap_or(chunks.remainder().len())
+}
+
+/// Escape sequence for one byte that [`clean_prefix`] stopped at.
+#[inline(always)]
+fn escape_byte(b: u8, buf: &mut [u8; 6]) -> &[u8] {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let short = match b {
+        b'"' => b'"',
+        b'\\' => b'\\',
+        0x08 => b'b',
+        0x0C => b'f',
+        b'\n' => b'n',
+        b'\r' => b'r',
+        b'\t' => b't',
+        _ => {
+            *buf = [
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                HEX[(b >> 4) as usize],
+                HEX[(b & 15) as usize],
+            ];
+            return &buf[..];
+        }
+    };
+    buf[..2].copy_from_slice(&[b'\\', short]);
+    &buf[..2]
+}
+
+fn json_escape_impl(src: &[u8], dst: &mut [u8]) -> Option<usize> {
+    let (mut i, mut o) = (0, 0);
+    let mut buf = [0u8; 6];
+    loop {
+        let run = clean_prefix(&src[i..]);
+        dst.get_mut(o..o + run)?.copy_from_slice(&src[i..i + run]);
+        (i, o) = (i + run, o + run);
+        let Some(&b) = src.get(i) else {
+            return Some(o);
+        };
+        let esc = escape_byte(b, &mut buf);
+        dst.get_mut(o..o + esc.len())?.copy_from_slice(esc);
+        (i, o) = (i + 1, o + esc.len());
+    }
+}
+
+/// Escape `len` bytes for use inside a JSON string literal (without the
+/// surrounding quotes): `"` and `\` are backslash-escaped, control bytes
+/// become `\b \f \n \r \t` or `\u00XX`, everything else (including UTF-8) is
+/// copied verbatim.  Clean runs are found and copied with SIMD.
+///
+/// Returns the number of bytes written, or -1 if `dst_cap` is too small
+/// (`6 * len` always suffices).
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `dst_cap` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("json_escape"))]
+pub unsafe extern "C" fn json_escape(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    dst_cap: usize,
+) -> isize {
+    if src.is_null() || len == 0 {
+        return 0;
+    }
+    if dst.is_null() {
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, dst_cap);
+    json_escape_impl(src, dst).map_or(-1, |n| n as isize)
+}
+
+#[derive(Debug, PartialEq)]
+enum UnescapeError {
+    /// Output does not fit.
+    Capacity,
+    /// Malformed escape starting at this offset.
+    Invalid(usize),
+}
+
+#[inline(always)]
+fn hex4(s: &[u8]) -> Option<u32> {
+    let s = s.get(..4)?;
+    s.iter()
+        .try_fold(0, |acc, &c| Some(acc << 4 | (c as char).to_digit(16)?))
+}
+
+unsafe fn json_unescape_impl(src: &[u8], dst: &mut [u8]) -> Result<usize, UnescapeError> {
+    use UnescapeError::*;
+    let (mut i, mut o) = (0, 0);
+    loop {
+        let run = index_of_u8_impl::<ESCAPE_LANES>(&src[i..], b'\\').unwrap_or(src.len() - i);
+        dst.get_mut(o..o + run)
+            .ok_or(Capacity)?
+            .copy_from_slice(&src[i..i + run]);
+        (i, o) = (i + run, o + run);
+        if i == src.len() {
+            return Ok(o);
+        }
+        let at = i;
+        let simple = match src.get(i + 1) {
+            Some(b'"') => Some(b'"'),
+            Some(b'\\') => Some(b'\\'),
+            Some(b'/') => Some(b'/'),
+            Some(b'b') => Some(0x08),
+            Some(b'f') => Some(0x0C),
+            Some(b'n') => Some(b'\n'),
+            Some(b'r') => Some(b'\r'),
+            Some(b't') => Some(b'\t'),
+            Some(b'u') => None,
+            _ => return Err(Invalid(at)),
+        };
+        let mut utf8 = [0u8; 4];
+        let out: &[u8] = if let Some(c) = simple {
+            i += 2;
+            utf8[0] = c;
+            &utf8[..1]
+        } else {
+            let hi = hex4(&src[i + 2..]).ok_or(Invalid(at))?;
+            i += 6;
+            let mut cp = hi;
+            if (0xD800..0xDC00).contains(&hi) && src[i..].starts_with(b"\\u") {
+                // A high surrogate combines with an immediately following low
+                // surrogate; anything else leaves it unpaired.
+                let lo = hex4(&src[i + 2..]).ok_or(Invalid(i))?;
+                if (0xDC00..0xE000).contains(&lo) {
+                    cp = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                    i += 6;
+                }
+            }
+            // Unpaired surrogates decode to U+FFFD, as Go's encoding/json does.
+            let ch = char::from_u32(cp).unwrap_or(char::REPLACEMENT_CHARACTER);
+            ch.encode_utf8(&mut utf8).as_bytes()
+        };
+        dst.get_mut(o..o + out.len())
+            .ok_or(Capacity)?
+            .copy_from_slice(out);
+        o += out.len();
+    }
+}
+
+/// Decode the escapes of a JSON string literal body (without the surrounding
+/// quotes): `\" \\ \/ \b \f \n \r \t` and `\uXXXX`, combining surrogate pairs
+/// into UTF-8 and replacing unpaired surrogates with U+FFFD.  Other bytes are
+/// copied verbatim; runs without a backslash are skipped with SIMD.
+///
+/// Returns the number of bytes written, -1 if `dst_cap` is too small (`len`
+/// always suffices), or `-(offset + 2)` where `offset` is the position of the
+/// backslash starting a malformed escape.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes; `dst` must be valid for
+/// `dst_cap` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("json_unescape"))]
+pub unsafe extern "C" fn json_unescape(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    dst_cap: usize,
+) -> isize {
+    if src.is_null() || len == 0 {
+        return 0;
+    }
+    if dst.is_null() {
+        return -1;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, dst_cap);
+    match json_unescape_impl(src, dst) {
+        Ok(n) => n as isize,
+        Err(UnescapeError::Capacity) => {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("unescaped output exceeds {dst_cap} bytes"),
+            );
+            -1
+        }
+        Err(UnescapeError::Invalid(at)) => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("malformed escape at offset {at}"),
+            );
+            -(at as isize) - 2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Byte-at-a-time reference for the same definition of "structural".
+    fn reference(data: &[u8]) -> Option<Vec<u32>> {
+        let mut out = Vec::new();
+        let (mut in_str, mut esc, mut prev_scalar) = (false, false, false);
+        for (i, &c) in data.iter().enumerate() {
+            let escaped = core::mem::take(&mut esc);
+            if c == b'\\' && !escaped {
+                esc = true;
+            }
+            let quote = c == b'"' && !escaped;
+            let op = b"{}[]:,".contains(&c);
+            let ws = b" \t\n\r".contains(&c);
+            let start = op || (!ws && !prev_scalar);
+            if quote && in_str {
+                in_str = false;
+            } else if !in_str {
+                if start {
+                    out.push(i as u32);
+                }
+                in_str = quote;
+            }
+            prev_scalar = !op && !ws && !quote;
+        }
+        (!in_str).then_some(out)
+    }
+
+    fn index(data: &[u8]) -> Result<Vec<u32>, isize> {
+        let mut out = vec![0u32; data.len() + 1];
+        let n = unsafe {
+            json_structural_indices(data.as_ptr(), data.len(), out.as_mut_ptr(), out.len())
+        };
+        if n < 0 {
+            return Err(n);
+        }
+        out.truncate(n as usize);
+        Ok(out)
+    }
+
+    #[test]
+    fn test_json_structural_known() {
+        let doc = br#"{"a\"b": [1, true, "x,y"], "c\\": -2.5e3}"#;
+        let want: Vec<u32> = vec![0, 1, 7, 9, 10, 11, 13, 17, 19, 24, 25, 27, 32, 34, 40];
+        assert_eq!(index(doc), Ok(want));
+        assert_eq!(index(br#"{"open"#), Err(-2));
+        let mut small = [0u32; 2];
+        assert_eq!(
+            unsafe { json_structural_indices(doc.as_ptr(), doc.len(), small.as_mut_ptr(), 2) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_json_structural_matches_reference() {
+        let mut x = 0x2545_F491u32;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        // Backslash-heavy alphabet so escape runs cross block boundaries.
+        let alphabet = b"\\\\\\\"\"{}[]:, \tab1";
+        for len in [1usize, 63, 64, 65, 200, 1000] {
+            for _ in 0..50 {
+                let doc: Vec<u8> = (0..len)
+                    .map(|_| alphabet[next() as usize % alphabet.len()])
+                    .collect();
+                let want = reference(&doc).ok_or(-2);
+                assert_eq!(index(&doc), want, "{:?}", String::from_utf8_lossy(&doc));
+            }
+        }
+    }
+
+    fn escape(src: &[u8]) -> Vec<u8> {
+        let mut dst = vec![0u8; 6 * src.len()];
+        let n = unsafe { json_escape(src.as_ptr(), src.len(), dst.as_mut_ptr(), dst.len()) };
+        dst.truncate(n as usize);
+        dst
+    }
+
+    fn unescape(src: &[u8]) -> Result<Vec<u8>, isize> {
+        let mut dst = vec![0u8; src.len()];
+        let n = unsafe { json_unescape(src.as_ptr(), src.len(), dst.as_mut_ptr(), dst.len()) };
+        if n < 0 {
+            return Err(n);
+        }
+        dst.truncate(n as usize);
+        Ok(dst)
+    }
+
+    #[test]
+    fn test_json_escape() {
+        assert_eq!(escape(b"plain"), b"plain");
+        // DEL and non-ASCII pass through untouched.
+        let want = [&br#"say \"hi\"\\ \n\t\u0001"#[..], "\u{7f} é".as_bytes()].concat();
+        assert_eq!(escape("say \"hi\"\\ \n\t\u{1}\u{7f} é".as_bytes()), want);
+        // Escapes on both sides of SIMD block boundaries.
+        let long: Vec<u8> = (0..500u32)
+            .map(|i| {
+                if i % 37 == 0 {
+                    b'"'
+                } else {
+                    b'a' + (i % 26) as u8
+                }
+            })
+            .collect();
+        let want: Vec<u8> = long
+            .iter()
+            .flat_map(|&b| {
+                if b == b'"' {
+                    vec![b'\\', b'"']
+                } else {
+                    vec![b]
+                }
+            })
+            .collect();
+        assert_eq!(escape(&long), want);
+        assert_eq!(unescape(&escape(&long)), Ok(long.clone()));
+        let mut tiny = [0u8; 3];
+        assert_eq!(
+            unsafe { json_escape(b"\"\"".as_ptr(), 2, tiny.as_mut_ptr(), 3) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_json_unescape() {
+        assert_eq!(
+            unescape(br#"a\"b\\c\/d\b\f\n\r\t"#),
+            Ok(b"a\"b\\c/d\x08\x0c\n\r\t".to_vec())
+        );
+        assert_eq!(
+            unescape(br#"\u00e9\u20AC\ud83e\udd81"#),
+            Ok("é€🦁".as_bytes().to_vec())
+        );
+        assert_eq!(unescape(br#"\ud83ex"#), Ok("\u{FFFD}x".as_bytes().to_vec()));
+        assert_eq!(unescape(br#"ok\x"#), Err(-4));
+        assert_eq!(unescape(br#"\u12"#), Err(-2));
+        assert_eq!(unescape(br#"abc\"#), Err(-5));
+        let all: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        assert_eq!(unescape(&escape(&all)), Ok(all));
+    }
+}
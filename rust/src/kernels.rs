@@ -0,0 +1,197 @@
+//! Safe, slice-based Rust API over the exported kernels, for using the crate
+//! as an ordinary Rust dependency (including on `wasm32`, where the C exports
+//! below also surface as plain wasm exports of a `cdylib`).  Everything here
+//! is re-exported at the crate root.
+//!
+//! Each function runs exactly the kernel behind the corresponding C export,
+//! with the same runtime dispatch.
+
+/// Sum of the bytes modulo 2^32 (`sum_u8`).
+pub fn sum_u8(data: &[u8]) -> u32 {
+    unsafe { crate::auto::sum_u8(data.as_ptr(), data.len()) }
+}
+
+/// Whether every byte is ASCII (`is_ascii`).
+pub fn is_ascii(data: &[u8]) -> bool {
+    unsafe { crate::auto::is_ascii(data.as_ptr(), data.len()) != 0 }
+}
+
+/// Offset of the first non-ASCII byte, if any (`first_non_ascii`).
+pub fn first_non_ascii(data: &[u8]) -> Option<usize> {
+    match unsafe { crate::ascii::first_non_ascii(data.as_ptr(), data.len()) } {
+        -1 => None,
+        i => Some(i as usize),
+    }
+}
+
+/// Whether every byte has a non-zero entry in `table` (`validate_u8_lut32`).
+pub fn validate_u8_lut(data: &[u8], table: &[u8; 256]) -> bool {
+    unsafe { crate::validate_u8_lut32(data.as_ptr(), data.len(), table.as_ptr()) != 0 }
+}
+
+/// `Err(offset)` of the first byte with a zero entry in `table`, if any
+/// (`validate_u8_lut_pos32`).
+pub fn validate_u8_lut_pos(data: &[u8], table: &[u8; 256]) -> Result<(), usize> {
+    match unsafe { crate::validate_u8_lut_pos32(data.as_ptr(), data.len(), table.as_ptr()) } {
+        -1 => Ok(()),
+        i => Err(i as usize),
+    }
+}
+
+/// Map `src` through `table` into `dst` (`map_u8_lut`).
+///
+/// # Panics
+/// If `dst` is shorter than `src`.
+pub fn map_u8_lut(src: &[u8], dst: &mut [u8], table: &[u8; 256]) {
+    assert!(dst.len() >= src.len(), "dst shorter than src");
+    unsafe { crate::auto::map_u8_lut(src.as_ptr(), src.len(), dst.as_mut_ptr(), table.as_ptr()) }
+}
+
+/// Equality bitmasks of `data` against `needle`, 64 bytes per word
+/// (`eq_u8_masks`).  Returns the number of words written.
+///
+/// # Panics
+/// If `out` holds fewer than `data.len().div_ceil(64)` words.
+pub fn eq_masks(data: &[u8], needle: u8, out: &mut [u64]) -> usize {
+    assert!(out.len() >= data.len().div_ceil(64), "out too short");
+    unsafe { crate::auto::eq_u8_masks(data.as_ptr(), data.len(), needle, out.as_mut_ptr()) }
+}
+
+/// `Err(offset)` of the first invalid or truncated sequence, if any
+/// (`validate_utf8_32`).
+pub fn validate_utf8(data: &[u8]) -> Result<(), usize> {
+    match unsafe { crate::utf8::validate_utf8_32(data.as_ptr(), data.len()) } {
+        -1 => Ok(()),
+        i => Err(i as usize),
+    }
+}
+
+/// Update a finalised CRC32C (Castagnoli) with `data`.
+pub fn crc32c(crc: u32, data: &[u8]) -> u32 {
+    crate::crc32c_update(crc, data)
+}
+
+/// CRC32C of `A || B` from `crc32c(A)`, `crc32c(B)` and `len(B)`.
+pub fn crc32c_combine(crc1: u32, crc2: u32, len2: usize) -> u32 {
+    crate::crc32c_combine_go(crc1, crc2, len2)
+}
+
+/// Streaming CRC32C (Castagnoli) over data fed in pieces; also usable as a
+/// [`Hasher`](core::hash::Hasher), whose `finish` is the CRC.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Crc32c(u32);
+
+impl Crc32c {
+    /// CRC32C of the empty input.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Feed `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.0 = crc32c(self.0, data);
+    }
+
+    /// CRC32C of everything fed so far.
+    pub const fn finalize(&self) -> u32 {
+        self.0
+    }
+}
+
+impl core::hash::Hasher for Crc32c {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+/// Update a finalised CRC32 (IEEE) with `data`.
+pub fn crc32_ieee(crc: u32, data: &[u8]) -> u32 {
+    crate::crc::crc32_ieee_update(crc, data)
+}
+
+/// Update an Adler-32 checksum (start from 1) with `data`.
+pub fn adler32(adler: u32, data: &[u8]) -> u32 {
+    unsafe { crate::checksum::adler32_update(adler, data.as_ptr(), data.len()) }
+}
+
+/// xxHash64 of `data`.
+pub fn xxh64(data: &[u8], seed: u64) -> u64 {
+    unsafe { crate::hash::xxh64(data.as_ptr(), data.len(), seed) }
+}
+
+/// XXH3 64-bit hash of `data`.
+pub fn xxh3_64(data: &[u8], seed: u64) -> u64 {
+    unsafe { crate::hash::xxh3_64(data.as_ptr(), data.len(), seed) }
+}
+
+/// wyhash of `data`.
+pub fn wyhash(data: &[u8], seed: u64) -> u64 {
+    unsafe { crate::hash::wyhash(data.as_ptr(), data.len(), seed) }
+}
+
+/// SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    unsafe { crate::sha256::sha256(data.as_ptr(), data.len(), out.as_mut_ptr()) };
+    out
+}
+
+/// BLAKE3 digest of `data`.
+pub fn blake3(data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    unsafe { crate::blake3::blake3(data.as_ptr(), data.len(), out.as_mut_ptr(), 32) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_wrappers() {
+        let data = b"hello, world";
+        assert_eq!(sum_u8(data), data.iter().map(|&b| b as u32).sum());
+        assert!(is_ascii(data));
+        assert!(!is_ascii(&[0x80]));
+        assert_eq!(first_non_ascii(data), None);
+        assert_eq!(first_non_ascii("hé".as_bytes()), Some(1));
+        let mut table = [0u8; 256];
+        table[b'a' as usize] = 1;
+        assert!(validate_u8_lut(b"aaa", &table));
+        assert!(!validate_u8_lut(b"ab", &table));
+        assert_eq!(validate_u8_lut_pos(b"aaa", &table), Ok(()));
+        assert_eq!(validate_u8_lut_pos(b"aab", &table), Err(2));
+        let upper: [u8; 256] = core::array::from_fn(|b| (b as u8).to_ascii_uppercase());
+        let mut dst = [0u8; 12];
+        map_u8_lut(data, &mut dst, &upper);
+        assert_eq!(&dst, b"HELLO, WORLD");
+        let mut masks = [0u64; 1];
+        assert_eq!(eq_masks(data, b'l', &mut masks), 1);
+        assert_eq!(masks[0], 0b0100_0000_1100);
+        assert_eq!(validate_utf8("héllo".as_bytes()), Ok(()));
+        assert_eq!(validate_utf8(b"ab\xFF"), Err(2));
+        assert_eq!(crc32c(0, b"hello"), 0x9A71_BB4C);
+        assert_eq!(
+            crc32c_combine(crc32c(0, b"hel"), crc32c(0, b"lo"), 2),
+            0x9A71_BB4C
+        );
+        let mut crc = Crc32c::new();
+        crc.update(b"hel");
+        core::hash::Hasher::write(&mut crc, b"lo");
+        assert_eq!(crc.finalize(), 0x9A71_BB4C);
+        assert_eq!(core::hash::Hasher::finish(&crc), 0x9A71_BB4C);
+        assert_eq!(crc32_ieee(0, b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(1, b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh3_64(b"", 0), 0x2D06_8005_38D3_94C2);
+        assert_eq!(wyhash(b"", 0), unsafe {
+            crate::hash::wyhash(core::ptr::null(), 0, 0)
+        });
+        assert_eq!(sha256(b"abc")[..4], [0xBA, 0x78, 0x16, 0xBF]);
+        assert_eq!(blake3(b"")[..4], [0xAF, 0x13, 0x49, 0xB9]);
+    }
+}
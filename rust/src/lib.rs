@@ -1,26 +1,155 @@
 //! Rust SIMD kernels for Simba FFI layer
+//!
+//! The kernels are exported as `extern "C"` symbols for the Go trampolines
+//! and C callers.  Rust callers use the safe slice API re-exported here from
+//! [`kernels`] (`simba::sum_u8(&[u8])`, [`Crc32c`], [`eq_masks`], ...)
+//! instead of the raw-pointer exports.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![feature(portable_simd)]
 #![allow(unsafe_op_in_unsafe_fn)] // calls to unsafe APIs are audited and wrapped inside unsafe fns
-use core::simd::prelude::{SimdPartialEq, SimdUint};
-use core::simd::{LaneCount, Simd, SupportedLaneCount};
-use crc32c::{crc32c_append, crc32c_combine};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Mask, Simd, SimdElement, SupportedLaneCount};
+
+/// Linked name of an export: `name` behind the symbol prefix chosen at build
+/// time with `SIMBA_SYMBOL_PREFIX` (empty by default; see build.rs).
+macro_rules! c_symbol {
+    ($name:expr) => {
+        concat!(env!("SIMBA_SYMBOL_PREFIX"), $name)
+    };
+}
+
+/// Runtime x86 CPU feature check.  Without `std` there is no runtime
+/// detection, so only features enabled at build time count.
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+macro_rules! x86_feature {
+    ($f:tt) => {
+        std::arch::is_x86_feature_detected!($f)
+    };
+}
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+macro_rules! x86_feature {
+    ($f:tt) => {
+        cfg!(target_feature = $f)
+    };
+}
+
+/// aarch64 counterpart of `x86_feature!`.
+#[cfg(all(target_arch = "aarch64", feature = "std"))]
+macro_rules! aarch64_feature {
+    ($f:tt) => {
+        std::arch::is_aarch64_feature_detected!($f)
+    };
+}
+#[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+macro_rules! aarch64_feature {
+    ($f:tt) => {
+        cfg!(target_feature = $f)
+    };
+}
+
+mod abi;
+mod ascii;
+mod audio;
+mod auto;
+#[cfg(target_arch = "x86_64")]
+mod avx512;
+mod base32;
+mod base58;
+mod base64;
+mod bitops;
+mod blake3;
+mod bloom;
+mod chacha;
+mod checksum;
+mod cpu;
+mod crc;
+mod csv;
+mod delta;
+mod distance;
+mod error;
+mod format;
+mod fuzzy;
+mod gather;
+mod gf;
+mod gorilla;
+mod half;
+mod hash;
+#[cfg(feature = "alloc")]
+mod hasher;
+mod hex;
+mod image;
+mod inet;
+mod interleave;
+mod intersect;
+mod iov;
+mod json;
+pub mod kernels;
+pub use kernels::{
+    Crc32c, adler32, blake3, crc32_ieee, crc32c, crc32c_combine, eq_masks, first_non_ascii,
+    is_ascii, map_u8_lut, sha256, sum_u8, validate_u8_lut, validate_u8_lut_pos, validate_utf8,
+    wyhash, xxh3_64, xxh64,
+};
+mod lut;
+mod numeric;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod parse;
+mod prefetch;
+mod search;
+mod selftest;
+mod sha256;
+mod sort;
+mod stats;
+mod stream;
+#[cfg(feature = "alloc")]
+mod teddy;
+mod transpose;
+mod url;
+mod utf8;
+#[cfg(feature = "alloc")]
+mod validator;
+mod varint;
+
+// === Raw-pointer vector I/O ==================================================
+
+// Kernels that allow `dst == src` must not materialise a `&[T]` and a
+// `&mut [T]` over the same memory.  These helpers load/store one vector
+// straight through raw pointers instead.
+
+/// Unaligned load of `L` elements starting at `p`.
+#[inline(always)]
+pub(crate) unsafe fn load_simd<T: SimdElement, const L: usize>(p: *const T) -> Simd<T, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    Simd::from_array(core::ptr::read_unaligned(p as *const [T; L]))
+}
+
+/// Unaligned store of `v` to the `L` elements starting at `p`.
+#[inline(always)]
+pub(crate) unsafe fn store_simd<T: SimdElement, const L: usize>(p: *mut T, v: Simd<T, L>)
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    core::ptr::write_unaligned(p as *mut [T; L], v.to_array())
+}
 
 // === CRC32C (Castagnoli) update & combine ====================================
 
 // Go's hash/crc32 package expects CRCs to be in *finalised* form—i.e. the
 // algorithm complements the accumulator before and after processing a buffer.
-// The `crc32c_append` function, on the other hand, operates on the *raw*
-// (un-finalised) value so that callers can chain updates cheaply.  Therefore we
-// need to mirror Go’s semantics by XOR-ing with 0xFFFF_FFFF around the call.
+// The kernels in `crc` take and return finalised values, so both the update
+// and combine paths forward Go's values unmodified.
 fn crc32c_update(init_finalised: u32, data: &[u8]) -> u32 {
-    crc32c_append(init_finalised, data)
+    crc::crc32c_update(init_finalised, data)
 }
 
 #[inline(always)]
 fn crc32c_combine_go(crc1_final: u32, crc2_final: u32, len2: usize) -> u32 {
-    // `crc32c_combine` operates directly on *finalised* CRC digests, matching Go’s
-    // semantics, so we can forward the values unmodified.
-    crc32c_combine(crc1_final, crc2_final, len2)
+    crc::crc32c_combine(crc1_final, crc2_final, len2)
 }
 
 macro_rules! export_crc32_update {
@@ -28,7 +157,7 @@ macro_rules! export_crc32_update {
         #[doc = "Update CRC32C (Castagnoli) with additional bytes.\n\n\
                 # Safety\n\
                 `ptr` must be null or valid for `len` bytes."]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, init: u32) -> u32 {
             if ptr.is_null() || len == 0 {
                 return init;
@@ -48,36 +177,59 @@ export_crc32_update!(crc32_update_32);
 export_crc32_update!(crc32_update_64);
 
 /// Combine two finalised CRC32C digests (Castagnoli) as per Go's semantics.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn crc32_combine_raw(crc1: u32, crc2: u32, len2: usize) -> u32 {
+#[unsafe(export_name = c_symbol!("crc32_combine_raw"))]
+pub extern "C" fn crc32_combine_raw(crc1: u32, crc2: u32, len2: usize) -> u32 {
     crc32c_combine_go(crc1, crc2, len2)
 }
 
 // Optional alias without `_raw`.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn crc32_combine(crc1: u32, crc2: u32, len2: usize) -> u32 {
+#[unsafe(export_name = c_symbol!("crc32_combine"))]
+pub extern "C" fn crc32_combine(crc1: u32, crc2: u32, len2: usize) -> u32 {
     crc32c_combine_go(crc1, crc2, len2)
 }
 
+/// Combine `n` finalised CRC32C digests of consecutive chunks into the digest
+/// of their concatenation: `crcs[i]` covers `lens[i]` bytes.  Equivalent to
+/// folding [`crc32_combine_raw`] over the array in one call.  Returns 0 (the
+/// CRC of no data) when `n` is 0.
+///
+/// # Safety
+/// `crcs` and `lens` must be null or valid for `n` reads each.
+#[unsafe(export_name = c_symbol!("crc32_combine_many"))]
+pub unsafe extern "C" fn crc32_combine_many(crcs: *const u32, lens: *const usize, n: usize) -> u32 {
+    if crcs.is_null() || lens.is_null() || n == 0 {
+        return 0;
+    }
+    let crcs = core::slice::from_raw_parts(crcs, n);
+    let lens = core::slice::from_raw_parts(lens, n);
+    crc::crc32c_combine_many(crcs, lens)
+}
+
 // === Portable SIMD byte-sum ===================================================
 
 // ---- Generic helpers --------------------------------------------------------
 
+/// Zero-padded load of a head or tail shorter than `L` bytes.
+#[inline(always)]
+fn load_partial<const L: usize>(part: &[u8]) -> Simd<u8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    Simd::load_or_default(part)
+}
+
 #[inline(always)]
 unsafe fn sum_u8_impl<const LANES_N: usize>(data: &[u8]) -> u32
 where
     LaneCount<LANES_N>: SupportedLaneCount,
 {
-    let mut total: u64 = 0;
-
-    let mut chunks = data.chunks_exact(LANES_N);
-    for chunk in &mut chunks {
-        let v = Simd::<u8, LANES_N>::from_slice(chunk);
-        let v32: Simd<u32, LANES_N> = v.cast();
-        total += v32.reduce_sum() as u64;
-    }
-    for &b in chunks.remainder() {
-        total += b as u64;
+    // Peel the unaligned head so the main loop only loads aligned vectors;
+    // head and tail are zero-padded, which leaves the sum unchanged.
+    let (head, body, tail) = data.as_simd::<LANES_N>();
+    let part = |p: &[u8]| load_partial::<LANES_N>(p).cast::<u32>().reduce_sum() as u64;
+    let mut total = part(head) + part(tail);
+    for v in body {
+        total += v.cast::<u32>().reduce_sum() as u64;
     }
     (total & 0xFFFF_FFFF) as u32
 }
@@ -94,12 +246,16 @@ macro_rules! export_sum_u8 {
             "• `ptr` must be either null or valid for `len` bytes.\n",
             "• The buffer must not be mutated for the duration of the call."
         )]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(ptr: *const u8, len: usize) -> u32 {
             if ptr.is_null() || len == 0 {
                 return 0;
             }
             let data = core::slice::from_raw_parts(ptr, len);
+            #[cfg(target_arch = "x86_64")]
+            if $lanes == 64 && avx512::available() {
+                return avx512::sum_u8(data);
+            }
             sum_u8_impl::<$lanes>(data)
         }
     };
@@ -116,15 +272,15 @@ unsafe fn is_ascii_impl<const N: usize>(data: &[u8]) -> bool
 where
     LaneCount<N>: SupportedLaneCount,
 {
-    let mut chunks = data.chunks_exact(N);
-    for chunk in &mut chunks {
-        let v = Simd::<u8, N>::from_slice(chunk);
-        if v.reduce_max() >= 0x80 {
-            return false;
-        }
+    let (head, body, tail) = data.as_simd::<N>();
+    let high = Simd::splat(0x80);
+    if (load_partial::<N>(head) | load_partial::<N>(tail))
+        .simd_ge(high)
+        .any()
+    {
+        return false;
     }
-
-    chunks.remainder().iter().all(|&b| b < 0x80)
+    !body.iter().any(|v| v.simd_ge(high).any())
 }
 
 /* ─── is_ascii public exports via macro ─────────────────────────────────── */
@@ -135,12 +291,16 @@ macro_rules! export_is_ascii {
             "# Safety\n",
             "Same as other FFI helpers: `ptr` must be null or valid for `len` bytes."
         )]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(ptr: *const u8, len: usize) -> u8 {
             if ptr.is_null() || len == 0 {
                 return 1;
             }
             let data = core::slice::from_raw_parts(ptr, len);
+            #[cfg(target_arch = "x86_64")]
+            if $lanes == 64 && avx512::available() {
+                return avx512::is_ascii(data) as u8;
+            }
             is_ascii_impl::<$lanes>(data) as u8
         }
     };
@@ -151,26 +311,185 @@ export_is_ascii!(is_ascii64, 64);
 
 // === Generic byte-set validator ============================================
 
+/// A 256-entry byte-class table compiled for nibble-shuffle lookup (the
+/// pshufb/tbl technique): byte `b` is a member when
+/// `hi[k][b >> 4] & lo[k][b & 15]` is non-zero for `k` 0 or 1.  Each distinct
+/// non-empty row of the table (the set of low nibbles accepted under one high
+/// nibble) gets one of the 16 class bits, so every table compiles exactly.
+/// Build one with `nibble_lut_compile`.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct NibbleLut {
+    lo: [[u8; 16]; 2],
+    hi: [[u8; 16]; 2],
+}
+
+impl NibbleLut {
+    pub(crate) fn compile(table: &[u8]) -> Self {
+        let mut rows = [0u16; 16];
+        for (b, &t) in table[..256].iter().enumerate() {
+            rows[b >> 4] |= ((t != 0) as u16) << (b & 15);
+        }
+        let mut n = NibbleLut::default();
+        let mut classes = [0u16; 16];
+        let mut used = 0;
+        for (h, &row) in rows.iter().enumerate() {
+            if row == 0 {
+                continue;
+            }
+            let k = match classes[..used].iter().position(|&c| c == row) {
+                Some(k) => k,
+                None => {
+                    classes[used] = row;
+                    used += 1;
+                    used - 1
+                }
+            };
+            n.hi[k / 8][h] |= 1 << (k % 8);
+        }
+        for (k, &row) in classes[..used].iter().enumerate() {
+            for l in 0..16 {
+                if row >> l & 1 == 1 {
+                    n.lo[k / 8][l] |= 1 << (k % 8);
+                }
+            }
+        }
+        n
+    }
+
+    /// Per-lane membership test.  Tables with at most 8 distinct rows need
+    /// only one shuffle pair.
+    #[inline(always)]
+    pub(crate) fn classifier<const L: usize>(&self) -> impl Fn(Simd<u8, L>) -> Mask<i8, L>
+    where
+        LaneCount<L>: SupportedLaneCount,
+    {
+        let t = |t: &[u8; 16]| Simd::<u8, L>::from_array(core::array::from_fn(|i| t[i % 16]));
+        let (lo0, hi0, lo1, hi1) = (
+            t(&self.lo[0]),
+            t(&self.hi[0]),
+            t(&self.lo[1]),
+            t(&self.hi[1]),
+        );
+        let wide = self.hi[1] != [0; 16];
+        move |v| {
+            let (h, l) = (v >> Simd::splat(4), v & Simd::splat(15));
+            let mut hits = hi0.swizzle_dyn(h) & lo0.swizzle_dyn(l);
+            if wide {
+                hits |= hi1.swizzle_dyn(h) & lo1.swizzle_dyn(l);
+            }
+            hits.simd_ne(Simd::splat(0))
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn contains(&self, b: u8) -> bool {
+        let (h, l) = ((b >> 4) as usize, (b & 15) as usize);
+        (self.hi[0][h] & self.lo[0][l]) | (self.hi[1][h] & self.lo[1][l]) != 0
+    }
+}
+
+/// Index of the first byte that is not a member of `set`, if any.
 #[inline(always)]
-unsafe fn validate_u8_lut_impl<const L: usize>(data: &[u8], table: &[u8]) -> bool
+pub(crate) unsafe fn nibble_lut_pos_impl<const L: usize>(
+    data: &[u8],
+    set: &NibbleLut,
+) -> Option<usize>
 where
     LaneCount<L>: SupportedLaneCount,
 {
+    let member = set.classifier::<L>();
     let mut chunks = data.chunks_exact(L);
-    for chunk in &mut chunks {
-        let v = Simd::<u8, L>::from_slice(chunk);
-        let idx: Simd<usize, L> = v.cast();
-        let flags = Simd::<u8, L>::gather_or_default(table, idx);
-        if flags.reduce_min() == 0 {
-            return false;
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let ok = member(Simd::<u8, L>::from_slice(chunk));
+        if !ok.all() {
+            return Some(i * L + (!ok).to_bitmask().trailing_zeros() as usize);
         }
     }
-    for &b in chunks.remainder() {
-        if table[b as usize] == 0 {
-            return false;
-        }
+    let tail = data.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|&b| !set.contains(b))
+        .map(|i| tail + i)
+}
+
+#[inline(always)]
+unsafe fn validate_u8_lut_impl<const L: usize>(data: &[u8], table: &[u8]) -> bool
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    validate_u8_lut_pos_impl::<L>(data, table).is_none()
+}
+
+/// Index of the first byte whose `table` entry is zero, if any.  Inputs
+/// shorter than a vector are checked directly against `table`; longer ones
+/// compile it to nibble form first.
+#[inline(always)]
+pub(crate) unsafe fn validate_u8_lut_pos_impl<const L: usize>(
+    data: &[u8],
+    table: &[u8],
+) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    if data.len() < L {
+        return data.iter().position(|&b| table[b as usize] == 0);
     }
-    true
+    nibble_lut_pos_impl::<L>(data, &NibbleLut::compile(table))
+}
+
+/// Compile the 256-byte class table `lut` (non-zero entry = member) into the
+/// nibble form used by `validate_u8_nibble_lut*`, so a table reused across
+/// calls is prepared once.
+///
+/// # Safety
+/// `lut` must be valid for 256 bytes and `out` for one `NibbleLut` write.
+#[unsafe(export_name = c_symbol!("nibble_lut_compile"))]
+pub unsafe extern "C" fn nibble_lut_compile(lut: *const u8, out: *mut NibbleLut) {
+    if lut.is_null() || out.is_null() {
+        return;
+    }
+    *out = NibbleLut::compile(core::slice::from_raw_parts(lut, 256));
+}
+
+/// Like `validate_u8_lut64` with a table compiled by `nibble_lut_compile`.
+/// Returns 1 if every byte is a member, 0 otherwise.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `set` must point to a
+/// compiled `NibbleLut`.
+#[unsafe(export_name = c_symbol!("validate_u8_nibble_lut"))]
+pub unsafe extern "C" fn validate_u8_nibble_lut(
+    ptr: *const u8,
+    len: usize,
+    set: *const NibbleLut,
+) -> u8 {
+    if ptr.is_null() || len == 0 {
+        return 1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    nibble_lut_pos_impl::<64>(data, &*set).is_none() as u8
+}
+
+/// Like `validate_u8_lut_pos64` with a table compiled by
+/// `nibble_lut_compile`.  Returns the index of the first non-member byte, or
+/// -1 if every byte is a member.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `set` must point to a
+/// compiled `NibbleLut`.
+#[unsafe(export_name = c_symbol!("validate_u8_nibble_lut_pos"))]
+pub unsafe extern "C" fn validate_u8_nibble_lut_pos(
+    ptr: *const u8,
+    len: usize,
+    set: *const NibbleLut,
+) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    nibble_lut_pos_impl::<64>(data, &*set).map_or(-1, |i| i as isize)
 }
 
 /* ─── validate_u8_lut exports via macro ─────────────────────────────────── */
@@ -181,7 +500,7 @@ macro_rules! export_validate_u8_lut {
             "# Safety\n",
             "• `ptr`/`lut` must be valid for `len`/256 bytes respectively."
         )]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, lut: *const u8) -> u8 {
             if ptr.is_null() || len == 0 {
                 return 1;
@@ -196,10 +515,38 @@ export_validate_u8_lut!(validate_u8_lut16, 16);
 export_validate_u8_lut!(validate_u8_lut32, 32);
 export_validate_u8_lut!(validate_u8_lut64, 64);
 
+macro_rules! export_validate_u8_lut_pos {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Validate every byte against a 256-byte lookup table using a ", stringify!($lanes), "-lane SIMD kernel, like `validate_u8_lut", stringify!($lanes), "`. Returns the index of the first byte whose table entry is zero, or -1 if every byte is valid.\n\n",
+            "# Safety\n",
+            "`ptr`/`lut` must be valid for `len`/256 bytes respectively."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, lut: *const u8) -> isize {
+            if ptr.is_null() || len == 0 {
+                return -1;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let table = core::slice::from_raw_parts(lut, 256);
+            validate_u8_lut_pos_impl::<$lanes>(data, table).map_or(-1, |i| i as isize)
+        }
+    };
+}
+export_validate_u8_lut_pos!(validate_u8_lut_pos16, 16);
+export_validate_u8_lut_pos!(validate_u8_lut_pos32, 32);
+export_validate_u8_lut_pos!(validate_u8_lut_pos64, 64);
+
 // === Byte mapping via LUT ====================================================
 
+/// Map every byte of `src` through the 256-byte `table` into `dst`.  The
+/// table is split into 16 rows of 16 entries (one per high nibble); each
+/// vector is looked up in every row with a low-nibble shuffle and the row
+/// matching the high nibble is kept.  When `dst` starts inside the source
+/// range the buffer is walked backwards, so any overlap behaves like
+/// `memmove`.
 #[inline(always)]
-unsafe fn map_u8_lut_impl<const L: usize>(
+pub(crate) unsafe fn map_u8_lut_impl<const L: usize>(
     src: *const u8,
     len: usize,
     dst: *mut u8,
@@ -207,23 +554,37 @@ unsafe fn map_u8_lut_impl<const L: usize>(
 ) where
     LaneCount<L>: SupportedLaneCount,
 {
-    let src_slice = core::slice::from_raw_parts(src, len);
-    let dst_slice = core::slice::from_raw_parts_mut(dst, len);
     let map = core::slice::from_raw_parts(table, 256);
+    let rows: [Simd<u8, L>; 16] =
+        core::array::from_fn(|h| Simd::from_array(core::array::from_fn(|i| map[16 * h + i % 16])));
+    let map_vec = |i: usize| {
+        let v = load_simd::<u8, L>(src.add(i));
+        let (hi, lo) = (v >> Simd::splat(4), v & Simd::splat(15));
+        let mut out = rows[0].swizzle_dyn(lo);
+        for (h, row) in rows.iter().enumerate().skip(1) {
+            out = hi
+                .simd_eq(Simd::splat(h as u8))
+                .select(row.swizzle_dyn(lo), out);
+        }
+        store_simd(dst.add(i), out);
+    };
 
-    let mut chunks = src_slice.chunks_exact(L);
-    let mut out_chunks = dst_slice.chunks_exact_mut(L);
-    for (chunk, out) in (&mut chunks).zip(&mut out_chunks) {
-        let v = Simd::<u8, L>::from_slice(chunk);
-        let idx: Simd<usize, L> = v.cast();
-        let mapped = Simd::<u8, L>::gather_or_default(map, idx);
-        for i in 0..L {
-            out[i] = mapped[i];
+    let body = len - len % L;
+    let backward = (dst as usize) > (src as usize) && (dst as usize) < (src as usize) + len;
+    if backward {
+        for i in (body..len).rev() {
+            *dst.add(i) = map[*src.add(i) as usize];
+        }
+        for i in (0..body).step_by(L).rev() {
+            map_vec(i);
+        }
+    } else {
+        for i in (0..body).step_by(L) {
+            map_vec(i);
+        }
+        for i in body..len {
+            *dst.add(i) = map[*src.add(i) as usize];
         }
-    }
-    // tail
-    for (i, &b) in chunks.remainder().iter().enumerate() {
-        dst_slice[len - chunks.remainder().len() + i] = map[b as usize];
     }
 }
 
@@ -233,13 +594,17 @@ macro_rules! export_map_u8_lut {
         #[doc = concat!(
             "Map each source byte through a 256-byte translation table using a ", stringify!($lanes), "-lane SIMD kernel and write results to `dst`.\n\n",
             "# Safety\n",
-            "All pointers must be non-null and valid for `len` bytes. Buffers may overlap."
+            "`src` and `dst` must be valid for `len` bytes and `map` for 256. `src` and `dst` may overlap in any way (the result is as if `src` were copied first, like `memmove`); `map` must not overlap `dst`."
         )]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(src: *const u8, len: usize, dst: *mut u8, map: *const u8) {
             if len == 0 || src.is_null() || dst.is_null() || map.is_null() {
                 return;
             }
+            #[cfg(target_arch = "x86_64")]
+            if $lanes == 64 && avx512::available() {
+                return avx512::map_u8_lut(src, len, dst, map);
+            }
             map_u8_lut_impl::<$lanes>(src, len, dst, map);
         }
     };
@@ -250,12 +615,51 @@ export_map_u8_lut!(map_u8_lut64, 64);
 
 // === Byte equality mask =====================================================
 
+/// Call `emit(i, mask)` for each `L`-byte word of `data`, where bit `j` of
+/// `mask` is set when `data[i * L + j] == needle`; a final partial word has
+/// zero bits past the end.  The unaligned head is peeled so that full
+/// vectors are only loaded from aligned addresses, and the masks of each
+/// aligned vector are shifted back into place relative to `data`.
+#[inline(always)]
+pub(crate) fn eq_u8_words<const L: usize>(data: &[u8], needle: u8, mut emit: impl FnMut(usize, u64))
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let (head, body, tail) = data.as_simd::<L>();
+    let splat = Simd::splat(needle);
+    let partial = |p: &[u8]| {
+        let bits = load_partial::<L>(p).simd_eq(splat).to_bitmask();
+        bits & ((1u64 << p.len()) - 1)
+    };
+    let word = u128::MAX >> (128 - L);
+    let shift = head.len();
+    // Bits `shift..` of each aligned vector land in the next output word.
+    let mut acc = partial(head) as u128;
+    let mut i = 0;
+    for v in body {
+        acc |= (v.simd_eq(splat).to_bitmask() as u128) << shift;
+        emit(i, (acc & word) as u64);
+        acc >>= L;
+        i += 1;
+    }
+    acc |= (partial(tail) as u128) << shift;
+    let mut left = shift + tail.len();
+    while left > 0 {
+        emit(i, (acc & word) as u64);
+        acc >>= L;
+        left = left.saturating_sub(L);
+        i += 1;
+    }
+}
+
 #[inline(always)]
-unsafe fn eq_u8_masks_impl<const LANES: usize>(
+unsafe fn eq_u8_masks_impl<const LANES: usize, W>(
     src: *const u8,
     len: usize,
     needle: u8,
-    out: *mut u128, // storage large enough for any mask size, cast later
+    out: *mut W,
+    conv: impl Fn(u64) -> W,
+    tail: bool,
 ) -> usize
 where
     LaneCount<LANES>: SupportedLaneCount,
@@ -263,31 +667,32 @@ where
     if len == 0 {
         return 0;
     }
-    let chunks = len / LANES;
+    let words = if tail {
+        len.div_ceil(LANES)
+    } else {
+        len / LANES
+    };
     let src_slice = core::slice::from_raw_parts(src, len);
-    let out_slice = core::slice::from_raw_parts_mut(out as *mut u128, chunks);
-
-    for (i, chunk) in src_slice.chunks_exact(LANES).enumerate() {
-        if i == chunks {
-            break;
+    let out_slice = core::slice::from_raw_parts_mut(out, words);
+    // Without `tail` the final partial word is dropped.
+    eq_u8_words::<LANES>(src_slice, needle, |i, m| {
+        if let Some(o) = out_slice.get_mut(i) {
+            *o = conv(m);
         }
-        let v = Simd::<u8, LANES>::from_slice(chunk);
-        let mask = v.simd_eq(Simd::splat(needle));
-        out_slice[i] = mask.to_bitmask() as u128;
-    }
-    chunks
+    });
+    words
 }
 
 // Generic helper: generates a thin extern "C" wrapper that validates pointers
 // Export helper specific to eq_u8_masks kernels (16/32/64 lanes)
 macro_rules! export_eq_masks {
-    ($name:ident, $lanes:expr, $int:ty) => {
+    ($name:ident, $full:ident, $lanes:expr, $int:ty) => {
         #[doc = concat!(
-            "Generate equality bitmasks comparing each byte to `needle` across chunks of ", stringify!($lanes), " lanes. The resulting mask words are stored in `out`. Returns number of mask words written.\n\n",
+            "Generate equality bitmasks comparing each byte to `needle` across chunks of ", stringify!($lanes), " lanes. The resulting mask words are stored in `out`. Returns number of mask words written. A final partial chunk is ignored; see `", stringify!($full), "`.\n\n",
             "# Safety\n",
             "`src` and `out` must be valid for `len` and `len/", stringify!($lanes), "` elements respectively."
         )]
-        #[unsafe(no_mangle)]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
         pub unsafe extern "C" fn $name(
             src: *const u8,
             len: usize,
@@ -297,28 +702,120 @@ macro_rules! export_eq_masks {
             if src.is_null() || out.is_null() || len == 0 {
                 return 0;
             }
-            eq_u8_masks_impl::<$lanes>(src, len, needle, out as *mut u128)
+            #[cfg(target_arch = "x86_64")]
+            if $lanes == 64 && avx512::available() {
+                return avx512::eq_u8_masks(src, len, needle, out as *mut u64, false);
+            }
+            eq_u8_masks_impl::<$lanes, $int>(src, len, needle, out, |m| m as $int, false)
+        }
+
+        #[doc = concat!(
+            "Like `", stringify!($name), "`, but also emits a mask word for a final partial chunk, so `len.div_ceil(", stringify!($lanes), ")` words are written and returned. The last word has `len % ", stringify!($lanes), "` valid low bits (all ", stringify!($lanes), " if that is 0); the bits past the end of the buffer are zero.\n\n",
+            "# Safety\n",
+            "`src` and `out` must be valid for `len` and `len.div_ceil(", stringify!($lanes), ")` elements respectively."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($full)))]
+        pub unsafe extern "C" fn $full(
+            src: *const u8,
+            len: usize,
+            needle: u8,
+            out: *mut $int,
+        ) -> usize {
+            if src.is_null() || out.is_null() || len == 0 {
+                return 0;
+            }
+            #[cfg(target_arch = "x86_64")]
+            if $lanes == 64 && avx512::available() {
+                return avx512::eq_u8_masks(src, len, needle, out as *mut u64, true);
+            }
+            eq_u8_masks_impl::<$lanes, $int>(src, len, needle, out, |m| m as $int, true)
         }
     };
 }
 
-export_eq_masks!(eq_u8_masks16, 16, u16);
-export_eq_masks!(eq_u8_masks32, 32, u32);
-export_eq_masks!(eq_u8_masks64, 64, u64);
+export_eq_masks!(eq_u8_masks16, eq_u8_masks16_full, 16, u16);
+export_eq_masks!(eq_u8_masks32, eq_u8_masks32_full, 32, u32);
+export_eq_masks!(eq_u8_masks64, eq_u8_masks64_full, 64, u64);
+
+// === Byte-class membership mask =============================================
+
+/// Whole chunks are classified with nibble shuffles (see `NibbleLut`); a
+/// partial tail reads `table` directly.
+#[inline(always)]
+unsafe fn classify_u8_masks_impl<const LANES: usize, W>(
+    data: &[u8],
+    table: &[u8],
+    out: &mut [W],
+    conv: impl Fn(u64) -> W,
+) -> usize
+where
+    LaneCount<LANES>: SupportedLaneCount,
+{
+    let mut chunks = data.chunks_exact(LANES);
+    if data.len() >= LANES {
+        let set = NibbleLut::compile(table);
+        let member = set.classifier::<LANES>();
+        for (chunk, o) in (&mut chunks).zip(out.iter_mut()) {
+            *o = conv(member(Simd::<u8, LANES>::from_slice(chunk)).to_bitmask());
+        }
+    }
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        let mask = rem.iter().enumerate().fold(0u64, |m, (i, &b)| {
+            m | (((table[b as usize] != 0) as u64) << i)
+        });
+        out[out.len() - 1] = conv(mask);
+    }
+    out.len()
+}
+
+/* ─── classify_u8_masks exports via macro ────────────────────────────────── */
+macro_rules! export_classify_masks {
+    ($name:ident, $lanes:expr, $int:ty) => {
+        #[doc = concat!(
+            "Generate class-membership bitmasks across chunks of ", stringify!($lanes), " lanes: bit `i` of a word is set when the byte's entry in the 256-byte `lut` is non-zero. Writes and returns `len.div_ceil(", stringify!($lanes), ")` words; the last word covers a partial chunk when `len` is not a multiple of ", stringify!($lanes), ", with the bits past the end zero.\n\n",
+            "# Safety\n",
+            "`src`/`lut` must be valid for `len`/256 bytes; `out` must be valid for `len.div_ceil(", stringify!($lanes), ")` elements."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(
+            src: *const u8,
+            len: usize,
+            lut: *const u8,
+            out: *mut $int,
+        ) -> usize {
+            if src.is_null() || lut.is_null() || out.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(src, len);
+            let table = core::slice::from_raw_parts(lut, 256);
+            let out = core::slice::from_raw_parts_mut(out, len.div_ceil($lanes));
+            classify_u8_masks_impl::<$lanes, $int>(data, table, out, |m| m as $int)
+        }
+    };
+}
+export_classify_masks!(classify_u8_masks16, 16, u16);
+export_classify_masks!(classify_u8_masks32, 32, u32);
+export_classify_masks!(classify_u8_masks64, 64, u64);
 
 // -----------------------------------------------------------------------------
 
+// The helpers below only exist to test and measure the Go assembly
+// trampolines, so wasm builds leave them out.
+
 // FFI helper: no-op function to measure call overhead -------------------------
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn noop() {
+#[cfg(not(target_family = "wasm"))]
+#[unsafe(export_name = c_symbol!("noop"))]
+pub extern "C" fn noop() {
     // deliberately does nothing
 }
 
 // === FFI trampoline sanity helper ===========================================
 /// Simple checksum over the arguments; used only by Go tests to verify that
 /// assembly trampolines pass parameters with the correct width/order.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn trampoline_sanity(
+#[cfg(not(target_family = "wasm"))]
+#[unsafe(export_name = c_symbol!("trampoline_sanity"))]
+pub extern "C" fn trampoline_sanity(
     ptr: *const u8,
     len: usize,
     val32: u32,
@@ -346,6 +843,7 @@ pub unsafe extern "C" fn trampoline_sanity(
 }
 
 /// Echo structure for detailed trampoline debugging (test builds only).
+#[cfg(not(target_family = "wasm"))]
 #[repr(C)]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Echo {
@@ -360,7 +858,11 @@ pub struct Echo {
 
 /// Bounce all parameters back to the caller; used by Go unit tests to pinpoint
 /// which argument (if any) is mis-marshalled by the assembly trampolines.
-#[unsafe(no_mangle)]
+///
+/// # Safety
+/// `out` must be null or valid for one `Echo` write.
+#[cfg(not(target_family = "wasm"))]
+#[unsafe(export_name = c_symbol!("trampoline_echo"))]
 pub unsafe extern "C" fn trampoline_echo(
     ptr: *const u8,
     len: usize,
@@ -444,6 +946,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_u8_lut_pos() {
+        // Hex digits only.
+        let lut: Vec<u8> = (0..=255u8).map(|b| b.is_ascii_hexdigit() as u8).collect();
+        type Pos = unsafe extern "C" fn(*const u8, usize, *const u8) -> isize;
+        type Valid = unsafe extern "C" fn(*const u8, usize, *const u8) -> u8;
+        let kernels: [(Pos, Valid); 3] = [
+            (super::validate_u8_lut_pos16, super::validate_u8_lut16),
+            (super::validate_u8_lut_pos32, super::validate_u8_lut32),
+            (super::validate_u8_lut_pos64, super::validate_u8_lut64),
+        ];
+        let clean: Vec<u8> = b"0123456789abcdefABCDEF".repeat(12);
+        for (pos, valid) in kernels {
+            unsafe {
+                assert_eq!(pos(clean.as_ptr(), clean.len(), lut.as_ptr()), -1);
+                assert_eq!(pos(core::ptr::null(), 0, lut.as_ptr()), -1);
+                for bad in [0, 1, 15, 16, 31, 63, 64, 100, clean.len() - 1] {
+                    let mut data = clean.clone();
+                    data[bad] = b'g';
+                    // A second invalid byte later on must not win.
+                    data[clean.len() - 1] = b'-';
+                    assert_eq!(pos(data.as_ptr(), data.len(), lut.as_ptr()), bad as isize);
+                    assert_eq!(valid(data.as_ptr(), data.len(), lut.as_ptr()), 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nibble_lut() {
+        // Tables with few and with many (> 8) distinct nibble rows.
+        let tables: [[u8; 256]; 4] = [
+            core::array::from_fn(|b| (b as u8).is_ascii_hexdigit() as u8),
+            core::array::from_fn(|b| (b as u8).is_ascii_whitespace() as u8),
+            core::array::from_fn(|b| (b.wrapping_mul(2654435761) >> 7 & 1) as u8),
+            core::array::from_fn(|b| (b % 17 != 3) as u8),
+        ];
+        let data: Vec<u8> = (0..=255u8).cycle().take(700).collect();
+        for table in &tables {
+            let mut set = super::NibbleLut::default();
+            unsafe { super::nibble_lut_compile(table.as_ptr(), &mut set) };
+            for b in 0..=255u8 {
+                assert_eq!(set.contains(b), table[b as usize] != 0, "byte {b}");
+            }
+            // Every byte alone, through the vector path.
+            for b in 0..=255u8 {
+                let run = [b; 100];
+                let want = if table[b as usize] != 0 { -1 } else { 0 };
+                assert_eq!(
+                    unsafe { super::validate_u8_nibble_lut_pos(run.as_ptr(), 100, &set) },
+                    want
+                );
+            }
+            let want = data
+                .iter()
+                .position(|&b| table[b as usize] == 0)
+                .map_or(-1, |i| i as isize);
+            assert_eq!(
+                unsafe { super::validate_u8_nibble_lut_pos(data.as_ptr(), data.len(), &set) },
+                want
+            );
+            assert_eq!(
+                unsafe { super::validate_u8_lut_pos32(data.as_ptr(), data.len(), table.as_ptr()) },
+                want
+            );
+        }
+    }
+
     #[test]
     fn test_map_u8_lut_basic() {
         // Mapping table: invert each byte (x -> 255 - x)
@@ -478,6 +1048,31 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_map_u8_lut_overlap() {
+        // A scrambling table catches rows or nibbles mixed up.
+        let map: Vec<u8> = (0..=255u32).map(|b| (b * 167 + 13) as u8).collect();
+        type Map = unsafe extern "C" fn(*const u8, usize, *mut u8, *const u8);
+        let kernels: [Map; 3] = [
+            super::map_u8_lut16,
+            super::map_u8_lut32,
+            super::map_u8_lut64,
+        ];
+        for kernel in kernels {
+            for shift in [-70isize, -17, -1, 0, 1, 5, 16, 64, 100] {
+                let len = 300;
+                let mut buf: Vec<u8> = (0..len + 200).map(|i| (i * 31 + 7) as u8).collect();
+                let (s, d) = (100usize, (100 + shift) as usize);
+                let want: Vec<u8> = buf[s..s + len].iter().map(|&b| map[b as usize]).collect();
+                unsafe {
+                    let p = buf.as_mut_ptr();
+                    kernel(p.add(s), len, p.add(d), map.as_ptr());
+                }
+                assert_eq!(buf[d..d + len], want[..], "shift {shift}");
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -511,6 +1106,116 @@ mod mask_tests {
             let chunk = &data[start..start + 16];
             assert_eq!(mask as u128, scalar_mask(chunk, 3));
         }
+        for (i, &mask) in out32.iter().enumerate() {
+            assert_eq!(mask as u128, scalar_mask(&data[i * 32..(i + 1) * 32], 3));
+        }
+        for (i, &mask) in out64.iter().enumerate() {
+            assert_eq!(mask as u128, scalar_mask(&data[i * 64..(i + 1) * 64], 3));
+        }
+    }
+
+    #[test]
+    fn test_eq_u8_masks_full_tail() {
+        // 0 bytes everywhere: padding must not show up as matches.
+        let data: Vec<u8> = (0..150u16).map(|i| (i % 7) as u8).collect();
+        let mut out16 = vec![!0u16; data.len().div_ceil(16)];
+        let mut out32 = vec![!0u32; data.len().div_ceil(32)];
+        let mut out64 = vec![!0u64; data.len().div_ceil(64)];
+        unsafe {
+            let c16 = super::eq_u8_masks16_full(data.as_ptr(), data.len(), 0, out16.as_mut_ptr());
+            let c32 = super::eq_u8_masks32_full(data.as_ptr(), data.len(), 0, out32.as_mut_ptr());
+            let c64 = super::eq_u8_masks64_full(data.as_ptr(), data.len(), 0, out64.as_mut_ptr());
+            assert_eq!((c16, c32, c64), (out16.len(), out32.len(), out64.len()));
+            // The plain variants still stop at the last whole chunk.
+            assert_eq!(
+                super::eq_u8_masks64(data.as_ptr(), data.len(), 0, out64.as_mut_ptr()),
+                2
+            );
+        }
+        for (i, &mask) in out16.iter().enumerate() {
+            let chunk = &data[i * 16..data.len().min((i + 1) * 16)];
+            assert_eq!(mask as u128, scalar_mask(chunk, 0));
+        }
+        for (i, &mask) in out32.iter().enumerate() {
+            let chunk = &data[i * 32..data.len().min((i + 1) * 32)];
+            assert_eq!(mask as u128, scalar_mask(chunk, 0));
+        }
+        for (i, &mask) in out64.iter().enumerate() {
+            let chunk = &data[i * 64..data.len().min((i + 1) * 64)];
+            assert_eq!(mask as u128, scalar_mask(chunk, 0));
+        }
+    }
+
+    #[test]
+    fn test_misaligned_starts() {
+        // Every start offset within a 64-byte line, so heads of all lengths
+        // are peeled before the aligned loop.
+        let buf: Vec<u8> = (0..600u32).map(|i| (i * 13 % 7) as u8).collect();
+        for off in 0..64 {
+            for len in [0usize, 1, 17, 63, 64, 65, 130, 500] {
+                let data = &buf[off..off + len];
+                let sum = data.iter().map(|&b| b as u32).sum::<u32>();
+                let mut out = vec![!0u64; len.div_ceil(64)];
+                unsafe {
+                    assert_eq!(super::sum_u8_16(data.as_ptr(), len), sum);
+                    assert_eq!(super::sum_u8_64(data.as_ptr(), len), sum);
+                    assert_eq!(super::is_ascii64(data.as_ptr(), len), 1);
+                    super::eq_u8_masks64_full(data.as_ptr(), len, 3, out.as_mut_ptr());
+                }
+                for (i, &mask) in out.iter().enumerate() {
+                    let chunk = &data[i * 64..len.min((i + 1) * 64)];
+                    assert_eq!(mask as u128, scalar_mask(chunk, 3), "off {off} len {len}");
+                }
+            }
+        }
+        let mut high = buf.clone();
+        high[300] = 0x80;
+        for off in [0, 5, 33] {
+            let s = &high[off..];
+            assert_eq!(unsafe { super::is_ascii32(s.as_ptr(), s.len()) }, 0);
+        }
+    }
+
+    #[test]
+    fn test_classify_u8_masks() {
+        // Delimiters or whitespace.
+        let mut delims = [0u8; 256];
+        for &b in b" \t\n,;" {
+            delims[b as usize] = 1;
+        }
+        // More than eight distinct rows need both shuffle banks.
+        let scattered: [u8; 256] = core::array::from_fn(|b| (b * 37 % 11 < 4) as u8);
+        let text = b"key=value; other,thing\tmore stuff\nand the rest of the line;x".repeat(3);
+        let every: Vec<u8> = (0..=255u8).chain(0..=255u8).rev().collect();
+        for lut in [&delims, &scattered] {
+            for data in [&text[..], &text[..10], &every[..]] {
+                let mut out16 = vec![0u16; data.len().div_ceil(16)];
+                let mut out64 = vec![0u64; data.len().div_ceil(64)];
+                unsafe {
+                    let c16 = super::classify_u8_masks16(
+                        data.as_ptr(),
+                        data.len(),
+                        lut.as_ptr(),
+                        out16.as_mut_ptr(),
+                    );
+                    let c64 = super::classify_u8_masks64(
+                        data.as_ptr(),
+                        data.len(),
+                        lut.as_ptr(),
+                        out64.as_mut_ptr(),
+                    );
+                    assert_eq!((c16, c64), (out16.len(), out64.len()));
+                }
+                for (i, &b) in data.iter().enumerate() {
+                    let want = lut[b as usize] != 0;
+                    assert_eq!((out16[i / 16] >> (i % 16)) & 1 == 1, want, "i={}", i);
+                    assert_eq!((out64[i / 64] >> (i % 64)) & 1 == 1, want, "i={}", i);
+                }
+                if data.len() % 64 != 0 {
+                    assert_eq!(out64[data.len() / 64] >> (data.len() % 64), 0);
+                }
+            }
+        }
     }
 }
 
@@ -606,10 +1311,10 @@ mod crc32c_tests {
 
         let crc1 = super::crc32c_update(0, &buf1);
         let crc2 = super::crc32c_update(0, &buf2);
-        let mut concat = Vec::from(buf1);
+        let mut concat = buf1;
         concat.extend_from_slice(&buf2);
         let expected_concat = super::crc32c_update(0, &concat);
-        let combined = unsafe { super::crc32_combine_raw(crc1, crc2, len2) };
+        let combined = super::crc32_combine_raw(crc1, crc2, len2);
         assert_eq!(combined, expected_concat, "combine mismatch");
     }
 
@@ -621,7 +1326,7 @@ mod crc32c_tests {
         let crc1 = super::crc32c_update(0, buf1);
         let crc2 = super::crc32c_update(0, buf2);
         let mut concat = Vec::from(buf1);
-        concat.extend_from_slice(&buf2);
+        concat.extend_from_slice(buf2);
         let expected = super::crc32c_update(0, &concat);
 
         let raw1 = !crc1;
@@ -0,0 +1,188 @@
+//! Registered lookup tables.  Callers that reuse the same few 256-byte
+//! tables for every request register them once and pass a small id to the
+//! `*_lut_id` kernels, which read the table from cache-line-aligned storage
+//! owned by the library instead of a caller pointer.  Validation and
+//! counting use the table's nibble-shuffle form, compiled once at
+//! registration.
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::error::{SIMBA_ERR_CAPACITY, SIMBA_ERR_INVALID_INPUT, SIMBA_ERR_NULL_POINTER, fail};
+use crate::{NibbleLut, nibble_lut_pos_impl};
+
+/// Tables that can be registered at once.
+const LUT_SLOTS: usize = 64;
+
+const FREE: u8 = 0;
+/// Claimed by `lut_register`, table not yet copied in.
+const FILLING: u8 = 1;
+const READY: u8 = 2;
+
+#[repr(C, align(64))]
+struct Slot {
+    table: UnsafeCell<[u8; 256]>,
+    nibbles: UnsafeCell<NibbleLut>,
+}
+
+// A slot's tables are written only while its state is `FILLING`, which a
+// single registering thread owns.
+unsafe impl Sync for Slot {}
+
+static TABLES: [Slot; LUT_SLOTS] = [const {
+    Slot {
+        table: UnsafeCell::new([0; 256]),
+        nibbles: UnsafeCell::new(NibbleLut {
+            lo: [[0; 16]; 2],
+            hi: [[0; 16]; 2],
+        }),
+    }
+}; LUT_SLOTS];
+static STATE: [AtomicU8; LUT_SLOTS] = [const { AtomicU8::new(FREE) }; LUT_SLOTS];
+
+/// The slot registered under `id`, if any.
+fn registered(id: u32) -> Option<&'static Slot> {
+    let slot = (id as usize).checked_sub(1).filter(|&s| s < LUT_SLOTS)?;
+    if STATE[slot].load(Ordering::Acquire) != READY {
+        return None;
+    }
+    Some(&TABLES[slot])
+}
+
+fn unknown(id: u32) -> i32 {
+    fail(
+        SIMBA_ERR_INVALID_INPUT,
+        format_args!("no lookup table is registered under id {id}"),
+    )
+}
+
+/// Copy the 256-byte `table` into library-owned, cache-line-aligned storage
+/// and return its id (non-zero) for the `*_lut_id` kernels.  Returns 0 when
+/// `table` is null or all 64 slots are in use.  Thread-safe.
+///
+/// # Safety
+/// `table` must be null or valid for 256 bytes.
+#[unsafe(export_name = c_symbol!("lut_register"))]
+pub unsafe extern "C" fn lut_register(table: *const u8) -> u32 {
+    if table.is_null() {
+        fail(SIMBA_ERR_NULL_POINTER, format_args!("lookup table is null"));
+        return 0;
+    }
+    for (slot, state) in STATE.iter().enumerate() {
+        if state
+            .compare_exchange(FREE, FILLING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let table = core::slice::from_raw_parts(table, 256);
+            (*TABLES[slot].table.get()).copy_from_slice(table);
+            *TABLES[slot].nibbles.get() = NibbleLut::compile(table);
+            state.store(READY, Ordering::Release);
+            return slot as u32 + 1;
+        }
+    }
+    fail(
+        SIMBA_ERR_CAPACITY,
+        format_args!("all {LUT_SLOTS} lookup table slots are in use"),
+    );
+    0
+}
+
+/// Release the table registered under `id` so its slot can be reused.
+/// Returns 0, or `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.  The
+/// caller must ensure no `*_lut_id` call using `id` is still running.
+#[unsafe(export_name = c_symbol!("lut_release"))]
+pub extern "C" fn lut_release(id: u32) -> i32 {
+    let Some(slot) = (id as usize).checked_sub(1).filter(|&s| s < LUT_SLOTS) else {
+        return unknown(id);
+    };
+    match STATE[slot].compare_exchange(READY, FREE, Ordering::AcqRel, Ordering::Relaxed) {
+        Ok(_) => 0,
+        Err(_) => unknown(id),
+    }
+}
+
+/// `validate_u8_nibble_lut` with a registered table: returns 1 if every byte
+/// has a non-zero entry, 0 on the first mismatch, or
+/// `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("validate_u8_lut_id"))]
+pub unsafe extern "C" fn validate_u8_lut_id(ptr: *const u8, len: usize, id: u32) -> i32 {
+    let Some(slot) = registered(id) else {
+        return unknown(id);
+    };
+    if ptr.is_null() || len == 0 {
+        return 1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    nibble_lut_pos_impl::<64>(data, &*slot.nibbles.get()).is_none() as i32
+}
+
+/// `map_u8_lut64` with a registered table.  Returns 0, or
+/// `SIMBA_ERR_INVALID_INPUT` (writing nothing) if `id` is not registered.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes. Buffers may overlap.
+#[unsafe(export_name = c_symbol!("map_u8_lut_id"))]
+pub unsafe extern "C" fn map_u8_lut_id(src: *const u8, len: usize, dst: *mut u8, id: u32) -> i32 {
+    let Some(slot) = registered(id) else {
+        return unknown(id);
+    };
+    crate::map_u8_lut64(src, len, dst, slot.table.get() as *const u8);
+    0
+}
+
+/// `count_u8_lut` with a registered table: the number of bytes with a
+/// non-zero entry, or `SIMBA_ERR_INVALID_INPUT` if `id` is not registered.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("count_u8_lut_id"))]
+pub unsafe extern "C" fn count_u8_lut_id(ptr: *const u8, len: usize, id: u32) -> i64 {
+    let Some(slot) = registered(id) else {
+        return unknown(id) as i64;
+    };
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    crate::search::count_u8_set_impl(data, &*slot.nibbles.get()) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lut_registry() {
+        let digits: [u8; 256] = core::array::from_fn(|b| (b as u8).is_ascii_digit() as u8);
+        let upper: [u8; 256] = core::array::from_fn(|b| (b as u8).to_ascii_uppercase());
+        let (d, u) = unsafe { (lut_register(digits.as_ptr()), lut_register(upper.as_ptr())) };
+        assert!(d != 0 && u != 0 && d != u);
+
+        let data = b"0123456789x".repeat(10);
+        unsafe {
+            assert_eq!(validate_u8_lut_id(data.as_ptr(), 10, d), 1);
+            assert_eq!(validate_u8_lut_id(data.as_ptr(), data.len(), d), 0);
+            assert_eq!(count_u8_lut_id(data.as_ptr(), data.len(), d), 100);
+            let mut out = vec![0u8; data.len()];
+            assert_eq!(
+                map_u8_lut_id(data.as_ptr(), data.len(), out.as_mut_ptr(), u),
+                0
+            );
+            assert_eq!(out, b"0123456789X".repeat(10));
+        }
+
+        assert_eq!(lut_release(d), 0);
+        assert_eq!(lut_release(d), SIMBA_ERR_INVALID_INPUT);
+        assert_eq!(lut_release(0), SIMBA_ERR_INVALID_INPUT);
+        unsafe {
+            assert_eq!(
+                count_u8_lut_id(data.as_ptr(), data.len(), d),
+                SIMBA_ERR_INVALID_INPUT as i64
+            );
+            assert_eq!(lut_register(core::ptr::null()), 0);
+        }
+        assert_eq!(lut_release(u), 0);
+    }
+}
@@ -0,0 +1,490 @@
+//! Numeric column kernels: sums over wide integer arrays, floating-point sums
+//! and dot products, the AXPY / scale updates that round out a minimal
+//! BLAS-1 set, int <-> float conversion and clamping, and saturating byte
+//! arithmetic.
+use core::simd::Simd;
+use core::simd::prelude::{SimdFloat, SimdInt, SimdUint};
+
+use crate::{load_simd, store_simd};
+
+// === Integer sums ============================================================
+
+macro_rules! export_sum {
+    ($name:ident, $t:ty, $acc:ty, $lanes:expr, $wrap:literal) => {
+        #[doc = concat!(
+            "Sum `len` `", stringify!($t), "` values into a `", stringify!($acc), "` using a ", stringify!($lanes), "-lane SIMD kernel. ", $wrap, "\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` `", stringify!($t), "` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const $t, len: usize) -> $acc {
+            if ptr.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let mut chunks = data.chunks_exact($lanes);
+            let mut acc = Simd::<$acc, $lanes>::splat(0);
+            for chunk in &mut chunks {
+                acc += Simd::<$t, $lanes>::from_slice(chunk).cast::<$acc>();
+            }
+            chunks
+                .remainder()
+                .iter()
+                .fold(acc.reduce_sum(), |s, &x| s.wrapping_add(x as $acc))
+        }
+    };
+}
+export_sum!(
+    sum_u16,
+    u16,
+    u64,
+    16,
+    "Exact (no wrap) for fewer than 2^48 values."
+);
+export_sum!(
+    sum_u32,
+    u32,
+    u64,
+    8,
+    "Exact (no wrap) for fewer than 2^32 values."
+);
+export_sum!(
+    sum_i32,
+    i32,
+    i64,
+    8,
+    "Exact (no wrap) for fewer than 2^32 values."
+);
+export_sum!(sum_u64, u64, u64, 8, "Wraps modulo 2^64 on overflow.");
+export_sum!(
+    sum_i64,
+    i64,
+    i64,
+    8,
+    "Wraps in two's complement on overflow."
+);
+
+// === Floating-point sums and dot products ====================================
+//
+// The reduction order is fixed so results are reproducible across hosts:
+// element `i` of every whole block accumulates into lane `i % LANES`, the
+// lanes are then folded pairwise (lane `j` += lane `j + LANES / 2`, halving
+// until one is left) and the tail is added in order.  Products are rounded
+// before they are added; no FMA is used.
+
+/// Fold the lanes pairwise, in halves.
+#[inline(always)]
+fn pairwise_sum<T: Copy + core::ops::Add<Output = T>, const L: usize>(mut v: [T; L]) -> T {
+    let mut n = L;
+    while n > 1 {
+        n /= 2;
+        for j in 0..n {
+            v[j] = v[j] + v[j + n];
+        }
+    }
+    v[0]
+}
+
+macro_rules! export_float_sum {
+    ($sum:ident, $dot:ident, $t:ty, $lanes:expr) => {
+        #[doc = concat!(
+            "Sum `len` `", stringify!($t), "` values with ", stringify!($lanes), " lane accumulators folded pairwise (see the module notes for the exact order).\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($sum)))]
+        pub unsafe extern "C" fn $sum(ptr: *const $t, len: usize) -> $t {
+            if ptr.is_null() || len == 0 {
+                return 0.0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let mut chunks = data.chunks_exact($lanes);
+            let mut acc = Simd::<$t, $lanes>::splat(0.0);
+            for chunk in &mut chunks {
+                acc += Simd::from_slice(chunk);
+            }
+            chunks
+                .remainder()
+                .iter()
+                .fold(pairwise_sum(acc.to_array()), |s, &x| s + x)
+        }
+
+        #[doc = concat!(
+            "Dot product of two `len`-element `", stringify!($t), "` vectors, in the same order as [`", stringify!($sum), "`] over the products.\n\n",
+            "# Safety\n",
+            "`a` and `b` must be null or valid for `len` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($dot)))]
+        pub unsafe extern "C" fn $dot(a: *const $t, b: *const $t, len: usize) -> $t {
+            if a.is_null() || b.is_null() || len == 0 {
+                return 0.0;
+            }
+            let a = core::slice::from_raw_parts(a, len);
+            let b = core::slice::from_raw_parts(b, len);
+            let (mut ca, mut cb) = (a.chunks_exact($lanes), b.chunks_exact($lanes));
+            let mut acc = Simd::<$t, $lanes>::splat(0.0);
+            for (x, y) in (&mut ca).zip(&mut cb) {
+                acc += Simd::from_slice(x) * Simd::from_slice(y);
+            }
+            ca.remainder()
+                .iter()
+                .zip(cb.remainder())
+                .fold(pairwise_sum(acc.to_array()), |s, (&x, &y)| s + x * y)
+        }
+    };
+}
+export_float_sum!(sum_f32, dot_f32, f32, 32);
+export_float_sum!(sum_f64, dot_f64, f64, 16);
+
+// === AXPY / scale ============================================================
+
+macro_rules! export_axpy {
+    ($axpy:ident, $scale:ident, $t:ty, $lanes:expr) => {
+        #[doc = concat!(
+            "`dst[i] += a * x[i]` over `len` `", stringify!($t), "` values (the product is rounded before the add; no FMA).\n\n",
+            "# Safety\n",
+            "`dst` and `x` must be valid for `len` values.  `dst` may alias `x` exactly, but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($axpy)))]
+        pub unsafe extern "C" fn $axpy(dst: *mut $t, x: *const $t, a: $t, len: usize) {
+            if dst.is_null() || x.is_null() || len == 0 {
+                return;
+            }
+            let va = Simd::<$t, $lanes>::splat(a);
+            let mut i = 0;
+            while i + $lanes <= len {
+                let d = load_simd::<$t, $lanes>(dst.add(i));
+                store_simd(dst.add(i), d + va * load_simd::<$t, $lanes>(x.add(i)));
+                i += $lanes;
+            }
+            while i < len {
+                *dst.add(i) += a * *x.add(i);
+                i += 1;
+            }
+        }
+
+        #[doc = concat!(
+            "`dst[i] *= a` over `len` `", stringify!($t), "` values.\n\n",
+            "# Safety\n",
+            "`dst` must be null or valid for `len` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($scale)))]
+        pub unsafe extern "C" fn $scale(dst: *mut $t, a: $t, len: usize) {
+            if dst.is_null() || len == 0 {
+                return;
+            }
+            let dst = core::slice::from_raw_parts_mut(dst, len);
+            let va = Simd::<$t, $lanes>::splat(a);
+            let mut chunks = dst.chunks_exact_mut($lanes);
+            for chunk in &mut chunks {
+                (Simd::from_slice(chunk) * va).copy_to_slice(chunk);
+            }
+            for d in chunks.into_remainder() {
+                *d *= a;
+            }
+        }
+    };
+}
+export_axpy!(axpy_f32, scale_f32, f32, 16);
+export_axpy!(axpy_f64, scale_f64, f64, 8);
+
+// === Conversion and clamping =================================================
+
+const CONVERT_LANES: usize = 16;
+
+/// Convert `len` i32 values to f32 (round to nearest-even above 2^24).
+///
+/// # Safety
+/// `src` must be null or valid for `len` i32 values and `dst` for `len` f32
+/// writes; they must not overlap.
+#[unsafe(export_name = c_symbol!("i32_to_f32"))]
+pub unsafe extern "C" fn i32_to_f32(src: *const i32, len: usize, dst: *mut f32) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, len);
+    let mut s = src.chunks_exact(CONVERT_LANES);
+    let mut d = dst.chunks_exact_mut(CONVERT_LANES);
+    for (s, d) in (&mut s).zip(&mut d) {
+        Simd::<i32, CONVERT_LANES>::from_slice(s)
+            .cast::<f32>()
+            .copy_to_slice(d);
+    }
+    for (&x, d) in s.remainder().iter().zip(d.into_remainder()) {
+        *d = x as f32;
+    }
+}
+
+/// Convert `len` f32 values to i32, truncating toward zero like Go's
+/// `int32(f)`; out-of-range values saturate to `i32::MIN`/`i32::MAX` and NaN
+/// becomes 0.
+///
+/// # Safety
+/// `src` must be null or valid for `len` f32 values and `dst` for `len` i32
+/// writes; they must not overlap.
+#[unsafe(export_name = c_symbol!("f32_to_i32_saturating"))]
+pub unsafe extern "C" fn f32_to_i32_saturating(src: *const f32, len: usize, dst: *mut i32) {
+    if src.is_null() || dst.is_null() || len == 0 {
+        return;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, len);
+    let mut s = src.chunks_exact(CONVERT_LANES);
+    let mut d = dst.chunks_exact_mut(CONVERT_LANES);
+    for (s, d) in (&mut s).zip(&mut d) {
+        // The float -> int cast saturates and maps NaN to 0.
+        Simd::<f32, CONVERT_LANES>::from_slice(s)
+            .cast::<i32>()
+            .copy_to_slice(d);
+    }
+    for (&x, d) in s.remainder().iter().zip(d.into_remainder()) {
+        *d = x as i32;
+    }
+}
+
+/// Clamp `len` f32 values in place to `[lo, hi]`.  NaN becomes `lo`; if
+/// `lo > hi` every value becomes `hi`.
+///
+/// # Safety
+/// `dst` must be null or valid for `len` f32 values.
+#[unsafe(export_name = c_symbol!("clamp_f32"))]
+pub unsafe extern "C" fn clamp_f32(dst: *mut f32, len: usize, lo: f32, hi: f32) {
+    if dst.is_null() || len == 0 {
+        return;
+    }
+    let dst = core::slice::from_raw_parts_mut(dst, len);
+    let (vlo, vhi) = (Simd::splat(lo), Simd::splat(hi));
+    let mut chunks = dst.chunks_exact_mut(CONVERT_LANES);
+    for chunk in &mut chunks {
+        Simd::<f32, CONVERT_LANES>::from_slice(chunk)
+            .simd_max(vlo)
+            .simd_min(vhi)
+            .copy_to_slice(chunk);
+    }
+    for x in chunks.into_remainder() {
+        *x = x.max(lo).min(hi);
+    }
+}
+
+// === Saturating byte arithmetic ==============================================
+
+const SATURATING_LANES: usize = 64;
+
+macro_rules! export_saturating_u8 {
+    ($name:ident, $op:ident, $desc:literal) => {
+        #[doc = concat!(
+            "Compute `dst[i] = ", $desc, "` for `len` bytes, clamped to `0..=255` instead of wrapping.\n\n",
+            "# Safety\n",
+            "All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or `b` exactly, but must not partially overlap either."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(dst: *mut u8, a: *const u8, b: *const u8, len: usize) {
+            if dst.is_null() || a.is_null() || b.is_null() || len == 0 {
+                return;
+            }
+            let mut i = 0;
+            while i + SATURATING_LANES <= len {
+                let x = load_simd::<u8, SATURATING_LANES>(a.add(i));
+                let y = load_simd::<u8, SATURATING_LANES>(b.add(i));
+                store_simd(dst.add(i), x.$op(y));
+                i += SATURATING_LANES;
+            }
+            while i < len {
+                *dst.add(i) = (*a.add(i)).$op(*b.add(i));
+                i += 1;
+            }
+        }
+    };
+}
+export_saturating_u8!(saturating_add_u8, saturating_add, "a[i] + b[i]");
+export_saturating_u8!(saturating_sub_u8, saturating_sub, "a[i] - b[i]");
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_integer_sums() {
+        let wide: Vec<u64> = (0..1003u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        for len in [0usize, 1, 7, 8, 16, 17, wide.len()] {
+            let w = &wide[..len];
+            let u16s: Vec<u16> = w.iter().map(|&x| x as u16).collect();
+            let u32s: Vec<u32> = w.iter().map(|&x| x as u32).collect();
+            let i32s: Vec<i32> = w.iter().map(|&x| x as i32).collect();
+            let i64s: Vec<i64> = w.iter().map(|&x| x as i64).collect();
+            let wrapped = w.iter().fold(0u64, |s, &x| s.wrapping_add(x));
+            unsafe {
+                assert_eq!(
+                    super::sum_u16(u16s.as_ptr(), len),
+                    u16s.iter().map(|&x| x as u64).sum::<u64>()
+                );
+                assert_eq!(
+                    super::sum_u32(u32s.as_ptr(), len),
+                    u32s.iter().map(|&x| x as u64).sum::<u64>()
+                );
+                assert_eq!(
+                    super::sum_i32(i32s.as_ptr(), len),
+                    i32s.iter().map(|&x| x as i64).sum::<i64>()
+                );
+                assert_eq!(super::sum_u64(w.as_ptr(), len), wrapped);
+                assert_eq!(super::sum_i64(i64s.as_ptr(), len), wrapped as i64);
+            }
+        }
+        // Overflow wraps rather than saturating.
+        let big = [u64::MAX, 2];
+        let neg = [i64::MIN, -1];
+        unsafe {
+            assert_eq!(super::sum_u64(big.as_ptr(), 2), 1);
+            assert_eq!(super::sum_i64(neg.as_ptr(), 2), i64::MAX);
+            assert_eq!(super::sum_u32(core::ptr::null(), 0), 0);
+        }
+    }
+
+    #[test]
+    fn test_float_sum_dot() {
+        // Reference evaluation of the documented order.
+        fn reference(v: &[f64], lanes: usize) -> f64 {
+            let whole = v.len() / lanes * lanes;
+            let mut acc = vec![0.0; lanes];
+            for (i, &x) in v[..whole].iter().enumerate() {
+                acc[i % lanes] += x;
+            }
+            let mut n = lanes;
+            while n > 1 {
+                n /= 2;
+                for j in 0..n {
+                    acc[j] += acc[j + n];
+                }
+            }
+            v[whole..].iter().fold(acc[0], |s, &x| s + x)
+        }
+        let xs: Vec<f64> = (0..1000)
+            .map(|i| ((i * 37 % 101) as f64 - 50.0) / 7.0)
+            .collect();
+        let ys: Vec<f64> = (0..1000)
+            .map(|i| ((i * 53 % 97) as f64 - 48.0) / 3.0)
+            .collect();
+        let prods: Vec<f64> = xs.iter().zip(&ys).map(|(x, y)| x * y).collect();
+        let xs32: Vec<f32> = xs.iter().map(|&x| x as f32).collect();
+        let ys32: Vec<f32> = ys.iter().map(|&y| y as f32).collect();
+        for len in [0usize, 1, 15, 16, 33, 1000] {
+            unsafe {
+                assert_eq!(super::sum_f64(xs.as_ptr(), len), reference(&xs[..len], 16));
+                assert_eq!(
+                    super::dot_f64(xs.as_ptr(), ys.as_ptr(), len),
+                    reference(&prods[..len], 16)
+                );
+                let sum32 = super::sum_f32(xs32.as_ptr(), len) as f64;
+                let want32: f64 = xs32[..len].iter().map(|&x| x as f64).sum();
+                assert!((sum32 - want32).abs() < 1e-3, "len={len}");
+                let dot32 = super::dot_f32(xs32.as_ptr(), ys32.as_ptr(), len) as f64;
+                let want32: f64 = xs32[..len]
+                    .iter()
+                    .zip(&ys32)
+                    .map(|(&x, &y)| x as f64 * y as f64)
+                    .sum();
+                assert!((dot32 - want32).abs() < 1e-1, "len={len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_axpy_scale() {
+        let x: Vec<f32> = (0..101).map(|i| i as f32 * 0.25 - 7.0).collect();
+        let y: Vec<f32> = (0..101).map(|i| 3.0 - i as f32 * 0.5).collect();
+        for len in [0usize, 1, 15, 16, 17, 101] {
+            let mut dst = y.clone();
+            unsafe { super::axpy_f32(dst.as_mut_ptr(), x.as_ptr(), -1.5, len) };
+            for i in 0..101 {
+                let want = if i < len { y[i] + -1.5 * x[i] } else { y[i] };
+                assert_eq!(dst[i], want, "len={len} i={i}");
+            }
+            unsafe { super::scale_f32(dst.as_mut_ptr(), 2.0, len) };
+            for i in 0..len {
+                assert_eq!(dst[i], (y[i] + -1.5 * x[i]) * 2.0);
+            }
+        }
+        // Exact aliasing: x += 2x.
+        let mut z: Vec<f64> = (0..37).map(|i| i as f64).collect();
+        unsafe { super::axpy_f64(z.as_mut_ptr(), z.as_ptr(), 2.0, z.len()) };
+        assert!(z.iter().enumerate().all(|(i, &v)| v == 3.0 * i as f64));
+        unsafe { super::scale_f64(z.as_mut_ptr(), 0.5, z.len()) };
+        assert!(z.iter().enumerate().all(|(i, &v)| v == 1.5 * i as f64));
+    }
+
+    #[test]
+    fn test_convert_clamp() {
+        let ints: Vec<i32> = (0..37)
+            .map(|i: i32| i.wrapping_mul(0x4F1B_BCDD) >> (i % 20))
+            .collect();
+        let mut floats = vec![0f32; ints.len()];
+        unsafe { super::i32_to_f32(ints.as_ptr(), ints.len(), floats.as_mut_ptr()) };
+        assert!(ints.iter().zip(&floats).all(|(&i, &f)| f == i as f32));
+
+        let src = [
+            1.9f32,
+            -1.9,
+            0.5,
+            -0.0,
+            3e9,
+            -3e9,
+            f32::NAN,
+            f32::INFINITY,
+            2147483520.0,
+            -2147483648.0,
+        ]
+        .repeat(4);
+        let mut out = vec![7i32; src.len()];
+        unsafe { super::f32_to_i32_saturating(src.as_ptr(), src.len(), out.as_mut_ptr()) };
+        assert_eq!(
+            out[..10],
+            [
+                1,
+                -1,
+                0,
+                0,
+                i32::MAX,
+                i32::MIN,
+                0,
+                i32::MAX,
+                2147483520,
+                i32::MIN
+            ]
+        );
+        assert!(out.chunks(10).all(|c| c == &out[..10]));
+
+        let mut v = src.clone();
+        unsafe { super::clamp_f32(v.as_mut_ptr(), v.len(), -1.0, 1.0) };
+        assert_eq!(
+            v[..10],
+            [1.0, -1.0, 0.5, -0.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0]
+        );
+        assert!(v.chunks(10).all(|c| c == &v[..10]));
+    }
+
+    #[test]
+    fn test_saturating_u8() {
+        let a: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        let b: Vec<u8> = (0..300u32).map(|i| (i * 13 + 100) as u8).collect();
+        for len in [1usize, 63, 64, 65, 300] {
+            let mut sum = vec![0u8; len];
+            let mut diff = vec![0u8; len];
+            unsafe {
+                super::saturating_add_u8(sum.as_mut_ptr(), a.as_ptr(), b.as_ptr(), len);
+                super::saturating_sub_u8(diff.as_mut_ptr(), a.as_ptr(), b.as_ptr(), len);
+            }
+            for i in 0..len {
+                assert_eq!(sum[i], a[i].saturating_add(b[i]), "len={len} i={i}");
+                assert_eq!(diff[i], a[i].saturating_sub(b[i]), "len={len} i={i}");
+            }
+        }
+        // In place, with `dst` aliasing `a`.
+        let mut v = [250u8, 5, 128, 0];
+        let d = [10u8, 10, 128, 0];
+        unsafe { super::saturating_add_u8(v.as_mut_ptr(), v.as_ptr(), d.as_ptr(), 4) };
+        assert_eq!(v, [255, 15, 255, 0]);
+        unsafe { super::saturating_sub_u8(v.as_mut_ptr(), v.as_ptr(), d.as_ptr(), 4) };
+        assert_eq!(v, [245, 5, 127, 0]);
+    }
+}
@@ -0,0 +1,123 @@
+//! Multi-threaded variants of `sum_u8` and `crc32c` for buffers of hundreds
+//! of megabytes and up (the `parallel` feature).  The buffer is cut into
+//! one contiguous piece per thread; the pieces run on scoped worker threads,
+//! with the calling thread taking the first, and the per-piece results are
+//! merged: sums by wrapping addition, CRCs with `crc32c_combine`.
+use std::thread;
+use std::vec::Vec;
+
+use crate::crc;
+
+/// Smallest piece worth a thread of its own; below this, starting and
+/// joining the thread costs more than the work it takes over.
+const MIN_PIECE: usize = 1 << 20;
+
+/// Number of pieces for `len` bytes when the caller asks for `nthreads`
+/// (0 = one per available CPU).
+fn pieces_for(len: usize, nthreads: usize) -> usize {
+    let want = match nthreads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    want.min(len / MIN_PIECE).max(1)
+}
+
+/// Apply `f` to `n` contiguous pieces of `data` (split on 64-byte
+/// boundaries), one thread each, and return the results in buffer order.
+fn map_pieces<R: Send>(data: &[u8], n: usize, f: impl Fn(&[u8]) -> R + Sync) -> Vec<R> {
+    let size = data.len().div_ceil(n).next_multiple_of(64);
+    let mut pieces = data.chunks(size);
+    let first = pieces.next().unwrap_or_default();
+    let f = &f;
+    thread::scope(|s| {
+        let workers: Vec<_> = pieces.map(|p| s.spawn(move || f(p))).collect();
+        let mut out = Vec::with_capacity(n);
+        out.push(f(first));
+        out.extend(workers.into_iter().map(|w| w.join().unwrap()));
+        out
+    })
+}
+
+/// `sum_u8` split across up to `nthreads` threads (0 = one per CPU).
+/// Buffers under 2 MiB, or `nthreads == 1`, run on the calling thread.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("sum_u8_parallel"))]
+pub unsafe extern "C" fn sum_u8_parallel(ptr: *const u8, len: usize, nthreads: usize) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    let n = pieces_for(len, nthreads);
+    if n == 1 {
+        return crate::auto::sum_u8(ptr, len);
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    map_pieces(data, n, |p| unsafe {
+        crate::auto::sum_u8(p.as_ptr(), p.len())
+    })
+    .into_iter()
+    .fold(0, u32::wrapping_add)
+}
+
+/// `crc32c` split across up to `nthreads` threads (0 = one per CPU); the
+/// CRCs of the pieces are combined in order.  Buffers under 2 MiB, or
+/// `nthreads == 1`, run on the calling thread.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("crc32c_parallel"))]
+pub unsafe extern "C" fn crc32c_parallel(
+    ptr: *const u8,
+    len: usize,
+    init: u32,
+    nthreads: usize,
+) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return init;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let n = pieces_for(len, nthreads);
+    if n == 1 {
+        return crc::crc32c_update(init, data);
+    }
+    map_pieces(data, n, |p| (crc::crc32c_update(0, p), p.len()))
+        .into_iter()
+        .fold(init, |acc, (c, l)| crc::crc32c_combine(acc, c, l))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let data: Vec<u8> = (0..5_000_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        for len in [
+            0usize,
+            1,
+            1000,
+            MIN_PIECE * 2 - 1,
+            MIN_PIECE * 2 + 77,
+            data.len(),
+        ] {
+            let d = &data[..len];
+            for nthreads in [0usize, 1, 2, 3, 8] {
+                unsafe {
+                    assert_eq!(
+                        sum_u8_parallel(d.as_ptr(), len, nthreads),
+                        crate::auto::sum_u8(d.as_ptr(), len),
+                        "sum len={len} threads={nthreads}"
+                    );
+                    assert_eq!(
+                        crc32c_parallel(d.as_ptr(), len, 0xdead_beef, nthreads),
+                        crc::crc32c_update(0xdead_beef, d),
+                        "crc len={len} threads={nthreads}"
+                    );
+                }
+            }
+        }
+    }
+}
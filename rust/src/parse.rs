@@ -0,0 +1,691 @@
+//! Decimal integer parsing: digits are validated a vector at a time and
+//! combined with a multiply-add tree instead of a per-digit loop.
+use core::simd::prelude::{SimdPartialOrd, SimdUint};
+use core::simd::{Simd, simd_swizzle};
+
+use crate::error::{SIMBA_ERR_INVALID_INPUT, SIMBA_ERR_OVERFLOW, fail};
+use crate::iov::Iovec;
+
+mod pow5;
+use pow5::{POW5_128, POW5_MIN_EXP};
+
+/// Digits combined per vector step.
+const DIGIT_LANES: usize = 16;
+
+/// Why a field failed to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ParseError {
+    /// Empty field or a non-digit at this index.
+    Invalid(usize),
+    /// The value does not fit the result type.
+    Overflow,
+}
+
+/// Index of the first byte of `s` that is not an ASCII digit.
+#[inline(always)]
+fn first_non_digit(s: &[u8]) -> Option<usize> {
+    let mut chunks = s.chunks_exact(DIGIT_LANES);
+    for (k, chunk) in (&mut chunks).enumerate() {
+        let d = Simd::<u8, DIGIT_LANES>::from_slice(chunk) - Simd::splat(b'0');
+        let bad = d.simd_gt(Simd::splat(9)).to_bitmask();
+        if bad != 0 {
+            return Some(k * DIGIT_LANES + bad.trailing_zeros() as usize);
+        }
+    }
+    let at = s.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|c| !c.is_ascii_digit())
+        .map(|i| at + i)
+}
+
+/// Value of at most 16 validated digits: pairs, then quads, then octets are
+/// combined lane-wise, so the whole number takes four multiply-adds.
+#[inline(always)]
+fn digits16(s: &[u8]) -> u64 {
+    let mut buf = [b'0'; DIGIT_LANES];
+    buf[DIGIT_LANES - s.len()..].copy_from_slice(s);
+    let d = Simd::from_array(buf) - Simd::splat(b'0');
+    let hi = simd_swizzle!(d, [0, 2, 4, 6, 8, 10, 12, 14]).cast::<u16>();
+    let lo = simd_swizzle!(d, [1, 3, 5, 7, 9, 11, 13, 15]).cast::<u16>();
+    let v = hi * Simd::splat(10) + lo;
+    let hi = simd_swizzle!(v, [0, 2, 4, 6]).cast::<u32>();
+    let lo = simd_swizzle!(v, [1, 3, 5, 7]).cast::<u32>();
+    let v = hi * Simd::splat(100) + lo;
+    let hi = simd_swizzle!(v, [0, 2]).cast::<u64>();
+    let lo = simd_swizzle!(v, [1, 3]).cast::<u64>();
+    let v = hi * Simd::splat(10_000) + lo;
+    v[0] * 100_000_000 + v[1]
+}
+
+/// Parse a field of ASCII digits (no sign, no whitespace) as a u64.
+#[inline(always)]
+pub(crate) fn parse_u64_digits(s: &[u8]) -> Result<u64, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::Invalid(0));
+    }
+    if let Some(at) = first_non_digit(s) {
+        return Err(ParseError::Invalid(at));
+    }
+    let zeros = s.iter().take_while(|&&c| c == b'0').count();
+    let s = &s[zeros..];
+    match s.len() {
+        0..=DIGIT_LANES => Ok(digits16(s)),
+        // u64::MAX has 20 digits, so the high part is at most 4 of them.
+        17..=20 => {
+            let (hi, lo) = s.split_at(s.len() - DIGIT_LANES);
+            let hi = hi.iter().fold(0u64, |x, &c| x * 10 + (c - b'0') as u64);
+            hi.checked_mul(10u64.pow(DIGIT_LANES as u32))
+                .and_then(|x| x.checked_add(digits16(lo)))
+                .ok_or(ParseError::Overflow)
+        }
+        _ => Err(ParseError::Overflow),
+    }
+}
+
+/// Parse `n` decimal fields into `out`, writing each field's status to
+/// `errs` (when non-null): 0, `SIMBA_ERR_INVALID_INPUT` for an empty field or
+/// one with a non-digit, or `SIMBA_ERR_OVERFLOW` for a value above
+/// `u64::MAX`.  Fields are digits only (leading zeros allowed); failed
+/// fields get `out[i] = 0`.
+///
+/// Returns the number of fields that failed.
+///
+/// # Safety
+/// `fields` must be valid for `n` entries, each null or valid for `len`
+/// bytes; `out` must be valid for `n` values and `errs` null or valid for `n`.
+#[unsafe(export_name = c_symbol!("parse_u64_batch"))]
+pub unsafe extern "C" fn parse_u64_batch(
+    fields: *const Iovec,
+    n: usize,
+    out: *mut u64,
+    errs: *mut i32,
+) -> usize {
+    if fields.is_null() || out.is_null() || n == 0 {
+        return 0;
+    }
+    let fields = core::slice::from_raw_parts(fields, n);
+    let mut failed = 0;
+    for (i, f) in fields.iter().enumerate() {
+        let s: &[u8] = if f.ptr.is_null() {
+            &[]
+        } else {
+            core::slice::from_raw_parts(f.ptr, f.len)
+        };
+        let (value, code) = match parse_u64_digits(s) {
+            Ok(x) => (x, 0),
+            Err(e) => {
+                failed += 1;
+                let code = match e {
+                    ParseError::Invalid(at) => fail(
+                        SIMBA_ERR_INVALID_INPUT,
+                        format_args!("field {i}: invalid digit at offset {at}"),
+                    ),
+                    ParseError::Overflow => fail(
+                        SIMBA_ERR_OVERFLOW,
+                        format_args!("field {i}: value out of range for u64"),
+                    ),
+                };
+                (0, code)
+            }
+        };
+        *out.add(i) = value;
+        if !errs.is_null() {
+            *errs.add(i) = code;
+        }
+    }
+    failed
+}
+
+/// Parse an optionally signed (`+` / `-`) decimal integer from the `len`
+/// bytes at `ptr` into `*out`.
+///
+/// Returns 0 on success.  On a malformed input (empty, a lone sign or a
+/// non-digit) returns `SIMBA_ERR_INVALID_INPUT` and stores the index of the
+/// first bad character in `*out` (`len` when the digits are missing).  On
+/// overflow returns `SIMBA_ERR_OVERFLOW` and stores `i64::MIN` or `i64::MAX`
+/// according to the sign.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+/// write.
+#[unsafe(export_name = c_symbol!("parse_i64"))]
+pub unsafe extern "C" fn parse_i64(ptr: *const u8, len: usize, out: *mut i64) -> i32 {
+    let s: &[u8] = if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    let (neg, digits) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let sign_len = s.len() - digits.len();
+    let value = parse_u64_digits(digits).and_then(|mag| match neg {
+        true if mag <= i64::MIN.unsigned_abs() => Ok((mag as i64).wrapping_neg()),
+        false if mag <= i64::MAX as u64 => Ok(mag as i64),
+        _ => Err(ParseError::Overflow),
+    });
+    match value {
+        Ok(x) => {
+            *out = x;
+            0
+        }
+        Err(ParseError::Invalid(at)) => {
+            *out = (sign_len + at) as i64;
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid character at offset {}", sign_len + at),
+            )
+        }
+        Err(ParseError::Overflow) => {
+            *out = if neg { i64::MIN } else { i64::MAX };
+            fail(
+                SIMBA_ERR_OVERFLOW,
+                format_args!("value out of range for i64"),
+            )
+        }
+    }
+}
+
+// === Floats ==================================================================
+
+// Decimal → f64 follows fast_float (Lemire, "Number Parsing at a Gigabyte per
+// Second", 2021): up to 19 significant digits are read into a u64 `w`, then
+// `w * 10^q` is rounded either exactly in f64 (Clinger's fast path) or from a
+// 128-bit product with a truncated power of five (Eisel–Lemire).  The rare
+// inputs that neither settles — halfway cases hidden by the truncation, or
+// more than 19 digits whose rounding depends on the dropped ones — go to
+// core's arbitrary-precision parser.
+
+/// Smallest 19-digit integer; accumulating stops once the mantissa reaches it.
+const MIN_19DIGIT_INT: u64 = 100_0000_0000_0000_0000;
+/// Explicit mantissa bits of an f64.
+const F64_SIG_BITS: u32 = 52;
+/// Biased exponent of infinity.
+const F64_INFINITE_POWER: i32 = 0x7FF;
+
+/// Powers of ten that are exact in an f64.
+const F64_EXACT_POW10: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// `mantissa * 10^exponent`, with `many_digits` set when digits beyond the
+/// first 19 significant ones were dropped from `mantissa`.
+struct DecimalFloat {
+    negative: bool,
+    mantissa: u64,
+    exponent: i64,
+    many_digits: bool,
+}
+
+/// Eight ASCII digits read little-endian from `s`, if they are all digits.
+#[inline(always)]
+fn eight_digits(s: &[u8]) -> Option<u64> {
+    let v = u64::from_le_bytes(s.get(..8)?.try_into().unwrap());
+    // Every byte is 0x3X and stays 0x3X after adding 6.
+    let high = 0xF0F0_F0F0_F0F0_F0F0;
+    let ok =
+        (v & high) | ((v.wrapping_add(0x0606_0606_0606_0606) & high) >> 4) == 0x3333_3333_3333_3333;
+    ok.then(|| {
+        // SWAR multiply-add: pairs, then quads, then the octet.
+        let v = v - 0x3030_3030_3030_3030;
+        let v = v * 10 + (v >> 8);
+        let mask = 0x0000_00FF_0000_00FF;
+        let (mul1, mul2) = (100 + (1_000_000 << 32), 1 + (10_000 << 32));
+        (v & mask)
+            .wrapping_mul(mul1)
+            .wrapping_add(((v >> 16) & mask).wrapping_mul(mul2))
+            >> 32
+    })
+}
+
+/// Read digits from `s[*i..]` into `mantissa` (wrapping) and advance `*i`.
+#[inline(always)]
+fn read_digits(s: &[u8], i: &mut usize, mantissa: &mut u64) {
+    while let Some(x) = eight_digits(&s[*i..]) {
+        *mantissa = mantissa.wrapping_mul(100_000_000).wrapping_add(x);
+        *i += 8;
+    }
+    while let Some(&c) = s.get(*i).filter(|c| c.is_ascii_digit()) {
+        *mantissa = mantissa.wrapping_mul(10).wrapping_add((c - b'0') as u64);
+        *i += 1;
+    }
+}
+
+/// Read digits from `s[*i..]` while `mantissa` is below 19 digits.
+#[inline(always)]
+fn read_19_digits(s: &[u8], i: &mut usize, mantissa: &mut u64) {
+    while *mantissa < MIN_19DIGIT_INT {
+        match s.get(*i) {
+            Some(&c) if c.is_ascii_digit() => {
+                *mantissa = *mantissa * 10 + (c - b'0') as u64;
+                *i += 1;
+            }
+            _ => break,
+        }
+    }
+}
+
+/// Split a decimal literal `[+-]digits[.digits][(e|E)[+-]digits]` (at least
+/// one mantissa digit) into its parts, or return the index of the first byte
+/// that does not fit the grammar.
+fn scan_decimal(s: &[u8]) -> Result<DecimalFloat, usize> {
+    let negative = s.first() == Some(&b'-');
+    let mut i = matches!(s.first(), Some(b'-' | b'+')) as usize;
+    let start = i;
+    let mut mantissa = 0u64;
+    read_digits(s, &mut i, &mut mantissa);
+    let int_end = i;
+    let mut n_digits = (int_end - start) as i64;
+    let mut exponent = 0i64;
+    if s.get(i) == Some(&b'.') {
+        i += 1;
+        let frac_start = i;
+        read_digits(s, &mut i, &mut mantissa);
+        exponent = -((i - frac_start) as i64);
+        n_digits -= exponent;
+    }
+    if n_digits == 0 {
+        return Err(i);
+    }
+    let mut exp_number = 0i64;
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        let neg_exp = s.get(i) == Some(&b'-');
+        i += matches!(s.get(i), Some(b'-' | b'+')) as usize;
+        if !s.get(i).is_some_and(u8::is_ascii_digit) {
+            return Err(i);
+        }
+        while let Some(&c) = s.get(i).filter(|c| c.is_ascii_digit()) {
+            // Anything past ±65536 is already zero or infinity.
+            if exp_number < 0x10000 {
+                exp_number = 10 * exp_number + (c - b'0') as i64;
+            }
+            i += 1;
+        }
+        if neg_exp {
+            exp_number = -exp_number;
+        }
+        exponent += exp_number;
+    }
+    if i != s.len() {
+        return Err(i);
+    }
+
+    let mut many_digits = false;
+    if n_digits > 19 {
+        // Leading zeros (and the point) do not count as significant.
+        n_digits -= s[start..]
+            .iter()
+            .take_while(|&&c| c == b'0' || c == b'.')
+            .filter(|&&c| c == b'0')
+            .count() as i64;
+        if n_digits > 19 {
+            many_digits = true;
+            mantissa = 0;
+            let mut j = start;
+            read_19_digits(s, &mut j, &mut mantissa);
+            exponent = if mantissa >= MIN_19DIGIT_INT {
+                (int_end - j) as i64
+            } else {
+                j += 1;
+                let frac_start = j;
+                read_19_digits(s, &mut j, &mut mantissa);
+                -((j - frac_start) as i64)
+            } + exp_number;
+        }
+    }
+    Ok(DecimalFloat {
+        negative,
+        mantissa,
+        exponent,
+        many_digits,
+    })
+}
+
+/// High and low words of `w * 5^q` to the precision needed for an f64,
+/// refined with the low word of the power only when the high word's
+/// truncated bits are all ones.
+#[inline(always)]
+fn pow5_product(q: i64, w: u64) -> (u64, u64) {
+    let mask = u64::MAX >> (F64_SIG_BITS + 3);
+    let (p_hi, p_lo) = POW5_128[(q - POW5_MIN_EXP) as usize];
+    let first = w as u128 * p_hi as u128;
+    let (mut hi, mut lo) = ((first >> 64) as u64, first as u64);
+    if hi & mask == mask {
+        let second_hi = ((w as u128 * p_lo as u128) >> 64) as u64;
+        lo = lo.wrapping_add(second_hi);
+        if second_hi > lo {
+            hi += 1;
+        }
+    }
+    (hi, lo)
+}
+
+/// Eisel–Lemire: the biased f64 `(mantissa, exponent)` nearest `w * 10^q`,
+/// or `None` when the truncated power of five leaves the rounding open.
+fn eisel_lemire(q: i64, mut w: u64) -> Option<(u64, i32)> {
+    if w == 0 || q < POW5_MIN_EXP {
+        return Some((0, 0));
+    }
+    if q > 308 {
+        return Some((0, F64_INFINITE_POWER));
+    }
+    let lz = w.leading_zeros();
+    w <<= lz;
+    let (hi, lo) = pow5_product(q, w);
+    // An all-ones low word may hide a carry, which only matters where
+    // 5^q is not exact in 128 bits.
+    if lo == u64::MAX && !(-27..=55).contains(&q) {
+        return None;
+    }
+    let upper_bit = (hi >> 63) as i32;
+    let shift = upper_bit + 64 - F64_SIG_BITS as i32 - 3;
+    let mut mantissa = hi >> shift;
+    // floor(log2(10^q)) + 63, the binary exponent of the product.
+    let log2 = ((q as i32).wrapping_mul(152_170 + 65_536) >> 16) + 63;
+    let mut power2 = log2 + upper_bit - lz as i32 + 1023;
+    if power2 <= 0 {
+        // Subnormal, or zero below 2^-1074.
+        if -power2 + 1 >= 64 {
+            return Some((0, 0));
+        }
+        mantissa >>= -power2 + 1;
+        mantissa += mantissa & 1;
+        mantissa >>= 1;
+        return Some((mantissa, (mantissa >= 1 << F64_SIG_BITS) as i32));
+    }
+    // An exact halfway product rounds to even rather than up.  Exactness is
+    // only possible where 5^q fits in 64 bits.
+    if lo <= 1 && (-4..=23).contains(&q) && mantissa & 3 == 1 && mantissa << shift == hi {
+        mantissa &= !1;
+    }
+    mantissa += mantissa & 1;
+    mantissa >>= 1;
+    if mantissa >= 2 << F64_SIG_BITS {
+        mantissa = 1 << F64_SIG_BITS;
+        power2 += 1;
+    }
+    mantissa &= !(1 << F64_SIG_BITS);
+    if power2 >= F64_INFINITE_POWER {
+        return Some((0, F64_INFINITE_POWER));
+    }
+    Some((mantissa, power2))
+}
+
+/// Magnitude of a scanned decimal, or `None` if only the slow path can
+/// round it.
+#[inline(always)]
+fn decimal_to_f64(d: &DecimalFloat) -> Option<f64> {
+    if !d.many_digits && (-22..=22).contains(&d.exponent) && d.mantissa <= 2 << F64_SIG_BITS {
+        // Both operands are exact, so one IEEE operation rounds correctly.
+        let m = d.mantissa as f64;
+        let p = F64_EXACT_POW10[d.exponent.unsigned_abs() as usize];
+        return Some(if d.exponent < 0 { m / p } else { m * p });
+    }
+    let fp = eisel_lemire(d.exponent, d.mantissa)?;
+    // With dropped digits the true value lies in [w, w + 1); both ends must
+    // round the same way.
+    if d.many_digits && eisel_lemire(d.exponent, d.mantissa + 1) != Some(fp) {
+        return None;
+    }
+    Some(f64::from_bits(fp.0 | (fp.1 as u64) << F64_SIG_BITS))
+}
+
+/// `inf`, `infinity` or `nan` (any case, optionally signed), as Go accepts.
+fn special_f64(s: &[u8]) -> Option<f64> {
+    let (negative, rest) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let v = if rest.eq_ignore_ascii_case(b"inf") || rest.eq_ignore_ascii_case(b"infinity") {
+        f64::INFINITY
+    } else if rest.eq_ignore_ascii_case(b"nan") {
+        f64::NAN
+    } else {
+        return None;
+    };
+    Some(if negative { -v } else { v })
+}
+
+/// Parse a decimal floating-point literal (`[+-]digits[.digits][e[+-]digits]`,
+/// or `inf` / `infinity` / `nan` in any case) from the `len` bytes at `ptr`
+/// into `*out`, rounded to the nearest f64 (ties to even).
+///
+/// Returns 0 on success.  On a malformed input returns
+/// `SIMBA_ERR_INVALID_INPUT` and stores the index of the first bad character
+/// in `*out`.  A finite literal beyond the f64 range returns
+/// `SIMBA_ERR_OVERFLOW` and stores ±infinity; underflow quietly rounds to
+/// (signed) zero.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for one
+/// write.
+#[unsafe(export_name = c_symbol!("parse_f64"))]
+pub unsafe extern "C" fn parse_f64(ptr: *const u8, len: usize, out: *mut f64) -> i32 {
+    let s: &[u8] = if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    };
+    let d = match scan_decimal(s) {
+        Ok(d) => d,
+        Err(at) => {
+            if let Some(v) = special_f64(s) {
+                *out = v;
+                return 0;
+            }
+            *out = at as f64;
+            return fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid character at offset {at}"),
+            );
+        }
+    };
+    let v = match decimal_to_f64(&d) {
+        Some(v) if d.negative => -v,
+        Some(v) => v,
+        // The scanner accepted only ASCII in a grammar core also parses.
+        None => core::str::from_utf8_unchecked(s)
+            .parse()
+            .unwrap_or(f64::NAN),
+    };
+    *out = v;
+    if v.is_infinite() {
+        return fail(
+            SIMBA_ERR_OVERFLOW,
+            format_args!("value out of range for f64"),
+        );
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_u64_digits() {
+        for x in [
+            0u64,
+            7,
+            10,
+            99,
+            12345678,
+            1 << 53,
+            9_999_999_999_999_999,
+            u64::MAX,
+        ] {
+            assert_eq!(parse_u64_digits(x.to_string().as_bytes()), Ok(x));
+        }
+        for len in 1..=20 {
+            let s: String = (0..len).map(|i| char::from(b'1' + (i % 9) as u8)).collect();
+            assert_eq!(
+                parse_u64_digits(s.as_bytes()),
+                Ok(s.parse().unwrap()),
+                "{s}"
+            );
+        }
+        assert_eq!(parse_u64_digits(b"000000000000000000000042"), Ok(42));
+        assert_eq!(
+            parse_u64_digits(b"18446744073709551616"),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            parse_u64_digits(b"100000000000000000000"),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(parse_u64_digits(b""), Err(ParseError::Invalid(0)));
+        assert_eq!(parse_u64_digits(b"12a4"), Err(ParseError::Invalid(2)));
+        assert_eq!(
+            parse_u64_digits(b"1234567890123456789/"),
+            Err(ParseError::Invalid(19))
+        );
+        assert_eq!(parse_u64_digits(b"-1"), Err(ParseError::Invalid(0)));
+    }
+
+    #[test]
+    fn test_parse_u64_batch() {
+        let texts: [&[u8]; 5] = [
+            b"42",
+            b"",
+            b"18446744073709551615",
+            b"1x",
+            b"99999999999999999999",
+        ];
+        let fields: Vec<Iovec> = texts
+            .iter()
+            .map(|t| Iovec {
+                ptr: t.as_ptr(),
+                len: t.len(),
+            })
+            .collect();
+        let (mut out, mut errs) = ([7u64; 5], [7i32; 5]);
+        let failed =
+            unsafe { parse_u64_batch(fields.as_ptr(), 5, out.as_mut_ptr(), errs.as_mut_ptr()) };
+        assert_eq!(failed, 3);
+        assert_eq!(out, [42, 0, u64::MAX, 0, 0]);
+        assert_eq!(
+            errs,
+            [
+                0,
+                SIMBA_ERR_INVALID_INPUT,
+                0,
+                SIMBA_ERR_INVALID_INPUT,
+                SIMBA_ERR_OVERFLOW
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_i64() {
+        let parse = |s: &str| {
+            let mut out = 0i64;
+            let rc = unsafe { parse_i64(s.as_ptr(), s.len(), &mut out) };
+            (rc, out)
+        };
+        for x in [0i64, 1, -1, 42, -9_876_543_210, i64::MAX, i64::MIN] {
+            assert_eq!(parse(&x.to_string()), (0, x));
+        }
+        assert_eq!(parse("+17"), (0, 17));
+        assert_eq!(parse("-0000000000000000000000012"), (0, -12));
+        assert_eq!(parse("9223372036854775808"), (SIMBA_ERR_OVERFLOW, i64::MAX));
+        assert_eq!(
+            parse("-9223372036854775809"),
+            (SIMBA_ERR_OVERFLOW, i64::MIN)
+        );
+        assert_eq!(
+            parse("-123456789012345678901234"),
+            (SIMBA_ERR_OVERFLOW, i64::MIN)
+        );
+        assert_eq!(parse(""), (SIMBA_ERR_INVALID_INPUT, 0));
+        assert_eq!(parse("-"), (SIMBA_ERR_INVALID_INPUT, 1));
+        assert_eq!(parse("+-1"), (SIMBA_ERR_INVALID_INPUT, 1));
+        assert_eq!(parse("-12 "), (SIMBA_ERR_INVALID_INPUT, 3));
+        assert_eq!(parse("12345678901234567x"), (SIMBA_ERR_INVALID_INPUT, 17));
+    }
+
+    #[test]
+    fn test_parse_f64() {
+        let parse = |s: &str| {
+            let mut out = 0f64;
+            let rc = unsafe { parse_f64(s.as_ptr(), s.len(), &mut out) };
+            (rc, out)
+        };
+        let cases = [
+            "0",
+            "-0",
+            "1",
+            "+1.5",
+            "3.141592653589793",
+            "1e308",
+            "1.7976931348623157e308",
+            "2.2250738585072014e-308",
+            "2.2250738585072011e-308",
+            "4.9e-324",
+            "2.4e-324",
+            "2.5e-324",
+            "1e-400",
+            ".5",
+            "5.",
+            "0.1",
+            "123456789012345678",
+            "9007199254740993",
+            "1.00000000000000011102230246251565404236316680908203125",
+            "1.00000000000000011102230246251565404236316680908203124",
+            "1.00000000000000011102230246251565404236316680908203126",
+            "0.000000000000000000000000000000000000000000001234567890123456789012",
+            "12345678901234567890123456789e-10",
+            "7.3177701707893310e+15",
+            "1E+22",
+            "1e23",
+            "8.988465674311579e307",
+            "2.718281828459045E0",
+            "-123.456e-7",
+            "00000000000000000000000.5",
+        ];
+        for s in cases {
+            let (rc, v) = parse(s);
+            assert_eq!(rc, 0, "{s}");
+            assert_eq!(v.to_bits(), s.parse::<f64>().unwrap().to_bits(), "{s}");
+        }
+        // Pseudo-random literals across the exponent range.
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        for _ in 0..20_000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let s = format!(
+                "{}e{}",
+                x % 100_000_000_000_000_000,
+                (x >> 40) as i64 % 340 - 170
+            );
+            assert_eq!(
+                parse(&s).1.to_bits(),
+                s.parse::<f64>().unwrap().to_bits(),
+                "{s}"
+            );
+            let f = f64::from_bits(x >> 1);
+            if f.is_finite() {
+                let s = format!("{f:e}");
+                assert_eq!(parse(&s), (0, f), "{s}");
+            }
+        }
+
+        assert_eq!(parse("-Infinity").1, f64::NEG_INFINITY);
+        assert!(parse("nan").1.is_nan());
+        assert_eq!(parse("1e309"), (SIMBA_ERR_OVERFLOW, f64::INFINITY));
+        assert_eq!(parse("-1e400"), (SIMBA_ERR_OVERFLOW, f64::NEG_INFINITY));
+        assert_eq!(parse(""), (SIMBA_ERR_INVALID_INPUT, 0.0));
+        assert_eq!(parse("-."), (SIMBA_ERR_INVALID_INPUT, 2.0));
+        assert_eq!(parse("1.5e"), (SIMBA_ERR_INVALID_INPUT, 4.0));
+        assert_eq!(parse("1.5e+x"), (SIMBA_ERR_INVALID_INPUT, 5.0));
+        assert_eq!(parse("12.34.5"), (SIMBA_ERR_INVALID_INPUT, 5.0));
+        assert_eq!(parse("0x10"), (SIMBA_ERR_INVALID_INPUT, 1.0));
+    }
+}
@@ -0,0 +1,218 @@
+//! Pipelined big-buffer variants of `sum_u8`, `crc32c` and
+//! `validate_u8_lut_pos`.  Once a buffer is well past L2 the kernels stall
+//! on memory rather than on arithmetic, so these split it into two halves
+//! walked in lockstep, block by block, with software prefetches issued a
+//! couple of blocks ahead in each half.  Two independent streams keep twice
+//! as many misses in flight as one; the per-half results are then merged
+//! (sums added, CRCs combined, the earliest error kept).
+//!
+//! The `flags` argument selects the mode: [`PIPELINE_AUTO`] pipelines from
+//! [`PIPELINE_THRESHOLD`] bytes up, [`PIPELINE_ON`] and [`PIPELINE_OFF`]
+//! force it either way (unknown values act as auto).  Prefetches are only issued on x86_64; elsewhere the
+//! two streams still interleave but rely on the hardware prefetcher.
+use crate::{NibbleLut, crc, nibble_lut_pos_impl, sum_u8_impl};
+
+/// `flags` value: pipeline buffers of at least [`PIPELINE_THRESHOLD`] bytes.
+pub const PIPELINE_AUTO: u32 = 0;
+/// `flags` value: always pipeline.
+pub const PIPELINE_ON: u32 = 1;
+/// `flags` value: never pipeline; same as the plain kernel.
+pub const PIPELINE_OFF: u32 = 2;
+
+/// Size from which [`PIPELINE_AUTO`] pipelines: larger than the L2 of
+/// current cores, so the buffer is streamed from L3 or DRAM.
+pub const PIPELINE_THRESHOLD: usize = 4 << 20;
+
+/// Bytes handled per stream per step.
+const PIPELINE_BLOCK: usize = 4096;
+/// How many blocks ahead of the current one each stream prefetches.
+const PREFETCH_BLOCKS: usize = 2;
+const PIPELINE_LANES: usize = 32;
+
+/// Whether `flags` picks the pipelined mode for a `len`-byte buffer.
+fn pipelined(len: usize, flags: u32) -> bool {
+    match flags {
+        PIPELINE_ON => true,
+        PIPELINE_OFF => false,
+        PIPELINE_AUTO => len >= PIPELINE_THRESHOLD,
+        // Unknown values behave like `PIPELINE_AUTO`.
+        _ => len >= PIPELINE_THRESHOLD,
+    }
+}
+
+/// Pull the block of `stream` starting at `at` (if any) towards L1.
+#[inline(always)]
+fn prefetch_block(stream: &[u8], at: usize) {
+    #[cfg(target_arch = "x86_64")]
+    if let Some(rest) = stream.get(at..) {
+        use core::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+        let block = &rest[..rest.len().min(PIPELINE_BLOCK)];
+        for line in block.chunks(64) {
+            unsafe { _mm_prefetch::<_MM_HINT_T0>(line.as_ptr() as *const i8) };
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = (stream, at);
+}
+
+/// Walk the two halves of `data` (split at a 64-byte offset) in lockstep,
+/// calling `step(stream, block)` for the next block of stream 0 and then of
+/// stream 1.  A stream whose `step` returns `false` is not visited again.
+#[inline(always)]
+fn two_streams(data: &[u8], mut step: impl FnMut(usize, &[u8]) -> bool) {
+    let (a, b) = data.split_at(second_half(data.len()));
+    let mut live = [true, true];
+    let mut at = 0;
+    while (live[0] && at < a.len()) || (live[1] && at < b.len()) {
+        let ahead = at + PREFETCH_BLOCKS * PIPELINE_BLOCK;
+        for (s, half) in [a, b].into_iter().enumerate() {
+            if !live[s] || at >= half.len() {
+                continue;
+            }
+            prefetch_block(half, ahead);
+            live[s] = step(s, &half[at..half.len().min(at + PIPELINE_BLOCK)]);
+        }
+        at += PIPELINE_BLOCK;
+    }
+}
+
+/// Offset of stream 1 within the buffer split by `two_streams`.
+fn second_half(len: usize) -> usize {
+    (len / 2) & !63
+}
+
+/// `sum_u8` with a big-buffer mode selected by `flags` (see the module docs).
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("sum_u8_pipelined"))]
+pub unsafe extern "C" fn sum_u8_pipelined(ptr: *const u8, len: usize, flags: u32) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    if !pipelined(len, flags) {
+        return crate::auto::sum_u8(ptr, len);
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let mut sums = [0u32; 2];
+    two_streams(data, |s, block| {
+        sums[s] = sums[s].wrapping_add(sum_u8_impl::<PIPELINE_LANES>(block));
+        true
+    });
+    sums[0].wrapping_add(sums[1])
+}
+
+/// `crc32c` with a big-buffer mode selected by `flags` (see the module docs):
+/// the CRCs of the two halves are combined at the end.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("crc32c_pipelined"))]
+pub unsafe extern "C" fn crc32c_pipelined(
+    ptr: *const u8,
+    len: usize,
+    init: u32,
+    flags: u32,
+) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return init;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    if !pipelined(len, flags) {
+        return crc::crc32c_update(init, data);
+    }
+    let mut crcs = [init, 0];
+    two_streams(data, |s, block| {
+        crcs[s] = crc::crc32c_update(crcs[s], block);
+        true
+    });
+    crc::crc32c_combine(crcs[0], crcs[1], len - second_half(len))
+}
+
+/// `validate_u8_lut_pos` with a big-buffer mode selected by `flags` (see the
+/// module docs).  Returns the index of the first byte whose table entry is
+/// zero, or -1 if every byte is valid.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes and `lut` for 256.
+#[unsafe(export_name = c_symbol!("validate_u8_lut_pos_pipelined"))]
+pub unsafe extern "C" fn validate_u8_lut_pos_pipelined(
+    ptr: *const u8,
+    len: usize,
+    lut: *const u8,
+    flags: u32,
+) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let set = NibbleLut::compile(core::slice::from_raw_parts(lut, 256));
+    if !pipelined(len, flags) {
+        return nibble_lut_pos_impl::<PIPELINE_LANES>(data, &set).map_or(-1, |i| i as isize);
+    }
+    // An error in the first half precedes anything in the second, so it
+    // ends both streams; one in the second half only ends that stream.
+    let base = [0, second_half(len)];
+    let mut done = [0usize; 2];
+    let mut found = [None; 2];
+    two_streams(data, |s, block| {
+        if found[0].is_some() {
+            return false;
+        }
+        found[s] =
+            nibble_lut_pos_impl::<PIPELINE_LANES>(block, &set).map(|i| base[s] + done[s] + i);
+        done[s] += block.len();
+        found[s].is_none()
+    });
+    found[0].or(found[1]).map_or(-1, |i| i as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipelined_matches_plain() {
+        let data: Vec<u8> = (0..300_000u32)
+            .map(|i| (i * 31 + i / 9) as u8 & 0x7f)
+            .collect();
+        let ascii: Vec<u8> = (0..=255u32).map(|b| (b < 0x80) as u8).collect();
+        for len in [0usize, 1, 63, 4095, 4096, 12_345, 100_000, 300_000] {
+            let d = &data[..len];
+            for flags in [PIPELINE_AUTO, PIPELINE_ON, PIPELINE_OFF] {
+                unsafe {
+                    assert_eq!(
+                        sum_u8_pipelined(d.as_ptr(), len, flags),
+                        crate::auto::sum_u8(d.as_ptr(), len),
+                        "sum len={len}"
+                    );
+                    assert_eq!(
+                        crc32c_pipelined(d.as_ptr(), len, 0x1234, flags),
+                        crc::crc32c_update(0x1234, d),
+                        "crc len={len}"
+                    );
+                    let pos = validate_u8_lut_pos_pipelined(d.as_ptr(), len, ascii.as_ptr(), flags);
+                    assert_eq!(pos, -1, "validate len={len}");
+                }
+            }
+        }
+
+        // Errors in either half, or both: the earliest one wins.
+        let mut bad = data.clone();
+        for errs in [
+            &[10usize][..],
+            &[200_000],
+            &[150_000, 290_000],
+            &[5_000, 160_000],
+        ] {
+            bad.copy_from_slice(&data);
+            for &e in errs {
+                bad[e] = 0x80;
+            }
+            let pos = unsafe {
+                validate_u8_lut_pos_pipelined(bad.as_ptr(), bad.len(), ascii.as_ptr(), PIPELINE_ON)
+            };
+            assert_eq!(pos, errs[0] as isize);
+        }
+    }
+}
@@ -0,0 +1,1301 @@
+//! Byte-search kernels (memchr-style first index of one or several needles,
+//! typed u16/u32/u64 element search, batched lower bounds over sorted u64s,
+//! occurrence counting, line offsets, field splitting, substring search,
+//! first mismatch, lexicographic compare, C strings).
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Mask, Simd, SupportedLaneCount};
+
+use crate::NibbleLut;
+
+/// Lane width for single-entry-point search kernels.
+const SEARCH_LANES: usize = 32;
+
+// === First occurrence (memchr) ===============================================
+
+#[inline(always)]
+pub(crate) unsafe fn index_of_u8_impl<const L: usize>(data: &[u8], needle: u8) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let splat = Simd::<u8, L>::splat(needle);
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let eq = Simd::<u8, L>::from_slice(chunk).simd_eq(splat);
+        if eq.any() {
+            return Some(i * L + eq.to_bitmask().trailing_zeros() as usize);
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|&b| b == needle)
+        .map(|i| base + i)
+}
+
+macro_rules! export_index_of {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Return the offset of the first byte equal to `needle` using a ", stringify!($lanes), "-lane SIMD kernel, or -1 if absent. Stops at the first matching chunk.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, needle: u8) -> isize {
+            if ptr.is_null() || len == 0 {
+                return -1;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            index_of_u8_impl::<$lanes>(data, needle).map_or(-1, |i| i as isize)
+        }
+    };
+}
+export_index_of!(index_of_u8_16, 16);
+export_index_of!(index_of_u8_32, 32);
+export_index_of!(index_of_u8_64, 64);
+
+// === First occurrence of any of several needles (memchr2/memchr3) ============
+
+#[inline(always)]
+unsafe fn index_of_any_impl<const L: usize, const K: usize>(
+    data: &[u8],
+    needles: [u8; K],
+) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let splats = needles.map(Simd::<u8, L>::splat);
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let v = Simd::<u8, L>::from_slice(chunk);
+        let mut hit = v.simd_eq(splats[0]);
+        for &s in &splats[1..] {
+            hit |= v.simd_eq(s);
+        }
+        if hit.any() {
+            return Some(i * L + hit.to_bitmask().trailing_zeros() as usize);
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|b| needles.contains(b))
+        .map(|i| base + i)
+}
+
+/// Return the offset of the first byte equal to `n1` or `n2` (memchr2), or
+/// -1 if neither occurs.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("index_of_u8_any2"))]
+pub unsafe extern "C" fn index_of_u8_any2(ptr: *const u8, len: usize, n1: u8, n2: u8) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    index_of_any_impl::<SEARCH_LANES, 2>(data, [n1, n2]).map_or(-1, |i| i as isize)
+}
+
+/// Return the offset of the first byte equal to `n1`, `n2` or `n3`
+/// (memchr3), or -1 if none occurs.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("index_of_u8_any3"))]
+pub unsafe extern "C" fn index_of_u8_any3(
+    ptr: *const u8,
+    len: usize,
+    n1: u8,
+    n2: u8,
+    n3: u8,
+) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    index_of_any_impl::<SEARCH_LANES, 3>(data, [n1, n2, n3]).map_or(-1, |i| i as isize)
+}
+
+// === Typed element search ====================================================
+
+macro_rules! export_typed_search {
+    ($t:ty, $index:ident, $masks:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Return the index (in elements) of the first `", stringify!($t), "` equal to `needle` using a ", stringify!($lanes), "-lane SIMD kernel, or -1 if absent.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` `", stringify!($t), "` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($index)))]
+        pub unsafe extern "C" fn $index(ptr: *const $t, len: usize, needle: $t) -> isize {
+            if ptr.is_null() || len == 0 {
+                return -1;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let splat = Simd::<$t, $lanes>::splat(needle);
+            let mut chunks = data.chunks_exact($lanes);
+            for (i, chunk) in (&mut chunks).enumerate() {
+                let eq = Simd::<$t, $lanes>::from_slice(chunk).simd_eq(splat);
+                if eq.any() {
+                    return (i * $lanes + eq.to_bitmask().trailing_zeros() as usize) as isize;
+                }
+            }
+            let base = len - chunks.remainder().len();
+            chunks
+                .remainder()
+                .iter()
+                .position(|&x| x == needle)
+                .map_or(-1, |i| (base + i) as isize)
+        }
+
+        #[doc = concat!(
+            "Generate one u64 equality bitmask per 64 `", stringify!($t), "` elements (bit `i` of word `w` is element `64 * w + i`). Writes and returns `len.div_ceil(64)` words; bits past the end of the array are zero.\n\n",
+            "# Safety\n",
+            "`ptr` must be valid for `len` `", stringify!($t), "` values; `out` must be valid for `len.div_ceil(64)` u64 writes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($masks)))]
+        pub unsafe extern "C" fn $masks(ptr: *const $t, len: usize, needle: $t, out: *mut u64) -> usize {
+            if ptr.is_null() || out.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let out = core::slice::from_raw_parts_mut(out, len.div_ceil(64));
+            let splat = Simd::<$t, 64>::splat(needle);
+            let mut chunks = data.chunks_exact(64);
+            for (chunk, o) in (&mut chunks).zip(out.iter_mut()) {
+                *o = Simd::<$t, 64>::from_slice(chunk).simd_eq(splat).to_bitmask();
+            }
+            let rem = chunks.remainder();
+            if !rem.is_empty() {
+                out[out.len() - 1] = rem
+                    .iter()
+                    .enumerate()
+                    .fold(0, |m, (i, &x)| m | (((x == needle) as u64) << i));
+            }
+            out.len()
+        }
+    };
+}
+export_typed_search!(u16, index_of_u16, eq_u16_masks, 32);
+export_typed_search!(u32, index_of_u32, eq_u32_masks, 16);
+export_typed_search!(u64, index_of_u64, eq_u64_masks, 8);
+
+// === Batch lower bound =======================================================
+
+/// Needles searched together by [`lower_bound_u64_batch`], one per lane.
+const BOUND_LANES: usize = 8;
+
+/// Lower bounds of each needle in `hay` (non-empty), written to `out`.  The
+/// search is branchless: every needle halves the same range length per
+/// step, so lanes stay in lockstep and only their bases differ.
+#[inline(always)]
+fn lower_bound_u64_impl(hay: &[u64], needles: &[u64], out: &mut [usize]) {
+    type V = Simd<u64, BOUND_LANES>;
+    type I = Simd<usize, BOUND_LANES>;
+    let mut chunks = needles.chunks_exact(BOUND_LANES);
+    let mut outs = out.chunks_exact_mut(BOUND_LANES);
+    for (chunk, o) in (&mut chunks).zip(&mut outs) {
+        let x = V::from_slice(chunk);
+        let mut base = I::splat(0);
+        let mut len = hay.len();
+        while len > 1 {
+            let half = len / 2;
+            let mid = base + I::splat(half);
+            let below = V::gather_or_default(hay, mid).simd_lt(x);
+            base = below.cast::<isize>().select(mid, base);
+            len -= half;
+        }
+        let below = V::gather_or_default(hay, base).simd_lt(x);
+        base += below.cast::<isize>().select(I::splat(1), I::splat(0));
+        o.copy_from_slice(base.as_array());
+    }
+    for (&x, o) in chunks.remainder().iter().zip(outs.into_remainder()) {
+        *o = hay.partition_point(|&v| v < x);
+    }
+}
+
+/// For each of the `n` needles, write to `out_indices` the index of the
+/// first element of the ascending `haystack` that is not less than it
+/// (`hlen` if there is none), like C++ `std::lower_bound`.  Eight needles
+/// are searched at once with branchless steps and gathered loads.
+///
+/// # Safety
+/// `haystack` must be null or valid for `hlen` values, `needles` for `n`
+/// values and `out_indices` for `n` writes.
+#[unsafe(export_name = c_symbol!("lower_bound_u64_batch"))]
+pub unsafe extern "C" fn lower_bound_u64_batch(
+    haystack: *const u64,
+    hlen: usize,
+    needles: *const u64,
+    n: usize,
+    out_indices: *mut usize,
+) {
+    if needles.is_null() || out_indices.is_null() || n == 0 {
+        return;
+    }
+    let out = core::slice::from_raw_parts_mut(out_indices, n);
+    if haystack.is_null() || hlen == 0 {
+        out.fill(0);
+        return;
+    }
+    let hay = core::slice::from_raw_parts(haystack, hlen);
+    lower_bound_u64_impl(hay, core::slice::from_raw_parts(needles, n), out);
+}
+
+// === Occurrence count ========================================================
+
+/// Number of bytes for which `member` holds.
+#[inline(always)]
+fn count_matching_impl<const L: usize>(
+    data: &[u8],
+    member: impl Fn(Simd<u8, L>) -> Mask<i8, L>,
+    member_scalar: impl Fn(u8) -> bool,
+) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let (one, zero) = (Simd::<u8, L>::splat(1), Simd::<u8, L>::splat(0));
+    let mut total = 0u64;
+    let mut chunks = data.chunks_exact(L);
+    // Per-lane u8 counters are flushed before they can wrap (255 steps).
+    loop {
+        let mut acc = zero;
+        let mut steps = 0;
+        for chunk in (&mut chunks).take(255) {
+            acc += member(Simd::from_slice(chunk)).select(one, zero);
+            steps += 1;
+        }
+        total += acc.cast::<u32>().reduce_sum() as u64;
+        if steps < 255 {
+            break;
+        }
+    }
+    total
+        + chunks
+            .remainder()
+            .iter()
+            .filter(|&&b| member_scalar(b))
+            .count() as u64
+}
+
+#[inline(always)]
+unsafe fn count_u8_impl<const L: usize>(data: &[u8], needle: u8) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let splat = Simd::<u8, L>::splat(needle);
+    count_matching_impl::<L>(data, |v| v.simd_eq(splat), |b| b == needle)
+}
+
+macro_rules! export_count_u8 {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Count the bytes equal to `needle` using a ", stringify!($lanes), "-lane SIMD kernel.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, needle: u8) -> u64 {
+            if ptr.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            count_u8_impl::<$lanes>(data, needle)
+        }
+    };
+}
+export_count_u8!(count_u8_16, 16);
+export_count_u8!(count_u8_32, 32);
+export_count_u8!(count_u8_64, 64);
+
+/// Count the members of a compiled class table.
+#[inline(always)]
+pub(crate) fn count_u8_set_impl(data: &[u8], set: &NibbleLut) -> u64 {
+    count_matching_impl::<SEARCH_LANES>(data, set.classifier::<SEARCH_LANES>(), |b| set.contains(b))
+}
+
+/// Count the bytes with a non-zero entry in the 256-byte class table `lut`
+/// (e.g. printable characters), without a map + sum round trip.  Inputs of a
+/// vector or more classify with nibble shuffles (see `NibbleLut`); shorter
+/// ones read `lut` directly.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `lut` must be valid for 256
+/// bytes.
+#[unsafe(export_name = c_symbol!("count_u8_lut"))]
+pub unsafe extern "C" fn count_u8_lut(ptr: *const u8, len: usize, lut: *const u8) -> u64 {
+    if ptr.is_null() || lut.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let lut = core::slice::from_raw_parts(lut, 256);
+    if data.len() < SEARCH_LANES {
+        return data.iter().filter(|&&b| lut[b as usize] != 0).count() as u64;
+    }
+    count_u8_set_impl(data, &NibbleLut::compile(lut))
+}
+
+// === Line offsets ============================================================
+
+/// Count `needle` bytes, writing the offsets of the first `out.len()` of them
+/// into `out`.  Returns the total count.
+#[inline(always)]
+unsafe fn byte_offsets_impl<const L: usize, T>(
+    data: &[u8],
+    needle: u8,
+    out: &mut [T],
+    conv: impl Fn(usize) -> T,
+) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let splat = Simd::<u8, L>::splat(needle);
+    let mut n = 0;
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let mut bits = Simd::<u8, L>::from_slice(chunk).simd_eq(splat).to_bitmask();
+        while bits != 0 {
+            if n == out.len() {
+                // Output is full: count the rest without recording it.
+                let rest = &data[(i + 1) * L..];
+                return (n + bits.count_ones() as usize) as u64 + count_u8_impl::<L>(rest, needle);
+            }
+            out[n] = conv(i * L + bits.trailing_zeros() as usize);
+            n += 1;
+            bits &= bits - 1;
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    for (k, _) in chunks
+        .remainder()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == needle)
+    {
+        if let Some(slot) = out.get_mut(n) {
+            *slot = conv(base + k);
+        }
+        n += 1;
+    }
+    n as u64
+}
+
+macro_rules! export_newline_offsets {
+    ($name:ident, $int:ty, $limit:literal) => {
+        #[doc = concat!(
+            "Count the `\\n` bytes in the buffer and, if `out` is non-null, write the byte offsets of the first `cap` of them as `", stringify!($int), "` values. Returns the total number of newlines, which exceeds `cap` when the output was truncated.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes", $limit, "; `out` must be null or valid for `cap` writes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize, out: *mut $int, cap: usize) -> u64 {
+            if ptr.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            if out.is_null() || cap == 0 {
+                return count_u8_impl::<SEARCH_LANES>(data, b'\n');
+            }
+            let out = core::slice::from_raw_parts_mut(out, cap);
+            byte_offsets_impl::<SEARCH_LANES, $int>(data, b'\n', out, |i| i as $int)
+        }
+    };
+}
+export_newline_offsets!(newline_offsets_u32, u32, ", with `len <= u32::MAX`");
+export_newline_offsets!(newline_offsets_u64, u64, "");
+
+// === Field splitting =========================================================
+
+/// Split `data` on `needle`, writing the `[start, end)` offsets of the first
+/// `out.len()` fields into `out`.  Returns the total number of fields.
+#[inline(always)]
+unsafe fn split_u8_impl<const L: usize>(data: &[u8], needle: u8, out: &mut [[u32; 2]]) -> usize
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let splat = Simd::<u8, L>::splat(needle);
+    let (mut n, mut start) = (0, 0);
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let mut bits = Simd::<u8, L>::from_slice(chunk).simd_eq(splat).to_bitmask();
+        while bits != 0 {
+            if n == out.len() {
+                // Output is full: count the remaining fields without
+                // recording them.
+                let rest = &data[(i + 1) * L..];
+                return n
+                    + bits.count_ones() as usize
+                    + 1
+                    + count_u8_impl::<L>(rest, needle) as usize;
+            }
+            let end = i * L + bits.trailing_zeros() as usize;
+            out[n] = [start as u32, end as u32];
+            (n, start) = (n + 1, end + 1);
+            bits &= bits - 1;
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    for (k, _) in chunks
+        .remainder()
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == needle)
+    {
+        if let Some(slot) = out.get_mut(n) {
+            *slot = [start as u32, (base + k) as u32];
+        }
+        (n, start) = (n + 1, base + k + 1);
+    }
+    if let Some(slot) = out.get_mut(n) {
+        *slot = [start as u32, data.len() as u32];
+    }
+    n + 1
+}
+
+/// Split the buffer on `delim` in one pass and, if `out` is non-null, write
+/// the `(start, end)` byte offsets of the first `cap` fields as `u32` pairs
+/// (`out[2k]`, `out[2k + 1]`; `end` is exclusive).  `n` delimiters make
+/// `n + 1` fields, empty ones included, like Go's `bytes.Split`.  Returns the
+/// total number of fields, which exceeds `cap` when the output was truncated.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes, with `len <= u32::MAX`;
+/// `out` must be null or valid for `2 * cap` writes.
+#[unsafe(export_name = c_symbol!("split_u8"))]
+pub unsafe extern "C" fn split_u8(
+    ptr: *const u8,
+    len: usize,
+    delim: u8,
+    out: *mut u32,
+    cap: usize,
+) -> usize {
+    if ptr.is_null() || len == 0 {
+        if !out.is_null() && cap > 0 {
+            *out.add(0) = 0;
+            *out.add(1) = 0;
+        }
+        return 1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    if out.is_null() || cap == 0 {
+        return count_u8_impl::<SEARCH_LANES>(data, delim) as usize + 1;
+    }
+    let out = core::slice::from_raw_parts_mut(out as *mut [u32; 2], cap);
+    split_u8_impl::<SEARCH_LANES>(data, delim, out)
+}
+
+// === Substring search (memmem) ===============================================
+
+/// Find `needle` in `hay` by filtering candidate windows on two needle bytes
+/// (at offsets `i1` and `i2`) with SIMD compares, then verifying each
+/// candidate.  Probing two distant or rare bytes keeps false positives low.
+#[inline(always)]
+pub(crate) unsafe fn find_substring_impl<const L: usize>(
+    hay: &[u8],
+    needle: &[u8],
+    i1: usize,
+    i2: usize,
+) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let n = needle.len();
+    if n == 0 {
+        return Some(0);
+    }
+    if n > hay.len() {
+        return None;
+    }
+    if n == 1 {
+        return index_of_u8_impl::<L>(hay, needle[0]);
+    }
+    let starts = hay.len() - n + 1;
+    let (b1, b2) = (
+        Simd::<u8, L>::splat(needle[i1]),
+        Simd::<u8, L>::splat(needle[i2]),
+    );
+    let mut i = 0;
+    while i + L <= starts {
+        let v1 = Simd::<u8, L>::from_slice(&hay[i + i1..i + i1 + L]);
+        let v2 = Simd::<u8, L>::from_slice(&hay[i + i2..i + i2 + L]);
+        let mut bits = (v1.simd_eq(b1) & v2.simd_eq(b2)).to_bitmask();
+        while bits != 0 {
+            let p = i + bits.trailing_zeros() as usize;
+            if &hay[p..p + n] == needle {
+                return Some(p);
+            }
+            bits &= bits - 1;
+        }
+        i += L;
+    }
+    (i..starts).find(|&p| hay[p + i1] == needle[i1] && &hay[p..p + n] == needle)
+}
+
+/// Return the offset of the first occurrence of `needle` in `haystack`, or -1
+/// if absent.  An empty needle matches at 0.  Candidates are filtered on the
+/// needle's first and last bytes with 32-lane SIMD compares and then verified.
+///
+/// # Safety
+/// `haystack`/`needle` must be null or valid for `hlen`/`nlen` bytes.
+#[unsafe(export_name = c_symbol!("find_substring"))]
+pub unsafe extern "C" fn find_substring(
+    haystack: *const u8,
+    hlen: usize,
+    needle: *const u8,
+    nlen: usize,
+) -> isize {
+    let hay = if haystack.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(haystack, hlen)
+    };
+    let needle = if needle.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(needle, nlen)
+    };
+    let last = needle.len().saturating_sub(1);
+    find_substring_impl::<SEARCH_LANES>(hay, needle, 0, last).map_or(-1, |i| i as isize)
+}
+
+/// Count the non-overlapping occurrences of `needle` in `haystack`, scanning
+/// left to right like Go's `bytes.Count`; candidates are filtered with the
+/// same SIMD probe as [`find_substring`].  An empty needle matches at every
+/// position and yields `hlen + 1`.
+///
+/// # Safety
+/// `haystack`/`needle` must be null or valid for `hlen`/`nlen` bytes.
+#[unsafe(export_name = c_symbol!("count_substring"))]
+pub unsafe extern "C" fn count_substring(
+    haystack: *const u8,
+    hlen: usize,
+    needle: *const u8,
+    nlen: usize,
+) -> u64 {
+    let hay = if haystack.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(haystack, hlen)
+    };
+    let needle = if needle.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(needle, nlen)
+    };
+    match needle.len() {
+        0 => hay.len() as u64 + 1,
+        1 => count_u8_impl::<SEARCH_LANES>(hay, needle[0]),
+        n => {
+            let mut count = 0;
+            let mut at = 0;
+            while let Some(p) = find_substring_impl::<SEARCH_LANES>(&hay[at..], needle, 0, n - 1) {
+                count += 1;
+                at += p + n;
+            }
+            count
+        }
+    }
+}
+
+// === Precompiled searcher ====================================================
+
+#[cfg(feature = "alloc")]
+/// Rough frequency rank of a byte in typical text/binary payloads (higher is
+/// more common); used to pick probe bytes that rarely produce candidates.
+#[inline(always)]
+fn byte_rank(b: u8) -> u8 {
+    match b {
+        b' ' => 255,
+        b'e' | b't' | b'a' | b'o' | b'i' | b'n' | b's' | b'r' | b'h' => 220,
+        b'a'..=b'z' => 180,
+        0 => 170,
+        b'0'..=b'9' => 150,
+        b'A'..=b'Z' => 130,
+        b'.' | b',' | b'/' | b'-' | b'_' | b'"' | b'\n' | b':' | b'=' => 140,
+        0x21..=0x7E => 90,
+        _ => 60,
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Needle plus the offsets of the two probe bytes used by the SIMD filter.
+pub struct Searcher {
+    needle: Vec<u8>,
+    i1: usize,
+    i2: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Searcher {
+    fn new(needle: &[u8]) -> Self {
+        let n = needle.len();
+        let rarest = |skip: Option<usize>| {
+            (0..n)
+                .filter(|&i| skip.is_none_or(|j| i != j && needle[i] != needle[j]))
+                .min_by_key(|&i| byte_rank(needle[i]))
+        };
+        // Fall back to first/last when the needle has a single distinct byte.
+        let (i1, i2) = match rarest(None) {
+            Some(a) => match rarest(Some(a)) {
+                Some(b) => (a.min(b), a.max(b)),
+                None => (0, n - 1),
+            },
+            None => (0, 0),
+        };
+        Searcher {
+            needle: needle.to_vec(),
+            i1,
+            i2,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Compile `needle` into a reusable searcher.  Needle-derived state (a copy
+/// of the needle and the choice of the two rarest bytes to filter on) is
+/// computed once here instead of on every search.  Release the handle with
+/// [`searcher_free`].
+///
+/// # Safety
+/// `needle` must be null or valid for `nlen` bytes.
+#[unsafe(export_name = c_symbol!("searcher_compile"))]
+pub unsafe extern "C" fn searcher_compile(needle: *const u8, nlen: usize) -> *mut Searcher {
+    let needle = if needle.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(needle, nlen)
+    };
+    Box::into_raw(Box::new(Searcher::new(needle)))
+}
+
+#[cfg(feature = "alloc")]
+/// Return the offset of the first occurrence of the compiled needle in
+/// `haystack`, or -1 if absent (or `s` is null).
+///
+/// # Safety
+/// `s` must be a live handle from [`searcher_compile`]; `haystack` must be
+/// null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("searcher_find"))]
+pub unsafe extern "C" fn searcher_find(
+    s: *const Searcher,
+    haystack: *const u8,
+    len: usize,
+) -> isize {
+    let Some(s) = s.as_ref() else {
+        return -1;
+    };
+    let hay = if haystack.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(haystack, len)
+    };
+    find_substring_impl::<SEARCH_LANES>(hay, &s.needle, s.i1, s.i2).map_or(-1, |i| i as isize)
+}
+
+#[cfg(feature = "alloc")]
+/// Release a handle from [`searcher_compile`].  Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("searcher_free"))]
+pub unsafe extern "C" fn searcher_free(s: *mut Searcher) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+// === First mismatch ==========================================================
+
+#[inline(always)]
+unsafe fn mismatch_index_impl<const L: usize>(a: &[u8], b: &[u8]) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut ca = a.chunks_exact(L);
+    let mut cb = b.chunks_exact(L);
+    for (i, (x, y)) in (&mut ca).zip(&mut cb).enumerate() {
+        let ne = Simd::<u8, L>::from_slice(x).simd_ne(Simd::from_slice(y));
+        if ne.any() {
+            return Some(i * L + ne.to_bitmask().trailing_zeros() as usize);
+        }
+    }
+    let base = a.len() - ca.remainder().len();
+    ca.remainder()
+        .iter()
+        .zip(cb.remainder())
+        .position(|(x, y)| x != y)
+        .map(|i| base + i)
+}
+
+/// Return the index of the first byte where `a` and `b` differ, or -1 if the
+/// first `len` bytes are equal.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("mismatch_index"))]
+pub unsafe extern "C" fn mismatch_index(a: *const u8, b: *const u8, len: usize) -> isize {
+    if a.is_null() || b.is_null() || len == 0 {
+        return -1;
+    }
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    mismatch_index_impl::<SEARCH_LANES>(a, b).map_or(-1, |i| i as isize)
+}
+
+/// memcmp-style alias of [`mismatch_index`]: -1 when the buffers are equal,
+/// otherwise the index of the first mismatching byte.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("first_diff_u8"))]
+pub unsafe extern "C" fn first_diff_u8(a: *const u8, b: *const u8, len: usize) -> isize {
+    mismatch_index(a, b, len)
+}
+
+// === Lexicographic compare ===================================================
+
+/// Compare two byte strings lexicographically (unsigned bytes, shorter prefix
+/// sorts first), like Go's `bytes.Compare`.  Returns -1, 0 or 1.
+///
+/// # Safety
+/// `a`/`b` must be null or valid for `alen`/`blen` bytes.  A null pointer is
+/// treated as an empty string.
+#[unsafe(export_name = c_symbol!("compare_u8"))]
+pub unsafe extern "C" fn compare_u8(a: *const u8, alen: usize, b: *const u8, blen: usize) -> i32 {
+    let a = if a.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(a, alen)
+    };
+    let b = if b.is_null() {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(b, blen)
+    };
+    let n = a.len().min(b.len());
+    let ord = match mismatch_index_impl::<SEARCH_LANES>(&a[..n], &b[..n]) {
+        Some(i) => a[i].cmp(&b[i]),
+        None => a.len().cmp(&b.len()),
+    };
+    ord as i32
+}
+
+// === C strings ===============================================================
+
+#[inline(always)]
+unsafe fn cstr_len_impl<const L: usize>(data: &[u8]) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let mut chunks = data.chunks_exact(L);
+    for (i, chunk) in (&mut chunks).enumerate() {
+        let z = Simd::<u8, L>::from_slice(chunk).simd_eq(Simd::splat(0));
+        if z.any() {
+            return Some(i * L + z.to_bitmask().trailing_zeros() as usize);
+        }
+    }
+    let base = data.len() - chunks.remainder().len();
+    chunks
+        .remainder()
+        .iter()
+        .position(|&b| b == 0)
+        .map(|i| base + i)
+}
+
+/// Return the length of the NUL-terminated string at `ptr`, scanning at most
+/// `max_scan` bytes, or -1 if no NUL occurs within the bound.
+///
+/// # Safety
+/// `ptr` must be null or valid for `max_scan` bytes (the whole field, even if
+/// the terminator comes earlier): the kernel reads full vectors.
+#[unsafe(export_name = c_symbol!("cstr_len"))]
+pub unsafe extern "C" fn cstr_len(ptr: *const u8, max_scan: usize) -> isize {
+    if ptr.is_null() || max_scan == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, max_scan);
+    cstr_len_impl::<SEARCH_LANES>(data).map_or(-1, |n| n as isize)
+}
+
+/// `strnlen` semantics: the offset of the first NUL in `ptr[..maxlen]`, or
+/// `maxlen` if there is none (0 for null).
+///
+/// # Safety
+/// `ptr` must be null or valid for `maxlen` bytes (the whole field, even if
+/// the terminator comes earlier): the kernel reads full vectors.
+#[unsafe(export_name = c_symbol!("strnlen_u8"))]
+pub unsafe extern "C" fn strnlen_u8(ptr: *const u8, maxlen: usize) -> usize {
+    if ptr.is_null() || maxlen == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, maxlen);
+    cstr_len_impl::<SEARCH_LANES>(data).unwrap_or(maxlen)
+}
+
+#[inline(always)]
+unsafe fn cstr_eq_impl<const L: usize>(a: &[u8], b: &[u8]) -> bool
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    // Stop at the first lane that either differs or terminates `a`; the
+    // strings are equal iff that lane is a shared NUL (or there is none).
+    let mut ca = a.chunks_exact(L);
+    let mut cb = b.chunks_exact(L);
+    for (x, y) in (&mut ca).zip(&mut cb) {
+        let va = Simd::<u8, L>::from_slice(x);
+        let ne = va.simd_ne(Simd::from_slice(y)).to_bitmask();
+        let nul = va.simd_eq(Simd::splat(0)).to_bitmask();
+        let stop = ne | nul;
+        if stop != 0 {
+            return ne & (stop & stop.wrapping_neg()) == 0;
+        }
+    }
+    for (&x, &y) in ca.remainder().iter().zip(cb.remainder()) {
+        if x != y {
+            return false;
+        }
+        if x == 0 {
+            return true;
+        }
+    }
+    true
+}
+
+/// Compare two NUL-terminated strings over at most `max` bytes, like
+/// `strncmp(a, b, max) == 0`.  Returns 1 if equal, 0 otherwise.
+///
+/// # Safety
+/// `a` and `b` must be null or valid for `max` bytes each.  Two null
+/// pointers compare equal; a null and a non-null pointer do not.
+#[unsafe(export_name = c_symbol!("cstr_eq"))]
+pub unsafe extern "C" fn cstr_eq(a: *const u8, b: *const u8, max: usize) -> u8 {
+    if a.is_null() || b.is_null() {
+        return (a.is_null() && b.is_null()) as u8;
+    }
+    if max == 0 {
+        return 1;
+    }
+    let a = core::slice::from_raw_parts(a, max);
+    let b = core::slice::from_raw_parts(b, max);
+    cstr_eq_impl::<SEARCH_LANES>(a, b) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_index_of_u8() {
+        let mut data = [b'.'; 200];
+        unsafe {
+            assert_eq!(super::index_of_u8_32(data.as_ptr(), data.len(), b'x'), -1);
+            for pos in [0usize, 15, 16, 63, 64, 130, 199] {
+                data.fill(b'.');
+                data[pos] = b'x';
+                data[199] = b'x';
+                let want = pos as isize;
+                assert_eq!(super::index_of_u8_16(data.as_ptr(), data.len(), b'x'), want);
+                assert_eq!(super::index_of_u8_32(data.as_ptr(), data.len(), b'x'), want);
+                assert_eq!(super::index_of_u8_64(data.as_ptr(), data.len(), b'x'), want);
+            }
+        }
+    }
+
+    #[test]
+    fn test_index_of_u8_any() {
+        let line = b"field one,field two\r\nnext".repeat(3);
+        unsafe {
+            let p = line.as_ptr();
+            assert_eq!(super::index_of_u8_any2(p, line.len(), b'\n', b','), 9);
+            assert_eq!(super::index_of_u8_any2(p, line.len(), b'\n', b'\r'), 19);
+            assert_eq!(
+                super::index_of_u8_any3(p, line.len(), b'#', b'\n', b'\r'),
+                19
+            );
+            assert_eq!(super::index_of_u8_any3(p, line.len(), b'#', b'!', b'?'), -1);
+            // Match only in the scalar tail.
+            let mut tail = [b'a'; 41];
+            tail[40] = b'z';
+            assert_eq!(
+                super::index_of_u8_any2(tail.as_ptr(), tail.len(), b'y', b'z'),
+                40
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_u8() {
+        // Long enough to flush the per-lane counters several times.
+        let data: Vec<u8> = (0..200_003u32).map(|i| (i % 7) as u8).collect();
+        let want = data.iter().filter(|&&b| b == 3).count() as u64;
+        unsafe {
+            assert_eq!(super::count_u8_16(data.as_ptr(), data.len(), 3), want);
+            assert_eq!(super::count_u8_32(data.as_ptr(), data.len(), 3), want);
+            assert_eq!(super::count_u8_64(data.as_ptr(), data.len(), 3), want);
+            let all = [9u8; 64 * 300];
+            assert_eq!(
+                super::count_u8_64(all.as_ptr(), all.len(), 9),
+                all.len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_u8_lut() {
+        let printable: Vec<u8> = (0..=255u8)
+            .map(|b| (0x20..0x7F).contains(&b) as u8)
+            .collect();
+        let data: Vec<u8> = (0..100_001u32).map(|i| (i * 31 % 251) as u8).collect();
+        let want = data.iter().filter(|&&b| printable[b as usize] != 0).count() as u64;
+        unsafe {
+            assert_eq!(
+                super::count_u8_lut(data.as_ptr(), data.len(), printable.as_ptr()),
+                want
+            );
+            assert_eq!(super::count_u8_lut(data.as_ptr(), 0, printable.as_ptr()), 0);
+            let all = [b'x'; 32 * 300 + 5];
+            assert_eq!(
+                super::count_u8_lut(all.as_ptr(), all.len(), printable.as_ptr()),
+                all.len() as u64
+            );
+
+            // More than eight distinct rows need both shuffle banks; short
+            // inputs take the scalar path.
+            let scattered: Vec<u8> = (0..=255u32).map(|b| (b * 37 % 11 < 4) as u8).collect();
+            for len in [0, 5, 31, 32, 33, 1000, data.len()] {
+                let want = data[..len]
+                    .iter()
+                    .filter(|&&b| scattered[b as usize] != 0)
+                    .count() as u64;
+                assert_eq!(
+                    super::count_u8_lut(data.as_ptr(), len, scattered.as_ptr()),
+                    want,
+                    "len={len}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mismatch_index() {
+        let a: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        unsafe {
+            assert_eq!(super::mismatch_index(a.as_ptr(), a.as_ptr(), a.len()), -1);
+            for pos in [0usize, 1, 31, 32, 33, 64, 250, 299] {
+                let mut b = a.clone();
+                b[pos] ^= 0x40;
+                b[299] ^= 1; // a later difference must not win
+                let want = pos.min(299) as isize;
+                assert_eq!(super::mismatch_index(a.as_ptr(), b.as_ptr(), a.len()), want);
+                assert_eq!(super::first_diff_u8(a.as_ptr(), b.as_ptr(), a.len()), want);
+            }
+            assert_eq!(super::mismatch_index(core::ptr::null(), a.as_ptr(), 0), -1);
+        }
+    }
+
+    #[test]
+    fn test_compare_u8() {
+        let cases: [(&[u8], &[u8]); 7] = [
+            (b"", b""),
+            (b"", b"a"),
+            (b"abc", b"abd"),
+            (b"abc", b"ab"),
+            (b"\xff", b"\x01"),
+            (&[7; 100], &[7; 100]),
+            (&[7; 100], &[7; 101]),
+        ];
+        for (a, b) in cases {
+            let want = a.cmp(b) as i32;
+            let got = unsafe { super::compare_u8(a.as_ptr(), a.len(), b.as_ptr(), b.len()) };
+            assert_eq!(got, want, "{:?} vs {:?}", a, b);
+            let rev = unsafe { super::compare_u8(b.as_ptr(), b.len(), a.as_ptr(), a.len()) };
+            assert_eq!(rev, -want);
+        }
+        let mut long = vec![1u8; 1000];
+        let other = long.clone();
+        long[777] = 0;
+        assert_eq!(
+            unsafe { super::compare_u8(long.as_ptr(), 1000, other.as_ptr(), 1000) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_cstr_len() {
+        let mut field = [b'x'; 100];
+        unsafe {
+            assert_eq!(super::cstr_len(field.as_ptr(), field.len()), -1);
+            for pos in [0usize, 5, 31, 32, 63, 99] {
+                field = [b'x'; 100];
+                field[pos] = 0;
+                field[99] = 0;
+                assert_eq!(super::cstr_len(field.as_ptr(), field.len()), pos as isize);
+            }
+            assert_eq!(super::cstr_len(field.as_ptr(), 50), -1);
+        }
+    }
+
+    #[test]
+    fn test_strnlen_u8() {
+        let mut field = [b'x'; 100];
+        unsafe {
+            assert_eq!(super::strnlen_u8(field.as_ptr(), field.len()), 100);
+            assert_eq!(super::strnlen_u8(core::ptr::null(), 10), 0);
+            for pos in [0usize, 5, 31, 32, 63, 64, 99] {
+                field = [b'x'; 100];
+                field[pos] = 0;
+                assert_eq!(super::strnlen_u8(field.as_ptr(), field.len()), pos);
+                assert_eq!(super::strnlen_u8(field.as_ptr(), 40), pos.min(40));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cstr_eq() {
+        let mut a = [0u8; 80];
+        let mut b = [0u8; 80];
+        a[..40].copy_from_slice(&[b'q'; 40]);
+        b[..40].copy_from_slice(&[b'q'; 40]);
+        // Garbage after the terminator must be ignored.
+        a[41..].fill(1);
+        b[41..].fill(2);
+        unsafe {
+            assert_eq!(super::cstr_eq(a.as_ptr(), b.as_ptr(), 80), 1);
+            b[39] = b'r';
+            assert_eq!(super::cstr_eq(a.as_ptr(), b.as_ptr(), 80), 0);
+            assert_eq!(super::cstr_eq(a.as_ptr(), b.as_ptr(), 39), 1);
+            b[39] = 0; // b is a strict prefix of a
+            assert_eq!(super::cstr_eq(a.as_ptr(), b.as_ptr(), 80), 0);
+            assert_eq!(super::cstr_eq(core::ptr::null(), core::ptr::null(), 4), 1);
+        }
+    }
+
+    #[test]
+    fn test_newline_offsets() {
+        let text: Vec<u8> = (0..3000u32)
+            .map(|i| if (i * 7919) % 53 < 3 { b'\n' } else { b'a' })
+            .collect();
+        let want: Vec<u64> = (0..text.len())
+            .filter(|&i| text[i] == b'\n')
+            .map(|i| i as u64)
+            .collect();
+        for cap in [0, 1, 5, want.len() - 1, want.len(), want.len() + 10] {
+            let mut out = vec![u64::MAX; cap];
+            let mut out32 = vec![u32::MAX; cap];
+            unsafe {
+                let n =
+                    super::newline_offsets_u64(text.as_ptr(), text.len(), out.as_mut_ptr(), cap);
+                assert_eq!(n, want.len() as u64);
+                let n32 =
+                    super::newline_offsets_u32(text.as_ptr(), text.len(), out32.as_mut_ptr(), cap);
+                assert_eq!(n32, want.len() as u64);
+            }
+            let k = cap.min(want.len());
+            assert_eq!(&out[..k], &want[..k], "cap={}", cap);
+            assert!(out32[..k].iter().zip(&want).all(|(&a, &b)| a as u64 == b));
+            assert!(out[k..].iter().all(|&x| x == u64::MAX));
+        }
+        let n = unsafe {
+            super::newline_offsets_u64(text.as_ptr(), text.len(), core::ptr::null_mut(), 0)
+        };
+        assert_eq!(n, want.len() as u64);
+    }
+
+    #[test]
+    fn test_split_u8() {
+        let split = |data: &[u8], cap: usize| {
+            let mut out = vec![0u32; 2 * cap];
+            let n =
+                unsafe { super::split_u8(data.as_ptr(), data.len(), b',', out.as_mut_ptr(), cap) };
+            let pairs: Vec<(u32, u32)> = out.chunks(2).map(|p| (p[0], p[1])).collect();
+            (n, pairs)
+        };
+        assert_eq!(split(b"a,bc,,d", 8).0, 4);
+        assert_eq!(
+            split(b"a,bc,,d", 8).1[..4],
+            [(0, 1), (2, 4), (5, 5), (6, 7)]
+        );
+        assert_eq!(split(b",", 2), (2, vec![(0, 0), (1, 1)]));
+        assert_eq!(split(b"", 1), (1, vec![(0, 0)]));
+        assert_eq!(split(b"abc", 1), (1, vec![(0, 3)]));
+
+        // Long input through the vector path, and truncated output.
+        let data: Vec<u8> = (0..500)
+            .map(|i| if i % 7 == 3 { b',' } else { b'x' })
+            .collect();
+        let want: Vec<(u32, u32)> = {
+            let mut start = 0;
+            let mut v = Vec::new();
+            for (i, &b) in data.iter().enumerate() {
+                if b == b',' {
+                    v.push((start, i as u32));
+                    start = i as u32 + 1;
+                }
+            }
+            v.push((start, data.len() as u32));
+            v
+        };
+        let (n, got) = split(&data, want.len());
+        assert_eq!((n, got), (want.len(), want.clone()));
+        for cap in [1, 5, 40, want.len() - 1] {
+            let (n, got) = split(&data, cap);
+            assert_eq!(n, want.len(), "cap={cap}");
+            assert_eq!(got, want[..cap], "cap={cap}");
+        }
+        assert_eq!(
+            unsafe { super::split_u8(data.as_ptr(), data.len(), b',', core::ptr::null_mut(), 0) },
+            want.len()
+        );
+    }
+
+    #[test]
+    fn test_find_substring() {
+        let find = |h: &[u8], n: &[u8]| unsafe {
+            super::find_substring(h.as_ptr(), h.len(), n.as_ptr(), n.len())
+        };
+        assert_eq!(find(b"hello world", b"world"), 6);
+        assert_eq!(find(b"hello world", b"worlds"), -1);
+        assert_eq!(find(b"hello", b""), 0);
+        assert_eq!(find(b"", b"x"), -1);
+        assert_eq!(find(b"abc", b"c"), 2);
+
+        // Many first/last-byte candidates that fail verification.
+        let mut hay = b"aXXXXb".repeat(100);
+        hay.extend_from_slice(b"aXXYXb");
+        hay.extend_from_slice(&b"aXXXXb".repeat(10));
+        assert_eq!(find(&hay, b"aXXYXb"), 600);
+        for start in [0, 31, 32, 33, 63, 500] {
+            for n in [2, 3, 17, 40] {
+                let needle = hay[start..start + n].to_vec();
+                let want = hay.windows(n).position(|w| w == needle).unwrap() as isize;
+                assert_eq!(find(&hay, &needle), want, "start={} n={}", start, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_substring() {
+        let count = |hay: &[u8], needle: &[u8]| unsafe {
+            super::count_substring(hay.as_ptr(), hay.len(), needle.as_ptr(), needle.len())
+        };
+        assert_eq!(count(b"aaaa", b"aa"), 2);
+        assert_eq!(count(b"aaaaa", b"aa"), 2);
+        assert_eq!(count(b"abcabc", b"c"), 2);
+        assert_eq!(count(b"abc", b""), 4);
+        assert_eq!(count(b"ab", b"abc"), 0);
+
+        let mut hay = vec![b'.'; 1000];
+        for p in [0usize, 26, 32, 100, 500, 994] {
+            hay[p..p + 6].copy_from_slice(b"ERROR:");
+        }
+        assert_eq!(count(&hay, b"ERROR:"), 6);
+        assert_eq!(count(&hay, b"R:"), 6);
+        // Non-overlapping reference count.
+        let (mut want, mut p) = (0, 0);
+        while p + 3 <= hay.len() {
+            if &hay[p..p + 3] == b"..." {
+                (want, p) = (want + 1, p + 3);
+            } else {
+                p += 1;
+            }
+        }
+        assert_eq!(count(&hay, b"..."), want);
+    }
+
+    #[test]
+    fn test_searcher_handle() {
+        let hay = [
+            b"the quick brown fox jumps over the lazy dog; ".repeat(30),
+            b"Zebra#1".to_vec(),
+        ]
+        .concat();
+        for needle in [
+            &b"Zebra#1"[..],
+            b"lazy dog",
+            b"the",
+            b"x",
+            b"",
+            b"ooo",
+            b"not here",
+        ] {
+            let want = if needle.is_empty() {
+                0
+            } else {
+                hay.windows(needle.len())
+                    .position(|w| w == needle)
+                    .map_or(-1, |i| i as isize)
+            };
+            unsafe {
+                let s = super::searcher_compile(needle.as_ptr(), needle.len());
+                // The same handle serves repeated searches.
+                for _ in 0..2 {
+                    assert_eq!(super::searcher_find(s, hay.as_ptr(), hay.len()), want);
+                }
+                super::searcher_free(s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_typed_search() {
+        let col: Vec<u32> = (0..200u32).map(|i| i * 3 % 101).collect();
+        let wide: Vec<u64> = col.iter().map(|&x| (x as u64) << 40).collect();
+        let short: Vec<u16> = col.iter().map(|&x| x as u16 | 0x100).collect();
+        for needle in [0u32, 7, 100, 55, 999] {
+            let want = col
+                .iter()
+                .position(|&x| x == needle)
+                .map_or(-1, |i| i as isize);
+            unsafe {
+                assert_eq!(super::index_of_u32(col.as_ptr(), col.len(), needle), want);
+                assert_eq!(
+                    super::index_of_u64(wide.as_ptr(), wide.len(), (needle as u64) << 40),
+                    want
+                );
+                // A byte-level search would false-positive on the 0x01 high byte.
+                assert_eq!(
+                    super::index_of_u16(short.as_ptr(), short.len(), needle as u16 | 0x100),
+                    want
+                );
+            }
+            let mut masks = vec![!0u64; col.len().div_ceil(64)];
+            let n =
+                unsafe { super::eq_u32_masks(col.as_ptr(), col.len(), needle, masks.as_mut_ptr()) };
+            assert_eq!(n, masks.len());
+            for (i, &x) in col.iter().enumerate() {
+                assert_eq!(masks[i / 64] >> (i % 64) & 1 == 1, x == needle);
+            }
+            assert_eq!(masks[col.len() / 64] >> (col.len() % 64), 0);
+        }
+    }
+
+    #[test]
+    fn test_lower_bound_u64_batch() {
+        // Duplicates and gaps: lower bound must land on the first of a run.
+        let hay: Vec<u64> = (0..1000u64).map(|i| i / 3 * 10 + (1 << 40)).collect();
+        let needles: Vec<u64> = (0..250u64)
+            .map(|i| (i * 7 + 1) % 3400 + (1 << 40) - 5)
+            .chain([0, u64::MAX, 1 << 40])
+            .collect();
+        for hlen in [0usize, 1, 2, 3, 8, 999, 1000] {
+            let mut out = vec![!0usize; needles.len()];
+            unsafe {
+                super::lower_bound_u64_batch(
+                    hay.as_ptr(),
+                    hlen,
+                    needles.as_ptr(),
+                    needles.len(),
+                    out.as_mut_ptr(),
+                )
+            };
+            for (&x, &got) in needles.iter().zip(&out) {
+                assert_eq!(
+                    got,
+                    hay[..hlen].partition_point(|&v| v < x),
+                    "hlen={hlen} x={x}"
+                );
+            }
+        }
+    }
+}
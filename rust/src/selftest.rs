@@ -0,0 +1,177 @@
+//! Startup self-test: known-answer checks of each kernel family through the
+//! exported entry points, so the paths runtime dispatch picks on this CPU
+//! (AVX-512, CRC instructions, ...) are the ones exercised.
+//!
+//! The inputs are statics and the scratch buffers small, since Go calls the
+//! exports on the goroutine stack.
+
+use crate::auto::{eq_u8_masks, is_ascii, map_u8_lut, sum_u8};
+use crate::crc::crc32_ieee_update_64;
+use crate::hash::{xxh3_64, xxh64};
+use crate::search::count_u8_lut;
+use crate::{
+    crc32_update_64, eq_u8_masks16_full, is_ascii16, map_u8_lut16, sum_u8_16, sum_u8_32, sum_u8_64,
+    validate_u8_lut_pos64,
+};
+
+/// [`simba_selftest`] failure bit: CRC32C and CRC32 (IEEE).
+pub const SELFTEST_CRC: u32 = 1 << 0;
+/// [`simba_selftest`] failure bit: byte sums.
+pub const SELFTEST_SUM: u32 = 1 << 1;
+/// [`simba_selftest`] failure bit: byte-equality bitmasks.
+pub const SELFTEST_MASKS: u32 = 1 << 2;
+/// [`simba_selftest`] failure bit: lookup-table map, validate and count.
+pub const SELFTEST_LUT: u32 = 1 << 3;
+/// [`simba_selftest`] failure bit: XXH64 and XXH3.
+pub const SELFTEST_HASH: u32 = 1 << 4;
+/// [`simba_selftest`] failure bit: ASCII validation.
+pub const SELFTEST_ASCII: u32 = 1 << 5;
+
+/// Pattern length: many 64-byte vectors and an odd tail.
+const LEN: usize = 4096 + 77;
+
+/// Every byte value, in a non-periodic order.
+static PATTERN: [u8; LEN] = pattern(0xff);
+/// [`PATTERN`] folded to ASCII.
+static TEXT: [u8; LEN] = pattern(0x7f);
+/// Long enough for the three-way 8 KiB CRC stripes.
+static ZEROS: [u8; 3 * 8192 + 77] = [0; 3 * 8192 + 77];
+
+const fn pattern(mask: u8) -> [u8; LEN] {
+    let mut p = [0; LEN];
+    let mut i = 0;
+    while i < LEN {
+        p[i] = (i * 167 + (i >> 7)) as u8 & mask;
+        i += 1;
+    }
+    p
+}
+
+fn crc_ok(buf: &[u8]) -> bool {
+    let check = b"123456789";
+    unsafe {
+        crc32_update_64(check.as_ptr(), check.len(), 0) == 0xE306_9283
+            && crc32_ieee_update_64(check.as_ptr(), check.len(), 0) == 0xCBF4_3926
+            && crc32_update_64(buf.as_ptr(), buf.len(), 0) == 0xEC06_D3E4
+            && crc32_ieee_update_64(buf.as_ptr(), buf.len(), 0) == 0xAF50_6327
+            && crc32_update_64(ZEROS.as_ptr(), ZEROS.len(), 0) == 0xADD4_A828
+            && crc32_ieee_update_64(ZEROS.as_ptr(), ZEROS.len(), 0) == 0x417D_E479
+    }
+}
+
+fn sum_ok(buf: &[u8]) -> bool {
+    const SUM: u32 = 531_810;
+    unsafe {
+        sum_u8(buf.as_ptr(), buf.len()) == SUM
+            && sum_u8_16(buf.as_ptr(), buf.len()) == SUM
+            && sum_u8_32(buf.as_ptr(), buf.len()) == SUM
+            && sum_u8_64(buf.as_ptr(), buf.len()) == SUM
+    }
+}
+
+fn masks_ok(buf: &[u8]) -> bool {
+    let needle = buf[100];
+    let mut words = [0u64; LEN.div_ceil(64)];
+    let mut halves = [0u16; LEN.div_ceil(16)];
+    unsafe {
+        if eq_u8_masks(buf.as_ptr(), buf.len(), needle, words.as_mut_ptr()) != words.len()
+            || eq_u8_masks16_full(buf.as_ptr(), buf.len(), needle, halves.as_mut_ptr())
+                != halves.len()
+        {
+            return false;
+        }
+    }
+    buf.iter().enumerate().all(|(i, &b)| {
+        let hit = b == needle;
+        (words[i / 64] >> (i % 64) & 1 == 1) == hit && (halves[i / 16] >> (i % 16) & 1 == 1) == hit
+    })
+}
+
+fn lut_ok(buf: &[u8]) -> bool {
+    // Swap case of ASCII letters; the class table marks hex digits.
+    let map: [u8; 256] = core::array::from_fn(|b| {
+        let b = b as u8;
+        if b.is_ascii_alphabetic() { b ^ 0x20 } else { b }
+    });
+    let hex: [u8; 256] = core::array::from_fn(|b| (b as u8).is_ascii_hexdigit() as u8);
+    let digits = *b"0123456789abcdefABCDEF";
+    let mut valid: [u8; 200] = core::array::from_fn(|i| digits[i % digits.len()]);
+    valid[150] = b'g';
+    let mut out = [0u8; 1024];
+    let mapped = |out: &[u8], src: &[u8]| src.iter().zip(out).all(|(&b, &o)| map[b as usize] == o);
+    for src in buf.chunks(out.len()) {
+        let n = src.len();
+        unsafe { map_u8_lut(src.as_ptr(), n, out.as_mut_ptr(), map.as_ptr()) };
+        if !mapped(&out, src) {
+            return false;
+        }
+        unsafe { map_u8_lut16(src.as_ptr(), n, out.as_mut_ptr(), map.as_ptr()) };
+        if !mapped(&out, src) {
+            return false;
+        }
+    }
+    let hexes = buf.iter().filter(|b| b.is_ascii_hexdigit()).count() as u64;
+    unsafe {
+        count_u8_lut(buf.as_ptr(), buf.len(), hex.as_ptr()) == hexes
+            && validate_u8_lut_pos64(valid.as_ptr(), valid.len(), hex.as_ptr()) == 150
+    }
+}
+
+fn hash_ok(buf: &[u8]) -> bool {
+    unsafe {
+        xxh64(core::ptr::null(), 0, 0) == 0xEF46_DB37_51D8_E999
+            && xxh3_64(core::ptr::null(), 0, 0) == 0x2D06_8005_38D3_94C2
+            && xxh64(buf.as_ptr(), buf.len(), 0) == 0xC53A_3191_E6F0_5D8A
+            && xxh3_64(buf.as_ptr(), buf.len(), 0) == 0x8874_B317_1530_0AE8
+    }
+}
+
+fn ascii_ok() -> bool {
+    // One high byte near the end, past all the whole vectors.
+    let mut bad = [0u8; 1024];
+    bad.copy_from_slice(&TEXT[..1024]);
+    bad[1020] = 0x80;
+    unsafe {
+        is_ascii(TEXT.as_ptr(), LEN) == 1
+            && is_ascii16(TEXT.as_ptr(), LEN) == 1
+            && is_ascii(bad.as_ptr(), bad.len()) == 0
+            && is_ascii16(bad.as_ptr(), bad.len()) == 0
+    }
+}
+
+/// Run built-in known-answer checks for each kernel family on the running
+/// CPU and return a bit set of the families that failed (`SELFTEST_*`); 0
+/// means every check passed.  Cheap enough to call once at startup, as a
+/// guard that the dispatched code paths compute correct results here.
+#[unsafe(export_name = c_symbol!("simba_selftest"))]
+pub extern "C" fn simba_selftest() -> u32 {
+    let buf = &PATTERN[..];
+    [
+        (SELFTEST_CRC, crc_ok(buf)),
+        (SELFTEST_SUM, sum_ok(buf)),
+        (SELFTEST_MASKS, masks_ok(buf)),
+        (SELFTEST_LUT, lut_ok(buf)),
+        (SELFTEST_HASH, hash_ok(buf)),
+        (SELFTEST_ASCII, ascii_ok()),
+    ]
+    .iter()
+    .filter(|&&(_, ok)| !ok)
+    .fold(0, |failed, &(bit, _)| failed | bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selftest() {
+        assert_eq!(simba_selftest(), 0);
+        // A corrupted input trips exactly the families that read it.
+        let mut buf = PATTERN;
+        buf[LEN - 1] ^= 1;
+        assert!(!crc_ok(&buf));
+        assert!(!sum_ok(&buf));
+        assert!(!hash_ok(&buf));
+        assert!(masks_ok(&buf) && lut_ok(&buf));
+    }
+}
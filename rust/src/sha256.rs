@@ -0,0 +1,345 @@
+//! SHA-256, one-shot and streaming, using the SHA extensions (SHA-NI on
+//! x86_64, the ARMv8 crypto extension on aarch64) when the CPU has them and a
+//! portable implementation otherwise.
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+const BLOCK: usize = 64;
+
+const H0: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Portable compression of whole 64-byte blocks.
+fn compress_soft(state: &mut [u32; 8], blocks: &[u8]) {
+    for block in blocks.chunks_exact(BLOCK) {
+        let mut w = [0u32; 64];
+        for (i, c) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(c.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
+
+/// SHA-NI compression: four rounds per `sha256rnds2` pair, message schedule
+/// with `sha256msg1`/`sha256msg2`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn compress_x86(state: &mut [u32; 8], blocks: &[u8]) {
+    use core::arch::x86_64::*;
+
+    let bswap = _mm_set_epi64x(0x0C0D_0E0F_0809_0A0B, 0x0405_0607_0001_0203);
+    // The rounds instruction wants the state as ABEF / CDGH.
+    let dcba = _mm_shuffle_epi32(_mm_loadu_si128(state.as_ptr() as *const __m128i), 0xB1);
+    let efgh = _mm_shuffle_epi32(
+        _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i),
+        0x1B,
+    );
+    let mut abef = _mm_alignr_epi8(dcba, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, dcba, 0xF0);
+
+    for block in blocks.chunks_exact(BLOCK) {
+        let (save_abef, save_cdgh) = (abef, cdgh);
+        let mut w: [__m128i; 4] = core::array::from_fn(|i| {
+            _mm_shuffle_epi8(
+                _mm_loadu_si128(block.as_ptr().add(16 * i) as *const __m128i),
+                bswap,
+            )
+        });
+        for i in 0..16 {
+            if i >= 4 {
+                // w[i % 4] holds words 4i-16.. and becomes words 4i..
+                let t = _mm_alignr_epi8(w[(i + 3) % 4], w[(i + 2) % 4], 4);
+                let x = _mm_add_epi32(_mm_sha256msg1_epu32(w[i % 4], w[(i + 1) % 4]), t);
+                w[i % 4] = _mm_sha256msg2_epu32(x, w[(i + 3) % 4]);
+            }
+            let msg = _mm_add_epi32(
+                w[i % 4],
+                _mm_loadu_si128(K.as_ptr().add(4 * i) as *const __m128i),
+            );
+            cdgh = _mm_sha256rnds2_epu32(cdgh, abef, msg);
+            abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(msg, 0x0E));
+        }
+        abef = _mm_add_epi32(abef, save_abef);
+        cdgh = _mm_add_epi32(cdgh, save_cdgh);
+    }
+
+    let feba = _mm_shuffle_epi32(abef, 0x1B);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xB1);
+    _mm_storeu_si128(
+        state.as_mut_ptr() as *mut __m128i,
+        _mm_blend_epi16(feba, dchg, 0xF0),
+    );
+    _mm_storeu_si128(
+        state.as_mut_ptr().add(4) as *mut __m128i,
+        _mm_alignr_epi8(dchg, feba, 8),
+    );
+}
+
+/// ARMv8 crypto-extension compression.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "sha2")]
+unsafe fn compress_arm(state: &mut [u32; 8], blocks: &[u8]) {
+    use core::arch::aarch64::*;
+
+    let mut abcd = vld1q_u32(state.as_ptr());
+    let mut efgh = vld1q_u32(state.as_ptr().add(4));
+    for block in blocks.chunks_exact(BLOCK) {
+        let (save_abcd, save_efgh) = (abcd, efgh);
+        let mut w: [uint32x4_t; 4] = core::array::from_fn(|i| {
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr().add(16 * i))))
+        });
+        for i in 0..16 {
+            if i >= 4 {
+                let t = vsha256su0q_u32(w[i % 4], w[(i + 1) % 4]);
+                w[i % 4] = vsha256su1q_u32(t, w[(i + 2) % 4], w[(i + 3) % 4]);
+            }
+            let wk = vaddq_u32(w[i % 4], vld1q_u32(K.as_ptr().add(4 * i)));
+            let prev = abcd;
+            abcd = vsha256hq_u32(abcd, efgh, wk);
+            efgh = vsha256h2q_u32(efgh, prev, wk);
+        }
+        abcd = vaddq_u32(abcd, save_abcd);
+        efgh = vaddq_u32(efgh, save_efgh);
+    }
+    vst1q_u32(state.as_mut_ptr(), abcd);
+    vst1q_u32(state.as_mut_ptr().add(4), efgh);
+}
+
+/// Compress whole blocks with the best implementation this CPU supports.
+fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    if x86_feature!("sha") && x86_feature!("sse4.1") {
+        return unsafe { compress_x86(state, blocks) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if aarch64_feature!("sha2") {
+        return unsafe { compress_arm(state, blocks) };
+    }
+    compress_soft(state, blocks)
+}
+
+/// Streaming SHA-256 state.  Opaque to callers.
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buf: [u8; BLOCK],
+    buffered: usize,
+    total: u64,
+}
+
+impl Sha256 {
+    pub(crate) fn new() -> Self {
+        Sha256 {
+            state: H0,
+            buf: [0; BLOCK],
+            buffered: 0,
+            total: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        if self.buffered > 0 {
+            let take = (BLOCK - self.buffered).min(data.len());
+            self.buf[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+            if self.buffered < BLOCK {
+                return;
+            }
+            let buf = self.buf;
+            compress(&mut self.state, &buf);
+            self.buffered = 0;
+        }
+        let whole = data.len() - data.len() % BLOCK;
+        compress(&mut self.state, &data[..whole]);
+        let rem = &data[whole..];
+        self.buf[..rem.len()].copy_from_slice(rem);
+        self.buffered = rem.len();
+    }
+
+    /// Pad and finish a copy of the state.
+    pub(crate) fn digest(&self) -> [u8; 32] {
+        let mut s = self.clone();
+        let bits = self.total.wrapping_mul(8);
+        let mut pad = [0u8; 2 * BLOCK];
+        pad[0] = 0x80;
+        let n = if self.buffered < BLOCK - 8 {
+            BLOCK
+        } else {
+            2 * BLOCK
+        } - self.buffered;
+        pad[n - 8..n].copy_from_slice(&bits.to_be_bytes());
+        s.update(&pad[..n]);
+        let mut out = [0u8; 32];
+        for (o, w) in out.chunks_exact_mut(4).zip(s.state) {
+            o.copy_from_slice(&w.to_be_bytes());
+        }
+        out
+    }
+}
+
+/// SHA-256 of `len` bytes, written to the 32 bytes at `out`.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for 32
+/// bytes.
+#[unsafe(export_name = c_symbol!("sha256"))]
+pub unsafe extern "C" fn sha256(ptr: *const u8, len: usize, out: *mut u8) {
+    if out.is_null() {
+        return;
+    }
+    let mut s = Sha256::new();
+    if !ptr.is_null() && len > 0 {
+        s.update(core::slice::from_raw_parts(ptr, len));
+    }
+    core::ptr::copy_nonoverlapping(s.digest().as_ptr(), out, 32);
+}
+
+#[cfg(feature = "alloc")]
+/// Start a streaming SHA-256.  Release with [`sha256_free`].
+#[unsafe(export_name = c_symbol!("sha256_new"))]
+pub extern "C" fn sha256_new() -> *mut Sha256 {
+    Box::into_raw(Box::new(Sha256::new()))
+}
+
+/// Feed `len` more bytes to a streaming SHA-256.
+///
+/// # Safety
+/// `s` must be a live handle from [`sha256_new`]; `ptr` must be null or valid
+/// for `len` bytes.
+#[unsafe(export_name = c_symbol!("sha256_update"))]
+pub unsafe extern "C" fn sha256_update(s: *mut Sha256, ptr: *const u8, len: usize) {
+    if let Some(s) = s.as_mut().filter(|_| !ptr.is_null() && len > 0) {
+        s.update(core::slice::from_raw_parts(ptr, len));
+    }
+}
+
+/// Write the digest of everything fed so far to the 32 bytes at `out`; the
+/// state is left intact, so more input may follow.
+///
+/// # Safety
+/// `s` must be null or a live handle from [`sha256_new`]; `out` must be valid
+/// for 32 bytes.
+#[unsafe(export_name = c_symbol!("sha256_digest"))]
+pub unsafe extern "C" fn sha256_digest(s: *const Sha256, out: *mut u8) {
+    if let Some(s) = s.as_ref().filter(|_| !out.is_null()) {
+        core::ptr::copy_nonoverlapping(s.digest().as_ptr(), out, 32);
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Release a handle from [`sha256_new`].  Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("sha256_free"))]
+pub unsafe extern "C" fn sha256_free(s: *mut Sha256) {
+    if !s.is_null() {
+        drop(Box::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(d: &[u8]) -> String {
+        d.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn one_shot(data: &[u8]) -> String {
+        let mut out = [0u8; 32];
+        unsafe { sha256(data.as_ptr(), data.len(), out.as_mut_ptr()) };
+        hex(&out)
+    }
+
+    #[test]
+    fn test_sha256_known() {
+        assert_eq!(
+            one_shot(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            one_shot(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            one_shot(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        let million = vec![b'a'; 1_000_000];
+        assert_eq!(
+            one_shot(&million),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn test_sha256_hw_matches_soft_and_streaming() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 131 + i / 5) as u8).collect();
+        let mut soft = H0;
+        let mut hw = H0;
+        compress_soft(&mut soft, &data);
+        compress(&mut hw, &data);
+        assert_eq!(soft, hw);
+        for n in [0usize, 1, 55, 56, 63, 64, 65, 119, 120, 1000, 4096] {
+            let want = one_shot(&data[..n]);
+            for step in [1usize, 7, 64, 100] {
+                let mut out = [0u8; 32];
+                unsafe {
+                    let s = sha256_new();
+                    for c in data[..n].chunks(step) {
+                        sha256_update(s, c.as_ptr(), c.len());
+                    }
+                    sha256_digest(s, out.as_mut_ptr());
+                    sha256_free(s);
+                }
+                assert_eq!(hex(&out), want, "len {n} step {step}");
+            }
+        }
+    }
+}
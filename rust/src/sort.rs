@@ -0,0 +1,290 @@
+//! In-place SIMD sorting of `u32` / `u64` arrays.
+//!
+//! Large arrays are quicksorted (median-of-three Hoare partitioning) down
+//! to pieces of at most [`SMALL_SORT`] elements, which are sorted in
+//! registers in the style of vqsort's base case: the piece is padded with
+//! `MAX` to 8 or 16 vectors, a sorting network orders each lane across the
+//! vectors, a transpose turns the columns into sorted vectors, and bitonic
+//! vector merges combine those into one run.  A recursion depth limit falls
+//! back to `sort_unstable` for adversarial inputs.
+use core::simd::prelude::SimdOrd;
+use core::simd::{Simd, SimdElement, simd_swizzle};
+
+const SORT_LANES: usize = 8;
+/// Largest piece sorted entirely in registers (16 vectors).
+const SMALL_SORT: usize = 2 * SORT_LANES * SORT_LANES;
+
+type V<T> = Simd<T, SORT_LANES>;
+
+/// Optimal 19-comparator sorting network for 8 inputs.
+const NETWORK_8: [(usize, usize); 19] = [
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (2, 4),
+    (3, 5),
+    (1, 4),
+    (3, 6),
+    (1, 2),
+    (3, 4),
+    (5, 6),
+];
+
+/// Element types the kernel sorts.
+trait SortKey: SimdElement + Ord {
+    const MAX: Self;
+}
+
+impl SortKey for u32 {
+    const MAX: Self = u32::MAX;
+}
+
+impl SortKey for u64 {
+    const MAX: Self = u64::MAX;
+}
+
+/// Sort a bitonic vector ascending: three half-cleaner stages at lane
+/// distances 4, 2 and 1.
+#[inline(always)]
+fn bitonic_clean<T: SortKey>(v: V<T>) -> V<T>
+where
+    V<T>: SimdOrd,
+{
+    let s = simd_swizzle!(v, [4, 5, 6, 7, 0, 1, 2, 3]);
+    let v = simd_swizzle!(v.simd_min(s), v.simd_max(s), [0, 1, 2, 3, 12, 13, 14, 15]);
+    let s = simd_swizzle!(v, [2, 3, 0, 1, 6, 7, 4, 5]);
+    let v = simd_swizzle!(v.simd_min(s), v.simd_max(s), [0, 1, 10, 11, 4, 5, 14, 15]);
+    let s = simd_swizzle!(v, [1, 0, 3, 2, 5, 4, 7, 6]);
+    simd_swizzle!(v.simd_min(s), v.simd_max(s), [0, 9, 2, 11, 4, 13, 6, 15])
+}
+
+/// Merge two ascending vectors into the lower and upper halves of their
+/// union, each ascending.
+#[inline(always)]
+fn merge2<T: SortKey>(a: V<T>, b: V<T>) -> (V<T>, V<T>)
+where
+    V<T>: SimdOrd,
+{
+    let b = b.reverse();
+    (bitonic_clean(a.simd_min(b)), bitonic_clean(a.simd_max(b)))
+}
+
+/// Merge the sorted vector runs `a` and `b` into `out`: the vector with the
+/// smaller head is merged against the carried upper half each step.
+#[inline(always)]
+fn merge_runs<T: SortKey>(a: &[V<T>], b: &[V<T>], out: &mut [V<T>])
+where
+    V<T>: SimdOrd,
+{
+    let (lo, mut carry) = merge2(a[0], b[0]);
+    out[0] = lo;
+    let (mut i, mut j) = (1, 1);
+    for o in &mut out[1..a.len() + b.len() - 1] {
+        let take_a = j == b.len() || (i < a.len() && a[i][0] <= b[j][0]);
+        let next = if take_a {
+            i += 1;
+            a[i - 1]
+        } else {
+            j += 1;
+            b[j - 1]
+        };
+        let (lo, hi) = merge2(carry, next);
+        *o = lo;
+        carry = hi;
+    }
+    out[a.len() + b.len() - 1] = carry;
+}
+
+/// Sort `vs` (8 or 16 vectors) in place as one ascending sequence.
+#[inline(always)]
+fn sort_vectors<T: SortKey>(vs: &mut [V<T>])
+where
+    V<T>: SimdOrd,
+{
+    // Each group of eight: sort every lane across the vectors, then
+    // transpose so each vector is a sorted run of eight.
+    for group in vs.chunks_exact_mut(SORT_LANES) {
+        for &(i, j) in &NETWORK_8 {
+            let (lo, hi) = (group[i].simd_min(group[j]), group[i].simd_max(group[j]));
+            (group[i], group[j]) = (lo, hi);
+        }
+        let cols: [V<T>; SORT_LANES] = core::array::from_fn(|c| group[c]);
+        for (r, v) in group.iter_mut().enumerate() {
+            *v = Simd::from_array(core::array::from_fn(|c| cols[c][r]));
+        }
+    }
+    // Merge runs of 1, 2, 4 (and 8) vectors, ping-ponging through `tmp`.
+    let n = vs.len();
+    let mut tmp = [V::<T>::splat(T::MAX); SMALL_SORT / SORT_LANES];
+    let mut in_tmp = false;
+    let mut run = 1;
+    while run < n {
+        let (src, dst) = if in_tmp {
+            (&tmp[..n], &mut vs[..])
+        } else {
+            (&vs[..], &mut tmp[..n])
+        };
+        for (pair, out) in src.chunks(2 * run).zip(dst.chunks_mut(2 * run)) {
+            let (a, b) = pair.split_at(run);
+            merge_runs(a, b, out);
+        }
+        in_tmp = !in_tmp;
+        run *= 2;
+    }
+    if in_tmp {
+        vs.copy_from_slice(&tmp[..n]);
+    }
+}
+
+/// Sort at most `SMALL_SORT` elements in registers.
+fn small_sort<T: SortKey>(data: &mut [T])
+where
+    V<T>: SimdOrd,
+{
+    let n = data.len();
+    let nv = if n <= SMALL_SORT / 2 {
+        SORT_LANES
+    } else {
+        2 * SORT_LANES
+    };
+    let mut buf = [T::MAX; SMALL_SORT];
+    buf[..n].copy_from_slice(data);
+    let mut vs = [V::<T>::splat(T::MAX); SMALL_SORT / SORT_LANES];
+    for (v, c) in vs[..nv].iter_mut().zip(buf.chunks_exact(SORT_LANES)) {
+        *v = Simd::from_slice(c);
+    }
+    sort_vectors(&mut vs[..nv]);
+    for (c, v) in buf.chunks_exact_mut(SORT_LANES).zip(&vs[..nv]) {
+        v.copy_to_slice(c);
+    }
+    data.copy_from_slice(&buf[..n]);
+}
+
+/// Hoare partition around the median of the first, middle and last
+/// elements.  Returns `j` such that `data[..=j]` <= pivot <= `data[j + 1..]`,
+/// with both sides non-empty.
+fn partition<T: SortKey>(data: &mut [T]) -> usize {
+    let n = data.len();
+    let (a, b, c) = (data[0], data[n / 2], data[n - 1]);
+    let pivot = a.max(b).min(a.min(b).max(c));
+    let (mut i, mut j) = (0, n - 1);
+    loop {
+        while data[i] < pivot {
+            i += 1;
+        }
+        while data[j] > pivot {
+            j -= 1;
+        }
+        if i >= j {
+            return j;
+        }
+        data.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
+}
+
+fn sort_impl<T: SortKey>(mut data: &mut [T], mut depth: u32)
+where
+    V<T>: SimdOrd,
+{
+    while data.len() > SMALL_SORT {
+        if depth == 0 {
+            data.sort_unstable();
+            return;
+        }
+        depth -= 1;
+        let split = partition(data) + 1;
+        let (left, right) = data.split_at_mut(split);
+        // Recurse into the smaller side so the stack stays logarithmic.
+        if left.len() < right.len() {
+            sort_impl(left, depth);
+            data = right;
+        } else {
+            sort_impl(right, depth);
+            data = left;
+        }
+    }
+    if data.len() > 1 {
+        small_sort(data);
+    }
+}
+
+macro_rules! export_sort {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!(
+            "Sort the `len` `", stringify!($t), "` values at `ptr` ascending, in place.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` values."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *mut $t, len: usize) {
+            if ptr.is_null() || len < 2 {
+                return;
+            }
+            let data = core::slice::from_raw_parts_mut(ptr, len);
+            sort_impl(data, 2 * len.ilog2());
+        }
+    };
+}
+export_sort!(sort_u32, u32);
+export_sort!(sort_u64, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn splitmix64(mut x: u64) -> u64 {
+        x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^ (x >> 31)
+    }
+
+    #[test]
+    fn test_sort_u32_u64() {
+        for len in (0..=140).chain([255, 256, 1000, 10_007]) {
+            let inputs: [Vec<u64>; 5] = [
+                (0..len as u64).map(splitmix64).collect(),
+                (0..len as u64).collect(),
+                (0..len as u64).rev().collect(),
+                (0..len as u64).map(|i| splitmix64(i) % 4).collect(),
+                (0..len as u64)
+                    .map(|i| if i % 2 == 0 { u64::MAX } else { i })
+                    .collect(),
+            ];
+            for input in inputs {
+                let mut want = input.clone();
+                want.sort_unstable();
+                let mut got = input.clone();
+                unsafe { sort_u64(got.as_mut_ptr(), len) };
+                assert_eq!(got, want, "u64 len={len}");
+
+                let mut got: Vec<u32> = input.iter().map(|&x| (x >> 7) as u32).collect();
+                let mut want = got.clone();
+                want.sort_unstable();
+                unsafe { sort_u32(got.as_mut_ptr(), len) };
+                assert_eq!(got, want, "u32 len={len}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_depth_fallback() {
+        // With no depth budget the partitioning loop hands over at once.
+        let mut data: Vec<u32> = (0..5000u64).map(|i| splitmix64(i) as u32).collect();
+        let mut want = data.clone();
+        want.sort_unstable();
+        sort_impl(&mut data, 0);
+        assert_eq!(data, want);
+    }
+}
@@ -0,0 +1,444 @@
+//! Column statistics kernels (min / max, argmax / top-k, distinct counts /
+//! cardinality estimation).
+use core::simd::Simd;
+use core::simd::prelude::{SimdInt, SimdOrd, SimdPartialOrd, SimdUint};
+
+// === Min / max ===============================================================
+
+macro_rules! export_minmax {
+    ($t:ty, $out:ident, $name:ident, $lanes:expr) => {
+        #[doc = concat!("Smallest and largest of a `", stringify!($t), "` buffer, written by [`", stringify!($name), "`].")]
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $out {
+            pub min: $t,
+            pub max: $t,
+        }
+
+        #[doc = concat!(
+            "Write the minimum and maximum of `len` `", stringify!($t), "` values to `out` in one ", stringify!($lanes), "-lane SIMD pass. Returns 1, or 0 (leaving `out` untouched) when the buffer is empty.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` `", stringify!($t), "` values; `out` must be null or valid for one write."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const $t, len: usize, out: *mut $out) -> u8 {
+            if ptr.is_null() || out.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            let mut chunks = data.chunks_exact($lanes);
+            let (mut min, mut max) = match chunks.next() {
+                Some(first) => {
+                    let (mut lo, mut hi) = (Simd::<$t, $lanes>::from_slice(first), Simd::from_slice(first));
+                    for chunk in &mut chunks {
+                        let v = Simd::from_slice(chunk);
+                        lo = lo.simd_min(v);
+                        hi = hi.simd_max(v);
+                    }
+                    (lo.reduce_min(), hi.reduce_max())
+                }
+                None => (<$t>::MAX, <$t>::MIN),
+            };
+            for &x in chunks.remainder() {
+                min = min.min(x);
+                max = max.max(x);
+            }
+            *out = $out { min, max };
+            1
+        }
+    };
+}
+export_minmax!(u8, MinMaxU8, minmax_u8, 32);
+export_minmax!(i8, MinMaxI8, minmax_i8, 32);
+export_minmax!(u16, MinMaxU16, minmax_u16, 16);
+export_minmax!(i16, MinMaxI16, minmax_i16, 16);
+export_minmax!(u32, MinMaxU32, minmax_u32, 8);
+export_minmax!(i32, MinMaxI32, minmax_i32, 8);
+export_minmax!(u64, MinMaxU64, minmax_u64, 4);
+export_minmax!(i64, MinMaxI64, minmax_i64, 4);
+
+// === Argmax / top-k ==========================================================
+
+const ARG_LANES: usize = 8;
+
+/// Whether `a` ranks below `b`, as `(value, index)` pairs: a smaller value,
+/// or the same value at a later index.
+#[inline(always)]
+fn ranks_below(a: (f32, usize), b: (f32, usize)) -> bool {
+    a.0 < b.0 || (a.0 == b.0 && a.1 > b.1)
+}
+
+/// Index of the first largest non-NaN value.
+fn argmax_f32_impl(data: &[f32]) -> Option<usize> {
+    type V = Simd<f32, ARG_LANES>;
+    type I = Simd<u64, ARG_LANES>;
+    // Each lane tracks the first index of its largest value; NaNs never
+    // compare greater, so they are skipped.
+    let mut best = V::splat(f32::NEG_INFINITY);
+    let mut at = I::splat(u64::MAX);
+    let mut idx = I::from_array(core::array::from_fn(|l| l as u64));
+    let mut chunks = data.chunks_exact(ARG_LANES);
+    for chunk in &mut chunks {
+        let v = V::from_slice(chunk);
+        let gt = v.simd_gt(best);
+        best = gt.select(v, best);
+        at = gt.cast::<i64>().select(idx, at);
+        idx += I::splat(ARG_LANES as u64);
+    }
+    let lanes = (0..ARG_LANES)
+        .filter(|&l| at[l] != u64::MAX)
+        .map(|l| (best[l], at[l] as usize));
+    let base = data.len() - chunks.remainder().len();
+    let tail = chunks
+        .remainder()
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_nan())
+        .map(|(i, &x)| (x, base + i));
+    match lanes
+        .chain(tail)
+        .reduce(|r, c| if ranks_below(r, c) { c } else { r })
+    {
+        Some((v, i)) if v != f32::NEG_INFINITY => Some(i),
+        // Lanes never pick up -inf; if that is the maximum, it is the
+        // first non-NaN value.
+        _ => data.iter().position(|x| !x.is_nan()),
+    }
+}
+
+/// Index of the first maximum of the `len` `f32` values at `ptr`, or -1
+/// when there is none (empty or all NaN).  NaNs are skipped.  The maximum
+/// itself is written to `value` unless that is null.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` values; `value` must be null or
+/// valid for one write.
+#[unsafe(export_name = c_symbol!("argmax_f32"))]
+pub unsafe extern "C" fn argmax_f32(ptr: *const f32, len: usize, value: *mut f32) -> isize {
+    if ptr.is_null() || len == 0 {
+        return -1;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let Some(i) = argmax_f32_impl(data) else {
+        return -1;
+    };
+    if !value.is_null() {
+        *value = data[i];
+    }
+    i as isize
+}
+
+/// Restore the heap order (worst entry at the root) below `at` in the
+/// first `n` entries of `idx`/`val`.
+fn sift_down(idx: &mut [usize], val: &mut [f32], mut at: usize, n: usize) {
+    loop {
+        let mut worst = at;
+        for c in [2 * at + 1, 2 * at + 2] {
+            if c < n && ranks_below((val[c], idx[c]), (val[worst], idx[worst])) {
+                worst = c;
+            }
+        }
+        if worst == at {
+            return;
+        }
+        idx.swap(at, worst);
+        val.swap(at, worst);
+        at = worst;
+    }
+}
+
+/// Offer `(x, i)` to the heap of the best `idx.len()` entries, `n` of which
+/// are filled.
+fn offer(idx: &mut [usize], val: &mut [f32], n: &mut usize, i: usize, x: f32) {
+    if x.is_nan() {
+        return;
+    }
+    if *n < idx.len() {
+        let mut at = *n;
+        (idx[at], val[at]) = (i, x);
+        while at > 0 {
+            let up = (at - 1) / 2;
+            if !ranks_below((val[at], idx[at]), (val[up], idx[up])) {
+                break;
+            }
+            idx.swap(at, up);
+            val.swap(at, up);
+            at = up;
+        }
+        *n += 1;
+    } else if ranks_below((val[0], idx[0]), (x, i)) {
+        (idx[0], val[0]) = (i, x);
+        sift_down(idx, val, 0, *n);
+    }
+}
+
+fn topk_f32_impl(data: &[f32], idx: &mut [usize], val: &mut [f32]) -> usize {
+    type V = Simd<f32, ARG_LANES>;
+    let mut n = 0;
+    for (c, chunk) in data.chunks(ARG_LANES).enumerate() {
+        // Once the heap is full, only values above its worst can enter
+        // (an equal value comes later, so it ranks lower).
+        if n == idx.len()
+            && chunk.len() == ARG_LANES
+            && !V::from_slice(chunk).simd_gt(V::splat(val[0])).any()
+        {
+            continue;
+        }
+        for (j, &x) in chunk.iter().enumerate() {
+            offer(idx, val, &mut n, c * ARG_LANES + j, x);
+        }
+    }
+    // Heap sort: moving the worst entry to the back leaves the best first.
+    for end in (1..n).rev() {
+        idx.swap(0, end);
+        val.swap(0, end);
+        sift_down(idx, val, 0, end);
+    }
+    n
+}
+
+/// Write the `k` largest of the `len` `f32` values at `ptr`, best first, to
+/// `out_val`, and their indices to `out_idx`; equal values are ordered by
+/// index.  NaNs are skipped.  Returns the number of entries written, which
+/// is below `k` only when there are fewer than `k` non-NaN values.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` values; `out_idx` and `out_val`
+/// must be valid for `k` writes.
+#[unsafe(export_name = c_symbol!("topk_f32"))]
+pub unsafe extern "C" fn topk_f32(
+    ptr: *const f32,
+    len: usize,
+    k: usize,
+    out_idx: *mut usize,
+    out_val: *mut f32,
+) -> usize {
+    if ptr.is_null() || out_idx.is_null() || out_val.is_null() || len == 0 || k == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let k = k.min(len);
+    topk_f32_impl(
+        data,
+        core::slice::from_raw_parts_mut(out_idx, k),
+        core::slice::from_raw_parts_mut(out_val, k),
+    )
+}
+
+// === Exact distinct bytes ====================================================
+
+#[inline(always)]
+fn distinct_u8_impl(data: &[u8]) -> u32 {
+    // Four independent bitmaps break the load→or dependency chain; they are
+    // merged every block so the scan can stop once all 256 values are seen.
+    const BLOCK: usize = 1024;
+    let mut seen = [[0u64; 4]; 4];
+    let mut merged = [0u64; 4];
+    for block in data.chunks(BLOCK) {
+        let mut quads = block.chunks_exact(4);
+        for q in &mut quads {
+            for (k, &b) in q.iter().enumerate() {
+                seen[k][(b >> 6) as usize] |= 1 << (b & 63);
+            }
+        }
+        for &b in quads.remainder() {
+            seen[0][(b >> 6) as usize] |= 1 << (b & 63);
+        }
+        for (w, m) in merged.iter_mut().enumerate() {
+            *m = seen[0][w] | seen[1][w] | seen[2][w] | seen[3][w];
+        }
+        if merged.iter().all(|&m| m == u64::MAX) {
+            break;
+        }
+    }
+    merged.iter().map(|m| m.count_ones()).sum()
+}
+
+/// Return the exact number of distinct byte values (0..=256) in the buffer.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("distinct_u8"))]
+pub unsafe extern "C" fn distinct_u8(ptr: *const u8, len: usize) -> u32 {
+    if ptr.is_null() || len == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    distinct_u8_impl(data)
+}
+
+// === HyperLogLog estimate ====================================================
+// The estimator needs `ln`, which `core` lacks, so it is `std`-only.
+
+#[cfg(feature = "std")]
+/// Register-index bits; 4096 one-byte registers (~1.6 % standard error).
+const HLL_P: u32 = 12;
+#[cfg(feature = "std")]
+const HLL_M: usize = 1 << HLL_P;
+
+#[cfg(feature = "std")]
+#[inline(always)]
+fn approx_distinct_u64_impl(hashes: &[u64]) -> u64 {
+    let mut regs = [0u8; HLL_M];
+    for &h in hashes {
+        let idx = (h >> (64 - HLL_P)) as usize;
+        // Rank of the remaining bits; the sentinel caps it at 64 - P + 1.
+        let rank = ((h << HLL_P) | (1 << (HLL_P - 1))).leading_zeros() as u8 + 1;
+        regs[idx] = regs[idx].max(rank);
+    }
+
+    let m = HLL_M as f64;
+    let mut sum = 0.0f64;
+    let mut zeros = 0usize;
+    for &r in &regs {
+        sum += 1.0 / (1u64 << r) as f64;
+        zeros += (r == 0) as usize;
+    }
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let raw = alpha * m * m / sum;
+    let est = if raw <= 2.5 * m && zeros > 0 {
+        // Linear counting is far more accurate in the sparse regime.
+        m * (m / zeros as f64).ln()
+    } else {
+        raw
+    };
+    est.round() as u64
+}
+
+#[cfg(feature = "std")]
+/// Estimate the number of distinct values among `n` 64-bit hashes using a
+/// HyperLogLog sketch with 2^12 registers (standard error ≈ 1.6 %).
+///
+/// The inputs must already be well-mixed hashes, not raw keys: the top 12
+/// bits pick the register and the remaining bits feed the rank.
+///
+/// # Safety
+/// `hashes` must be null or valid for `n` u64 values.
+#[unsafe(export_name = c_symbol!("approx_distinct_u64"))]
+pub unsafe extern "C" fn approx_distinct_u64(hashes: *const u64, n: usize) -> u64 {
+    if hashes.is_null() || n == 0 {
+        return 0;
+    }
+    let data = core::slice::from_raw_parts(hashes, n);
+    approx_distinct_u64_impl(data)
+}
+
+#[cfg(test)]
+mod tests {
+    fn splitmix64(mut x: u64) -> u64 {
+        x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^ (x >> 31)
+    }
+
+    #[test]
+    fn test_minmax() {
+        macro_rules! check {
+            ($t:ty, $out:ident, $f:ident) => {
+                for len in [1usize, 3, 4, 7, 8, 31, 32, 33, 1000] {
+                    let data: Vec<$t> = (0..len as u64).map(|i| splitmix64(i) as $t).collect();
+                    let mut out = super::$out::default();
+                    assert_eq!(unsafe { super::$f(data.as_ptr(), len, &mut out) }, 1);
+                    let want_min = *data.iter().min().unwrap();
+                    let want_max = *data.iter().max().unwrap();
+                    assert_eq!((out.min, out.max), (want_min, want_max), "len={}", len);
+                }
+                let mut out = super::$out { min: 7, max: 7 };
+                assert_eq!(unsafe { super::$f(core::ptr::null(), 0, &mut out) }, 0);
+                assert_eq!((out.min, out.max), (7, 7));
+            };
+        }
+        check!(u8, MinMaxU8, minmax_u8);
+        check!(i8, MinMaxI8, minmax_i8);
+        check!(u16, MinMaxU16, minmax_u16);
+        check!(i16, MinMaxI16, minmax_i16);
+        check!(u32, MinMaxU32, minmax_u32);
+        check!(i32, MinMaxI32, minmax_i32);
+        check!(u64, MinMaxU64, minmax_u64);
+        check!(i64, MinMaxI64, minmax_i64);
+    }
+
+    #[test]
+    fn test_distinct_u8() {
+        let all: Vec<u8> = (0..=255u8).cycle().take(5000).collect();
+        let few: Vec<u8> = b"abcabcabcxyz".repeat(300);
+        unsafe {
+            assert_eq!(super::distinct_u8(all.as_ptr(), all.len()), 256);
+            assert_eq!(super::distinct_u8(few.as_ptr(), few.len()), 6);
+            assert_eq!(super::distinct_u8(few.as_ptr(), 1), 1);
+            assert_eq!(super::distinct_u8(core::ptr::null(), 0), 0);
+        }
+    }
+
+    #[test]
+    fn test_approx_distinct_u64_accuracy() {
+        for &card in &[10u64, 1000, 20_000, 500_000] {
+            // Every key appears three times; duplicates must not count.
+            let hashes: Vec<u64> = (0..3 * card).map(|i| splitmix64(i % card)).collect();
+            let est = unsafe { super::approx_distinct_u64(hashes.as_ptr(), hashes.len()) };
+            let err = (est as f64 - card as f64).abs() / card as f64;
+            assert!(err < 0.05, "card={} est={} err={:.3}", card, est, err);
+        }
+    }
+
+    #[test]
+    fn test_argmax_topk_f32() {
+        let data: Vec<f32> = (0..1000u64)
+            .map(|i| (splitmix64(i) % 2000) as f32 / 8.0 - 100.0)
+            .collect();
+        let mut order: Vec<usize> = (0..data.len()).collect();
+        order.sort_by(|&a, &b| data[b].total_cmp(&data[a]).then(a.cmp(&b)));
+        for len in [1usize, 7, 8, 9, 100, 1000] {
+            let d = &data[..len];
+            let want: Vec<usize> = order.iter().copied().filter(|&i| i < len).collect();
+            let mut v = 0f32;
+            assert_eq!(
+                unsafe { super::argmax_f32(d.as_ptr(), len, &mut v) },
+                want[0] as isize
+            );
+            assert_eq!(v, d[want[0]]);
+            for k in [1usize, 5, 64, 2000] {
+                let mut idx = vec![0usize; k];
+                let mut val = vec![0f32; k];
+                let n = unsafe {
+                    super::topk_f32(d.as_ptr(), len, k, idx.as_mut_ptr(), val.as_mut_ptr())
+                };
+                assert_eq!(n, k.min(len));
+                assert_eq!(idx[..n], want[..n], "len={len} k={k}");
+                assert!(val[..n].iter().zip(&idx[..n]).all(|(&v, &i)| v == d[i]));
+            }
+        }
+
+        // NaNs are skipped; ties go to the first index; -inf can win.
+        let nan = f32::NAN;
+        let tricky = [nan, 3.0, nan, 5.0, 5.0, nan, 1.0, 5.0, nan, 2.0];
+        assert_eq!(
+            unsafe { super::argmax_f32(tricky.as_ptr(), 10, core::ptr::null_mut()) },
+            3
+        );
+        let (mut idx, mut val) = ([0usize; 8], [0f32; 8]);
+        let n =
+            unsafe { super::topk_f32(tricky.as_ptr(), 10, 8, idx.as_mut_ptr(), val.as_mut_ptr()) };
+        assert_eq!((n, &idx[..n]), (6, &[3, 4, 7, 1, 9, 6][..]));
+        let low = [
+            nan,
+            f32::NEG_INFINITY,
+            nan,
+            f32::NEG_INFINITY,
+            nan,
+            nan,
+            nan,
+            nan,
+            nan,
+        ];
+        assert_eq!(
+            unsafe { super::argmax_f32(low.as_ptr(), low.len(), core::ptr::null_mut()) },
+            1
+        );
+        assert_eq!(
+            unsafe { super::argmax_f32(low.as_ptr(), 1, core::ptr::null_mut()) },
+            -1
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Non-temporal (`_nt`) variants of the bulk transform kernels, for outputs
+//! too large to be read back soon.  Each kernel writes one cache-resident
+//! bounce block at a time, which is then streamed to `dst` with
+//! non-temporal stores that bypass the cache; a store fence before
+//! returning orders the streamed data ahead of the caller's later writes.
+//! Targets without streaming stores (anything but x86_64) write `dst`
+//! directly.
+
+/// Output bytes staged per step; small enough to stay in L1.
+#[cfg(target_arch = "x86_64")]
+const STREAM_BLOCK: usize = 4096;
+
+/// Granule of `_mm_stream_si128`, which needs 16-byte-aligned addresses.
+#[cfg(target_arch = "x86_64")]
+const STREAM_ALIGN: usize = 16;
+
+#[cfg(target_arch = "x86_64")]
+#[repr(C, align(64))]
+struct Bounce([u8; STREAM_BLOCK]);
+
+/// Fill `len` elements at `dst` by calling `produce(offset, count, out)` on
+/// consecutive pieces, each of which must write `count` elements (the ones
+/// starting at element `offset`) to `out`.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn stream_out<T>(dst: *mut T, len: usize, mut produce: impl FnMut(usize, usize, *mut T)) {
+    use core::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_sfence, _mm_stream_si128};
+
+    let size = size_of::<T>();
+    let head_bytes = (dst as usize).wrapping_neg() % STREAM_ALIGN;
+    if !head_bytes.is_multiple_of(size) {
+        // Elements straddle every aligned granule; nothing can be streamed.
+        produce(0, len, dst);
+        return;
+    }
+    let head = (head_bytes / size).min(len);
+    if head > 0 {
+        produce(0, head, dst);
+    }
+    let mut bounce = Bounce([0; STREAM_BLOCK]);
+    let per_block = STREAM_BLOCK / size;
+    let mut i = head;
+    while i < len {
+        let n = per_block.min(len - i);
+        produce(i, n, bounce.0.as_mut_ptr() as *mut T);
+        let (src, out) = (bounce.0.as_ptr(), dst.add(i) as *mut u8);
+        let bytes = n * size;
+        let whole = bytes - bytes % STREAM_ALIGN;
+        for k in (0..whole).step_by(STREAM_ALIGN) {
+            let v = _mm_loadu_si128(src.add(k) as *const __m128i);
+            _mm_stream_si128(out.add(k) as *mut __m128i, v);
+        }
+        // A partial final granule goes through the cache.
+        core::ptr::copy_nonoverlapping(src.add(whole), out.add(whole), bytes - whole);
+        i += n;
+    }
+    _mm_sfence();
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+#[inline(always)]
+unsafe fn stream_out<T>(dst: *mut T, len: usize, mut produce: impl FnMut(usize, usize, *mut T)) {
+    produce(0, len, dst)
+}
+
+/// `map_u8_lut64` with non-temporal stores.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `len` bytes and `map` for 256.  `dst`
+/// may alias `src` exactly (in-place), but must not partially overlap it.
+#[unsafe(export_name = c_symbol!("map_u8_lut_nt"))]
+pub unsafe extern "C" fn map_u8_lut_nt(src: *const u8, len: usize, dst: *mut u8, map: *const u8) {
+    if len == 0 || src.is_null() || dst.is_null() || map.is_null() {
+        return;
+    }
+    stream_out(dst, len, |i, n, out| {
+        crate::map_u8_lut64(src.add(i), n, out, map)
+    });
+}
+
+/// `xor_u8` with non-temporal stores.
+///
+/// # Safety
+/// All pointers must be valid for `len` bytes.  `dst` may alias `a` and/or
+/// `b` exactly, but must not partially overlap either.
+#[unsafe(export_name = c_symbol!("xor_u8_nt"))]
+pub unsafe extern "C" fn xor_u8_nt(dst: *mut u8, a: *const u8, b: *const u8, len: usize) {
+    if dst.is_null() || a.is_null() || b.is_null() || len == 0 {
+        return;
+    }
+    stream_out(dst, len, |i, n, out| {
+        crate::bitops::xor_u8(out, a.add(i), b.add(i), n)
+    });
+}
+
+macro_rules! export_bswap_nt {
+    ($name:ident, $base:ident, $t:ty) => {
+        #[doc = concat!(
+            "`", stringify!($base), "` with non-temporal stores.\n\n",
+            "# Safety\n",
+            "`src` and `dst` must be valid for `count * size_of::<", stringify!($t), ">()` bytes.  `dst` may alias `src` exactly (in-place), but must not partially overlap it."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const u8, count: usize, dst: *mut u8) {
+            if src.is_null() || dst.is_null() || count == 0 {
+                return;
+            }
+            let (src, dst) = (src as *const $t, dst as *mut $t);
+            stream_out(dst, count, |i, n, out| {
+                crate::bitops::$base(src.add(i) as *const u8, n, out as *mut u8)
+            });
+        }
+    };
+}
+export_bswap_nt!(bswap16_buf_nt, bswap16_buf, u16);
+export_bswap_nt!(bswap32_buf_nt, bswap32_buf, u32);
+export_bswap_nt!(bswap64_buf_nt, bswap64_buf, u64);
+
+macro_rules! export_convert_nt {
+    ($name:ident, $base:path, $from:ty, $to:ty) => {
+        #[doc = concat!(
+            "`", stringify!($base), "` with non-temporal stores.\n\n",
+            "# Safety\n",
+            "`src` must be null or valid for `len` ", stringify!($from), " values and `dst` for `len` ", stringify!($to), " writes; they must not overlap."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(src: *const $from, len: usize, dst: *mut $to) {
+            if src.is_null() || dst.is_null() || len == 0 {
+                return;
+            }
+            stream_out(dst, len, |i, n, out| $base(src.add(i), n, out));
+        }
+    };
+}
+export_convert_nt!(i32_to_f32_nt, crate::numeric::i32_to_f32, i32, f32);
+export_convert_nt!(
+    f32_to_i32_saturating_nt,
+    crate::numeric::f32_to_i32_saturating,
+    f32,
+    i32
+);
+export_convert_nt!(f16_to_f32_nt, crate::half::f16_to_f32, u16, f32);
+export_convert_nt!(f32_to_f16_nt, crate::half::f32_to_f16, f32, u16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nt_matches_cached() {
+        let map: Vec<u8> = (0..=255u32).map(|b| (b * 167 + 13) as u8).collect();
+        let src: Vec<u8> = (0..20_000u32).map(|i| (i * 31 + i / 7) as u8).collect();
+        // Odd lengths and misaligned destinations exercise head and tail.
+        for (len, at) in [
+            (1, 0),
+            (15, 3),
+            (100, 1),
+            (4096, 0),
+            (9_999, 5),
+            (16_000, 8),
+        ] {
+            let mut want = vec![0u8; len + 16];
+            let mut got = vec![0u8; len + 16];
+            unsafe {
+                crate::map_u8_lut64(src.as_ptr(), len, want[at..].as_mut_ptr(), map.as_ptr());
+                map_u8_lut_nt(src.as_ptr(), len, got[at..].as_mut_ptr(), map.as_ptr());
+                assert_eq!(got, want, "map len={len}");
+                xor_u8_nt(got[at..].as_mut_ptr(), src.as_ptr(), src[7..].as_ptr(), len);
+                crate::bitops::xor_u8(
+                    want[at..].as_mut_ptr(),
+                    src.as_ptr(),
+                    src[7..].as_ptr(),
+                    len,
+                );
+                assert_eq!(got, want, "xor len={len}");
+                let count = len / 8;
+                bswap64_buf_nt(src.as_ptr(), count, got[at..].as_mut_ptr());
+                crate::bitops::bswap64_buf(src.as_ptr(), count, want[at..].as_mut_ptr());
+                assert_eq!(got, want, "bswap len={len}");
+            }
+
+            let ints: Vec<i32> = (0..len as i32).map(|i| i * 7919 - 50_000).collect();
+            let mut want = vec![0f32; len + 4];
+            let mut got = vec![0f32; len + 4];
+            let at = at % 4;
+            unsafe {
+                crate::numeric::i32_to_f32(ints.as_ptr(), len, want[at..].as_mut_ptr());
+                i32_to_f32_nt(ints.as_ptr(), len, got[at..].as_mut_ptr());
+                assert_eq!(got, want, "i32_to_f32 len={len}");
+                let mut halves = vec![0u16; len];
+                let mut back = vec![0u16; len];
+                crate::half::f32_to_f16(want[at..].as_ptr(), len, halves.as_mut_ptr());
+                f32_to_f16_nt(want[at..].as_ptr(), len, back.as_mut_ptr());
+                assert_eq!(back, halves, "f32_to_f16 len={len}");
+            }
+        }
+    }
+}
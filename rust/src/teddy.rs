@@ -0,0 +1,248 @@
+//! Multi-literal search (Teddy): find the first occurrence of any of a set of
+//! short patterns with one SIMD pass over the haystack.
+use alloc::{boxed::Box, vec::Vec};
+use core::simd::{LaneCount, Simd, SupportedLaneCount};
+
+/// Candidate-filter width; 16 lanes keep the nibble tables in one register.
+const TEDDY_LANES: usize = 16;
+/// Patterns are spread over 8 buckets, one bit each in the filter masks.
+const BUCKETS: usize = 8;
+/// Maximum number of patterns per set.
+pub const TEDDY_MAX_PATTERNS: usize = 64;
+/// Leading pattern bytes fed into the filter.
+const MAX_FINGERPRINT: usize = 3;
+
+/// A compiled pattern set.  Opaque to callers.
+pub struct Teddy {
+    patterns: Vec<Vec<u8>>,
+    /// Pattern ids per bucket, ascending.
+    buckets: [Vec<u32>; BUCKETS],
+    /// Fingerprint length: min(3, shortest pattern).
+    m: usize,
+    /// `lo[k][n]`/`hi[k][n]`: buckets with a pattern whose byte `k` has low /
+    /// high nibble `n`.
+    lo: [[u8; 16]; MAX_FINGERPRINT],
+    hi: [[u8; 16]; MAX_FINGERPRINT],
+}
+
+impl Teddy {
+    fn new(patterns: Vec<Vec<u8>>) -> Self {
+        let m = patterns
+            .iter()
+            .map(Vec::len)
+            .min()
+            .unwrap_or(0)
+            .min(MAX_FINGERPRINT);
+        let mut buckets: [Vec<u32>; BUCKETS] = Default::default();
+        let mut lo = [[0u8; 16]; MAX_FINGERPRINT];
+        let mut hi = [[0u8; 16]; MAX_FINGERPRINT];
+        for (id, p) in patterns.iter().enumerate() {
+            let b = id % BUCKETS;
+            buckets[b].push(id as u32);
+            for k in 0..m {
+                lo[k][(p[k] & 15) as usize] |= 1 << b;
+                hi[k][(p[k] >> 4) as usize] |= 1 << b;
+            }
+        }
+        Teddy {
+            patterns,
+            buckets,
+            m,
+            lo,
+            hi,
+        }
+    }
+
+    /// Lowest-id pattern of the buckets in `mask` occurring at `hay[at..]`.
+    #[inline(always)]
+    fn verify(&self, hay: &[u8], at: usize, mut mask: u8) -> Option<u32> {
+        let mut best: Option<u32> = None;
+        while mask != 0 {
+            let b = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            let hit = self.buckets[b]
+                .iter()
+                .copied()
+                .find(|&id| hay[at..].starts_with(&self.patterns[id as usize]));
+            if let Some(h) = hit {
+                best = Some(best.map_or(h, |b| b.min(h)));
+            }
+        }
+        best
+    }
+
+    unsafe fn find_impl<const L: usize>(&self, hay: &[u8]) -> Option<(usize, u32)>
+    where
+        LaneCount<L>: SupportedLaneCount,
+    {
+        let table = |t: &[u8; 16]| Simd::<u8, L>::from_array(core::array::from_fn(|i| t[i % 16]));
+        let lo: [Simd<u8, L>; MAX_FINGERPRINT] = core::array::from_fn(|k| table(&self.lo[k]));
+        let hi: [Simd<u8, L>; MAX_FINGERPRINT] = core::array::from_fn(|k| table(&self.hi[k]));
+        let nib = Simd::<u8, L>::splat(0x0F);
+        let m = self.m;
+
+        let mut i = 0;
+        while i + m - 1 + L <= hay.len() {
+            let mut res = Simd::<u8, L>::splat(0xFF);
+            for k in 0..m {
+                let c = Simd::<u8, L>::from_slice(&hay[i + k..i + k + L]);
+                res &= lo[k].swizzle_dyn(c & nib) & hi[k].swizzle_dyn(c >> 4);
+            }
+            let arr = res.to_array();
+            for (lane, &mask) in arr.iter().enumerate().filter(|&(_, &m)| m != 0) {
+                if let Some(id) = self.verify(hay, i + lane, mask) {
+                    return Some((i + lane, id));
+                }
+            }
+            i += L;
+        }
+        (i..hay.len()).find_map(|p| self.verify(hay, p, u8::MAX).map(|id| (p, id)))
+    }
+}
+
+/// Compile `n` literal patterns for [`teddy_find`].  Pattern `i` occupies
+/// `lens[i]` bytes of `pats`, the patterns being stored back to back.
+/// Returns null if `n` is 0 or exceeds [`TEDDY_MAX_PATTERNS`], or if any
+/// pattern is empty.  Release the handle with [`teddy_free`].
+///
+/// # Safety
+/// `lens` must be valid for `n` values and `pats` for their sum in bytes.
+#[unsafe(export_name = c_symbol!("teddy_compile"))]
+pub unsafe extern "C" fn teddy_compile(pats: *const u8, lens: *const u32, n: usize) -> *mut Teddy {
+    if pats.is_null() || lens.is_null() || n == 0 || n > TEDDY_MAX_PATTERNS {
+        return core::ptr::null_mut();
+    }
+    let lens = core::slice::from_raw_parts(lens, n);
+    if lens.contains(&0) {
+        return core::ptr::null_mut();
+    }
+    let total = lens.iter().map(|&l| l as usize).sum();
+    let mut rest = core::slice::from_raw_parts(pats, total);
+    let patterns = lens
+        .iter()
+        .map(|&l| {
+            let (p, r) = rest.split_at(l as usize);
+            rest = r;
+            p.to_vec()
+        })
+        .collect();
+    Box::into_raw(Box::new(Teddy::new(patterns)))
+}
+
+/// Find the leftmost occurrence of any compiled pattern in `haystack` and
+/// return its offset, storing the pattern's index in `*out_id`; when several
+/// patterns start at that offset the lowest index wins.  Returns -1 if none
+/// occurs (`*out_id` is then left untouched).
+///
+/// Candidates are found 16 positions at a time by matching the low and high
+/// nibbles of the first (up to 3) pattern bytes against per-bucket tables
+/// with byte shuffles, then verified exactly.
+///
+/// # Safety
+/// `t` must be a live handle from [`teddy_compile`]; `haystack` must be null
+/// or valid for `len` bytes; `out_id` must be null or valid for a u32 write.
+#[unsafe(export_name = c_symbol!("teddy_find"))]
+pub unsafe extern "C" fn teddy_find(
+    t: *const Teddy,
+    haystack: *const u8,
+    len: usize,
+    out_id: *mut u32,
+) -> isize {
+    let Some(t) = t.as_ref() else {
+        return -1;
+    };
+    if haystack.is_null() || len == 0 {
+        return -1;
+    }
+    let hay = core::slice::from_raw_parts(haystack, len);
+    match t.find_impl::<TEDDY_LANES>(hay) {
+        Some((pos, id)) => {
+            if !out_id.is_null() {
+                *out_id = id;
+            }
+            pos as isize
+        }
+        None => -1,
+    }
+}
+
+/// Release a handle from [`teddy_compile`].  Null is ignored.
+///
+/// # Safety
+/// `t` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("teddy_free"))]
+pub unsafe extern "C" fn teddy_free(t: *mut Teddy) {
+    if !t.is_null() {
+        drop(Box::from_raw(t));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pats: &[&[u8]], hay: &[u8]) -> (isize, u32) {
+        let lens: Vec<u32> = pats.iter().map(|p| p.len() as u32).collect();
+        let flat = pats.concat();
+        let mut id = u32::MAX;
+        unsafe {
+            let t = teddy_compile(flat.as_ptr(), lens.as_ptr(), lens.len());
+            assert!(!t.is_null());
+            let pos = teddy_find(t, hay.as_ptr(), hay.len(), &mut id);
+            teddy_free(t);
+            (pos, id)
+        }
+    }
+
+    fn naive(pats: &[&[u8]], hay: &[u8]) -> (isize, u32) {
+        for p in 0..hay.len() {
+            if let Some(id) = pats.iter().position(|pat| hay[p..].starts_with(pat)) {
+                return (p as isize, id as u32);
+            }
+        }
+        (-1, u32::MAX)
+    }
+
+    #[test]
+    fn test_teddy_known() {
+        let rules: [&[u8]; 5] = [b"<script", b"UNION SELECT", b"../", b"%00", b"<scr"];
+        let req = b"GET /static/app.js?x=1&path=..%2f../etc/passwd HTTP/1.1";
+        assert_eq!(find(&rules, req), (33, 2));
+        // Same start: lower index wins.
+        assert_eq!(find(&rules, b"....<script>"), (4, 0));
+        assert_eq!(
+            find(&rules, b"nothing to see here, move along please"),
+            (-1, u32::MAX)
+        );
+        assert_eq!(find(&[b"a"], b"a"), (0, 0));
+        unsafe {
+            let lens = [3u32, 0];
+            assert!(teddy_compile(b"abc".as_ptr(), lens.as_ptr(), 2).is_null());
+        }
+    }
+
+    #[test]
+    fn test_teddy_matches_naive() {
+        let mut x = 0x9E37_79B9u32;
+        let mut next = || {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        for _ in 0..200 {
+            let hay: Vec<u8> = (0..(next() % 400))
+                .map(|_| b"abcdxy"[next() as usize % 6])
+                .collect();
+            let owned: Vec<Vec<u8>> = (0..1 + next() % 40)
+                .map(|_| {
+                    (0..1 + next() % 6)
+                        .map(|_| b"abcdxy"[next() as usize % 6])
+                        .collect()
+                })
+                .collect();
+            let pats: Vec<&[u8]> = owned.iter().map(Vec::as_slice).collect();
+            assert_eq!(find(&pats, &hay), naive(&pats, &hay), "{:?}", owned);
+        }
+    }
+}
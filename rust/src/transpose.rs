@@ -0,0 +1,91 @@
+//! Byte-matrix transpose (row-major records → column-major planes).
+use core::simd::Simd;
+
+use crate::{load_simd, store_simd};
+
+/// Side of the square tiles transposed in registers.
+const TILE: usize = 16;
+
+/// Transpose one `TILE x TILE` tile.  Each round pairs row `i` with row
+/// `i + TILE / 2` and interleaves them into rows `2i`, `2i + 1`, which
+/// rotates the (row, column) index bits left by one; after log2(TILE) rounds
+/// rows and columns have swapped.
+#[inline(always)]
+unsafe fn transpose_tile(src: *const u8, src_stride: usize, dst: *mut u8, dst_stride: usize) {
+    let mut rows: [Simd<u8, TILE>; TILE] =
+        core::array::from_fn(|i| load_simd(src.add(i * src_stride)));
+    for _ in 0..TILE.trailing_zeros() {
+        let mut next = rows;
+        for i in 0..TILE / 2 {
+            (next[2 * i], next[2 * i + 1]) = rows[i].interleave(rows[i + TILE / 2]);
+        }
+        rows = next;
+    }
+    for (i, v) in rows.iter().enumerate() {
+        store_simd(dst.add(i * dst_stride), *v);
+    }
+}
+
+/// Transpose a `rows x cols` row-major byte matrix into `dst` as a
+/// `cols x rows` row-major matrix (`dst[c * rows + r] = src[r * cols + c]`),
+/// i.e. turn fixed-width records into one plane per byte column.  Works in
+/// 16x16 register tiles; ragged edges are copied scalar.
+///
+/// # Safety
+/// `src` and `dst` must be valid for `rows * cols` bytes and must not
+/// overlap.
+#[unsafe(export_name = c_symbol!("transpose_u8"))]
+pub unsafe extern "C" fn transpose_u8(src: *const u8, rows: usize, cols: usize, dst: *mut u8) {
+    if src.is_null() || dst.is_null() || rows == 0 || cols == 0 {
+        return;
+    }
+    let (full_rows, full_cols) = (rows / TILE * TILE, cols / TILE * TILE);
+    for r in (0..full_rows).step_by(TILE) {
+        for c in (0..full_cols).step_by(TILE) {
+            transpose_tile(src.add(r * cols + c), cols, dst.add(c * rows + r), rows);
+        }
+        for c in full_cols..cols {
+            for k in r..r + TILE {
+                *dst.add(c * rows + k) = *src.add(k * cols + c);
+            }
+        }
+    }
+    for r in full_rows..rows {
+        for c in 0..cols {
+            *dst.add(c * rows + r) = *src.add(r * cols + c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_transpose_u8() {
+        for (rows, cols) in [
+            (1, 1),
+            (1, 40),
+            (40, 1),
+            (16, 16),
+            (32, 48),
+            (17, 33),
+            (100, 7),
+        ] {
+            let src: Vec<u8> = (0..rows * cols).map(|i| (i * 131 + i / 7) as u8).collect();
+            let mut dst = vec![0u8; rows * cols];
+            unsafe { super::transpose_u8(src.as_ptr(), rows, cols, dst.as_mut_ptr()) };
+            for r in 0..rows {
+                for c in 0..cols {
+                    assert_eq!(
+                        dst[c * rows + r],
+                        src[r * cols + c],
+                        "{rows}x{cols} at ({r}, {c})"
+                    );
+                }
+            }
+            // Transposing back restores the input.
+            let mut back = vec![0u8; rows * cols];
+            unsafe { super::transpose_u8(dst.as_ptr(), cols, rows, back.as_mut_ptr()) };
+            assert_eq!(back, src);
+        }
+    }
+}
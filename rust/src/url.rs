@@ -0,0 +1,284 @@
+//! Percent-encoding (RFC 3986 §2.1).  The caller's 256-byte table marks the
+//! bytes that pass through literally, so one pair of kernels covers paths,
+//! query components and custom sets; runs of literal bytes are found with
+//! a nibble-shuffle class scan and copied whole.
+use crate::error::{SIMBA_ERR_CAPACITY, SIMBA_ERR_INVALID_INPUT, fail};
+use crate::{NibbleLut, nibble_lut_pos_impl};
+
+const URL_LANES: usize = 32;
+
+const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// RFC 3986 unreserved characters, the literal set of a null encode `lut`.
+const UNRESERVED: [u8; 256] = {
+    let mut t = [0u8; 256];
+    let mut c = 0;
+    while c < 256 {
+        let b = c as u8;
+        t[c] = (b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')) as u8;
+        c += 1;
+    }
+    t
+};
+
+/// Encode `src` into `dst`; `Err(offset)` gives the first input byte whose
+/// output did not fit.
+#[inline(always)]
+unsafe fn url_encode_impl(src: &[u8], dst: &mut [u8], table: &NibbleLut) -> Result<usize, usize> {
+    let (mut i, mut o) = (0, 0);
+    while i < src.len() {
+        let run = nibble_lut_pos_impl::<URL_LANES>(&src[i..], table).unwrap_or(src.len() - i);
+        let fits = run.min(dst.len() - o);
+        dst[o..o + fits].copy_from_slice(&src[i..i + fits]);
+        (i, o) = (i + fits, o + fits);
+        if fits < run {
+            return Err(i);
+        }
+        if i == src.len() {
+            break;
+        }
+        if o + 3 > dst.len() {
+            return Err(i);
+        }
+        let b = src[i];
+        dst[o..o + 3].copy_from_slice(&[
+            b'%',
+            HEX_UPPER[(b >> 4) as usize],
+            HEX_UPPER[(b & 15) as usize],
+        ]);
+        (i, o) = (i + 1, o + 3);
+    }
+    Ok(o)
+}
+
+/// Percent-encode `len` bytes from `src` into `dst`: bytes with a non-zero
+/// `lut` entry are copied, every other byte becomes `%XX` (uppercase hex).
+/// A null `lut` copies only the RFC 3986 unreserved characters
+/// (`A-Z a-z 0-9 - . _ ~`).  `3 * len` bytes of output always suffice.
+///
+/// Returns the number of bytes written, or `-(offset + 1)` where `offset` is
+/// the first input byte whose encoding did not fit in `cap` (`dst` then holds
+/// the encoding of `src[..offset]`).
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes and `dst` for `cap` bytes;
+/// `lut` must be null or valid for 256 bytes; `dst` must not overlap `src`.
+#[unsafe(export_name = c_symbol!("url_encode"))]
+pub unsafe extern "C" fn url_encode(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    cap: usize,
+    lut: *const u8,
+) -> isize {
+    if src.is_null() || len == 0 {
+        return 0;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst: &mut [u8] = if dst.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(dst, cap)
+    };
+    let table = NibbleLut::compile(if lut.is_null() {
+        &UNRESERVED
+    } else {
+        core::slice::from_raw_parts(lut, 256)
+    });
+    match url_encode_impl(src, dst, &table) {
+        Ok(n) => n as isize,
+        Err(at) => {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("encoding of byte {at} does not fit in {cap} bytes"),
+            );
+            -(at as isize) - 1
+        }
+    }
+}
+
+/// Why [`url_decode_impl`] stopped, with the input offset.
+enum UrlDecodeError {
+    Invalid(usize),
+    Capacity(usize),
+}
+
+#[inline(always)]
+unsafe fn url_decode_impl(
+    src: &[u8],
+    dst: &mut [u8],
+    literal: &NibbleLut,
+) -> Result<usize, UrlDecodeError> {
+    let (mut i, mut o) = (0, 0);
+    while i < src.len() {
+        let run = nibble_lut_pos_impl::<URL_LANES>(&src[i..], literal).unwrap_or(src.len() - i);
+        let fits = run.min(dst.len() - o);
+        dst[o..o + fits].copy_from_slice(&src[i..i + fits]);
+        (i, o) = (i + fits, o + fits);
+        if fits < run {
+            return Err(UrlDecodeError::Capacity(i));
+        }
+        if i == src.len() {
+            break;
+        }
+        let (hi, lo) = match src.get(i..i + 3) {
+            Some(&[b'%', hi, lo]) => (hi, lo),
+            _ => return Err(UrlDecodeError::Invalid(i)),
+        };
+        let (Some(hi), Some(lo)) = ((hi as char).to_digit(16), (lo as char).to_digit(16)) else {
+            return Err(UrlDecodeError::Invalid(i));
+        };
+        if o == dst.len() {
+            return Err(UrlDecodeError::Capacity(i));
+        }
+        dst[o] = (hi << 4 | lo) as u8;
+        (i, o) = (i + 3, o + 1);
+    }
+    Ok(o)
+}
+
+/// Decode `len` percent-encoded bytes from `src` into `dst`: `%XX` (either
+/// case) becomes the byte it names, and bytes with a non-zero `lut` entry
+/// are copied (a null `lut` accepts every byte).  `+` is not special; map it
+/// to a space first for `application/x-www-form-urlencoded` input.  The
+/// output is never longer than the input.
+///
+/// Returns the number of bytes written, or `-(offset + 1)` where `offset` is
+/// the first input byte that is not allowed (a byte outside `lut`, or a `%`
+/// not followed by two hex digits) or whose output did not fit in `cap`;
+/// `simba_last_error` tells the two apart.
+///
+/// # Safety
+/// `src` must be null or valid for `len` bytes and `dst` for `cap` bytes;
+/// `lut` must be null or valid for 256 bytes; `dst` must not overlap `src`.
+#[unsafe(export_name = c_symbol!("url_decode"))]
+pub unsafe extern "C" fn url_decode(
+    src: *const u8,
+    len: usize,
+    dst: *mut u8,
+    cap: usize,
+    lut: *const u8,
+) -> isize {
+    if src.is_null() || len == 0 {
+        return 0;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst: &mut [u8] = if dst.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(dst, cap)
+    };
+    // Stop the literal scan at every `%` as well as at disallowed bytes.
+    let mut literal = [1u8; 256];
+    if !lut.is_null() {
+        literal.copy_from_slice(core::slice::from_raw_parts(lut, 256));
+    }
+    literal[b'%' as usize] = 0;
+    match url_decode_impl(src, dst, &NibbleLut::compile(&literal)) {
+        Ok(n) => n as isize,
+        Err(UrlDecodeError::Invalid(at)) => {
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("invalid percent-encoding at offset {at}"),
+            );
+            -(at as isize) - 1
+        }
+        Err(UrlDecodeError::Capacity(at)) => {
+            fail(
+                SIMBA_ERR_CAPACITY,
+                format_args!("decoding of byte {at} does not fit in {cap} bytes"),
+            );
+            -(at as isize) - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(src: &[u8], cap: usize) -> (isize, Vec<u8>) {
+        let mut dst = vec![0u8; cap];
+        let n = unsafe {
+            url_encode(
+                src.as_ptr(),
+                src.len(),
+                dst.as_mut_ptr(),
+                cap,
+                UNRESERVED.as_ptr(),
+            )
+        };
+        dst.truncate(n.max(0) as usize);
+        (n, dst)
+    }
+
+    fn decode(src: &[u8], lut: Option<&[u8; 256]>) -> (isize, Vec<u8>) {
+        let mut dst = vec![0u8; src.len()];
+        let lut = lut.map_or(core::ptr::null(), |t| t.as_ptr());
+        let n = unsafe { url_decode(src.as_ptr(), src.len(), dst.as_mut_ptr(), src.len(), lut) };
+        dst.truncate(n.max(0) as usize);
+        (n, dst)
+    }
+
+    #[test]
+    fn test_url_encode() {
+        let (n, out) = encode(b"a b/c?d=\xff~", 64);
+        assert_eq!(out, b"a%20b%2Fc%3Fd%3D%FF~");
+        assert_eq!(n, out.len() as isize);
+
+        // Long literal runs go through the vector scan.
+        let src: Vec<u8> = (0..200u32)
+            .map(|i| {
+                if i % 61 == 60 {
+                    b' '
+                } else {
+                    b'a' + (i % 26) as u8
+                }
+            })
+            .collect();
+        let (_, out) = encode(&src, 3 * src.len());
+        let want: Vec<u8> = src
+            .iter()
+            .flat_map(|&c| if c == b' ' { b"%20".to_vec() } else { vec![c] })
+            .collect();
+        assert_eq!(out, want);
+        assert_eq!(decode(&out, None).1, src);
+
+        // A partial escape never goes out; the offset says where to resume.
+        let (n, _) = encode(b"ab cd", 4);
+        assert_eq!(n, -3);
+        let (n, _) = encode(b"abcdef", 4);
+        assert_eq!(n, -5);
+
+        // A null table encodes everything outside the unreserved set.
+        let mut dst = [0u8; 32];
+        let src = b"a b/~-._\xff";
+        let n = unsafe {
+            url_encode(
+                src.as_ptr(),
+                src.len(),
+                dst.as_mut_ptr(),
+                32,
+                core::ptr::null(),
+            )
+        };
+        assert_eq!(&dst[..n as usize], b"a%20b%2F~-._%FF");
+    }
+
+    #[test]
+    fn test_url_decode() {
+        assert_eq!(decode(b"a%20b%2fc%3F", None), (6, b"a b/c?".to_vec()));
+        assert_eq!(decode(b"%e2%82%AC+", None), (4, "€+".as_bytes().to_vec()));
+        assert_eq!(decode(b"ab%2", None).0, -3);
+        assert_eq!(decode(b"ab%zz", None).0, -3);
+        assert_eq!(decode(b"%", None).0, -1);
+        // With a table, literal bytes outside it are rejected.
+        assert_eq!(decode(b"ok%20fine", Some(&UNRESERVED)).0, 7);
+        assert_eq!(decode(b"not ok", Some(&UNRESERVED)).0, -4);
+
+        let mut dst = [0u8; 2];
+        let n = unsafe { url_decode(b"a%41b".as_ptr(), 5, dst.as_mut_ptr(), 2, core::ptr::null()) };
+        assert_eq!(n, -5);
+        assert_eq!(dst, *b"aA");
+    }
+}
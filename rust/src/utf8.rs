@@ -0,0 +1,362 @@
+//! UTF-8 validation kernels (Keiser–Lemire lookup algorithm).
+use core::simd::prelude::{SimdPartialEq, SimdPartialOrd, SimdUint};
+use core::simd::{LaneCount, Simd, SupportedLaneCount};
+
+// === Lookup tables ===========================================================
+
+// Error classes from "Validating UTF-8 In Less Than One Instruction Per Byte"
+// (Keiser & Lemire, 2021).  Every (previous byte, current byte) pair is
+// classified by three 16-entry nibble lookups; a pair is bad iff the three
+// results share a bit.  `TWO_CONTS` is expected exactly where the current
+// byte is the 2nd/3rd continuation of a 3/4-byte sequence.
+const TOO_SHORT: u8 = 1 << 0;
+const TOO_LONG: u8 = 1 << 1;
+const OVERLONG_3: u8 = 1 << 2;
+const TOO_LARGE: u8 = 1 << 3;
+const SURROGATE: u8 = 1 << 4;
+const OVERLONG_2: u8 = 1 << 5;
+const TOO_LARGE_1000: u8 = 1 << 6;
+const OVERLONG_4: u8 = 1 << 6;
+const TWO_CONTS: u8 = 1 << 7;
+const CARRY: u8 = TOO_SHORT | TOO_LONG | TWO_CONTS;
+
+/// Indexed by the high nibble of the previous byte.
+const BYTE_1_HIGH: [u8; 16] = [
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    TOO_SHORT | OVERLONG_2,
+    TOO_SHORT,
+    TOO_SHORT | OVERLONG_3 | SURROGATE,
+    TOO_SHORT | TOO_LARGE | TOO_LARGE_1000 | OVERLONG_4,
+];
+
+/// Indexed by the low nibble of the previous byte.
+const BYTE_1_LOW: [u8; 16] = [
+    CARRY | OVERLONG_3 | OVERLONG_2 | OVERLONG_4,
+    CARRY | OVERLONG_2,
+    CARRY,
+    CARRY,
+    CARRY | TOO_LARGE,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000 | SURROGATE,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+];
+
+/// Indexed by the high nibble of the current byte.
+const BYTE_2_HIGH: [u8; 16] = [
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE_1000 | OVERLONG_4,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+];
+
+/// Spread a 16-entry table into the first lanes of an `L`-lane vector so it
+/// can be indexed with `swizzle_dyn` (pshufb / tbl).
+#[inline(always)]
+fn nibble_table<const L: usize>(t: &[u8; 16]) -> Simd<u8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    Simd::from_array(core::array::from_fn(|i| if i < 16 { t[i] } else { 0 }))
+}
+
+// === Validation ==============================================================
+
+/// Length of the longest prefix of `data` that is valid UTF-8, or `None` if
+/// the whole buffer is valid.
+#[inline(always)]
+pub(crate) unsafe fn validate_utf8_impl<const L: usize>(data: &[u8]) -> Option<usize>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let t1h = nibble_table::<L>(&BYTE_1_HIGH);
+    let t1l = nibble_table::<L>(&BYTE_1_LOW);
+    let t2h = nibble_table::<L>(&BYTE_2_HIGH);
+    let nib = Simd::<u8, L>::splat(0x0F);
+
+    // Each block is checked together with the three bytes preceding it; the
+    // first block sees zero padding instead (`pos` is otherwise >= L > 3).
+    let mut head = [0u8; 3 + 64];
+    let mut pos = 0;
+    while pos + L <= data.len() {
+        let (src, at) = if pos == 0 {
+            head[3..3 + L].copy_from_slice(&data[..L]);
+            (&head[..3 + L], 3)
+        } else {
+            (data, pos)
+        };
+        let input = Simd::<u8, L>::from_slice(&src[at..at + L]);
+        let prev1 = Simd::<u8, L>::from_slice(&src[at - 1..at - 1 + L]);
+        let prev2 = Simd::<u8, L>::from_slice(&src[at - 2..at - 2 + L]);
+        let prev3 = Simd::<u8, L>::from_slice(&src[at - 3..at - 3 + L]);
+
+        let sc = t1h.swizzle_dyn(prev1 >> 4)
+            & t1l.swizzle_dyn(prev1 & nib)
+            & t2h.swizzle_dyn(input >> 4);
+        let must23 = prev2.simd_ge(Simd::splat(0xE0)) | prev3.simd_ge(Simd::splat(0xF0));
+        let must23_80 = must23.select(Simd::splat(0x80), Simd::splat(0));
+        if (sc ^ must23_80).reduce_or() != 0 {
+            break;
+        }
+        pos += L;
+    }
+
+    // Everything before `pos` is valid except possibly a sequence straddling
+    // `pos`; restart the exact scalar check at that sequence's lead byte
+    // (skipping continuation bytes of any sequence that ends before `pos`).
+    let mut start = pos.saturating_sub(3);
+    while start < pos && data[start] & 0xC0 == 0x80 {
+        start += 1;
+    }
+    match core::str::from_utf8(&data[start..]) {
+        Ok(_) => None,
+        Err(e) => Some(start + e.valid_up_to()),
+    }
+}
+
+macro_rules! export_validate_utf8 {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Validate UTF-8 using a ", stringify!($lanes), "-lane SIMD kernel. Returns -1 if the buffer is valid, otherwise the offset at which the first invalid or truncated sequence starts (i.e. the length of the longest valid prefix).\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize) -> isize {
+            if ptr.is_null() || len == 0 {
+                return -1;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            validate_utf8_impl::<$lanes>(data).map_or(-1, |i| i as isize)
+        }
+    };
+}
+export_validate_utf8!(validate_utf8_32, 32);
+export_validate_utf8!(validate_utf8_64, 64);
+
+// === Code-point counting =====================================================
+
+/// Mask of the lanes holding a continuation byte (`0b10xx_xxxx`).
+#[inline(always)]
+fn continuation_lanes<const L: usize>(v: Simd<u8, L>) -> core::simd::Mask<i8, L>
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    (v & Simd::splat(0xC0)).simd_eq(Simd::splat(0x80))
+}
+
+#[inline(always)]
+unsafe fn count_utf8_runes_impl<const L: usize>(data: &[u8]) -> u64
+where
+    LaneCount<L>: SupportedLaneCount,
+{
+    let (one, zero) = (Simd::<u8, L>::splat(1), Simd::<u8, L>::splat(0));
+    let mut conts = 0u64;
+    let mut chunks = data.chunks_exact(L);
+    // Per-lane u8 counters are flushed before they can wrap (255 steps).
+    loop {
+        let mut acc = zero;
+        let mut steps = 0;
+        for chunk in (&mut chunks).take(255) {
+            acc += continuation_lanes(Simd::<u8, L>::from_slice(chunk)).select(one, zero);
+            steps += 1;
+        }
+        conts += acc.cast::<u32>().reduce_sum() as u64;
+        if steps < 255 {
+            break;
+        }
+    }
+    conts += chunks
+        .remainder()
+        .iter()
+        .filter(|&&b| b & 0xC0 == 0x80)
+        .count() as u64;
+    data.len() as u64 - conts
+}
+
+macro_rules! export_count_utf8_runes {
+    ($name:ident, $lanes:expr) => {
+        #[doc = concat!(
+            "Count the UTF-8 code points in the buffer as the bytes that are not continuation bytes (`0b10xx_xxxx`), using a ", stringify!($lanes), "-lane SIMD kernel. The input is not validated: on valid UTF-8 this equals Go's `utf8.RuneCount`, but a stray continuation byte counts 0 where Go counts one `RuneError`.\n\n",
+            "# Safety\n",
+            "`ptr` must be null or valid for `len` bytes."
+        )]
+        #[unsafe(export_name = c_symbol!(stringify!($name)))]
+        pub unsafe extern "C" fn $name(ptr: *const u8, len: usize) -> u64 {
+            if ptr.is_null() || len == 0 {
+                return 0;
+            }
+            let data = core::slice::from_raw_parts(ptr, len);
+            count_utf8_runes_impl::<$lanes>(data)
+        }
+    };
+}
+export_count_utf8_runes!(count_utf8_runes_32, 32);
+export_count_utf8_runes!(count_utf8_runes_64, 64);
+
+/// Count UTF-8 code points; alias of the 64-lane kernel.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("count_utf8_runes"))]
+pub unsafe extern "C" fn count_utf8_runes(ptr: *const u8, len: usize) -> u64 {
+    count_utf8_runes_64(ptr, len)
+}
+
+/// Write a bitmap of the continuation bytes (`0b10xx_xxxx`) in the buffer:
+/// bit `i % 64` of `out[i / 64]` is set iff byte `i` is a continuation byte.
+/// `out` must hold `len.div_ceil(64)` words; unused high bits of the last word
+/// are cleared.
+///
+/// # Safety
+/// `ptr` must be null or valid for `len` bytes; `out` must be valid for
+/// `len.div_ceil(64)` u64 writes.
+#[unsafe(export_name = c_symbol!("utf8_continuation_mask"))]
+pub unsafe extern "C" fn utf8_continuation_mask(ptr: *const u8, len: usize, out: *mut u64) {
+    if ptr.is_null() || out.is_null() || len == 0 {
+        return;
+    }
+    let data = core::slice::from_raw_parts(ptr, len);
+    let out = core::slice::from_raw_parts_mut(out, len.div_ceil(64));
+    let mut chunks = data.chunks_exact(64);
+    for (w, chunk) in out.iter_mut().zip(&mut chunks) {
+        *w = continuation_lanes(Simd::<u8, 64>::from_slice(chunk)).to_bitmask();
+    }
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        out[len / 64] = rem
+            .iter()
+            .enumerate()
+            .fold(0, |m, (i, &b)| m | (((b & 0xC0 == 0x80) as u64) << i));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn check(data: &[u8]) {
+        let want = match core::str::from_utf8(data) {
+            Ok(_) => -1,
+            Err(e) => e.valid_up_to() as isize,
+        };
+        unsafe {
+            assert_eq!(
+                super::validate_utf8_32(data.as_ptr(), data.len()),
+                want,
+                "{:x?}",
+                data
+            );
+            assert_eq!(
+                super::validate_utf8_64(data.as_ptr(), data.len()),
+                want,
+                "{:x?}",
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_utf8_valid() {
+        let text = "héllo wörld — ユニコード 🦁 ".repeat(20);
+        check(text.as_bytes());
+        check(b"");
+        check(&[0x7F; 200]);
+    }
+
+    #[test]
+    fn test_validate_utf8_invalid_everywhere() {
+        let base = "aé€🦁".repeat(30).into_bytes();
+        let bad: [&[u8]; 10] = [
+            &[0x80],                   // stray continuation
+            &[0xC0, 0x80],             // overlong 2-byte
+            &[0xE0, 0x80, 0x80],       // overlong 3-byte
+            &[0xED, 0xA0, 0x80],       // surrogate
+            &[0xF4, 0x90, 0x80, 0x80], // > U+10FFFF
+            &[0xF5, 0x80, 0x80, 0x80], // invalid lead
+            &[0xFF],
+            &[0xE2, 0x82],       // truncated, followed by ASCII
+            &[0xC3, 0xC3],       // lead followed by lead
+            &[0xF0, 0x9F, 0xA6], // truncated 4-byte
+        ];
+        for seq in bad {
+            for at in [0usize, 1, 29, 31, 32, 60, 63, 64, 100] {
+                // Splice at a char boundary so the prefix stays valid.
+                let mut cut = at.min(base.len());
+                while core::str::from_utf8(&base[..cut]).is_err() {
+                    cut -= 1;
+                }
+                let mut data = base[..cut].to_vec();
+                data.extend_from_slice(seq);
+                data.extend_from_slice(b"tail bytes that are plain ascii .....");
+                check(&data);
+                // And with the bad sequence at the very end.
+                let mut data = base[..cut].to_vec();
+                data.extend_from_slice(seq);
+                check(&data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_utf8_runes() {
+        let text = "héllo wörld — ユニコード 🦁 ".repeat(300);
+        for n in [0, 1, 5, 63, 64, 65, 1000, text.len()] {
+            // Cut anywhere, including mid-sequence; a leading-byte count is
+            // still well defined.
+            let bytes = &text.as_bytes()[..n];
+            let want = bytes.iter().filter(|&&b| b & 0xC0 != 0x80).count() as u64;
+            unsafe {
+                assert_eq!(super::count_utf8_runes_32(bytes.as_ptr(), n), want);
+                assert_eq!(super::count_utf8_runes_64(bytes.as_ptr(), n), want);
+            }
+        }
+        let s = "aé€🦁";
+        assert_eq!(unsafe { super::count_utf8_runes(s.as_ptr(), s.len()) }, 4);
+    }
+
+    #[test]
+    fn test_utf8_continuation_mask() {
+        let text = "aé€🦁".repeat(20);
+        let bytes = text.as_bytes();
+        let mut out = vec![!0u64; bytes.len().div_ceil(64)];
+        unsafe { super::utf8_continuation_mask(bytes.as_ptr(), bytes.len(), out.as_mut_ptr()) };
+        for (i, &b) in bytes.iter().enumerate() {
+            assert_eq!(
+                (out[i / 64] >> (i % 64)) & 1 == 1,
+                b & 0xC0 == 0x80,
+                "byte {}",
+                i
+            );
+        }
+        assert_eq!(out[bytes.len() / 64] >> (bytes.len() % 64), 0);
+    }
+}
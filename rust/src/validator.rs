@@ -0,0 +1,207 @@
+//! Incremental (streaming) ASCII / UTF-8 validation behind an opaque handle.
+use crate::ascii::first_non_ascii_impl;
+use crate::utf8::validate_utf8_impl;
+use alloc::boxed::Box;
+
+/// `kind` value for [`simba_validator_new`]: every byte must be < 0x80.
+pub const VALIDATOR_ASCII: u32 = 0;
+/// `kind` value for [`simba_validator_new`]: the stream must be valid UTF-8.
+pub const VALIDATOR_UTF8: u32 = 1;
+
+const STREAM_LANES: usize = 64;
+
+/// State carried between chunks.  Opaque to callers.
+pub struct Validator {
+    kind: u32,
+    /// Total bytes fed so far.
+    consumed: u64,
+    /// Stream offset of the first error, once one has been seen.
+    error: Option<u64>,
+    /// Leading bytes of a UTF-8 sequence cut off by the end of the last chunk.
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl Validator {
+    fn feed(&mut self, chunk: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        let base = self.consumed;
+        self.consumed += chunk.len() as u64;
+        self.error = match self.kind {
+            VALIDATOR_ASCII => first_non_ascii_impl::<STREAM_LANES>(chunk).map(|i| base + i as u64),
+            _ => self.feed_utf8(base, chunk),
+        };
+    }
+
+    /// Validate `chunk` (starting at stream offset `base`) as a continuation
+    /// of the bytes seen so far; returns the stream offset of the first error.
+    fn feed_utf8(&mut self, base: u64, chunk: &[u8]) -> Option<u64> {
+        let pl = self.pending_len;
+        let mut start = 0;
+        if pl > 0 {
+            // Stitch the cut-off sequence to the head of the new chunk.  At
+            // most 3 more bytes are needed, one extra keeps the boundary exact.
+            let k = chunk.len().min(4);
+            let mut tmp = [0u8; 8];
+            tmp[..pl].copy_from_slice(&self.pending[..pl]);
+            tmp[pl..pl + k].copy_from_slice(&chunk[..k]);
+            let seq_base = base - pl as u64;
+            match core::str::from_utf8(&tmp[..pl + k]) {
+                Ok(_) => start = k,
+                Err(e) if e.error_len().is_some() => {
+                    return Some(seq_base + e.valid_up_to() as u64);
+                }
+                // Still incomplete: the whole chunk was too short to finish it.
+                Err(e) if e.valid_up_to() < pl => {
+                    let v = e.valid_up_to();
+                    self.pending_len = pl + k - v;
+                    self.pending.copy_within(v..pl, 0);
+                    self.pending[pl - v..self.pending_len].copy_from_slice(&chunk[..k]);
+                    return None;
+                }
+                Err(e) => start = e.valid_up_to() - pl,
+            }
+            self.pending_len = 0;
+        }
+
+        let rest = &chunk[start..];
+        let bad = unsafe { validate_utf8_impl::<STREAM_LANES>(rest) }?;
+        let tail = &rest[bad..];
+        match core::str::from_utf8(tail) {
+            // A truncated sequence at the very end may be completed later.
+            Err(e) if e.valid_up_to() == 0 && e.error_len().is_none() => {
+                self.pending[..tail.len()].copy_from_slice(tail);
+                self.pending_len = tail.len();
+                None
+            }
+            _ => Some(base + (start + bad) as u64),
+        }
+    }
+
+    fn finish(&self) -> Option<u64> {
+        self.error
+            .or_else(|| (self.pending_len > 0).then(|| self.consumed - self.pending_len as u64))
+    }
+}
+
+/// Create a streaming validator of the given `kind` ([`VALIDATOR_ASCII`] or
+/// [`VALIDATOR_UTF8`]).  Returns null for an unknown kind.  The handle must be
+/// released with [`simba_validator_free`].
+#[unsafe(export_name = c_symbol!("simba_validator_new"))]
+pub extern "C" fn simba_validator_new(kind: u32) -> *mut Validator {
+    if kind != VALIDATOR_ASCII && kind != VALIDATOR_UTF8 {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Validator {
+        kind,
+        consumed: 0,
+        error: None,
+        pending: [0; 4],
+        pending_len: 0,
+    }))
+}
+
+/// Feed the next chunk of the stream.  Multi-byte UTF-8 sequences may be
+/// split across chunks.  Returns 1 while no error has been seen, 0 once the
+/// stream is known to be invalid (later chunks are then ignored).
+///
+/// # Safety
+/// `v` must be a live handle from [`simba_validator_new`]; `ptr` must be null
+/// or valid for `len` bytes.
+#[unsafe(export_name = c_symbol!("simba_validator_feed"))]
+pub unsafe extern "C" fn simba_validator_feed(v: *mut Validator, ptr: *const u8, len: usize) -> u8 {
+    let Some(v) = v.as_mut() else {
+        return 0;
+    };
+    if !ptr.is_null() && len > 0 {
+        v.feed(core::slice::from_raw_parts(ptr, len));
+    }
+    v.error.is_none() as u8
+}
+
+/// Finish the stream.  Returns -1 if everything fed so far is valid,
+/// otherwise the stream offset of the first invalid byte; a UTF-8 sequence
+/// left incomplete at the end reports the offset of its lead byte.  The
+/// handle stays valid, so more chunks may still be fed afterwards.
+///
+/// # Safety
+/// `v` must be a live handle from [`simba_validator_new`].
+#[unsafe(export_name = c_symbol!("simba_validator_finish"))]
+pub unsafe extern "C" fn simba_validator_finish(v: *const Validator) -> i64 {
+    match v.as_ref() {
+        Some(v) => v.finish().map_or(-1, |i| i as i64),
+        None => -1,
+    }
+}
+
+/// Release a handle from [`simba_validator_new`].  Null is ignored.
+///
+/// # Safety
+/// `v` must be null or a live handle; it must not be used afterwards.
+#[unsafe(export_name = c_symbol!("simba_validator_free"))]
+pub unsafe extern "C" fn simba_validator_free(v: *mut Validator) {
+    if !v.is_null() {
+        drop(Box::from_raw(v));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `data` split at every `step` bytes and return the finish result.
+    fn run(kind: u32, data: &[u8], step: usize) -> i64 {
+        unsafe {
+            let v = simba_validator_new(kind);
+            for c in data.chunks(step) {
+                simba_validator_feed(v, c.as_ptr(), c.len());
+            }
+            let r = simba_validator_finish(v);
+            simba_validator_free(v);
+            r
+        }
+    }
+
+    #[test]
+    fn test_validator_utf8_chunked() {
+        let text = "aé€🦁 — ユニコード ".repeat(40).into_bytes();
+        let mut cases = vec![text.clone()];
+        for (at, seq) in [
+            (7usize, &[0x80u8][..]),
+            (100, &[0xED, 0xA0, 0x80]),
+            (333, &[0xF0, 0x9F, 0xA6]),
+            (text.len(), &[0xE2, 0x82]),
+        ] {
+            let mut cut = at;
+            while core::str::from_utf8(&text[..cut]).is_err() {
+                cut -= 1;
+            }
+            let mut d = text[..cut].to_vec();
+            d.extend_from_slice(seq);
+            d.extend_from_slice(&text[cut..]);
+            cases.push(d);
+        }
+        for data in &cases {
+            let want = match core::str::from_utf8(data) {
+                Ok(_) => -1,
+                Err(e) => e.valid_up_to() as i64,
+            };
+            for step in [1, 2, 3, 5, 7, 64, 65, 1000, data.len()] {
+                assert_eq!(run(VALIDATOR_UTF8, data, step), want, "step {}", step);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validator_ascii_and_handles() {
+        let mut data = b"plain ascii ".repeat(30);
+        assert_eq!(run(VALIDATOR_ASCII, &data, 7), -1);
+        data[200] = 0xC3;
+        assert_eq!(run(VALIDATOR_ASCII, &data, 7), 200);
+        assert_eq!(run(VALIDATOR_ASCII, &data, 1000), 200);
+        assert!(simba_validator_new(99).is_null());
+        unsafe { simba_validator_free(core::ptr::null_mut()) };
+    }
+}
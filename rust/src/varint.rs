@@ -0,0 +1,454 @@
+//! Variable-length integer codecs.
+use core::simd::prelude::{SimdPartialOrd, SimdUint};
+use core::simd::{Simd, ToBytes};
+
+use crate::error::{SIMBA_ERR_INVALID_INPUT, SIMBA_ERR_NULL_POINTER, fail};
+use crate::store_simd;
+
+// === StreamVByte =============================================================
+
+// StreamVByte (Lemire, Kurz, Rupp) splits the stream into a control block and
+// a data block.  Each control byte describes four values, two bits apiece
+// (byte length - 1, lowest value in the low bits); the data block holds the
+// values' significant little-endian bytes back to back.  Keeping the lengths
+// out of band lets a quad be decoded with one 16-byte load and one table
+// shuffle.
+
+/// Data bytes used by the quad with control byte `c`.
+const fn svb_quad_len(c: usize) -> usize {
+    (c & 3) + (c >> 2 & 3) + (c >> 4 & 3) + (c >> 6 & 3) + 4
+}
+
+/// Per control byte, the shuffle that spreads a quad's packed bytes into four
+/// u32 lanes (0x80 zero-fills).
+const SVB_DECODE_SHUFFLE: [[u8; 16]; 256] = {
+    let mut t = [[0x80u8; 16]; 256];
+    let mut c = 0;
+    while c < 256 {
+        let mut src = 0;
+        let mut k = 0;
+        while k < 4 {
+            let len = (c >> (2 * k) & 3) + 1;
+            let mut b = 0;
+            while b < len {
+                t[c][4 * k + b] = src as u8;
+                src += 1;
+                b += 1;
+            }
+            k += 1;
+        }
+        c += 1;
+    }
+    t
+};
+
+/// Inverse of [`SVB_DECODE_SHUFFLE`]: packs four u32 lanes down to their
+/// significant bytes.
+const SVB_ENCODE_SHUFFLE: [[u8; 16]; 256] = {
+    let mut t = [[0x80u8; 16]; 256];
+    let mut c = 0;
+    while c < 256 {
+        let mut dst = 0;
+        let mut k = 0;
+        while k < 4 {
+            let len = (c >> (2 * k) & 3) + 1;
+            let mut b = 0;
+            while b < len {
+                t[c][dst] = (4 * k + b) as u8;
+                dst += 1;
+                b += 1;
+            }
+            k += 1;
+        }
+        c += 1;
+    }
+    t
+};
+
+/// Length code (byte length - 1) of `x`.
+#[inline(always)]
+fn svb_code(x: u32) -> usize {
+    (31 - (x | 1).leading_zeros()) as usize / 8
+}
+
+/// Return the worst-case StreamVByte size of `n` values, i.e. the `dst`
+/// capacity required by [`streamvbyte_encode_u32`].
+#[unsafe(export_name = c_symbol!("streamvbyte_max_encoded_len"))]
+pub extern "C" fn streamvbyte_max_encoded_len(n: usize) -> usize {
+    n.div_ceil(4) + 4 * n
+}
+
+#[inline(always)]
+unsafe fn streamvbyte_encode_impl(src: &[u32], dst: *mut u8) -> usize {
+    let n = src.len();
+    let cap = streamvbyte_max_encoded_len(n);
+    let (ctrl, mut o) = (dst, n.div_ceil(4));
+    let mut quads = src.chunks_exact(4);
+    for (q, quad) in (&mut quads).enumerate() {
+        let v = Simd::<u32, 4>::from_slice(quad);
+        let codes = (Simd::splat(31) - (v | Simd::splat(1)).leading_zeros()) / Simd::splat(8);
+        let c = (codes << Simd::from_array([0, 2, 4, 6])).reduce_or() as usize;
+        *ctrl.add(q) = c as u8;
+        if o + 16 <= cap {
+            let shuffle = Simd::from_array(SVB_ENCODE_SHUFFLE[c]);
+            // The full 16-byte store spills into bytes the next quad
+            // overwrites; near the end of `dst` copy value by value.
+            store_simd(dst.add(o), v.to_le_bytes().swizzle_dyn(shuffle));
+            o += svb_quad_len(c);
+        } else {
+            for &x in quad {
+                let len = svb_code(x) + 1;
+                core::ptr::copy_nonoverlapping(x.to_le_bytes().as_ptr(), dst.add(o), len);
+                o += len;
+            }
+        }
+    }
+    let rest = quads.remainder();
+    if !rest.is_empty() {
+        let mut c = 0;
+        for (k, &x) in rest.iter().enumerate() {
+            let code = svb_code(x);
+            c |= code << (2 * k);
+            core::ptr::copy_nonoverlapping(x.to_le_bytes().as_ptr(), dst.add(o), code + 1);
+            o += code + 1;
+        }
+        *ctrl.add(n / 4) = c as u8;
+    }
+    o
+}
+
+/// Encode `n` u32 values in StreamVByte format: `ceil(n / 4)` control bytes
+/// followed by the packed data bytes.  Returns the number of bytes written.
+///
+/// # Safety
+/// `src` must be null or valid for `n` values; `dst` must be valid for
+/// `streamvbyte_max_encoded_len(n)` bytes and must not overlap `src`.
+#[unsafe(export_name = c_symbol!("streamvbyte_encode_u32"))]
+pub unsafe extern "C" fn streamvbyte_encode_u32(src: *const u32, n: usize, dst: *mut u8) -> usize {
+    if src.is_null() || dst.is_null() || n == 0 {
+        return 0;
+    }
+    streamvbyte_encode_impl(core::slice::from_raw_parts(src, n), dst)
+}
+
+/// Decode into `dst`; `None` if `src` ends early.  Returns bytes consumed.
+#[inline(always)]
+fn streamvbyte_decode_impl(src: &[u8], dst: &mut [u32]) -> Option<usize> {
+    let n = dst.len();
+    let (ctrl, data) = src.split_at_checked(n.div_ceil(4))?;
+    let mut o = 0;
+    let mut quads = dst.chunks_exact_mut(4);
+    for (quad, &c) in (&mut quads).zip(ctrl) {
+        let c = c as usize;
+        let len = svb_quad_len(c);
+        if o + 16 <= data.len() {
+            let bytes = Simd::<u8, 16>::from_slice(&data[o..o + 16]);
+            let v = Simd::<u32, 4>::from_le_bytes(
+                bytes.swizzle_dyn(Simd::from_array(SVB_DECODE_SHUFFLE[c])),
+            );
+            v.copy_to_slice(quad);
+        } else {
+            let packed = data.get(o..o + len)?;
+            let mut at = 0;
+            for (k, x) in quad.iter_mut().enumerate() {
+                let w = (c >> (2 * k) & 3) + 1;
+                let mut b = [0u8; 4];
+                b[..w].copy_from_slice(&packed[at..at + w]);
+                *x = u32::from_le_bytes(b);
+                at += w;
+            }
+        }
+        o += len;
+    }
+    let rest = quads.into_remainder();
+    if !rest.is_empty() {
+        let c = ctrl[n / 4] as usize;
+        for (k, x) in rest.iter_mut().enumerate() {
+            let w = (c >> (2 * k) & 3) + 1;
+            let mut b = [0u8; 4];
+            b[..w].copy_from_slice(data.get(o..o + w)?);
+            *x = u32::from_le_bytes(b);
+            o += w;
+        }
+    }
+    Some(ctrl.len() + o)
+}
+
+/// Decode `n` u32 values written by [`streamvbyte_encode_u32`] from the
+/// `len` bytes at `src` into `dst`.
+///
+/// Returns the number of bytes consumed, `SIMBA_ERR_INVALID_INPUT` if the
+/// stream ends before `n` values, or `SIMBA_ERR_NULL_POINTER`.
+///
+/// # Safety
+/// `src` must be valid for `len` bytes and `dst` for `n` values.
+#[unsafe(export_name = c_symbol!("streamvbyte_decode_u32"))]
+pub unsafe extern "C" fn streamvbyte_decode_u32(
+    src: *const u8,
+    len: usize,
+    n: usize,
+    dst: *mut u32,
+) -> isize {
+    if n == 0 {
+        return 0;
+    }
+    if src.is_null() || dst.is_null() {
+        return fail(SIMBA_ERR_NULL_POINTER, format_args!("src or dst is null")) as isize;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, n);
+    match streamvbyte_decode_impl(src, dst) {
+        Some(used) => used as isize,
+        None => fail(
+            SIMBA_ERR_INVALID_INPUT,
+            format_args!("streamvbyte stream of {len} bytes ends before {n} values"),
+        ) as isize,
+    }
+}
+
+// === LEB128 batch decode =====================================================
+
+/// Longest LEB128 encoding of a u64; its last byte may only carry one bit.
+const LEB128_MAX_LEN: usize = 10;
+
+/// Bytes scanned per continuation-bit mask.
+const LEB128_WINDOW: usize = 16;
+
+/// Outcome of [`varint_decode_u64`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VarintBatch {
+    /// Input bytes consumed; on error, the offset of the malformed varint.
+    pub consumed: usize,
+    /// Values written to `dst`.
+    pub produced: usize,
+}
+
+/// Value of the complete varint `b` (its last byte is the only one without
+/// the continuation bit); `None` if it is too long or overflows 64 bits.
+#[inline(always)]
+fn leb128_value(b: &[u8]) -> Option<u64> {
+    if b.len() > LEB128_MAX_LEN || (b.len() == LEB128_MAX_LEN && b[LEB128_MAX_LEN - 1] > 1) {
+        return None;
+    }
+    Some(b.iter().rev().fold(0, |x, &c| x << 7 | (c & 0x7F) as u64))
+}
+
+/// Decode varints from `src` into `dst` until either runs out.  A varint cut
+/// off by the end of `src` is left unconsumed.
+fn varint_decode_impl(src: &[u8], dst: &mut [u64]) -> Result<VarintBatch, VarintBatch> {
+    let (mut pos, mut n) = (0, 0);
+    while pos + LEB128_WINDOW <= src.len() && n < dst.len() {
+        let v = Simd::<u8, LEB128_WINDOW>::from_slice(&src[pos..pos + LEB128_WINDOW]);
+        let mut ends = !v.simd_ge(Simd::splat(0x80)).to_bitmask();
+        // A window of single-byte values widens in one step.
+        if ends as u16 == u16::MAX && n + LEB128_WINDOW <= dst.len() {
+            v.cast::<u64>()
+                .copy_to_slice(&mut dst[n..n + LEB128_WINDOW]);
+            pos += LEB128_WINDOW;
+            n += LEB128_WINDOW;
+            continue;
+        }
+        let mut start = 0;
+        while ends as u16 != 0 && n < dst.len() {
+            let end = ends.trailing_zeros() as usize;
+            let Some(x) = leb128_value(&src[pos + start..pos + end + 1]) else {
+                return Err(VarintBatch {
+                    consumed: pos + start,
+                    produced: n,
+                });
+            };
+            dst[n] = x;
+            n += 1;
+            start = end + 1;
+            ends &= ends - 1;
+        }
+        if start == 0 {
+            // Sixteen continuation bytes in a row.
+            return Err(VarintBatch {
+                consumed: pos,
+                produced: n,
+            });
+        }
+        pos += start;
+    }
+    while pos < src.len() && n < dst.len() {
+        let Some(len) = src[pos..].iter().position(|&c| c < 0x80).map(|i| i + 1) else {
+            if src.len() - pos >= LEB128_MAX_LEN {
+                return Err(VarintBatch {
+                    consumed: pos,
+                    produced: n,
+                });
+            }
+            break;
+        };
+        let Some(x) = leb128_value(&src[pos..pos + len]) else {
+            return Err(VarintBatch {
+                consumed: pos,
+                produced: n,
+            });
+        };
+        dst[n] = x;
+        n += 1;
+        pos += len;
+    }
+    Ok(VarintBatch {
+        consumed: pos,
+        produced: n,
+    })
+}
+
+/// Decode a run of LEB128 (protobuf) varints from the `len` bytes at `src`
+/// into up to `cap` u64 values at `dst`, stopping when `dst` is full or no
+/// complete varint is left.  Bytes and values used are written to `out`; a
+/// varint truncated by the end of `src` is not consumed, so the caller can
+/// refill and resume at `out.consumed`.
+///
+/// Returns 0, `SIMBA_ERR_INVALID_INPUT` for a varint longer than 10 bytes or
+/// one that overflows 64 bits (`out.consumed` is then its offset, and the
+/// values before it have been decoded), or `SIMBA_ERR_NULL_POINTER`.
+///
+/// # Safety
+/// `src` must be valid for `len` bytes, `dst` for `cap` values and `out`
+/// for one write.
+#[unsafe(export_name = c_symbol!("varint_decode_u64"))]
+pub unsafe extern "C" fn varint_decode_u64(
+    src: *const u8,
+    len: usize,
+    dst: *mut u64,
+    cap: usize,
+    out: *mut VarintBatch,
+) -> i32 {
+    if out.is_null() || ((src.is_null() || dst.is_null()) && len != 0 && cap != 0) {
+        return fail(
+            SIMBA_ERR_NULL_POINTER,
+            format_args!("src, dst or out is null"),
+        );
+    }
+    if len == 0 || cap == 0 {
+        *out = VarintBatch::default();
+        return 0;
+    }
+    let src = core::slice::from_raw_parts(src, len);
+    let dst = core::slice::from_raw_parts_mut(dst, cap);
+    match varint_decode_impl(src, dst) {
+        Ok(batch) => {
+            *out = batch;
+            0
+        }
+        Err(batch) => {
+            *out = batch;
+            fail(
+                SIMBA_ERR_INVALID_INPUT,
+                format_args!("malformed varint at offset {}", batch.consumed),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streamvbyte_round_trip() {
+        for n in [1usize, 3, 4, 5, 17, 64, 1001] {
+            let src: Vec<u32> = (0..n as u32)
+                .map(|i| match i % 5 {
+                    0 => i,
+                    1 => i * 300,
+                    2 => i.wrapping_mul(0x9E37_79B9),
+                    3 => 0,
+                    _ => u32::MAX - i,
+                })
+                .collect();
+            let mut enc = vec![0u8; streamvbyte_max_encoded_len(n)];
+            let used = unsafe { streamvbyte_encode_u32(src.as_ptr(), n, enc.as_mut_ptr()) };
+            let want = n.div_ceil(4) + src.iter().map(|&x| svb_code(x) + 1).sum::<usize>();
+            assert_eq!(used, want, "n={n}");
+
+            let mut dec = vec![0u32; n];
+            let got = unsafe { streamvbyte_decode_u32(enc.as_ptr(), used, n, dec.as_mut_ptr()) };
+            assert_eq!(got, used as isize, "n={n}");
+            assert_eq!(dec, src, "n={n}");
+
+            // One byte short is reported, not read past.
+            let got =
+                unsafe { streamvbyte_decode_u32(enc.as_ptr(), used - 1, n, dec.as_mut_ptr()) };
+            assert_eq!(got, SIMBA_ERR_INVALID_INPUT as isize, "n={n}");
+        }
+
+        // Layout: control byte 0b11_00_01_00, then 1 + 2 + 1 + 4 data bytes.
+        let mut enc = [0u8; 21];
+        let used = unsafe {
+            streamvbyte_encode_u32([7, 0x1234, 0, u32::MAX].as_ptr(), 4, enc.as_mut_ptr())
+        };
+        assert_eq!(
+            &enc[..used],
+            &[0b1100_0100, 7, 0x34, 0x12, 0, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    fn leb128(mut x: u64, out: &mut Vec<u8>) {
+        while x >= 0x80 {
+            out.push(x as u8 | 0x80);
+            x >>= 7;
+        }
+        out.push(x as u8);
+    }
+
+    fn decode(src: &[u8], cap: usize) -> (i32, VarintBatch, Vec<u64>) {
+        let mut dst = vec![0u64; cap];
+        let mut out = VarintBatch::default();
+        let rc =
+            unsafe { varint_decode_u64(src.as_ptr(), src.len(), dst.as_mut_ptr(), cap, &mut out) };
+        dst.truncate(out.produced);
+        (rc, out, dst)
+    }
+
+    #[test]
+    fn test_varint_decode() {
+        // Runs of one-byte values, mixed widths and the u64 extremes.
+        let values: Vec<u64> = (0..300u64)
+            .map(|i| match i % 50 {
+                0..20 => i % 128,
+                20 => u64::MAX,
+                21 => 1 << 63,
+                _ => i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64),
+            })
+            .collect();
+        let mut src = Vec::new();
+        values.iter().for_each(|&x| leb128(x, &mut src));
+
+        let (rc, out, got) = decode(&src, values.len());
+        assert_eq!((rc, out.consumed), (0, src.len()));
+        assert_eq!(got, values);
+
+        // A full `dst` stops early; resuming at `consumed` finishes the run.
+        let (rc, first, head) = decode(&src, 37);
+        assert_eq!((rc, first.produced), (0, 37));
+        let (_, _, tail) = decode(&src[first.consumed..], values.len());
+        assert_eq!([head, tail].concat(), values);
+
+        // A truncated trailing varint is left for the next call.
+        let (rc, out, got) = decode(&src[..src.len() - 1], values.len());
+        assert_eq!(rc, 0);
+        assert_eq!(got, values[..values.len() - 1]);
+        assert!(src.len() - out.consumed < LEB128_MAX_LEN);
+
+        // Overflowing and over-long varints are reported at their offset.
+        let mut bad = vec![1, 2, 3];
+        bad.extend([0xFF; 9]);
+        bad.push(0x02);
+        bad.extend([4; 20]);
+        let (rc, out, got) = decode(&bad, 64);
+        assert_eq!(
+            (rc, out.consumed, got),
+            (SIMBA_ERR_INVALID_INPUT, 3, vec![1, 2, 3])
+        );
+        let (rc, out, _) = decode(&[0x80; 40], 64);
+        assert_eq!((rc, out.consumed), (SIMBA_ERR_INVALID_INPUT, 0));
+        let (rc, out, _) = decode(&[0x80; 12], 64);
+        assert_eq!((rc, out.consumed), (SIMBA_ERR_INVALID_INPUT, 0));
+    }
+}
@@ -5,9 +5,18 @@ set -euo pipefail
 SCRIPT_DIR="$( cd -- "$( dirname -- "${BASH_SOURCE[0]}" )" &> /dev/null && pwd )"
 ROOT_DIR="${SCRIPT_DIR}/.."
 
+# Refresh the C header shipped with the static/shared libraries.
+if command -v cbindgen >/dev/null 2>&1; then
+  "${SCRIPT_DIR}/gen_header.sh"
+else
+  echo "[WARN] cbindgen not found; leaving rust/include/simba.h as is" >&2
+fi
+
 echo "[SIMBA build] Building Rust static and shared libraries..."
 (
   cd "${ROOT_DIR}/rust"
+  # build.rs writes the kernel manifest (derived from simba.h) here as well.
+  export SIMBA_MANIFEST="${ROOT_DIR}/rust/include/simba.json"
   # Build with nightly to enable portable_simd feature
   if command -v rustup >/dev/null 2>&1; then
     cargo +nightly build --release --lib
@@ -23,24 +32,14 @@ rm -f "${ROOT_DIR}/internal/ffi/libsimba.syso" \
        "${ROOT_DIR}/internal/ffi/libsimba.dylib"
 
 # ---------------------------------------------------------------------------
-# Build .syso archives for the no-cgo backend (darwin/amd64 and darwin/arm64)
+# Build .syso archives for the no-cgo backend (darwin and linux, amd64 and arm64)
 # ---------------------------------------------------------------------------
-echo "[SIMBA build] Building Mach-O .syso archives for simba_syso tag..."
-(
-  cd "${ROOT_DIR}/rust"
-  if command -v rustup >/dev/null 2>&1; then
-    for target in x86_64-apple-darwin aarch64-apple-darwin; do
-      rustup target add ${target} --toolchain nightly >/dev/null 2>&1 || true
-      cargo +nightly rustc --release --lib --target ${target} -- -C relocation-model=pic
-      arch=${target%%-*}; os=${target##*-darwin}; # arch part first segment until '-' maybe x86_64 or aarch64
-      goarch=$( [ "$arch" = "x86_64" ] && echo amd64 || echo arm64 )
-      cp "${ROOT_DIR}/rust/target/${target}/release/libsimba.a" \
-         "${ROOT_DIR}/internal/ffi/libsimba_darwin_${goarch}.syso" || true
-    done
-  else
-    echo "[WARN] rustup not found; skipping .syso build" >&2
-  fi
-)
+echo "[SIMBA build] Building .syso archives for simba_syso tag..."
+if command -v rustup >/dev/null 2>&1; then
+  "${SCRIPT_DIR}/build_syso.sh" nightly
+else
+  echo "[WARN] rustup not found; skipping .syso build" >&2
+fi
 
 echo "[SIMBA build] syso builds complete."
 
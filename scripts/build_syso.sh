@@ -1,20 +1,30 @@
 #!/usr/bin/env bash
 set -euo pipefail
 
-# Build libsimba static archives for both macOS targets and copy them as .syso
-# so that the Go linker picks them up automatically.
+# Build libsimba static archives for every supported GOOS/GOARCH and copy them
+# as libsimba_<goos>_<goarch>.syso so that the Go linker picks the matching one
+# up automatically.
 
 readonly TOOLCHAIN="${1:-nightly}"
 readonly MANIFEST="$(dirname "$0")/../rust/Cargo.toml"
 
-for target in x86_64-apple-darwin aarch64-apple-darwin; do
+for target in x86_64-apple-darwin aarch64-apple-darwin x86_64-unknown-linux-gnu aarch64-unknown-linux-gnu \
+  x86_64-pc-windows-gnu; do
   rustup target add "$target" --toolchain "$TOOLCHAIN" >/dev/null 2>&1 || true
-  cargo +"$TOOLCHAIN" rustc --manifest-path "$MANIFEST" --release --lib --target "$target" -- -C relocation-model=pic
-  if [[ "$target" == x86_64-apple-darwin ]]; then
+  cargo +"$TOOLCHAIN" rustc --manifest-path "$MANIFEST" --release --lib --crate-type staticlib --target "$target" -- -C relocation-model=pic
+  if [[ "$target" == x86_64-* ]]; then
     goarch=amd64
   else
     goarch=arm64
   fi
-  cp "$(dirname "$MANIFEST")/target/$target/release/libsimba.a" "$(dirname "$0")/../internal/ffi/libsimba_darwin_${goarch}.syso"
-  echo "Generated libsimba_darwin_${goarch}.syso"
-done 
\ No newline at end of file
+  if [[ "$target" == *-apple-darwin ]]; then
+    goos=darwin
+  elif [[ "$target" == *-windows-* ]]; then
+    # Exports follow the Win64 convention here; syso_windows_amd64.s matches.
+    goos=windows
+  else
+    goos=linux
+  fi
+  cp "$(dirname "$MANIFEST")/target/$target/release/libsimba.a" "$(dirname "$0")/../internal/ffi/libsimba_${goos}_${goarch}.syso"
+  echo "Generated libsimba_${goos}_${goarch}.syso"
+done
@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Regenerate rust/include/simba.h, the C header for the staticlib/cdylib
+# builds, from the crate's exports.  Requires cbindgen
+# (`cargo install cbindgen`); the crate is macro-expanded first, which needs
+# the nightly toolchain.  The header always declares the unprefixed names;
+# a SIMBA_SYMBOL_PREFIX build prepends the prefix to each of them.
+
+readonly TOOLCHAIN="${1:-nightly}"
+readonly CRATE_DIR="$(cd -- "$(dirname -- "${BASH_SOURCE[0]}")/../rust" &>/dev/null && pwd)"
+
+cd "$CRATE_DIR"
+env -u SIMBA_SYMBOL_PREFIX RUSTUP_TOOLCHAIN="$TOOLCHAIN" cbindgen --quiet --config cbindgen.toml --crate simba --output include/simba.h
+echo "Generated include/simba.h"
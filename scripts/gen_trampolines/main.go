@@ -1,6 +1,7 @@
 package main
 
 import (
+	"encoding/json"
 	"fmt"
 	"go/ast"
 	"go/parser"
@@ -11,6 +12,10 @@ import (
 	"strings"
 )
 
+// symbolPrefix must match the SIMBA_SYMBOL_PREFIX the .syso archives were
+// built with; the Rust exports are linked as <prefix><name>.
+var symbolPrefix = os.Getenv("SIMBA_SYMBOL_PREFIX")
+
 type FuncInfo struct {
 	Name   string
 	Params []string // type names
@@ -96,8 +101,69 @@ func main() {
 		return
 	}
 
-	generateArch("amd64", funcs)
-	generateArch("arm64", funcs)
+	checkManifest(filepath.Join("..", "..", "rust", "include", "simba.json"), funcs)
+
+	generateArch("amd64", "", funcs)
+	generateArch("amd64", "windows", funcs)
+	generateArch("arm64", "", funcs)
+}
+
+// manifestSymbol is one entry of the manifest the Rust build emits (see
+// rust/build.rs); only the widths matter here.
+type manifestSymbol struct {
+	Name    string `json:"name"`
+	Returns *struct {
+		Bits int `json:"bits"`
+	} `json:"returns"`
+	Args []struct {
+		Name string `json:"name"`
+		Bits int    `json:"bits"`
+	} `json:"args"`
+}
+
+// checkManifest refuses to generate stubs for a prototype that does not match
+// the Rust export it calls: a missing symbol, a different argument count, or
+// an argument or result of a different width.
+func checkManifest(path string, funcs []FuncInfo) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		log.Printf("skipping signature check: %v", err)
+		return
+	}
+	var m struct {
+		Symbols []manifestSymbol `json:"symbols"`
+	}
+	if err := json.Unmarshal(data, &m); err != nil {
+		log.Fatalf("parse %s: %v", path, err)
+	}
+	symbols := make(map[string]manifestSymbol, len(m.Symbols))
+	for _, s := range m.Symbols {
+		symbols[s.Name] = s
+	}
+	for _, fn := range funcs {
+		rustName := strings.TrimSuffix(fn.Name, "_raw")
+		sym, ok := symbols[rustName]
+		if !ok {
+			log.Fatalf("%s: %s is not exported by the Rust crate", fn.Name, rustName)
+		}
+		if len(sym.Args) != len(fn.Params) {
+			log.Fatalf("%s: %d parameters, %s takes %d", fn.Name, len(fn.Params), rustName, len(sym.Args))
+		}
+		for i, pair := range fn.Params {
+			name, typ := split(pair)
+			if got, want := sizeOf(typ)*8, sym.Args[i].Bits; got != want {
+				log.Fatalf("%s: %s is %d bits, %s expects %d for %s", fn.Name, name, got, rustName, want, sym.Args[i].Name)
+			}
+		}
+		switch {
+		case fn.Result == "" && sym.Returns != nil:
+			log.Fatalf("%s: no result, %s returns %d bits", fn.Name, rustName, sym.Returns.Bits)
+		case fn.Result != "" && sym.Returns == nil:
+			log.Fatalf("%s: returns %s, %s returns nothing", fn.Name, fn.Result, rustName)
+		case fn.Result != "" && sizeOf(fn.Result)*8 != sym.Returns.Bits:
+			log.Fatalf("%s: returns %s, %s returns %d bits", fn.Name, fn.Result, rustName, sym.Returns.Bits)
+		}
+	}
 }
 
 func exprToString(e ast.Expr) string {
@@ -111,16 +177,35 @@ func exprToString(e ast.Expr) string {
 	}
 }
 
-func generateArch(arch string, funcs []FuncInfo) {
+// generateArch writes the stubs for one GOARCH.  goos is empty for the
+// System V flavour shared by linux and darwin, or "windows" for the Microsoft
+// x64 convention, which Rust's `extern "C"` resolves to on that target.
+func generateArch(arch, goos string, funcs []FuncInfo) {
+	win64 := arch == "amd64" && goos == "windows"
 	var b strings.Builder
 	b.WriteString("// Code generated by gen_trampolines; DO NOT EDIT.\n")
-	fmt.Fprintf(&b, "//go:build %s\n// +build %s\n\n", arch, arch)
+	switch {
+	case win64:
+		fmt.Fprintf(&b, "//go:build windows && %s\n// +build windows,%s\n\n", arch, arch)
+	case arch == "amd64":
+		fmt.Fprintf(&b, "//go:build %s && !windows\n// +build %s,!windows\n\n", arch, arch)
+	default:
+		fmt.Fprintf(&b, "//go:build %s\n// +build %s\n\n", arch, arch)
+	}
 	b.WriteString("#include \"textflag.h\"\n\n")
 
 	regOrder := map[string][]string{
 		"amd64": {"DI", "SI", "DX", "CX", "R8", "R9"},
 		"arm64": {"R0", "R1", "R2", "R3", "R4", "R5", "R6", "R7"},
 	}[arch]
+	// Win64 passes only four integer arguments in registers and requires
+	// the caller to reserve 32 bytes of "shadow space" above the stack
+	// arguments, even when there are none.
+	shadowBytes := 0
+	if win64 {
+		regOrder = []string{"CX", "DX", "R8", "R9"}
+		shadowBytes = 32
+	}
 
 	for _, fn := range funcs {
 		frame := 0
@@ -224,10 +309,11 @@ func generateArch(arch string, funcs []FuncInfo) {
 		}
 		// -------- Spill handling -------------------------------------------
 		// For parameters that did not fit in the register set we reserve a
-		// contiguous stack area (spillBytes = extra*8).  We copy each arg
-		// from the Go ABI frame into this scratch space before the CALL so
-		// that Rust can read it using the platform’s standard stack layout.
-		spillBytes := extra * 8
+		// contiguous stack area (extra*8 bytes, above the Win64 shadow space
+		// if any).  We copy each arg from the Go ABI frame into this scratch
+		// space before the CALL so that Rust can read it using the
+		// platform’s standard stack layout.
+		spillBytes := shadowBytes + extra*8
 		if spillBytes > 0 {
 			if arch == "amd64" {
 				b.WriteString(fmt.Sprintf("    SUBQ $%d, SP\n", spillBytes))
@@ -253,9 +339,9 @@ func generateArch(arch string, funcs []FuncInfo) {
 					}
 					b.WriteString(fmt.Sprintf("    %s %s+%d(FP), AX\n", instLoad, name, off))
 					if instStore == "MOVB" {
-						b.WriteString(fmt.Sprintf("    MOVB AL, %d(SP)\n", j*8))
+						b.WriteString(fmt.Sprintf("    MOVB AL, %d(SP)\n", shadowBytes+j*8))
 					} else {
-						b.WriteString(fmt.Sprintf("    %s AX, %d(SP)\n", instStore, j*8))
+						b.WriteString(fmt.Sprintf("    %s AX, %d(SP)\n", instStore, shadowBytes+j*8))
 					}
 				} else { // arm64
 					switch typ {
@@ -272,7 +358,7 @@ func generateArch(arch string, funcs []FuncInfo) {
 			}
 		}
 		rustName := strings.TrimSuffix(fn.Name, "_raw")
-		b.WriteString(fmt.Sprintf("    CALL %s(SB)\n", rustName))
+		b.WriteString(fmt.Sprintf("    CALL %s%s(SB)\n", symbolPrefix, rustName))
 		if spillBytes > 0 {
 			if arch == "amd64" {
 				b.WriteString(fmt.Sprintf("    ADDQ $%d, SP\n", spillBytes))
@@ -322,6 +408,9 @@ func generateArch(arch string, funcs []FuncInfo) {
 	}
 
 	path := fmt.Sprintf("syso_%s.s", arch)
+	if goos != "" {
+		path = fmt.Sprintf("syso_%s_%s.s", goos, arch)
+	}
 	if err := os.WriteFile(path, []byte(b.String()), 0644); err != nil {
 		log.Fatalf("write %s: %v", path, err)
 	}
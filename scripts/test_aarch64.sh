@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Run the Rust unit tests for aarch64 under QEMU user-mode emulation so the
+# NEON, CRC32 and SHA2 code paths are exercised from an amd64 CI host.  On a
+# native arm64 host (e.g. Graviton) a plain `cargo +nightly test` does the same.
+#
+# Needs qemu-user (`qemu-aarch64`) and an aarch64 cross linker
+# (`gcc-aarch64-linux-gnu` on Debian/Ubuntu).  `-cpu max` turns on every
+# optional extension QEMU emulates, including CRC32, SHA2 and SVE.
+#
+# It first cross-builds the `no_std` kernel layer for Linux and macOS, where
+# `aarch64_feature!` falls back to compile-time `cfg!` checks.  Those builds
+# only need the Rust targets, not a linker.
+
+readonly TOOLCHAIN="${1:-nightly}"
+readonly MANIFEST="$(dirname "$0")/../rust/Cargo.toml"
+readonly TARGET=aarch64-unknown-linux-gnu
+
+for target in "$TARGET" aarch64-apple-darwin; do
+  rustup target add "$target" --toolchain "$TOOLCHAIN" >/dev/null 2>&1 || true
+  cargo +"$TOOLCHAIN" rustc --manifest-path "$MANIFEST" --release --lib --crate-type rlib \
+    --no-default-features --target "$target"
+done
+
+export CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER="${AARCH64_LINKER:-aarch64-linux-gnu-gcc}"
+export CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER="qemu-aarch64 -cpu max -L ${AARCH64_SYSROOT:-/usr/aarch64-linux-gnu}"
+cargo +"$TOOLCHAIN" test --manifest-path "$MANIFEST" --release --lib --target "$TARGET"